@@ -4,7 +4,7 @@ use spodes_rs::classes::register::Register;
 use spodes_rs::obis::ObisCode;
 use spodes_rs::serialization::{deserialize_object, serialize_object};
 use spodes_rs::types::attrs::ScalerUnit;
-use spodes_rs::types::CosemDataType;
+use spodes_rs::types::{BerEncoder, CosemDataRef, CosemDataType};
 
 fn bench_data_serialize(c: &mut Criterion) {
     let obis = ObisCode::new(1, 0, 1, 8, 0, 0xFF);
@@ -64,6 +64,64 @@ fn bench_obis_serialize(c: &mut Criterion) {
     });
 }
 
+/// A 1000-row load-profile buffer: (clock, A+, A-, status) per row.
+fn profile_buffer() -> CosemDataType {
+    CosemDataType::Array(
+        (0..1000u32)
+            .map(|i| {
+                CosemDataType::Structure(vec![
+                    CosemDataType::OctetString(vec![0x07, 0xE9, 5, 1, 4, 0, 0, 0, 0, 0, 0, 0]),
+                    CosemDataType::DoubleLongUnsigned(i * 10),
+                    CosemDataType::DoubleLongUnsigned(i),
+                    CosemDataType::Unsigned(0),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn bench_profile_decode(c: &mut Criterion) {
+    let mut encoded = Vec::new();
+    profile_buffer().serialize_ber(&mut encoded).unwrap();
+
+    c.bench_function("Profile buffer decode (owned)", |b| {
+        b.iter(|| CosemDataType::deserialize_ber(black_box(&encoded)).unwrap());
+    });
+    c.bench_function("Profile buffer decode (borrowed)", |b| {
+        b.iter(|| {
+            let (buffer, _) = CosemDataRef::deserialize_ber(black_box(&encoded)).unwrap();
+            let total: i64 = buffer.elements().unwrap().filter_map(|row| row.elements()?.nth(1)?.as_i64()).sum();
+            black_box(total)
+        });
+    });
+}
+
+fn bench_profile_encode(c: &mut Criterion) {
+    let buffer = profile_buffer();
+
+    c.bench_function("Profile buffer encode (tree)", |b| {
+        b.iter(|| {
+            let mut buf = Vec::new();
+            black_box(&buffer).serialize_ber(&mut buf).unwrap();
+            buf
+        });
+    });
+    c.bench_function("Profile buffer encode (streaming)", |b| {
+        b.iter(|| {
+            let mut encoder = BerEncoder::new(Vec::new());
+            encoder.begin_array(1000).unwrap();
+            for i in 0..1000u32 {
+                encoder.begin_structure(4).unwrap();
+                encoder.value(&CosemDataType::OctetString(vec![0x07, 0xE9, 5, 1, 4, 0, 0, 0, 0, 0, 0, 0])).unwrap();
+                encoder.value(&CosemDataType::DoubleLongUnsigned(i * 10)).unwrap();
+                encoder.value(&CosemDataType::DoubleLongUnsigned(i)).unwrap();
+                encoder.value(&CosemDataType::Unsigned(0)).unwrap();
+            }
+            encoder.finish().unwrap()
+        });
+    });
+}
+
 criterion_group!(
     benches,
     bench_data_serialize,
@@ -71,5 +129,7 @@ criterion_group!(
     bench_register_serialize,
     bench_register_deserialize,
     bench_obis_serialize,
+    bench_profile_decode,
+    bench_profile_encode,
 );
 criterion_main!(benches);
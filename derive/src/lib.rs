@@ -0,0 +1,245 @@
+//! Derive macros for the `spodes-rs` COSEM data mapping traits.
+//!
+//! `#[derive(ToCosem, FromCosem)]` implements
+//! `spodes_rs::types::codec::{ToCosem, FromCosem}`:
+//!
+//! * a struct (named or tuple fields) maps to a COSEM structure of its fields
+//!   in declaration order, a unit struct to an empty structure;
+//! * an enum whose variants are all unit variants maps to a COSEM enum of the
+//!   variant's discriminant (explicit `= n`, or counting from 0).
+//!
+//! Attributes:
+//!
+//! * `#[cosem(transparent)]` on a single-field struct encodes it as its field;
+//! * `#[cosem(octets)]` on a `Vec<u8>` or `[u8; N]` field encodes it as an
+//!   octet-string instead of an array of unsigned.
+//!
+//! Use the macros through `spodes_rs::types::codec` (the `derive` feature).
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Expr, Fields, Generics, Index, Lit};
+
+/// Derives `ToCosem`.
+#[proc_macro_derive(ToCosem, attributes(cosem))]
+pub fn derive_to_cosem(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_cosem(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `FromCosem`.
+#[proc_macro_derive(FromCosem, attributes(cosem))]
+pub fn derive_from_cosem(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_cosem(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Flags of a `#[cosem(...)]` attribute.
+#[derive(Default)]
+struct Options {
+    transparent: bool,
+    octets: bool,
+}
+
+fn options(attrs: &[Attribute]) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("cosem")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("transparent") {
+                options.transparent = true;
+                Ok(())
+            } else if meta.path.is_ident("octets") {
+                options.octets = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown cosem attribute, expected `transparent` or `octets`"))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+/// Adds `bound` to every type parameter.
+fn bounded(generics: &Generics, bound: &TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    let params: Vec<_> = generics.type_params().map(|p| p.ident.clone()).collect();
+    let clause = generics.make_where_clause();
+    for param in params {
+        clause.predicates.push(parse_quote!(#param: #bound));
+    }
+    generics
+}
+
+/// One struct field: how to access it and how it is encoded.
+struct FieldInfo {
+    member: TokenStream2,
+    name: String,
+    octets: bool,
+}
+
+fn struct_fields(fields: &Fields) -> syn::Result<Vec<FieldInfo>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (member, name) = match &field.ident {
+                Some(ident) => (quote!(#ident), ident.to_string()),
+                None => {
+                    let index = Index::from(i);
+                    (quote!(#index), i.to_string())
+                }
+            };
+            Ok(FieldInfo { member, name, octets: options(&field.attrs)?.octets })
+        })
+        .collect()
+}
+
+/// The unit variants of an enum with their discriminants.
+fn enum_variants(input: &DeriveInput) -> syn::Result<Vec<(syn::Ident, u8)>> {
+    let Data::Enum(data) = &input.data else {
+        unreachable!("called for enums only");
+    };
+    let mut next: u16 = 0;
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(variant, "only enums with unit variants map to a COSEM enum"));
+        }
+        if let Some((_, discriminant)) = &variant.discriminant {
+            let Expr::Lit(syn::ExprLit { lit: Lit::Int(value), .. }) = discriminant else {
+                return Err(syn::Error::new_spanned(discriminant, "expected an integer literal discriminant"));
+            };
+            next = value.base10_parse::<u8>()?.into();
+        }
+        let value = u8::try_from(next)
+            .map_err(|_| syn::Error::new_spanned(variant, "COSEM enum discriminants must be 0..=255"))?;
+        variants.push((variant.ident.clone(), value));
+        next += 1;
+    }
+    Ok(variants)
+}
+
+fn transparent_field(input: &DeriveInput, fields: &Fields) -> syn::Result<()> {
+    if fields.len() == 1 {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(&input.ident, "#[cosem(transparent)] requires exactly one field"))
+    }
+}
+
+fn expand_to_cosem(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let codec = quote!(::spodes_rs::types::codec);
+    let data_type = quote!(::spodes_rs::types::CosemDataType);
+    let options = options(&input.attrs)?;
+    let encode = |field: &FieldInfo| {
+        let member = &field.member;
+        if field.octets {
+            quote!(#codec::octets::to_cosem(&self.#member))
+        } else {
+            quote!(#codec::ToCosem::to_cosem(&self.#member))
+        }
+    };
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = struct_fields(&data.fields)?;
+            if options.transparent {
+                transparent_field(input, &data.fields)?;
+                encode(&fields[0])
+            } else {
+                let elements = fields.iter().map(encode);
+                quote!(#data_type::Structure(::std::vec![#(#elements),*]))
+            }
+        }
+        Data::Enum(_) => {
+            let arms = enum_variants(input)?.into_iter().map(|(ident, value)| quote!(Self::#ident => #value));
+            quote!(#data_type::Enum(match self { #(#arms,)* }))
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(&input.ident, "unions cannot be mapped to COSEM data")),
+    };
+    let name = &input.ident;
+    let generics = bounded(&input.generics, &quote!(#codec::ToCosem));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #codec::ToCosem for #name #ty_generics #where_clause {
+            fn to_cosem(&self) -> #data_type {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_from_cosem(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let codec = quote!(::spodes_rs::types::codec);
+    let data_type = quote!(::spodes_rs::types::CosemDataType);
+    let options = options(&input.attrs)?;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = struct_fields(&data.fields)?;
+            let values: Vec<TokenStream2> = if options.transparent {
+                transparent_field(input, &data.fields)?;
+                let decode = if fields[0].octets {
+                    quote!(#codec::octets::from_cosem(value)?)
+                } else {
+                    quote!(#codec::FromCosem::from_cosem(value)?)
+                };
+                vec![decode]
+            } else {
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        let name = &field.name;
+                        if field.octets {
+                            quote!(#codec::octets::from_cosem(&items[#i]).map_err(|e| e.in_field(#name))?)
+                        } else {
+                            quote!(#codec::field(items, #i, #name)?)
+                        }
+                    })
+                    .collect()
+            };
+            let construct = match &data.fields {
+                Fields::Named(_) => {
+                    let members = fields.iter().map(|f| &f.member);
+                    quote!(Self { #(#members: #values),* })
+                }
+                Fields::Unnamed(_) => quote!(Self(#(#values),*)),
+                Fields::Unit => quote!(Self),
+            };
+            if options.transparent {
+                quote!(::std::result::Result::Ok(#construct))
+            } else {
+                let len = fields.len();
+                quote! {
+                    let items = #codec::structure(value, #len)?;
+                    ::std::result::Result::Ok(#construct)
+                }
+            }
+        }
+        Data::Enum(_) => {
+            let variants = enum_variants(input)?;
+            let expected = format!(
+                "enum (one of {})",
+                variants.iter().map(|(_, value)| value.to_string()).collect::<Vec<_>>().join(", ")
+            );
+            let arms = variants.iter().map(|(ident, value)| quote!(#value => ::std::result::Result::Ok(Self::#ident)));
+            quote! {
+                match #codec::enum_value(value)? {
+                    #(#arms,)*
+                    _ => ::std::result::Result::Err(#codec::DecodeError::expected(#expected)),
+                }
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(&input.ident, "unions cannot be mapped to COSEM data")),
+    };
+    let name = &input.ident;
+    let generics = bounded(&input.generics, &quote!(#codec::FromCosem));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #codec::FromCosem for #name #ty_generics #where_clause {
+            fn from_cosem(value: &#data_type) -> ::std::result::Result<Self, #codec::DecodeError> {
+                #body
+            }
+        }
+    })
+}
@@ -36,7 +36,7 @@ fn main() {
     // Build the server side: a dispatcher holding one Data object.
     let mut server = RequestDispatcher::new();
     let obis = ObisCode::new(1, 0, 1, 8, 0, 0xFF); // active energy import
-    server.add(Box::new(Data::new(obis.clone(), CosemDataType::DoubleLongUnsigned(123_456))));
+    server.add(Box::new(Data::new(obis, CosemDataType::DoubleLongUnsigned(123_456))));
 
     // Drive it from the client side over the loopback link.
     let link = LocalLink { server, pending: None };
@@ -9,7 +9,7 @@ fn main() {
     let obis = ObisCode::new(0, 0, 1, 0, 0, 255);
     let time = DateTime([0x07, 0xE5, 0x05, 0x01, 0x02, 0x10, 0x25, 0x12, 0x00, 0x00, 0x00, 0x00]);
     let config = ClockConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         time,
         time_zone: 180,
         status: 1,
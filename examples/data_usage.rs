@@ -7,7 +7,7 @@ use spodes_rs::types::CosemDataType;
 fn main() {
     let obis = ObisCode::new(0, 0, 96, 1, 0, 255);
     let value = CosemDataType::Integer(42);
-    let data = Data::new(obis.clone(), value);
+    let data = Data::new(obis, value);
 
     println!("Data object: {data:?}");
     println!("Logical name: {}", data.logical_name());
@@ -9,7 +9,7 @@ fn main() {
     let obis = ObisCode::new(1, 0, 1, 8, 2, 255);
 
     let config = DemandRegisterConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         current_average_value: CosemDataType::DoubleLong(3000),
         last_average_value: CosemDataType::DoubleLong(2500),
         scaler_unit: ScalerUnit::new(0, 0x1B),
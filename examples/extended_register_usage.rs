@@ -9,7 +9,7 @@ fn main() {
     let obis = ObisCode::new(1, 0, 1, 8, 1, 255);
 
     let mut extended_register = ExtendedRegister::new(
-        obis.clone(),
+        obis,
         CosemDataType::DoubleLong(2000),
         ScalerUnit::new(0, 0x1B),
         CosemDataType::Unsigned(1),
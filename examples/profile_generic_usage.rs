@@ -13,7 +13,7 @@ fn main() {
     let data = Data::new(data_obis, CosemDataType::Integer(42));
     let capture_objects = vec![(Arc::new(data) as Arc<dyn InterfaceClass + Send + Sync>, 2)];
     let config = ProfileGenericConfig {
-        logical_name: profile_obis.clone(),
+        logical_name: profile_obis,
         version: 1,
         buffer: vec![],
         capture_objects,
@@ -1,74 +1,98 @@
-//! Push listener example — simulates a COSEM server that can be pushed to.
+//! Push listener example — a head-end receiving the pushes of a COSEM server.
 //!
-//! Demonstrates how to configure a `Push Setup` object and trigger a push
-//! operation. In a real deployment the push data would be sent over the
-//! configured transport (TCP, UDP, SMS, …).
+//! A `PushListener` accepts TCP connections on a local port. A simulated meter
+//! configures a `Push Setup` object pointing at that port, triggers its `push`
+//! method and lets a `PushService` deliver the DATA-NOTIFICATION. The push is
+//! confirmed, so the listener answers it and the meter records the
+//! confirmation. The library `SocketConnector` opens the TCP connection.
 
+use std::io;
+use std::net::TcpListener;
+use std::ops::ControlFlow;
+use std::thread;
+
+use spodes_rs::classes::data::Data;
 use spodes_rs::classes::push_setup::{PushSetup, PushSetupConfig};
 use spodes_rs::interface::InterfaceClass;
+use spodes_rs::listener::{Framing, PushListener};
 use spodes_rs::obis::ObisCode;
+use spodes_rs::push::{transport_service, PushService, SocketConnector};
 use spodes_rs::types::attrs::{
     CaptureObjectDefinition, CommunicationWindow, ConfirmationParameters, DateTime, SendDestinationAndMethod,
 };
 use spodes_rs::types::CosemDataType;
 
-fn main() {
-    // 1. Configure the push destination: TCP to 192.168.1.100:4059, A-XDR encoding.
-    let send_dest = SendDestinationAndMethod {
-        transport_service: 0, // TCP
-        destination: b"192.168.1.100:4059".to_vec(),
-        message: 2, // A-XDR encoded xDLMS APDU
-    };
+fn main() -> io::Result<()> {
+    // 1. The head-end: listen on a local port, answering confirmed pushes.
+    let socket = TcpListener::bind("127.0.0.1:0")?;
+    let address = socket.local_addr()?;
+    println!("Listening for pushes on {address}");
+    let head_end = thread::spawn(move || {
+        let mut listener = PushListener::new().with_confirmations(true);
+        listener.serve_tcp(&socket, Framing::Wrapper, |received| {
+            match received {
+                Ok(push) => {
+                    println!("Push from {:?} (wPort {:?}):", push.peer, push.address);
+                    for value in push.notification.values() {
+                        println!("  {value:?}");
+                    }
+                    println!("  confirmation sent: {}", push.reply.is_some());
+                }
+                Err(e) => println!("Rejected push: {e}"),
+            }
+            ControlFlow::Break(())
+        })
+    });
 
-    // 2. Define a communication window: push is only allowed between 08:00 and 18:00.
-    let window = CommunicationWindow {
-        begin: DateTime::from_ymdhms(2025, 1, 1, 8, 0, 0),
-        end: DateTime::from_ymdhms(2025, 12, 31, 18, 0, 0),
-    };
-
-    // 3. The objects whose values will be pushed.
-    //    Each entry is { class_id, logical_name, attribute_index, data_index }.
-    let push_objects = vec![
-        CaptureObjectDefinition::new(3, ObisCode::new(0, 0, 1, 0, 0, 255), 2, 0), // Register class, attr 2
-        CaptureObjectDefinition::new(1, ObisCode::new(0, 0, 96, 1, 0, 255), 2, 0), // Data class, attr 2
-    ];
-
-    // 4. Build the Push Setup object.
+    // 2. The meter: a Push Setup sending two values to the head-end over TCP,
+    //    confirmed (push_operation_method 2), at any time of day.
+    let energy = ObisCode::new(1, 0, 1, 8, 0, 255);
+    let status = ObisCode::new(0, 0, 96, 1, 0, 255);
     let config = PushSetupConfig {
-        logical_name: ObisCode::new(0, 0, 25, 1, 0, 255),
-        version: 0,
-        push_object_list: push_objects,
-        send_destination_and_method: send_dest,
-        communication_window: vec![window],
-        randomisation_start_interval: 30,
+        logical_name: ObisCode::new(0, 0, 25, 9, 0, 255),
+        version: 2,
+        push_object_list: vec![
+            CaptureObjectDefinition::new(1, energy, 2, 0),
+            CaptureObjectDefinition::new(1, status, 2, 0),
+        ],
+        send_destination_and_method: SendDestinationAndMethod {
+            transport_service: transport_service::TCP,
+            destination: address.to_string().into_bytes(),
+            message: 0, // A-XDR encoded xDLMS APDU
+        },
+        communication_window: vec![CommunicationWindow {
+            begin: DateTime::new([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0x80, 0x00, 0xFF]),
+            end: DateTime::new([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 23, 59, 59, 0, 0x80, 0x00, 0xFF]),
+        }],
+        randomisation_start_interval: 0,
         number_of_retries: 3,
         repetition_delay: CosemDataType::LongUnsigned(60),
         port_reference: vec![],
-        push_client_sap: 0,
+        push_client_sap: 0x66,
         push_protection_parameters: vec![],
-        push_operation_method: 0,
+        push_operation_method: 2,
         confirmation_parameters: ConfirmationParameters { data: vec![] },
-        last_confirmation_date_time: DateTime::new([0u8; 12]),
+        last_confirmation_date_time: DateTime::new([0xFF; 12]),
     };
+    let mut objects: Vec<Box<dyn InterfaceClass>> = vec![
+        Box::new(PushSetup::new(config)),
+        Box::new(Data::new(energy, CosemDataType::DoubleLongUnsigned(123_456))),
+        Box::new(Data::new(status, CosemDataType::Unsigned(1))),
+    ];
 
-    let mut push = PushSetup::new(config);
-
-    println!("Push Setup object created:");
-    println!("  class_id:    {}", push.class_id());
-    println!("  logical_name:{}", push.logical_name());
-    println!("  version:     {}", push.version());
-    println!("  attributes:  {}", push.attributes().len());
-
-    // 5. Trigger the push operation.
-    let result = push.invoke_method(1, Some(CosemDataType::Integer(0)));
-    match result {
-        Ok(CosemDataType::Null) => println!("Push triggered successfully (transport not implemented yet)."),
-        Ok(other) => println!("Push returned unexpected: {other:?}"),
-        Err(e) => println!("Push failed: {e}"),
+    // 3. Trigger the push and deliver it.
+    objects[0].invoke_method(1, Some(CosemDataType::Integer(0))).expect("push method");
+    let now = DateTime::from_ymdhms(2025, 6, 1, 12, 0, 0);
+    let mut service = PushService::new();
+    for attempt in service.poll(&mut objects, &mut SocketConnector, &now) {
+        match attempt.result {
+            Ok(delivery) => println!("Push {} delivered: {delivery:?}", attempt.setup),
+            Err(e) => println!("Push {} failed: {e}", attempt.setup),
+        }
     }
 
-    // 6. Show the serialized representation.
-    let mut buf = Vec::new();
-    push.serialize_ber(&mut buf).unwrap();
-    println!("  serialized: {} bytes", buf.len());
+    head_end.join().expect("listener thread")?;
+    let setup: &PushSetup = objects[0].as_any().downcast_ref().expect("push setup");
+    println!("Last confirmation: {:?}", setup.last_confirmation_date_time());
+    Ok(())
 }
@@ -11,7 +11,7 @@ fn main() {
 
     // Initialize the RegisterActivation configuration
     let config = RegisterActivationConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         register_assignment: vec![ObjectDefinition {
             class_id: 3, // Register
             logical_name: ObisCode::new(1, 0, 1, 8, 0, 255),
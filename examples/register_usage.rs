@@ -9,12 +9,15 @@ fn main() {
     let obis = ObisCode::new(1, 0, 1, 8, 0, 255);
     let value = CosemDataType::DoubleLong(1000);
     let scaler_unit = ScalerUnit::new(0, 0x1B);
-    let mut register = Register::new(obis.clone(), value, scaler_unit);
+    let mut register = Register::new(obis, value, scaler_unit);
 
     println!("Register object: {register:?}");
     println!("Logical name: {}", register.logical_name());
     println!("Class ID: {}", register.class_id());
     println!("Version: {}", register.version());
+    if let Some(info) = register.metadata() {
+        print!("{info}");
+    }
 
     let serialized = serialize_object(&register).expect("Serialization failed");
     println!("Serialized register: {serialized:?}");
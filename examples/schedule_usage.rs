@@ -23,7 +23,7 @@ fn main() {
     }];
 
     // Build the Schedule configuration
-    let config = ScheduleConfig { logical_name: obis.clone(), entries, enabled: true };
+    let config = ScheduleConfig { logical_name: obis, entries, enabled: true };
 
     // Build the Schedule object
     let schedule = Schedule::new(config);
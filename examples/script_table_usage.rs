@@ -21,7 +21,7 @@ fn main() {
     let scripts = vec![Script { script_identifier: 1, actions: vec![action] }];
 
     // Build the ScriptTable configuration
-    let config = ScriptTableConfig { logical_name: obis.clone(), scripts };
+    let config = ScriptTableConfig { logical_name: obis, scripts };
 
     // Build the ScriptTable object
     let mut script_table = ScriptTable::new(config);
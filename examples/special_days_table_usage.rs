@@ -17,7 +17,7 @@ fn main() {
     }];
 
     // Build the SpecialDaysTable configuration
-    let config = SpecialDaysTableConfig { logical_name: obis.clone(), entries };
+    let config = SpecialDaysTableConfig { logical_name: obis, entries };
 
     // Build the SpecialDaysTable object
     let mut special_days_table = SpecialDaysTable::new(config);
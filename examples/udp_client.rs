@@ -82,7 +82,7 @@ fn main() -> io::Result<()> {
     // GET the value attribute (attribute 2) of a Data object (class_id 1).
     let obis = ObisCode::new(1, 0, 1, 8, 0, 0xFF); // active energy import
 
-    match session.get(1, obis.clone(), 2) {
+    match session.get(1, obis, 2) {
         Ok(GetResponse::Normal { result: GetDataResult::Data(value), .. }) => {
             println!("GET {obis} = {value:?}");
         }
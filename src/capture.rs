@@ -0,0 +1,552 @@
+//! Capture driver for Profile generic (class 7) and Compact data (class 62)
+//! objects.
+//!
+//! The objects only hold their buffers;
+//! [`CaptureDriver::tick`](crate::capture::CaptureDriver::tick) reads the
+//! capture objects from the live object set and stores the captures:
+//!
+//! * **Periodic** — a Profile generic with a non-zero `capture_period` is
+//!   captured when the clock crosses a multiple of the period, in local time (a
+//!   1800 s period captures at :00 and :30). The first tick only records the
+//!   running period, or — for a profile whose buffer already holds entries,
+//!   such as a reopened [`FileRing`](crate::classes::profile_buffer::FileRing)
+//!   — the period of the newest entry's Clock column, so the periods missed
+//!   while the meter was off are stamped after a restart. A clock set back
+//!   waits for the next boundary.
+//! * **On demand** — the `capture` method of an object whose capture objects
+//!   are definitions only requests a capture (a script run by the
+//!   [`Scheduler`](crate::scheduler::Scheduler) or
+//!   [`ThresholdMonitor`](crate::monitor::ThresholdMonitor) invokes it); the
+//!   next tick takes the request and captures at once. A Compact data with
+//!   the implicit capture method is refreshed on every tick.
+//! * **Missing periods** — boundaries passed while the driver did not run (a
+//!   power failure) are stamped as the СПОДЭС load profile requires: one entry
+//!   per missing period, up to the buffer capacity, whose Clock columns hold
+//!   the boundary time, whose profile status columns hold
+//!   [`POWER_DOWN`](crate::capture::profile_status::POWER_DOWN) |
+//!   [`DATA_NOT_VALID`](crate::capture::profile_status::DATA_NOT_VALID) and
+//!   whose other columns are `null-data`. Boundaries skipped by a forward clock
+//!   correction reported with
+//!   [`CaptureDriver::clock_adjusted`](crate::capture::CaptureDriver::clock_adjusted)
+//!   are stamped
+//!   [`CLOCK_ADJUSTED`](crate::capture::profile_status::CLOCK_ADJUSTED) instead
+//!   of `POWER_DOWN`.
+//!
+//! A capture object's `data_index` selects an element (1-based) of an array
+//! or structure attribute; 0 captures the whole attribute.
+
+use crate::classes::compact_data::{capture_method, CompactData};
+use crate::classes::profile_generic::ProfileGeneric;
+use crate::interface::{AccessError, InterfaceClass};
+use crate::obis::ObisCode;
+use crate::scheduler::local_seconds;
+use crate::types::attrs::{CaptureObjectDefinition, SortMethod};
+use crate::types::datetime::DateTime;
+use crate::types::CosemDataType;
+use std::collections::BTreeMap;
+
+/// Bits of the profile status captured with an entry (object
+/// `0.0.96.10.1.255` by default).
+pub mod profile_status {
+    /// A critical error occurred during the period.
+    pub const CRITICAL_ERROR: u8 = 0x01;
+    /// The clock was invalid during the period.
+    pub const CLOCK_INVALID: u8 = 0x02;
+    /// The captured values are not valid.
+    pub const DATA_NOT_VALID: u8 = 0x04;
+    /// Daylight saving was active.
+    pub const DAYLIGHT_SAVING: u8 = 0x08;
+    /// The clock was adjusted during the period.
+    pub const CLOCK_ADJUSTED: u8 = 0x20;
+    /// The meter was powered down during the period.
+    pub const POWER_DOWN: u8 = 0x80;
+}
+
+/// Most missing periods stamped at once in a profile without a capacity.
+const MAX_MISSING: i64 = 10_000;
+
+/// Why an object was captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureReason {
+    /// The clock crossed a capture period boundary.
+    Period,
+    /// A period boundary passed while the driver did not run.
+    Missing,
+    /// The object's `capture` method was invoked.
+    Request,
+    /// The Compact data captures implicitly.
+    Implicit,
+}
+
+/// One capture of [`CaptureDriver::tick`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capture {
+    /// Logical name of the Profile generic or Compact data.
+    pub object: ObisCode,
+    /// Why it was captured.
+    pub reason: CaptureReason,
+    /// The period boundary, or the tick time of other captures.
+    pub time: DateTime,
+    /// The result of reading the capture objects and storing the entry.
+    pub result: Result<(), AccessError>,
+}
+
+/// Captures the Profile generic and Compact data objects of an object set,
+/// keeping the last captured period of each profile between ticks.
+#[derive(Debug, Clone)]
+pub struct CaptureDriver {
+    status_object: ObisCode,
+    served: BTreeMap<ObisCode, i64>,
+    /// Set by [`CaptureDriver::clock_adjusted`] until the next tick.
+    adjusted: bool,
+}
+
+impl Default for CaptureDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CaptureDriver {
+    /// Creates a driver stamping missing periods in the `0.0.96.10.1.255`
+    /// status columns.
+    pub fn new() -> Self {
+        Self { status_object: ObisCode::new(0, 0, 96, 10, 1, 255), served: BTreeMap::new(), adjusted: false }
+    }
+
+    /// Stamps the status of missing periods in the columns capturing
+    /// `logical_name`.
+    pub fn with_status_object(mut self, logical_name: ObisCode) -> Self {
+        self.status_object = logical_name;
+        self
+    }
+
+    /// Forgets the captured periods: the next tick takes them from the
+    /// buffers again, or only records them.
+    pub fn reset(&mut self) {
+        self.served.clear();
+    }
+
+    /// Reports that the clock was set forward: the boundaries the next tick
+    /// finds skipped are stamped
+    /// [`CLOCK_ADJUSTED`](profile_status::CLOCK_ADJUSTED) |
+    /// [`DATA_NOT_VALID`](profile_status::DATA_NOT_VALID) rather than as a
+    /// power failure.
+    pub fn clock_adjusted(&mut self) {
+        self.adjusted = true;
+    }
+
+    /// Captures every object due at `now`: the missing and current periods
+    /// of periodic profiles, then the requested and implicit captures. A
+    /// `now` that is not fully specified only serves requests.
+    pub fn tick(&mut self, objects: &mut [Box<dyn InterfaceClass>], now: &DateTime) -> Vec<Capture> {
+        let local = local_seconds(now);
+        let deviation = now.deviation().unwrap_or(0);
+        let instant = |local: i64| DateTime::from_unix(local - i64::from(deviation) * 60, deviation);
+        let missing_status = if std::mem::take(&mut self.adjusted) {
+            profile_status::CLOCK_ADJUSTED | profile_status::DATA_NOT_VALID
+        } else {
+            profile_status::POWER_DOWN | profile_status::DATA_NOT_VALID
+        };
+        let mut captures = Vec::new();
+        for index in 0..objects.len() {
+            let object = objects[index].as_any_mut();
+            if let Some(profile) = object.downcast_mut::<ProfileGeneric>() {
+                let object = *profile.logical_name();
+                let requested = profile.take_capture_request();
+                let period = i64::from(profile.capture_period());
+                let capacity = match profile.profile_entries() {
+                    0 => MAX_MISSING,
+                    entries => i64::from(entries).min(MAX_MISSING),
+                };
+                let mut due = Vec::new();
+                if let (Some(local), true) = (local, period > 0) {
+                    let boundary = local - local.rem_euclid(period);
+                    let served = match self.served.get(&object) {
+                        Some(served) => Some(*served),
+                        None => newest_capture(profile).map(|newest| newest - newest.rem_euclid(period)),
+                    };
+                    self.served.insert(object, boundary);
+                    match served {
+                        Some(served) if boundary > served => {
+                            let missing = ((boundary - served) / period - 1).min(capacity);
+                            due.extend((1..=missing).rev().map(|n| (CaptureReason::Missing, boundary - n * period)));
+                            due.push((CaptureReason::Period, boundary));
+                        }
+                        // The clock was set back: keep the boundary already
+                        // captured until the clock passes it again.
+                        Some(served) if boundary < served => {
+                            self.served.insert(object, served);
+                        }
+                        _ => {}
+                    }
+                }
+                if requested {
+                    due.push((CaptureReason::Request, local.unwrap_or_default()));
+                }
+                for (reason, at) in due {
+                    let time = match reason {
+                        CaptureReason::Request => now.clone(),
+                        _ => match instant(at) {
+                            Some(time) => time,
+                            None => continue,
+                        },
+                    };
+                    let result = self.capture_profile(objects, index, reason, &time, missing_status);
+                    captures.push(Capture { object, reason, time, result });
+                }
+            } else if let Some(compact) = object.downcast_mut::<CompactData>() {
+                let object = *compact.logical_name();
+                let reason = if compact.take_capture_request() {
+                    CaptureReason::Request
+                } else if compact.capture_method() == capture_method::IMPLICIT {
+                    CaptureReason::Implicit
+                } else {
+                    continue;
+                };
+                let result = capture_compact(objects, index);
+                captures.push(Capture { object, reason, time: now.clone(), result });
+            }
+        }
+        captures
+    }
+
+    /// Reads the capture objects of the profile at `index` (or stamps a
+    /// missing period at `time` with `status`) and appends the entry.
+    fn capture_profile(
+        &self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        index: usize,
+        reason: CaptureReason,
+        time: &DateTime,
+        status: u8,
+    ) -> Result<(), AccessError> {
+        let definitions = profile(objects, index).capture_definitions();
+        let entry = if reason == CaptureReason::Missing {
+            definitions.iter().map(|definition| self.missing_value(objects, definition, time, status)).collect()
+        } else {
+            read_all(objects, &definitions)?
+        };
+        objects[index]
+            .as_any_mut()
+            .downcast_mut::<ProfileGeneric>()
+            .expect("profile at index")
+            .append(CosemDataType::Structure(entry))
+    }
+
+    /// The value of a column in the entry of a missing period.
+    fn missing_value(
+        &self,
+        objects: &[Box<dyn InterfaceClass>],
+        definition: &CaptureObjectDefinition,
+        time: &DateTime,
+        status: u8,
+    ) -> CosemDataType {
+        if is_clock(definition) {
+            CosemDataType::OctetString(time.as_bytes().to_vec())
+        } else if definition.logical_name == self.status_object {
+            read(objects, definition)
+                .ok()
+                .and_then(|current| current.integer_like(i64::from(status)))
+                .unwrap_or(CosemDataType::Unsigned(status))
+        } else {
+            CosemDataType::Null
+        }
+    }
+}
+
+/// Whether a capture object is a Clock time.
+fn is_clock(definition: &CaptureObjectDefinition) -> bool {
+    definition.class_id == 8 && definition.attribute_index == 2 && definition.data_index == 0
+}
+
+/// The local time of the newest entry of a profile, from its Clock column.
+fn newest_capture(profile: &ProfileGeneric) -> Option<i64> {
+    let column = profile.capture_definitions().iter().position(is_clock)?;
+    let time = |entry: CosemDataType| match entry {
+        CosemDataType::Structure(values) => {
+            values.get(column).and_then(|value| DateTime::try_from(value).ok()).and_then(|time| local_seconds(&time))
+        }
+        _ => None,
+    };
+    match profile.sort_method() {
+        SortMethod::Fifo => profile.entry(profile.len().checked_sub(1)?).ok()?.and_then(time),
+        SortMethod::Lifo => profile.entry(0).ok()?.and_then(time),
+        // Value-sorted buffers keep the newest entry anywhere.
+        _ => profile.entries(0..profile.len()).ok()?.into_iter().filter_map(time).max(),
+    }
+}
+
+/// The Profile generic at `index`.
+fn profile(objects: &[Box<dyn InterfaceClass>], index: usize) -> &ProfileGeneric {
+    objects[index].as_any().downcast_ref::<ProfileGeneric>().expect("profile at index")
+}
+
+/// Reads the capture objects of the Compact data at `index` and stores them.
+fn capture_compact(objects: &mut [Box<dyn InterfaceClass>], index: usize) -> Result<(), AccessError> {
+    let compact = objects[index].as_any().downcast_ref::<CompactData>().expect("compact data at index");
+    let values = read_all(objects, &compact.capture_definitions())?;
+    objects[index].as_any_mut().downcast_mut::<CompactData>().expect("compact data at index").store(&values)
+}
+
+/// Reads every capture object, in order.
+fn read_all(
+    objects: &[Box<dyn InterfaceClass>],
+    definitions: &[CaptureObjectDefinition],
+) -> Result<Vec<CosemDataType>, AccessError> {
+    definitions.iter().map(|definition| read(objects, definition)).collect()
+}
+
+/// Reads the attribute (or the `data_index` element of it) a capture object
+/// refers to.
+fn read(
+    objects: &[Box<dyn InterfaceClass>],
+    definition: &CaptureObjectDefinition,
+) -> Result<CosemDataType, AccessError> {
+    let object = objects
+        .iter()
+        .find(|o| o.class_id() == definition.class_id && *o.logical_name() == definition.logical_name)
+        .ok_or_else(|| {
+            AccessError::ObjectUndefined(format!(
+                "No object {} of class {}",
+                definition.logical_name, definition.class_id
+            ))
+        })?;
+    let value = object.get_attribute(definition.attribute_index).ok_or_else(|| {
+        AccessError::ObjectUnavailable(format!(
+            "No attribute {} in {}",
+            definition.attribute_index, definition.logical_name
+        ))
+    })?;
+    if definition.data_index == 0 {
+        return Ok(value);
+    }
+    match value {
+        CosemDataType::Array(mut items) | CosemDataType::Structure(mut items)
+            if usize::from(definition.data_index) <= items.len() =>
+        {
+            Ok(items.swap_remove(usize::from(definition.data_index) - 1))
+        }
+        _ => Err(AccessError::ObjectUnavailable(format!(
+            "No element {} in attribute {} of {}",
+            definition.data_index, definition.attribute_index, definition.logical_name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::clock::{Clock, ClockConfig};
+    use crate::classes::compact_data::CompactDataConfig;
+    use crate::classes::data::Data;
+    use crate::classes::profile_generic::ProfileGenericConfig;
+    use crate::classes::register::Register;
+    use crate::classes::script_table::{ScriptTable, ScriptTableConfig};
+    use crate::scheduler::execute_script;
+    use crate::types::attrs::{ActionSpecification, ExecutedScript, ScalerUnit, Script, SortMethod};
+
+    fn clock_ln() -> ObisCode {
+        ObisCode::new(0, 0, 1, 0, 0, 255)
+    }
+
+    fn energy_ln() -> ObisCode {
+        ObisCode::new(1, 0, 1, 8, 0, 255)
+    }
+
+    fn status_ln() -> ObisCode {
+        ObisCode::new(0, 0, 96, 10, 1, 255)
+    }
+
+    fn load_profile(capture_period: u32) -> ProfileGeneric {
+        ProfileGeneric::new(ProfileGenericConfig {
+            logical_name: ObisCode::new(1, 0, 99, 1, 0, 255),
+            version: 1,
+            buffer: vec![],
+            capture_objects: vec![],
+            capture_period,
+            sort_method: SortMethod::Fifo,
+            sort_object: None,
+            entries_in_use: 0,
+            profile_entries: 100,
+        })
+        .with_capture_definitions(vec![
+            CaptureObjectDefinition::new(8, clock_ln(), 2, 0),
+            CaptureObjectDefinition::new(1, status_ln(), 2, 0),
+            CaptureObjectDefinition::new(3, energy_ln(), 2, 0),
+        ])
+    }
+
+    fn objects(profile: ProfileGeneric, now: &DateTime) -> Vec<Box<dyn InterfaceClass>> {
+        let clock = Clock::new(ClockConfig {
+            logical_name: clock_ln(),
+            time: now.clone(),
+            time_zone: 0,
+            status: 0,
+            daylight_savings_begin: DateTime::new([0xFF; 12]),
+            daylight_savings_end: DateTime::new([0xFF; 12]),
+            daylight_savings_deviation: 0,
+            daylight_savings_enabled: false,
+            clock_base: 1,
+        });
+        vec![
+            Box::new(profile),
+            Box::new(clock),
+            Box::new(Data::new(status_ln(), CosemDataType::Unsigned(0))),
+            Box::new(Register::new(energy_ln(), CosemDataType::DoubleLongUnsigned(1000), ScalerUnit::new(0, 30))),
+        ]
+    }
+
+    /// 2025-06-01 at `hour`:`minute` as the driver stamps it.
+    fn at(hour: u8, minute: u8) -> DateTime {
+        DateTime::from_unix(DateTime::from_ymdhms(2025, 6, 1, hour, minute, 0).to_unix().unwrap(), 0).unwrap()
+    }
+
+    fn entries(objects: &[Box<dyn InterfaceClass>]) -> Vec<CosemDataType> {
+        profile(objects, 0).entries(0..profile(objects, 0).len()).unwrap()
+    }
+
+    #[test]
+    fn captures_on_period_boundaries_and_stamps_missing_periods() {
+        let start = DateTime::from_ymdhms(2025, 6, 1, 11, 50, 0);
+        let mut objects = objects(load_profile(1800), &start);
+        let mut driver = CaptureDriver::new();
+        // The first tick records the running period.
+        assert!(driver.tick(&mut objects, &start).is_empty());
+        assert!(driver.tick(&mut objects, &DateTime::from_ymdhms(2025, 6, 1, 11, 59, 59)).is_empty());
+
+        let noon = DateTime::from_ymdhms(2025, 6, 1, 12, 0, 5);
+        let captures = driver.tick(&mut objects, &noon);
+        assert_eq!(captures.len(), 1);
+        assert_eq!(captures[0].reason, CaptureReason::Period);
+        assert_eq!(captures[0].time, at(12, 0));
+        assert_eq!(captures[0].result, Ok(()));
+        let CosemDataType::Structure(entry) = &entries(&objects)[0] else { panic!("entry") };
+        assert_eq!(entry[2], CosemDataType::DoubleLongUnsigned(1000));
+
+        // Powered down from 12:10 to 13:40: 12:30 and 13:00 are missing.
+        let captures = driver.tick(&mut objects, &DateTime::from_ymdhms(2025, 6, 1, 13, 40, 0));
+        let reasons: Vec<_> = captures.iter().map(|c| c.reason).collect();
+        assert_eq!(reasons, [CaptureReason::Missing, CaptureReason::Missing, CaptureReason::Period]);
+        let entries = entries(&objects);
+        assert_eq!(entries.len(), 4);
+        let missing = at(12, 30);
+        assert_eq!(
+            entries[1],
+            CosemDataType::Structure(vec![
+                CosemDataType::OctetString(missing.as_bytes().to_vec()),
+                CosemDataType::Unsigned(profile_status::POWER_DOWN | profile_status::DATA_NOT_VALID),
+                CosemDataType::Null,
+            ])
+        );
+        assert_eq!(captures[2].time, at(13, 30));
+
+        // A clock set back does not capture the served periods again.
+        assert!(driver.tick(&mut objects, &DateTime::from_ymdhms(2025, 6, 1, 13, 0, 0)).is_empty());
+        assert!(driver.tick(&mut objects, &DateTime::from_ymdhms(2025, 6, 1, 13, 50, 0)).is_empty());
+    }
+
+    #[test]
+    fn restarted_driver_stamps_the_outage_from_the_buffer() {
+        let noon = DateTime::from_ymdhms(2025, 6, 1, 12, 0, 5);
+        let mut before = objects(load_profile(1800), &noon);
+        let mut driver = CaptureDriver::new();
+        driver.tick(&mut before, &DateTime::from_ymdhms(2025, 6, 1, 11, 50, 0));
+        assert_eq!(driver.tick(&mut before, &noon).len(), 1);
+        let half_past = DateTime::from_ymdhms(2025, 6, 1, 12, 30, 0);
+        before[1].set_attribute(2, half_past.clone().into()).unwrap();
+        assert_eq!(driver.tick(&mut before, &half_past).len(), 1);
+
+        // The meter restarts at 13:40 with the stored buffer and a new driver.
+        let restart = DateTime::from_ymdhms(2025, 6, 1, 13, 40, 0);
+        let mut after = objects(load_profile(1800), &restart);
+        let profile = after[0].as_any_mut().downcast_mut::<ProfileGeneric>().unwrap();
+        for entry in entries(&before) {
+            profile.append(entry).unwrap();
+        }
+        let captures = CaptureDriver::new().tick(&mut after, &restart);
+        let stamped: Vec<_> = captures.iter().map(|c| (c.reason, c.time.clone())).collect();
+        assert_eq!(stamped, [(CaptureReason::Missing, at(13, 0)), (CaptureReason::Period, at(13, 30))]);
+        assert_eq!(entries(&after).len(), 4);
+    }
+
+    #[test]
+    fn forward_clock_correction_is_stamped_clock_adjusted() {
+        let start = DateTime::from_ymdhms(2025, 6, 1, 12, 10, 0);
+        let mut objects = objects(load_profile(1800), &start);
+        let mut driver = CaptureDriver::new();
+        driver.tick(&mut objects, &start);
+        driver.clock_adjusted();
+        let captures = driver.tick(&mut objects, &DateTime::from_ymdhms(2025, 6, 1, 13, 5, 0));
+        let reasons: Vec<_> = captures.iter().map(|c| c.reason).collect();
+        assert_eq!(reasons, [CaptureReason::Missing, CaptureReason::Period]);
+        let CosemDataType::Structure(missing) = &entries(&objects)[0] else { panic!("entry") };
+        assert_eq!(
+            missing[1],
+            CosemDataType::Unsigned(profile_status::CLOCK_ADJUSTED | profile_status::DATA_NOT_VALID)
+        );
+    }
+
+    #[test]
+    fn script_requests_capture_with_data_index() {
+        let now = DateTime::from_ymdhms(2025, 6, 1, 12, 0, 0);
+        let values = ObisCode::new(0, 0, 96, 1, 0, 255);
+        let script_table = ObisCode::new(0, 0, 10, 0, 1, 255);
+        let mut objects = objects(
+            load_profile(0).with_capture_definitions(vec![CaptureObjectDefinition::new(1, values, 2, 2)]),
+            &now,
+        );
+        objects.push(Box::new(Data::new(
+            values,
+            CosemDataType::Structure(vec![CosemDataType::Unsigned(1), CosemDataType::LongUnsigned(7)]),
+        )));
+        objects.push(Box::new(ScriptTable::new(ScriptTableConfig {
+            logical_name: script_table,
+            scripts: vec![Script {
+                script_identifier: 1,
+                actions: vec![ActionSpecification {
+                    service_id: 2,
+                    class_id: 7,
+                    logical_name: ObisCode::new(1, 0, 99, 1, 0, 255),
+                    index: 2,
+                    parameter: CosemDataType::Integer(0),
+                }],
+            }],
+        })));
+        let mut driver = CaptureDriver::new();
+        execute_script(&mut objects, &ExecutedScript { script_logical_name: script_table, script_selector: 1 })
+            .unwrap();
+        assert!(profile(&objects, 0).is_empty());
+
+        let captures = driver.tick(&mut objects, &now);
+        assert_eq!(captures.len(), 1);
+        assert_eq!(captures[0].reason, CaptureReason::Request);
+        assert_eq!(entries(&objects), [CosemDataType::Structure(vec![CosemDataType::LongUnsigned(7)])]);
+        // The request was taken.
+        assert!(driver.tick(&mut objects, &now).is_empty());
+    }
+
+    #[test]
+    fn compact_data_captures_live_values() {
+        let now = DateTime::from_ymdhms(2025, 6, 1, 12, 0, 0);
+        let compact = CompactData::new(CompactDataConfig {
+            logical_name: ObisCode::new(0, 0, 66, 0, 1, 255),
+            compact_buffer: vec![],
+            capture_objects: vec![],
+            template_id: 1,
+            template_description: vec![],
+            capture_method: capture_method::INVOKE,
+        })
+        .with_capture_definitions(vec![CaptureObjectDefinition::new(3, energy_ln(), 2, 0)]);
+        let mut objects = objects(load_profile(0), &now);
+        objects.push(Box::new(compact));
+        objects[4].invoke_method(2, None).unwrap();
+        objects[3].set_attribute(2, CosemDataType::DoubleLongUnsigned(1234)).unwrap();
+
+        let captures = CaptureDriver::new().tick(&mut objects, &now);
+        assert_eq!(captures.len(), 1);
+        assert_eq!(captures[0].result, Ok(()));
+        let compact = objects[4].as_any().downcast_ref::<CompactData>().unwrap();
+        assert_eq!(compact.compact_buffer(), &[0, 0, 0x04, 0xD2]);
+    }
+}
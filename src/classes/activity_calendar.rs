@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{DayProfile, SeasonProfile, WeekProfile};
 use crate::types::{BerError, CosemDataType};
@@ -64,6 +64,52 @@ impl ActivityCalendar {
         }
     }
 
+    /// Name of the active calendar (attr 2).
+    pub fn calendar_name_active(&self) -> &[u8] {
+        &self.calendar_name_active
+    }
+
+    /// The active season profile (attr 3).
+    pub fn season_profile_active(&self) -> &[SeasonProfile] {
+        &self.season_profile_active
+    }
+
+    /// The active week profile table (attr 4).
+    pub fn week_profile_table_active(&self) -> &[WeekProfile] {
+        &self.week_profile_table_active
+    }
+
+    /// The active day profile table (attr 5).
+    pub fn day_profile_table_active(&self) -> &[DayProfile] {
+        &self.day_profile_table_active
+    }
+
+    /// Name of the passive calendar (attr 6).
+    pub fn calendar_name_passive(&self) -> &[u8] {
+        &self.calendar_name_passive
+    }
+
+    /// The passive season profile (attr 7).
+    pub fn season_profile_passive(&self) -> &[SeasonProfile] {
+        &self.season_profile_passive
+    }
+
+    /// The passive week profile table (attr 8).
+    pub fn week_profile_table_passive(&self) -> &[WeekProfile] {
+        &self.week_profile_table_passive
+    }
+
+    /// The passive day profile table (attr 9).
+    pub fn day_profile_table_passive(&self) -> &[DayProfile] {
+        &self.day_profile_table_passive
+    }
+
+    /// The date-time octet-string at which the passive calendar becomes
+    /// active (attr 10).
+    pub fn activate_passive_calendar_time(&self) -> &[u8] {
+        &self.activate_passive_calendar_time
+    }
+
     /// Method 1: `activate_passive_calendar` — copies the passive calendar over
     /// the active one, making it effective immediately (IEC 62056-6-2 §4.5.5.3).
     fn activate_passive_calendar(&mut self) -> CosemDataType {
@@ -75,6 +121,23 @@ impl ActivityCalendar {
     }
 }
 
+/// Attribute descriptors of the Activity calendar class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "calendar_name_active", DataKind::OctetString).writable(),
+    AttributeInfo::new(3, "season_profile_active", DataKind::Array).writable(),
+    AttributeInfo::new(4, "week_profile_table_active", DataKind::Array).writable(),
+    AttributeInfo::new(5, "day_profile_table_active", DataKind::Array).writable(),
+    AttributeInfo::new(6, "calendar_name_passive", DataKind::OctetString).writable(),
+    AttributeInfo::new(7, "season_profile_passive", DataKind::Array).writable(),
+    AttributeInfo::new(8, "week_profile_table_passive", DataKind::Array).writable(),
+    AttributeInfo::new(9, "day_profile_table_passive", DataKind::Array).writable(),
+    AttributeInfo::new(10, "activate_passive_calendar_time", DataKind::DateTime).writable(),
+];
+
+/// Method descriptors of the Activity calendar class.
+const METHODS: &[MethodInfo] = &[MethodInfo::new(1, "activate_passive_calendar", DataKind::Integer)];
+
 impl InterfaceClass for ActivityCalendar {
     fn class_id(&self) -> u16 {
         20
@@ -89,24 +152,39 @@ impl InterfaceClass for ActivityCalendar {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::OctetString(self.calendar_name_active.clone())),
-            (3, CosemDataType::Array(self.season_profile_active.iter().map(|s| s.clone().into()).collect())),
-            (4, CosemDataType::Array(self.week_profile_table_active.iter().map(|w| w.clone().into()).collect())),
-            (5, CosemDataType::Array(self.day_profile_table_active.iter().map(|d| d.clone().into()).collect())),
-            (6, CosemDataType::OctetString(self.calendar_name_passive.clone())),
-            (7, CosemDataType::Array(self.season_profile_passive.iter().map(|s| s.clone().into()).collect())),
-            (8, CosemDataType::Array(self.week_profile_table_passive.iter().map(|w| w.clone().into()).collect())),
-            (9, CosemDataType::Array(self.day_profile_table_passive.iter().map(|d| d.clone().into()).collect())),
-            (10, CosemDataType::OctetString(self.activate_passive_calendar_time.clone())),
-        ]
+        (1..=10).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::OctetString(self.calendar_name_active.clone()),
+            3 => CosemDataType::Array(self.season_profile_active.iter().map(|s| s.clone().into()).collect()),
+            4 => CosemDataType::Array(self.week_profile_table_active.iter().map(|w| w.clone().into()).collect()),
+            5 => CosemDataType::Array(self.day_profile_table_active.iter().map(|d| d.clone().into()).collect()),
+            6 => CosemDataType::OctetString(self.calendar_name_passive.clone()),
+            7 => CosemDataType::Array(self.season_profile_passive.iter().map(|s| s.clone().into()).collect()),
+            8 => CosemDataType::Array(self.week_profile_table_passive.iter().map(|w| w.clone().into()).collect()),
+            9 => CosemDataType::Array(self.day_profile_table_passive.iter().map(|d| d.clone().into()).collect()),
+            10 => CosemDataType::OctetString(self.activate_passive_calendar_time.clone()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "activate_passive_calendar".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Activity calendar",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -159,58 +237,73 @@ impl InterfaceClass for ActivityCalendar {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => {
-                self.calendar_name_active = take_octet_string(&value).map_err(|e| format!("{e:?}"))?;
+                self.calendar_name_active =
+                    take_octet_string(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
             3 => {
-                self.season_profile_active = take_typed_array(&value).map_err(|e| format!("{e:?}"))?;
+                self.season_profile_active =
+                    take_typed_array(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
             4 => {
-                self.week_profile_table_active = take_typed_array(&value).map_err(|e| format!("{e:?}"))?;
+                self.week_profile_table_active =
+                    take_typed_array(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
             5 => {
-                self.day_profile_table_active = take_typed_array(&value).map_err(|e| format!("{e:?}"))?;
+                self.day_profile_table_active =
+                    take_typed_array(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
             6 => {
-                self.calendar_name_passive = take_octet_string(&value).map_err(|e| format!("{e:?}"))?;
+                self.calendar_name_passive =
+                    take_octet_string(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
             7 => {
-                self.season_profile_passive = take_typed_array(&value).map_err(|e| format!("{e:?}"))?;
+                self.season_profile_passive =
+                    take_typed_array(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
             8 => {
-                self.week_profile_table_passive = take_typed_array(&value).map_err(|e| format!("{e:?}"))?;
+                self.week_profile_table_passive =
+                    take_typed_array(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
             9 => {
-                self.day_profile_table_passive = take_typed_array(&value).map_err(|e| format!("{e:?}"))?;
+                self.day_profile_table_passive =
+                    take_typed_array(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
             10 => {
-                self.activate_passive_calendar_time = take_octet_string(&value).map_err(|e| format!("{e:?}"))?;
+                self.activate_passive_calendar_time =
+                    take_octet_string(&value).map_err(|e| AccessError::TypeUnmatched(format!("{e:?}")))?;
                 Ok(())
             }
-            _ => Err(format!("ActivityCalendar attribute {attribute_id} is not writable")),
+            _ => {
+                Err(AccessError::ReadWriteDenied(format!("ActivityCalendar attribute {attribute_id} is not writable")))
+            }
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => Ok(self.activate_passive_calendar()),
-            _ => Err(format!("Method {method_id} not supported for Activity calendar")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Activity calendar"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_octet_string(value: &CosemDataType) -> Result<Vec<u8>, BerError> {
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::ActionItem;
 use crate::types::{BerError, CosemDataType};
@@ -68,6 +68,20 @@ impl Arbitrator {
     }
 }
 
+/// Attribute descriptors of the Arbitrator class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "actions", DataKind::Array),
+    AttributeInfo::new(3, "permissions_table", DataKind::Array),
+    AttributeInfo::new(4, "weightings_table", DataKind::Array),
+    AttributeInfo::new(5, "most_recent_requests_table", DataKind::Array).dynamic(),
+    AttributeInfo::new(6, "last_outcome", DataKind::Unsigned).dynamic(),
+];
+
+/// Method descriptors of the Arbitrator class.
+const METHODS: &[MethodInfo] =
+    &[MethodInfo::new(1, "request_action", DataKind::Structure), MethodInfo::new(2, "reset", DataKind::Integer)];
+
 impl InterfaceClass for Arbitrator {
     fn class_id(&self) -> u16 {
         68
@@ -82,20 +96,35 @@ impl InterfaceClass for Arbitrator {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.actions.iter().map(|a| CosemDataType::from(a.clone())).collect())),
-            (3, CosemDataType::Array(self.permissions_table.clone())),
-            (4, CosemDataType::Array(self.weightings_table.clone())),
-            (5, CosemDataType::Array(self.most_recent_requests_table.clone())),
-            (6, CosemDataType::Unsigned(self.last_outcome)),
-        ]
+        (1..=6).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.actions.iter().map(|a| CosemDataType::from(a.clone())).collect()),
+            3 => CosemDataType::Array(self.permissions_table.clone()),
+            4 => CosemDataType::Array(self.weightings_table.clone()),
+            5 => CosemDataType::Array(self.most_recent_requests_table.clone()),
+            6 => CosemDataType::Unsigned(self.last_outcome),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "request_action".to_string()), (2, "reset".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Arbitrator",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -150,17 +179,23 @@ impl InterfaceClass for Arbitrator {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
-            1 => Ok(self.request_action(params.ok_or("Missing method parameter")?)),
+            1 => Ok(self.request_action(
+                params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?,
+            )),
             2 => Ok(self.reset()),
-            _ => Err(format!("Method {method_id} not supported for Arbitrator")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Arbitrator"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_array(value: &CosemDataType) -> Result<Vec<CosemDataType>, BerError> {
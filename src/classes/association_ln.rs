@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::security::access_rights::ObjectListEntry;
 use crate::security::{gost3410, hls, signature, AuthMechanism, SecuritySuite};
@@ -234,7 +234,7 @@ impl AssociationLn {
     }
 
     /// Method 2: `change_HLS_secret` — replaces the secret (password/key).
-    fn change_hls_secret(&mut self, data: CosemDataType) -> Result<CosemDataType, String> {
+    fn change_hls_secret(&mut self, data: CosemDataType) -> Result<CosemDataType, AccessError> {
         match data {
             CosemDataType::OctetString(secret) => {
                 // Zeroize the replaced secret so it does not linger in memory.
@@ -242,7 +242,7 @@ impl AssociationLn {
                 self.secret = secret;
                 Ok(CosemDataType::Null)
             }
-            _ => Err("Expected OctetString for HLS secret".to_string()),
+            _ => Err(AccessError::TypeUnmatched("Expected OctetString for HLS secret".to_string())),
         }
     }
 
@@ -258,9 +258,9 @@ impl AssociationLn {
     /// [`AssociationLn::set_stoc`]/[`AssociationLn::generate_stoc`] and
     /// [`AssociationLn::set_ctos`]. For mechanism 5 (GMAC), also
     /// [`AssociationLn::set_hls_context`].
-    fn reply_to_hls_authentication(&self, data: CosemDataType) -> Result<CosemDataType, String> {
+    fn reply_to_hls_authentication(&self, data: CosemDataType) -> Result<CosemDataType, AccessError> {
         let CosemDataType::OctetString(f_stoc) = data else {
-            return Err("Expected octet-string for f(StoC)".to_string());
+            return Err(AccessError::TypeUnmatched("Expected octet-string for f(StoC)".to_string()));
         };
         let mechanism = self.hls_mechanism();
 
@@ -270,28 +270,41 @@ impl AssociationLn {
             return if self.secret.len() == f_stoc.len() && self.secret.ct_eq(f_stoc.as_slice()).into() {
                 Ok(CosemDataType::Null)
             } else {
-                Err("LLS authentication failed".to_string())
+                Err(AccessError::ReadWriteDenied("LLS authentication failed".to_string()))
             };
         }
 
-        let stoc = self.stoc.as_ref().ok_or("StoC challenge not set (Pass 2 missing)")?;
-        let ctos = self.ctos.as_ref().ok_or("CtoS challenge not set (Pass 1 missing)")?;
+        let stoc = self
+            .stoc
+            .as_ref()
+            .ok_or_else(|| AccessError::TemporaryFailure("StoC challenge not set (Pass 2 missing)".to_string()))?;
+        let ctos = self
+            .ctos
+            .as_ref()
+            .ok_or_else(|| AccessError::TemporaryFailure("CtoS challenge not set (Pass 1 missing)".to_string()))?;
 
         match mechanism {
             // Mechanisms 3/4: f(challenge) = HASH(challenge ‖ secret).
             AuthMechanism::HlsMd5 | AuthMechanism::HlsSha1 => {
                 let secret = self.secret_bytes();
-                let expected = hls::hash_legacy(mechanism, stoc, secret).ok_or("HLS hash computation failed")?;
+                let expected = hls::hash_legacy(mechanism, stoc, secret)
+                    .ok_or_else(|| AccessError::OtherReason("HLS hash computation failed".to_string()))?;
                 if expected != f_stoc {
-                    return Err("HLS authentication failed: f(StoC) mismatch".to_string());
+                    return Err(AccessError::ReadWriteDenied(
+                        "HLS authentication failed: f(StoC) mismatch".to_string(),
+                    ));
                 }
-                let f_ctos = hls::hash_legacy(mechanism, ctos, secret).ok_or("HLS hash computation failed")?;
+                let f_ctos = hls::hash_legacy(mechanism, ctos, secret)
+                    .ok_or_else(|| AccessError::OtherReason("HLS hash computation failed".to_string()))?;
                 Ok(CosemDataType::OctetString(f_ctos))
             }
             // Mechanisms 6/9: f = HASH(secret ‖ ST_a ‖ ST_b ‖ chal_a ‖ chal_b).
             AuthMechanism::HlsSha256 | AuthMechanism::HlsGostStreebog => {
                 let secret = self.secret_bytes();
-                let ctx = self.hls_context.as_ref().ok_or("HLS context (system titles) not set")?;
+                let ctx = self
+                    .hls_context
+                    .as_ref()
+                    .ok_or_else(|| AccessError::TemporaryFailure("HLS context (system titles) not set".to_string()))?;
                 let expected = hls::hash_with_titles(
                     mechanism,
                     secret,
@@ -300,9 +313,11 @@ impl AssociationLn {
                     stoc,
                     ctos,
                 )
-                .ok_or("HLS hash computation failed")?;
+                .ok_or_else(|| AccessError::OtherReason("HLS hash computation failed".to_string()))?;
                 if expected != f_stoc {
-                    return Err("HLS authentication failed: f(StoC) mismatch".to_string());
+                    return Err(AccessError::ReadWriteDenied(
+                        "HLS authentication failed: f(StoC) mismatch".to_string(),
+                    ));
                 }
                 let f_ctos = hls::hash_with_titles(
                     mechanism,
@@ -312,23 +327,30 @@ impl AssociationLn {
                     ctos,
                     stoc,
                 )
-                .ok_or("HLS hash computation failed")?;
+                .ok_or_else(|| AccessError::OtherReason("HLS hash computation failed".to_string()))?;
                 Ok(CosemDataType::OctetString(f_ctos))
             }
             // Mechanism 5: f = SC ‖ IC ‖ GMAC(SC ‖ AK ‖ challenge), 12-octet tag.
             AuthMechanism::HlsGmac => {
-                let ctx = self.hls_context.as_ref().ok_or("HLS context not configured for GMAC")?;
+                let ctx = self
+                    .hls_context
+                    .as_ref()
+                    .ok_or_else(|| AccessError::TemporaryFailure("HLS context not configured for GMAC".to_string()))?;
                 if f_stoc.len() != 17 {
-                    return Err("f(StoC) must be 17 octets (SC ‖ IC ‖ 12-octet tag)".to_string());
+                    return Err(AccessError::TypeUnmatched(
+                        "f(StoC) must be 17 octets (SC ‖ IC ‖ 12-octet tag)".to_string(),
+                    ));
                 }
                 let sc = f_stoc[0];
                 if sc != ctx.security_control_byte {
-                    return Err("f(StoC) security control byte mismatch".to_string());
+                    return Err(AccessError::ReadWriteDenied("f(StoC) security control byte mismatch".to_string()));
                 }
                 let client_iv = build_iv(&ctx.client_system_title, &f_stoc[1..5])?;
                 let aad_stoc = [&[sc][..], &ctx.authentication_key, stoc].concat();
                 if gmac_tag(&ctx.encryption_key, &client_iv, &aad_stoc)? != f_stoc[5..17] {
-                    return Err("HLS authentication failed: GMAC f(StoC) mismatch".to_string());
+                    return Err(AccessError::ReadWriteDenied(
+                        "HLS authentication failed: GMAC f(StoC) mismatch".to_string(),
+                    ));
                 }
                 let server_ic = ctx.server_invocation_counter.to_be_bytes();
                 let server_iv = build_iv(&ctx.server_system_title, &server_ic)?;
@@ -338,49 +360,68 @@ impl AssociationLn {
             }
             // Mechanism 8 (GOST): f = SC ‖ IC ‖ KUZN_CMAC(LSB256(K_EM), IV ‖ SC ‖ chal_a ‖ chal_b).
             AuthMechanism::HlsGostCmac => {
-                let ctx = self.hls_context.as_ref().ok_or("HLS context not configured for GOST CMAC")?;
+                let ctx = self.hls_context.as_ref().ok_or_else(|| {
+                    AccessError::TemporaryFailure("HLS context not configured for GOST CMAC".to_string())
+                })?;
                 if f_stoc.len() != 21 {
-                    return Err("f(StoC) must be 21 octets (SC ‖ IC ‖ 16-octet MAC)".to_string());
+                    return Err(AccessError::TypeUnmatched(
+                        "f(StoC) must be 21 octets (SC ‖ IC ‖ 16-octet MAC)".to_string(),
+                    ));
                 }
                 let sc = f_stoc[0];
                 if sc != ctx.security_control_byte {
-                    return Err("f(StoC) security control byte mismatch".to_string());
+                    return Err(AccessError::ReadWriteDenied("f(StoC) security control byte mismatch".to_string()));
                 }
                 let client_iv = build_iv(&ctx.client_system_title, &f_stoc[1..5])?;
-                let expected = hls::gost_cmac(&ctx.gost_key, &client_iv, sc, stoc, ctos).map_err(str::to_string)?;
+                let expected = hls::gost_cmac(&ctx.gost_key, &client_iv, sc, stoc, ctos)
+                    .map_err(|e| AccessError::OtherReason(e.to_string()))?;
                 if expected != f_stoc[5..21] {
-                    return Err("HLS authentication failed: GOST CMAC f(StoC) mismatch".to_string());
+                    return Err(AccessError::ReadWriteDenied(
+                        "HLS authentication failed: GOST CMAC f(StoC) mismatch".to_string(),
+                    ));
                 }
                 let server_ic = ctx.server_invocation_counter.to_be_bytes();
                 let server_iv = build_iv(&ctx.server_system_title, &server_ic)?;
                 let mac = hls::gost_cmac(&ctx.gost_key, &server_iv, ctx.security_control_byte, ctos, stoc)
-                    .map_err(str::to_string)?;
+                    .map_err(|e| AccessError::OtherReason(e.to_string()))?;
                 Ok(CosemDataType::OctetString(assemble_sc_ic_mac(ctx.security_control_byte, &server_ic, &mac)))
             }
             // Mechanism 7 (ECDSA): f = SIGN(d, ST_a ‖ ST_b ‖ chal_a ‖ chal_b),
             // hashed with the suite's SHA-256 (P-256) or SHA-384 (P-384).
             AuthMechanism::HlsEcdsa => {
-                let ctx = self.hls_context.as_ref().ok_or("HLS context not configured for ECDSA")?;
+                let ctx = self
+                    .hls_context
+                    .as_ref()
+                    .ok_or_else(|| AccessError::TemporaryFailure("HLS context not configured for ECDSA".to_string()))?;
                 let suite = SecuritySuite::from_id(ctx.security_control_byte & 0x0F)
                     .filter(|s| s.has_public_key())
-                    .ok_or("ECDSA (mechanism 7) requires security suite 1 or 2")?;
+                    .ok_or_else(|| {
+                        AccessError::ObjectUnavailable("ECDSA (mechanism 7) requires security suite 1 or 2".to_string())
+                    })?;
                 let msg_c = [&ctx.client_system_title[..], &ctx.server_system_title, stoc, ctos].concat();
-                signature::ecdsa_verify(suite, &ctx.peer_public_key, &msg_c, &f_stoc)
-                    .map_err(|e| format!("HLS authentication failed: ECDSA f(StoC) invalid: {e}"))?;
+                signature::ecdsa_verify(suite, &ctx.peer_public_key, &msg_c, &f_stoc).map_err(|e| {
+                    AccessError::ReadWriteDenied(format!("HLS authentication failed: ECDSA f(StoC) invalid: {e}"))
+                })?;
                 let msg_s = [&ctx.server_system_title[..], &ctx.client_system_title, ctos, stoc].concat();
-                let sig = signature::ecdsa_sign(suite, &ctx.signing_key, &msg_s).map_err(|e| e.to_string())?;
+                let sig = signature::ecdsa_sign(suite, &ctx.signing_key, &msg_s)
+                    .map_err(|e| AccessError::OtherReason(e.to_string()))?;
                 Ok(CosemDataType::OctetString(sig))
             }
             // Mechanism 10 (GOST 34.10-2018-256): f = SIGN(d, ST_a ‖ ST_b ‖
             // chal_a ‖ chal_b) over curve paramSetB, Streebog-256.
             AuthMechanism::HlsGostSignature => {
-                let ctx = self.hls_context.as_ref().ok_or("HLS context not configured for GOST signature")?;
+                let ctx = self.hls_context.as_ref().ok_or_else(|| {
+                    AccessError::TemporaryFailure("HLS context not configured for GOST signature".to_string())
+                })?;
                 let msg_c = [&ctx.client_system_title[..], &ctx.server_system_title, stoc, ctos].concat();
-                gost3410::gost_verify(&ctx.peer_public_key, &msg_c, &f_stoc)
-                    .map_err(|e| format!("HLS authentication failed: GOST 34.10 f(StoC) invalid: {e:?}"))?;
+                gost3410::gost_verify(&ctx.peer_public_key, &msg_c, &f_stoc).map_err(|e| {
+                    AccessError::ReadWriteDenied(format!(
+                        "HLS authentication failed: GOST 34.10 f(StoC) invalid: {e:?}"
+                    ))
+                })?;
                 let msg_s = [&ctx.server_system_title[..], &ctx.client_system_title, ctos, stoc].concat();
                 let sig = gost3410::gost_sign(&ctx.signing_key, &msg_s)
-                    .map_err(|e| format!("GOST 34.10 signing failed: {e:?}"))?;
+                    .map_err(|e| AccessError::OtherReason(format!("GOST 34.10 signing failed: {e:?}")))?;
                 Ok(CosemDataType::OctetString(sig.to_vec()))
             }
             // Mechanism 2 (manufacturer-specific): f(challenge) = AES-128 over
@@ -389,11 +430,15 @@ impl AssociationLn {
                 let secret = self.secret_bytes();
                 let expected = hls::manufacturer_aes(secret, stoc);
                 if expected != f_stoc {
-                    return Err("HLS authentication failed: f(StoC) mismatch".to_string());
+                    return Err(AccessError::ReadWriteDenied(
+                        "HLS authentication failed: f(StoC) mismatch".to_string(),
+                    ));
                 }
                 Ok(CosemDataType::OctetString(hls::manufacturer_aes(secret, ctos)))
             }
-            AuthMechanism::None => Err("mechanism 0 does not use HLS authentication".to_string()),
+            AuthMechanism::None => {
+                Err(AccessError::ReadWriteDenied("mechanism 0 does not use HLS authentication".to_string()))
+            }
             AuthMechanism::Lls => unreachable!("LLS handled above"),
         }
     }
@@ -403,9 +448,11 @@ impl AssociationLn {
     /// further attempts are rejected without even checking the response,
     /// mitigating brute-force guessing of the challenge response. The
     /// counter resets on a successful authentication.
-    fn reply_to_hls_authentication_checked(&mut self, data: CosemDataType) -> Result<CosemDataType, String> {
+    fn reply_to_hls_authentication_checked(&mut self, data: CosemDataType) -> Result<CosemDataType, AccessError> {
         if self.hls_failures >= MAX_HLS_FAILURES {
-            return Err("HLS authentication locked out after too many failed attempts".to_string());
+            return Err(AccessError::TemporaryFailure(
+                "HLS authentication locked out after too many failed attempts".to_string(),
+            ));
         }
         match self.reply_to_hls_authentication(data) {
             Ok(reply) => {
@@ -428,8 +475,8 @@ impl AssociationLn {
 
     /// Method 3: `add_object` — adds an `object_list_element` to `object_list`.
     /// If an object with the same (class_id, logical_name) already exists, it is updated.
-    fn add_object(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
-        let elem = ObjectListElement::try_from(data)?;
+    fn add_object(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
+        let elem = ObjectListElement::try_from(data).map_err(AccessError::TypeUnmatched)?;
         let key = (elem.class_id, elem.logical_name.to_bytes());
         if let Some(existing) = self.object_list.iter_mut().find(|e| (e.class_id, e.logical_name.to_bytes()) == key) {
             *existing = elem;
@@ -440,13 +487,13 @@ impl AssociationLn {
     }
 
     /// Method 4: `remove_object` — removes an `object_list_element` from `object_list`.
-    fn remove_object(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
-        let elem = ObjectListElement::try_from(data)?;
+    fn remove_object(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
+        let elem = ObjectListElement::try_from(data).map_err(AccessError::TypeUnmatched)?;
         let key = (elem.class_id, elem.logical_name.to_bytes());
         let before = self.object_list.len();
         self.object_list.retain(|e| (e.class_id, e.logical_name.to_bytes()) != key);
         if self.object_list.len() == before {
-            return Err("Object not found in object_list".to_string());
+            return Err(AccessError::ObjectUnavailable("Object not found in object_list".to_string()));
         }
         Ok(CosemDataType::Null)
     }
@@ -454,8 +501,8 @@ impl AssociationLn {
     /// Method 5 (version 2): `add_user` — adds a `user { id, name }` entry to
     /// `user_list`. If an entry with the same user id already exists, it is
     /// updated (IEC 62056-6-2 §5.3.7.3.5).
-    fn add_user(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
-        let user = User::try_from(data)?;
+    fn add_user(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
+        let user = User::try_from(data).map_err(AccessError::TypeUnmatched)?;
         let id = user.user_id;
         if let Some(existing) = self.user_list.iter_mut().find(|e| e.user_id == id) {
             *existing = user;
@@ -467,13 +514,13 @@ impl AssociationLn {
 
     /// Method 6 (version 2): `remove_user` — removes the `user` entry with the
     /// given id from `user_list` (IEC 62056-6-2 §5.3.7.3.6).
-    fn remove_user(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
-        let user = User::try_from(data)?;
+    fn remove_user(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
+        let user = User::try_from(data).map_err(AccessError::TypeUnmatched)?;
         let id = user.user_id;
         let before = self.user_list.len();
         self.user_list.retain(|e| e.user_id != id);
         if self.user_list.len() == before {
-            return Err("User not found in user_list".to_string());
+            return Err(AccessError::ObjectUnavailable("User not found in user_list".to_string()));
         }
         Ok(CosemDataType::Null)
     }
@@ -524,12 +571,12 @@ fn assemble_sc_ic_mac(security_control: u8, invocation_counter: &[u8], mac: &[u8
 }
 
 /// Builds the 12-octet initialization vector `IV = system_title (8) ‖ IC (4)`.
-fn build_iv(system_title: &[u8], invocation_counter: &[u8]) -> Result<[u8; 12], String> {
+fn build_iv(system_title: &[u8], invocation_counter: &[u8]) -> Result<[u8; 12], AccessError> {
     if system_title.len() != 8 {
-        return Err("System-Title must be 8 octets".to_string());
+        return Err(AccessError::OtherReason("System-Title must be 8 octets".to_string()));
     }
     if invocation_counter.len() != 4 {
-        return Err("Invocation counter must be 4 octets".to_string());
+        return Err(AccessError::OtherReason("Invocation counter must be 4 octets".to_string()));
     }
     let mut iv = [0u8; 12];
     iv[..8].copy_from_slice(system_title);
@@ -541,27 +588,54 @@ fn build_iv(system_title: &[u8], invocation_counter: &[u8]) -> Result<[u8; 12],
 /// additional authenticated data `aad`, with key `ek` (16 or 32 octets) and
 /// initialization vector `iv`. The full 16-octet tag is truncated to 96 bits
 /// (most significant octets) per NIST SP 800-38D / IEC 62056-5-3.
-fn gmac_tag(ek: &[u8], iv: &[u8; 12], aad: &[u8]) -> Result<Vec<u8>, String> {
+fn gmac_tag(ek: &[u8], iv: &[u8; 12], aad: &[u8]) -> Result<Vec<u8>, AccessError> {
     let nonce = Nonce::from(*iv);
     let out = match ek.len() {
         16 => {
-            let cipher = Aes128Gcm::new_from_slice(ek).map_err(|_| "invalid EK".to_string())?;
+            let cipher =
+                Aes128Gcm::new_from_slice(ek).map_err(|_| AccessError::OtherReason("invalid EK".to_string()))?;
             cipher
                 .encrypt(&nonce, aead::Payload { msg: &[], aad })
-                .map_err(|_| "GMAC computation failed".to_string())?
+                .map_err(|_| AccessError::OtherReason("GMAC computation failed".to_string()))?
         }
         32 => {
-            let cipher = Aes256Gcm::new_from_slice(ek).map_err(|_| "invalid EK".to_string())?;
+            let cipher =
+                Aes256Gcm::new_from_slice(ek).map_err(|_| AccessError::OtherReason("invalid EK".to_string()))?;
             cipher
                 .encrypt(&nonce, aead::Payload { msg: &[], aad })
-                .map_err(|_| "GMAC computation failed".to_string())?
+                .map_err(|_| AccessError::OtherReason("GMAC computation failed".to_string()))?
         }
-        _ => return Err("EK must be 16 or 32 octets".to_string()),
+        _ => return Err(AccessError::OtherReason("EK must be 16 or 32 octets".to_string())),
     };
     // Empty plaintext → the output consists solely of the 16-octet tag.
     Ok(out[..12].to_vec())
 }
 
+/// Attribute descriptors of the Association LN class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "object_list", DataKind::Array).dynamic(),
+    AttributeInfo::new(3, "associated_partners_id", DataKind::Structure),
+    AttributeInfo::new(4, "application_context_name", DataKind::Any),
+    AttributeInfo::new(5, "xDLMS_context_info", DataKind::Structure),
+    AttributeInfo::new(6, "authentication_mechanism_name", DataKind::Any),
+    AttributeInfo::new(7, "secret", DataKind::OctetString).writable(),
+    AttributeInfo::new(8, "association_status", DataKind::Enum).dynamic(),
+    AttributeInfo::new(9, "security_setup_reference", DataKind::OctetString),
+    AttributeInfo::new(10, "user_list", DataKind::Array).writable(),
+    AttributeInfo::new(11, "current_user", DataKind::Any).dynamic().writable(),
+];
+
+/// Method descriptors of the Association LN class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "reply_to_HLS_authentication", DataKind::OctetString),
+    MethodInfo::new(2, "change_HLS_secret", DataKind::OctetString),
+    MethodInfo::new(3, "add_object", DataKind::Structure),
+    MethodInfo::new(4, "remove_object", DataKind::Structure),
+    MethodInfo::new(5, "add_user", DataKind::Structure),
+    MethodInfo::new(6, "remove_user", DataKind::Structure),
+];
+
 impl InterfaceClass for AssociationLn {
     fn class_id(&self) -> u16 {
         15
@@ -580,29 +654,28 @@ impl InterfaceClass for AssociationLn {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        let mut attrs = vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.object_list.iter().cloned().map(CosemDataType::from).collect())),
-            (3, CosemDataType::from(self.associated_partners_id.clone())),
-            (4, CosemDataType::from(self.application_context_name.clone())),
-            (5, CosemDataType::from(self.xdlms_context_info.clone())),
-            (6, self.authentication_mechanism_name()),
-            (7, CosemDataType::OctetString(self.secret.clone())),
-            (8, CosemDataType::Enum(self.association_status)),
-        ];
-        // Attribute 9 (security_setup_reference) is present starting from version 1.
-        if matches!(self.version, AssociationLnVersion::Version1 | AssociationLnVersion::Version2) {
-            attrs.push((9, CosemDataType::OctetString(self.security_setup_reference.to_bytes())));
-        }
-        // Attributes 10 (user_list) and 11 (current_user) were added in version 2.
-        if matches!(self.version, AssociationLnVersion::Version2) {
-            attrs.push((10, CosemDataType::Array(self.user_list.iter().cloned().map(CosemDataType::from).collect())));
-            match &self.current_user {
-                Some(user) => attrs.push((11, CosemDataType::from(user.clone()))),
-                None => attrs.push((11, CosemDataType::Null)),
-            }
-        }
-        attrs
+        (1..=11).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        let version1 = matches!(self.version, AssociationLnVersion::Version1 | AssociationLnVersion::Version2);
+        let version2 = matches!(self.version, AssociationLnVersion::Version2);
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.object_list.iter().cloned().map(CosemDataType::from).collect()),
+            3 => CosemDataType::from(self.associated_partners_id.clone()),
+            4 => CosemDataType::from(self.application_context_name.clone()),
+            5 => CosemDataType::from(self.xdlms_context_info.clone()),
+            6 => self.authentication_mechanism_name(),
+            7 => CosemDataType::OctetString(self.secret.clone()),
+            8 => CosemDataType::Enum(self.association_status),
+            // Attribute 9 (security_setup_reference) is present starting from version 1.
+            9 if version1 => CosemDataType::OctetString(self.security_setup_reference.to_bytes()),
+            // Attributes 10 (user_list) and 11 (current_user) were added in version 2.
+            10 if version2 => CosemDataType::Array(self.user_list.iter().cloned().map(CosemDataType::from).collect()),
+            11 if version2 => self.current_user.clone().map_or(CosemDataType::Null, CosemDataType::from),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -621,6 +694,21 @@ impl InterfaceClass for AssociationLn {
         methods
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        let (attributes, methods) = match self.version {
+            AssociationLnVersion::Version0 => (8, 4),
+            AssociationLnVersion::Version1 => (9, 4),
+            AssociationLnVersion::Version2 => (11, 6),
+        };
+        Some(ClassInfo {
+            name: "Association LN",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: &ATTRIBUTES[..attributes],
+            methods: &METHODS[..methods],
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -719,7 +807,7 @@ impl InterfaceClass for AssociationLn {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             7 => match value {
                 CosemDataType::OctetString(secret) => {
@@ -727,28 +815,32 @@ impl InterfaceClass for AssociationLn {
                     self.secret = secret;
                     Ok(())
                 }
-                _ => Err("secret must be octet-string".into()),
+                _ => Err(AccessError::TypeUnmatched("secret must be octet-string".to_string())),
             },
             10 if matches!(self.version, AssociationLnVersion::Version2) => {
                 let CosemDataType::Array(list) = value else {
-                    return Err("user_list must be array".into());
+                    return Err(AccessError::TypeUnmatched("user_list must be array".to_string()));
                 };
-                self.user_list = list.iter().map(User::try_from).collect::<Result<Vec<_>, _>>()?;
+                self.user_list = list
+                    .iter()
+                    .map(User::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
             11 if matches!(self.version, AssociationLnVersion::Version2) => {
                 self.current_user = match value {
                     CosemDataType::Null => None,
-                    other => Some(User::try_from(&other)?),
+                    other => Some(User::try_from(&other).map_err(AccessError::TypeUnmatched)?),
                 };
                 Ok(())
             }
-            _ => Err(format!("AssociationLn attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!("AssociationLn attribute {attribute_id} is not writable"))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        let params = params.ok_or("Missing method parameter")?;
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let params = params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?;
         let is_v2 = matches!(self.version, AssociationLnVersion::Version2);
         match method_id {
             1 => self.reply_to_hls_authentication_checked(params),
@@ -758,13 +850,21 @@ impl InterfaceClass for AssociationLn {
             // add_user / remove_user exist only in version 2.
             5 if is_v2 => self.add_user(&params),
             6 if is_v2 => self.remove_user(&params),
-            _ => Err(format!("Method {} not supported for Association LN version {}", method_id, self.version())),
+            _ => Err(AccessError::ObjectUnavailable(format!(
+                "Method {} not supported for Association LN version {}",
+                method_id,
+                self.version()
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 impl AssociationLn {
@@ -969,7 +1069,10 @@ mod tests {
         }
         // The correct response is now rejected too: the lockout persists.
         let err = obj.invoke_method(1, Some(CosemDataType::OctetString(f_stoc))).unwrap_err();
-        assert!(err.contains("locked out"), "unexpected error: {err}");
+        assert!(
+            matches!(&err, AccessError::TemporaryFailure(m) if m.contains("locked out")),
+            "unexpected error: {err}"
+        );
     }
 
     /// HLS-5 (GMAC): reference test vector from IEC 62056-5-3, Table 33.
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::DateTime;
 use crate::types::{BerError, CosemDataType};
@@ -127,7 +127,7 @@ impl Clock {
     }
 
     /// Sets a preset time.
-    fn adjust_to_preset_time(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn adjust_to_preset_time(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         if let Some(CosemDataType::DateTime(dt) | CosemDataType::OctetString(dt)) = params {
             if dt.len() == 12 {
                 let mut buf = [0u8; 12];
@@ -136,7 +136,7 @@ impl Clock {
                 return Ok(CosemDataType::Null);
             }
         }
-        Err("Invalid DateTime parameter".to_string())
+        Err(AccessError::TypeUnmatched("Invalid DateTime parameter".to_string()))
     }
 
     /// Preset-time adjustment (stub).
@@ -144,13 +144,46 @@ impl Clock {
         CosemDataType::Null
     }
 
-    /// Shifts the clock by a relative offset in seconds (Blue Book method 6).
-    /// Parameter is ignored for success/failure — C++ returns null without applying.
-    fn shift_time(_params: Option<CosemDataType>) -> CosemDataType {
-        CosemDataType::Null
+    /// Shifts the clock by a relative offset in seconds (Blue Book method 6,
+    /// long in -900..=900). The deviation and clock-status octets are kept.
+    fn shift_time(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let Some(CosemDataType::Long(shift)) = params else {
+            return Err(AccessError::TypeUnmatched("shift_time parameter must be long".to_string()));
+        };
+        if !(-900..=900).contains(&shift) {
+            return Err(AccessError::ScopeOfAccessViolated(format!("shift_time {shift} s is outside -900..=900")));
+        }
+        self.time = self
+            .time
+            .shifted(i64::from(shift))
+            .ok_or_else(|| AccessError::TemporaryFailure("Clock time is not specified".to_string()))?;
+        Ok(CosemDataType::Null)
     }
 }
 
+/// Attribute descriptors of the Clock class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "time", DataKind::DateTime).dynamic().writable(),
+    AttributeInfo::new(3, "time_zone", DataKind::Long).writable(),
+    AttributeInfo::new(4, "status", DataKind::Unsigned).dynamic(),
+    AttributeInfo::new(5, "daylight_savings_begin", DataKind::DateTime).writable(),
+    AttributeInfo::new(6, "daylight_savings_end", DataKind::DateTime).writable(),
+    AttributeInfo::new(7, "daylight_savings_deviation", DataKind::Integer).writable(),
+    AttributeInfo::new(8, "daylight_savings_enabled", DataKind::Boolean).writable(),
+    AttributeInfo::new(9, "clock_base", DataKind::Enum),
+];
+
+/// Method descriptors of the Clock class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "adjust_to_quarter", DataKind::Integer),
+    MethodInfo::new(2, "adjust_to_measuring_period", DataKind::Integer),
+    MethodInfo::new(3, "adjust_to_minute", DataKind::Integer),
+    MethodInfo::new(4, "adjust_to_preset_time", DataKind::Integer),
+    MethodInfo::new(5, "preset_adjusting_time", DataKind::Structure),
+    MethodInfo::new(6, "shift_time", DataKind::Long),
+];
+
 impl InterfaceClass for Clock {
     fn class_id(&self) -> u16 {
         8
@@ -165,19 +198,24 @@ impl InterfaceClass for Clock {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
+        (1..=9).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
         // Blue Book date-time attributes are carried as octet-string (tag 0x09),
         // matching `osp_val_cosem_datetime` / common client expectations — not tag 0x19.
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::OctetString(self.time.0.to_vec())),
-            (3, CosemDataType::Long(self.time_zone)),
-            (4, CosemDataType::Unsigned(self.status)),
-            (5, CosemDataType::OctetString(self.daylight_savings_begin.0.to_vec())),
-            (6, CosemDataType::OctetString(self.daylight_savings_end.0.to_vec())),
-            (7, CosemDataType::Integer(self.daylight_savings_deviation)),
-            (8, CosemDataType::Boolean(self.daylight_savings_enabled)),
-            (9, CosemDataType::Enum(self.clock_base)),
-        ]
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::OctetString(self.time.0.to_vec()),
+            3 => CosemDataType::Long(self.time_zone),
+            4 => CosemDataType::Unsigned(self.status),
+            5 => CosemDataType::OctetString(self.daylight_savings_begin.0.to_vec()),
+            6 => CosemDataType::OctetString(self.daylight_savings_end.0.to_vec()),
+            7 => CosemDataType::Integer(self.daylight_savings_deviation),
+            8 => CosemDataType::Boolean(self.daylight_savings_enabled),
+            9 => CosemDataType::Enum(self.clock_base),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -191,6 +229,16 @@ impl InterfaceClass for Clock {
         ]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Clock",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -257,10 +305,10 @@ impl InterfaceClass for Clock {
         Err(BerError::InvalidTag)
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => {
-                self.time = DateTime::try_from(&value)?;
+                self.time = DateTime::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
             3 => match value {
@@ -268,15 +316,15 @@ impl InterfaceClass for Clock {
                     self.time_zone = v;
                     Ok(())
                 }
-                _ => Err("time_zone must be long".into()),
+                _ => Err(AccessError::TypeUnmatched("time_zone must be long".to_string())),
             },
             // Attr 4 (status) is read-only in the ACL mask.
             5 => {
-                self.daylight_savings_begin = DateTime::try_from(&value)?;
+                self.daylight_savings_begin = DateTime::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
             6 => {
-                self.daylight_savings_end = DateTime::try_from(&value)?;
+                self.daylight_savings_end = DateTime::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
             7 => match value {
@@ -284,35 +332,39 @@ impl InterfaceClass for Clock {
                     self.daylight_savings_deviation = v;
                     Ok(())
                 }
-                _ => Err("daylight_savings_deviation must be integer".into()),
+                _ => Err(AccessError::TypeUnmatched("daylight_savings_deviation must be integer".to_string())),
             },
             8 => match value {
                 CosemDataType::Boolean(v) => {
                     self.daylight_savings_enabled = v;
                     Ok(())
                 }
-                _ => Err("daylight_savings_enabled must be boolean".into()),
+                _ => Err(AccessError::TypeUnmatched("daylight_savings_enabled must be boolean".to_string())),
             },
             // Attr 9 (clock_base) is read-only.
-            _ => Err(format!("Clock attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!("Clock attribute {attribute_id} is not writable"))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => Ok(self.adjust_to_quarter()),
             2 => Ok(self.adjust_to_measuring_period()),
             3 => Ok(self.adjust_to_minute()),
             4 => self.adjust_to_preset_time(params),
             5 => Ok(Self::preset_adjusting_time()),
-            6 => Ok(Self::shift_time(params)),
-            _ => Err(format!("Method {method_id} not supported for Clock class")),
+            6 => self.shift_time(params),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Clock class"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a length in BER (short or long form).
@@ -1,5 +1,7 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
+use crate::types::attrs::CaptureObjectDefinition;
+use crate::types::compact::{self, TypeDescription};
 use crate::types::{BerError, CosemDataType};
 use serde::{Deserialize, Serialize};
 use std::any::Any;
@@ -19,7 +21,8 @@ pub mod capture_method {
 pub struct CompactDataConfig {
     /// Attribute 1: the object's logical name (OBIS code).
     pub logical_name: ObisCode,
-    /// Attribute 2: the compact buffer (octet-string of concatenated values).
+    /// Attribute 2: the compact buffer (octet-string of the captured values'
+    /// contents, without tags).
     pub compact_buffer: Vec<u8>,
     /// Attribute 3: the objects captured into the buffer, paired with the
     /// captured attribute index.
@@ -27,7 +30,9 @@ pub struct CompactDataConfig {
     pub capture_objects: Vec<(Arc<dyn InterfaceClass + Send + Sync>, u8)>,
     /// Attribute 4: identifier of the template describing the buffer contents.
     pub template_id: u8,
-    /// Attribute 5: template description (octet-string of type tags).
+    /// Attribute 5: template description (the A-XDR type description of the
+    /// captured structure). Left empty, it is generated from the capture
+    /// objects, or by the first capture of objects given as definitions.
     pub template_description: Vec<u8>,
     /// Attribute 6: capture method (see [`capture_method`]).
     pub capture_method: u8,
@@ -48,16 +53,26 @@ impl fmt::Debug for CompactDataConfig {
 
 /// `Compact data` interface class (class_id = 62, version = 0) per
 /// IEC 62056-6-2 §4.3.8. Holds the values of the capture objects in a compact
-/// (contents-only) encoding described by the template.
+/// (contents-only) encoding described by the template: each capture stores the
+/// structure of the captured values in the [`compact`] form laid out by
+/// `template_description`, so a client decodes the buffer with
+/// [`compact::decode_rows`]. The description, identified by `template_id`,
+/// is generated from the first capture when none is configured.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CompactData {
     logical_name: ObisCode,
     compact_buffer: Vec<u8>,
     #[serde(skip)]
     capture_objects: Vec<(Arc<dyn InterfaceClass + Send + Sync>, u8)>,
+    /// Capture objects given as definitions, read from the live object set.
+    #[serde(skip)]
+    capture_definitions: Option<Vec<CaptureObjectDefinition>>,
     template_id: u8,
     template_description: Vec<u8>,
     capture_method: u8,
+    /// Set by the `capture` method until the capture driver takes it.
+    #[serde(skip)]
+    capture_requested: bool,
 }
 
 impl fmt::Debug for CompactData {
@@ -76,14 +91,88 @@ impl fmt::Debug for CompactData {
 impl CompactData {
     /// Builds a new [`CompactData`] from its configuration.
     pub fn new(config: CompactDataConfig) -> Self {
+        let mut template_description = config.template_description;
+        if template_description.is_empty() && !config.capture_objects.is_empty() {
+            let values: Option<Vec<_>> =
+                config.capture_objects.iter().map(|(obj, attr_id)| obj.get_attribute(*attr_id)).collect();
+            if let Some(Ok(description)) = values.map(|v| TypeDescription::of(&CosemDataType::Structure(v))) {
+                description.serialize(&mut template_description);
+            }
+        }
         CompactData {
             logical_name: config.logical_name,
             compact_buffer: config.compact_buffer,
             capture_objects: config.capture_objects,
+            capture_definitions: None,
             template_id: config.template_id,
-            template_description: config.template_description,
+            template_description,
             capture_method: config.capture_method,
+            capture_requested: false,
+        }
+    }
+
+    /// Captures the objects described by `definitions` instead of the
+    /// configured snapshots. Their values are read from the live object set
+    /// by the [`CaptureDriver`](crate::capture::CaptureDriver), so the
+    /// `capture` method only requests a capture.
+    pub fn with_capture_definitions(mut self, definitions: Vec<CaptureObjectDefinition>) -> Self {
+        self.capture_definitions = Some(definitions);
+        self
+    }
+
+    /// The capture objects (attribute 3), in buffer order.
+    pub fn capture_definitions(&self) -> Vec<CaptureObjectDefinition> {
+        match &self.capture_definitions {
+            Some(definitions) => definitions.clone(),
+            None => self
+                .capture_objects
+                .iter()
+                .map(|(obj, attr_id)| CaptureObjectDefinition::new(obj.class_id(), *obj.logical_name(), *attr_id, 0))
+                .collect(),
+        }
+    }
+
+    /// The capture method (attribute 6, see [`capture_method`]).
+    pub fn capture_method(&self) -> u8 {
+        self.capture_method
+    }
+
+    /// Takes the request set by the `capture` method, clearing it.
+    pub fn take_capture_request(&mut self) -> bool {
+        std::mem::take(&mut self.capture_requested)
+    }
+
+    /// Stores captured `values`, in capture object order, as the compact
+    /// buffer: the contents of their structure without tags. The values must
+    /// match the template description; without one, it is generated from
+    /// the values' types. `template_id` is left as configured.
+    pub fn store(&mut self, values: &[CosemDataType]) -> Result<(), AccessError> {
+        let value = CosemDataType::Structure(values.to_vec());
+        let serialization = |e| AccessError::OtherReason(format!("Capture serialization failed: {e:?}"));
+        let generated = self.template_description.is_empty();
+        let description = if generated {
+            TypeDescription::of(&value).map_err(serialization)?
+        } else {
+            match TypeDescription::deserialize(&self.template_description) {
+                Ok((description, [])) => description,
+                _ => return Err(AccessError::OtherReason("Invalid template description".to_string())),
+            }
+        };
+        let mut buffer = Vec::new();
+        description.encode(&value, &mut buffer).map_err(|_| {
+            AccessError::TypeUnmatched(format!("Captured values do not match template {}", self.template_id))
+        })?;
+        if generated {
+            description.serialize(&mut self.template_description);
         }
+        self.compact_buffer = buffer;
+        Ok(())
+    }
+
+    /// Decodes the compact buffer with the template description: one
+    /// structure per capture held in the buffer.
+    pub fn rows(&self) -> Result<Vec<CosemDataType>, BerError> {
+        compact::decode_rows(&self.template_description, &self.compact_buffer)
     }
 
     /// Method 1: `reset` — clears the compact buffer.
@@ -93,23 +182,26 @@ impl CompactData {
     }
 
     /// Method 2: `capture` — reads every capture object's attribute and stores
-    /// the serialized values (full TLV encoding, one after another) into the
-    /// compact buffer.
-    fn capture(&mut self) -> Result<CosemDataType, String> {
+    /// the values into the compact buffer (see [`CompactData::store`]).
+    /// Capture objects given as definitions are only requested; the
+    /// [`CaptureDriver`](crate::capture::CaptureDriver) takes the request and
+    /// reads them from the object set.
+    fn capture(&mut self) -> Result<CosemDataType, AccessError> {
+        if self.capture_definitions.is_some() {
+            self.capture_requested = true;
+            return Ok(CosemDataType::Null);
+        }
         if self.capture_objects.is_empty() {
-            return Err("No capture objects configured".to_string());
+            return Err(AccessError::TemporaryFailure("No capture objects configured".to_string()));
         }
-        let mut buffer = Vec::new();
+        let mut values = Vec::new();
         for (obj, attr_id) in &self.capture_objects {
             let value = obj
-                .attributes()
-                .into_iter()
-                .find(|(id, _)| id == attr_id)
-                .map(|(_, v)| v)
-                .ok_or_else(|| format!("Capture object has no attribute {attr_id}"))?;
-            value.serialize_ber(&mut buffer).map_err(|e| format!("Capture serialization failed: {e:?}"))?;
+                .get_attribute(*attr_id)
+                .ok_or_else(|| AccessError::ObjectUnavailable(format!("Capture object has no attribute {attr_id}")))?;
+            values.push(value);
         }
-        self.compact_buffer = buffer;
+        self.store(&values)?;
         Ok(CosemDataType::Null)
     }
 
@@ -119,6 +211,20 @@ impl CompactData {
     }
 }
 
+/// Attribute descriptors of the Compact data class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "compact_buffer", DataKind::OctetString).dynamic(),
+    AttributeInfo::new(3, "capture_objects", DataKind::Array),
+    AttributeInfo::new(4, "template_id", DataKind::Unsigned),
+    AttributeInfo::new(5, "template_description", DataKind::OctetString),
+    AttributeInfo::new(6, "capture_method", DataKind::Enum),
+];
+
+/// Method descriptors of the Compact data class.
+const METHODS: &[MethodInfo] =
+    &[MethodInfo::new(1, "reset", DataKind::Integer), MethodInfo::new(2, "capture", DataKind::Integer)];
+
 impl InterfaceClass for CompactData {
     fn class_id(&self) -> u16 {
         62
@@ -133,36 +239,53 @@ impl InterfaceClass for CompactData {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        let capture_objects = CosemDataType::Array(
-            self.capture_objects
-                .iter()
-                .map(|(obj, attr_id)| {
-                    // Attribute ids are always <128 in practice (i8-valued on the wire).
-                    #[allow(clippy::cast_possible_wrap)]
-                    let attr_id = *attr_id as i8;
-                    CosemDataType::Structure(vec![
-                        CosemDataType::LongUnsigned(obj.class_id()),
-                        CosemDataType::OctetString(obj.logical_name().to_bytes()),
-                        CosemDataType::Integer(attr_id), // attribute_index (integer)
-                        CosemDataType::LongUnsigned(0),  // data_index (long-unsigned, default 0)
-                    ])
-                })
-                .collect(),
-        );
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::OctetString(self.compact_buffer.clone())),
-            (3, capture_objects),
-            (4, CosemDataType::Unsigned(self.template_id)),
-            (5, CosemDataType::OctetString(self.template_description.clone())),
-            (6, CosemDataType::Enum(self.capture_method)),
-        ]
+        (1..=6).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::OctetString(self.compact_buffer.clone()),
+            3 => match &self.capture_definitions {
+                Some(definitions) => CosemDataType::Array(definitions.iter().cloned().map(Into::into).collect()),
+                None => CosemDataType::Array(
+                    self.capture_objects
+                        .iter()
+                        .map(|(obj, attr_id)| {
+                            // Attribute ids are always <128 in practice (i8-valued on the wire).
+                            #[allow(clippy::cast_possible_wrap)]
+                            let attr_id = *attr_id as i8;
+                            CosemDataType::Structure(vec![
+                                CosemDataType::LongUnsigned(obj.class_id()),
+                                CosemDataType::OctetString(obj.logical_name().to_bytes()),
+                                CosemDataType::Integer(attr_id), // attribute_index (integer)
+                                CosemDataType::LongUnsigned(0),  // data_index (long-unsigned, default 0)
+                            ])
+                        })
+                        .collect(),
+                ),
+            },
+            4 => CosemDataType::Unsigned(self.template_id),
+            5 => CosemDataType::OctetString(self.template_description.clone()),
+            6 => CosemDataType::Enum(self.capture_method),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "reset".to_string()), (2, "capture".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Compact data",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -223,17 +346,23 @@ impl InterfaceClass for CompactData {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => Ok(self.reset()),
             2 => self.capture(),
-            _ => Err(format!("Method {method_id} not supported for Compact data class")),
+            _ => {
+                Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Compact data class")))
+            }
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -264,7 +393,7 @@ mod tests {
                 2,
             )],
             template_id: 1,
-            template_description: vec![0x12],
+            template_description: vec![0x02, 0x01, 0x12],
             capture_method: capture_method::INVOKE,
         })
     }
@@ -281,12 +410,47 @@ mod tests {
     fn capture_fills_and_reset_clears_buffer() {
         let mut obj = sample();
         obj.invoke_method(2, None).unwrap();
-        // long-unsigned 0x1234 → 12 12 34.
-        assert_eq!(obj.compact_buffer(), &[0x12, 0x12, 0x34]);
+        // long-unsigned 0x1234 → 12 34, described as structure { long-unsigned }.
+        assert_eq!(obj.compact_buffer(), &[0x12, 0x34]);
+        assert_eq!(obj.get_attribute(5), Some(CosemDataType::OctetString(vec![0x02, 0x01, 0x12])));
+        assert_eq!(obj.rows().unwrap(), [CosemDataType::Structure(vec![CosemDataType::LongUnsigned(0x1234)])]);
         obj.invoke_method(1, None).unwrap();
         assert!(obj.compact_buffer().is_empty());
     }
 
+    #[test]
+    fn captures_must_match_the_template() {
+        let mut obj = sample();
+        obj.invoke_method(2, None).unwrap();
+        let err = obj.store(&[CosemDataType::Unsigned(1)]).unwrap_err();
+        assert!(matches!(err, AccessError::TypeUnmatched(_)));
+        assert_eq!(obj.compact_buffer(), &[0x12, 0x34]);
+        assert_eq!(obj.get_attribute(4), Some(CosemDataType::Unsigned(1)));
+        assert_eq!(obj.get_attribute(5), Some(CosemDataType::OctetString(vec![0x02, 0x01, 0x12])));
+    }
+
+    #[test]
+    fn template_is_generated_by_the_first_capture() {
+        let mut obj = CompactData::new(CompactDataConfig {
+            logical_name: ObisCode::new(0, 0, 66, 0, 1, 255),
+            compact_buffer: vec![],
+            capture_objects: vec![],
+            template_id: 2,
+            template_description: vec![],
+            capture_method: capture_method::INVOKE,
+        })
+        .with_capture_definitions(vec![CaptureObjectDefinition::new(
+            3,
+            ObisCode::new(1, 0, 1, 8, 0, 255),
+            2,
+            0,
+        )]);
+        obj.store(&[CosemDataType::DoubleLongUnsigned(7)]).unwrap();
+        assert_eq!(obj.get_attribute(5), Some(CosemDataType::OctetString(vec![0x02, 0x01, 0x06])));
+        assert!(obj.store(&[CosemDataType::LongUnsigned(7)]).is_err());
+        assert_eq!(obj.rows().unwrap(), [CosemDataType::Structure(vec![CosemDataType::DoubleLongUnsigned(7)])]);
+    }
+
     #[test]
     fn round_trip() {
         let mut obj = sample();
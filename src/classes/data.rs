@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::Choice;
 use crate::types::{BerError, CosemDataType};
@@ -34,6 +34,15 @@ impl Data {
     }
 }
 
+/// Attribute descriptors of the Data class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "value", DataKind::Any).dynamic().writable(),
+];
+
+/// Method descriptors of the Data class.
+const METHODS: &[MethodInfo] = &[MethodInfo::new(1, "reset", DataKind::Integer)];
+
 impl InterfaceClass for Data {
     fn class_id(&self) -> u16 {
         1
@@ -48,13 +57,38 @@ impl InterfaceClass for Data {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![(1, CosemDataType::OctetString(self.logical_name.to_bytes())), (2, self.value.clone())]
+        (1..=2).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => self.value.clone(),
+            _ => return None,
+        })
+    }
+
+    fn attribute_ref(&self, attribute_id: u8) -> Option<&CosemDataType> {
+        match attribute_id {
+            2 => Some(&self.value),
+            _ => None,
+        }
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "reset".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Data",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -95,16 +129,16 @@ impl InterfaceClass for Data {
         Err(BerError::InvalidTag)
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         if attribute_id == 2 {
             self.value = value;
             Ok(())
         } else {
-            Err(format!("Attribute {} not writable for Data", attribute_id))
+            Err(AccessError::ReadWriteDenied(format!("Attribute {} not writable for Data", attribute_id)))
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             // Method 1: `reset` — sets the value to its default (null-data),
             // per IEC 62056-6-2 §4.3.1.3.1.
@@ -112,13 +146,17 @@ impl InterfaceClass for Data {
                 self.value = CosemDataType::Null;
                 Ok(CosemDataType::Null)
             }
-            _ => Err(format!("Method {method_id} not supported for Data class")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Data class"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a length in BER (short or long form).
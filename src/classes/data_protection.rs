@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::ProtectionObject;
 use crate::types::{BerError, CosemDataType};
@@ -61,14 +61,16 @@ impl DataProtection {
 
     /// Method 2: `set_protected_attributes` — applies protected attribute values.
     /// Best-effort: stores the supplied protected data into the buffer.
-    fn set_protected_attributes(&mut self, data: CosemDataType) -> Result<CosemDataType, String> {
+    fn set_protected_attributes(&mut self, data: CosemDataType) -> Result<CosemDataType, AccessError> {
         match data {
             CosemDataType::OctetString(bytes) => {
                 self.protection_buffer = bytes;
                 Ok(CosemDataType::Null)
             }
             CosemDataType::Structure(_) => Ok(CosemDataType::Null),
-            _ => Err("set_protected_attributes expects an octet-string or structure".to_string()),
+            _ => Err(AccessError::TypeUnmatched(
+                "set_protected_attributes expects an octet-string or structure".to_string(),
+            )),
         }
     }
 
@@ -79,6 +81,23 @@ impl DataProtection {
     }
 }
 
+/// Attribute descriptors of the Data protection class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "protection_buffer", DataKind::OctetString).dynamic(),
+    AttributeInfo::new(3, "protection_object_list", DataKind::Array),
+    AttributeInfo::new(4, "protection_parameters_get", DataKind::Array),
+    AttributeInfo::new(5, "protection_parameters_set", DataKind::Array),
+    AttributeInfo::new(6, "required_protection", DataKind::Enum),
+];
+
+/// Method descriptors of the Data protection class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "get_protected_attributes", DataKind::Structure),
+    MethodInfo::new(2, "set_protected_attributes", DataKind::Structure),
+    MethodInfo::new(3, "invoke_protected_method", DataKind::Structure),
+];
+
 impl InterfaceClass for DataProtection {
     fn class_id(&self) -> u16 {
         30
@@ -93,24 +112,23 @@ impl InterfaceClass for DataProtection {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::OctetString(self.protection_buffer.clone())),
-            (3, CosemDataType::Array(self.protection_object_list.iter().cloned().map(CosemDataType::from).collect())),
-            (
-                4,
-                CosemDataType::Array(
-                    self.protection_parameters_get.iter().cloned().map(CosemDataType::OctetString).collect(),
-                ),
+        (1..=6).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::OctetString(self.protection_buffer.clone()),
+            3 => CosemDataType::Array(self.protection_object_list.iter().cloned().map(CosemDataType::from).collect()),
+            4 => CosemDataType::Array(
+                self.protection_parameters_get.iter().cloned().map(CosemDataType::OctetString).collect(),
             ),
-            (
-                5,
-                CosemDataType::Array(
-                    self.protection_parameters_set.iter().cloned().map(CosemDataType::OctetString).collect(),
-                ),
+            5 => CosemDataType::Array(
+                self.protection_parameters_set.iter().cloned().map(CosemDataType::OctetString).collect(),
             ),
-            (6, CosemDataType::Enum(self.required_protection)),
-        ]
+            6 => CosemDataType::Enum(self.required_protection),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -121,6 +139,16 @@ impl InterfaceClass for DataProtection {
         ]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Data protection",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -175,19 +203,23 @@ impl InterfaceClass for DataProtection {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        let params = params.ok_or("Missing method parameter")?;
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let params = params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?;
         match method_id {
             1 => Ok(self.get_protected_attributes(params)),
             2 => self.set_protected_attributes(params),
             3 => Ok(Self::invoke_protected_method(params)),
-            _ => Err(format!("Method {method_id} not supported for Data protection")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Data protection"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_po_array(value: &CosemDataType) -> Result<Vec<ProtectionObject>, BerError> {
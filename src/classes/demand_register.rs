@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{Choice, DateTime, ScalerUnit};
 use crate::types::{BerError, CosemDataType};
@@ -77,9 +77,51 @@ impl DemandRegister {
         }
     }
 
+    /// The demand of the running period (attr 2).
+    pub fn current_average_value(&self) -> &Choice {
+        &self.current_average_value
+    }
+
+    /// The demand of the last completed period (attr 3).
+    pub fn last_average_value(&self) -> &Choice {
+        &self.last_average_value
+    }
+
+    /// The time `last_average_value` was captured (attr 6).
+    pub fn capture_time(&self) -> &DateTime {
+        &self.capture_time
+    }
+
+    /// The start of the running period (attr 7).
+    pub fn start_time_current(&self) -> &DateTime {
+        &self.start_time_current
+    }
+
+    /// The demand integration period in seconds (attr 8).
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+
+    /// The number of periods of the sliding demand window (attr 9).
+    pub fn number_of_periods(&self) -> u16 {
+        self.number_of_periods
+    }
+
+    /// Records the demand of the running period and the period's start.
+    pub fn record_current(&mut self, value: Choice, start_time_current: DateTime) {
+        self.current_average_value = value;
+        self.start_time_current = start_time_current;
+    }
+
+    /// Records the demand of a completed period and its capture time.
+    pub fn record_last(&mut self, value: Choice, capture_time: DateTime) {
+        self.last_average_value = value;
+        self.capture_time = capture_time;
+    }
+
     /// Resets `current_average_value` and `last_average_value` to 0, and clears
     /// the status, capture time and current-period start time.
-    fn reset(&mut self) -> Result<CosemDataType, String> {
+    fn reset(&mut self) -> Result<CosemDataType, AccessError> {
         match &self.current_average_value {
             CosemDataType::Integer(_) => {
                 self.current_average_value = CosemDataType::Integer(0);
@@ -105,7 +147,7 @@ impl DemandRegister {
                 self.current_average_value = CosemDataType::DoubleLongUnsigned(0);
                 self.last_average_value = CosemDataType::DoubleLongUnsigned(0);
             }
-            _ => return Err("Unsupported value type for reset".to_string()),
+            _ => return Err(AccessError::TypeUnmatched("Unsupported value type for reset".to_string())),
         }
         self.status = CosemDataType::Null;
         self.capture_time = DateTime::new([0u8; 12]);
@@ -120,7 +162,7 @@ impl DemandRegister {
     /// # Returns
     /// * `Ok(CosemDataType::Null)` - On a successful transition.
     /// * `Err(String)` - If the value type does not support reset.
-    fn next_period(&mut self) -> Result<CosemDataType, String> {
+    fn next_period(&mut self) -> Result<CosemDataType, AccessError> {
         // Move the current value into the last value.
         self.last_average_value = self.current_average_value.clone();
         // Reset the current value.
@@ -131,7 +173,7 @@ impl DemandRegister {
             CosemDataType::Unsigned(_) => self.current_average_value = CosemDataType::Unsigned(0),
             CosemDataType::LongUnsigned(_) => self.current_average_value = CosemDataType::LongUnsigned(0),
             CosemDataType::DoubleLongUnsigned(_) => self.current_average_value = CosemDataType::DoubleLongUnsigned(0),
-            _ => return Err("Unsupported value type for next_period".to_string()),
+            _ => return Err(AccessError::TypeUnmatched("Unsupported value type for next_period".to_string())),
         }
         // Update the status (1 means a successful measurement).
         self.status = CosemDataType::Unsigned(1);
@@ -149,6 +191,23 @@ impl DemandRegister {
     }
 }
 
+/// Attribute descriptors of the Demand register class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "current_average_value", DataKind::Any).dynamic(),
+    AttributeInfo::new(3, "last_average_value", DataKind::Any).dynamic(),
+    AttributeInfo::new(4, "scaler_unit", DataKind::Structure),
+    AttributeInfo::new(5, "status", DataKind::Any).dynamic(),
+    AttributeInfo::new(6, "capture_time", DataKind::DateTime).dynamic(),
+    AttributeInfo::new(7, "start_time_current", DataKind::DateTime).dynamic(),
+    AttributeInfo::new(8, "period", DataKind::DoubleLongUnsigned).writable(),
+    AttributeInfo::new(9, "number_of_periods", DataKind::LongUnsigned).writable(),
+];
+
+/// Method descriptors of the Demand register class.
+const METHODS: &[MethodInfo] =
+    &[MethodInfo::new(1, "reset", DataKind::Integer), MethodInfo::new(2, "next_period", DataKind::Integer)];
+
 impl InterfaceClass for DemandRegister {
     fn class_id(&self) -> u16 {
         5
@@ -163,23 +222,47 @@ impl InterfaceClass for DemandRegister {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, self.current_average_value.clone()),
-            (3, self.last_average_value.clone()),
-            (4, self.scaler_unit.clone().into()),
-            (5, self.status.clone()),
-            (6, self.capture_time.clone().into()),
-            (7, self.start_time_current.clone().into()),
-            (8, CosemDataType::DoubleLongUnsigned(self.period)),
-            (9, CosemDataType::LongUnsigned(self.number_of_periods)),
-        ]
+        (1..=9).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => self.current_average_value.clone(),
+            3 => self.last_average_value.clone(),
+            4 => self.scaler_unit.clone().into(),
+            5 => self.status.clone(),
+            6 => self.capture_time.clone().into(),
+            7 => self.start_time_current.clone().into(),
+            8 => CosemDataType::DoubleLongUnsigned(self.period),
+            9 => CosemDataType::LongUnsigned(self.number_of_periods),
+            _ => return None,
+        })
+    }
+
+    fn attribute_ref(&self, attribute_id: u8) -> Option<&CosemDataType> {
+        match attribute_id {
+            2 => Some(&self.current_average_value),
+            3 => Some(&self.last_average_value),
+            5 => Some(&self.status),
+            _ => None,
+        }
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "reset".to_string()), (2, "next_period".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Demand register",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -236,37 +319,43 @@ impl InterfaceClass for DemandRegister {
         Err(BerError::InvalidTag)
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             8 => match value {
                 CosemDataType::DoubleLongUnsigned(v) => {
                     self.period = v;
                     Ok(())
                 }
-                _ => Err("period must be double-long-unsigned".into()),
+                _ => Err(AccessError::TypeUnmatched("period must be double-long-unsigned".to_string())),
             },
             9 => match value {
                 CosemDataType::LongUnsigned(v) => {
                     self.number_of_periods = v;
                     Ok(())
                 }
-                _ => Err("number_of_periods must be long-unsigned".into()),
+                _ => Err(AccessError::TypeUnmatched("number_of_periods must be long-unsigned".to_string())),
             },
-            _ => Err(format!("DemandRegister attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!("DemandRegister attribute {attribute_id} is not writable"))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => self.reset(),
             2 => self.next_period(),
-            _ => Err(format!("Method {method_id} not supported for DemandRegister class")),
+            _ => Err(AccessError::ObjectUnavailable(format!(
+                "Method {method_id} not supported for DemandRegister class"
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a length in BER (short or long form).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 use serde::{Deserialize, Serialize};
@@ -27,15 +27,80 @@ pub struct DisconnectControlConfig {
     pub control_mode: u8,
 }
 
+/// What drives a [`DisconnectControl`] transition (IEC 62056-6-2 §4.5.8.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Trigger {
+    /// Method 1, `remote_disconnect`.
+    RemoteDisconnect,
+    /// Method 2, `remote_reconnect`.
+    RemoteReconnect,
+    /// The consumer opens the output (pushbutton).
+    ManualDisconnect,
+    /// The consumer closes the output (pushbutton).
+    ManualReconnect,
+    /// A local function, such as a Limiter, opens the output.
+    LocalDisconnect,
+    /// A local function closes the output again.
+    LocalReconnect,
+}
+
+/// A change of `control_state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transition {
+    /// What drove it.
+    pub trigger: Trigger,
+    /// The previous control state (see [`control_state`]).
+    pub from: u8,
+    /// The new control state.
+    pub to: u8,
+}
+
+impl Transition {
+    /// The transition's letter in the Blue Book state diagram (`a` … `h`).
+    pub fn label(&self) -> char {
+        match (self.trigger, self.from) {
+            (Trigger::RemoteReconnect, _) if self.to == control_state::READY_FOR_RECONNECTION => 'a',
+            (Trigger::RemoteDisconnect, control_state::READY_FOR_RECONNECTION) => 'b',
+            (Trigger::RemoteDisconnect, _) => 'c',
+            (Trigger::RemoteReconnect, _) => 'd',
+            (Trigger::ManualReconnect, _) => 'e',
+            (Trigger::ManualDisconnect, _) => 'f',
+            (Trigger::LocalDisconnect, _) => 'g',
+            (Trigger::LocalReconnect, _) => 'h',
+        }
+    }
+
+    /// The output state the transition leads to (true = closed/connected).
+    pub fn output_state(&self) -> bool {
+        self.to == control_state::CONNECTED
+    }
+}
+
 /// `Disconnect control` interface class (class_id = 70, version = 0) per
+/// Transitions kept for the disconnect service; older ones are dropped when
+/// no service takes them.
+const MAX_TRANSITIONS: usize = 64;
+
 /// IEC 62056-6-2 §4.5.8. Manages the internal or external disconnect relay
 /// (breaker) that connects or disconnects the consumer.
+///
+/// The state machine covers the remote (methods 1 and 2), manual
+/// ([`manual_disconnect`](Self::manual_disconnect),
+/// [`manual_reconnect`](Self::manual_reconnect)) and local
+/// ([`local_disconnect`](Self::local_disconnect),
+/// [`local_reconnect`](Self::local_reconnect)) transitions each control mode
+/// allows. The last 64 transitions are queued for the
+/// [`DisconnectService`](crate::disconnect::DisconnectService), which drives
+/// the relay and records the resulting `output_state`.
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct DisconnectControl {
     logical_name: ObisCode,
     output_state: bool,
     control_state: u8,
     control_mode: u8,
+    /// Transitions not yet taken by the disconnect service.
+    #[serde(skip)]
+    transitions: Vec<Transition>,
 }
 
 impl DisconnectControl {
@@ -46,41 +111,126 @@ impl DisconnectControl {
             output_state: config.output_state,
             control_state: config.control_state,
             control_mode: config.control_mode,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Attribute 2: the physical output state (true = closed/connected).
+    pub fn output_state(&self) -> bool {
+        self.output_state
+    }
+
+    /// Attribute 3: the control state (see [`control_state`]).
+    pub fn control_state(&self) -> u8 {
+        self.control_state
+    }
+
+    /// Attribute 4: the control mode.
+    pub fn control_mode(&self) -> u8 {
+        self.control_mode
+    }
+
+    /// Applies `trigger` if the control mode allows it from the current
+    /// state, returning the transition made. `output_state` follows the new
+    /// state until the relay reports otherwise (IEC 62056-6-2 §4.5.8.1):
+    ///
+    /// | mode | disconnection                    | reconnection                     |
+    /// |------|----------------------------------|----------------------------------|
+    /// | 0    | none, always connected           | none                             |
+    /// | 1    | remote (b, c), manual (f), local (g) | remote (a), manual (e)       |
+    /// | 2    | remote (b, c), manual (f), local (g) | remote (d), manual (e)       |
+    /// | 3    | remote (b, c), local (g)         | remote (a), manual (e)           |
+    /// | 4    | remote (b, c), local (g)         | remote (d), manual (e)           |
+    /// | 5    | remote (b, c), manual (f), local (g) | remote (a), manual (e), local (h) |
+    /// | 6    | remote (b, c), local (g)         | remote (a), manual (e), local (h) |
+    pub fn apply(&mut self, trigger: Trigger) -> Option<Transition> {
+        use control_state::{CONNECTED, DISCONNECTED, READY_FOR_RECONNECTION};
+        let mode = self.control_mode;
+        let to = match (trigger, self.control_state) {
+            (_, _) if !(1..=6).contains(&mode) => return None,
+            (Trigger::RemoteDisconnect, CONNECTED | READY_FOR_RECONNECTION) => DISCONNECTED,
+            (Trigger::RemoteReconnect, DISCONNECTED) if matches!(mode, 2 | 4) => CONNECTED,
+            (Trigger::RemoteReconnect, DISCONNECTED) => READY_FOR_RECONNECTION,
+            (Trigger::ManualDisconnect, CONNECTED) if matches!(mode, 1 | 2 | 5) => READY_FOR_RECONNECTION,
+            (Trigger::ManualReconnect, READY_FOR_RECONNECTION) => CONNECTED,
+            (Trigger::LocalDisconnect, CONNECTED) => READY_FOR_RECONNECTION,
+            (Trigger::LocalReconnect, READY_FOR_RECONNECTION) if matches!(mode, 5 | 6) => CONNECTED,
+            _ => return None,
+        };
+        let transition = Transition { trigger, from: self.control_state, to };
+        self.control_state = to;
+        self.output_state = transition.output_state();
+        if self.transitions.len() == MAX_TRANSITIONS {
+            self.transitions.remove(0);
         }
+        self.transitions.push(transition);
+        Some(transition)
+    }
+
+    /// Manual disconnection by the consumer (transition f).
+    pub fn manual_disconnect(&mut self) -> Option<Transition> {
+        self.apply(Trigger::ManualDisconnect)
+    }
+
+    /// Manual reconnection by the consumer (transition e).
+    pub fn manual_reconnect(&mut self) -> Option<Transition> {
+        self.apply(Trigger::ManualReconnect)
+    }
+
+    /// Local disconnection, e.g. by a Limiter (transition g).
+    pub fn local_disconnect(&mut self) -> Option<Transition> {
+        self.apply(Trigger::LocalDisconnect)
+    }
+
+    /// Local reconnection (transition h).
+    pub fn local_reconnect(&mut self) -> Option<Transition> {
+        self.apply(Trigger::LocalReconnect)
+    }
+
+    /// Takes the transitions made since the last call, oldest first.
+    pub fn take_transitions(&mut self) -> Vec<Transition> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    /// Records the physical output state reported by the relay, or left
+    /// unchanged by a failed switching.
+    pub fn record_output(&mut self, closed: bool) {
+        self.output_state = closed;
     }
 
     /// Method 1: `remote_disconnect` — forces the object into the `disconnected`
     /// state if remote disconnection is enabled (control_mode > 0). When
     /// control_mode is 0 the method has no effect (IEC 62056-6-2 §4.5.8.3.1).
     fn remote_disconnect(&mut self) -> CosemDataType {
-        if self.control_mode > 0 {
-            self.control_state = control_state::DISCONNECTED;
-            self.output_state = false;
-        }
+        self.apply(Trigger::RemoteDisconnect);
         CosemDataType::Null
     }
 
-    /// Method 2: `remote_reconnect` — forces the object into the
-    /// `ready_for_reconnection` state when direct remote reconnection is disabled
-    /// (control_mode 1, 3, 5, 6), or directly into the `connected` state when it
-    /// is enabled (control_mode 2, 4). When control_mode is 0 the method has no
-    /// effect (IEC 62056-6-2 §4.5.8.3.2).
+    /// Method 2: `remote_reconnect` — moves a disconnected object into the
+    /// `ready_for_reconnection` state when direct remote reconnection is
+    /// disabled (control_mode 1, 3, 5, 6), or directly into the `connected`
+    /// state when it is enabled (control_mode 2, 4). When control_mode is 0
+    /// the method has no effect (IEC 62056-6-2 §4.5.8.3.2).
     fn remote_reconnect(&mut self) -> CosemDataType {
-        match self.control_mode {
-            2 | 4 => {
-                self.control_state = control_state::CONNECTED;
-                self.output_state = true;
-            }
-            1 | 3 | 5 | 6 => {
-                self.control_state = control_state::READY_FOR_RECONNECTION;
-                self.output_state = false;
-            }
-            _ => {}
-        }
+        self.apply(Trigger::RemoteReconnect);
         CosemDataType::Null
     }
 }
 
+/// Attribute descriptors of the Disconnect control class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "output_state", DataKind::Boolean).dynamic(),
+    AttributeInfo::new(3, "control_state", DataKind::Enum).dynamic(),
+    AttributeInfo::new(4, "control_mode", DataKind::Enum).writable(),
+];
+
+/// Method descriptors of the Disconnect control class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "remote_disconnect", DataKind::Integer),
+    MethodInfo::new(2, "remote_reconnect", DataKind::Integer),
+];
+
 impl InterfaceClass for DisconnectControl {
     fn class_id(&self) -> u16 {
         70
@@ -95,18 +245,33 @@ impl InterfaceClass for DisconnectControl {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Boolean(self.output_state)),
-            (3, CosemDataType::Enum(self.control_state)),
-            (4, CosemDataType::Enum(self.control_mode)),
-        ]
+        (1..=4).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Boolean(self.output_state),
+            3 => CosemDataType::Enum(self.control_state),
+            4 => CosemDataType::Enum(self.control_mode),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "remote_disconnect".to_string()), (2, "remote_reconnect".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Disconnect control",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -162,30 +327,38 @@ impl InterfaceClass for DisconnectControl {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             4 => match value {
                 CosemDataType::Enum(v) => {
                     self.control_mode = v;
                     Ok(())
                 }
-                _ => Err("control_mode must be enum".into()),
+                _ => Err(AccessError::TypeUnmatched("control_mode must be enum".to_string())),
             },
-            _ => Err(format!("DisconnectControl attribute {attribute_id} is not writable")),
+            _ => {
+                Err(AccessError::ReadWriteDenied(format!("DisconnectControl attribute {attribute_id} is not writable")))
+            }
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => Ok(self.remote_disconnect()),
             2 => Ok(self.remote_reconnect()),
-            _ => Err(format!("Method {method_id} not supported for Disconnect control")),
+            _ => {
+                Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Disconnect control")))
+            }
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -262,4 +435,69 @@ mod tests {
         obj.invoke_method(2, None).unwrap();
         assert_eq!(obj.attributes()[2].1, CosemDataType::Enum(control_state::READY_FOR_RECONNECTION));
     }
+
+    /// The transition labels `trigger` makes from each state, for one mode.
+    fn labels(control_mode: u8, trigger: Trigger) -> Vec<Option<char>> {
+        [control_state::DISCONNECTED, control_state::CONNECTED, control_state::READY_FOR_RECONNECTION]
+            .into_iter()
+            .map(|state| {
+                let mut obj = sample(control_mode);
+                obj.control_state = state;
+                obj.apply(trigger).map(|t| t.label())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn state_machine_follows_control_mode() {
+        // Per mode: manual disconnect (f), local reconnect (h), remote reconnect (a/d).
+        for mode in 0..=6 {
+            let manual = if matches!(mode, 1 | 2 | 5) { Some('f') } else { None };
+            assert_eq!(labels(mode, Trigger::ManualDisconnect), [None, manual, None], "mode {mode}");
+            let local = if matches!(mode, 5 | 6) { Some('h') } else { None };
+            assert_eq!(labels(mode, Trigger::LocalReconnect), [None, None, local], "mode {mode}");
+            let remote = match mode {
+                0 => None,
+                2 | 4 => Some('d'),
+                _ => Some('a'),
+            };
+            assert_eq!(labels(mode, Trigger::RemoteReconnect), [remote, None, None], "mode {mode}");
+        }
+        for mode in 1..=6 {
+            assert_eq!(labels(mode, Trigger::RemoteDisconnect), [None, Some('c'), Some('b')]);
+            assert_eq!(labels(mode, Trigger::ManualReconnect), [None, None, Some('e')]);
+            assert_eq!(labels(mode, Trigger::LocalDisconnect), [None, Some('g'), None]);
+        }
+        assert_eq!(labels(0, Trigger::LocalDisconnect), [None, None, None]);
+    }
+
+    #[test]
+    fn transitions_are_queued_with_the_output() {
+        let mut obj = sample(1);
+        assert_eq!(obj.local_disconnect().map(|t| t.label()), Some('g'));
+        assert!(!obj.output_state());
+        // remote_reconnect leaves a locally disconnected consumer ready for
+        // reconnection.
+        obj.invoke_method(2, None).unwrap();
+        assert_eq!(obj.control_state(), control_state::READY_FOR_RECONNECTION);
+        assert_eq!(obj.manual_reconnect().map(|t| t.label()), Some('e'));
+        obj.invoke_method(2, None).unwrap();
+        assert_eq!(obj.control_state(), control_state::CONNECTED);
+        assert!(obj.output_state());
+        let triggers: Vec<Trigger> = obj.take_transitions().iter().map(|t| t.trigger).collect();
+        assert_eq!(triggers, [Trigger::LocalDisconnect, Trigger::ManualReconnect]);
+        assert!(obj.take_transitions().is_empty());
+    }
+
+    #[test]
+    fn untaken_transitions_keep_only_the_latest() {
+        let mut obj = sample(5);
+        for _ in 0..MAX_TRANSITIONS {
+            obj.local_disconnect();
+            obj.local_reconnect();
+        }
+        let transitions = obj.take_transitions();
+        assert_eq!(transitions.len(), MAX_TRANSITIONS);
+        assert_eq!(transitions.last().map(|t| t.trigger), Some(Trigger::LocalReconnect));
+    }
 }
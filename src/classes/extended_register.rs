@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{Choice, DateTime, ScalerUnit};
 use crate::types::{BerError, CosemDataType};
@@ -56,12 +56,19 @@ impl ExtendedRegister {
         &self.capture_time
     }
 
+    /// Records a captured `value` and its `capture_time`, as a maximum-demand
+    /// register does when a demand period ends with a new maximum.
+    pub fn record(&mut self, value: Choice, capture_time: DateTime) {
+        self.value = value;
+        self.capture_time = capture_time;
+    }
+
     /// Resets the register value to 0 and clears the status and capture time.
     ///
     /// # Returns
     /// * `Ok(CosemDataType::Null)` - On successful reset.
     /// * `Err(String)` - If the value type does not support reset.
-    fn reset(&mut self) -> Result<CosemDataType, String> {
+    fn reset(&mut self) -> Result<CosemDataType, AccessError> {
         match &self.value {
             CosemDataType::Integer(_) => {
                 self.value = CosemDataType::Integer(0);
@@ -99,7 +106,7 @@ impl ExtendedRegister {
                 self.capture_time = DateTime::new([0u8; 12]);
                 Ok(CosemDataType::Null)
             }
-            _ => Err("Unsupported value type for reset".to_string()),
+            _ => Err(AccessError::TypeUnmatched("Unsupported value type for reset".to_string())),
         }
     }
 
@@ -115,6 +122,19 @@ impl ExtendedRegister {
     }
 }
 
+/// Attribute descriptors of the Extended register class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "value", DataKind::Any).dynamic(),
+    AttributeInfo::new(3, "scaler_unit", DataKind::Structure),
+    AttributeInfo::new(4, "status", DataKind::Any).dynamic(),
+    AttributeInfo::new(5, "capture_time", DataKind::DateTime).dynamic(),
+];
+
+/// Method descriptors of the Extended register class.
+const METHODS: &[MethodInfo] =
+    &[MethodInfo::new(1, "reset", DataKind::Integer), MethodInfo::new(2, "capture", DataKind::Integer)];
+
 impl InterfaceClass for ExtendedRegister {
     fn class_id(&self) -> u16 {
         4
@@ -129,19 +149,42 @@ impl InterfaceClass for ExtendedRegister {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, self.value.clone()),
-            (3, self.scaler_unit.clone().into()),
-            (4, self.status.clone()),
-            (5, self.capture_time.clone().into()),
-        ]
+        (1..=5).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => self.value.clone(),
+            3 => self.scaler_unit.clone().into(),
+            4 => self.status.clone(),
+            5 => self.capture_time.clone().into(),
+            _ => return None,
+        })
+    }
+
+    fn attribute_ref(&self, attribute_id: u8) -> Option<&CosemDataType> {
+        match attribute_id {
+            2 => Some(&self.value),
+            4 => Some(&self.status),
+            _ => None,
+        }
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "reset".to_string()), (2, "capture".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Extended register",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -188,17 +231,23 @@ impl InterfaceClass for ExtendedRegister {
         Err(BerError::InvalidTag)
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => self.reset(),
             2 => Ok(self.capture()),
-            _ => Err(format!("Method {method_id} not supported for ExtendedRegister class")),
+            _ => Err(AccessError::ObjectUnavailable(format!(
+                "Method {method_id} not supported for ExtendedRegister class"
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a length in BER (short or long form).
@@ -0,0 +1,437 @@
+use crate::dictionary;
+use crate::interface::{AccessError, InterfaceClass};
+use crate::obis::ObisCode;
+use crate::security::access_rights::AttributeAccessMode;
+use crate::types::attrs::{ObjectListElement, ScalerUnit};
+use crate::types::{BerError, CosemDataType};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::BTreeMap;
+
+/// One attribute of a [`GenericObject`]: its current value and whether a SET
+/// may change it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenericAttribute {
+    /// The current value.
+    pub value: CosemDataType,
+    /// Whether `set_attribute` accepts a new value.
+    pub writable: bool,
+}
+
+/// A COSEM object of any class id and version, holding its attribute values
+/// as plain data and answering its methods with stub results.
+///
+/// It stands in for classes the crate does not implement — manufacturer
+/// classes, or standard ones such as Sensor manager — so emulators and
+/// mirrors can represent any device. Objects are built by hand with the
+/// `with_*` builders or from an Association LN `object_list` scan
+/// ([`from_object_list_element`](Self::from_object_list_element)), then filled
+/// with [`set_value`](Self::set_value) as the attributes are read;
+/// [`label`](Self::label) and [`formatted_value`](Self::formatted_value)
+/// render the scan results through the [`dictionary`].
+///
+/// Attribute 1 is always the logical name. A SET succeeds only for attributes
+/// marked writable; a method returns its configured result.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenericObject {
+    class_id: u16,
+    version: u8,
+    logical_name: ObisCode,
+    attributes: BTreeMap<u8, GenericAttribute>,
+    methods: BTreeMap<u8, CosemDataType>,
+}
+
+impl GenericObject {
+    /// Creates an object of class `class_id` / `version` with no attributes
+    /// besides the logical name and no methods.
+    pub fn new(class_id: u16, version: u8, logical_name: ObisCode) -> Self {
+        GenericObject { class_id, version, logical_name, attributes: BTreeMap::new(), methods: BTreeMap::new() }
+    }
+
+    /// Builds the object an `object_list` entry describes: every listed
+    /// attribute (null-data until read) writable when its access mode allows
+    /// writing, and every listed method answering null-data.
+    pub fn from_object_list_element(element: &ObjectListElement) -> Self {
+        let mut object = GenericObject::new(element.class_id, element.version, element.logical_name);
+        for item in &element.access_rights.attribute_access {
+            let Ok(id) = u8::try_from(item.attribute_id) else { continue };
+            if id > 1 {
+                let writable = AttributeAccessMode::from_u8(item.access_mode).is_some_and(|mode| mode.allows_write());
+                object.attributes.insert(id, GenericAttribute { value: CosemDataType::Null, writable });
+            }
+        }
+        for item in &element.access_rights.method_access {
+            if let Ok(id) = u8::try_from(item.method_id) {
+                object.methods.insert(id, CosemDataType::Null);
+            }
+        }
+        object
+    }
+
+    /// Builds the objects of an Association LN `object_list` value (an array
+    /// of `object_list_element`s).
+    ///
+    /// # Errors
+    /// Returns a description of the first malformed entry.
+    pub fn from_object_list(object_list: &CosemDataType) -> Result<Vec<Self>, String> {
+        match object_list {
+            CosemDataType::Array(entries) => entries
+                .iter()
+                .map(|entry| ObjectListElement::try_from(entry).map(|e| Self::from_object_list_element(&e)))
+                .collect(),
+            _ => Err("object_list must be array".to_string()),
+        }
+    }
+
+    /// Adds (or replaces) attribute `attribute_id` with its value and
+    /// writability. Attribute 1 is the logical name and is ignored.
+    pub fn with_attribute(mut self, attribute_id: u8, value: CosemDataType, writable: bool) -> Self {
+        if attribute_id > 1 {
+            self.attributes.insert(attribute_id, GenericAttribute { value, writable });
+        }
+        self
+    }
+
+    /// Adds (or replaces) method `method_id`, answering `result` when invoked.
+    pub fn with_method(mut self, method_id: u8, result: CosemDataType) -> Self {
+        self.methods.insert(method_id, result);
+        self
+    }
+
+    /// The attribute `attribute_id` (other than the logical name).
+    pub fn attribute(&self, attribute_id: u8) -> Option<&GenericAttribute> {
+        self.attributes.get(&attribute_id)
+    }
+
+    /// Stores a value read from the device, regardless of writability.
+    /// Returns `false` if the object has no attribute `attribute_id`.
+    pub fn set_value(&mut self, attribute_id: u8, value: CosemDataType) -> bool {
+        match self.attributes.get_mut(&attribute_id) {
+            Some(attribute) => {
+                attribute.value = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Marks attribute `attribute_id` writable or read-only. Returns `false` if
+    /// the object has no such attribute.
+    pub fn set_writable(&mut self, attribute_id: u8, writable: bool) -> bool {
+        match self.attributes.get_mut(&attribute_id) {
+            Some(attribute) => {
+                attribute.writable = writable;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the result method `method_id` answers with, adding the method if
+    /// needed.
+    pub fn set_method_result(&mut self, method_id: u8, result: CosemDataType) {
+        self.methods.insert(method_id, result);
+    }
+
+    /// The object's dictionary label, e.g. `Active energy import (A+), total
+    /// [1-0:1.8.0*255] (IC 3)` (see [`dictionary::object_label`]).
+    pub fn label(&self) -> String {
+        dictionary::object_label(&self.logical_name, self.class_id)
+    }
+
+    /// The value of a Register, Extended register or Demand register with
+    /// its scaler applied and its unit symbol, e.g. `123.456 Wh`, once both
+    /// attributes have been read; `None` for other classes.
+    pub fn formatted_value(&self) -> Option<String> {
+        // Demand register: current_average_value (2) and scaler_unit (4).
+        let (value, scaler_unit) = match self.class_id {
+            3 | 4 => (2, 3),
+            5 => (2, 4),
+            _ => return None,
+        };
+        let scaler_unit = ScalerUnit::try_from(&self.attribute(scaler_unit)?.value).ok()?;
+        scaler_unit.format_value(&self.attribute(value)?.value)
+    }
+}
+
+impl InterfaceClass for GenericObject {
+    fn class_id(&self) -> u16 {
+        self.class_id
+    }
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn logical_name(&self) -> &ObisCode {
+        &self.logical_name
+    }
+
+    fn attributes(&self) -> Vec<(u8, CosemDataType)> {
+        let mut attributes = vec![(1, CosemDataType::OctetString(self.logical_name.to_bytes()))];
+        attributes.extend(self.attributes.iter().map(|(id, a)| (*id, a.value.clone())));
+        attributes
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        match attribute_id {
+            1 => Some(CosemDataType::OctetString(self.logical_name.to_bytes())),
+            _ => self.attribute_ref(attribute_id).cloned(),
+        }
+    }
+
+    fn attribute_ref(&self, attribute_id: u8) -> Option<&CosemDataType> {
+        self.attributes.get(&attribute_id).map(|a| &a.value)
+    }
+
+    fn methods(&self) -> Vec<(u8, String)> {
+        self.methods.keys().map(|id| (*id, format!("method_{id}"))).collect()
+    }
+
+    /// Serializes the object as `structure { class_id, version, logical_name,
+    /// array of structure { attribute_id, value, writable }, array of
+    /// structure { method_id, result } }`.
+    fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|(id, a)| {
+                CosemDataType::Structure(vec![
+                    CosemDataType::Unsigned(*id),
+                    a.value.clone(),
+                    CosemDataType::Boolean(a.writable),
+                ])
+            })
+            .collect();
+        let methods = self
+            .methods
+            .iter()
+            .map(|(id, result)| CosemDataType::Structure(vec![CosemDataType::Unsigned(*id), result.clone()]))
+            .collect();
+        CosemDataType::Structure(vec![
+            CosemDataType::LongUnsigned(self.class_id),
+            CosemDataType::Unsigned(self.version),
+            CosemDataType::OctetString(self.logical_name.to_bytes()),
+            CosemDataType::Array(attributes),
+            CosemDataType::Array(methods),
+        ])
+        .serialize_ber(buf)
+    }
+
+    /// Restores an object written by [`serialize_ber`](Self::serialize_ber),
+    /// including its class id and version.
+    fn deserialize_ber(&mut self, data: &[u8]) -> Result<(), BerError> {
+        let (tlv, rest) = CosemDataType::deserialize_ber(data)?;
+        if !rest.is_empty() {
+            return Err(BerError::InvalidLength);
+        }
+        let CosemDataType::Structure(fields) = tlv else { return Err(BerError::InvalidTag) };
+        let [class_id, version, obis, attributes, methods] = fields.as_slice() else {
+            return Err(BerError::InvalidLength);
+        };
+        let (CosemDataType::LongUnsigned(class_id), CosemDataType::Unsigned(version)) = (class_id, version) else {
+            return Err(BerError::InvalidTag);
+        };
+        let (CosemDataType::OctetString(obis), CosemDataType::Array(attributes), CosemDataType::Array(methods)) =
+            (obis, attributes, methods)
+        else {
+            return Err(BerError::InvalidTag);
+        };
+        let logical_name: [u8; 6] = obis.as_slice().try_into().map_err(|_| BerError::InvalidLength)?;
+        let attributes = attributes
+            .iter()
+            .map(|item| match item {
+                CosemDataType::Structure(f) => match f.as_slice() {
+                    [CosemDataType::Unsigned(id), value, CosemDataType::Boolean(writable)] => {
+                        Ok((*id, GenericAttribute { value: value.clone(), writable: *writable }))
+                    }
+                    _ => Err(BerError::InvalidValue),
+                },
+                _ => Err(BerError::InvalidTag),
+            })
+            .collect::<Result<_, _>>()?;
+        let methods = methods
+            .iter()
+            .map(|item| match item {
+                CosemDataType::Structure(f) => match f.as_slice() {
+                    [CosemDataType::Unsigned(id), result] => Ok((*id, result.clone())),
+                    _ => Err(BerError::InvalidValue),
+                },
+                _ => Err(BerError::InvalidTag),
+            })
+            .collect::<Result<_, _>>()?;
+        let [a, b, c, d, e, f] = logical_name;
+        *self = GenericObject {
+            class_id: *class_id,
+            version: *version,
+            logical_name: ObisCode::new(a, b, c, d, e, f),
+            attributes,
+            methods,
+        };
+        Ok(())
+    }
+
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
+        let class_id = self.class_id;
+        match self.attributes.get_mut(&attribute_id) {
+            Some(attribute) if attribute.writable => {
+                attribute.value = value;
+                Ok(())
+            }
+            Some(_) => {
+                Err(AccessError::ReadWriteDenied(format!("class {class_id} attribute {attribute_id} is not writable")))
+            }
+            None if attribute_id == 1 => Err(AccessError::ReadWriteDenied("logical_name is not writable".to_string())),
+            None => Err(AccessError::ObjectUnavailable(format!("class {class_id} has no attribute {attribute_id}"))),
+        }
+    }
+
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        self.methods
+            .get(&method_id)
+            .cloned()
+            .ok_or_else(|| AccessError::ObjectUnavailable(format!("class {} has no method {method_id}", self.class_id)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::RequestDispatcher;
+    use crate::service::action::{ActionRequest, ActionResponse};
+    use crate::service::get::{GetDataResult, GetRequest, GetResponse};
+    use crate::service::set::{SetRequest, SetResponse};
+    use crate::service::{data_access_result, AttributeDescriptor, MethodDescriptor};
+    use crate::types::attrs::{AccessRight, AttributeAccessItem, MethodAccessItem};
+
+    /// A Sensor manager (class 67) entry as an object-list scan returns it.
+    fn sensor_manager_entry() -> CosemDataType {
+        let attribute =
+            |attribute_id, access_mode| AttributeAccessItem { attribute_id, access_mode, access_selectors: None };
+        CosemDataType::from(ObjectListElement {
+            class_id: 67,
+            version: 0,
+            logical_name: ObisCode::new(0, 0, 65, 0, 0, 255),
+            access_rights: AccessRight {
+                attribute_access: vec![attribute(1, 1), attribute(2, 1), attribute(3, 3)],
+                method_access: vec![MethodAccessItem { method_id: 1, access_mode: 1 }],
+            },
+        })
+    }
+
+    #[test]
+    fn object_list_scan_builds_the_objects() {
+        let objects = GenericObject::from_object_list(&CosemDataType::Array(vec![sensor_manager_entry()])).unwrap();
+        let [sensor] = objects.as_slice() else { panic!("one object expected") };
+        assert_eq!((sensor.class_id(), sensor.version()), (67, 0));
+        assert_eq!(sensor.attributes().iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(!sensor.attribute(2).unwrap().writable && sensor.attribute(3).unwrap().writable);
+        assert_eq!(sensor.methods(), vec![(1, "method_1".to_string())]);
+        assert!(GenericObject::from_object_list(&CosemDataType::Null).is_err());
+    }
+
+    #[test]
+    fn scanned_registers_render_through_the_dictionary() {
+        let attribute = |attribute_id| AttributeAccessItem { attribute_id, access_mode: 1, access_selectors: None };
+        let entry = ObjectListElement {
+            class_id: 3,
+            version: 0,
+            logical_name: ObisCode::new(1, 0, 1, 8, 0, 255),
+            access_rights: AccessRight {
+                attribute_access: vec![attribute(1), attribute(2), attribute(3)],
+                method_access: vec![],
+            },
+        };
+        let mut register = GenericObject::from_object_list_element(&entry);
+        assert_eq!(register.label(), "Active energy import (A+), total [1-0:1.8.0*255] (IC 3)");
+        assert_eq!(register.formatted_value(), None);
+        register.set_value(2, CosemDataType::DoubleLongUnsigned(123_456));
+        register.set_value(3, ScalerUnit::new(-3, 30).into());
+        assert_eq!(register.formatted_value().as_deref(), Some("123.456 Wh"));
+
+        let [sensor] =
+            &GenericObject::from_object_list(&CosemDataType::Array(vec![sensor_manager_entry()])).unwrap()[..]
+        else {
+            panic!("one object expected")
+        };
+        assert_eq!(sensor.formatted_value(), None);
+    }
+
+    #[test]
+    fn dispatcher_serves_a_generic_object() {
+        let obis = ObisCode::new(0, 0, 65, 0, 0, 255);
+        let mut sensor =
+            GenericObject::from_object_list_element(&ObjectListElement::try_from(&sensor_manager_entry()).unwrap())
+                .with_method(1, CosemDataType::Boolean(true));
+        assert!(sensor.set_value(2, CosemDataType::OctetString(b"SN-0042".to_vec())));
+        let mut d = RequestDispatcher::new();
+        d.add(Box::new(sensor));
+
+        let get = GetRequest::Normal {
+            invoke_id_and_priority: 0xC1,
+            attribute: AttributeDescriptor::new(67, obis, 2),
+            access_selection: None,
+        };
+        let resp = GetResponse::decode(&d.dispatch(&get.encode().unwrap()).unwrap()).unwrap();
+        assert_eq!(
+            resp,
+            GetResponse::Normal {
+                invoke_id_and_priority: 0xC1,
+                result: GetDataResult::Data(CosemDataType::OctetString(b"SN-0042".to_vec())),
+            }
+        );
+
+        let mut set = |attribute_id| {
+            let set = SetRequest::Normal {
+                invoke_id_and_priority: 0xC1,
+                attribute: AttributeDescriptor::new(67, obis, attribute_id),
+                access_selection: None,
+                value: CosemDataType::Unsigned(5),
+            };
+            match SetResponse::decode(&d.dispatch(&set.encode().unwrap()).unwrap()).unwrap() {
+                SetResponse::Normal { result, .. } => result,
+                other => panic!("unexpected {other:?}"),
+            }
+        };
+        assert_eq!(set(2), data_access_result::READ_WRITE_DENIED);
+        assert_eq!(set(3), data_access_result::SUCCESS);
+        assert_eq!(set(9), data_access_result::OBJECT_UNAVAILABLE);
+
+        let action = ActionRequest::Normal {
+            invoke_id_and_priority: 0xC1,
+            method: MethodDescriptor::new(67, obis, 1),
+            parameters: None,
+        };
+        let resp = ActionResponse::decode(&d.dispatch(&action.encode().unwrap()).unwrap()).unwrap();
+        assert!(matches!(
+            resp,
+            ActionResponse::Normal {
+                result: 0,
+                return_parameters: Some(GetDataResult::Data(CosemDataType::Boolean(true))),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn ber_round_trip_restores_the_object() {
+        let object = GenericObject::new(0x8001, 2, ObisCode::new(0, 128, 96, 5, 1, 255))
+            .with_attribute(2, CosemDataType::DoubleLongUnsigned(7), true)
+            .with_attribute(4, CosemDataType::Array(vec![CosemDataType::Enum(1)]), false)
+            .with_method(1, CosemDataType::Null);
+        let mut buf = Vec::new();
+        object.serialize_ber(&mut buf).unwrap();
+        let mut restored = GenericObject::new(0, 0, ObisCode::new(0, 0, 0, 0, 0, 0));
+        restored.deserialize_ber(&buf).unwrap();
+        assert_eq!(restored, object);
+        assert!(restored.deserialize_ber(&buf[..buf.len() - 1]).is_err());
+    }
+}
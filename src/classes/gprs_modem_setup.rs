@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::QualityOfService;
 use crate::types::{BerError, CosemDataType};
@@ -42,6 +42,17 @@ impl GprsModemSetup {
     }
 }
 
+/// Attribute descriptors of the GPRS modem setup class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "APN", DataKind::OctetString),
+    AttributeInfo::new(3, "PIN_code", DataKind::LongUnsigned),
+    AttributeInfo::new(4, "quality_of_service", DataKind::Structure),
+];
+
+/// Method descriptors of the GPRS modem setup class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for GprsModemSetup {
     fn class_id(&self) -> u16 {
         45
@@ -56,12 +67,17 @@ impl InterfaceClass for GprsModemSetup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::OctetString(self.apn.clone())),
-            (3, CosemDataType::LongUnsigned(self.pin_code)),
-            (4, CosemDataType::from(self.quality_of_service.clone())),
-        ]
+        (1..=4).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::OctetString(self.apn.clone()),
+            3 => CosemDataType::LongUnsigned(self.pin_code),
+            4 => CosemDataType::from(self.quality_of_service.clone()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -69,6 +85,16 @@ impl InterfaceClass for GprsModemSetup {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "GPRS modem setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -121,13 +147,19 @@ impl InterfaceClass for GprsModemSetup {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for GPRS modem setup (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for GPRS modem setup (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{CellInfo, DateTime, GsmAdjacentCell};
 use crate::types::{BerError, CosemDataType};
@@ -64,6 +64,21 @@ impl GsmDiagnostic {
     }
 }
 
+/// Attribute descriptors of the GSM diagnostic class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "operator", DataKind::OctetString).dynamic(),
+    AttributeInfo::new(3, "status", DataKind::Enum).dynamic(),
+    AttributeInfo::new(4, "cs_attachment", DataKind::Enum).dynamic(),
+    AttributeInfo::new(5, "ps_status", DataKind::Enum).dynamic(),
+    AttributeInfo::new(6, "cell_info", DataKind::Structure).dynamic(),
+    AttributeInfo::new(7, "adjacent_cells", DataKind::Array).dynamic(),
+    AttributeInfo::new(8, "capture_time", DataKind::DateTime).dynamic(),
+];
+
+/// Method descriptors of the GSM diagnostic class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for GsmDiagnostic {
     fn class_id(&self) -> u16 {
         47
@@ -78,16 +93,21 @@ impl InterfaceClass for GsmDiagnostic {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::OctetString(self.operator.clone())),
-            (3, CosemDataType::Enum(self.status)),
-            (4, CosemDataType::Enum(self.cs_attachment)),
-            (5, CosemDataType::Enum(self.ps_status)),
-            (6, CosemDataType::from(self.cell_info.clone())),
-            (7, CosemDataType::Array(self.adjacent_cells.iter().cloned().map(CosemDataType::from).collect())),
-            (8, self.capture_time.clone().into()),
-        ]
+        (1..=8).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::OctetString(self.operator.clone()),
+            3 => CosemDataType::Enum(self.status),
+            4 => CosemDataType::Enum(self.cs_attachment),
+            5 => CosemDataType::Enum(self.ps_status),
+            6 => CosemDataType::from(self.cell_info.clone()),
+            7 => CosemDataType::Array(self.adjacent_cells.iter().cloned().map(CosemDataType::from).collect()),
+            8 => self.capture_time.clone().into(),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -95,6 +115,16 @@ impl InterfaceClass for GsmDiagnostic {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "GSM diagnostic",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -155,13 +185,19 @@ impl InterfaceClass for GsmDiagnostic {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for GSM diagnostic (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for GSM diagnostic (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_enum(value: &CosemDataType) -> Result<u8, BerError> {
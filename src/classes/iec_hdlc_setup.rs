@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 use serde::{Deserialize, Serialize};
@@ -84,6 +84,36 @@ impl IecHdlcSetup {
     }
 }
 
+/// Attribute descriptors of the IEC HDLC setup class, version 0 (the maximum
+/// information field lengths are `unsigned`).
+const ATTRIBUTES_V0: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "comm_speed", DataKind::Enum).writable(),
+    AttributeInfo::new(3, "window_size_transmit", DataKind::Unsigned).writable(),
+    AttributeInfo::new(4, "window_size_receive", DataKind::Unsigned).writable(),
+    AttributeInfo::new(5, "max_info_field_length_transmit", DataKind::Unsigned).writable(),
+    AttributeInfo::new(6, "max_info_field_length_receive", DataKind::Unsigned).writable(),
+    AttributeInfo::new(7, "inter_octet_time_out", DataKind::LongUnsigned).writable(),
+    AttributeInfo::new(8, "inactivity_time_out", DataKind::LongUnsigned).writable(),
+    AttributeInfo::new(9, "device_address", DataKind::LongUnsigned).writable(),
+];
+
+/// Attribute descriptors of the IEC HDLC setup class, version 1.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "comm_speed", DataKind::Enum).writable(),
+    AttributeInfo::new(3, "window_size_transmit", DataKind::Unsigned).writable(),
+    AttributeInfo::new(4, "window_size_receive", DataKind::Unsigned).writable(),
+    AttributeInfo::new(5, "max_info_field_length_transmit", DataKind::LongUnsigned).writable(),
+    AttributeInfo::new(6, "max_info_field_length_receive", DataKind::LongUnsigned).writable(),
+    AttributeInfo::new(7, "inter_octet_time_out", DataKind::LongUnsigned).writable(),
+    AttributeInfo::new(8, "inactivity_time_out", DataKind::LongUnsigned).writable(),
+    AttributeInfo::new(9, "device_address", DataKind::LongUnsigned).writable(),
+];
+
+/// Method descriptors of the IEC HDLC setup class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for IecHdlcSetup {
     fn class_id(&self) -> u16 {
         23
@@ -98,17 +128,22 @@ impl InterfaceClass for IecHdlcSetup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Enum(self.comm_speed)),
-            (3, CosemDataType::Unsigned(self.window_size_transmit)),
-            (4, CosemDataType::Unsigned(self.window_size_receive)),
-            (5, self.max_info(self.max_info_field_length_transmit)),
-            (6, self.max_info(self.max_info_field_length_receive)),
-            (7, CosemDataType::LongUnsigned(self.inter_octet_time_out)),
-            (8, CosemDataType::LongUnsigned(self.inactivity_time_out)),
-            (9, CosemDataType::LongUnsigned(self.device_address)),
-        ]
+        (1..=9).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Enum(self.comm_speed),
+            3 => CosemDataType::Unsigned(self.window_size_transmit),
+            4 => CosemDataType::Unsigned(self.window_size_receive),
+            5 => self.max_info(self.max_info_field_length_transmit),
+            6 => self.max_info(self.max_info_field_length_receive),
+            7 => CosemDataType::LongUnsigned(self.inter_octet_time_out),
+            8 => CosemDataType::LongUnsigned(self.inactivity_time_out),
+            9 => CosemDataType::LongUnsigned(self.device_address),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -116,6 +151,16 @@ impl InterfaceClass for IecHdlcSetup {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "IEC HDLC setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: if self.version >= 1 { ATTRIBUTES } else { ATTRIBUTES_V0 },
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -175,61 +220,70 @@ impl InterfaceClass for IecHdlcSetup {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => match value {
                 CosemDataType::Enum(v) => {
                     self.comm_speed = v;
                     Ok(())
                 }
-                _ => Err("comm_speed must be enum".into()),
+                _ => Err(AccessError::TypeUnmatched("comm_speed must be enum".to_string())),
             },
             3 => {
-                self.window_size_transmit =
-                    take_unsigned(&value).map_err(|_| "window_size_transmit must be unsigned".to_string())?;
+                self.window_size_transmit = take_unsigned(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("window_size_transmit must be unsigned".to_string()))?;
                 Ok(())
             }
             4 => {
-                self.window_size_receive =
-                    take_unsigned(&value).map_err(|_| "window_size_receive must be unsigned".to_string())?;
+                self.window_size_receive = take_unsigned(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("window_size_receive must be unsigned".to_string()))?;
                 Ok(())
             }
             5 => {
-                self.max_info_field_length_transmit =
-                    take_u16(&value).map_err(|_| "max_info_field_length_transmit must be unsigned".to_string())?;
+                self.max_info_field_length_transmit = take_u16(&value).map_err(|_| {
+                    AccessError::TypeUnmatched("max_info_field_length_transmit must be unsigned".to_string())
+                })?;
                 Ok(())
             }
             6 => {
-                self.max_info_field_length_receive =
-                    take_u16(&value).map_err(|_| "max_info_field_length_receive must be unsigned".to_string())?;
+                self.max_info_field_length_receive = take_u16(&value).map_err(|_| {
+                    AccessError::TypeUnmatched("max_info_field_length_receive must be unsigned".to_string())
+                })?;
                 Ok(())
             }
             7 => {
-                self.inter_octet_time_out =
-                    take_long_unsigned(&value).map_err(|_| "inter_octet_time_out must be long-unsigned".to_string())?;
+                self.inter_octet_time_out = take_long_unsigned(&value).map_err(|_| {
+                    AccessError::TypeUnmatched("inter_octet_time_out must be long-unsigned".to_string())
+                })?;
                 Ok(())
             }
             8 => {
-                self.inactivity_time_out =
-                    take_long_unsigned(&value).map_err(|_| "inactivity_time_out must be long-unsigned".to_string())?;
+                self.inactivity_time_out = take_long_unsigned(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("inactivity_time_out must be long-unsigned".to_string()))?;
                 Ok(())
             }
             9 => {
-                self.device_address =
-                    take_long_unsigned(&value).map_err(|_| "device_address must be long-unsigned".to_string())?;
+                self.device_address = take_long_unsigned(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("device_address must be long-unsigned".to_string()))?;
                 Ok(())
             }
-            _ => Err(format!("IecHdlcSetup attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!("IecHdlcSetup attribute {attribute_id} is not writable"))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for IEC HDLC setup (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for IEC HDLC setup (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_unsigned(value: &CosemDataType) -> Result<u8, BerError> {
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 use serde::{Deserialize, Serialize};
@@ -64,6 +64,22 @@ impl IecLocalPortSetup {
     }
 }
 
+/// Attribute descriptors of the IEC local port setup class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "default_mode", DataKind::Enum).writable(),
+    AttributeInfo::new(3, "default_baud", DataKind::Enum).writable(),
+    AttributeInfo::new(4, "prop_baud", DataKind::Enum).writable(),
+    AttributeInfo::new(5, "response_time", DataKind::Enum).writable(),
+    AttributeInfo::new(6, "device_addr", DataKind::OctetString).writable(),
+    AttributeInfo::new(7, "pass_p1", DataKind::OctetString).writable(),
+    AttributeInfo::new(8, "pass_p2", DataKind::OctetString).writable(),
+    AttributeInfo::new(9, "pass_w5", DataKind::OctetString).writable(),
+];
+
+/// Method descriptors of the IEC local port setup class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for IecLocalPortSetup {
     fn class_id(&self) -> u16 {
         19
@@ -78,17 +94,22 @@ impl InterfaceClass for IecLocalPortSetup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Enum(self.default_mode)),
-            (3, CosemDataType::Enum(self.default_baud)),
-            (4, CosemDataType::Enum(self.prop_baud)),
-            (5, CosemDataType::Enum(self.response_time)),
-            (6, CosemDataType::OctetString(self.device_addr.clone())),
-            (7, CosemDataType::OctetString(self.pass_p1.clone())),
-            (8, CosemDataType::OctetString(self.pass_p2.clone())),
-            (9, CosemDataType::OctetString(self.pass_w5.clone())),
-        ]
+        (1..=9).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Enum(self.default_mode),
+            3 => CosemDataType::Enum(self.default_baud),
+            4 => CosemDataType::Enum(self.prop_baud),
+            5 => CosemDataType::Enum(self.response_time),
+            6 => CosemDataType::OctetString(self.device_addr.clone()),
+            7 => CosemDataType::OctetString(self.pass_p1.clone()),
+            8 => CosemDataType::OctetString(self.pass_p2.clone()),
+            9 => CosemDataType::OctetString(self.pass_w5.clone()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -96,6 +117,16 @@ impl InterfaceClass for IecLocalPortSetup {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "IEC local port setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -147,52 +178,67 @@ impl InterfaceClass for IecLocalPortSetup {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => {
-                self.default_mode = take_enum(&value).map_err(|_| "default_mode must be enum".to_string())?;
+                self.default_mode = take_enum(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("default_mode must be enum".to_string()))?;
                 Ok(())
             }
             3 => {
-                self.default_baud = take_enum(&value).map_err(|_| "default_baud must be enum".to_string())?;
+                self.default_baud = take_enum(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("default_baud must be enum".to_string()))?;
                 Ok(())
             }
             4 => {
-                self.prop_baud = take_enum(&value).map_err(|_| "prop_baud must be enum".to_string())?;
+                self.prop_baud =
+                    take_enum(&value).map_err(|_| AccessError::TypeUnmatched("prop_baud must be enum".to_string()))?;
                 Ok(())
             }
             5 => {
-                self.response_time = take_enum(&value).map_err(|_| "response_time must be enum".to_string())?;
+                self.response_time = take_enum(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("response_time must be enum".to_string()))?;
                 Ok(())
             }
             6 => {
-                self.device_addr =
-                    take_octet_string(&value).map_err(|_| "device_addr must be octet-string".to_string())?;
+                self.device_addr = take_octet_string(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("device_addr must be octet-string".to_string()))?;
                 Ok(())
             }
             7 => {
-                self.pass_p1 = take_octet_string(&value).map_err(|_| "pass_p1 must be octet-string".to_string())?;
+                self.pass_p1 = take_octet_string(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("pass_p1 must be octet-string".to_string()))?;
                 Ok(())
             }
             8 => {
-                self.pass_p2 = take_octet_string(&value).map_err(|_| "pass_p2 must be octet-string".to_string())?;
+                self.pass_p2 = take_octet_string(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("pass_p2 must be octet-string".to_string()))?;
                 Ok(())
             }
             9 => {
-                self.pass_w5 = take_octet_string(&value).map_err(|_| "pass_w5 must be octet-string".to_string())?;
+                self.pass_w5 = take_octet_string(&value)
+                    .map_err(|_| AccessError::TypeUnmatched("pass_w5 must be octet-string".to_string()))?;
                 Ok(())
             }
-            _ => Err(format!("IecLocalPortSetup attribute {attribute_id} is not writable")),
+            _ => {
+                Err(AccessError::ReadWriteDenied(format!("IecLocalPortSetup attribute {attribute_id} is not writable")))
+            }
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for IEC local port setup (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for IEC local port setup (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_enum(value: &CosemDataType) -> Result<u8, BerError> {
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::ImageToActivateInfo;
 use crate::types::{BerError, CosemDataType};
@@ -74,18 +74,18 @@ impl ImageTransfer {
         }
     }
 
-    fn ensure_enabled(&self) -> Result<(), String> {
+    fn ensure_enabled(&self) -> Result<(), AccessError> {
         if self.image_transfer_enabled {
             Ok(())
         } else {
-            Err("Image transfer is disabled".to_string())
+            Err(AccessError::TemporaryFailure("Image transfer is disabled".to_string()))
         }
     }
 
     /// Method 1: `image_transfer_initiate` — starts a new transfer, clearing the
     /// block status (IEC 62056-6-2 §4.4.6.5). Parameter is
     /// `structure { image_identifier: octet-string, image_size: double-long-unsigned }`.
-    fn image_transfer_initiate(&mut self, _data: CosemDataType) -> Result<CosemDataType, String> {
+    fn image_transfer_initiate(&mut self, _data: CosemDataType) -> Result<CosemDataType, AccessError> {
         self.ensure_enabled()?;
         self.image_transferred_blocks_status.clear();
         self.image_first_not_transferred_block_number = 0;
@@ -96,14 +96,22 @@ impl ImageTransfer {
     /// Method 2: `image_block_transfer` — transfers one block, marking it as
     /// received. Parameter is `structure { image_block_number: double-long-unsigned,
     /// image_block_value: octet-string }`.
-    fn image_block_transfer(&mut self, data: CosemDataType) -> Result<CosemDataType, String> {
+    fn image_block_transfer(&mut self, data: CosemDataType) -> Result<CosemDataType, AccessError> {
         self.ensure_enabled()?;
         let block_number = match data {
             CosemDataType::Structure(fields) if !fields.is_empty() => match &fields[0] {
                 CosemDataType::DoubleLongUnsigned(n) => *n,
-                _ => return Err("image_block_number must be double-long-unsigned".to_string()),
+                _ => {
+                    return Err(AccessError::TypeUnmatched(
+                        "image_block_number must be double-long-unsigned".to_string(),
+                    ))
+                }
             },
-            _ => return Err("Expected structure { image_block_number, image_block_value }".to_string()),
+            _ => {
+                return Err(AccessError::TypeUnmatched(
+                    "Expected structure { image_block_number, image_block_value }".to_string(),
+                ))
+            }
         };
         self.set_block_transferred(block_number);
         self.image_first_not_transferred_block_number = self.first_clear_bit();
@@ -112,7 +120,7 @@ impl ImageTransfer {
 
     /// Method 3: `image_verify` — verifies the transferred image. Parameter is
     /// `integer (0)`.
-    fn image_verify(&mut self, _data: CosemDataType) -> Result<CosemDataType, String> {
+    fn image_verify(&mut self, _data: CosemDataType) -> Result<CosemDataType, AccessError> {
         self.ensure_enabled()?;
         self.image_transfer_status = transfer_status::VERIFICATION_SUCCESSFUL;
         Ok(CosemDataType::Null)
@@ -120,7 +128,7 @@ impl ImageTransfer {
 
     /// Method 4: `image_activate` — activates the verified image. Parameter is
     /// `integer (0)`.
-    fn image_activate(&mut self, _data: CosemDataType) -> Result<CosemDataType, String> {
+    fn image_activate(&mut self, _data: CosemDataType) -> Result<CosemDataType, AccessError> {
         self.ensure_enabled()?;
         self.image_transfer_status = transfer_status::ACTIVATION_SUCCESSFUL;
         Ok(CosemDataType::Null)
@@ -152,6 +160,25 @@ impl ImageTransfer {
     }
 }
 
+/// Attribute descriptors of the Image transfer class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "image_block_size", DataKind::DoubleLongUnsigned),
+    AttributeInfo::new(3, "image_transferred_blocks_status", DataKind::BitString).dynamic(),
+    AttributeInfo::new(4, "image_first_not_transferred_block_number", DataKind::DoubleLongUnsigned).dynamic(),
+    AttributeInfo::new(5, "image_transfer_enabled", DataKind::Boolean),
+    AttributeInfo::new(6, "image_transfer_status", DataKind::Enum).dynamic(),
+    AttributeInfo::new(7, "image_to_activate_info", DataKind::Array).dynamic(),
+];
+
+/// Method descriptors of the Image transfer class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "image_transfer_initiate", DataKind::Structure),
+    MethodInfo::new(2, "image_block_transfer", DataKind::Structure),
+    MethodInfo::new(3, "image_verify", DataKind::Integer),
+    MethodInfo::new(4, "image_activate", DataKind::Integer),
+];
+
 impl InterfaceClass for ImageTransfer {
     fn class_id(&self) -> u16 {
         18
@@ -166,15 +193,20 @@ impl InterfaceClass for ImageTransfer {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::DoubleLongUnsigned(self.image_block_size)),
-            (3, CosemDataType::BitString(self.image_transferred_blocks_status.clone())),
-            (4, CosemDataType::DoubleLongUnsigned(self.image_first_not_transferred_block_number)),
-            (5, CosemDataType::Boolean(self.image_transfer_enabled)),
-            (6, CosemDataType::Enum(self.image_transfer_status)),
-            (7, CosemDataType::Array(self.image_to_activate_info.iter().cloned().map(CosemDataType::from).collect())),
-        ]
+        (1..=7).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::DoubleLongUnsigned(self.image_block_size),
+            3 => CosemDataType::BitString(self.image_transferred_blocks_status.clone()),
+            4 => CosemDataType::DoubleLongUnsigned(self.image_first_not_transferred_block_number),
+            5 => CosemDataType::Boolean(self.image_transfer_enabled),
+            6 => CosemDataType::Enum(self.image_transfer_status),
+            7 => CosemDataType::Array(self.image_to_activate_info.iter().cloned().map(CosemDataType::from).collect()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -186,6 +218,16 @@ impl InterfaceClass for ImageTransfer {
         ]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Image transfer",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -257,20 +299,24 @@ impl InterfaceClass for ImageTransfer {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        let params = params.ok_or("Missing method parameter")?;
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let params = params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?;
         match method_id {
             1 => self.image_transfer_initiate(params),
             2 => self.image_block_transfer(params),
             3 => self.image_verify(params),
             4 => self.image_activate(params),
-            _ => Err(format!("Method {method_id} not supported for Image transfer")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Image transfer"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::IpOption;
 use crate::types::{BerError, CosemDataType};
@@ -64,10 +64,12 @@ impl Ipv4Setup {
     }
 
     /// Method 1: `add_mc_IP_address` — adds a multicast IP address.
-    fn add_mc_ip_address(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
+    fn add_mc_ip_address(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
         let addr = match data {
             CosemDataType::DoubleLongUnsigned(v) => *v,
-            _ => return Err("add_mc_IP_address expects a double-long-unsigned".to_string()),
+            _ => {
+                return Err(AccessError::TypeUnmatched("add_mc_IP_address expects a double-long-unsigned".to_string()))
+            }
         };
         if !self.multicast_ip_address.contains(&addr) {
             self.multicast_ip_address.push(addr);
@@ -76,15 +78,19 @@ impl Ipv4Setup {
     }
 
     /// Method 2: `delete_mc_IP_address` — removes a multicast IP address.
-    fn delete_mc_ip_address(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
+    fn delete_mc_ip_address(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
         let addr = match data {
             CosemDataType::DoubleLongUnsigned(v) => *v,
-            _ => return Err("delete_mc_IP_address expects a double-long-unsigned".to_string()),
+            _ => {
+                return Err(AccessError::TypeUnmatched(
+                    "delete_mc_IP_address expects a double-long-unsigned".to_string(),
+                ))
+            }
         };
         let before = self.multicast_ip_address.len();
         self.multicast_ip_address.retain(|a| *a != addr);
         if self.multicast_ip_address.len() == before {
-            return Err("Multicast IP address not found".to_string());
+            return Err(AccessError::ObjectUnavailable("Multicast IP address not found".to_string()));
         }
         Ok(CosemDataType::Null)
     }
@@ -99,6 +105,27 @@ impl Ipv4Setup {
     }
 }
 
+/// Attribute descriptors of the IPv4 setup class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "DL_reference", DataKind::OctetString),
+    AttributeInfo::new(3, "IP_address", DataKind::DoubleLongUnsigned),
+    AttributeInfo::new(4, "multicast_IP_address", DataKind::Array),
+    AttributeInfo::new(5, "IP_options", DataKind::Array),
+    AttributeInfo::new(6, "subnet_mask", DataKind::DoubleLongUnsigned),
+    AttributeInfo::new(7, "gateway_IP_address", DataKind::DoubleLongUnsigned),
+    AttributeInfo::new(8, "use_DHCP_flag", DataKind::Boolean),
+    AttributeInfo::new(9, "primary_DNS_address", DataKind::DoubleLongUnsigned),
+    AttributeInfo::new(10, "secondary_DNS_address", DataKind::DoubleLongUnsigned),
+];
+
+/// Method descriptors of the IPv4 setup class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "add_mc_IP_address", DataKind::DoubleLongUnsigned),
+    MethodInfo::new(2, "delete_mc_IP_address", DataKind::DoubleLongUnsigned),
+    MethodInfo::new(3, "get_nbof_mc_IP_addresses", DataKind::Integer),
+];
+
 impl InterfaceClass for Ipv4Setup {
     fn class_id(&self) -> u16 {
         42
@@ -113,23 +140,25 @@ impl InterfaceClass for Ipv4Setup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::OctetString(self.dl_reference.clone())),
-            (3, CosemDataType::DoubleLongUnsigned(self.ip_address)),
-            (
-                4,
-                CosemDataType::Array(
-                    self.multicast_ip_address.iter().copied().map(CosemDataType::DoubleLongUnsigned).collect(),
-                ),
+        (1..=10).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::OctetString(self.dl_reference.clone()),
+            3 => CosemDataType::DoubleLongUnsigned(self.ip_address),
+            4 => CosemDataType::Array(
+                self.multicast_ip_address.iter().copied().map(CosemDataType::DoubleLongUnsigned).collect(),
             ),
-            (5, CosemDataType::Array(self.ip_options.iter().cloned().map(CosemDataType::from).collect())),
-            (6, CosemDataType::DoubleLongUnsigned(self.subnet_mask)),
-            (7, CosemDataType::DoubleLongUnsigned(self.gateway_ip_address)),
-            (8, CosemDataType::Boolean(self.use_dhcp_flag)),
-            (9, CosemDataType::DoubleLongUnsigned(self.primary_dns_address)),
-            (10, CosemDataType::DoubleLongUnsigned(self.secondary_dns_address)),
-        ]
+            5 => CosemDataType::Array(self.ip_options.iter().cloned().map(CosemDataType::from).collect()),
+            6 => CosemDataType::DoubleLongUnsigned(self.subnet_mask),
+            7 => CosemDataType::DoubleLongUnsigned(self.gateway_ip_address),
+            8 => CosemDataType::Boolean(self.use_dhcp_flag),
+            9 => CosemDataType::DoubleLongUnsigned(self.primary_dns_address),
+            10 => CosemDataType::DoubleLongUnsigned(self.secondary_dns_address),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -140,6 +169,16 @@ impl InterfaceClass for Ipv4Setup {
         ]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "IPv4 setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -198,18 +237,26 @@ impl InterfaceClass for Ipv4Setup {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
-            1 => self.add_mc_ip_address(&params.ok_or("Missing method parameter")?),
-            2 => self.delete_mc_ip_address(&params.ok_or("Missing method parameter")?),
+            1 => self.add_mc_ip_address(
+                &params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?,
+            ),
+            2 => self.delete_mc_ip_address(
+                &params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?,
+            ),
             3 => Ok(self.get_nbof_mc_ip_addresses()),
-            _ => Err(format!("Method {method_id} not supported for IPv4 setup")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for IPv4 setup"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_dlu(value: &CosemDataType) -> Result<u32, BerError> {
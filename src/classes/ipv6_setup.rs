@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::NeighborDiscoverySetup;
 use crate::types::{BerError, CosemDataType};
@@ -64,7 +64,7 @@ impl Ipv6Setup {
     }
 
     /// Method 1: `add_IPv6_address` — adds a unicast IPv6 address.
-    fn add_ipv6_address(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
+    fn add_ipv6_address(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
         match data {
             CosemDataType::OctetString(addr) => {
                 if !self.unicast_ipv6_addresses.contains(addr) {
@@ -72,25 +72,45 @@ impl Ipv6Setup {
                 }
                 Ok(CosemDataType::Null)
             }
-            _ => Err("add_IPv6_address expects an octet-string".to_string()),
+            _ => Err(AccessError::TypeUnmatched("add_IPv6_address expects an octet-string".to_string())),
         }
     }
 
     /// Method 2: `remove_IPv6_address` — removes a unicast IPv6 address.
-    fn remove_ipv6_address(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
+    fn remove_ipv6_address(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
         let addr = match data {
             CosemDataType::OctetString(v) => v.clone(),
-            _ => return Err("remove_IPv6_address expects an octet-string".to_string()),
+            _ => return Err(AccessError::TypeUnmatched("remove_IPv6_address expects an octet-string".to_string())),
         };
         let before = self.unicast_ipv6_addresses.len();
         self.unicast_ipv6_addresses.retain(|a| *a != addr);
         if self.unicast_ipv6_addresses.len() == before {
-            return Err("IPv6 address not found".to_string());
+            return Err(AccessError::ObjectUnavailable("IPv6 address not found".to_string()));
         }
         Ok(CosemDataType::Null)
     }
 }
 
+/// Attribute descriptors of the IPv6 setup class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "DL_reference", DataKind::OctetString),
+    AttributeInfo::new(3, "address_config_mode", DataKind::Enum),
+    AttributeInfo::new(4, "unicast_IPv6_addresses", DataKind::Array),
+    AttributeInfo::new(5, "multicast_IPv6_addresses", DataKind::Array),
+    AttributeInfo::new(6, "gateway_IPv6_addresses", DataKind::Array),
+    AttributeInfo::new(7, "primary_DNS_address", DataKind::OctetString),
+    AttributeInfo::new(8, "secondary_DNS_address", DataKind::OctetString),
+    AttributeInfo::new(9, "traffic_class", DataKind::Unsigned),
+    AttributeInfo::new(10, "neighbor_discovery_setup", DataKind::Array),
+];
+
+/// Method descriptors of the IPv6 setup class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "add_IPv6_address", DataKind::Structure),
+    MethodInfo::new(2, "remove_IPv6_address", DataKind::Structure),
+];
+
 impl InterfaceClass for Ipv6Setup {
     fn class_id(&self) -> u16 {
         48
@@ -105,42 +125,47 @@ impl InterfaceClass for Ipv6Setup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::OctetString(self.dl_reference.clone())),
-            (3, CosemDataType::Enum(self.address_config_mode)),
-            (
-                4,
-                CosemDataType::Array(
-                    self.unicast_ipv6_addresses.iter().cloned().map(CosemDataType::OctetString).collect(),
-                ),
-            ),
-            (
-                5,
-                CosemDataType::Array(
-                    self.multicast_ipv6_addresses.iter().cloned().map(CosemDataType::OctetString).collect(),
-                ),
+        (1..=10).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::OctetString(self.dl_reference.clone()),
+            3 => CosemDataType::Enum(self.address_config_mode),
+            4 => CosemDataType::Array(
+                self.unicast_ipv6_addresses.iter().cloned().map(CosemDataType::OctetString).collect(),
             ),
-            (
-                6,
-                CosemDataType::Array(
-                    self.gateway_ipv6_addresses.iter().cloned().map(CosemDataType::OctetString).collect(),
-                ),
+            5 => CosemDataType::Array(
+                self.multicast_ipv6_addresses.iter().cloned().map(CosemDataType::OctetString).collect(),
             ),
-            (7, CosemDataType::OctetString(self.primary_dns_address.clone())),
-            (8, CosemDataType::OctetString(self.secondary_dns_address.clone())),
-            (9, CosemDataType::Unsigned(self.traffic_class)),
-            (
-                10,
-                CosemDataType::Array(self.neighbor_discovery_setup.iter().cloned().map(CosemDataType::from).collect()),
+            6 => CosemDataType::Array(
+                self.gateway_ipv6_addresses.iter().cloned().map(CosemDataType::OctetString).collect(),
             ),
-        ]
+            7 => CosemDataType::OctetString(self.primary_dns_address.clone()),
+            8 => CosemDataType::OctetString(self.secondary_dns_address.clone()),
+            9 => CosemDataType::Unsigned(self.traffic_class),
+            10 => {
+                CosemDataType::Array(self.neighbor_discovery_setup.iter().cloned().map(CosemDataType::from).collect())
+            }
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "add_IPv6_address".to_string()), (2, "remove_IPv6_address".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "IPv6 setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -199,17 +224,25 @@ impl InterfaceClass for Ipv6Setup {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
-            1 => self.add_ipv6_address(&params.ok_or("Missing method parameter")?),
-            2 => self.remove_ipv6_address(&params.ok_or("Missing method parameter")?),
-            _ => Err(format!("Method {method_id} not supported for IPv6 setup")),
+            1 => self.add_ipv6_address(
+                &params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?,
+            ),
+            2 => self.remove_ipv6_address(
+                &params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?,
+            ),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for IPv6 setup"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_octet_string(value: &CosemDataType) -> Result<Vec<u8>, BerError> {
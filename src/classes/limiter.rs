@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{EmergencyProfile, LimiterAction, ValueDefinition};
 use crate::types::{BerError, CosemDataType};
@@ -69,8 +69,78 @@ impl Limiter {
             actions: config.actions,
         }
     }
+
+    /// The monitored attribute (attr 2).
+    pub fn monitored_value(&self) -> &ValueDefinition {
+        &self.monitored_value
+    }
+
+    /// The threshold the monitored value is compared with (attr 3).
+    pub fn threshold_active(&self) -> &CosemDataType {
+        &self.threshold_active
+    }
+
+    /// The threshold of normal operation (attr 4).
+    pub fn threshold_normal(&self) -> &CosemDataType {
+        &self.threshold_normal
+    }
+
+    /// The threshold while an emergency profile is active (attr 5).
+    pub fn threshold_emergency(&self) -> &CosemDataType {
+        &self.threshold_emergency
+    }
+
+    /// Seconds the value must stay over the threshold before
+    /// `action_over_threshold` runs (attr 6).
+    pub fn min_over_threshold_duration(&self) -> u32 {
+        self.min_over_threshold_duration
+    }
+
+    /// Seconds the value must stay under the threshold before
+    /// `action_under_threshold` runs (attr 7).
+    pub fn min_under_threshold_duration(&self) -> u32 {
+        self.min_under_threshold_duration
+    }
+
+    /// The emergency profile (attr 8).
+    pub fn emergency_profile(&self) -> &EmergencyProfile {
+        &self.emergency_profile
+    }
+
+    /// The emergency profile groups this limiter belongs to (attr 9).
+    pub fn emergency_profile_group_id_list(&self) -> &[u16] {
+        &self.emergency_profile_group_id_list
+    }
+
+    /// Whether an emergency profile is active (attr 10).
+    pub fn emergency_profile_active(&self) -> bool {
+        self.emergency_profile_active
+    }
+
+    /// The over/under-threshold scripts (attr 11).
+    pub fn actions(&self) -> &LimiterAction {
+        &self.actions
+    }
 }
 
+/// Attribute descriptors of the Limiter class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "monitored_value", DataKind::Structure).writable(),
+    AttributeInfo::new(3, "threshold_active", DataKind::Any).dynamic().writable(),
+    AttributeInfo::new(4, "threshold_normal", DataKind::Any).writable(),
+    AttributeInfo::new(5, "threshold_emergency", DataKind::Any).writable(),
+    AttributeInfo::new(6, "min_over_threshold_duration", DataKind::DoubleLongUnsigned).writable(),
+    AttributeInfo::new(7, "min_under_threshold_duration", DataKind::DoubleLongUnsigned).writable(),
+    AttributeInfo::new(8, "emergency_profile", DataKind::Structure).writable(),
+    AttributeInfo::new(9, "emergency_profile_group_id_list", DataKind::Array).writable(),
+    AttributeInfo::new(10, "emergency_profile_active", DataKind::Boolean).dynamic().writable(),
+    AttributeInfo::new(11, "actions", DataKind::Structure).writable(),
+];
+
+/// Method descriptors of the Limiter class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for Limiter {
     fn class_id(&self) -> u16 {
         71
@@ -85,24 +155,26 @@ impl InterfaceClass for Limiter {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::from(self.monitored_value.clone())),
-            (3, self.threshold_active.clone()),
-            (4, self.threshold_normal.clone()),
-            (5, self.threshold_emergency.clone()),
-            (6, CosemDataType::DoubleLongUnsigned(self.min_over_threshold_duration)),
-            (7, CosemDataType::DoubleLongUnsigned(self.min_under_threshold_duration)),
-            (8, CosemDataType::from(self.emergency_profile.clone())),
-            (
-                9,
-                CosemDataType::Array(
-                    self.emergency_profile_group_id_list.iter().map(|id| CosemDataType::LongUnsigned(*id)).collect(),
-                ),
+        (1..=11).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::from(self.monitored_value.clone()),
+            3 => self.threshold_active.clone(),
+            4 => self.threshold_normal.clone(),
+            5 => self.threshold_emergency.clone(),
+            6 => CosemDataType::DoubleLongUnsigned(self.min_over_threshold_duration),
+            7 => CosemDataType::DoubleLongUnsigned(self.min_under_threshold_duration),
+            8 => CosemDataType::from(self.emergency_profile.clone()),
+            9 => CosemDataType::Array(
+                self.emergency_profile_group_id_list.iter().map(|id| CosemDataType::LongUnsigned(*id)).collect(),
             ),
-            (10, CosemDataType::Boolean(self.emergency_profile_active)),
-            (11, CosemDataType::from(self.actions.clone())),
-        ]
+            10 => CosemDataType::Boolean(self.emergency_profile_active),
+            11 => CosemDataType::from(self.actions.clone()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -110,6 +182,16 @@ impl InterfaceClass for Limiter {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Limiter",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -181,10 +263,10 @@ impl InterfaceClass for Limiter {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => {
-                self.monitored_value = ValueDefinition::try_from(&value)?;
+                self.monitored_value = ValueDefinition::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
             3 => {
@@ -204,30 +286,36 @@ impl InterfaceClass for Limiter {
                     self.min_over_threshold_duration = v;
                     Ok(())
                 }
-                _ => Err("min_over_threshold_duration must be double-long-unsigned".into()),
+                _ => Err(AccessError::TypeUnmatched(
+                    "min_over_threshold_duration must be double-long-unsigned".to_string(),
+                )),
             },
             7 => match value {
                 CosemDataType::DoubleLongUnsigned(v) => {
                     self.min_under_threshold_duration = v;
                     Ok(())
                 }
-                _ => Err("min_under_threshold_duration must be double-long-unsigned".into()),
+                _ => Err(AccessError::TypeUnmatched(
+                    "min_under_threshold_duration must be double-long-unsigned".to_string(),
+                )),
             },
             8 => {
-                self.emergency_profile = EmergencyProfile::try_from(&value)?;
+                self.emergency_profile = EmergencyProfile::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
             9 => {
                 let CosemDataType::Array(list) = value else {
-                    return Err("emergency_profile_group_id_list must be array".into());
+                    return Err(AccessError::TypeUnmatched(
+                        "emergency_profile_group_id_list must be array".to_string(),
+                    ));
                 };
                 self.emergency_profile_group_id_list = list
                     .iter()
                     .map(|e| match e {
                         CosemDataType::LongUnsigned(v) => Ok(*v),
-                        _ => Err("group id must be long-unsigned".into()),
+                        _ => Err(AccessError::TypeUnmatched("group id must be long-unsigned".to_string())),
                     })
-                    .collect::<Result<Vec<_>, String>>()?;
+                    .collect::<Result<Vec<_>, _>>()?;
                 Ok(())
             }
             10 => match value {
@@ -235,23 +323,29 @@ impl InterfaceClass for Limiter {
                     self.emergency_profile_active = v;
                     Ok(())
                 }
-                _ => Err("emergency_profile_active must be boolean".into()),
+                _ => Err(AccessError::TypeUnmatched("emergency_profile_active must be boolean".to_string())),
             },
             11 => {
-                self.actions = LimiterAction::try_from(&value)?;
+                self.actions = LimiterAction::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
-            _ => Err(format!("Limiter attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!("Limiter attribute {attribute_id} is not writable"))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for Limiter (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for Limiter (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 use serde::{Deserialize, Serialize};
@@ -54,6 +54,20 @@ impl MbusSlave {
     }
 }
 
+/// Attribute descriptors of the M-Bus slave class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "physical_address", DataKind::LongUnsigned),
+    AttributeInfo::new(3, "logical_address", DataKind::LongUnsigned),
+    AttributeInfo::new(4, "id_number", DataKind::OctetString),
+    AttributeInfo::new(5, "manufacturer", DataKind::OctetString),
+    AttributeInfo::new(6, "version", DataKind::Unsigned),
+    AttributeInfo::new(7, "medium", DataKind::Unsigned),
+];
+
+/// Method descriptors of the M-Bus slave class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for MbusSlave {
     fn class_id(&self) -> u16 {
         76
@@ -68,15 +82,20 @@ impl InterfaceClass for MbusSlave {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::LongUnsigned(self.physical_address)),
-            (3, CosemDataType::LongUnsigned(self.logical_address)),
-            (4, CosemDataType::OctetString(self.id_number.clone())),
-            (5, CosemDataType::OctetString(self.manufacturer.clone())),
-            (6, CosemDataType::Unsigned(self.version)),
-            (7, CosemDataType::Unsigned(self.medium)),
-        ]
+        (1..=7).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::LongUnsigned(self.physical_address),
+            3 => CosemDataType::LongUnsigned(self.logical_address),
+            4 => CosemDataType::OctetString(self.id_number.clone()),
+            5 => CosemDataType::OctetString(self.manufacturer.clone()),
+            6 => CosemDataType::Unsigned(self.version),
+            7 => CosemDataType::Unsigned(self.medium),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -84,6 +103,16 @@ impl InterfaceClass for MbusSlave {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "M-Bus slave",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -132,13 +161,19 @@ impl InterfaceClass for MbusSlave {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for M-Bus slave (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for M-Bus slave (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_long_unsigned(value: &CosemDataType) -> Result<u16, BerError> {
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 use serde::{Deserialize, Serialize};
@@ -45,6 +45,18 @@ impl MbusSlavePortSetup {
     }
 }
 
+/// Attribute descriptors of the M-Bus slave port setup class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "default_baud", DataKind::Enum),
+    AttributeInfo::new(3, "avail_baud", DataKind::Enum),
+    AttributeInfo::new(4, "addr_state", DataKind::Enum).dynamic(),
+    AttributeInfo::new(5, "bus_address", DataKind::Unsigned).dynamic(),
+];
+
+/// Method descriptors of the M-Bus slave port setup class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for MbusSlavePortSetup {
     fn class_id(&self) -> u16 {
         25
@@ -59,13 +71,18 @@ impl InterfaceClass for MbusSlavePortSetup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Enum(self.default_baud)),
-            (3, CosemDataType::Enum(self.avail_baud)),
-            (4, CosemDataType::Enum(self.addr_state)),
-            (5, CosemDataType::Unsigned(self.bus_address)),
-        ]
+        (1..=5).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Enum(self.default_baud),
+            3 => CosemDataType::Enum(self.avail_baud),
+            4 => CosemDataType::Enum(self.addr_state),
+            5 => CosemDataType::Unsigned(self.bus_address),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -73,6 +90,16 @@ impl InterfaceClass for MbusSlavePortSetup {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "M-Bus slave port setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -123,13 +150,19 @@ impl InterfaceClass for MbusSlavePortSetup {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for M-Bus slave port setup (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for M-Bus slave port setup (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 fn take_enum(value: &CosemDataType) -> Result<u8, BerError> {
@@ -21,6 +21,8 @@ pub mod demand_register;
 pub mod disconnect_control;
 /// Extended register (class 4).
 pub mod extended_register;
+/// Generic object of any class id (unknown and manufacturer-specific classes).
+pub mod generic;
 /// GPRS modem setup (class 45).
 pub mod gprs_modem_setup;
 /// GSM diagnostic (class 47).
@@ -45,6 +47,8 @@ pub mod mbus_slave;
 pub mod mbus_slave_port_setup;
 /// Parameter monitor (class 65).
 pub mod parameter_monitor;
+/// Buffer storage of Profile generic objects (in-memory and file-backed rings).
+pub mod profile_buffer;
 /// Profile generic (class 7).
 pub mod profile_generic;
 /// Push setup (class 40).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::ValueDefinition;
 use crate::types::{BerError, CosemDataType};
@@ -57,6 +57,18 @@ impl ParameterMonitor {
     }
 }
 
+/// Attribute descriptors of the Parameter monitor class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "monitored_value", DataKind::Structure),
+    AttributeInfo::new(3, "thresholds", DataKind::Any),
+    AttributeInfo::new(4, "events", DataKind::Array).dynamic(),
+    AttributeInfo::new(5, "minimal_duration", DataKind::DoubleLongUnsigned),
+];
+
+/// Method descriptors of the Parameter monitor class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for ParameterMonitor {
     fn class_id(&self) -> u16 {
         65
@@ -71,13 +83,18 @@ impl InterfaceClass for ParameterMonitor {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, self.monitored_value.clone().into()),
-            (3, self.thresholds.clone()),
-            (4, CosemDataType::Array(self.events.clone())),
-            (5, CosemDataType::DoubleLongUnsigned(self.minimal_duration)),
-        ]
+        (1..=5).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => self.monitored_value.clone().into(),
+            3 => self.thresholds.clone(),
+            4 => CosemDataType::Array(self.events.clone()),
+            5 => CosemDataType::DoubleLongUnsigned(self.minimal_duration),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -85,6 +102,16 @@ impl InterfaceClass for ParameterMonitor {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Parameter monitor",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -137,13 +164,19 @@ impl InterfaceClass for ParameterMonitor {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for Parameter monitor (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for Parameter monitor (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
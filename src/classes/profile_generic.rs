@@ -1,10 +1,13 @@
-use crate::interface::InterfaceClass;
+use super::profile_buffer::{self, BufferStorage, MemoryRing};
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{CaptureObjectDefinition, Choice, SortMethod};
 use crate::types::{BerError, CosemDataType};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::any::Any;
+use std::cmp::Ordering;
 use std::fmt;
+use std::io;
 use std::sync::Arc;
 
 /// Configuration structure used to build a `ProfileGeneric` object.
@@ -27,7 +30,8 @@ pub struct ProfileGenericConfig {
     pub sort_method: SortMethod,
     /// Attribute 6: `capture_object_definition` used as the sort key.
     pub sort_object: Option<CaptureObjectDefinition>,
-    /// Attribute 7: number of entries currently stored in the buffer.
+    /// Attribute 7: number of entries currently stored in the buffer. The
+    /// object counts the entries of `buffer` instead.
     pub entries_in_use: u32,
     /// Attribute 8: maximum number of entries the buffer can hold.
     pub profile_entries: u32,
@@ -48,16 +52,58 @@ impl fmt::Debug for ProfileGenericConfig {
     }
 }
 
+/// The entries of a profile, in storage order. Serialized as the array of
+/// entries; a clone or a deserialized buffer is held in memory.
+struct Buffer(Box<dyn BufferStorage>);
+
+impl Buffer {
+    fn entries(&self) -> io::Result<Vec<Choice>> {
+        profile_buffer::entries(self.0.as_ref())
+    }
+}
+
+impl Clone for Buffer {
+    /// Copies the entries into memory; a storage that cannot be read is
+    /// copied empty.
+    fn clone(&self) -> Self {
+        Buffer(Box::new(MemoryRing::from(self.entries().unwrap_or_default())))
+    }
+}
+
+impl fmt::Debug for Buffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for Buffer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries().map_err(serde::ser::Error::custom)?.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Buffer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Buffer(Box::new(MemoryRing::from(Vec::<Choice>::deserialize(deserializer)?))))
+    }
+}
+
 /// `ProfileGeneric` interface class (class_id = 7, version = 1) for storing data
 /// profiles such as load profiles or event logs, per IEC 62056-6-2 §4.3.6 in the
 /// `spodes-rs` library.
 ///
 /// Supports capturing data from the specified objects and their attributes.
+/// The buffer is ordered by `sort_method`: FIFO in capture order, LIFO newest
+/// first, and the value-sorted methods most significant first by the
+/// `sort_object` column (the first column when it is not set or not
+/// captured). A full buffer drops its least significant entry — the oldest
+/// for FIFO and LIFO. The entries live in a [`BufferStorage`], in memory by
+/// default or in a file with [`ProfileGeneric::with_storage`].
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ProfileGeneric {
     version: u8,
     logical_name: ObisCode,
-    buffer: Vec<Choice>,
+    buffer: Buffer,
     #[serde(skip)]
     capture_objects: Vec<(Arc<dyn InterfaceClass + Send + Sync>, u8)>,
     /// When set via SET, overrides the Arc-based capture_objects for GET.
@@ -68,6 +114,9 @@ pub struct ProfileGeneric {
     sort_object: Option<CaptureObjectDefinition>,
     entries_in_use: u32,
     profile_entries: u32,
+    /// Set by the `capture` method until the capture driver takes it.
+    #[serde(skip)]
+    capture_requested: bool,
 }
 
 impl fmt::Debug for ProfileGeneric {
@@ -94,65 +143,212 @@ impl ProfileGeneric {
     /// # Returns
     /// A new `ProfileGeneric` structure.
     pub fn new(config: ProfileGenericConfig) -> Self {
+        let entries_in_use = config.buffer.len() as u32;
         ProfileGeneric {
             version: config.version,
             logical_name: config.logical_name,
-            buffer: config.buffer,
+            buffer: Buffer(Box::new(MemoryRing::from(config.buffer))),
             capture_objects: config.capture_objects,
             capture_objects_value: None,
             capture_period: config.capture_period,
             sort_method: config.sort_method,
             sort_object: config.sort_object,
-            entries_in_use: config.entries_in_use,
+            entries_in_use,
             profile_entries: config.profile_entries,
+            capture_requested: false,
+        }
+    }
+
+    /// Captures the objects described by `definitions` instead of the
+    /// configured snapshots. Their values are read from the live object set
+    /// by the [`CaptureDriver`](crate::capture::CaptureDriver), so the
+    /// `capture` method only requests a capture.
+    pub fn with_capture_definitions(mut self, definitions: Vec<CaptureObjectDefinition>) -> Self {
+        self.capture_objects_value = Some(CosemDataType::Array(definitions.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// The capture objects (attribute 3), in column order.
+    pub fn capture_definitions(&self) -> Vec<CaptureObjectDefinition> {
+        match self.get_attribute(3) {
+            Some(CosemDataType::Array(list)) => {
+                list.iter().filter_map(|item| CaptureObjectDefinition::try_from(item).ok()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The capturing period in seconds (attribute 4), 0 when captures are
+    /// event-driven.
+    pub fn capture_period(&self) -> u32 {
+        self.capture_period
+    }
+
+    /// The order of the buffer entries (attribute 5).
+    pub fn sort_method(&self) -> SortMethod {
+        self.sort_method
+    }
+
+    /// The buffer capacity in entries (attribute 8), 0 when unbounded.
+    pub fn profile_entries(&self) -> u32 {
+        self.profile_entries
+    }
+
+    /// Takes the request set by the `capture` method, clearing it.
+    pub fn take_capture_request(&mut self) -> bool {
+        std::mem::take(&mut self.capture_requested)
+    }
+
+    /// Keeps the buffer in `storage`, which is taken as it is: a storage
+    /// holding entries (a reopened [`FileRing`](super::profile_buffer::FileRing))
+    /// becomes the buffer, in this profile's order.
+    pub fn with_storage(mut self, storage: Box<dyn BufferStorage>) -> Self {
+        self.entries_in_use = storage.len() as u32;
+        self.buffer = Buffer(storage);
+        self
+    }
+
+    /// The number of entries in the buffer.
+    pub fn len(&self) -> usize {
+        self.buffer.0.len()
+    }
+
+    /// Whether the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.0.is_empty()
+    }
+
+    /// The buffer entry at `index` (0-based, in buffer order).
+    pub fn entry(&self, index: usize) -> Result<Option<Choice>, AccessError> {
+        let storage = self.buffer.0.as_ref();
+        let index = match self.sort_method {
+            SortMethod::Lifo => match storage.len().checked_sub(index + 1) {
+                Some(index) => index,
+                None => return Ok(None),
+            },
+            _ => index,
+        };
+        storage.get(index).map_err(storage_error)
+    }
+
+    /// The buffer entries in `range` (0-based, in buffer order), reading only
+    /// those.
+    pub fn entries(&self, range: std::ops::Range<usize>) -> Result<Vec<Choice>, AccessError> {
+        let end = range.end.min(self.len());
+        (range.start..end).filter_map(|index| self.entry(index).transpose()).collect()
+    }
+
+    /// Appends an entry built by the caller, such as an event journal record,
+    /// at its place in the buffer order. When the buffer is full its least
+    /// significant entry is dropped, which may be the new one.
+    pub fn append(&mut self, entry: Choice) -> Result<(), AccessError> {
+        self.insert(&entry).map_err(storage_error)?;
+        self.entries_in_use = self.len() as u32;
+        Ok(())
+    }
+
+    fn insert(&mut self, entry: &Choice) -> io::Result<()> {
+        let limit = match (self.profile_entries as usize, self.buffer.0.capacity()) {
+            (0, capacity) => capacity,
+            (entries, Some(capacity)) => Some(entries.min(capacity)),
+            (entries, None) => Some(entries),
+        };
+        let full = |storage: &dyn BufferStorage| limit.is_some_and(|limit| storage.len() >= limit);
+        let Some(order) = Significance::of(self.sort_method) else {
+            // FIFO and LIFO keep capture order and drop the oldest entry.
+            let storage = self.buffer.0.as_mut();
+            while full(storage) && !storage.is_empty() {
+                storage.pop_front()?;
+            }
+            return if limit == Some(0) { Ok(()) } else { storage.push_back(entry) };
+        };
+        let column = self.sort_column();
+        let storage = self.buffer.0.as_mut();
+        let key = sort_key(entry, column);
+        // Entries at least as significant as the new one stay before it.
+        let (mut low, mut high) = (0, storage.len());
+        while low < high {
+            let middle = (low + high) / 2;
+            let current = storage.get(middle)?.unwrap_or(CosemDataType::Null);
+            if order.compare(key, sort_key(&current, column)) == Ordering::Greater {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
         }
+        if full(storage) {
+            if low >= storage.len() {
+                return Ok(());
+            }
+            storage.remove(storage.len() - 1)?;
+        }
+        storage.insert(low, entry)
+    }
+
+    /// The column of the entries holding the `sort_object` value.
+    fn sort_column(&self) -> usize {
+        let Some(sort_object) = &self.sort_object else {
+            return 0;
+        };
+        let Some(CosemDataType::Array(definitions)) = self.get_attribute(3) else {
+            return 0;
+        };
+        definitions
+            .iter()
+            .filter_map(|definition| CaptureObjectDefinition::try_from(definition).ok())
+            .position(|definition| {
+                definition.class_id == sort_object.class_id
+                    && definition.logical_name == sort_object.logical_name
+                    && definition.attribute_index == sort_object.attribute_index
+                    && definition.data_index == sort_object.data_index
+            })
+            .unwrap_or(0)
     }
 
     /// Resets the profile buffer, clearing all entries.
     ///
     /// # Returns
     /// * `Ok(CosemDataType::Null)` - If the reset succeeded.
-    /// * `Err(String)` - If an error occurred.
-    fn reset(&mut self) -> CosemDataType {
-        self.buffer.clear();
+    /// * `Err(AccessError)` - If the buffer storage failed.
+    fn reset(&mut self) -> Result<CosemDataType, AccessError> {
+        self.buffer.0.clear().map_err(storage_error)?;
         self.entries_in_use = 0;
-        CosemDataType::Null
+        Ok(CosemDataType::Null)
     }
 
     /// Captures a new entry into the profile buffer, reading the attribute values
     /// from the objects listed in `capture_objects`.
     ///
+    /// When the capture objects are definitions (set with
+    /// [`with_capture_definitions`](Self::with_capture_definitions) or written
+    /// to attribute 3) their values live in the object set, which this class
+    /// does not hold: the capture is only requested and the
+    /// [`CaptureDriver`](crate::capture::CaptureDriver) takes the request.
+    ///
     /// An entry holds only the values of the captured objects (a timestamp, if
-    /// needed, is provided by a `Clock` object included in `capture_objects`). For
-    /// an unsorted profile the buffer behaves as a FIFO: when full, the oldest
-    /// entry is evicted (IEC 62056-6-2, §5.2.1.2.5).
+    /// needed, is provided by a `Clock` object included in `capture_objects`).
+    /// It is placed per `sort_method`; when the buffer is full the least
+    /// significant entry is evicted, the oldest for FIFO and LIFO
+    /// (IEC 62056-6-2, §5.2.1.2.5).
     ///
     /// # Returns
     /// * `Ok(CosemDataType::Null)` - If the capture succeeded.
     /// * `Err(String)` - If a captured object's attribute is not found.
-    fn capture(&mut self) -> Result<CosemDataType, String> {
+    fn capture(&mut self) -> Result<CosemDataType, AccessError> {
+        if self.capture_objects_value.is_some() {
+            self.capture_requested = true;
+            return Ok(CosemDataType::Null);
+        }
         let mut captured_values = Vec::new();
 
         for (obj, attr_id) in &self.capture_objects {
-            let attributes = obj.attributes();
-            if let Some((_, value)) = attributes.iter().find(|(id, _)| *id == *attr_id) {
-                captured_values.push(value.clone());
-            } else {
-                return Err(format!("Attribute {attr_id} not found in object"));
-            }
+            let value = obj
+                .get_attribute(*attr_id)
+                .ok_or_else(|| AccessError::ObjectUnavailable(format!("Attribute {attr_id} not found in object")))?;
+            captured_values.push(value);
         }
 
-        let new_entry = CosemDataType::Structure(captured_values);
-
-        // When the buffer is full, evict the oldest entry (FIFO).
-        if self.profile_entries > 0 && self.entries_in_use >= self.profile_entries && !self.buffer.is_empty() {
-            self.buffer.remove(0);
-            self.entries_in_use -= 1;
-        }
-
-        self.buffer.push(new_entry);
-        self.entries_in_use += 1;
-
+        self.append(CosemDataType::Structure(captured_values))?;
         Ok(CosemDataType::Null)
     }
 
@@ -162,8 +358,8 @@ impl ProfileGeneric {
     /// Full range filtering requires evaluating the sort object of each entry;
     /// that ordering logic is not modelled here, so this returns the whole
     /// buffer as a best effort.
-    fn get_buffer_by_range(&self, _params: Option<CosemDataType>) -> CosemDataType {
-        CosemDataType::Array(self.buffer.clone())
+    fn get_buffer_by_range(&self, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Ok(CosemDataType::Array(self.buffer_entries()?))
     }
 
     /// Method 4 (version 0 only): `get_buffer_by_index` — returns the buffer
@@ -173,26 +369,117 @@ impl ProfileGeneric {
     /// The parameter is `structure { from_entry: double-long-unsigned,
     /// to_entry: double-long-unsigned, .. }`; when it is absent, the whole
     /// buffer is returned.
-    fn get_buffer_by_index(&self, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn get_buffer_by_index(&self, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         let (from_entry, to_entry) = match params {
             Some(CosemDataType::Structure(fields)) if fields.len() >= 2 => {
-                let from = as_u32(&fields[0]).ok_or("from_entry must be an unsigned integer")?;
-                let to = as_u32(&fields[1]).ok_or("to_entry must be an unsigned integer")?;
+                let from = as_u32(&fields[0])
+                    .ok_or_else(|| AccessError::TypeUnmatched("from_entry must be an unsigned integer".to_string()))?;
+                let to = as_u32(&fields[1])
+                    .ok_or_else(|| AccessError::TypeUnmatched("to_entry must be an unsigned integer".to_string()))?;
                 (from, to)
             }
-            None => return Ok(CosemDataType::Array(self.buffer.clone())),
-            _ => return Err("Expected structure { from_entry, to_entry, .. }".to_string()),
+            None => return Ok(CosemDataType::Array(self.buffer_entries()?)),
+            _ => return Err(AccessError::TypeUnmatched("Expected structure { from_entry, to_entry, .. }".to_string())),
         };
         if from_entry == 0 || to_entry < from_entry {
-            return Err("Invalid entry range (1-based, from ≤ to)".to_string());
+            return Err(AccessError::ScopeOfAccessViolated("Invalid entry range (1-based, from ≤ to)".to_string()));
+        }
+        Ok(CosemDataType::Array(self.entries((from_entry - 1) as usize..to_entry as usize)?))
+    }
+
+    /// Every buffer entry, in buffer order.
+    fn buffer_entries(&self) -> Result<Vec<Choice>, AccessError> {
+        let mut entries = self.buffer.entries().map_err(storage_error)?;
+        if self.sort_method == SortMethod::Lifo {
+            entries.reverse();
+        }
+        Ok(entries)
+    }
+
+    /// Replaces the buffer with `entries`, given in buffer order.
+    fn replace_buffer(&mut self, entries: &[Choice]) -> io::Result<()> {
+        let storage = self.buffer.0.as_mut();
+        storage.clear()?;
+        let mut ordered: Vec<&Choice> = entries.iter().collect();
+        if self.sort_method == SortMethod::Lifo {
+            ordered.reverse();
         }
-        let start = (from_entry - 1) as usize;
-        let end = (to_entry as usize).min(self.buffer.len());
-        let slice = if start < end { self.buffer[start..end].to_vec() } else { Vec::new() };
-        Ok(CosemDataType::Array(slice))
+        for entry in ordered {
+            storage.push_back(entry)?;
+        }
+        self.entries_in_use = storage.len() as u32;
+        Ok(())
     }
 }
 
+/// How the value-sorted methods rank entries.
+#[derive(Clone, Copy)]
+enum Significance {
+    Largest,
+    Smallest,
+    NearestToZero,
+    FarthestFromZero,
+}
+
+/// The value an entry is sorted by.
+#[derive(Clone, Copy)]
+enum SortKey<'a> {
+    Integer(i64),
+    Octets(&'a [u8]),
+    None,
+}
+
+impl Significance {
+    /// The ranking of a value-sorted method; `None` for FIFO and LIFO.
+    fn of(sort_method: SortMethod) -> Option<Self> {
+        match sort_method {
+            SortMethod::Fifo | SortMethod::Lifo => None,
+            SortMethod::Largest => Some(Significance::Largest),
+            SortMethod::Smallest => Some(Significance::Smallest),
+            SortMethod::NearestToZero => Some(Significance::NearestToZero),
+            SortMethod::FarthestFromZero => Some(Significance::FarthestFromZero),
+        }
+    }
+
+    /// `Greater` when `a` is more significant than `b`. Octet strings
+    /// (date-times) compare by content; other values are equally significant.
+    fn compare(self, a: SortKey<'_>, b: SortKey<'_>) -> Ordering {
+        let ordering = match (self, a, b) {
+            (Significance::NearestToZero, SortKey::Integer(a), SortKey::Integer(b)) => {
+                return b.unsigned_abs().cmp(&a.unsigned_abs());
+            }
+            (Significance::FarthestFromZero, SortKey::Integer(a), SortKey::Integer(b)) => {
+                return a.unsigned_abs().cmp(&b.unsigned_abs());
+            }
+            (_, SortKey::Integer(a), SortKey::Integer(b)) => a.cmp(&b),
+            (_, SortKey::Octets(a), SortKey::Octets(b)) => a.cmp(b),
+            _ => return Ordering::Equal,
+        };
+        match self {
+            Significance::Smallest => ordering.reverse(),
+            _ => ordering,
+        }
+    }
+}
+
+/// The value of `entry` in `column`.
+fn sort_key(entry: &Choice, column: usize) -> SortKey<'_> {
+    let value = match entry {
+        CosemDataType::Structure(fields) => fields.get(column),
+        value => Some(value),
+    };
+    match value {
+        Some(CosemDataType::OctetString(octets) | CosemDataType::DateTime(octets)) => SortKey::Octets(octets),
+        Some(value) => value.as_i64().map_or(SortKey::None, SortKey::Integer),
+        None => SortKey::None,
+    }
+}
+
+/// Reports a buffer storage failure.
+fn storage_error(e: io::Error) -> AccessError {
+    AccessError::HardwareFault(format!("Profile buffer storage: {e}"))
+}
+
 /// Reads a non-negative COSEM integer as `u32` (used for entry indices).
 fn as_u32(value: &CosemDataType) -> Option<u32> {
     match value {
@@ -203,6 +490,26 @@ fn as_u32(value: &CosemDataType) -> Option<u32> {
     }
 }
 
+/// Attribute descriptors of the Profile generic class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "buffer", DataKind::Array).dynamic(),
+    AttributeInfo::new(3, "capture_objects", DataKind::Array).writable(),
+    AttributeInfo::new(4, "capture_period", DataKind::DoubleLongUnsigned).writable(),
+    AttributeInfo::new(5, "sort_method", DataKind::Unsigned).writable(),
+    AttributeInfo::new(6, "sort_object", DataKind::Any),
+    AttributeInfo::new(7, "entries_in_use", DataKind::DoubleLongUnsigned).dynamic(),
+    AttributeInfo::new(8, "profile_entries", DataKind::DoubleLongUnsigned).writable(),
+];
+
+/// Method descriptors of the Profile generic class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "reset", DataKind::Integer),
+    MethodInfo::new(2, "capture", DataKind::Integer),
+    MethodInfo::new(3, "get_buffer_by_range", DataKind::Structure),
+    MethodInfo::new(4, "get_buffer_by_index", DataKind::Structure),
+];
+
 impl InterfaceClass for ProfileGeneric {
     fn class_id(&self) -> u16 {
         7
@@ -221,35 +528,47 @@ impl InterfaceClass for ProfileGeneric {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        let capture_objects = self.capture_objects_value.clone().unwrap_or_else(|| {
-            CosemDataType::Array(
-                self.capture_objects
-                    .iter()
-                    .map(|(obj, attr_id)| {
-                        // Attribute ids are always <128 in practice (i8-valued on the wire).
-                        #[allow(clippy::cast_possible_wrap)]
-                        let attr_id = *attr_id as i8;
-                        CosemDataType::Structure(vec![
-                            CosemDataType::LongUnsigned(obj.class_id()),
-                            CosemDataType::OctetString(obj.logical_name().to_bytes()),
-                            CosemDataType::Integer(attr_id), // attribute_index (integer)
-                            CosemDataType::LongUnsigned(0),  // data_index (long-unsigned, default 0)
-                        ])
-                    })
-                    .collect(),
-            )
-        });
-
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.buffer.clone())),
-            (3, capture_objects),
-            (4, CosemDataType::DoubleLongUnsigned(self.capture_period)),
-            (5, CosemDataType::Unsigned(self.sort_method as u8)),
-            (6, self.sort_object.as_ref().map(|c| c.clone().into()).unwrap_or(CosemDataType::Null)),
-            (7, CosemDataType::DoubleLongUnsigned(self.entries_in_use)),
-            (8, CosemDataType::DoubleLongUnsigned(self.profile_entries)),
-        ]
+        (1..=8).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            // An unreadable storage leaves the buffer unavailable; read it
+            // with `entries` to get the storage error.
+            2 => CosemDataType::Array(self.buffer_entries().ok()?),
+            3 => self.capture_objects_value.clone().unwrap_or_else(|| {
+                CosemDataType::Array(
+                    self.capture_objects
+                        .iter()
+                        .map(|(obj, attr_id)| {
+                            // Attribute ids are always <128 in practice (i8-valued on the wire).
+                            #[allow(clippy::cast_possible_wrap)]
+                            let attr_id = *attr_id as i8;
+                            CosemDataType::Structure(vec![
+                                CosemDataType::LongUnsigned(obj.class_id()),
+                                CosemDataType::OctetString(obj.logical_name().to_bytes()),
+                                CosemDataType::Integer(attr_id), // attribute_index (integer)
+                                CosemDataType::LongUnsigned(0),  // data_index (long-unsigned, default 0)
+                            ])
+                        })
+                        .collect(),
+                )
+            }),
+            4 => CosemDataType::DoubleLongUnsigned(self.capture_period),
+            5 => CosemDataType::Unsigned(self.sort_method as u8),
+            6 => self.sort_object.as_ref().map(|c| c.clone().into()).unwrap_or(CosemDataType::Null),
+            7 => CosemDataType::DoubleLongUnsigned(self.entries_in_use),
+            8 => CosemDataType::DoubleLongUnsigned(self.profile_entries),
+            _ => return None,
+        })
+    }
+
+    fn attribute_ref(&self, attribute_id: u8) -> Option<&CosemDataType> {
+        match attribute_id {
+            3 => self.capture_objects_value.as_ref(),
+            _ => None,
+        }
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -266,6 +585,18 @@ impl InterfaceClass for ProfileGeneric {
         }
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        // Version 1 reserves the buffer-reading methods 3 and 4.
+        let methods = if self.version == 0 { METHODS } else { &METHODS[..2] };
+        Some(ClassInfo {
+            name: "Profile generic",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -301,11 +632,9 @@ impl InterfaceClass for ProfileGeneric {
                 } else {
                     return Err(BerError::InvalidTag);
                 }
-                if let CosemDataType::Array(buffer) = &seq[2] {
-                    self.buffer.clone_from(buffer);
-                } else {
+                let CosemDataType::Array(buffer) = &seq[2] else {
                     return Err(BerError::InvalidTag);
-                }
+                };
                 if let CosemDataType::Array(_) = &seq[3] {
                     // capture_objects are deserialized separately, if needed
                 } else {
@@ -322,31 +651,30 @@ impl InterfaceClass for ProfileGeneric {
                     return Err(BerError::InvalidTag);
                 }
                 self.sort_object = CaptureObjectDefinition::try_from(&seq[6]).ok();
-                if let CosemDataType::DoubleLongUnsigned(entries_in_use) = seq[7] {
-                    self.entries_in_use = entries_in_use;
-                } else {
+                let CosemDataType::DoubleLongUnsigned(_entries_in_use) = seq[7] else {
                     return Err(BerError::InvalidTag);
-                }
+                };
                 if let CosemDataType::DoubleLongUnsigned(profile_entries) = seq[8] {
                     self.profile_entries = profile_entries;
                 } else {
                     return Err(BerError::InvalidTag);
                 }
-                return Ok(());
+                // entries_in_use follows the restored buffer.
+                return self.replace_buffer(buffer).map_err(|_| BerError::InvalidValue);
             }
             return Err(BerError::InvalidLength);
         }
         Err(BerError::InvalidTag)
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             3 => {
                 let CosemDataType::Array(ref list) = value else {
-                    return Err("capture_objects must be array".into());
+                    return Err(AccessError::TypeUnmatched("capture_objects must be array".to_string()));
                 };
                 for item in list {
-                    let _ = CaptureObjectDefinition::try_from(item)?;
+                    let _ = CaptureObjectDefinition::try_from(item).map_err(AccessError::TypeUnmatched)?;
                 }
                 self.capture_objects_value = Some(value);
                 Ok(())
@@ -356,40 +684,53 @@ impl InterfaceClass for ProfileGeneric {
                     self.capture_period = v;
                     Ok(())
                 }
-                _ => Err("capture_period must be double-long-unsigned".into()),
+                _ => Err(AccessError::TypeUnmatched("capture_period must be double-long-unsigned".to_string())),
             },
             5 => match value {
                 CosemDataType::Unsigned(v) => {
-                    self.sort_method = SortMethod::from_u8(v).unwrap_or(SortMethod::Fifo);
+                    let sort_method = SortMethod::from_u8(v)
+                        .ok_or_else(|| AccessError::ScopeOfAccessViolated(format!("Unknown sort_method {v}")))?;
+                    if sort_method != self.sort_method {
+                        // The entries are ordered by the old method.
+                        self.reset()?;
+                        self.sort_method = sort_method;
+                    }
                     Ok(())
                 }
-                _ => Err("sort_method must be unsigned".into()),
+                _ => Err(AccessError::TypeUnmatched("sort_method must be unsigned".to_string())),
             },
             8 => match value {
                 CosemDataType::DoubleLongUnsigned(v) => {
                     self.profile_entries = v;
                     Ok(())
                 }
-                _ => Err("profile_entries must be double-long-unsigned".into()),
+                _ => Err(AccessError::TypeUnmatched("profile_entries must be double-long-unsigned".to_string())),
             },
-            _ => Err(format!("ProfileGeneric attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!("ProfileGeneric attribute {attribute_id} is not writable"))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
-            1 => Ok(self.reset()),
+            1 => self.reset(),
             2 => self.capture(),
             // Methods 3 and 4 exist only in version 0.
-            3 if self.version == 0 => Ok(self.get_buffer_by_range(params)),
+            3 if self.version == 0 => self.get_buffer_by_range(params),
             4 if self.version == 0 => self.get_buffer_by_index(params),
-            _ => Err(format!("Method {} not supported for ProfileGeneric version {}", method_id, self.version)),
+            _ => Err(AccessError::ObjectUnavailable(format!(
+                "Method {} not supported for ProfileGeneric version {}",
+                method_id, self.version
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -479,4 +820,125 @@ mod tests {
             panic!("get_buffer_by_index must return an array");
         }
     }
+
+    #[test]
+    fn single_attribute_get_agrees_with_attributes() {
+        let mut p = empty_profile(100);
+        for _ in 0..50 {
+            p.invoke_method(2, None).unwrap();
+        }
+        for (id, value) in p.attributes() {
+            assert_eq!(p.get_attribute(id), Some(value));
+        }
+        assert_eq!(p.get_attribute(4), Some(CosemDataType::DoubleLongUnsigned(0)));
+        assert_eq!(p.get_attribute(9), None);
+        assert_eq!(p.attribute_ref(2), None);
+    }
+
+    fn sorted_profile(sort_method: SortMethod, profile_entries: u32) -> ProfileGeneric {
+        let mut p = empty_profile(profile_entries);
+        p.sort_method = sort_method;
+        // Entries are {clock, value}; sorted by the value column.
+        let definition =
+            |class_id, logical_name| CosemDataType::from(CaptureObjectDefinition::new(class_id, logical_name, 2, 0));
+        let value = ObisCode::new(1, 0, 1, 7, 0, 255);
+        p.set_attribute(
+            3,
+            CosemDataType::Array(vec![definition(8, ObisCode::new(0, 0, 1, 0, 0, 255)), definition(3, value)]),
+        )
+        .unwrap();
+        p.sort_object = Some(CaptureObjectDefinition::new(3, value, 2, 0));
+        p
+    }
+
+    fn row(time: u8, value: i32) -> CosemDataType {
+        CosemDataType::Structure(vec![CosemDataType::OctetString(vec![time]), CosemDataType::DoubleLong(value)])
+    }
+
+    /// The value column of the buffer, in buffer order.
+    fn values(p: &ProfileGeneric) -> Vec<i64> {
+        let Some(CosemDataType::Array(rows)) = p.get_attribute(2) else { panic!("buffer must be an array") };
+        rows.iter()
+            .map(|row| match row {
+                CosemDataType::Structure(fields) => fields[1].as_i64().unwrap(),
+                other => panic!("unexpected row {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sort_methods_order_and_evict() {
+        let captures = [5, -20, 3, 40, -1, 7];
+        let expected: [(SortMethod, &[i64]); 6] = [
+            (SortMethod::Fifo, &[3, 40, -1, 7]),
+            (SortMethod::Lifo, &[7, -1, 40, 3]),
+            (SortMethod::Largest, &[40, 7, 5, 3]),
+            (SortMethod::Smallest, &[-20, -1, 3, 5]),
+            (SortMethod::NearestToZero, &[-1, 3, 5, 7]),
+            (SortMethod::FarthestFromZero, &[40, -20, 7, 5]),
+        ];
+        for (sort_method, expected) in expected {
+            let mut p = sorted_profile(sort_method, 4);
+            for (time, value) in captures.into_iter().enumerate() {
+                p.append(row(time as u8, value)).unwrap();
+            }
+            assert_eq!(values(&p), expected, "{sort_method:?}");
+            assert_eq!(p.get_attribute(7), Some(CosemDataType::DoubleLongUnsigned(4)));
+            assert_eq!(p.entry(4).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn equal_values_keep_capture_order_and_method_changes_reset() {
+        let mut p = sorted_profile(SortMethod::Largest, 3);
+        for (time, value) in [(1, 10), (2, 10), (3, 20), (4, 10)] {
+            p.append(row(time, value)).unwrap();
+        }
+        // The later 10 is the least significant: it is dropped.
+        assert_eq!(p.entries(0..3).unwrap(), vec![row(3, 20), row(1, 10), row(2, 10)]);
+
+        p.set_attribute(5, CosemDataType::Unsigned(SortMethod::Largest as u8)).unwrap();
+        assert_eq!(p.len(), 3);
+        // An unknown method is refused before the buffer is touched.
+        assert!(matches!(p.set_attribute(5, CosemDataType::Unsigned(7)), Err(AccessError::ScopeOfAccessViolated(_))));
+        assert_eq!((p.len(), p.sort_method), (3, SortMethod::Largest));
+        p.set_attribute(5, CosemDataType::Unsigned(SortMethod::Lifo as u8)).unwrap();
+        assert!(p.is_empty());
+    }
+
+    #[test]
+    fn file_storage_holds_a_year_of_half_hour_profile() {
+        let path = std::env::temp_dir().join(format!("spodes-profile-year-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let year = 365 * 48;
+        let ring = super::profile_buffer::FileRing::open(&path, year, 32).unwrap();
+        let mut p = versioned_profile(0, year).with_storage(Box::new(ring));
+        for n in 0..year + 10 {
+            let clock = CosemDataType::OctetString(n.to_be_bytes().to_vec());
+            p.append(CosemDataType::Structure(vec![clock, CosemDataType::DoubleLongUnsigned(n)])).unwrap();
+        }
+        assert_eq!(p.len(), year as usize);
+        let first = p.entry(0).unwrap().unwrap();
+        assert_eq!(
+            first,
+            CosemDataType::Structure(vec![
+                CosemDataType::OctetString(10u32.to_be_bytes().to_vec()),
+                CosemDataType::DoubleLongUnsigned(10),
+            ])
+        );
+        let params = CosemDataType::Structure(vec![
+            CosemDataType::DoubleLongUnsigned(year - 1),
+            CosemDataType::DoubleLongUnsigned(year + 5),
+        ]);
+        let Ok(CosemDataType::Array(last)) = p.invoke_method(4, Some(params)) else { panic!("expected entries") };
+        assert_eq!(last.len(), 2);
+
+        // Reopened, the ring is the same buffer.
+        drop(p);
+        let ring = super::profile_buffer::FileRing::open(&path, year, 32).unwrap();
+        let p = versioned_profile(0, year).with_storage(Box::new(ring));
+        assert_eq!(p.get_attribute(7), Some(CosemDataType::DoubleLongUnsigned(year)));
+        assert_eq!(p.entry(0).unwrap(), Some(first));
+        std::fs::remove_file(path).unwrap();
+    }
 }
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{
     CaptureObjectDefinition, CommunicationWindow, ConfirmationParameters, DateTime, PushProtectionParameter,
@@ -66,9 +66,10 @@ pub struct PushSetupConfig {
 /// * version 2 — attributes 1..13, methods `push` and `reset`.
 ///
 /// In versions 0 and 1 attribute 7 (`repetition_delay`) is a `long-unsigned`;
-/// in version 2 it is a structure. The `push` ACTION only validates that the
-/// object is triggerable; assembling and sending the actual DataNotification
-/// is done by [`RequestDispatcher::build_push_delivery_request`](crate::server::RequestDispatcher::build_push_delivery_request),
+/// in version 2 it is a structure. The `push` ACTION only marks the object as
+/// triggered; assembling and sending the actual DataNotification is done by
+/// [`PushService`](crate::push::PushService) (or
+/// [`RequestDispatcher::build_push_delivery_request`](crate::server::RequestDispatcher::build_push_delivery_request)),
 /// which has access to the live object registry that this class does not
 /// hold.
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -87,6 +88,9 @@ pub struct PushSetup {
     push_operation_method: u8,
     confirmation_parameters: ConfirmationParameters,
     last_confirmation_date_time: DateTime,
+    /// Set by the `push` method until the delivery runtime takes it.
+    #[serde(skip)]
+    triggered: bool,
 }
 
 impl PushSetup {
@@ -107,17 +111,18 @@ impl PushSetup {
             push_operation_method: config.push_operation_method,
             confirmation_parameters: config.confirmation_parameters,
             last_confirmation_date_time: config.last_confirmation_date_time,
+            triggered: false,
         }
     }
 
     /// Method 1: `push` — triggers sending the push object list to the
     /// destination (IEC 62056-6-2 §4.4.8.3.1). Building the notification body
     /// requires reading the live values of the referenced objects, which this
-    /// class does not hold; use
-    /// [`RequestDispatcher::build_push_delivery_request`](crate::server::RequestDispatcher::build_push_delivery_request)
-    /// against the object registry to actually assemble and send the push. The
-    /// ACTION itself only validates that the object is triggerable.
-    fn push() -> CosemDataType {
+    /// class does not hold: the ACTION marks the object as triggered and
+    /// [`PushService::poll`](crate::push::PushService::poll) takes the trigger
+    /// and delivers the push.
+    fn push(&mut self) -> CosemDataType {
+        self.triggered = true;
         CosemDataType::Null
     }
 
@@ -143,8 +148,81 @@ impl PushSetup {
     pub fn push_client_sap(&self) -> i8 {
         self.push_client_sap
     }
+
+    /// Returns the communication windows (attribute 4); empty means always.
+    pub fn communication_window(&self) -> &[CommunicationWindow] {
+        &self.communication_window
+    }
+
+    /// Returns the randomisation start interval in seconds (attribute 5).
+    pub fn randomisation_start_interval(&self) -> u16 {
+        self.randomisation_start_interval
+    }
+
+    /// Returns the number of retries (attribute 6).
+    pub fn number_of_retries(&self) -> u8 {
+        self.number_of_retries
+    }
+
+    /// Returns the repetition delay (attribute 7): a `long-unsigned` in
+    /// seconds, or in version 2 the structure `{repetition_delay_min,
+    /// repetition_delay_exponent, repetition_delay_max}`.
+    pub fn repetition_delay(&self) -> &CosemDataType {
+        &self.repetition_delay
+    }
+
+    /// Returns the push protection parameters (attribute 10).
+    pub fn push_protection_parameters(&self) -> &[PushProtectionParameter] {
+        &self.push_protection_parameters
+    }
+
+    /// Returns the push operation method (attribute 11, 0 before version 2).
+    pub fn push_operation_method(&self) -> u8 {
+        self.push_operation_method
+    }
+
+    /// Returns the confirmation parameters (attribute 12).
+    pub fn confirmation_parameters(&self) -> &ConfirmationParameters {
+        &self.confirmation_parameters
+    }
+
+    /// Returns the date-time of the last confirmed push (attribute 13).
+    pub fn last_confirmation_date_time(&self) -> &DateTime {
+        &self.last_confirmation_date_time
+    }
+
+    /// Records the date-time of a confirmed push (attribute 13).
+    pub fn record_confirmation(&mut self, date_time: DateTime) {
+        self.last_confirmation_date_time = date_time;
+    }
+
+    /// Takes the trigger set by the `push` method, clearing it.
+    pub fn take_trigger(&mut self) -> bool {
+        std::mem::take(&mut self.triggered)
+    }
 }
 
+/// Attribute descriptors of the Push setup class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "push_object_list", DataKind::Array).writable(),
+    AttributeInfo::new(3, "send_destination_and_method", DataKind::Structure).writable(),
+    AttributeInfo::new(4, "communication_window", DataKind::Array).writable(),
+    AttributeInfo::new(5, "randomisation_start_interval", DataKind::LongUnsigned).writable(),
+    AttributeInfo::new(6, "number_of_retries", DataKind::Unsigned).writable(),
+    AttributeInfo::new(7, "repetition_delay", DataKind::Any).writable(),
+    AttributeInfo::new(8, "port_reference", DataKind::OctetString).writable(),
+    AttributeInfo::new(9, "push_client_SAP", DataKind::Integer),
+    AttributeInfo::new(10, "push_protection_parameters", DataKind::Array),
+    AttributeInfo::new(11, "push_operation_method", DataKind::Enum),
+    AttributeInfo::new(12, "confirmation_parameters", DataKind::Structure),
+    AttributeInfo::new(13, "last_confirmation_date_time", DataKind::DateTime).dynamic(),
+];
+
+/// Method descriptors of the Push setup class.
+const METHODS: &[MethodInfo] =
+    &[MethodInfo::new(1, "push", DataKind::Integer), MethodInfo::new(2, "reset", DataKind::Integer)];
+
 impl InterfaceClass for PushSetup {
     fn class_id(&self) -> u16 {
         40
@@ -159,39 +237,40 @@ impl InterfaceClass for PushSetup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        // Attributes 1..7 are common to all versions.
-        let mut attrs = vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.push_object_list.iter().map(|o| CosemDataType::from(o.clone())).collect())),
-            (3, CosemDataType::from(self.send_destination_and_method.clone())),
-            (
-                4,
-                CosemDataType::Array(
-                    self.communication_window.iter().map(|cw| CosemDataType::from(cw.clone())).collect(),
-                ),
+        (1..=13).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            // Attributes 1..7 are common to all versions.
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.push_object_list.iter().map(|o| CosemDataType::from(o.clone())).collect()),
+            3 => CosemDataType::from(self.send_destination_and_method.clone()),
+            4 => CosemDataType::Array(
+                self.communication_window.iter().map(|cw| CosemDataType::from(cw.clone())).collect(),
             ),
-            (5, CosemDataType::LongUnsigned(self.randomisation_start_interval)),
-            (6, CosemDataType::Unsigned(self.number_of_retries)),
-            (7, self.repetition_delay.clone()),
-        ];
-        // Attributes 8..10 were added in version 1.
-        if self.version >= 1 {
-            attrs.push((8, CosemDataType::OctetString(self.port_reference.clone())));
-            attrs.push((9, CosemDataType::Integer(self.push_client_sap)));
-            attrs.push((
-                10,
-                CosemDataType::Array(
-                    self.push_protection_parameters.iter().map(|p| CosemDataType::from(p.clone())).collect(),
-                ),
-            ));
-        }
-        // Attributes 11..13 were added in version 2.
-        if self.version >= 2 {
-            attrs.push((11, CosemDataType::Enum(self.push_operation_method)));
-            attrs.push((12, CosemDataType::from(self.confirmation_parameters.clone())));
-            attrs.push((13, CosemDataType::from(self.last_confirmation_date_time.clone())));
+            5 => CosemDataType::LongUnsigned(self.randomisation_start_interval),
+            6 => CosemDataType::Unsigned(self.number_of_retries),
+            7 => self.repetition_delay.clone(),
+            // Attributes 8..10 were added in version 1.
+            8 if self.version >= 1 => CosemDataType::OctetString(self.port_reference.clone()),
+            9 if self.version >= 1 => CosemDataType::Integer(self.push_client_sap),
+            10 if self.version >= 1 => CosemDataType::Array(
+                self.push_protection_parameters.iter().map(|p| CosemDataType::from(p.clone())).collect(),
+            ),
+            // Attributes 11..13 were added in version 2.
+            11 if self.version >= 2 => CosemDataType::Enum(self.push_operation_method),
+            12 if self.version >= 2 => CosemDataType::from(self.confirmation_parameters.clone()),
+            13 if self.version >= 2 => CosemDataType::from(self.last_confirmation_date_time.clone()),
+            _ => return None,
+        })
+    }
+
+    fn attribute_ref(&self, attribute_id: u8) -> Option<&CosemDataType> {
+        match attribute_id {
+            7 => Some(&self.repetition_delay),
+            _ => None,
         }
-        attrs
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -203,6 +282,22 @@ impl InterfaceClass for PushSetup {
         }
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        let attributes = match self.version {
+            0 => 7,
+            1 => 10,
+            _ => 13,
+        };
+        let methods = if self.version >= 2 { METHODS } else { &METHODS[..1] };
+        Some(ClassInfo {
+            name: "Push setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: &ATTRIBUTES[..attributes],
+            methods,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -304,41 +399,48 @@ impl InterfaceClass for PushSetup {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => match value {
                 CosemDataType::Array(list) => {
-                    self.push_object_list =
-                        list.iter().map(CaptureObjectDefinition::try_from).collect::<Result<Vec<_>, _>>()?;
+                    self.push_object_list = list
+                        .iter()
+                        .map(CaptureObjectDefinition::try_from)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(AccessError::TypeUnmatched)?;
                     Ok(())
                 }
-                _ => Err("push_object_list must be array".to_string()),
+                _ => Err(AccessError::TypeUnmatched("push_object_list must be array".to_string())),
             },
             3 => {
-                self.send_destination_and_method = SendDestinationAndMethod::try_from(&value)?;
+                self.send_destination_and_method =
+                    SendDestinationAndMethod::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
             4 => match value {
                 CosemDataType::Array(list) => {
-                    self.communication_window =
-                        list.iter().map(CommunicationWindow::try_from).collect::<Result<Vec<_>, _>>()?;
+                    self.communication_window = list
+                        .iter()
+                        .map(CommunicationWindow::try_from)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(AccessError::TypeUnmatched)?;
                     Ok(())
                 }
-                _ => Err("communication_window must be array".to_string()),
+                _ => Err(AccessError::TypeUnmatched("communication_window must be array".to_string())),
             },
             5 => match value {
                 CosemDataType::LongUnsigned(v) => {
                     self.randomisation_start_interval = v;
                     Ok(())
                 }
-                _ => Err("randomisation_start_interval must be long-unsigned".to_string()),
+                _ => Err(AccessError::TypeUnmatched("randomisation_start_interval must be long-unsigned".to_string())),
             },
             6 => match value {
                 CosemDataType::Unsigned(v) => {
                     self.number_of_retries = v;
                     Ok(())
                 }
-                _ => Err("number_of_retries must be unsigned".to_string()),
+                _ => Err(AccessError::TypeUnmatched("number_of_retries must be unsigned".to_string())),
             },
             7 => {
                 self.repetition_delay = value;
@@ -349,23 +451,30 @@ impl InterfaceClass for PushSetup {
                     self.port_reference = v;
                     Ok(())
                 }
-                _ => Err("port_reference must be octet-string".to_string()),
+                _ => Err(AccessError::TypeUnmatched("port_reference must be octet-string".to_string())),
             },
-            _ => Err(format!("Attribute {} not writable for PushSetup", attribute_id)),
+            _ => Err(AccessError::ReadWriteDenied(format!("Attribute {} not writable for PushSetup", attribute_id))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
-            1 => Ok(Self::push()),
+            1 => Ok(self.push()),
             2 if self.version >= 2 => Ok(self.reset()),
-            _ => Err(format!("Method {} not supported for Push setup version {}", method_id, self.version)),
+            _ => Err(AccessError::ObjectUnavailable(format!(
+                "Method {} not supported for Push setup version {}",
+                method_id, self.version
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -425,6 +534,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn single_attribute_get_follows_version() {
+        let v0 = sample_versioned(0);
+        assert!(v0.get_attribute(7).is_some());
+        assert_eq!(v0.get_attribute(8), None);
+        assert_eq!(v0.attribute_ref(7), Some(&v0.get_attribute(7).unwrap()));
+        let v2 = sample_versioned(2);
+        assert_eq!(v2.get_attribute(13), Some(v2.attributes()[12].1.clone()));
+        assert_eq!(v2.get_attribute(14), None);
+    }
+
     #[test]
     fn round_trip_all_versions() {
         for version in 0..=2u8 {
@@ -448,4 +568,16 @@ mod tests {
         let mut v1 = sample_versioned(1);
         assert!(v1.invoke_method(2, None).is_err());
     }
+
+    #[test]
+    fn metadata_follows_the_version() {
+        for (version, attributes, methods) in [(0, 7, 1), (1, 10, 1), (2, 13, 2)] {
+            let push = sample_versioned(version);
+            let info = push.metadata().unwrap();
+            assert_eq!((info.version, info.attributes.len(), info.methods.len()), (version, attributes, methods));
+            // The descriptors cover exactly the attributes the object exposes.
+            let ids: Vec<u8> = push.attributes().iter().map(|(id, _)| *id).collect();
+            assert_eq!(info.attributes.iter().map(|a| a.id).collect::<Vec<_>>(), ids);
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{Choice, ScalerUnit};
 use crate::types::{BerError, CosemDataType};
@@ -50,7 +50,7 @@ impl Register {
     /// # Returns
     /// * `Ok(CosemDataType::Null)` - On successful reset.
     /// * `Err(String)` - If the value type does not support reset.
-    fn reset(&mut self) -> Result<CosemDataType, String> {
+    fn reset(&mut self) -> Result<CosemDataType, AccessError> {
         match &self.value {
             CosemDataType::Integer(_) => {
                 self.value = CosemDataType::Integer(0);
@@ -76,11 +76,21 @@ impl Register {
                 self.value = CosemDataType::DoubleLongUnsigned(0);
                 Ok(CosemDataType::Null)
             }
-            _ => Err("Unsupported value type for reset".to_string()),
+            _ => Err(AccessError::TypeUnmatched("Unsupported value type for reset".to_string())),
         }
     }
 }
 
+/// Attribute descriptors of the Register class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "value", DataKind::Any).dynamic().writable(),
+    AttributeInfo::new(3, "scaler_unit", DataKind::Structure).writable(),
+];
+
+/// Method descriptors of the Register class.
+const METHODS: &[MethodInfo] = &[MethodInfo::new(1, "reset", DataKind::Integer)];
+
 impl InterfaceClass for Register {
     fn class_id(&self) -> u16 {
         3
@@ -95,17 +105,39 @@ impl InterfaceClass for Register {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, self.value.clone()),
-            (3, self.scaler_unit.clone().into()),
-        ]
+        (1..=3).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => self.value.clone(),
+            3 => self.scaler_unit.clone().into(),
+            _ => return None,
+        })
+    }
+
+    fn attribute_ref(&self, attribute_id: u8) -> Option<&CosemDataType> {
+        match attribute_id {
+            2 => Some(&self.value),
+            _ => None,
+        }
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "reset".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Register",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -148,30 +180,34 @@ impl InterfaceClass for Register {
         Err(BerError::InvalidTag)
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => {
                 self.value = value;
                 Ok(())
             }
             3 => {
-                self.scaler_unit = ScalerUnit::try_from(&value)?;
+                self.scaler_unit = ScalerUnit::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
-            _ => Err(format!("Register attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!("Register attribute {attribute_id} is not writable"))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => self.reset(),
-            _ => Err(format!("Method {method_id} not supported for Register class")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Register class"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a length in BER (short or long form).
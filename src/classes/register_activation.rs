@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{ObjectDefinition, RegisterActMask};
 use crate::types::{BerError, CosemDataType};
@@ -45,6 +45,16 @@ impl RegisterActivation {
         }
     }
 
+    /// The activation masks (attr 3).
+    pub fn mask_list(&self) -> &[RegisterActMask] {
+        &self.mask_list
+    }
+
+    /// The name of the currently active mask (attr 4).
+    pub fn active_mask(&self) -> &[u8] {
+        &self.active_mask
+    }
+
     /// Adds a new activation mask to `mask_list`.
     ///
     /// # Arguments
@@ -53,11 +63,12 @@ impl RegisterActivation {
     /// # Returns
     /// * `Ok(CosemDataType::Null)` - If the mask was added.
     /// * `Err(String)` - If the parameter is invalid or the mask already exists.
-    fn add_mask(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        let param = params.ok_or("Invalid mask parameter".to_string())?;
-        let mask = RegisterActMask::try_from(&param).map_err(|e| format!("Invalid mask parameter: {e}"))?;
+    fn add_mask(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let param = params.ok_or_else(|| AccessError::TypeUnmatched("Invalid mask parameter".to_string()))?;
+        let mask = RegisterActMask::try_from(&param)
+            .map_err(|e| AccessError::TypeUnmatched(format!("Invalid mask parameter: {e}")))?;
         if self.mask_list.iter().any(|m| m.mask_name == mask.mask_name) {
-            return Err("Mask with this name already exists".to_string());
+            return Err(AccessError::OtherReason("Mask with this name already exists".to_string()));
         }
         self.mask_list.push(mask);
         Ok(CosemDataType::Null)
@@ -71,9 +82,9 @@ impl RegisterActivation {
     /// # Returns
     /// * `Ok(CosemDataType::Null)` - If the mask was removed.
     /// * `Err(String)` - If the mask was not found or the parameter is invalid.
-    fn delete_mask(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn delete_mask(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         let Some(CosemDataType::OctetString(mask_name)) = params else {
-            return Err("Invalid mask name parameter".to_string());
+            return Err(AccessError::TypeUnmatched("Invalid mask name parameter".to_string()));
         };
         let initial_len = self.mask_list.len();
         self.mask_list.retain(|m| m.mask_name != mask_name);
@@ -83,10 +94,22 @@ impl RegisterActivation {
             }
             return Ok(CosemDataType::Null);
         }
-        Err("Mask not found".to_string())
+        Err(AccessError::ObjectUnavailable("Mask not found".to_string()))
     }
 }
 
+/// Attribute descriptors of the Register activation class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "register_assignment", DataKind::Array),
+    AttributeInfo::new(3, "mask_list", DataKind::Array),
+    AttributeInfo::new(4, "active_mask", DataKind::OctetString).writable(),
+];
+
+/// Method descriptors of the Register activation class.
+const METHODS: &[MethodInfo] =
+    &[MethodInfo::new(1, "add_mask", DataKind::Structure), MethodInfo::new(2, "delete_mask", DataKind::OctetString)];
+
 impl InterfaceClass for RegisterActivation {
     fn class_id(&self) -> u16 {
         6
@@ -101,23 +124,35 @@ impl InterfaceClass for RegisterActivation {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (
-                2,
-                CosemDataType::Array(
-                    self.register_assignment.iter().map(|od| CosemDataType::from(od.clone())).collect(),
-                ),
+        (1..=4).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(
+                self.register_assignment.iter().map(|od| CosemDataType::from(od.clone())).collect(),
             ),
-            (3, CosemDataType::Array(self.mask_list.iter().map(|m| CosemDataType::from(m.clone())).collect())),
-            (4, CosemDataType::OctetString(self.active_mask.clone())),
-        ]
+            3 => CosemDataType::Array(self.mask_list.iter().map(|m| CosemDataType::from(m.clone())).collect()),
+            4 => CosemDataType::OctetString(self.active_mask.clone()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "add_mask".to_string()), (2, "delete_mask".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Register activation",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -183,17 +218,40 @@ impl InterfaceClass for RegisterActivation {
         Err(BerError::InvalidTag)
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
+        match (attribute_id, value) {
+            (4, CosemDataType::OctetString(name)) => {
+                if !self.mask_list.iter().any(|m| m.mask_name == name) {
+                    return Err(AccessError::ScopeOfAccessViolated(format!(
+                        "No mask named {:?}",
+                        String::from_utf8_lossy(&name)
+                    )));
+                }
+                self.active_mask = name;
+                Ok(())
+            }
+            (4, _) => Err(AccessError::TypeUnmatched("active_mask must be an octet-string".to_string())),
+            (id, _) => Err(AccessError::ReadWriteDenied(format!("RegisterActivation attribute {id} is not writable"))),
+        }
+    }
+
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => self.add_mask(params),
             2 => self.delete_mask(params),
-            _ => Err(format!("Method {method_id} not supported for RegisterActivation class")),
+            _ => Err(AccessError::ObjectUnavailable(format!(
+                "Method {method_id} not supported for RegisterActivation class"
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a length in BER (short or long form).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{ActionSet, ValueDefinition};
 use crate::types::{BerError, CosemDataType};
@@ -41,8 +41,34 @@ impl RegisterMonitor {
             actions: config.actions,
         }
     }
+
+    /// The thresholds (attr 2).
+    pub fn thresholds(&self) -> &[CosemDataType] {
+        &self.thresholds
+    }
+
+    /// The monitored attribute (attr 3).
+    pub fn monitored_value(&self) -> &ValueDefinition {
+        &self.monitored_value
+    }
+
+    /// The action sets, one per threshold (attr 4).
+    pub fn actions(&self) -> &[ActionSet] {
+        &self.actions
+    }
 }
 
+/// Attribute descriptors of the Register monitor class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "thresholds", DataKind::Array),
+    AttributeInfo::new(3, "monitored_value", DataKind::Structure),
+    AttributeInfo::new(4, "actions", DataKind::Array),
+];
+
+/// Method descriptors of the Register monitor class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for RegisterMonitor {
     fn class_id(&self) -> u16 {
         21
@@ -57,12 +83,17 @@ impl InterfaceClass for RegisterMonitor {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.thresholds.clone())),
-            (3, CosemDataType::from(self.monitored_value.clone())),
-            (4, CosemDataType::Array(self.actions.iter().map(|a| CosemDataType::from(a.clone())).collect())),
-        ]
+        (1..=4).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.thresholds.clone()),
+            3 => CosemDataType::from(self.monitored_value.clone()),
+            4 => CosemDataType::Array(self.actions.iter().map(|a| CosemDataType::from(a.clone())).collect()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -70,6 +101,16 @@ impl InterfaceClass for RegisterMonitor {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Register monitor",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -124,13 +165,19 @@ impl InterfaceClass for RegisterMonitor {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for Register monitor (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for Register monitor (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
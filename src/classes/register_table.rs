@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::ScalerUnit;
 use crate::types::{BerError, CosemDataType};
@@ -52,6 +52,18 @@ impl RegisterTable {
     }
 }
 
+/// Attribute descriptors of the Register table class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "table_cell_values", DataKind::Array).dynamic(),
+    AttributeInfo::new(3, "table_cell_definition", DataKind::Structure),
+    AttributeInfo::new(4, "scaler_unit", DataKind::Structure),
+];
+
+/// Method descriptors of the Register table class.
+const METHODS: &[MethodInfo] =
+    &[MethodInfo::new(1, "reset", DataKind::Integer), MethodInfo::new(2, "capture", DataKind::Integer)];
+
 impl InterfaceClass for RegisterTable {
     fn class_id(&self) -> u16 {
         61
@@ -66,18 +78,33 @@ impl InterfaceClass for RegisterTable {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.table_cell_values.clone())),
-            (3, self.table_cell_definition.clone()),
-            (4, self.scaler_unit.clone().into()),
-        ]
+        (1..=4).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.table_cell_values.clone()),
+            3 => self.table_cell_definition.clone(),
+            4 => self.scaler_unit.clone().into(),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "reset".to_string()), (2, "capture".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Register table",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -126,19 +153,25 @@ impl InterfaceClass for RegisterTable {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => Ok(self.reset()),
             // Method 2 `capture` is host-driven: the values live outside the
             // object model, so capturing is delegated to the application.
             2 => Ok(CosemDataType::Null),
-            _ => Err(format!("Method {method_id} not supported for Register table class")),
+            _ => Err(AccessError::ObjectUnavailable(format!(
+                "Method {method_id} not supported for Register table class"
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
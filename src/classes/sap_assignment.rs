@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::SapAssignmentEntry;
 use crate::types::{BerError, CosemDataType};
@@ -32,23 +32,36 @@ impl SapAssignment {
     /// Method 1: `connect_logical_device` — adds or updates a SAP-to-logical-device
     /// assignment (IEC 62056-6-2 §4.4.5.3). An empty logical device name removes
     /// the assignment for that SAP.
-    fn connect_logical_device(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
+    fn connect_logical_device(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
         let (sap, ldn) = match data {
             CosemDataType::Structure(fields) if fields.len() == 2 => match (&fields[0], &fields[1]) {
                 (CosemDataType::LongUnsigned(sap), CosemDataType::OctetString(ldn)) => (*sap, ldn.clone()),
-                _ => return Err("Expected structure { SAP: long-unsigned, ldn: octet-string }".to_string()),
+                _ => {
+                    return Err(AccessError::TypeUnmatched(
+                        "Expected structure { SAP: long-unsigned, ldn: octet-string }".to_string(),
+                    ))
+                }
             },
-            _ => return Err("Expected structure { SAP, logical_device_name }".to_string()),
+            _ => return Err(AccessError::TypeUnmatched("Expected structure { SAP, logical_device_name }".to_string())),
         };
         self.sap_assignment_list.retain(|e| e.sap != sap);
         if !ldn.is_empty() {
-            let entry = SapAssignmentEntry::try_from(data)?;
+            let entry = SapAssignmentEntry::try_from(data).map_err(AccessError::TypeUnmatched)?;
             self.sap_assignment_list.push(entry);
         }
         Ok(CosemDataType::Null)
     }
 }
 
+/// Attribute descriptors of the SAP assignment class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "SAP_assignment_list", DataKind::Array),
+];
+
+/// Method descriptors of the SAP assignment class.
+const METHODS: &[MethodInfo] = &[MethodInfo::new(1, "connect_logical_device", DataKind::Structure)];
+
 impl InterfaceClass for SapAssignment {
     fn class_id(&self) -> u16 {
         17
@@ -63,16 +76,31 @@ impl InterfaceClass for SapAssignment {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.sap_assignment_list.iter().cloned().map(CosemDataType::from).collect())),
-        ]
+        (1..=2).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.sap_assignment_list.iter().cloned().map(CosemDataType::from).collect()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "connect_logical_device".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "SAP assignment",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -124,16 +152,22 @@ impl InterfaceClass for SapAssignment {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
-            1 => self.connect_logical_device(&params.ok_or("Missing method parameter")?),
-            _ => Err(format!("Method {method_id} not supported for SAP assignment")),
+            1 => self.connect_logical_device(
+                &params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?,
+            ),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for SAP assignment"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
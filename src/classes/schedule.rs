@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::ScheduleTableEntry;
 use crate::types::{BerError, CosemDataType};
@@ -39,28 +39,36 @@ impl Schedule {
 
     /// Method 1: `enable_disable` — toggles the `enable` flag of the entry at
     /// the given 0-based index (IEC 62056-6-2 §4.5.3).
-    fn enable_disable(&mut self, params: Option<&CosemDataType>) -> Result<CosemDataType, String> {
-        let idx = index_param(params).ok_or("enable_disable requires an entry index")?;
-        let entry = self.entries.get_mut(idx).ok_or_else(|| format!("No schedule entry at index {idx}"))?;
+    fn enable_disable(&mut self, params: Option<&CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let idx = index_param(params)
+            .ok_or_else(|| AccessError::TypeUnmatched("enable_disable requires an entry index".to_string()))?;
+        let entry = self
+            .entries
+            .get_mut(idx)
+            .ok_or_else(|| AccessError::ObjectUnavailable(format!("No schedule entry at index {idx}")))?;
         entry.enable = !entry.enable;
         Ok(CosemDataType::Null)
     }
 
     /// Method 2: `insert` — appends a new `schedule_table_entry`
     /// (IEC 62056-6-2 §4.5.3).
-    fn insert(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        let value = params.ok_or("insert requires a schedule_table_entry structure")?;
-        let entry = ScheduleTableEntry::try_from(&value).map_err(|e| format!("Invalid schedule_table_entry: {e}"))?;
+    fn insert(&mut self, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let value = params.ok_or_else(|| {
+            AccessError::TypeUnmatched("insert requires a schedule_table_entry structure".to_string())
+        })?;
+        let entry = ScheduleTableEntry::try_from(&value)
+            .map_err(|e| AccessError::TypeUnmatched(format!("Invalid schedule_table_entry: {e}")))?;
         self.entries.push(entry);
         Ok(CosemDataType::Null)
     }
 
     /// Method 3: `delete` — removes the entry at the given 0-based index
     /// (IEC 62056-6-2 §4.5.3).
-    fn delete(&mut self, params: Option<&CosemDataType>) -> Result<CosemDataType, String> {
-        let idx = index_param(params).ok_or("delete requires an entry index")?;
+    fn delete(&mut self, params: Option<&CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let idx = index_param(params)
+            .ok_or_else(|| AccessError::TypeUnmatched("delete requires an entry index".to_string()))?;
         if idx >= self.entries.len() {
-            return Err(format!("No schedule entry at index {idx}"));
+            return Err(AccessError::ObjectUnavailable(format!("No schedule entry at index {idx}")));
         }
         self.entries.remove(idx);
         Ok(CosemDataType::Null)
@@ -87,6 +95,17 @@ fn index_param(value: Option<&CosemDataType>) -> Option<usize> {
     }
 }
 
+/// Attribute descriptors of the Schedule class.
+const ATTRIBUTES: &[AttributeInfo] =
+    &[AttributeInfo::new(1, "logical_name", DataKind::OctetString), AttributeInfo::new(2, "entries", DataKind::Array)];
+
+/// Method descriptors of the Schedule class.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "enable_disable", DataKind::Structure),
+    MethodInfo::new(2, "insert", DataKind::Structure),
+    MethodInfo::new(3, "delete", DataKind::Structure),
+];
+
 impl InterfaceClass for Schedule {
     fn class_id(&self) -> u16 {
         10
@@ -101,16 +120,31 @@ impl InterfaceClass for Schedule {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.entries.iter().cloned().map(CosemDataType::from).collect())),
-        ]
+        (1..=2).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.entries.iter().cloned().map(CosemDataType::from).collect()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "enable_disable".to_string()), (2, "insert".to_string()), (3, "delete".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Schedule",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -170,18 +204,22 @@ impl InterfaceClass for Schedule {
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => self.enable_disable(params.as_ref()),
             2 => self.insert(params),
             3 => self.delete(params.as_ref()),
-            _ => Err(format!("Method {method_id} not supported for Schedule class")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for Schedule class"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a length in BER (short or long form).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::Script;
 use crate::types::{BerError, CosemDataType};
@@ -35,6 +35,11 @@ impl ScriptTable {
         ScriptTable { logical_name: config.logical_name, scripts: config.scripts }
     }
 
+    /// The scripts (attr 2).
+    pub fn scripts(&self) -> &[Script] {
+        &self.scripts
+    }
+
     /// Executes the script with the given id.
     ///
     /// # Arguments
@@ -43,7 +48,7 @@ impl ScriptTable {
     /// # Returns
     /// * `Ok(CosemDataType::Null)` - If the script was found and executed.
     /// * `Err(String)` - If the script was not found or the parameter is invalid.
-    fn execute(&self, params: Option<&CosemDataType>) -> Result<CosemDataType, String> {
+    fn execute(&self, params: Option<&CosemDataType>) -> Result<CosemDataType, AccessError> {
         if let Some(CosemDataType::LongUnsigned(script_id)) = params {
             for script in &self.scripts {
                 if script.script_identifier == *script_id {
@@ -51,12 +56,19 @@ impl ScriptTable {
                     return Ok(CosemDataType::Null);
                 }
             }
-            return Err(format!("Script with ID {script_id} not found"));
+            return Err(AccessError::ObjectUnavailable(format!("Script with ID {script_id} not found")));
         }
-        Err("Invalid script ID parameter".to_string())
+        Err(AccessError::TypeUnmatched("Invalid script ID parameter".to_string()))
     }
 }
 
+/// Attribute descriptors of the Script table class.
+const ATTRIBUTES: &[AttributeInfo] =
+    &[AttributeInfo::new(1, "logical_name", DataKind::OctetString), AttributeInfo::new(2, "scripts", DataKind::Array)];
+
+/// Method descriptors of the Script table class.
+const METHODS: &[MethodInfo] = &[MethodInfo::new(1, "execute", DataKind::LongUnsigned)];
+
 impl InterfaceClass for ScriptTable {
     fn class_id(&self) -> u16 {
         9
@@ -71,16 +83,31 @@ impl InterfaceClass for ScriptTable {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.scripts.iter().cloned().map(CosemDataType::from).collect())),
-        ]
+        (1..=2).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.scripts.iter().cloned().map(CosemDataType::from).collect()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "execute".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Script table",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -132,16 +159,20 @@ impl InterfaceClass for ScriptTable {
         Err(BerError::InvalidTag)
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => self.execute(params.as_ref()),
-            _ => Err(format!("Method {method_id} not supported for ScriptTable class")),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for ScriptTable class"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a length in BER (short or long form).
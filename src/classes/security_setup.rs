@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::Certificate;
 use crate::types::{BerError, CosemDataType};
@@ -89,12 +89,12 @@ impl SecuritySetup {
     /// Method 1: `security_activate` — activates and strengthens the security
     /// policy (IEC 62056-6-2 §4.4.7.3.1). Strengthening is one-way: a value
     /// weaker than the current policy is rejected.
-    fn security_activate(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
+    fn security_activate(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
         let &CosemDataType::Enum(new_policy) = data else {
-            return Err("security_activate expects an enum".to_string());
+            return Err(AccessError::TypeUnmatched("security_activate expects an enum".to_string()));
         };
         if new_policy < self.security_policy {
-            return Err("security policy cannot be weakened".to_string());
+            return Err(AccessError::ScopeOfAccessViolated("security policy cannot be weakened".to_string()));
         }
         self.security_policy = new_policy;
         Ok(CosemDataType::Null)
@@ -107,25 +107,27 @@ impl SecuritySetup {
     /// The keys are stored as received. Unwrapping (AES key unwrap with the KEK)
     /// is not performed here, since the master key management belongs to the
     /// ciphering layer, which is out of scope for this data-model class.
-    fn key_transfer(&mut self, data: CosemDataType) -> Result<CosemDataType, String> {
+    fn key_transfer(&mut self, data: CosemDataType) -> Result<CosemDataType, AccessError> {
         let CosemDataType::Array(entries) = data else {
-            return Err("key_transfer expects an array of key_transfer_data".to_string());
+            return Err(AccessError::TypeUnmatched("key_transfer expects an array of key_transfer_data".to_string()));
         };
         let mut staged = BTreeMap::new();
         for entry in &entries {
             let CosemDataType::Structure(fields) = entry else {
-                return Err("key_transfer_data must be a structure".to_string());
+                return Err(AccessError::TypeUnmatched("key_transfer_data must be a structure".to_string()));
             };
             if fields.len() != 2 {
-                return Err("key_transfer_data must hold key_id and key_wrapped".to_string());
+                return Err(AccessError::TypeUnmatched(
+                    "key_transfer_data must hold key_id and key_wrapped".to_string(),
+                ));
             }
             let key_id = match &fields[0] {
                 CosemDataType::Enum(id) => *id,
-                _ => return Err("key_id must be an enum".to_string()),
+                _ => return Err(AccessError::TypeUnmatched("key_id must be an enum".to_string())),
             };
             let key = match &fields[1] {
                 CosemDataType::OctetString(bytes) => bytes.clone(),
-                _ => return Err("key_wrapped must be an octet-string".to_string()),
+                _ => return Err(AccessError::TypeUnmatched("key_wrapped must be an octet-string".to_string())),
             };
             staged.insert(key_id, key);
         }
@@ -135,6 +137,35 @@ impl SecuritySetup {
     }
 }
 
+/// Attribute descriptors of the Security setup class; version 1 adds
+/// `certificates`.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "security_policy", DataKind::Enum).writable(),
+    AttributeInfo::new(3, "security_suite", DataKind::Enum).writable(),
+    AttributeInfo::new(4, "client_system_title", DataKind::OctetString).writable(),
+    AttributeInfo::new(5, "server_system_title", DataKind::OctetString).writable(),
+    AttributeInfo::new(6, "certificates", DataKind::Array).dynamic(),
+];
+
+/// Method descriptors of the Security setup class, version 0.
+const METHODS_V0: &[MethodInfo] = &[
+    MethodInfo::new(1, "security_activate", DataKind::Enum),
+    MethodInfo::new(2, "global_key_transfer", DataKind::Array),
+];
+
+/// Method descriptors of the Security setup class, version 1.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "security_activate", DataKind::Enum),
+    MethodInfo::new(2, "key_transfer", DataKind::Array),
+    MethodInfo::new(3, "key_agreement", DataKind::Array),
+    MethodInfo::new(4, "generate_key_pair", DataKind::Enum),
+    MethodInfo::new(5, "generate_certificate_request", DataKind::Enum),
+    MethodInfo::new(6, "import_certificate", DataKind::OctetString),
+    MethodInfo::new(7, "export_certificate", DataKind::Structure),
+    MethodInfo::new(8, "remove_certificate", DataKind::Structure),
+];
+
 impl InterfaceClass for SecuritySetup {
     fn class_id(&self) -> u16 {
         64
@@ -149,22 +180,23 @@ impl InterfaceClass for SecuritySetup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        // Attributes 1..5 are common to both versions.
-        let mut attrs = vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Enum(self.security_policy)),
-            (3, CosemDataType::Enum(self.security_suite)),
-            (4, CosemDataType::OctetString(self.client_system_title.clone())),
-            (5, CosemDataType::OctetString(self.server_system_title.clone())),
-        ];
-        // The `certificates` attribute was added in version 1.
-        if self.version >= 1 {
-            attrs.push((
-                6,
-                CosemDataType::Array(self.certificates.iter().map(|c| CosemDataType::from(c.clone())).collect()),
-            ));
-        }
-        attrs
+        (1..=6).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            // Attributes 1..5 are common to both versions.
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Enum(self.security_policy),
+            3 => CosemDataType::Enum(self.security_suite),
+            4 => CosemDataType::OctetString(self.client_system_title.clone()),
+            5 => CosemDataType::OctetString(self.server_system_title.clone()),
+            // The `certificates` attribute was added in version 1.
+            6 if self.version >= 1 => {
+                CosemDataType::Array(self.certificates.iter().map(|c| CosemDataType::from(c.clone())).collect())
+            }
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -186,6 +218,18 @@ impl InterfaceClass for SecuritySetup {
         }
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        let (attributes, methods) =
+            if self.version >= 1 { (ATTRIBUTES, METHODS) } else { (&ATTRIBUTES[..5], METHODS_V0) };
+        Some(ClassInfo {
+            name: "Security setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes,
+            methods,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -258,55 +302,62 @@ impl InterfaceClass for SecuritySetup {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => match value {
                 CosemDataType::Enum(v) => {
                     self.security_policy = v;
                     Ok(())
                 }
-                _ => Err("security_policy must be enum".into()),
+                _ => Err(AccessError::TypeUnmatched("security_policy must be enum".to_string())),
             },
             3 => match value {
                 CosemDataType::Enum(v) => {
                     self.security_suite = v;
                     Ok(())
                 }
-                _ => Err("security_suite must be enum".into()),
+                _ => Err(AccessError::TypeUnmatched("security_suite must be enum".to_string())),
             },
             4 => match value {
                 CosemDataType::OctetString(v) => {
                     self.client_system_title = v;
                     Ok(())
                 }
-                _ => Err("client_system_title must be octet-string".into()),
+                _ => Err(AccessError::TypeUnmatched("client_system_title must be octet-string".to_string())),
             },
             5 => match value {
                 CosemDataType::OctetString(v) => {
                     self.server_system_title = v;
                     Ok(())
                 }
-                _ => Err("server_system_title must be octet-string".into()),
+                _ => Err(AccessError::TypeUnmatched("server_system_title must be octet-string".to_string())),
             },
-            _ => Err(format!("SecuritySetup attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!("SecuritySetup attribute {attribute_id} is not writable"))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        let params = params.ok_or("Missing method parameter")?;
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        let params = params.ok_or_else(|| AccessError::TypeUnmatched("Missing method parameter".to_string()))?;
         match method_id {
             1 => self.security_activate(&params),
             2 => self.key_transfer(params),
-            3..=8 if self.version >= 1 => {
-                Err(format!("Method {method_id} requires security suite 1 or 2 (PKI/ECDH); not supported for suite 0"))
-            }
-            _ => Err(format!("Method {} not supported for Security setup version {}", method_id, self.version)),
+            3..=8 if self.version >= 1 => Err(AccessError::ObjectUnavailable(format!(
+                "Method {method_id} requires security suite 1 or 2 (PKI/ECDH); not supported for suite 0"
+            ))),
+            _ => Err(AccessError::ObjectUnavailable(format!(
+                "Method {} not supported for Security setup version {}",
+                method_id, self.version
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
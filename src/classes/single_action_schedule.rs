@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::{ExecutedScript, ExecutionTime};
 use crate::types::{BerError, CosemDataType};
@@ -41,8 +41,34 @@ impl SingleActionSchedule {
             execution_time: config.execution_time,
         }
     }
+
+    /// The script executed at each execution time (attr 2).
+    pub fn executed_script(&self) -> &ExecutedScript {
+        &self.executed_script
+    }
+
+    /// The schedule type (attr 3).
+    pub fn schedule_type(&self) -> u8 {
+        self.schedule_type
+    }
+
+    /// The execution times (attr 4); date and time may contain wildcards.
+    pub fn execution_time(&self) -> &[ExecutionTime] {
+        &self.execution_time
+    }
 }
 
+/// Attribute descriptors of the Single action schedule class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "executed_script", DataKind::Structure).writable(),
+    AttributeInfo::new(3, "type", DataKind::Enum).writable(),
+    AttributeInfo::new(4, "execution_time", DataKind::Array).writable(),
+];
+
+/// Method descriptors of the Single action schedule class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for SingleActionSchedule {
     fn class_id(&self) -> u16 {
         22
@@ -57,12 +83,17 @@ impl InterfaceClass for SingleActionSchedule {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::from(self.executed_script.clone())),
-            (3, CosemDataType::Enum(self.schedule_type)),
-            (4, CosemDataType::Array(self.execution_time.iter().cloned().map(CosemDataType::from).collect())),
-        ]
+        (1..=4).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::from(self.executed_script.clone()),
+            3 => CosemDataType::Enum(self.schedule_type),
+            4 => CosemDataType::Array(self.execution_time.iter().cloned().map(CosemDataType::from).collect()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -70,6 +101,16 @@ impl InterfaceClass for SingleActionSchedule {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Single action schedule",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -126,10 +167,10 @@ impl InterfaceClass for SingleActionSchedule {
         Ok(())
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => {
-                self.executed_script = ExecutedScript::try_from(&value)?;
+                self.executed_script = ExecutedScript::try_from(&value).map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
             3 => match value {
@@ -137,26 +178,38 @@ impl InterfaceClass for SingleActionSchedule {
                     self.schedule_type = v;
                     Ok(())
                 }
-                _ => Err("schedule_type must be enum".into()),
+                _ => Err(AccessError::TypeUnmatched("schedule_type must be enum".to_string())),
             },
             4 => {
                 let CosemDataType::Array(list) = value else {
-                    return Err("execution_time must be array".into());
+                    return Err(AccessError::TypeUnmatched("execution_time must be array".to_string()));
                 };
-                self.execution_time = list.iter().map(ExecutionTime::try_from).collect::<Result<Vec<_>, _>>()?;
+                self.execution_time = list
+                    .iter()
+                    .map(ExecutionTime::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
-            _ => Err(format!("SingleActionSchedule attribute {attribute_id} is not writable")),
+            _ => Err(AccessError::ReadWriteDenied(format!(
+                "SingleActionSchedule attribute {attribute_id} is not writable"
+            ))),
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for Single action schedule (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for Single action schedule (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::attrs::SpecialDayEntry;
 use crate::types::{BerError, CosemDataType};
@@ -28,22 +28,37 @@ impl SpecialDaysTable {
         SpecialDaysTable { logical_name: config.logical_name, entries: config.entries }
     }
 
-    fn insert(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
-        let entry = SpecialDayEntry::try_from(data)?;
+    /// The special day entries (attr 2).
+    pub fn entries(&self) -> &[SpecialDayEntry] {
+        &self.entries
+    }
+
+    fn insert(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
+        let entry = SpecialDayEntry::try_from(data).map_err(AccessError::TypeUnmatched)?;
         if entry.specialday_date.len() != 12 {
-            return Err("Invalid DateTime length".to_string());
+            return Err(AccessError::TypeUnmatched("Invalid DateTime length".to_string()));
         }
         self.entries.push(entry);
         Ok(CosemDataType::Null)
     }
 
-    fn delete(&mut self, data: &CosemDataType) -> Result<CosemDataType, String> {
-        let entry = SpecialDayEntry::try_from(data)?;
+    fn delete(&mut self, data: &CosemDataType) -> Result<CosemDataType, AccessError> {
+        let entry = SpecialDayEntry::try_from(data).map_err(AccessError::TypeUnmatched)?;
         self.entries.retain(|e| e.index != entry.index);
         Ok(CosemDataType::Null)
     }
 }
 
+/// Attribute descriptors of the Special days table class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "entries", DataKind::Array).writable(),
+];
+
+/// Method descriptors of the Special days table class.
+const METHODS: &[MethodInfo] =
+    &[MethodInfo::new(1, "insert", DataKind::Structure), MethodInfo::new(2, "delete", DataKind::LongUnsigned)];
+
 impl InterfaceClass for SpecialDaysTable {
     fn class_id(&self) -> u16 {
         11
@@ -58,16 +73,31 @@ impl InterfaceClass for SpecialDaysTable {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::Array(self.entries.iter().cloned().map(Into::into).collect())),
-        ]
+        (1..=2).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::Array(self.entries.iter().cloned().map(Into::into).collect()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
         vec![(1, "insert".to_string()), (2, "delete".to_string())]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Special days table",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -124,30 +154,44 @@ impl InterfaceClass for SpecialDaysTable {
         }
     }
 
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => {
                 let CosemDataType::Array(entries) = value else {
-                    return Err("entries must be array".into());
+                    return Err(AccessError::TypeUnmatched("entries must be array".to_string()));
                 };
-                self.entries = entries.iter().map(SpecialDayEntry::try_from).collect::<Result<Vec<_>, _>>()?;
+                self.entries = entries
+                    .iter()
+                    .map(SpecialDayEntry::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(AccessError::TypeUnmatched)?;
                 Ok(())
             }
-            _ => Err(format!("SpecialDaysTable attribute {attribute_id} is not writable")),
+            _ => {
+                Err(AccessError::ReadWriteDenied(format!("SpecialDaysTable attribute {attribute_id} is not writable")))
+            }
         }
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
-            1 => self.insert(&params.ok_or("Missing parameter for insert method")?),
-            2 => self.delete(&params.ok_or("Missing parameter for delete method")?),
-            _ => Err(format!("Method {method_id} not supported for SpecialDaysTable")),
+            1 => self.insert(
+                &params.ok_or_else(|| AccessError::TypeUnmatched("Missing parameter for insert method".to_string()))?,
+            ),
+            2 => self.delete(
+                &params.ok_or_else(|| AccessError::TypeUnmatched("Missing parameter for delete method".to_string()))?,
+            ),
+            _ => Err(AccessError::ObjectUnavailable(format!("Method {method_id} not supported for SpecialDaysTable"))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER/A-XDR length octet (short or long form).
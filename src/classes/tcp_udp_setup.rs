@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 use serde::{Deserialize, Serialize};
@@ -50,6 +50,19 @@ impl TcpUdpSetup {
     }
 }
 
+/// Attribute descriptors of the TCP-UDP setup class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "TCP-UDP_port", DataKind::LongUnsigned),
+    AttributeInfo::new(3, "IP_reference", DataKind::OctetString),
+    AttributeInfo::new(4, "MSS", DataKind::LongUnsigned),
+    AttributeInfo::new(5, "nb_of_sim_conn", DataKind::Unsigned),
+    AttributeInfo::new(6, "inactivity_time_out", DataKind::LongUnsigned),
+];
+
+/// Method descriptors of the TCP-UDP setup class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for TcpUdpSetup {
     fn class_id(&self) -> u16 {
         41
@@ -64,14 +77,19 @@ impl InterfaceClass for TcpUdpSetup {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::LongUnsigned(self.tcp_udp_port)),
-            (3, CosemDataType::OctetString(self.ip_reference.clone())),
-            (4, CosemDataType::LongUnsigned(self.mss)),
-            (5, CosemDataType::Unsigned(self.nb_of_sim_conn)),
-            (6, CosemDataType::LongUnsigned(self.inactivity_time_out)),
-        ]
+        (1..=6).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::LongUnsigned(self.tcp_udp_port),
+            3 => CosemDataType::OctetString(self.ip_reference.clone()),
+            4 => CosemDataType::LongUnsigned(self.mss),
+            5 => CosemDataType::Unsigned(self.nb_of_sim_conn),
+            6 => CosemDataType::LongUnsigned(self.inactivity_time_out),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -79,6 +97,16 @@ impl InterfaceClass for TcpUdpSetup {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "TCP-UDP setup",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -142,13 +170,19 @@ impl InterfaceClass for TcpUdpSetup {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for TCP-UDP setup (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for TCP-UDP setup (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
@@ -1,4 +1,4 @@
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 use serde::{Deserialize, Serialize};
@@ -42,6 +42,17 @@ impl UtilityTables {
     }
 }
 
+/// Attribute descriptors of the Utility tables class.
+const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo::new(1, "logical_name", DataKind::OctetString),
+    AttributeInfo::new(2, "table_ID", DataKind::LongUnsigned),
+    AttributeInfo::new(3, "length", DataKind::DoubleLongUnsigned).dynamic(),
+    AttributeInfo::new(4, "buffer", DataKind::OctetString).dynamic(),
+];
+
+/// Method descriptors of the Utility tables class.
+const METHODS: &[MethodInfo] = &[];
+
 impl InterfaceClass for UtilityTables {
     fn class_id(&self) -> u16 {
         26
@@ -56,12 +67,17 @@ impl InterfaceClass for UtilityTables {
     }
 
     fn attributes(&self) -> Vec<(u8, CosemDataType)> {
-        vec![
-            (1, CosemDataType::OctetString(self.logical_name.to_bytes())),
-            (2, CosemDataType::LongUnsigned(self.table_id)),
-            (3, CosemDataType::DoubleLongUnsigned(self.length)),
-            (4, CosemDataType::OctetString(self.buffer.clone())),
-        ]
+        (1..=4).filter_map(|id| self.get_attribute(id).map(|value| (id, value))).collect()
+    }
+
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        Some(match attribute_id {
+            1 => CosemDataType::OctetString(self.logical_name.to_bytes()),
+            2 => CosemDataType::LongUnsigned(self.table_id),
+            3 => CosemDataType::DoubleLongUnsigned(self.length),
+            4 => CosemDataType::OctetString(self.buffer.clone()),
+            _ => return None,
+        })
     }
 
     fn methods(&self) -> Vec<(u8, String)> {
@@ -69,6 +85,16 @@ impl InterfaceClass for UtilityTables {
         vec![]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Utility tables",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         let mut seq_buf = Vec::new();
         CosemDataType::LongUnsigned(self.class_id()).serialize_ber(&mut seq_buf)?;
@@ -123,13 +149,19 @@ impl InterfaceClass for UtilityTables {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err(format!("Method {method_id} not supported for Utility tables (no specific methods)"))
+    fn invoke_method(&mut self, method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable(format!(
+            "Method {method_id} not supported for Utility tables (no specific methods)"
+        )))
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Writes a BER length octet (short or long form).
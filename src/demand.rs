@@ -0,0 +1,522 @@
+//! Demand calculation for the Demand register (class 5).
+//!
+//! A [`DemandCalculator`](crate::demand::DemandCalculator) integrates energy
+//! over demand periods of `period` seconds aligned to the clock (a 900 s period
+//! starts at :00, :15, :30 and :45 local time). With `number_of_periods` = 1 it
+//! computes block demand; with more it computes sliding demand over the last
+//! `number_of_periods` periods (IEC 62056-6-2 §4.3.4):
+//!
+//! * `current_average_value` = (energy of the last *n* − 1 completed periods +
+//!   energy of the running period) / (*n* · `period`);
+//! * `last_average_value` = energy of the last *n* completed periods /
+//!   (*n* · `period`), captured when a period ends.
+//!
+//! Demand is energy per hour: energy in Wh (varh, VAh) gives demand in W
+//! (var, VA) at the same scaler.
+//!
+//! [`DemandEngine`](crate::demand::DemandEngine) drives a Demand register of an
+//! object set: it reads the cumulative value of a source energy register (or
+//! takes consumption samples), updates the demand register's dynamic attributes
+//! and raises the linked Extended register maximum-demand registers when a
+//! period ends with a new maximum.
+
+use crate::classes::demand_register::DemandRegister;
+use crate::classes::extended_register::ExtendedRegister;
+use crate::interface::{AccessError, InterfaceClass};
+use crate::obis::ObisCode;
+use crate::scheduler::local_seconds;
+use crate::types::attrs::ValueDefinition;
+use crate::types::datetime::DateTime;
+use std::collections::VecDeque;
+
+/// A completed demand period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemandPeriod {
+    /// Start of the period.
+    pub start: DateTime,
+    /// End of the period, the capture time of its demand.
+    pub end: DateTime,
+    /// The demand over the window ending with this period
+    /// (`last_average_value`).
+    pub average: i64,
+}
+
+/// Block and sliding demand over clock-aligned periods.
+#[derive(Debug, Clone)]
+pub struct DemandCalculator {
+    period: i64,
+    number_of_periods: usize,
+    completed: VecDeque<i64>,
+    start: Option<i64>,
+    last: Option<i64>,
+    energy: i64,
+    reading: Option<i64>,
+    last_average: Option<i64>,
+    deviation: i16,
+}
+
+impl DemandCalculator {
+    /// Creates a calculator for periods of `period` seconds and a window of
+    /// `number_of_periods` periods (both at least 1).
+    pub fn new(period: u32, number_of_periods: u16) -> Self {
+        Self {
+            period: i64::from(period.max(1)),
+            number_of_periods: usize::from(number_of_periods.max(1)),
+            completed: VecDeque::new(),
+            start: None,
+            last: None,
+            energy: 0,
+            reading: None,
+            last_average: None,
+            deviation: 0,
+        }
+    }
+
+    /// Adds `energy` consumed since the previous call up to `now`, then closes
+    /// the periods that ended by `now`. Energy spanning period boundaries is
+    /// spread over the periods in proportion to the time each covers. The
+    /// first call starts the running period at the period boundary before
+    /// `now`.
+    pub fn consume(&mut self, now: &DateTime, energy: i64) -> Vec<DemandPeriod> {
+        let Some(local) = self.anchor(now) else {
+            return Vec::new();
+        };
+        let closed = self.roll_to(local, energy);
+        self.last = Some(local);
+        closed
+    }
+
+    /// Like [`DemandCalculator::consume`] with the increase of a cumulative
+    /// energy `reading` since the previous one (nothing for the first reading
+    /// or when the counter went back).
+    pub fn reading(&mut self, now: &DateTime, reading: i64) -> Vec<DemandPeriod> {
+        let energy = self.reading.map_or(0, |previous| (reading - previous).max(0));
+        self.reading = Some(reading);
+        self.consume(now, energy)
+    }
+
+    /// Closes the periods that ended by `now` without adding energy.
+    pub fn roll(&mut self, now: &DateTime) -> Vec<DemandPeriod> {
+        self.consume(now, 0)
+    }
+
+    /// The running demand (`current_average_value`).
+    pub fn current_average(&self) -> i64 {
+        let history: i64 = self.completed.iter().rev().take(self.number_of_periods - 1).sum();
+        self.demand(history + self.energy)
+    }
+
+    /// The demand of the last completed window (`last_average_value`), once a
+    /// period has ended.
+    pub fn last_average(&self) -> Option<i64> {
+        self.last_average
+    }
+
+    /// The start of the running period (`start_time_current`).
+    pub fn start_time_current(&self) -> Option<DateTime> {
+        self.start.and_then(|start| self.instant(start))
+    }
+
+    /// Starts the running period on the first call and follows a clock set
+    /// back; returns `now` in local seconds.
+    fn anchor(&mut self, now: &DateTime) -> Option<i64> {
+        let local = local_seconds(now)?;
+        self.deviation = now.deviation().unwrap_or(0);
+        let aligned = local - local.rem_euclid(self.period);
+        if self.start.is_none_or(|start| local < start) {
+            // The energy of a period cut short by the clock stays in the
+            // running period.
+            self.start = Some(aligned);
+        }
+        Some(local)
+    }
+
+    /// Closes every period ending at or before `local`, crediting each its
+    /// share of `energy` consumed since the last update. After a gap of more
+    /// than a window only the first window's periods are reported; the
+    /// energy of the skipped ones is dropped and the running period restarts
+    /// at the clock.
+    fn roll_to(&mut self, local: i64, energy: i64) -> Vec<DemandPeriod> {
+        let mut closed = Vec::new();
+        let Some(mut start) = self.start else {
+            return closed;
+        };
+        // The energy accrued evenly from the last update (or the running
+        // period's start, after a clock set back) up to `local`.
+        let mut since = self.last.map_or(local, |last| last.clamp(start, local));
+        let mut remaining = energy;
+        while start + self.period <= local {
+            if closed.len() > self.number_of_periods {
+                start = local - local.rem_euclid(self.period);
+                remaining = share(remaining, local - start, local - since);
+                break;
+            }
+            let end = start + self.period;
+            let credited = share(remaining, end - since, local - since);
+            remaining -= credited;
+            since = end;
+            self.energy += credited;
+            self.completed.push_back(std::mem::take(&mut self.energy));
+            if self.completed.len() > self.number_of_periods {
+                self.completed.pop_front();
+            }
+            let average = self.demand(self.completed.iter().sum());
+            self.last_average = Some(average);
+            if let (Some(from), Some(to)) = (self.instant(start), self.instant(start + self.period)) {
+                closed.push(DemandPeriod { start: from, end: to, average });
+            }
+            start += self.period;
+        }
+        self.energy += remaining;
+        self.start = Some(start);
+        closed
+    }
+
+    /// Energy over the window as demand per hour.
+    fn demand(&self, energy: i64) -> i64 {
+        // The window is at most u16::MAX periods of u32::MAX seconds.
+        #[allow(clippy::cast_possible_wrap)]
+        let window = self.period * self.number_of_periods as i64;
+        energy.saturating_mul(3600) / window
+    }
+
+    /// Local seconds as a date-time in the deviation of the last update, when
+    /// representable.
+    fn instant(&self, local: i64) -> Option<DateTime> {
+        DateTime::from_unix(local - i64::from(self.deviation) * 60, self.deviation)
+    }
+}
+
+/// The part of `energy` accrued over `part` of `whole` seconds.
+fn share(energy: i64, part: i64, whole: i64) -> i64 {
+    if whole <= 0 {
+        return energy;
+    }
+    // |part| <= |whole|, so the quotient fits back in an i64.
+    #[allow(clippy::cast_possible_truncation)]
+    let share = (i128::from(energy) * i128::from(part) / i128::from(whole)) as i64;
+    share
+}
+
+/// Drives a Demand register of an object set.
+///
+/// The demand register's `period` and `number_of_periods` configure the
+/// calculation (a change restarts it); the source register's value (attribute
+/// given by the value definition, usually 2) is read as a cumulative energy
+/// counter.
+#[derive(Debug, Clone)]
+pub struct DemandEngine {
+    register: ObisCode,
+    source: Option<ValueDefinition>,
+    maxima: Vec<ObisCode>,
+    calculator: Option<DemandCalculator>,
+}
+
+impl DemandEngine {
+    /// Creates an engine for the Demand register `register`.
+    pub fn new(register: ObisCode) -> Self {
+        Self { register, source: None, maxima: Vec::new(), calculator: None }
+    }
+
+    /// Reads the cumulative energy from `source` on every [`DemandEngine::update`].
+    pub fn with_source(mut self, source: ValueDefinition) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Links an Extended register holding the maximum demand: it takes the
+    /// demand and capture time of every period that exceeds its value.
+    pub fn with_maximum(mut self, logical_name: ObisCode) -> Self {
+        self.maxima.push(logical_name);
+        self
+    }
+
+    /// The calculation state, once started.
+    pub fn calculator(&self) -> Option<&DemandCalculator> {
+        self.calculator.as_ref()
+    }
+
+    /// Reads the source register (if any), closes the periods that ended by
+    /// `now` and updates the demand and maximum-demand registers.
+    ///
+    /// Fails with `object-undefined` when a register is missing and with
+    /// `type-unmatched` when the source value or a register value is not an
+    /// integer.
+    pub fn update(
+        &mut self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        now: &DateTime,
+    ) -> Result<Vec<DemandPeriod>, AccessError> {
+        let reading = match &self.source {
+            Some(source) => Some(read(objects, source)?),
+            None => None,
+        };
+        let calculator = self.configure(objects)?;
+        let closed = match reading {
+            Some(reading) => calculator.reading(now, reading),
+            None => calculator.roll(now),
+        };
+        self.apply(objects, &closed)?;
+        Ok(closed)
+    }
+
+    /// Adds a consumption sample of `energy` up to `now` and updates the
+    /// registers as [`DemandEngine::update`] does.
+    pub fn consume(
+        &mut self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        now: &DateTime,
+        energy: i64,
+    ) -> Result<Vec<DemandPeriod>, AccessError> {
+        let closed = self.configure(objects)?.consume(now, energy);
+        self.apply(objects, &closed)?;
+        Ok(closed)
+    }
+
+    /// The calculator, (re)built from the demand register's configuration.
+    fn configure(&mut self, objects: &mut [Box<dyn InterfaceClass>]) -> Result<&mut DemandCalculator, AccessError> {
+        let register = demand_register(objects, &self.register)?;
+        let (period, number_of_periods) = (register.period(), register.number_of_periods());
+        let fresh = DemandCalculator::new(period, number_of_periods);
+        let calculator = self.calculator.get_or_insert_with(|| fresh.clone());
+        if (calculator.period, calculator.number_of_periods) != (fresh.period, fresh.number_of_periods) {
+            *calculator = fresh;
+        }
+        Ok(calculator)
+    }
+
+    /// Writes the demand values to the demand register and raises the
+    /// maximum-demand registers.
+    fn apply(&self, objects: &mut [Box<dyn InterfaceClass>], closed: &[DemandPeriod]) -> Result<(), AccessError> {
+        let Some(calculator) = &self.calculator else {
+            return Ok(());
+        };
+        let register = demand_register(objects, &self.register)?;
+        let unmatched = || AccessError::TypeUnmatched(format!("{} does not hold an integer demand", self.register));
+        let current =
+            register.current_average_value().integer_like(calculator.current_average()).ok_or_else(unmatched)?;
+        if let Some(start) = calculator.start_time_current() {
+            register.record_current(current, start);
+        }
+        if let Some(period) = closed.last() {
+            let last = register.last_average_value().integer_like(period.average).ok_or_else(unmatched)?;
+            register.record_last(last, period.end.clone());
+        }
+        for logical_name in &self.maxima {
+            let maximum = objects
+                .iter_mut()
+                .filter(|o| o.class_id() == 4 && o.logical_name() == logical_name)
+                .find_map(|o| o.as_any_mut().downcast_mut::<ExtendedRegister>())
+                .ok_or_else(|| AccessError::ObjectUndefined(format!("No extended register {logical_name}")))?;
+            for period in closed {
+                let value = maximum.value();
+                if value.as_i64().is_none_or(|max| period.average > max) {
+                    let value = value.integer_like(period.average).ok_or_else(|| {
+                        AccessError::TypeUnmatched(format!("{logical_name} does not hold an integer demand"))
+                    })?;
+                    maximum.record(value, period.end.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The Demand register `logical_name` in `objects`.
+fn demand_register<'a>(
+    objects: &'a mut [Box<dyn InterfaceClass>],
+    logical_name: &ObisCode,
+) -> Result<&'a mut DemandRegister, AccessError> {
+    objects
+        .iter_mut()
+        .filter(|o| o.class_id() == 5 && o.logical_name() == logical_name)
+        .find_map(|o| o.as_any_mut().downcast_mut::<DemandRegister>())
+        .ok_or_else(|| AccessError::ObjectUndefined(format!("No demand register {logical_name}")))
+}
+
+/// Reads the cumulative energy a value definition refers to.
+fn read(objects: &[Box<dyn InterfaceClass>], source: &ValueDefinition) -> Result<i64, AccessError> {
+    let object = objects
+        .iter()
+        .find(|o| o.class_id() == source.class_id && *o.logical_name() == source.logical_name)
+        .ok_or_else(|| {
+        AccessError::ObjectUndefined(format!("No object {} of class {}", source.logical_name, source.class_id))
+    })?;
+    let value = u8::try_from(source.attribute_index)
+        .ok()
+        .and_then(|attribute| object.get_attribute(attribute))
+        .ok_or_else(|| {
+            AccessError::ObjectUnavailable(format!(
+                "No attribute {} in {}",
+                source.attribute_index, source.logical_name
+            ))
+        })?;
+    value
+        .as_i64()
+        .ok_or_else(|| AccessError::TypeUnmatched(format!("{} is not an integer energy", source.logical_name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::data::Data;
+    use crate::classes::demand_register::DemandRegisterConfig;
+    use crate::types::attrs::ScalerUnit;
+    use crate::types::CosemDataType;
+
+    fn demand_ln() -> ObisCode {
+        ObisCode::new(1, 0, 1, 4, 0, 255)
+    }
+
+    fn maximum_ln() -> ObisCode {
+        ObisCode::new(1, 0, 1, 6, 0, 255)
+    }
+
+    fn energy_ln() -> ObisCode {
+        ObisCode::new(1, 0, 1, 8, 0, 255)
+    }
+
+    /// 2024-03-01 at `h:m:s`, Moscow time.
+    fn at(h: u8, m: u8, s: u8) -> DateTime {
+        let utc = DateTime::from_ymdhms(2024, 3, 1, h, m, s).to_unix().unwrap() - 180 * 60;
+        DateTime::from_unix(utc, 180).unwrap()
+    }
+
+    /// A demand register over `number_of_periods` 15-minute periods, a
+    /// maximum-demand register and a cumulative energy counter.
+    fn objects(number_of_periods: u16) -> Vec<Box<dyn InterfaceClass>> {
+        let register = DemandRegister::new(DemandRegisterConfig {
+            logical_name: demand_ln(),
+            current_average_value: CosemDataType::DoubleLongUnsigned(0),
+            last_average_value: CosemDataType::DoubleLongUnsigned(0),
+            scaler_unit: ScalerUnit::new(0, 27),
+            status: CosemDataType::Null,
+            capture_time: DateTime::from_unix(0, 180).unwrap(),
+            start_time_current: DateTime::from_unix(0, 180).unwrap(),
+            period: 900,
+            number_of_periods,
+        });
+        let maximum = ExtendedRegister::new(
+            maximum_ln(),
+            CosemDataType::DoubleLongUnsigned(0),
+            ScalerUnit::new(0, 27),
+            CosemDataType::Null,
+            DateTime::from_unix(0, 180).unwrap(),
+        );
+        let energy = Data::new(energy_ln(), CosemDataType::DoubleLongUnsigned(10_000));
+        vec![Box::new(register), Box::new(maximum), Box::new(energy)]
+    }
+
+    fn register(objects: &[Box<dyn InterfaceClass>]) -> &DemandRegister {
+        objects[0].as_any().downcast_ref().unwrap()
+    }
+
+    fn maximum(objects: &[Box<dyn InterfaceClass>]) -> &ExtendedRegister {
+        objects[1].as_any().downcast_ref().unwrap()
+    }
+
+    #[test]
+    fn block_demand_is_the_energy_of_one_period() {
+        let mut calculator = DemandCalculator::new(900, 1);
+        assert!(calculator.consume(&at(10, 3, 0), 0).is_empty());
+        assert_eq!(calculator.start_time_current(), Some(at(10, 0, 0)));
+        assert!(calculator.consume(&at(10, 10, 0), 250).is_empty());
+        assert_eq!(calculator.current_average(), 1000);
+        let closed = calculator.consume(&at(10, 15, 0), 250);
+        assert_eq!(closed, vec![DemandPeriod { start: at(10, 0, 0), end: at(10, 15, 0), average: 2000 }]);
+        assert_eq!(calculator.last_average(), Some(2000));
+        assert_eq!(calculator.current_average(), 0);
+        assert_eq!(calculator.start_time_current(), Some(at(10, 15, 0)));
+    }
+
+    #[test]
+    fn sliding_demand_averages_the_last_periods() {
+        let mut calculator = DemandCalculator::new(900, 3);
+        calculator.roll(&at(10, 0, 0));
+        calculator.consume(&at(10, 15, 0), 300);
+        calculator.consume(&at(10, 30, 0), 600);
+        let closed = calculator.consume(&at(10, 45, 0), 900);
+        // 1800 Wh over 45 minutes.
+        assert_eq!(closed[0].average, 2400);
+        calculator.consume(&at(10, 50, 0), 150);
+        // The last two periods and the running one: 1650 Wh over 45 minutes.
+        assert_eq!(calculator.current_average(), 2200);
+        let closed = calculator.consume(&at(11, 0, 0), 0);
+        // The first period slid out: 1650 Wh.
+        assert_eq!(closed[0].average, 2200);
+    }
+
+    #[test]
+    fn periods_roll_on_clock_boundaries() {
+        let mut calculator = DemandCalculator::new(900, 2);
+        calculator.roll(&at(10, 7, 30));
+        let closed = calculator.consume(&at(10, 47, 0), 500);
+        let bounds: Vec<_> = closed.iter().map(|p| (p.start.clone(), p.end.clone())).collect();
+        assert_eq!(
+            bounds,
+            vec![(at(10, 0, 0), at(10, 15, 0)), (at(10, 15, 0), at(10, 30, 0)), (at(10, 30, 0), at(10, 45, 0))]
+        );
+        assert_eq!(calculator.start_time_current(), Some(at(10, 45, 0)));
+        // After a long gap the running period restarts at the clock.
+        let closed = calculator.roll(&at(18, 20, 0));
+        assert_eq!(closed.len(), 3);
+        assert_eq!(closed.last().unwrap().average, 0);
+        assert_eq!(calculator.start_time_current(), Some(at(18, 15, 0)));
+        // A clock set back restarts the running period.
+        calculator.roll(&at(17, 5, 0));
+        assert_eq!(calculator.start_time_current(), Some(at(17, 0, 0)));
+    }
+
+    #[test]
+    fn energy_over_a_gap_is_spread_over_the_periods_it_covers() {
+        let mut calculator = DemandCalculator::new(900, 1);
+        calculator.reading(&at(10, 0, 0), 1000);
+        // 600 Wh over 30 minutes: half in each closed period.
+        let closed = calculator.reading(&at(10, 30, 0), 1600);
+        let averages: Vec<_> = closed.iter().map(|p| p.average).collect();
+        assert_eq!(averages, vec![1200, 1200]);
+        assert_eq!(calculator.current_average(), 0);
+        // 300 Wh by 10:40, then 300 Wh over 10:40-10:50 split at 10:45.
+        calculator.reading(&at(10, 40, 0), 1900);
+        let closed = calculator.reading(&at(10, 50, 0), 2200);
+        assert_eq!(closed[0].average, 1800);
+        assert_eq!(calculator.current_average(), 600);
+    }
+
+    #[test]
+    fn engine_updates_demand_and_maximum_registers() {
+        let mut objects = objects(1);
+        let mut engine = DemandEngine::new(demand_ln()).with_maximum(maximum_ln());
+        engine.consume(&mut objects, &at(10, 0, 0), 0).unwrap();
+        engine.consume(&mut objects, &at(10, 5, 0), 100).unwrap();
+        assert_eq!(register(&objects).current_average_value(), &CosemDataType::DoubleLongUnsigned(400));
+        assert_eq!(register(&objects).start_time_current(), &at(10, 0, 0));
+
+        engine.consume(&mut objects, &at(10, 15, 0), 200).unwrap();
+        assert_eq!(register(&objects).last_average_value(), &CosemDataType::DoubleLongUnsigned(1200));
+        assert_eq!(register(&objects).capture_time(), &at(10, 15, 0));
+        assert_eq!(maximum(&objects).value(), &CosemDataType::DoubleLongUnsigned(1200));
+
+        // A lower demand leaves the maximum alone.
+        engine.consume(&mut objects, &at(10, 30, 0), 100).unwrap();
+        assert_eq!(register(&objects).last_average_value(), &CosemDataType::DoubleLongUnsigned(400));
+        assert_eq!(maximum(&objects).value(), &CosemDataType::DoubleLongUnsigned(1200));
+        assert_eq!(maximum(&objects).capture_time(), &at(10, 15, 0));
+
+        let mut missing = DemandEngine::new(maximum_ln());
+        assert!(matches!(missing.consume(&mut objects, &at(10, 30, 0), 0), Err(AccessError::ObjectUndefined(_))));
+    }
+
+    #[test]
+    fn engine_reads_a_cumulative_source_register() {
+        let mut objects = objects(1);
+        let source = ValueDefinition { class_id: 1, logical_name: energy_ln(), attribute_index: 2 };
+        let mut engine = DemandEngine::new(demand_ln()).with_source(source);
+        engine.update(&mut objects, &at(10, 0, 0)).unwrap();
+        objects[2].set_attribute(2, CosemDataType::DoubleLongUnsigned(10_500)).unwrap();
+        let closed = engine.update(&mut objects, &at(10, 15, 0)).unwrap();
+        assert_eq!(closed[0].average, 2000);
+        assert_eq!(register(&objects).last_average_value(), &CosemDataType::DoubleLongUnsigned(2000));
+        assert_eq!(engine.calculator().unwrap().current_average(), 0);
+    }
+}
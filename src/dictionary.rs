@@ -0,0 +1,343 @@
+//! A built-in dictionary of OBIS codes: expected interface class, a readable
+//! description and the typical unit of each known object.
+//!
+//! Look-ups first consult a table of named objects — the abstract objects of
+//! the Blue Book and the СПОДЭС / СПОДУС object lists — and then fall back to
+//! composing a description of an electricity code (A = 1) from its value
+//! groups, so that `1-0:1.8.0*255` reads "Active energy import (A+), total" and
+//! `1-0:32.7.0*255` reads "Voltage L1, instantaneous".
+//!
+//! ```
+//! use spodes_rs::dictionary;
+//! use spodes_rs::obis::ObisCode;
+//!
+//! let info = dictionary::describe(&ObisCode::new(1, 0, 1, 8, 2, 255)).unwrap();
+//! assert_eq!(info.class_id, 3);
+//! assert_eq!(info.description, "Active energy import (A+), tariff 2");
+//! assert_eq!(info.unit.map(|u| u.symbol()), Some("Wh"));
+//! ```
+
+use crate::obis::{ObisCode, ObisPattern};
+use crate::types::attrs::ObjectListElement;
+use crate::types::unit::Unit;
+use std::sync::OnceLock;
+
+/// What the dictionary knows about an OBIS code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectInfo {
+    /// The interface class the object is expected to have (3 = Register, …).
+    pub class_id: u16,
+    /// A readable description, e.g. "Active energy import (A+), total".
+    pub description: String,
+    /// The typical unit of the value, when it has one.
+    pub unit: Option<Unit>,
+}
+
+/// The named objects, most specific patterns first: `(pattern, class id,
+/// description, unit)`.
+const OBJECTS: &[(&str, u16, &str, Option<Unit>)] = &[
+    // Abstract objects (Blue Book, value group A = 0).
+    ("0-0:1.0.0*255", 8, "Clock", None),
+    ("0-0:10.0.0*255", 9, "Global meter reset script table", None),
+    ("0-0:10.0.1*255", 9, "End of billing period script table", None),
+    ("0-0:10.0.100*255", 9, "Tariffication script table", None),
+    ("0-0:10.0.106*255", 9, "Disconnect control script table", None),
+    ("0-0:10.0.108*255", 9, "Push script table", None),
+    ("0-0:11.0.0*255", 11, "Special days table", None),
+    ("0-0:13.0.0*255", 20, "Activity calendar", None),
+    ("0-0:15.0.0*255", 22, "End of billing period single action schedule", None),
+    ("0-0:15.0.*.255", 22, "Single action schedule", None),
+    ("0-0:16.0.*.255", 21, "Register monitor", None),
+    ("0-0:17.0.*.255", 71, "Limiter", None),
+    ("0-0:20.0.0*255", 19, "IEC local port setup", None),
+    ("0-0:22.0.0*255", 23, "IEC HDLC setup", None),
+    ("0-*:25.0.0*255", 41, "TCP-UDP setup", None),
+    ("0-*:25.1.0*255", 42, "IPv4 setup", None),
+    ("0-0:25.9.0*255", 40, "Push setup", None),
+    ("0-0:25.9.*.255", 40, "Push setup", None),
+    ("0-0:40.0.0*255", 15, "Current association", None),
+    ("0-0:40.0.*.255", 15, "Association LN", None),
+    ("0-0:41.0.0*255", 17, "SAP assignment", None),
+    ("0-0:42.0.0*255", 1, "COSEM logical device name", None),
+    ("0-0:43.0.*.255", 64, "Security setup", None),
+    ("0-0:96.3.10*255", 70, "Disconnect control", None),
+    ("0-0:96.14.0*255", 1, "Currently active tariff", None),
+    ("1-0:0.2.0*255", 1, "Active firmware identifier", None),
+    ("1-0:0.8.0*255", 1, "Demand integration period", Some(Unit::Second)),
+    ("1-0:0.8.4*255", 1, "Load profile recording period 1", Some(Unit::Second)),
+    ("1-0:0.8.5*255", 1, "Load profile recording period 2", Some(Unit::Second)),
+    // СПОДЭС meter profiles and event logs.
+    ("1-0:99.1.0*255", 7, "Load profile 1", None),
+    ("1-0:99.2.0*255", 7, "Load profile 2", None),
+    ("1-0:98.1.0*255", 7, "Monthly billing profile", None),
+    ("1-0:98.2.0*255", 7, "Daily billing profile", None),
+    ("0-0:99.98.0*255", 7, "Voltage event log", None),
+    ("0-0:99.98.1*255", 7, "Current event log", None),
+    ("0-0:99.98.2*255", 7, "Power on/off event log", None),
+    ("0-0:99.98.3*255", 7, "Data correction event log", None),
+    ("0-0:99.98.4*255", 7, "External impact event log", None),
+    ("0-0:99.98.5*255", 7, "Communication event log", None),
+    ("0-0:99.98.6*255", 7, "Access control event log", None),
+    ("0-0:99.98.7*255", 7, "Self-diagnostics event log", None),
+    ("0-*:99.98.10*255", 7, "Discrete-I/O event log", None),
+    ("0-*:99.98.*.255", 7, "Event log", None),
+    // СПОДУС ИВКЭ objects (see `spodus::obis`).
+    ("0-0:0.2.1*255", 1, "Hardware version", None),
+    ("0-0:94.7.0*255", 7, "Passport-data reference profile", None),
+    ("0-0:94.7.128*255", 1, "Configured meter list", None),
+    ("0-0:94.7.129*255", 1, "Direct-channel table", None),
+    ("0-0:94.7.130*255", 7, "ИВКЭ channel list", None),
+    ("0-0:94.7.131*255", 7, "Discovered-meters list", None),
+    ("0-0:94.7.132*255", 1, "Meter access policies", None),
+    ("0-0:94.7.133*255", 1, "Meter data-exchange task list", None),
+    ("0-0:94.7.134*255", 7, "Meter status table", None),
+    ("0-0:94.7.135*255", 7, "Meter data-exchange-status journal", None),
+    ("0-0:94.7.136*255", 7, "Object-correction journal", None),
+    ("0-0:94.7.137*255", 7, "Numeric meter journal", None),
+    ("0-0:94.7.140*255", 7, "Incoming-events table", None),
+    ("0-0:94.7.141*255", 1, "Time difference with meters", Some(Unit::Second)),
+    ("0-0:96.1.0*255", 1, "Serial number", None),
+    ("0-0:96.1.1*255", 1, "Device type", None),
+    ("0-0:96.1.2*255", 1, "Firmware version", None),
+    ("0-0:96.1.3*255", 1, "Manufacturer name", None),
+    ("0-0:96.1.4*255", 1, "Production year", None),
+    ("0-0:96.1.6*255", 1, "СПОДУС specification version", None),
+    ("0-0:96.1.7*255", 1, "Last firmware-update date", None),
+    ("0-0:96.1.8*255", 1, "Non-metrological firmware identifier", None),
+    ("0-0:96.1.10*255", 1, "Metrological firmware checksum", None),
+    ("0-0:96.3.1*255", 1, "Discrete inputs state", None),
+    ("0-0:96.11.3*255", 7, "Parameter-programming event log", None),
+    ("0-*:96.11.5*255", 7, "Switching event log", None),
+    ("0-0:96.11.6*255", 7, "Access-control event log", None),
+    ("0-0:96.11.7*255", 7, "Self-diagnostics event log", None),
+    ("0-0:96.50.0*255", 1, "Event push message", None),
+    ("0-0:97.98.10*255", 1, "Push-message filter mask", None),
+];
+
+/// A parsed [`OBJECTS`] entry.
+type Entry = (ObisPattern, u16, &'static str, Option<Unit>);
+
+fn objects() -> &'static [Entry] {
+    static TABLE: OnceLock<Vec<Entry>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        OBJECTS
+            .iter()
+            .map(|&(pattern, class_id, description, unit)| {
+                let pattern = pattern.parse().unwrap_or_else(|e| panic!("dictionary pattern {e}"));
+                (pattern, class_id, description, unit)
+            })
+            .collect()
+    })
+}
+
+/// Describes `code`, or returns `None` when it is neither a named object nor
+/// an electricity code whose value groups the dictionary understands.
+pub fn describe(code: &ObisCode) -> Option<ObjectInfo> {
+    if let Some(&(_, class_id, description, unit)) = objects().iter().find(|(pattern, ..)| pattern.matches(code)) {
+        return Some(ObjectInfo { class_id, description: description.to_owned(), unit });
+    }
+    electricity(code)
+}
+
+/// The description of `code`, or its `A-B:C.D.E*F` notation when the
+/// dictionary does not know it.
+pub fn name(code: &ObisCode) -> String {
+    match describe(code) {
+        Some(info) => info.description,
+        None => ObisPattern::from(*code).to_string(),
+    }
+}
+
+/// Renders an object-list entry as `description [A-B:C.D.E*F] (IC n)`,
+/// flagging an interface class other than the one the dictionary expects.
+pub fn label(element: &ObjectListElement) -> String {
+    object_label(&element.logical_name, element.class_id)
+}
+
+/// Renders the object `code` of class `class_id` as [`label`] does.
+pub fn object_label(code: &ObisCode, class_id: u16) -> String {
+    let notation = ObisPattern::from(*code).to_string();
+    match describe(code) {
+        Some(info) if info.class_id == class_id => format!("{} [{notation}] (IC {class_id})", info.description),
+        Some(info) => {
+            format!("{} [{notation}] (IC {class_id}, expected IC {})", info.description, info.class_id)
+        }
+        None => format!("[{notation}] (IC {class_id})"),
+    }
+}
+
+/// The direction/quadrant part and kind of the power and energy quantities of
+/// value group C (1..10, 15, 16), indexed within a phase block.
+fn energy_quantity(c: u8) -> Option<(&'static str, &'static str)> {
+    Some(match c {
+        1 => ("Active", "import (A+)"),
+        2 => ("Active", "export (A−)"),
+        3 => ("Reactive", "import (R+)"),
+        4 => ("Reactive", "export (R−)"),
+        5 => ("Reactive", "QI (R1)"),
+        6 => ("Reactive", "QII (R2)"),
+        7 => ("Reactive", "QIII (R3)"),
+        8 => ("Reactive", "QIV (R4)"),
+        9 => ("Apparent", "import"),
+        10 => ("Apparent", "export"),
+        15 => ("Active", "absolute (|A+|+|A−|)"),
+        16 => ("Active", "net (|A+|−|A−|)"),
+        _ => return None,
+    })
+}
+
+/// The power and energy units of a quantity kind.
+fn kind_units(kind: &str) -> (Unit, Unit) {
+    match kind {
+        "Active" => (Unit::Watt, Unit::WattHour),
+        "Reactive" => (Unit::Var, Unit::VarHour),
+        _ => (Unit::VoltAmpere, Unit::VoltAmpereHour),
+    }
+}
+
+/// Value group D of a power quantity: whether it is an energy (a time
+/// integral), the suffix it adds and the expected interface class.
+fn processing(d: u8) -> Option<(bool, &'static str, u16)> {
+    Some(match d {
+        1 => (false, ", cumulative minimum", 4),
+        2 => (false, ", cumulative maximum demand", 4),
+        3 => (false, ", minimum", 4),
+        4 => (false, ", current demand", 5),
+        5 => (false, ", last demand", 5),
+        6 => (false, ", maximum demand", 4),
+        7 => (false, ", instantaneous", 3),
+        8 => (true, "", 3),
+        9 => (true, ", current billing period", 3),
+        29 => (true, ", profile period", 3),
+        _ => return None,
+    })
+}
+
+/// Composes the description of an electricity code from its value groups.
+fn electricity(code: &ObisCode) -> Option<ObjectInfo> {
+    let [a, b, c, d, e, f] = code.groups();
+    if a != 1 {
+        return None;
+    }
+    // C = 21..40, 41..60 and 61..80 repeat 1..20 for phases L1, L2 and L3.
+    let (phase, base) = match c {
+        21..=80 => ((c - 1) / 20, (c - 1) % 20 + 1),
+        _ => (0, c),
+    };
+    let phase = match phase {
+        0 => String::new(),
+        n => format!(" L{n}"),
+    };
+    let mut info = if let Some((kind, direction)) = energy_quantity(base) {
+        let (energy, suffix, class_id) = processing(d)?;
+        let (power_unit, energy_unit) = kind_units(kind);
+        let (quantity, unit) = if energy { ("energy", energy_unit) } else { ("power", power_unit) };
+        let tariff = match e {
+            0 => ", total".to_owned(),
+            n => format!(", tariff {n}"),
+        };
+        ObjectInfo {
+            class_id,
+            description: format!("{kind} {quantity} {direction}{phase}{suffix}{tariff}"),
+            unit: Some(unit),
+        }
+    } else {
+        let (quantity, unit) = match (c, base) {
+            (91, _) => ("Current", Some(Unit::Ampere)),
+            (92, _) => ("Voltage", Some(Unit::Volt)),
+            (_, 11) if c <= 80 => ("Current", Some(Unit::Ampere)),
+            (_, 12) if c <= 80 => ("Voltage", Some(Unit::Volt)),
+            (_, 13) if c <= 80 => ("Power factor", None),
+            (_, 14) if c <= 80 => ("Frequency", Some(Unit::Hertz)),
+            _ => return None,
+        };
+        let phase = if c >= 91 { " N".to_owned() } else { phase };
+        let (_, suffix, class_id) = processing(d).filter(|(energy, ..)| !energy)?;
+        let harmonic = match e {
+            0 => String::new(),
+            n if matches!(quantity, "Current" | "Voltage") => format!(", harmonic {n}"),
+            _ => return None,
+        };
+        ObjectInfo { class_id, description: format!("{quantity}{phase}{suffix}{harmonic}"), unit }
+    };
+    if b != 0 {
+        info.description.push_str(&format!(", channel {b}"));
+    }
+    if f != 255 {
+        info.description.push_str(&format!(", historical value {f}"));
+    }
+    Some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::attrs::AccessRight;
+
+    fn code(text: &str) -> ObisCode {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn every_table_pattern_parses() {
+        assert_eq!(objects().len(), OBJECTS.len());
+    }
+
+    #[test]
+    fn named_objects_come_first() {
+        let clock = describe(&code("0-0:1.0.0*255")).unwrap();
+        assert_eq!((clock.class_id, clock.description.as_str()), (8, "Clock"));
+        assert_eq!(name(&code("0-0:40.0.0*255")), "Current association");
+        assert_eq!(name(&code("0-0:40.0.3*255")), "Association LN");
+        assert_eq!(name(&crate::spodus::obis::switching_log(2)), "Switching event log");
+        assert_eq!(describe(&crate::spodus::obis::push_setup()).unwrap().class_id, 40);
+        assert_eq!(name(&code("0-0:99.98.9*255")), "Event log");
+        assert_eq!(name(&code("0-0:96.99.99*255")), "0-0:96.99.99*255");
+    }
+
+    #[test]
+    fn electricity_codes_are_composed_from_value_groups() {
+        let describe = |text: &str| describe(&code(text)).unwrap();
+        let total = describe("1-0:1.8.0*255");
+        assert_eq!(total.description, "Active energy import (A+), total");
+        assert_eq!((total.class_id, total.unit), (3, Some(Unit::WattHour)));
+        assert_eq!(describe("1-0:4.8.3*255").description, "Reactive energy export (R−), tariff 3");
+        assert_eq!(describe("1-0:8.8.0*255").unit, Some(Unit::VarHour));
+
+        let demand = describe("1-0:1.6.0*255");
+        assert_eq!(demand.description, "Active power import (A+), maximum demand, total");
+        assert_eq!((demand.class_id, demand.unit), (4, Some(Unit::Watt)));
+        assert_eq!(describe("1-0:21.7.0*255").description, "Active power import (A+) L1, instantaneous, total");
+
+        let voltage = describe("1-0:52.7.0*255");
+        assert_eq!(voltage.description, "Voltage L2, instantaneous");
+        assert_eq!(voltage.unit, Some(Unit::Volt));
+        assert_eq!(describe("1-0:91.7.0*255").description, "Current N, instantaneous");
+        assert_eq!(describe("1-0:31.7.3*255").description, "Current L1, instantaneous, harmonic 3");
+        assert_eq!(describe("1-0:14.7.0*255").unit, Some(Unit::Hertz));
+        assert_eq!(
+            describe("1-1:1.8.0*3").description,
+            "Active energy import (A+), total, channel 1, historical value 3"
+        );
+
+        assert_eq!(dictionary_miss("1-0:13.8.0*255"), None);
+        assert_eq!(dictionary_miss("7-0:1.8.0*255"), None);
+    }
+
+    fn dictionary_miss(text: &str) -> Option<ObjectInfo> {
+        super::describe(&code(text))
+    }
+
+    #[test]
+    fn object_list_labels_flag_class_mismatches() {
+        let element = |class_id, text: &str| ObjectListElement {
+            class_id,
+            version: 0,
+            logical_name: code(text),
+            access_rights: AccessRight { attribute_access: vec![], method_access: vec![] },
+        };
+        assert_eq!(label(&element(3, "1-0:1.8.0*255")), "Active energy import (A+), total [1-0:1.8.0*255] (IC 3)");
+        assert_eq!(label(&element(1, "0-0:1.0.0*255")), "Clock [0-0:1.0.0*255] (IC 1, expected IC 8)");
+        assert_eq!(label(&element(1, "0-0:96.99.0*255")), "[0-0:96.99.0*255] (IC 1)");
+    }
+}
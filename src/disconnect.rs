@@ -0,0 +1,553 @@
+//! Disconnect control runtime: drives the relays of Disconnect control
+//! objects (class 70), wires Limiters to local disconnection and logs the
+//! switching.
+//!
+//! [`DisconnectControl`](crate::classes::disconnect_control::DisconnectControl)
+//! runs the state machine: remote transitions through its methods, manual and
+//! local ones through
+//! [`DisconnectControl::apply`](crate::classes::disconnect_control::DisconnectControl::apply).
+//! Each transition is queued on the object;
+//! [`DisconnectService::poll`](crate::disconnect::DisconnectService::poll)
+//! takes the queue and
+//!
+//! * calls the host [`RelayDriver`](crate::disconnect::RelayDriver) when the
+//!   output has to open or close, and records the output state the relay
+//!   reports in `output_state` — a failed switching leaves it unchanged;
+//! * appends a `{date-time, event code}` entry to the switching journal of its
+//!   [`SwitchingLog`](crate::disconnect::SwitchingLog) and writes the code to
+//!   the journal's event object;
+//! * picks up output changes the relay reports on its own.
+//!
+//! A Limiter linked with
+//! [`DisconnectService::with_limiter`](crate::disconnect::DisconnectService::with_limiter)
+//! disconnects its Disconnect control locally (transition g) when
+//! [`ThresholdMonitor`](crate::monitor::ThresholdMonitor) reports it over the
+//! threshold, and reconnects it (transition h, modes 5 and 6) when it falls
+//! back under.
+
+use crate::classes::disconnect_control::{control_state, DisconnectControl, Transition, Trigger};
+use crate::classes::profile_generic::ProfileGeneric;
+use crate::interface::{AccessError, InterfaceClass};
+use crate::monitor::{MonitorEvent, ThresholdEvent};
+use crate::obis::ObisCode;
+use crate::types::datetime::DateTime;
+use crate::types::CosemDataType;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+
+/// СПОДЭС codes of the relay switching events (event object
+/// `0.0.96.11.2.255`), the default codes of a [`SwitchingLog`].
+pub mod event_code {
+    /// Remote disconnection of the consumer.
+    pub const REMOTE_DISCONNECT: u16 = 3;
+    /// Remote reconnection of the consumer.
+    pub const REMOTE_RECONNECT: u16 = 4;
+    /// Reconnection allowed to the consumer.
+    pub const RECONNECTION_ALLOWED: u16 = 5;
+    /// Load relay opened by the consumer.
+    pub const MANUAL_DISCONNECT: u16 = 6;
+    /// Load relay closed by the consumer.
+    pub const MANUAL_RECONNECT: u16 = 7;
+    /// Local disconnection on exceeding the power limit.
+    pub const LOCAL_DISCONNECT: u16 = 8;
+    /// Local reconnection once the power falls back under the limit.
+    pub const LOCAL_RECONNECT: u16 = 9;
+}
+
+/// Drives the physical output of Disconnect control objects.
+pub trait RelayDriver {
+    /// Closes (`true`, connects the consumer) or opens the output of
+    /// `disconnector`.
+    fn switch(&mut self, disconnector: &ObisCode, close: bool) -> io::Result<()>;
+
+    /// The output state the relay senses, when it reports one.
+    fn sense(&mut self, disconnector: &ObisCode) -> Option<bool> {
+        let _ = disconnector;
+        None
+    }
+}
+
+/// Where and how transitions are logged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwitchingLog {
+    /// The Profile generic journal the entries are appended to.
+    pub journal: ObisCode,
+    /// The Data object holding the code of the last event, if any.
+    pub event_object: Option<ObisCode>,
+    /// The event code of each transition, by its letter (`a` … `h`);
+    /// transitions without one are not logged.
+    pub codes: BTreeMap<char, u16>,
+    /// The event code logged when the relay fails to switch.
+    pub failure_code: Option<u16>,
+}
+
+impl SwitchingLog {
+    /// Logs into `journal` with the СПОДЭС codes of [`event_code`].
+    pub fn new(journal: ObisCode) -> Self {
+        let codes = [
+            ('a', event_code::RECONNECTION_ALLOWED),
+            ('b', event_code::REMOTE_DISCONNECT),
+            ('c', event_code::REMOTE_DISCONNECT),
+            ('d', event_code::REMOTE_RECONNECT),
+            ('e', event_code::MANUAL_RECONNECT),
+            ('f', event_code::MANUAL_DISCONNECT),
+            ('g', event_code::LOCAL_DISCONNECT),
+            ('h', event_code::LOCAL_RECONNECT),
+        ];
+        SwitchingLog { journal, event_object: None, codes: codes.into_iter().collect(), failure_code: None }
+    }
+
+    /// Writes each logged code to `event_object` too.
+    pub fn with_event_object(mut self, event_object: ObisCode) -> Self {
+        self.event_object = Some(event_object);
+        self
+    }
+
+    /// Sets the code of the transition `label`.
+    pub fn with_code(mut self, label: char, code: u16) -> Self {
+        self.codes.insert(label, code);
+        self
+    }
+
+    /// Logs relay failures with `code`.
+    pub fn with_failure_code(mut self, code: u16) -> Self {
+        self.failure_code = Some(code);
+        self
+    }
+}
+
+/// Errors raised while switching.
+#[derive(Debug)]
+pub enum SwitchingError {
+    /// The relay driver failed.
+    Relay(io::Error),
+    /// The relay reports the output unchanged after switching.
+    NotSwitched,
+    /// The journal or event object could not be updated.
+    Journal(AccessError),
+}
+
+impl fmt::Display for SwitchingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwitchingError::Relay(e) => write!(f, "relay failure: {e}"),
+            SwitchingError::NotSwitched => f.write_str("the relay did not switch"),
+            SwitchingError::Journal(e) => write!(f, "switching not logged: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SwitchingError {}
+
+impl From<io::Error> for SwitchingError {
+    fn from(e: io::Error) -> Self {
+        SwitchingError::Relay(e)
+    }
+}
+
+impl From<AccessError> for SwitchingError {
+    fn from(e: AccessError) -> Self {
+        SwitchingError::Journal(e)
+    }
+}
+
+/// One output change of [`DisconnectService::poll`].
+#[derive(Debug)]
+pub struct Switching {
+    /// Logical name of the Disconnect control.
+    pub disconnector: ObisCode,
+    /// The transition, or `None` when the relay changed on its own.
+    pub transition: Option<Transition>,
+    /// The resulting `output_state`.
+    pub output_state: bool,
+    /// Whether the relay switched and the event was logged.
+    pub result: Result<(), SwitchingError>,
+}
+
+/// Drives the Disconnect control objects of an object set.
+#[derive(Debug, Clone, Default)]
+pub struct DisconnectService {
+    limiters: BTreeMap<ObisCode, ObisCode>,
+    log: Option<SwitchingLog>,
+}
+
+impl DisconnectService {
+    /// Creates a service with no linked limiter and no journal.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Disconnects `disconnector` locally when `limiter` goes over its
+    /// threshold, and reconnects it locally when it falls back under.
+    pub fn with_limiter(mut self, limiter: ObisCode, disconnector: ObisCode) -> Self {
+        self.limiters.insert(limiter, disconnector);
+        self
+    }
+
+    /// Logs the transitions.
+    pub fn with_log(mut self, log: SwitchingLog) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    /// Applies a manual or local `trigger` to the Disconnect control
+    /// `disconnector`, returning the transition made, if the control mode
+    /// allows one.
+    pub fn trigger(
+        &self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        disconnector: &ObisCode,
+        trigger: Trigger,
+    ) -> Result<Option<Transition>, AccessError> {
+        let control = objects
+            .iter_mut()
+            .filter(|o| o.logical_name() == disconnector)
+            .find_map(|o| o.as_any_mut().downcast_mut::<DisconnectControl>())
+            .ok_or_else(|| AccessError::ObjectUndefined(format!("No Disconnect control {disconnector}")))?;
+        Ok(control.apply(trigger))
+    }
+
+    /// Applies the local transitions the limiter events of
+    /// [`ThresholdMonitor::evaluate`](crate::monitor::ThresholdMonitor::evaluate)
+    /// call for, returning them with their Disconnect control.
+    pub fn apply_limiter_events(
+        &self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        events: &[MonitorEvent],
+    ) -> Vec<(ObisCode, Transition)> {
+        events
+            .iter()
+            .filter_map(|event| {
+                let disconnector = self.limiters.get(&event.source)?;
+                let trigger = match event.event {
+                    ThresholdEvent::OverThreshold => Trigger::LocalDisconnect,
+                    ThresholdEvent::UnderThreshold => Trigger::LocalReconnect,
+                    _ => return None,
+                };
+                let transition = self.trigger(objects, disconnector, trigger).ok().flatten()?;
+                Some((*disconnector, transition))
+            })
+            .collect()
+    }
+
+    /// Takes the queued transitions of every Disconnect control in `objects`,
+    /// switches the relays, logs the transitions at `now` and records relay
+    /// feedback.
+    pub fn poll(
+        &mut self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        driver: &mut dyn RelayDriver,
+        now: &DateTime,
+    ) -> Vec<Switching> {
+        let mut switchings = Vec::new();
+        for index in 0..objects.len() {
+            let Some(control) = objects[index].as_any_mut().downcast_mut::<DisconnectControl>() else {
+                continue;
+            };
+            let disconnector = *control.logical_name();
+            for transition in control.take_transitions() {
+                let mut result = self.switch(objects, index, driver, &transition);
+                let output_state =
+                    objects[index].as_any().downcast_ref::<DisconnectControl>().map(|c| c.output_state());
+                let mut codes: Vec<u16> = Vec::new();
+                if let Some(log) = &self.log {
+                    codes.extend(log.codes.get(&transition.label()));
+                    if result.is_err() {
+                        codes.extend(log.failure_code);
+                    }
+                }
+                for code in codes {
+                    if let Err(e) = self.log_event(objects, code, now) {
+                        result = result.and(Err(e.into()));
+                    }
+                }
+                switchings.push(Switching {
+                    disconnector,
+                    transition: Some(transition),
+                    output_state: output_state.unwrap_or(transition.output_state()),
+                    result,
+                });
+            }
+            let Some(control) = objects[index].as_any_mut().downcast_mut::<DisconnectControl>() else {
+                continue;
+            };
+            if let Some(sensed) = driver.sense(&disconnector).filter(|&sensed| sensed != control.output_state()) {
+                control.record_output(sensed);
+                switchings.push(Switching { disconnector, transition: None, output_state: sensed, result: Ok(()) });
+            }
+        }
+        switchings
+    }
+
+    /// Switches the relay for a transition that opens or closes the output
+    /// and records the resulting output state.
+    fn switch(
+        &self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        index: usize,
+        driver: &mut dyn RelayDriver,
+        transition: &Transition,
+    ) -> Result<(), SwitchingError> {
+        let Some(control) = objects[index].as_any_mut().downcast_mut::<DisconnectControl>() else {
+            return Ok(());
+        };
+        let close = transition.output_state();
+        if close == (transition.from == control_state::CONNECTED) {
+            return Ok(());
+        }
+        let disconnector = *control.logical_name();
+        let result = driver.switch(&disconnector, close);
+        let output = driver.sense(&disconnector).unwrap_or(if result.is_ok() { close } else { !close });
+        control.record_output(output);
+        result?;
+        if output != close {
+            return Err(SwitchingError::NotSwitched);
+        }
+        Ok(())
+    }
+
+    /// Appends `code` to the journal and writes it to the event object.
+    fn log_event(&self, objects: &mut [Box<dyn InterfaceClass>], code: u16, now: &DateTime) -> Result<(), AccessError> {
+        let Some(log) = &self.log else {
+            return Ok(());
+        };
+        if let Some(event_object) = &log.event_object {
+            let object = objects
+                .iter_mut()
+                .find(|o| o.logical_name() == event_object)
+                .ok_or_else(|| AccessError::ObjectUndefined(format!("No event object {event_object}")))?;
+            object.set_attribute(2, CosemDataType::LongUnsigned(code))?;
+        }
+        let journal = objects
+            .iter_mut()
+            .filter(|o| *o.logical_name() == log.journal)
+            .find_map(|o| o.as_any_mut().downcast_mut::<ProfileGeneric>())
+            .ok_or_else(|| AccessError::ObjectUndefined(format!("No journal {}", log.journal)))?;
+        journal.append(CosemDataType::Structure(vec![
+            CosemDataType::OctetString(now.as_bytes().to_vec()),
+            CosemDataType::LongUnsigned(code),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::data::Data;
+    use crate::classes::disconnect_control::DisconnectControlConfig;
+    use crate::classes::limiter::{Limiter, LimiterConfig};
+    use crate::classes::profile_generic::ProfileGenericConfig;
+    use crate::classes::script_table::{ScriptTable, ScriptTableConfig};
+    use crate::monitor::ThresholdMonitor;
+    use crate::types::attrs::{ActionItem, EmergencyProfile, LimiterAction, SortMethod, ValueDefinition};
+
+    fn disconnector_ln() -> ObisCode {
+        ObisCode::new(0, 0, 96, 3, 10, 255)
+    }
+
+    fn journal_ln() -> ObisCode {
+        ObisCode::new(0, 0, 99, 98, 2, 255)
+    }
+
+    fn event_ln() -> ObisCode {
+        ObisCode::new(0, 0, 96, 11, 2, 255)
+    }
+
+    fn limiter_ln() -> ObisCode {
+        ObisCode::new(0, 0, 17, 0, 0, 255)
+    }
+
+    fn power_ln() -> ObisCode {
+        ObisCode::new(1, 0, 1, 7, 0, 255)
+    }
+
+    fn at(secs: i64) -> DateTime {
+        DateTime::from_unix(1_714_521_600 + secs, 180).unwrap()
+    }
+
+    fn objects(control_mode: u8) -> Vec<Box<dyn InterfaceClass>> {
+        vec![
+            Box::new(DisconnectControl::new(DisconnectControlConfig {
+                logical_name: disconnector_ln(),
+                output_state: true,
+                control_state: control_state::CONNECTED,
+                control_mode,
+            })),
+            Box::new(ProfileGeneric::new(ProfileGenericConfig {
+                logical_name: journal_ln(),
+                version: 1,
+                buffer: vec![],
+                capture_objects: vec![],
+                capture_period: 0,
+                sort_method: SortMethod::Fifo,
+                sort_object: None,
+                entries_in_use: 0,
+                profile_entries: 10,
+            })),
+            Box::new(Data::new(event_ln(), CosemDataType::LongUnsigned(0))),
+        ]
+    }
+
+    fn log() -> SwitchingLog {
+        SwitchingLog::new(journal_ln()).with_event_object(event_ln())
+    }
+
+    fn control(objects: &[Box<dyn InterfaceClass>]) -> &DisconnectControl {
+        objects[0].as_any().downcast_ref().unwrap()
+    }
+
+    fn journal(objects: &[Box<dyn InterfaceClass>]) -> Vec<CosemDataType> {
+        match objects[1].get_attribute(2) {
+            Some(CosemDataType::Array(entries)) => entries,
+            other => panic!("unexpected buffer {other:?}"),
+        }
+    }
+
+    /// A relay recording the switchings, failing or stuck on request.
+    #[derive(Default)]
+    struct Relay {
+        switched: Vec<bool>,
+        closed: Option<bool>,
+        fail: bool,
+        stuck: bool,
+    }
+
+    impl RelayDriver for Relay {
+        fn switch(&mut self, _: &ObisCode, close: bool) -> io::Result<()> {
+            if self.fail {
+                return Err(io::Error::other("coil open"));
+            }
+            self.switched.push(close);
+            if !self.stuck {
+                self.closed = Some(close);
+            }
+            Ok(())
+        }
+
+        fn sense(&mut self, _: &ObisCode) -> Option<bool> {
+            self.closed
+        }
+    }
+
+    #[test]
+    fn remote_transitions_switch_the_relay_and_are_logged() {
+        let mut objects = objects(1);
+        let mut relay = Relay::default();
+        let mut service = DisconnectService::new().with_log(log());
+
+        objects[0].invoke_method(1, None).unwrap();
+        let switchings = service.poll(&mut objects, &mut relay, &at(0));
+        assert_eq!(switchings.len(), 1);
+        assert!(switchings[0].result.is_ok());
+        assert_eq!(switchings[0].transition.map(|t| t.label()), Some('c'));
+        assert_eq!(relay.switched, [false]);
+
+        // a: reconnection allowed, the output stays open; e: the consumer closes it.
+        objects[0].invoke_method(2, None).unwrap();
+        service.trigger(&mut objects, &disconnector_ln(), Trigger::ManualReconnect).unwrap();
+        let switchings = service.poll(&mut objects, &mut relay, &at(60));
+        assert_eq!(switchings.iter().filter_map(|s| s.transition.map(|t| t.label())).collect::<String>(), "ae");
+        assert_eq!(relay.switched, [false, true]);
+        assert!(control(&objects).output_state());
+
+        let entries = journal(&objects);
+        let code = |entry: &CosemDataType| match entry {
+            CosemDataType::Structure(fields) => fields[1].clone(),
+            other => panic!("unexpected entry {other:?}"),
+        };
+        assert_eq!(entries.iter().map(code).collect::<Vec<_>>(), [3, 5, 7].map(CosemDataType::LongUnsigned).to_vec());
+        assert_eq!(
+            entries[0],
+            CosemDataType::Structure(vec![
+                CosemDataType::OctetString(at(0).as_bytes().to_vec()),
+                CosemDataType::LongUnsigned(3),
+            ])
+        );
+        assert_eq!(objects[2].get_attribute(2), Some(CosemDataType::LongUnsigned(7)));
+    }
+
+    #[test]
+    fn relay_failures_keep_the_output_and_are_reported() {
+        let mut objects = objects(2);
+        let mut relay = Relay { fail: true, closed: Some(true), ..Default::default() };
+        let mut service = DisconnectService::new().with_log(log().with_failure_code(40));
+
+        objects[0].invoke_method(1, None).unwrap();
+        let switchings = service.poll(&mut objects, &mut relay, &at(0));
+        assert!(matches!(switchings[0].result, Err(SwitchingError::Relay(_))));
+        assert!(switchings[0].output_state);
+        assert_eq!(control(&objects).control_state(), control_state::DISCONNECTED);
+        assert!(control(&objects).output_state());
+        assert_eq!(journal(&objects).len(), 2);
+        assert_eq!(objects[2].get_attribute(2), Some(CosemDataType::LongUnsigned(40)));
+
+        // A relay that accepts the command but does not move.
+        relay = Relay { stuck: true, closed: Some(false), ..Default::default() };
+        objects[0].invoke_method(2, None).unwrap();
+        let switchings = service.poll(&mut objects, &mut relay, &at(10));
+        assert!(matches!(switchings[0].result, Err(SwitchingError::NotSwitched)));
+        assert!(!control(&objects).output_state());
+
+        // Output changes sensed without a transition are picked up.
+        relay.closed = Some(true);
+        let switchings = service.poll(&mut objects, &mut relay, &at(20));
+        assert_eq!(switchings.len(), 1);
+        assert_eq!((switchings[0].transition, switchings[0].output_state), (None, true));
+        assert!(control(&objects).output_state());
+    }
+
+    #[test]
+    fn limiters_disconnect_locally() {
+        let scripts = ObisCode::new(0, 0, 10, 0, 106, 255);
+        let item = |selector| ActionItem { script_logical_name: scripts, script_selector: selector };
+        let mut objects = objects(5);
+        objects.push(Box::new(Data::new(power_ln(), CosemDataType::LongUnsigned(0))));
+        objects.push(Box::new(ScriptTable::new(ScriptTableConfig { logical_name: scripts, scripts: vec![] })));
+        objects.push(Box::new(Limiter::new(LimiterConfig {
+            logical_name: limiter_ln(),
+            monitored_value: ValueDefinition { class_id: 1, logical_name: power_ln(), attribute_index: 2 },
+            threshold_active: CosemDataType::LongUnsigned(100),
+            threshold_normal: CosemDataType::LongUnsigned(100),
+            threshold_emergency: CosemDataType::LongUnsigned(50),
+            min_over_threshold_duration: 0,
+            min_under_threshold_duration: 0,
+            emergency_profile: EmergencyProfile {
+                emergency_profile_id: 0,
+                emergency_activation_time: vec![],
+                emergency_duration: 0,
+            },
+            emergency_profile_group_id_list: vec![],
+            emergency_profile_active: false,
+            actions: LimiterAction { action_over_threshold: item(0), action_under_threshold: item(0) },
+        })));
+        let mut monitor = ThresholdMonitor::new();
+        let mut relay = Relay::default();
+        let mut service = DisconnectService::new().with_limiter(limiter_ln(), disconnector_ln()).with_log(log());
+
+        objects[3].set_attribute(2, CosemDataType::LongUnsigned(150)).unwrap();
+        let events = monitor.evaluate(&mut objects, &at(0));
+        let applied = service.apply_limiter_events(&mut objects, &events);
+        assert_eq!(applied.len(), 1);
+        assert_eq!((applied[0].0, applied[0].1.label()), (disconnector_ln(), 'g'));
+        service.poll(&mut objects, &mut relay, &at(0));
+        assert_eq!(relay.switched, [false]);
+        assert_eq!(control(&objects).control_state(), control_state::READY_FOR_RECONNECTION);
+
+        // Mode 5 reconnects locally once the load falls back.
+        objects[3].set_attribute(2, CosemDataType::LongUnsigned(20)).unwrap();
+        let events = monitor.evaluate(&mut objects, &at(10));
+        assert_eq!(service.apply_limiter_events(&mut objects, &events)[0].1.label(), 'h');
+        service.poll(&mut objects, &mut relay, &at(10));
+        assert_eq!(relay.switched, [false, true]);
+        let codes: Vec<_> = journal(&objects)
+            .into_iter()
+            .map(|entry| match entry {
+                CosemDataType::Structure(fields) => fields[1].clone(),
+                other => panic!("unexpected entry {other:?}"),
+            })
+            .collect();
+        assert_eq!(codes, [8, 9].map(CosemDataType::LongUnsigned).to_vec());
+        assert_eq!(objects[2].get_attribute(2), Some(CosemDataType::LongUnsigned(9)));
+    }
+}
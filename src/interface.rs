@@ -1,6 +1,8 @@
 use crate::obis::ObisCode;
+use crate::security::access_rights::{AttributeAccessMode, MethodAccessMode};
 use crate::types::{BerError, CosemDataType};
 use std::any::Any;
+use std::fmt;
 
 /// The trait shared by every COSEM interface class defined in IEC 62056-6-2.
 ///
@@ -20,9 +22,44 @@ pub trait InterfaceClass: Any {
     /// Returns the class attributes as `(attribute_id, value)` pairs.
     fn attributes(&self) -> Vec<(u8, CosemDataType)>;
 
+    /// Returns the value of a single attribute, or `None` if the class has no
+    /// attribute `attribute_id`.
+    ///
+    /// The default implementation clones a borrowed value from
+    /// [`attribute_ref`](Self::attribute_ref) or picks the attribute out of
+    /// [`attributes`](Self::attributes); the built-in classes override it so
+    /// that reading one attribute does not build the others (a profile buffer,
+    /// an object list).
+    fn get_attribute(&self, attribute_id: u8) -> Option<CosemDataType> {
+        if let Some(value) = self.attribute_ref(attribute_id) {
+            return Some(value.clone());
+        }
+        self.attributes().into_iter().find(|(id, _)| *id == attribute_id).map(|(_, value)| value)
+    }
+
+    /// Borrows an attribute that the object stores as a [`CosemDataType`]
+    /// (such as the `value` of a Data or Register object). Returns `None` for
+    /// attributes that are built on demand; use
+    /// [`get_attribute`](Self::get_attribute) for those.
+    fn attribute_ref(&self, attribute_id: u8) -> Option<&CosemDataType> {
+        let _ = attribute_id;
+        None
+    }
+
     /// Returns the class methods as `(method_id, name)` pairs.
     fn methods(&self) -> Vec<(u8, String)>;
 
+    /// Describes the class for the object's version: the names, types and
+    /// default access of its attributes and methods.
+    ///
+    /// The built-in classes return their IEC 62056-6-2 descriptors; the
+    /// default returns `None`, in which case the dispatcher does not check SET
+    /// types and [`full_access_entry`](crate::security::access_rights::full_access_entry)
+    /// grants access to every attribute and method the object lists.
+    fn metadata(&self) -> Option<ClassInfo> {
+        None
+    }
+
     /// Serializes the object into BER.
     ///
     /// # Arguments
@@ -52,10 +89,12 @@ pub trait InterfaceClass: Any {
     ///
     /// # Returns
     /// * `Ok(())` - If the attribute was written.
-    /// * `Err(String)` - If the attribute is not writable or the value is invalid.
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    /// * `Err(AccessError)` - The `data-access-result` explaining why not
+    ///   (read-write-denied for a read-only attribute, type-unmatched for a
+    ///   value of the wrong type, …).
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         let _ = (attribute_id, value);
-        Err("attribute is not writable".to_string())
+        Err(AccessError::ReadWriteDenied("attribute is not writable".to_string()))
     }
 
     /// Invokes the object method with the given id.
@@ -66,9 +105,380 @@ pub trait InterfaceClass: Any {
     ///
     /// # Returns
     /// * `Ok(CosemDataType)` - The method result.
-    /// * `Err(String)` - An error description if the method is not supported.
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String>;
+    /// * `Err(AccessError)` - The `action-result` explaining the failure
+    ///   (object-unavailable for an unsupported method, type-unmatched for
+    ///   malformed parameters, …).
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError>;
 
     /// Returns the object as `dyn Any` for dynamic downcasting.
     fn as_any(&self) -> &dyn Any;
+
+    /// Returns the object as mutable `dyn Any`, so runtimes driving an object
+    /// set (demand, capture, …) can update a concrete class's dynamic
+    /// attributes.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// Why writing an attribute or invoking a method failed: a `data-access-result`
+/// or `action-result` value of IEC 62056-5-3 (Blue Book), with a diagnostic
+/// message.
+///
+/// The request dispatcher answers SET and ACTION requests with
+/// [`code`](Self::code), so the client sees the precise result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessError {
+    /// `hardware-fault` (1).
+    HardwareFault(String),
+    /// `temporary-failure` (2): the object cannot serve the request in its
+    /// current state.
+    TemporaryFailure(String),
+    /// `read-write-denied` (3): the attribute is not writable or the
+    /// requester is not authorised.
+    ReadWriteDenied(String),
+    /// `object-undefined` (4).
+    ObjectUndefined(String),
+    /// `object-class-inconsistent` (9).
+    ObjectClassInconsistent(String),
+    /// `object-unavailable` (11): the attribute, method or referenced entry
+    /// does not exist.
+    ObjectUnavailable(String),
+    /// `type-unmatched` (12): the value or parameter has the wrong type or
+    /// shape.
+    TypeUnmatched(String),
+    /// `scope-of-access-violated` (13): the value is of the right type but
+    /// outside the permitted range.
+    ScopeOfAccessViolated(String),
+    /// `data-block-unavailable` (14).
+    DataBlockUnavailable(String),
+    /// `long-get-aborted` (15).
+    LongGetAborted(String),
+    /// `no-long-get-in-progress` (16).
+    NoLongGetInProgress(String),
+    /// `long-set-aborted` (17).
+    LongSetAborted(String),
+    /// `no-long-set-in-progress` (18).
+    NoLongSetInProgress(String),
+    /// `data-block-number-invalid` (19).
+    DataBlockNumberInvalid(String),
+    /// `long-action-aborted` (15 of `action-result`).
+    LongActionAborted(String),
+    /// `no-long-action-in-progress` (16 of `action-result`).
+    NoLongActionInProgress(String),
+    /// `other-reason` (250).
+    OtherReason(String),
+}
+
+impl AccessError {
+    /// The `data-access-result` / `action-result` code.
+    pub fn code(&self) -> u8 {
+        match self {
+            AccessError::HardwareFault(_) => 1,
+            AccessError::TemporaryFailure(_) => 2,
+            AccessError::ReadWriteDenied(_) => 3,
+            AccessError::ObjectUndefined(_) => 4,
+            AccessError::ObjectClassInconsistent(_) => 9,
+            AccessError::ObjectUnavailable(_) => 11,
+            AccessError::TypeUnmatched(_) => 12,
+            AccessError::ScopeOfAccessViolated(_) => 13,
+            AccessError::DataBlockUnavailable(_) => 14,
+            AccessError::LongGetAborted(_) | AccessError::LongActionAborted(_) => 15,
+            AccessError::NoLongGetInProgress(_) | AccessError::NoLongActionInProgress(_) => 16,
+            AccessError::LongSetAborted(_) => 17,
+            AccessError::NoLongSetInProgress(_) => 18,
+            AccessError::DataBlockNumberInvalid(_) => 19,
+            AccessError::OtherReason(_) => 250,
+        }
+    }
+
+    /// The diagnostic message.
+    pub fn message(&self) -> &str {
+        match self {
+            AccessError::HardwareFault(m)
+            | AccessError::TemporaryFailure(m)
+            | AccessError::ReadWriteDenied(m)
+            | AccessError::ObjectUndefined(m)
+            | AccessError::ObjectClassInconsistent(m)
+            | AccessError::ObjectUnavailable(m)
+            | AccessError::TypeUnmatched(m)
+            | AccessError::ScopeOfAccessViolated(m)
+            | AccessError::DataBlockUnavailable(m)
+            | AccessError::LongGetAborted(m)
+            | AccessError::NoLongGetInProgress(m)
+            | AccessError::LongSetAborted(m)
+            | AccessError::NoLongSetInProgress(m)
+            | AccessError::DataBlockNumberInvalid(m)
+            | AccessError::LongActionAborted(m)
+            | AccessError::NoLongActionInProgress(m)
+            | AccessError::OtherReason(m) => m,
+        }
+    }
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AccessError {}
+
+/// The data type an attribute holds or a method takes, as declared by
+/// IEC 62056-6-2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataKind {
+    /// A `CHOICE` of types (such as the `value` of a Register); any value is
+    /// accepted.
+    Any,
+    /// `null-data`.
+    Null,
+    /// `boolean`.
+    Boolean,
+    /// `bit-string`.
+    BitString,
+    /// `double-long`.
+    DoubleLong,
+    /// `double-long-unsigned`.
+    DoubleLongUnsigned,
+    /// `octet-string`.
+    OctetString,
+    /// `integer`.
+    Integer,
+    /// `long`.
+    Long,
+    /// `unsigned`.
+    Unsigned,
+    /// `long-unsigned`.
+    LongUnsigned,
+    /// `enum`.
+    Enum,
+    /// `date-time`, carried either as a `date-time` or as a 12-octet
+    /// `octet-string`.
+    DateTime,
+    /// `array`.
+    Array,
+    /// `structure`.
+    Structure,
+}
+
+impl DataKind {
+    /// Whether `value` is of this kind.
+    pub fn matches(self, value: &CosemDataType) -> bool {
+        matches!(
+            (self, value),
+            (DataKind::Any, _)
+                | (DataKind::Null, CosemDataType::Null)
+                | (DataKind::Boolean, CosemDataType::Boolean(_))
+                | (DataKind::BitString, CosemDataType::BitString(_))
+                | (DataKind::DoubleLong, CosemDataType::DoubleLong(_))
+                | (DataKind::DoubleLongUnsigned, CosemDataType::DoubleLongUnsigned(_))
+                | (DataKind::OctetString, CosemDataType::OctetString(_))
+                | (DataKind::Integer, CosemDataType::Integer(_))
+                | (DataKind::Long, CosemDataType::Long(_))
+                | (DataKind::Unsigned, CosemDataType::Unsigned(_))
+                | (DataKind::LongUnsigned, CosemDataType::LongUnsigned(_))
+                | (DataKind::Enum, CosemDataType::Enum(_))
+                | (DataKind::DateTime, CosemDataType::DateTime(_) | CosemDataType::OctetString(_))
+                | (DataKind::Array, CosemDataType::Array(_))
+                | (DataKind::Structure, CosemDataType::Structure(_))
+        )
+    }
+
+    /// The type name used by IEC 62056-6-2 (`double-long-unsigned`, …).
+    pub fn name(self) -> &'static str {
+        match self {
+            DataKind::Any => "CHOICE",
+            DataKind::Null => "null-data",
+            DataKind::Boolean => "boolean",
+            DataKind::BitString => "bit-string",
+            DataKind::DoubleLong => "double-long",
+            DataKind::DoubleLongUnsigned => "double-long-unsigned",
+            DataKind::OctetString => "octet-string",
+            DataKind::Integer => "integer",
+            DataKind::Long => "long",
+            DataKind::Unsigned => "unsigned",
+            DataKind::LongUnsigned => "long-unsigned",
+            DataKind::Enum => "enum",
+            DataKind::DateTime => "date-time",
+            DataKind::Array => "array",
+            DataKind::Structure => "structure",
+        }
+    }
+}
+
+impl fmt::Display for DataKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Describes one attribute of an interface class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttributeInfo {
+    /// The attribute id.
+    pub id: u8,
+    /// The attribute name, as spelled by IEC 62056-6-2.
+    pub name: &'static str,
+    /// The data type of the attribute.
+    pub kind: DataKind,
+    /// Whether the value changes by itself (a measured value, a buffer) rather
+    /// than only when it is written.
+    pub dynamic: bool,
+    /// Whether the class accepts writes to the attribute.
+    pub writable: bool,
+}
+
+impl AttributeInfo {
+    /// A static, read-only attribute.
+    pub const fn new(id: u8, name: &'static str, kind: DataKind) -> Self {
+        AttributeInfo { id, name, kind, dynamic: false, writable: false }
+    }
+
+    /// Marks the attribute as dynamic.
+    pub const fn dynamic(mut self) -> Self {
+        self.dynamic = true;
+        self
+    }
+
+    /// Marks the attribute as writable.
+    pub const fn writable(mut self) -> Self {
+        self.writable = true;
+        self
+    }
+
+    /// The access mode an object list grants by default: read-write for
+    /// writable attributes, read-only otherwise.
+    pub fn access_mode(&self) -> AttributeAccessMode {
+        if self.writable {
+            AttributeAccessMode::ReadWrite
+        } else {
+            AttributeAccessMode::ReadOnly
+        }
+    }
+}
+
+/// Describes one method of an interface class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MethodInfo {
+    /// The method id.
+    pub id: u8,
+    /// The method name, as spelled by IEC 62056-6-2.
+    pub name: &'static str,
+    /// The data type of the method parameter.
+    pub params: DataKind,
+}
+
+impl MethodInfo {
+    /// A method taking `params`.
+    pub const fn new(id: u8, name: &'static str, params: DataKind) -> Self {
+        MethodInfo { id, name, params }
+    }
+
+    /// The access mode an object list grants by default.
+    pub fn access_mode(&self) -> MethodAccessMode {
+        MethodAccessMode::Access
+    }
+}
+
+/// The metadata of an interface class at one version: its identification and
+/// the descriptors of its attributes and methods, in id order.
+///
+/// `Display` renders the class as a readable listing for tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassInfo {
+    /// The class name, as spelled by IEC 62056-6-2.
+    pub name: &'static str,
+    /// The class id.
+    pub class_id: u16,
+    /// The class version.
+    pub version: u8,
+    /// The attribute descriptors.
+    pub attributes: &'static [AttributeInfo],
+    /// The method descriptors.
+    pub methods: &'static [MethodInfo],
+}
+
+impl ClassInfo {
+    /// The descriptor of attribute `id`.
+    pub fn attribute(&self, id: u8) -> Option<&'static AttributeInfo> {
+        self.attributes.iter().find(|a| a.id == id)
+    }
+
+    /// The descriptor of method `id`.
+    pub fn method(&self, id: u8) -> Option<&'static MethodInfo> {
+        self.methods.iter().find(|m| m.id == id)
+    }
+
+    /// Checks that `value` is of the type of attribute `id`. Attributes the
+    /// class does not describe are left to the object to judge.
+    ///
+    /// # Errors
+    /// `TypeUnmatched` naming the expected type.
+    pub fn check_type(&self, id: u8, value: &CosemDataType) -> Result<(), AccessError> {
+        match self.attribute(id) {
+            Some(attribute) if !attribute.kind.matches(value) => {
+                Err(AccessError::TypeUnmatched(format!("{}.{} must be {}", self.name, attribute.name, attribute.kind)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ClassInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} (class_id {}, version {})", self.name, self.class_id, self.version)?;
+        for a in self.attributes {
+            let nature = if a.dynamic { "dynamic" } else { "static" };
+            let access = if a.writable { "rw" } else { "r" };
+            writeln!(f, "  {:>2}. {:<32} {:<22} {nature:<7} {access}", a.id, a.name, a.kind.name())?;
+        }
+        for m in self.methods {
+            writeln!(f, "  m{}. {}({})", m.id, m.name, m.params)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::data_access_result;
+
+    #[test]
+    fn access_errors_map_to_result_codes() {
+        let message = || "m".to_string();
+        assert_eq!(AccessError::TypeUnmatched(message()).code(), data_access_result::TYPE_UNMATCHED);
+        assert_eq!(AccessError::ReadWriteDenied(message()).code(), data_access_result::READ_WRITE_DENIED);
+        assert_eq!(AccessError::ScopeOfAccessViolated(message()).code(), data_access_result::SCOPE_OF_ACCESS_VIOLATED);
+        assert_eq!(AccessError::LongActionAborted(message()).code(), 15);
+        assert_eq!(AccessError::OtherReason(message()).code(), data_access_result::OTHER_REASON);
+        assert_eq!(AccessError::TemporaryFailure("busy".to_string()).to_string(), "busy");
+    }
+
+    #[test]
+    fn metadata_checks_set_types() {
+        let clock = crate::spodus::catalog::clock();
+        let info = clock.metadata().unwrap();
+        assert_eq!((info.class_id, info.version, info.name), (8, 0, "Clock"));
+        assert_eq!(info.attribute(3).map(|a| a.kind), Some(DataKind::Long));
+        assert!(info.attribute(2).is_some_and(|a| a.dynamic && a.writable));
+        assert!(info.check_type(3, &CosemDataType::Long(-180)).is_ok());
+        assert_eq!(
+            info.check_type(3, &CosemDataType::Unsigned(1)),
+            Err(AccessError::TypeUnmatched("Clock.time_zone must be long".to_string()))
+        );
+        // A date-time is accepted in either encoding; unknown attributes are left to the object.
+        assert!(info.check_type(2, &CosemDataType::OctetString(vec![0; 12])).is_ok());
+        assert!(info.check_type(42, &CosemDataType::Null).is_ok());
+        assert_eq!(info.method(6).map(|m| m.name), Some("shift_time"));
+    }
+
+    #[test]
+    fn class_info_renders_a_listing() {
+        use crate::classes::data::Data;
+        let data = Data::new(crate::obis::ObisCode::new(0, 0, 96, 1, 0, 255), CosemDataType::Null);
+        let listing = data.metadata().unwrap().to_string();
+        assert!(listing.starts_with("Data (class_id 1, version 0)\n"));
+        assert!(listing.contains("value") && listing.contains("CHOICE") && listing.contains("dynamic"));
+        assert!(listing.contains("m1. reset(integer)"));
+    }
 }
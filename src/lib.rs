@@ -37,6 +37,8 @@
 //! * [`types`] — the COSEM data types ([`CosemDataType`](types::CosemDataType))
 //!   and their A-XDR (BER) serialization.
 //! * [`obis`] — OBIS object identification codes ([`ObisCode`](obis::ObisCode)).
+//! * [`dictionary`] — readable names, expected classes and typical
+//!   [`Unit`](types::unit::Unit)s of known OBIS codes.
 //! * [`interface`] / [`classes`] — the COSEM interface classes (Data, Register,
 //!   Clock, Profile generic, Association LN, Security setup, …), all behind the
 //!   [`InterfaceClass`](interface::InterfaceClass) trait.
@@ -45,12 +47,27 @@
 //!   wrapper data-link sub-layers (IEC 62056-46 / IEC 62056-47).
 //! * [`service`] — the application-layer xDLMS services (GET/SET/ACTION,
 //!   notifications, association and ciphering APDUs), using LN referencing.
+//! * [`translator`] — lossless XML (DLMS UA XML) and JSON renderings of data
+//!   values and APDUs, for logs, test fixtures and tooling.
 //! * [`security`] — the security model: suites (0/1/2 and the GOST suite),
 //!   protection policy, the authentication mechanisms (0..10) and the ECDH/GOST
 //!   key-agreement primitives.
 //! * [`session`] — a blocking client-side driver
 //!   ([`ClientSession`](session::ClientSession)); [`server`] — a request
 //!   dispatcher ([`RequestDispatcher`](server::RequestDispatcher)).
+//! * [`scheduler`] — the time-driven runtime of the Schedule, Single action
+//!   schedule and Special days table objects
+//!   ([`Scheduler`](scheduler::Scheduler)); [`tariff`] — the Activity calendar
+//!   tariff engine ([`TariffEngine`](tariff::TariffEngine)); [`monitor`] —
+//!   Register monitor and Limiter threshold evaluation
+//!   ([`ThresholdMonitor`](monitor::ThresholdMonitor)); [`demand`] — block and
+//!   sliding demand ([`DemandEngine`](demand::DemandEngine)); [`push`] — Push
+//!   setup delivery ([`PushService`](push::PushService)); [`listener`] — push
+//!   reception at the head-end ([`PushListener`](listener::PushListener));
+//!   [`disconnect`] — Disconnect control relay driving, Limiter disconnection
+//!   and switching log ([`DisconnectService`](disconnect::DisconnectService)).
+//! * [`capture`] — periodic and requested Profile generic and Compact data
+//!   captures from the live objects ([`CaptureDriver`](capture::CaptureDriver)).
 //! * [`spodus`] — the СПОДУС ИВКЭ data-concentrator object model and the
 //!   [`Concentrator`](spodus::node::Concentrator) node.
 //!
@@ -121,6 +138,10 @@ pub mod types;
 /// OBIS object identification codes.
 pub mod obis;
 
+/// A dictionary of OBIS codes: expected interface class, description and
+/// typical unit of the Blue Book, СПОДЭС and СПОДУС objects.
+pub mod dictionary;
+
 /// The [`InterfaceClass`](interface::InterfaceClass) trait shared by all COSEM
 /// interface classes.
 pub mod interface;
@@ -139,6 +160,10 @@ pub mod transport;
 /// establishment (AARQ/AARE), per IEC 62056-5-3, using LN referencing.
 pub mod service;
 
+/// DLMS UA XML (Green Book XML) and JSON representations of COSEM data and
+/// xDLMS APDUs.
+pub mod translator;
+
 /// Security model: security suites (0/1/2), security policy (protection level)
 /// and the HLS/LLS authentication mechanisms (0..10), including the GOST profile
 /// of Р 1323565.1.
@@ -152,6 +177,38 @@ pub mod session;
 /// addressed COSEM object and returns the response APDU.
 pub mod server;
 
+/// Scheduling runtime: runs the scripts of due Schedule and Single action
+/// schedule entries, honouring special days and clock changes.
+pub mod scheduler;
+
+/// Tariff engine: resolves the Activity calendar's tariff, switches Register
+/// activation masks and lists tariff periods.
+pub mod tariff;
+
+/// Threshold evaluation: runs the scripts of Register monitor and Limiter
+/// objects when their monitored values cross the thresholds.
+pub mod monitor;
+
+/// Demand calculation: block and sliding demand for Demand register objects
+/// and their maximum-demand Extended registers.
+pub mod demand;
+
+/// Push delivery: sends the pushes of Push setup objects within their
+/// windows, with randomised start, retries, protection and confirmation.
+pub mod push;
+
+/// Push listener: receives, verifies, deciphers and decodes device pushes,
+/// answering confirmed ones.
+pub mod listener;
+
+/// Disconnect control runtime: drives the relays, disconnects on Limiter
+/// thresholds and logs the switching.
+pub mod disconnect;
+
+/// Capture driver: periodic, requested and missing-period captures of Profile
+/// generic and Compact data objects.
+pub mod capture;
+
 /// СПОДУС — the ИВКЭ concentrator/gateway information model
 /// (СТО 34.01-5.1-013-2023): meter aggregation upstream and pass-through access.
 pub mod spodus;
@@ -0,0 +1,951 @@
+//! Push listener: receives the DATA-NOTIFICATION and EVENT-NOTIFICATION pushes
+//! of devices at a head-end.
+//!
+//! [`PushListener`](crate::listener::PushListener) accepts TCP connections
+//! ([`PushListener::serve_tcp`](crate::listener::PushListener::serve_tcp)) or
+//! UDP datagrams
+//! ([`PushListener::serve_udp`](crate::listener::PushListener::serve_udp)), or
+//! reads any byte stream
+//! ([`PushListener::serve_stream`](crate::listener::PushListener::serve_stream)).
+//! TCP connections are read concurrently, each with a read timeout; a
+//! connection that fails or idles out is reported to the handler and the others
+//! are still served. The listener removes the wrapper or HDLC framing, then
+//! opens the APDU:
+//!
+//! * general-signing — the signature is verified with the public key of the
+//!   device named by the originator system title;
+//! * general-glo-ciphering, general-ded-ciphering and general-ciphering — the
+//!   APDU is deciphered with the global or dedicated context of the device
+//!   named by the system title;
+//! * glo-event-notification-request — deciphered with the global context of
+//!   the device pushing from the link address;
+//! * plain notifications — accepted from unknown devices, but not from a
+//!   device configured with ciphering keys.
+//!
+//! The decoded notification goes to the user handler with the device identity.
+//! With confirmations enabled, a confirmed DATA-NOTIFICATION (service class 1,
+//! see [`PushService`](crate::push::PushService)) is answered with a
+//! DATA-NOTIFICATION echoing its Long-Invoke-Id-And-Priority with a null body.
+
+use crate::push::SERVICE_CLASS_CONFIRMED;
+use crate::security::signature::{ecdsa_verify, SignError};
+use crate::security::SecuritySuite;
+use crate::service::ciphering::{self, glo, CipherError, SecurityContext};
+use crate::service::general_ciphering::{
+    GeneralCiphering, GeneralGloDedCiphering, GeneralSigning, KeyInfo, GENERAL_CIPHERING_TAG,
+    GENERAL_DED_CIPHERING_TAG, GENERAL_GLO_CIPHERING_TAG, GENERAL_SIGNING_TAG,
+};
+use crate::service::notification::{DataNotification, EventNotificationRequest};
+use crate::service::{push_length, tag, ServiceError};
+use crate::transport::hdlc::{Control, HdlcError, HdlcFrame};
+use crate::transport::wrapper::{self, WrapperError, WrapperHeader};
+use crate::types::CosemDataType;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::ops::ControlFlow;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// DLMS LLC header of an HDLC frame sent by a server (the pushing device).
+const LLC_RESPONSE: [u8; 3] = [0xE6, 0xE7, 0x00];
+
+/// DLMS LLC header of an HDLC frame sent by a client (the head-end).
+const LLC_REQUEST: [u8; 3] = [0xE6, 0xE6, 0x00];
+
+/// HDLC frame delimiter.
+const FLAG: u8 = 0x7E;
+
+/// Largest datagram or HDLC frame read.
+const MAX_PDU: usize = 65_535 + 8;
+
+/// Default time a TCP connection may stay silent before it is dropped.
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often [`PushListener::serve_tcp`] checks for new connections.
+const ACCEPT_POLL: Duration = Duration::from_millis(20);
+
+/// The framing of received pushes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// Wrapper PDUs (TCP, UDP).
+    Wrapper,
+    /// HDLC frames.
+    Hdlc,
+}
+
+/// A device allowed to push, with its keys.
+#[derive(Clone, Debug, Default)]
+pub struct PushDevice {
+    /// Identity reported to the handler (meter serial number, …).
+    pub id: Vec<u8>,
+    /// System title, naming the device in general-ciphering and
+    /// general-signing APDUs.
+    pub system_title: Vec<u8>,
+    /// Link address the device pushes from: the wrapper source wPort or the
+    /// HDLC source address.
+    pub address: Option<u32>,
+    /// Global unicast key context. When set (or `dedicated` is), plain pushes
+    /// from the device are rejected.
+    pub global: Option<SecurityContext>,
+    /// Dedicated key context, for general-ded-ciphering.
+    pub dedicated: Option<SecurityContext>,
+    /// Suite and public key verifying general-signing APDUs.
+    pub signature_key: Option<(SecuritySuite, Vec<u8>)>,
+}
+
+/// A decoded notification.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notification {
+    /// A DATA-NOTIFICATION (Push setup output).
+    Data(DataNotification),
+    /// An EVENT-NOTIFICATION-REQUEST.
+    Event(EventNotificationRequest),
+}
+
+impl Notification {
+    /// The pushed values: the elements of a DATA-NOTIFICATION array or
+    /// structure body (the body itself otherwise), or the event value.
+    pub fn values(&self) -> Vec<&CosemDataType> {
+        match self {
+            Notification::Data(data) => match &data.notification_body {
+                CosemDataType::Array(items) | CosemDataType::Structure(items) => items.iter().collect(),
+                body => vec![body],
+            },
+            Notification::Event(event) => vec![&event.value],
+        }
+    }
+}
+
+/// A received push.
+#[derive(Debug, Clone)]
+pub struct Received {
+    /// Identity of the sending device, when known.
+    pub device: Option<Vec<u8>>,
+    /// System title carried by a general-ciphering or general-signing APDU.
+    pub system_title: Option<Vec<u8>>,
+    /// Link address: the wrapper source wPort or the HDLC source address.
+    pub address: Option<u32>,
+    /// The peer socket address, when received from a socket.
+    pub peer: Option<SocketAddr>,
+    /// Whether the APDU was ciphered.
+    pub ciphered: bool,
+    /// Whether the APDU was signed (and the signature verified).
+    pub signed: bool,
+    /// The notification.
+    pub notification: Notification,
+    /// The framed confirmation to send back, when one is due.
+    pub reply: Option<Vec<u8>>,
+}
+
+/// Errors raised while receiving a push.
+#[derive(Debug)]
+pub enum ListenerError {
+    /// The wrapper PDU is malformed.
+    Wrapper(WrapperError),
+    /// The HDLC frame is malformed.
+    Hdlc(HdlcError),
+    /// The HDLC frame is not a UI frame carrying a server LLC header.
+    UnexpectedFrame,
+    /// A TCP connection failed, timed out or sent a malformed header; it is
+    /// closed and the other connections are still served.
+    Connection(SocketAddr, io::Error),
+    /// The APDU is malformed.
+    Service(ServiceError),
+    /// The APDU could not be deciphered.
+    Cipher(CipherError),
+    /// The signature did not verify.
+    Sign(SignError),
+    /// No device matches the system title or link address.
+    UnknownDevice,
+    /// The device has no key for the protection used.
+    NoKey(Vec<u8>),
+    /// A plain push arrived from a device configured with ciphering keys.
+    Unprotected(Vec<u8>),
+    /// A general-ciphering APDU uses key agreement, which is not supported.
+    KeyAgreement,
+    /// The APDU is not a (protected) notification.
+    UnexpectedApdu(u8),
+}
+
+impl fmt::Display for ListenerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenerError::Wrapper(e) => write!(f, "{e}"),
+            ListenerError::Hdlc(e) => write!(f, "{e}"),
+            ListenerError::UnexpectedFrame => f.write_str("HDLC frame is not a server UI frame"),
+            ListenerError::Connection(peer, e) => write!(f, "connection {peer}: {e}"),
+            ListenerError::Service(e) => write!(f, "malformed notification: {e:?}"),
+            ListenerError::Cipher(e) => write!(f, "deciphering failed: {e}"),
+            ListenerError::Sign(e) => write!(f, "signature check failed: {e}"),
+            ListenerError::UnknownDevice => f.write_str("unknown pushing device"),
+            ListenerError::NoKey(id) => write!(f, "no key for device {}", String::from_utf8_lossy(id)),
+            ListenerError::Unprotected(id) => write!(f, "plain push from device {}", String::from_utf8_lossy(id)),
+            ListenerError::KeyAgreement => f.write_str("general-ciphering key agreement is not supported"),
+            ListenerError::UnexpectedApdu(tag) => write!(f, "unexpected APDU tag {tag:#04x}"),
+        }
+    }
+}
+
+impl std::error::Error for ListenerError {}
+
+impl From<WrapperError> for ListenerError {
+    fn from(e: WrapperError) -> Self {
+        ListenerError::Wrapper(e)
+    }
+}
+
+impl From<HdlcError> for ListenerError {
+    fn from(e: HdlcError) -> Self {
+        ListenerError::Hdlc(e)
+    }
+}
+
+impl From<ServiceError> for ListenerError {
+    fn from(e: ServiceError) -> Self {
+        ListenerError::Service(e)
+    }
+}
+
+impl From<CipherError> for ListenerError {
+    fn from(e: CipherError) -> Self {
+        ListenerError::Cipher(e)
+    }
+}
+
+impl From<SignError> for ListenerError {
+    fn from(e: SignError) -> Self {
+        ListenerError::Sign(e)
+    }
+}
+
+/// An APDU with its protection removed.
+struct Opened {
+    device: Option<usize>,
+    system_title: Option<Vec<u8>>,
+    ciphered: bool,
+    signed: bool,
+    apdu: Vec<u8>,
+}
+
+/// What a connection reader thread of [`PushListener::serve_tcp`] reports.
+enum ConnectionEvent {
+    /// A wrapper PDU or HDLC frame arrived.
+    Pdu(usize, Vec<u8>),
+    /// The connection ended, cleanly or with an error.
+    Closed(usize, Option<io::Error>),
+}
+
+/// Receives and decodes device pushes.
+#[derive(Clone, Debug)]
+pub struct PushListener {
+    devices: Vec<PushDevice>,
+    confirm: bool,
+    read_timeout: Option<Duration>,
+}
+
+impl Default for PushListener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PushListener {
+    /// Creates a listener accepting plain pushes from any device.
+    pub fn new() -> Self {
+        Self { devices: Vec::new(), confirm: false, read_timeout: Some(DEFAULT_READ_TIMEOUT) }
+    }
+
+    /// Registers a device and its keys.
+    pub fn with_device(mut self, device: PushDevice) -> Self {
+        self.add_device(device);
+        self
+    }
+
+    /// Registers a device and its keys on a listener already built.
+    pub fn add_device(&mut self, device: PushDevice) {
+        self.devices.push(device);
+    }
+
+    /// Answers confirmed DATA-NOTIFICATIONs.
+    pub fn with_confirmations(mut self, confirm: bool) -> Self {
+        self.confirm = confirm;
+        self
+    }
+
+    /// Drops a TCP connection that stays silent for `timeout` (120 s by
+    /// default; `None` waits forever).
+    pub fn with_read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// The registered devices.
+    pub fn devices(&self) -> &[PushDevice] {
+        &self.devices
+    }
+
+    /// The registered devices, for updating their keys and counters.
+    pub fn devices_mut(&mut self) -> &mut [PushDevice] {
+        &mut self.devices
+    }
+
+    /// Accepts TCP connections until the handler breaks. Each connection is
+    /// read on its own thread and its pushes are decoded here, in arrival
+    /// order across connections. A connection that fails, idles past the
+    /// read timeout or sends a malformed header is closed and reported to the
+    /// handler as [`ListenerError::Connection`]; only a failing `listener`
+    /// ends the call with an error.
+    ///
+    /// `listener` is switched to non-blocking mode while serving.
+    pub fn serve_tcp<H>(&mut self, listener: &TcpListener, framing: Framing, mut handler: H) -> io::Result<()>
+    where
+        H: FnMut(Result<Received, ListenerError>) -> ControlFlow<()>,
+    {
+        listener.set_nonblocking(true)?;
+        let (events, arrivals) = mpsc::channel();
+        let mut connections: HashMap<usize, (SocketAddr, TcpStream)> = HashMap::new();
+        let mut next_id = 0;
+        let served = 'serve: loop {
+            // Take every pending connection.
+            loop {
+                let (stream, peer) = match listener.accept() {
+                    Ok(accepted) => accepted,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(e) if matches!(e.kind(), io::ErrorKind::ConnectionAborted | io::ErrorKind::Interrupted) => {
+                        continue
+                    }
+                    Err(e) => break 'serve Err(e),
+                };
+                match self.open_connection(next_id, stream, framing, events.clone()) {
+                    Ok(writer) => {
+                        connections.insert(next_id, (peer, writer));
+                        next_id += 1;
+                    }
+                    Err(e) => {
+                        if handler(Err(ListenerError::Connection(peer, e))).is_break() {
+                            break 'serve Ok(());
+                        }
+                    }
+                }
+            }
+            let flow = match arrivals.recv_timeout(ACCEPT_POLL) {
+                Ok(ConnectionEvent::Pdu(id, pdu)) => {
+                    let Some((peer, writer)) = connections.get_mut(&id) else { continue };
+                    let peer = *peer;
+                    let received =
+                        self.receive(framing, &pdu).map(|received| Received { peer: Some(peer), ..received });
+                    let written = match &received {
+                        Ok(Received { reply: Some(reply), .. }) => writer.write_all(reply),
+                        _ => Ok(()),
+                    };
+                    let mut flow = handler(received);
+                    if let Err(e) = written {
+                        if let Some((_, writer)) = connections.remove(&id) {
+                            let _ = writer.shutdown(Shutdown::Both);
+                        }
+                        if flow.is_continue() {
+                            flow = handler(Err(ListenerError::Connection(peer, e)));
+                        }
+                    }
+                    flow
+                }
+                Ok(ConnectionEvent::Closed(id, error)) => match (connections.remove(&id), error) {
+                    (Some((peer, _)), Some(e)) => handler(Err(ListenerError::Connection(peer, e))),
+                    _ => ControlFlow::Continue(()),
+                },
+                // The listener holds a sender, so the channel never disconnects.
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => ControlFlow::Continue(()),
+            };
+            if flow.is_break() {
+                break Ok(());
+            }
+        };
+        // Wake the reader threads of the open connections.
+        for (_, writer) in connections.into_values() {
+            let _ = writer.shutdown(Shutdown::Both);
+        }
+        listener.set_nonblocking(false)?;
+        served
+    }
+
+    /// Prepares an accepted connection and starts its reader thread; returns
+    /// the stream replies are written to.
+    fn open_connection(
+        &self,
+        id: usize,
+        stream: TcpStream,
+        framing: Framing,
+        events: Sender<ConnectionEvent>,
+    ) -> io::Result<TcpStream> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(self.read_timeout)?;
+        let writer = stream.try_clone()?;
+        thread::Builder::new().name("push-connection".to_string()).spawn(move || {
+            let mut stream = stream;
+            loop {
+                let pdu = match framing {
+                    Framing::Wrapper => read_wrapper_pdu(&mut stream),
+                    Framing::Hdlc => read_hdlc_frame(&mut stream),
+                };
+                let event = match pdu {
+                    Ok(Some(pdu)) => ConnectionEvent::Pdu(id, pdu),
+                    Ok(None) => ConnectionEvent::Closed(id, None),
+                    Err(e) => ConnectionEvent::Closed(id, Some(e)),
+                };
+                let closed = matches!(event, ConnectionEvent::Closed(..));
+                if events.send(event).is_err() || closed {
+                    return;
+                }
+            }
+        })?;
+        Ok(writer)
+    }
+
+    /// Receives datagrams, each one wrapper PDU or HDLC frame, until the
+    /// handler breaks. Confirmations go back to the sender.
+    pub fn serve_udp<H>(&mut self, socket: &UdpSocket, framing: Framing, mut handler: H) -> io::Result<()>
+    where
+        H: FnMut(Result<Received, ListenerError>) -> ControlFlow<()>,
+    {
+        let mut buf = vec![0u8; MAX_PDU];
+        loop {
+            let (n, peer) = socket.recv_from(&mut buf)?;
+            let received = self.receive(framing, &buf[..n]).map(|received| Received { peer: Some(peer), ..received });
+            if let Ok(Received { reply: Some(reply), .. }) = &received {
+                socket.send_to(reply, peer)?;
+            }
+            if handler(received).is_break() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Reads wrapper PDUs or HDLC frames from `stream` until it ends or the
+    /// handler breaks, writing confirmations back. Undecodable pushes go to
+    /// the handler as errors; a broken stream ends with an I/O error.
+    pub fn serve_stream<S, H>(
+        &mut self,
+        stream: &mut S,
+        peer: Option<SocketAddr>,
+        framing: Framing,
+        mut handler: H,
+    ) -> io::Result<ControlFlow<()>>
+    where
+        S: Read + Write,
+        H: FnMut(Result<Received, ListenerError>) -> ControlFlow<()>,
+    {
+        loop {
+            let pdu = match framing {
+                Framing::Wrapper => read_wrapper_pdu(stream)?,
+                Framing::Hdlc => read_hdlc_frame(stream)?,
+            };
+            let Some(pdu) = pdu else {
+                return Ok(ControlFlow::Continue(()));
+            };
+            let received = self.receive(framing, &pdu).map(|received| Received { peer, ..received });
+            if let Ok(Received { reply: Some(reply), .. }) = &received {
+                stream.write_all(reply)?;
+            }
+            if handler(received).is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+    }
+
+    /// Decodes one wrapper PDU or HDLC frame.
+    pub fn receive(&mut self, framing: Framing, pdu: &[u8]) -> Result<Received, ListenerError> {
+        match framing {
+            Framing::Wrapper => {
+                let (header, apdu) = wrapper::decode(pdu)?;
+                let mut received = self.receive_apdu(Some(u32::from(header.source)), &apdu)?;
+                received.reply = received
+                    .reply
+                    .map(|confirmation| wrapper::encode(header.destination, header.source, &confirmation));
+                Ok(received)
+            }
+            Framing::Hdlc => {
+                let frame = HdlcFrame::decode(pdu)?;
+                if !matches!(frame.control, Control::Ui { .. }) {
+                    return Err(ListenerError::UnexpectedFrame);
+                }
+                let apdu = frame.information.strip_prefix(&LLC_RESPONSE[..]).ok_or(ListenerError::UnexpectedFrame)?;
+                let mut received = self.receive_apdu(Some(frame.source.value), apdu)?;
+                received.reply = received.reply.map(|confirmation| {
+                    let information = [&LLC_REQUEST[..], &confirmation].concat();
+                    HdlcFrame::new(frame.source, frame.destination, Control::Ui { poll: false }, information).encode()
+                });
+                Ok(received)
+            }
+        }
+    }
+
+    /// Decodes one APDU pushed from the link `address`; the reply, if any, is
+    /// the unframed confirmation APDU.
+    pub fn receive_apdu(&mut self, address: Option<u32>, apdu: &[u8]) -> Result<Received, ListenerError> {
+        let opened = self.open(address, apdu)?;
+        let notification = match opened.apdu.first() {
+            Some(&tag::DATA_NOTIFICATION) => Notification::Data(DataNotification::decode(&opened.apdu)?),
+            _ => Notification::Event(EventNotificationRequest::decode(&opened.apdu)?),
+        };
+        let reply = match &notification {
+            Notification::Data(data)
+                if self.confirm && data.long_invoke_id_and_priority & SERVICE_CLASS_CONFIRMED != 0 =>
+            {
+                let confirmation = DataNotification {
+                    long_invoke_id_and_priority: data.long_invoke_id_and_priority,
+                    date_time: Vec::new(),
+                    notification_body: CosemDataType::Null,
+                };
+                Some(confirmation.encode()?)
+            }
+            _ => None,
+        };
+        Ok(Received {
+            device: opened.device.map(|index| self.devices[index].id.clone()),
+            system_title: opened.system_title,
+            address,
+            peer: None,
+            ciphered: opened.ciphered,
+            signed: opened.signed,
+            notification,
+            reply,
+        })
+    }
+
+    /// Verifies and deciphers the protection layers of `apdu`.
+    fn open(&mut self, address: Option<u32>, apdu: &[u8]) -> Result<Opened, ListenerError> {
+        let mut opened =
+            Opened { device: None, system_title: None, ciphered: false, signed: false, apdu: apdu.to_vec() };
+        loop {
+            let first = *opened.apdu.first().ok_or(ServiceError::Truncated)?;
+            match first {
+                GENERAL_SIGNING_TAG if !opened.signed && !opened.ciphered => {
+                    let signing = GeneralSigning::decode(&opened.apdu)?;
+                    let index = self.by_system_title(&signing.originator_system_title)?;
+                    let device = &self.devices[index];
+                    let (suite, key) =
+                        device.signature_key.as_ref().ok_or_else(|| ListenerError::NoKey(device.id.clone()))?;
+                    ecdsa_verify(*suite, key, &signing.signed_data(), &signing.signature)?;
+                    opened.device = Some(index);
+                    opened.system_title = Some(signing.originator_system_title);
+                    opened.signed = true;
+                    opened.apdu = signing.content;
+                }
+                GENERAL_GLO_CIPHERING_TAG | GENERAL_DED_CIPHERING_TAG if !opened.ciphered => {
+                    let general = GeneralGloDedCiphering::decode(&opened.apdu)?;
+                    let index = self.by_system_title(&general.system_title)?;
+                    opened.apdu = self.decipher(index, general.dedicated, first, &general.ciphered_service)?;
+                    opened.device = Some(index);
+                    opened.system_title = Some(general.system_title);
+                    opened.ciphered = true;
+                }
+                GENERAL_CIPHERING_TAG if !opened.ciphered => {
+                    let general = GeneralCiphering::decode(&opened.apdu)?;
+                    if general.key_info != KeyInfo::None {
+                        return Err(ListenerError::KeyAgreement);
+                    }
+                    let index = self.by_system_title(&general.originator_system_title)?;
+                    opened.apdu = self.decipher(index, false, first, &general.ciphered_content)?;
+                    opened.device = Some(index);
+                    opened.system_title = Some(general.originator_system_title);
+                    opened.ciphered = true;
+                }
+                glo::EVENT_NOTIFICATION_REQUEST if !opened.ciphered => {
+                    let index = match opened.device {
+                        Some(index) => index,
+                        None => self.by_address(address).ok_or(ListenerError::UnknownDevice)?,
+                    };
+                    let device = &mut self.devices[index];
+                    let context = device.global.as_mut().ok_or_else(|| ListenerError::NoKey(device.id.clone()))?;
+                    opened.apdu = ciphering::unprotect(context, &opened.apdu)?.1;
+                    opened.device = Some(index);
+                    opened.ciphered = true;
+                }
+                tag::DATA_NOTIFICATION | tag::EVENT_NOTIFICATION_REQUEST => {
+                    if opened.device.is_none() {
+                        opened.device = self.by_address(address);
+                    }
+                    if let Some(device) = opened.device.map(|index| &self.devices[index]) {
+                        if !opened.ciphered && !opened.signed && (device.global.is_some() || device.dedicated.is_some())
+                        {
+                            return Err(ListenerError::Unprotected(device.id.clone()));
+                        }
+                    }
+                    return Ok(opened);
+                }
+                other => return Err(ListenerError::UnexpectedApdu(other)),
+            }
+        }
+    }
+
+    /// Deciphers the `SC ‖ IC ‖ ciphertext ‖ tag` body of a general-ciphering
+    /// APDU with the device's global or dedicated context.
+    fn decipher(
+        &mut self,
+        index: usize,
+        dedicated: bool,
+        general_tag: u8,
+        ciphered: &[u8],
+    ) -> Result<Vec<u8>, ListenerError> {
+        let device = &mut self.devices[index];
+        let context = if dedicated { device.dedicated.as_mut() } else { device.global.as_mut() };
+        let context = context.ok_or_else(|| ListenerError::NoKey(device.id.clone()))?;
+        let mut apdu = vec![general_tag];
+        push_length(ciphered.len(), &mut apdu);
+        apdu.extend_from_slice(ciphered);
+        Ok(ciphering::unprotect(context, &apdu)?.1)
+    }
+
+    fn by_system_title(&self, system_title: &[u8]) -> Result<usize, ListenerError> {
+        self.devices.iter().position(|d| d.system_title == system_title).ok_or(ListenerError::UnknownDevice)
+    }
+
+    fn by_address(&self, address: Option<u32>) -> Option<usize> {
+        address.and_then(|address| self.devices.iter().position(|d| d.address == Some(address)))
+    }
+}
+
+/// Reads one wrapper PDU, or `None` at the end of the stream.
+fn read_wrapper_pdu(stream: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut pdu = vec![0u8; 8];
+    match stream.read_exact(&mut pdu) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let header = WrapperHeader::decode(&pdu)?;
+    pdu.resize(8 + usize::from(header.length), 0);
+    stream.read_exact(&mut pdu[8..])?;
+    Ok(Some(pdu))
+}
+
+/// Reads one HDLC frame (flags included), or `None` at the end of the stream.
+fn read_hdlc_frame(stream: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut octet = [0u8; 1];
+    // Skip to the opening flag, then past repeated flags.
+    let format = loop {
+        if stream.read(&mut octet)? == 0 {
+            return Ok(None);
+        }
+        if octet[0] == FLAG {
+            continue;
+        }
+        break octet[0];
+    };
+    let mut length = [0u8; 1];
+    stream.read_exact(&mut length)?;
+    let length = (usize::from(format & 0x07) << 8) | usize::from(length[0]);
+    let mut frame = vec![FLAG, format, length as u8];
+    frame.resize(1 + length.max(2) + 1, 0);
+    stream.read_exact(&mut frame[3..])?;
+    Ok(Some(frame))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obis::ObisCode;
+    use crate::security::SecurityPolicy;
+    use crate::service::AttributeDescriptor;
+    use crate::transport::hdlc::HdlcAddress;
+    use std::io::Cursor;
+
+    const METER_ST: [u8; 8] = *b"SIT\x00\x00\x00\x00\x07";
+
+    fn context(invocation_counter: u32) -> SecurityContext {
+        SecurityContext::for_suite(
+            SecurityPolicy::AuthenticationEncryption,
+            SecuritySuite::Suite0,
+            vec![0x11; 16],
+            vec![0x22; 16],
+            METER_ST.to_vec(),
+            invocation_counter,
+        )
+        .unwrap()
+    }
+
+    fn meter() -> PushDevice {
+        PushDevice {
+            id: b"METER-7".to_vec(),
+            system_title: METER_ST.to_vec(),
+            address: Some(0x10),
+            global: Some(context(0)),
+            ..Default::default()
+        }
+    }
+
+    fn data(long_invoke_id_and_priority: u32) -> DataNotification {
+        DataNotification {
+            long_invoke_id_and_priority,
+            date_time: Vec::new(),
+            notification_body: CosemDataType::Structure(vec![
+                CosemDataType::LongUnsigned(0x1234),
+                CosemDataType::Unsigned(9),
+            ]),
+        }
+    }
+
+    fn general_glo(apdu: &[u8], invocation_counter: u32) -> Vec<u8> {
+        let protected = ciphering::protect(&context(invocation_counter), GENERAL_GLO_CIPHERING_TAG, apdu).unwrap();
+        GeneralGloDedCiphering {
+            dedicated: false,
+            system_title: METER_ST.to_vec(),
+            ciphered_service: protected[2..].to_vec(),
+        }
+        .encode()
+    }
+
+    #[test]
+    fn plain_wrapped_data_notification_from_an_unknown_device() {
+        let mut listener = PushListener::new();
+        let pdu = wrapper::encode(1, 0x66, &data(1).encode().unwrap());
+        let received = listener.receive(Framing::Wrapper, &pdu).unwrap();
+        assert_eq!((received.device, received.address), (None, Some(1)));
+        assert!(!received.ciphered && !received.signed && received.reply.is_none());
+        assert_eq!(
+            received.notification.values(),
+            vec![&CosemDataType::LongUnsigned(0x1234), &CosemDataType::Unsigned(9)]
+        );
+    }
+
+    #[test]
+    fn ciphered_pushes_are_deciphered_with_the_device_keys() {
+        let mut listener = PushListener::new().with_device(meter());
+        let received = listener.receive_apdu(Some(1), &general_glo(&data(1).encode().unwrap(), 5)).unwrap();
+        assert_eq!(received.device, Some(b"METER-7".to_vec()));
+        assert_eq!(received.system_title, Some(METER_ST.to_vec()));
+        assert!(received.ciphered);
+        assert_eq!(received.notification, Notification::Data(data(1)));
+        // A replayed invocation counter is rejected.
+        assert!(matches!(
+            listener.receive_apdu(Some(1), &general_glo(&data(1).encode().unwrap(), 5)),
+            Err(ListenerError::Cipher(CipherError::ReplayDetected))
+        ));
+
+        // glo-event-notification is identified by the link address.
+        let event = EventNotificationRequest {
+            time: None,
+            attribute: AttributeDescriptor::new(1, ObisCode::new(0, 0, 96, 11, 0, 255), 2),
+            value: CosemDataType::LongUnsigned(0x21),
+        };
+        let glo_event =
+            ciphering::protect(&context(6), glo::EVENT_NOTIFICATION_REQUEST, &event.encode().unwrap()).unwrap();
+        assert!(matches!(listener.receive_apdu(Some(0x99), &glo_event), Err(ListenerError::UnknownDevice)));
+        let received = listener.receive_apdu(Some(0x10), &glo_event).unwrap();
+        assert_eq!(received.notification, Notification::Event(event.clone()));
+        assert_eq!(received.notification.values(), vec![&CosemDataType::LongUnsigned(0x21)]);
+
+        // Plain pushes from a device with keys are refused.
+        assert!(matches!(
+            listener.receive_apdu(Some(0x10), &event.encode().unwrap()),
+            Err(ListenerError::Unprotected(_))
+        ));
+        // Unknown system titles too.
+        let mut stranger = GeneralGloDedCiphering::decode(&general_glo(&data(1).encode().unwrap(), 7)).unwrap();
+        stranger.system_title = b"OTHER\x00\x00\x00".to_vec();
+        assert!(matches!(listener.receive_apdu(None, &stranger.encode()), Err(ListenerError::UnknownDevice)));
+    }
+
+    #[test]
+    fn signed_pushes_are_verified() {
+        let private_key = [0x42u8; 32];
+        let public_key = p256::ecdsa::SigningKey::from_slice(&private_key).unwrap();
+        let public_key = public_key.verifying_key().to_sec1_point(false).as_bytes().to_vec();
+        let device = PushDevice { signature_key: Some((SecuritySuite::Suite1, public_key)), ..meter() };
+        let mut listener = PushListener::new().with_device(device);
+
+        let mut signing = GeneralSigning {
+            transaction_id: Vec::new(),
+            originator_system_title: METER_ST.to_vec(),
+            recipient_system_title: Vec::new(),
+            date_time: Vec::new(),
+            other_information: Vec::new(),
+            content: general_glo(&data(3).encode().unwrap(), 1),
+            signature: Vec::new(),
+        };
+        signing.signature =
+            crate::security::signature::ecdsa_sign(SecuritySuite::Suite1, &private_key, &signing.signed_data())
+                .unwrap();
+        let received = listener.receive_apdu(None, &signing.encode()).unwrap();
+        assert!(received.signed && received.ciphered);
+        assert_eq!(received.notification, Notification::Data(data(3)));
+
+        signing.content = general_glo(&data(4).encode().unwrap(), 2);
+        assert!(matches!(listener.receive_apdu(None, &signing.encode()), Err(ListenerError::Sign(_))));
+    }
+
+    #[test]
+    fn confirmed_pushes_are_answered_in_the_same_framing() {
+        let mut listener = PushListener::new().with_confirmations(true);
+        let confirmed = data(SERVICE_CLASS_CONFIRMED | 9).encode().unwrap();
+
+        let received = listener.receive(Framing::Wrapper, &wrapper::encode(1, 0x66, &confirmed)).unwrap();
+        let (header, reply) = wrapper::decode(&received.reply.unwrap()).unwrap();
+        assert_eq!((header.source, header.destination), (0x66, 1));
+        let confirmation = DataNotification::decode(&reply).unwrap();
+        assert_eq!(confirmation.long_invoke_id_and_priority, SERVICE_CLASS_CONFIRMED | 9);
+
+        let information = [&LLC_RESPONSE[..], &confirmed].concat();
+        let frame = HdlcFrame::new(
+            HdlcAddress::one_byte(0x66),
+            HdlcAddress::one_byte(0x21),
+            Control::Ui { poll: false },
+            information,
+        );
+        let received = listener.receive(Framing::Hdlc, &frame.encode()).unwrap();
+        assert_eq!(received.address, Some(0x21));
+        let reply = HdlcFrame::decode(&received.reply.unwrap()).unwrap();
+        assert_eq!((reply.destination.value, reply.source.value), (0x21, 0x66));
+        assert_eq!(reply.information[..3], LLC_REQUEST);
+
+        // Unconfirmed pushes, or a listener not confirming, get no answer.
+        assert!(listener.receive_apdu(None, &data(9).encode().unwrap()).unwrap().reply.is_none());
+        assert!(PushListener::new().receive_apdu(None, &confirmed).unwrap().reply.is_none());
+    }
+
+    /// A byte stream reading `input` and recording the writes.
+    struct Duplex {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for Duplex {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Duplex {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn streams_are_split_into_pushes() {
+        let mut listener = PushListener::new().with_confirmations(true);
+        let mut input = wrapper::encode(1, 0x66, &data(SERVICE_CLASS_CONFIRMED | 1).encode().unwrap());
+        input.extend(wrapper::encode(1, 0x66, &[0xC4, 0x01]));
+        input.extend(wrapper::encode(1, 0x66, &data(2).encode().unwrap()));
+        let mut stream = Duplex { input: Cursor::new(input), output: Vec::new() };
+        let mut results = Vec::new();
+        let flow = listener
+            .serve_stream(&mut stream, None, Framing::Wrapper, |received| {
+                results.push(received);
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert!(flow.is_continue());
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[1], Err(ListenerError::UnexpectedApdu(0xC4))));
+        assert!(wrapper::decode(&stream.output).is_ok());
+
+        let frame = |apdu: &[u8]| {
+            let information = [&LLC_RESPONSE[..], apdu].concat();
+            HdlcFrame::new(
+                HdlcAddress::one_byte(0x66),
+                HdlcAddress::one_byte(0x21),
+                Control::Ui { poll: false },
+                information,
+            )
+            .encode()
+        };
+        let mut input = frame(&data(1).encode().unwrap());
+        input.extend(frame(&data(2).encode().unwrap()));
+        let mut stream = Duplex { input: Cursor::new(input), output: Vec::new() };
+        let mut count = 0;
+        let flow = listener
+            .serve_stream(&mut stream, None, Framing::Hdlc, |received| {
+                assert!(received.is_ok());
+                count += 1;
+                ControlFlow::Break(())
+            })
+            .unwrap();
+        assert!(flow.is_break());
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn tcp_connections_are_served_concurrently_past_failures() {
+        let socket = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            // An idle connection does not hold up the others.
+            let _idle = TcpStream::connect(address).unwrap();
+            // A malformed wrapper header closes only its own connection.
+            let mut broken = TcpStream::connect(address).unwrap();
+            broken.write_all(&[0x00, 0x07, 0, 1, 0, 0x66, 0, 0]).unwrap();
+            assert_eq!(broken.read(&mut [0u8; 8]).unwrap_or(0), 0);
+            let mut meter = TcpStream::connect(address).unwrap();
+            let push = data(SERVICE_CLASS_CONFIRMED | 4).encode().unwrap();
+            meter.write_all(&wrapper::encode(1, 0x66, &push)).unwrap();
+            read_wrapper_pdu(&mut meter).unwrap().expect("a confirmation")
+        });
+
+        let mut listener = PushListener::new().with_confirmations(true);
+        let mut failures = Vec::new();
+        let mut pushes = 0;
+        listener
+            .serve_tcp(&socket, Framing::Wrapper, |received| match received {
+                Ok(_) => {
+                    pushes += 1;
+                    ControlFlow::Break(())
+                }
+                Err(e) => {
+                    failures.push(e);
+                    ControlFlow::Continue(())
+                }
+            })
+            .unwrap();
+        assert_eq!(pushes, 1);
+        assert!(matches!(failures.as_slice(), [ListenerError::Connection(..)]));
+        let (_, confirmation) = wrapper::decode(&client.join().unwrap()).unwrap();
+        assert_eq!(confirmation[0], tag::DATA_NOTIFICATION);
+    }
+
+    #[test]
+    fn silent_tcp_connections_time_out() {
+        let socket = TcpListener::bind("127.0.0.1:0").unwrap();
+        let idle = TcpStream::connect(socket.local_addr().unwrap()).unwrap();
+        let mut listener = PushListener::new().with_read_timeout(Some(Duration::from_millis(50)));
+        let mut failure = None;
+        listener
+            .serve_tcp(&socket, Framing::Wrapper, |received| {
+                failure = received.err();
+                ControlFlow::Break(())
+            })
+            .unwrap();
+        let Some(ListenerError::Connection(peer, e)) = failure else { panic!("a connection failure") };
+        assert_eq!(peer, idle.local_addr().unwrap());
+        assert!(matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut));
+    }
+
+    #[test]
+    fn udp_datagrams_are_received_and_confirmed() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let push = data(SERVICE_CLASS_CONFIRMED | 4).encode().unwrap();
+        sender.send_to(&wrapper::encode(1, 0x66, &push), address).unwrap();
+
+        let mut listener = PushListener::new().with_confirmations(true);
+        let mut peer = None;
+        listener
+            .serve_udp(&socket, Framing::Wrapper, |received| {
+                peer = received.unwrap().peer;
+                ControlFlow::Break(())
+            })
+            .unwrap();
+        assert_eq!(peer, Some(sender.local_addr().unwrap()));
+        let mut buf = [0u8; 64];
+        let n = sender.recv(&mut buf).unwrap();
+        assert!(wrapper::decode(&buf[..n]).is_ok());
+    }
+}
@@ -0,0 +1,444 @@
+//! Threshold evaluation for the Register monitor (class 21) and Limiter
+//! (class 71) objects.
+//!
+//! The objects only hold thresholds and action sets;
+//! [`ThresholdMonitor::evaluate`](crate::monitor::ThresholdMonitor::evaluate)
+//! reads each monitored attribute from the object set, tracks threshold
+//! crossings and runs the referenced scripts with
+//! [`execute_script`](crate::scheduler::execute_script):
+//!
+//! * **Register monitor** (IEC 62056-6-2 §4.5.6) — when the value rises above
+//!   threshold *n* the `action_up` of action set *n* runs, when it falls back
+//!   to or below it `action_down` runs. The first evaluation only records the
+//!   current side of each threshold.
+//! * **Limiter** (§4.5.9) — the value is over the limit while it exceeds
+//!   `threshold_active`. `action_over_threshold` runs once it has stayed over
+//!   for `min_over_threshold_duration` seconds, `action_under_threshold` once
+//!   it has then stayed at or below for `min_under_threshold_duration`. While
+//!   the emergency profile is in force — its id is in the
+//!   `emergency_profile_group_id_list` and the time is within
+//!   `emergency_activation_time` + `emergency_duration` — the limiter is marked
+//!   `emergency_profile_active` and `threshold_emergency` is the active
+//!   threshold; `threshold_normal` is restored when it ends.
+//!
+//! Values are compared as integers of any width (see
+//! [`CosemDataType::as_i64`](crate::types::CosemDataType::as_i64)).
+
+use crate::classes::limiter::Limiter;
+use crate::classes::register_monitor::RegisterMonitor;
+use crate::interface::{AccessError, InterfaceClass};
+use crate::obis::ObisCode;
+use crate::scheduler::execute_script;
+use crate::types::attrs::{ActionItem, EmergencyProfile, ExecutedScript, ValueDefinition};
+use crate::types::datetime::DateTime;
+use crate::types::CosemDataType;
+use std::collections::BTreeMap;
+
+/// What happened to a monitor or limiter in an evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdEvent {
+    /// Register monitor: the value rose above the threshold at this index.
+    Up(usize),
+    /// Register monitor: the value fell to or below the threshold at this index.
+    Down(usize),
+    /// Limiter: the value stayed over the active threshold for the minimal
+    /// over-threshold duration.
+    OverThreshold,
+    /// Limiter: the value stayed at or under the active threshold for the
+    /// minimal under-threshold duration.
+    UnderThreshold,
+    /// Limiter: the emergency profile came into force.
+    EmergencyStarted,
+    /// Limiter: the emergency profile ended.
+    EmergencyEnded,
+    /// The monitored value could not be read or compared.
+    ValueUnavailable,
+}
+
+/// One event of [`ThresholdMonitor::evaluate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorEvent {
+    /// Logical name of the Register monitor or Limiter.
+    pub source: ObisCode,
+    /// What happened.
+    pub event: ThresholdEvent,
+    /// The script run for the event, if any.
+    pub script: Option<ExecutedScript>,
+    /// The result of the script, of updating the limiter, or why the value
+    /// is unavailable.
+    pub result: Result<(), AccessError>,
+}
+
+/// The crossing state of one limiter.
+#[derive(Debug, Clone, Copy, Default)]
+struct LimiterState {
+    over: bool,
+    pending_since: Option<i64>,
+}
+
+/// Evaluates the Register monitor and Limiter objects of an object set,
+/// keeping the state of each between evaluations.
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdMonitor {
+    monitors: BTreeMap<ObisCode, Vec<bool>>,
+    limiters: BTreeMap<ObisCode, LimiterState>,
+}
+
+impl ThresholdMonitor {
+    /// Creates a monitor with no recorded state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluates every Register monitor and Limiter in `objects` at `now`
+    /// and runs the scripts of the thresholds crossed. Limiters are skipped
+    /// when `now` is not fully specified.
+    pub fn evaluate(&mut self, objects: &mut [Box<dyn InterfaceClass>], now: &DateTime) -> Vec<MonitorEvent> {
+        let mut events = Vec::new();
+        for index in 0..objects.len() {
+            let any = objects[index].as_any();
+            if let Some(monitor) = any.downcast_ref::<RegisterMonitor>() {
+                let monitor = monitor.clone();
+                self.evaluate_monitor(objects, &monitor, &mut events);
+            } else if let Some(limiter) = any.downcast_ref::<Limiter>() {
+                let limiter = limiter.clone();
+                if let Some(now) = now.to_unix() {
+                    self.evaluate_limiter(objects, index, &limiter, now, &mut events);
+                }
+            }
+        }
+        events
+    }
+
+    /// Forgets the recorded state; the next evaluation starts afresh.
+    pub fn reset(&mut self) {
+        self.monitors.clear();
+        self.limiters.clear();
+    }
+
+    fn evaluate_monitor(
+        &mut self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        monitor: &RegisterMonitor,
+        events: &mut Vec<MonitorEvent>,
+    ) {
+        let source = *monitor.logical_name();
+        let sides = read(objects, monitor.monitored_value()).and_then(|value| {
+            monitor.thresholds().iter().map(|threshold| over(&value, threshold)).collect::<Result<Vec<_>, _>>()
+        });
+        let sides = match sides {
+            Ok(sides) => sides,
+            Err(e) => {
+                events.push(MonitorEvent {
+                    source,
+                    event: ThresholdEvent::ValueUnavailable,
+                    script: None,
+                    result: Err(e),
+                });
+                return;
+            }
+        };
+        let previous = self.monitors.insert(source, sides.clone());
+        let Some(previous) = previous.filter(|previous| previous.len() == sides.len()) else {
+            return;
+        };
+        // Rising crossings run from the lowest threshold up, falling ones down.
+        let rising = (0..sides.len()).filter(|&n| sides[n] && !previous[n]).map(|n| (n, ThresholdEvent::Up(n)));
+        let falling =
+            (0..sides.len()).rev().filter(|&n| !sides[n] && previous[n]).map(|n| (n, ThresholdEvent::Down(n)));
+        for (n, event) in rising.chain(falling) {
+            let Some(set) = monitor.actions().get(n) else {
+                continue;
+            };
+            let item = if matches!(event, ThresholdEvent::Up(_)) { &set.action_up } else { &set.action_down };
+            events.push(run(objects, source, event, item));
+        }
+    }
+
+    fn evaluate_limiter(
+        &mut self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        index: usize,
+        limiter: &Limiter,
+        now: i64,
+        events: &mut Vec<MonitorEvent>,
+    ) {
+        let source = *limiter.logical_name();
+        let mut threshold = limiter.threshold_active().clone();
+        let emergency =
+            limiter.emergency_profile_group_id_list().contains(&limiter.emergency_profile().emergency_profile_id)
+                && emergency_window(limiter.emergency_profile()).is_some_and(|(start, end)| start <= now && now < end);
+        if emergency != limiter.emergency_profile_active() {
+            threshold = if emergency { limiter.threshold_emergency() } else { limiter.threshold_normal() }.clone();
+            let target = &mut objects[index];
+            let result = target
+                .set_attribute(10, CosemDataType::Boolean(emergency))
+                .and_then(|()| target.set_attribute(3, threshold.clone()));
+            let event = if emergency { ThresholdEvent::EmergencyStarted } else { ThresholdEvent::EmergencyEnded };
+            events.push(MonitorEvent { source, event, script: None, result });
+        }
+
+        let over = match read(objects, limiter.monitored_value()).and_then(|value| over(&value, &threshold)) {
+            Ok(over) => over,
+            Err(e) => {
+                events.push(MonitorEvent {
+                    source,
+                    event: ThresholdEvent::ValueUnavailable,
+                    script: None,
+                    result: Err(e),
+                });
+                return;
+            }
+        };
+        let state = self.limiters.entry(source).or_default();
+        if over == state.over {
+            state.pending_since = None;
+            return;
+        }
+        let since = *state.pending_since.get_or_insert(now);
+        let (minimum, event, item) = if over {
+            (
+                limiter.min_over_threshold_duration(),
+                ThresholdEvent::OverThreshold,
+                &limiter.actions().action_over_threshold,
+            )
+        } else {
+            (
+                limiter.min_under_threshold_duration(),
+                ThresholdEvent::UnderThreshold,
+                &limiter.actions().action_under_threshold,
+            )
+        };
+        if now - since >= i64::from(minimum) {
+            *state = LimiterState { over, pending_since: None };
+            events.push(run(objects, source, event, item));
+        }
+    }
+}
+
+/// Reads the attribute a value definition refers to.
+fn read(objects: &[Box<dyn InterfaceClass>], definition: &ValueDefinition) -> Result<CosemDataType, AccessError> {
+    let object = objects
+        .iter()
+        .find(|o| o.class_id() == definition.class_id && *o.logical_name() == definition.logical_name)
+        .ok_or_else(|| {
+            AccessError::ObjectUndefined(format!(
+                "No object {} of class {}",
+                definition.logical_name, definition.class_id
+            ))
+        })?;
+    u8::try_from(definition.attribute_index).ok().and_then(|attribute| object.get_attribute(attribute)).ok_or_else(
+        || {
+            AccessError::ObjectUnavailable(format!(
+                "No attribute {} in {}",
+                definition.attribute_index, definition.logical_name
+            ))
+        },
+    )
+}
+
+/// Whether `value` is over `threshold`.
+fn over(value: &CosemDataType, threshold: &CosemDataType) -> Result<bool, AccessError> {
+    match (value.as_i64(), threshold.as_i64()) {
+        (Some(value), Some(threshold)) => Ok(value > threshold),
+        _ => Err(AccessError::TypeUnmatched(format!("Cannot compare {value:?} with threshold {threshold:?}"))),
+    }
+}
+
+/// The time range, in seconds since the Unix epoch, an emergency profile is
+/// in force.
+fn emergency_window(profile: &EmergencyProfile) -> Option<(i64, i64)> {
+    let bytes: [u8; 12] = profile.emergency_activation_time.as_slice().try_into().ok()?;
+    let start = DateTime::new(bytes).to_unix()?;
+    Some((start, start + i64::from(profile.emergency_duration)))
+}
+
+/// Runs the script of an action item and records it as an event.
+fn run(
+    objects: &mut [Box<dyn InterfaceClass>],
+    source: ObisCode,
+    event: ThresholdEvent,
+    item: &ActionItem,
+) -> MonitorEvent {
+    let script =
+        ExecutedScript { script_logical_name: item.script_logical_name, script_selector: item.script_selector };
+    let result = execute_script(objects, &script);
+    MonitorEvent { source, event, script: Some(script), result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::data::Data;
+    use crate::classes::limiter::LimiterConfig;
+    use crate::classes::register_monitor::RegisterMonitorConfig;
+    use crate::classes::script_table::{ScriptTable, ScriptTableConfig};
+    use crate::types::attrs::{ActionSet, ActionSpecification, LimiterAction, Script};
+
+    fn scripts_ln() -> ObisCode {
+        ObisCode::new(0, 0, 10, 0, 1, 255)
+    }
+
+    fn value_ln() -> ObisCode {
+        ObisCode::new(1, 0, 1, 7, 0, 255)
+    }
+
+    fn log_ln() -> ObisCode {
+        ObisCode::new(0, 0, 96, 1, 0, 255)
+    }
+
+    fn item(selector: u16) -> ActionItem {
+        ActionItem { script_logical_name: scripts_ln(), script_selector: selector }
+    }
+
+    fn monitored() -> ValueDefinition {
+        ValueDefinition { class_id: 1, logical_name: value_ln(), attribute_index: 2 }
+    }
+
+    /// The monitored Data value, a log Data object and a script table whose
+    /// script `n` writes `n` to the log.
+    fn objects(watcher: Box<dyn InterfaceClass>) -> Vec<Box<dyn InterfaceClass>> {
+        let scripts = (1..=4)
+            .map(|n| Script {
+                script_identifier: n,
+                actions: vec![ActionSpecification {
+                    service_id: 1,
+                    class_id: 1,
+                    logical_name: log_ln(),
+                    index: 2,
+                    parameter: CosemDataType::LongUnsigned(n),
+                }],
+            })
+            .collect();
+        vec![
+            Box::new(Data::new(value_ln(), CosemDataType::LongUnsigned(0))),
+            Box::new(Data::new(log_ln(), CosemDataType::LongUnsigned(0))),
+            Box::new(ScriptTable::new(ScriptTableConfig { logical_name: scripts_ln(), scripts })),
+            watcher,
+        ]
+    }
+
+    fn set_value(objects: &mut [Box<dyn InterfaceClass>], value: u16) {
+        objects[0].set_attribute(2, CosemDataType::LongUnsigned(value)).unwrap();
+    }
+
+    fn kinds(events: &[MonitorEvent]) -> Vec<ThresholdEvent> {
+        events.iter().map(|e| e.event).collect()
+    }
+
+    fn limiter(groups: Vec<u16>, activation: &DateTime) -> Box<dyn InterfaceClass> {
+        Box::new(Limiter::new(LimiterConfig {
+            logical_name: ObisCode::new(0, 0, 17, 0, 0, 255),
+            monitored_value: monitored(),
+            threshold_active: CosemDataType::LongUnsigned(100),
+            threshold_normal: CosemDataType::LongUnsigned(100),
+            threshold_emergency: CosemDataType::LongUnsigned(50),
+            min_over_threshold_duration: 10,
+            min_under_threshold_duration: 20,
+            emergency_profile: EmergencyProfile {
+                emergency_profile_id: 7,
+                emergency_activation_time: activation.as_bytes().to_vec(),
+                emergency_duration: 60,
+            },
+            emergency_profile_group_id_list: groups,
+            emergency_profile_active: false,
+            actions: LimiterAction { action_over_threshold: item(1), action_under_threshold: item(2) },
+        }))
+    }
+
+    fn at(secs: i64) -> DateTime {
+        DateTime::from_unix(1_714_521_600 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn register_monitor_runs_actions_on_crossings() {
+        let monitor = RegisterMonitor::new(RegisterMonitorConfig {
+            logical_name: ObisCode::new(0, 0, 16, 1, 0, 255),
+            thresholds: vec![CosemDataType::LongUnsigned(100), CosemDataType::LongUnsigned(200)],
+            monitored_value: monitored(),
+            actions: vec![
+                ActionSet { action_up: item(1), action_down: item(2) },
+                ActionSet { action_up: item(3), action_down: item(4) },
+            ],
+        });
+        let mut objects = objects(Box::new(monitor));
+        let mut watcher = ThresholdMonitor::new();
+        let log = |objects: &[Box<dyn InterfaceClass>]| objects[1].get_attribute(2);
+
+        set_value(&mut objects, 50);
+        assert!(watcher.evaluate(&mut objects, &at(0)).is_empty());
+
+        set_value(&mut objects, 250);
+        let events = watcher.evaluate(&mut objects, &at(1));
+        assert_eq!(kinds(&events), vec![ThresholdEvent::Up(0), ThresholdEvent::Up(1)]);
+        assert!(events.iter().all(|e| e.result.is_ok()));
+        assert_eq!(log(&objects), Some(CosemDataType::LongUnsigned(3)));
+
+        assert!(watcher.evaluate(&mut objects, &at(2)).is_empty());
+        set_value(&mut objects, 200);
+        assert_eq!(kinds(&watcher.evaluate(&mut objects, &at(3))), vec![ThresholdEvent::Down(1)]);
+        set_value(&mut objects, 0);
+        assert_eq!(kinds(&watcher.evaluate(&mut objects, &at(4))), vec![ThresholdEvent::Down(0)]);
+        assert_eq!(log(&objects), Some(CosemDataType::LongUnsigned(2)));
+    }
+
+    #[test]
+    fn limiter_waits_for_the_minimal_durations() {
+        let mut objects = objects(limiter(vec![], &at(0)));
+        let mut watcher = ThresholdMonitor::new();
+
+        set_value(&mut objects, 120);
+        assert!(watcher.evaluate(&mut objects, &at(0)).is_empty());
+        // A short dip does not count as under the threshold.
+        set_value(&mut objects, 80);
+        assert!(watcher.evaluate(&mut objects, &at(5)).is_empty());
+        set_value(&mut objects, 120);
+        assert!(watcher.evaluate(&mut objects, &at(6)).is_empty());
+        assert!(watcher.evaluate(&mut objects, &at(15)).is_empty());
+        let events = watcher.evaluate(&mut objects, &at(16));
+        assert_eq!(kinds(&events), vec![ThresholdEvent::OverThreshold]);
+        assert_eq!(events[0].script, Some(ExecutedScript { script_logical_name: scripts_ln(), script_selector: 1 }));
+        assert!(watcher.evaluate(&mut objects, &at(17)).is_empty());
+
+        set_value(&mut objects, 100);
+        assert!(watcher.evaluate(&mut objects, &at(20)).is_empty());
+        assert_eq!(kinds(&watcher.evaluate(&mut objects, &at(40))), vec![ThresholdEvent::UnderThreshold]);
+        assert_eq!(objects[1].get_attribute(2), Some(CosemDataType::LongUnsigned(2)));
+    }
+
+    #[test]
+    fn limiter_applies_the_emergency_profile() {
+        let mut objects = objects(limiter(vec![3, 7], &at(100)));
+        let mut watcher = ThresholdMonitor::new();
+        set_value(&mut objects, 80);
+        assert!(watcher.evaluate(&mut objects, &at(0)).is_empty());
+
+        let events = watcher.evaluate(&mut objects, &at(100));
+        assert_eq!(kinds(&events), vec![ThresholdEvent::EmergencyStarted]);
+        assert_eq!(objects[3].get_attribute(10), Some(CosemDataType::Boolean(true)));
+        assert_eq!(objects[3].get_attribute(3), Some(CosemDataType::LongUnsigned(50)));
+        assert_eq!(kinds(&watcher.evaluate(&mut objects, &at(110))), vec![ThresholdEvent::OverThreshold]);
+
+        let events = watcher.evaluate(&mut objects, &at(160));
+        assert_eq!(kinds(&events), vec![ThresholdEvent::EmergencyEnded]);
+        assert_eq!(objects[3].get_attribute(3), Some(CosemDataType::LongUnsigned(100)));
+        assert_eq!(kinds(&watcher.evaluate(&mut objects, &at(180))), vec![ThresholdEvent::UnderThreshold]);
+    }
+
+    #[test]
+    fn emergency_profile_needs_a_matching_group() {
+        let mut objects = objects(limiter(vec![3], &at(0)));
+        let mut watcher = ThresholdMonitor::new();
+        assert!(watcher.evaluate(&mut objects, &at(10)).is_empty());
+        assert_eq!(objects[3].get_attribute(10), Some(CosemDataType::Boolean(false)));
+    }
+
+    #[test]
+    fn unreadable_values_are_reported() {
+        let mut objects = objects(limiter(vec![], &at(0)));
+        objects.remove(0);
+        let events = ThresholdMonitor::new().evaluate(&mut objects, &at(0));
+        assert_eq!(kinds(&events), vec![ThresholdEvent::ValueUnavailable]);
+        assert!(matches!(events[0].result, Err(AccessError::ObjectUndefined(_))));
+    }
+}
@@ -1,9 +1,16 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 /// An OBIS code (Object Identification System) used by COSEM to identify
 /// objects. It is the six-octet `a.b.c.d.e.f` value group.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+///
+/// Codes order group by group (A first), so they can key ordered maps.
+/// [`FromStr`] accepts the common notations: `1-0:1.8.0*255`,
+/// `1-0:1.8.0.255`, `1-0:1.8.0` (F = 255), `1.0.1.8.0.255`, the
+/// `1-0.1.8.0.255` form printed by [`Display`](fmt::Display) and twelve hex
+/// digits (`0100010800FF`).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ObisCode {
     a: u8,
     b: u8,
@@ -30,7 +37,42 @@ impl ObisCode {
     /// # Returns
     /// A six-byte vector representing the OBIS code.
     pub fn to_bytes(&self) -> Vec<u8> {
-        vec![self.a, self.b, self.c, self.d, self.e, self.f]
+        self.groups().to_vec()
+    }
+
+    /// Returns the six value groups `[a, b, c, d, e, f]`.
+    pub fn groups(&self) -> [u8; 6] {
+        [self.a, self.b, self.c, self.d, self.e, self.f]
+    }
+
+    /// Value group A (media / energy type).
+    pub fn a(&self) -> u8 {
+        self.a
+    }
+
+    /// Value group B (channel).
+    pub fn b(&self) -> u8 {
+        self.b
+    }
+
+    /// Value group C (physical quantity).
+    pub fn c(&self) -> u8 {
+        self.c
+    }
+
+    /// Value group D (processing method).
+    pub fn d(&self) -> u8 {
+        self.d
+    }
+
+    /// Value group E (further classification, e.g. tariff).
+    pub fn e(&self) -> u8 {
+        self.e
+    }
+
+    /// Value group F (historical value / billing period).
+    pub fn f(&self) -> u8 {
+        self.f
     }
 }
 
@@ -39,3 +81,240 @@ impl fmt::Display for ObisCode {
         write!(f, "{}-{}.{}.{}.{}.{}", self.a, self.b, self.c, self.d, self.e, self.f)
     }
 }
+
+impl From<[u8; 6]> for ObisCode {
+    fn from([a, b, c, d, e, f]: [u8; 6]) -> Self {
+        ObisCode { a, b, c, d, e, f }
+    }
+}
+
+impl From<ObisCode> for [u8; 6] {
+    fn from(code: ObisCode) -> Self {
+        code.groups()
+    }
+}
+
+impl TryFrom<&[u8]> for ObisCode {
+    type Error = String;
+
+    /// Converts a six-octet string (a logical-name attribute value).
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; 6]>::try_from(bytes)
+            .map(ObisCode::from)
+            .map_err(|_| format!("an OBIS code has 6 octets, got {}", bytes.len()))
+    }
+}
+
+impl FromStr for ObisCode {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let hex = text.strip_prefix("0x").unwrap_or(text);
+        if hex.len() == 12 && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            let mut groups = [0u8; 6];
+            for (i, group) in groups.iter_mut().enumerate() {
+                *group = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|e| e.to_string())?;
+            }
+            return Ok(ObisCode::from(groups));
+        }
+        let mut groups = [0u8; 6];
+        for (group, (lo, hi)) in groups.iter_mut().zip(parse_groups(text)?) {
+            if lo != hi {
+                return Err(format!("`{text}`: an OBIS code cannot contain wildcards or ranges"));
+            }
+            *group = lo;
+        }
+        Ok(ObisCode::from(groups))
+    }
+}
+
+/// A pattern over OBIS codes: each value group is a single value, an
+/// inclusive range or a wildcard.
+///
+/// It is written like an OBIS code, with `*` for any value and `lo-hi` for a
+/// range: `1-0:1.8.0-4*255` (active energy import, total and tariffs 1..4),
+/// `0-0:96.11.*.255` or `1.0.*.8.0.255`. In the `A-B:…` notation the first
+/// `-` separates A from B; a missing F group means 255.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObisPattern {
+    groups: [(u8, u8); 6],
+}
+
+impl ObisPattern {
+    /// The pattern matching every code.
+    pub fn any() -> Self {
+        ObisPattern { groups: [(0, 255); 6] }
+    }
+
+    /// Restricts value group `index` (0 = A … 5 = F) to `lo..=hi`.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than 5.
+    pub fn with_group(mut self, index: usize, lo: u8, hi: u8) -> Self {
+        self.groups[index] = (lo.min(hi), lo.max(hi));
+        self
+    }
+
+    /// The inclusive range of value group `index` (0 = A … 5 = F).
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than 5.
+    pub fn group(&self, index: usize) -> (u8, u8) {
+        self.groups[index]
+    }
+
+    /// Whether `code` lies within every group of the pattern.
+    pub fn matches(&self, code: &ObisCode) -> bool {
+        self.groups.iter().zip(code.groups()).all(|(&(lo, hi), value)| (lo..=hi).contains(&value))
+    }
+
+    /// Whether the pattern matches exactly one code.
+    pub fn is_exact(&self) -> bool {
+        self.groups.iter().all(|(lo, hi)| lo == hi)
+    }
+}
+
+impl From<ObisCode> for ObisPattern {
+    fn from(code: ObisCode) -> Self {
+        ObisPattern { groups: code.groups().map(|v| (v, v)) }
+    }
+}
+
+impl FromStr for ObisPattern {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(ObisPattern { groups: parse_groups(text.trim())? })
+    }
+}
+
+impl fmt::Display for ObisPattern {
+    /// Writes the `A-B:C.D.E*F` notation.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let group = |(lo, hi): (u8, u8)| match (lo, hi) {
+            (0, 255) => "*".to_string(),
+            _ if lo == hi => lo.to_string(),
+            _ => format!("{lo}-{hi}"),
+        };
+        let [a, b, c, d, e, g] = self.groups.map(group);
+        write!(f, "{a}-{b}:{c}.{d}.{e}*{g}")
+    }
+}
+
+/// Parses the six value groups of an OBIS code or pattern; a missing F group
+/// is 255.
+fn parse_groups(text: &str) -> Result<[(u8, u8); 6], String> {
+    let tokens: Vec<&str> = if let Some((ab, rest)) = text.split_once(':') {
+        // `A-B:C.D.E*F`, `A-B:C.D.E.F` or `A-B:C.D.E`.
+        let (a, b) = ab.split_once('-').ok_or_else(|| format!("`{text}`: expected `A-B` before `:`"))?;
+        let mut tokens = vec![a, b];
+        let cde: Vec<&str> = rest.split('.').collect();
+        match cde.as_slice() {
+            [c, d, last] => {
+                tokens.extend([*c, *d]);
+                match last.rsplit_once('*') {
+                    Some((e, f)) if !e.is_empty() && !f.is_empty() => tokens.extend([e, f]),
+                    _ => tokens.push(last),
+                }
+            }
+            [c, d, e, f] => tokens.extend([*c, *d, *e, *f]),
+            _ => return Err(format!("`{text}`: expected C.D.E*F after `:`")),
+        }
+        tokens
+    } else {
+        let mut tokens: Vec<&str> = text.split('.').collect();
+        // The `A-B.C.D.E.F` form of `Display`.
+        if tokens.len() == 5 {
+            let (a, b) = tokens[0].split_once('-').ok_or_else(|| format!("`{text}`: expected six value groups"))?;
+            tokens.splice(0..1, [a, b]);
+        }
+        tokens
+    };
+    let mut groups = [(0u8, 255u8); 6];
+    match tokens.len() {
+        6 => {}
+        5 => groups[5] = (255, 255),
+        _ => return Err(format!("`{text}`: expected six value groups")),
+    }
+    for (group, token) in groups.iter_mut().zip(&tokens) {
+        *group = parse_group(token).ok_or_else(|| format!("`{text}`: invalid value group `{token}`"))?;
+    }
+    Ok(groups)
+}
+
+/// One value group: `n`, `lo-hi` or `*`.
+fn parse_group(token: &str) -> Option<(u8, u8)> {
+    let token = token.trim();
+    if token == "*" {
+        return Some((0, 255));
+    }
+    match token.split_once('-') {
+        Some((lo, hi)) => {
+            let (lo, hi) = (lo.trim().parse().ok()?, hi.trim().parse().ok()?);
+            (lo <= hi).then_some((lo, hi))
+        }
+        None => token.parse().ok().map(|v| (v, v)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeSet, HashMap};
+
+    #[test]
+    fn parses_common_notations() {
+        let energy = ObisCode::new(1, 0, 1, 8, 0, 255);
+        for text in ["1-0:1.8.0*255", "1-0:1.8.0.255", "1-0:1.8.0", "1.0.1.8.0.255", "0100010800FF", "0x0100010800ff"] {
+            assert_eq!(text.parse::<ObisCode>(), Ok(energy), "{text}");
+        }
+        assert_eq!(energy.to_string().parse::<ObisCode>(), Ok(energy));
+        assert_eq!((energy.a(), energy.c(), energy.f()), (1, 1, 255));
+        assert!("1-0:1.8.0-4*255".parse::<ObisCode>().is_err());
+        assert!("1.0.1.8.256.255".parse::<ObisCode>().is_err());
+        assert!("1.0.1.8".parse::<ObisCode>().is_err());
+
+        assert_eq!(ObisCode::try_from(&[0u8, 0, 96, 1, 0, 255][..]), Ok(ObisCode::new(0, 0, 96, 1, 0, 255)));
+        assert!(ObisCode::try_from(&[0u8; 5][..]).is_err());
+    }
+
+    #[test]
+    fn codes_are_map_keys_in_group_order() {
+        let codes: BTreeSet<ObisCode> =
+            ["1-0:2.8.0*255", "0-0:1.0.0*255", "1-0:1.8.1*255"].iter().map(|t| t.parse().unwrap()).collect();
+        let sorted: Vec<String> = codes.iter().map(ObisCode::to_string).collect();
+        assert_eq!(sorted, ["0-0.1.0.0.255", "1-0.1.8.1.255", "1-0.2.8.0.255"]);
+        let mut names = HashMap::new();
+        names.insert(ObisCode::new(0, 0, 1, 0, 0, 255), "clock");
+        assert_eq!(names.get(&"0.0.1.0.0.255".parse().unwrap()), Some(&"clock"));
+    }
+
+    #[test]
+    fn patterns_filter_object_lists() {
+        let tariffs: ObisPattern = "1-0:1.8.0-4*255".parse().unwrap();
+        assert_eq!(tariffs.group(4), (0, 4));
+        assert!(tariffs.matches(&ObisCode::new(1, 0, 1, 8, 3, 255)));
+        assert!(!tariffs.matches(&ObisCode::new(1, 0, 1, 8, 5, 255)));
+        assert_eq!(tariffs.to_string(), "1-0:1.8.0-4*255");
+
+        let events: ObisPattern = "0-0:96.11.*.255".parse().unwrap();
+        let list = [
+            ObisCode::new(0, 0, 96, 11, 0, 255),
+            ObisCode::new(0, 0, 96, 11, 7, 255),
+            ObisCode::new(0, 0, 96, 1, 0, 255),
+        ];
+        assert_eq!(list.iter().filter(|code| events.matches(code)).count(), 2);
+        assert_eq!(
+            "1.0.*.8.0.255".parse::<ObisPattern>(),
+            Ok(ObisPattern::any()
+                .with_group(0, 1, 1)
+                .with_group(1, 0, 0)
+                .with_group(3, 8, 8)
+                .with_group(4, 0, 0)
+                .with_group(5, 255, 255))
+        );
+        assert!(ObisPattern::from(list[0]).is_exact());
+        assert!("1-0:1.8.4-0*255".parse::<ObisPattern>().is_err());
+    }
+}
@@ -0,0 +1,1016 @@
+//! Push delivery runtime for the Push setup objects (class 40) of an object
+//! set.
+//!
+//! [`PushService::poll`](crate::push::PushService::poll) drives every Push
+//! setup of the set:
+//!
+//! * a push is triggered by the `push` method — a client ACTION or a script run
+//!   by the [`Scheduler`](crate::scheduler::Scheduler) — or by
+//!   [`PushService::trigger`](crate::push::PushService::trigger);
+//! * it starts after a random delay of up to `randomisation_start_interval`
+//!   seconds, inside one of the `communication_window`s (none: always); outside
+//!   them it waits, and the random delay restarts when a window opens;
+//! * a failed attempt is repeated up to `number_of_retries` times after
+//!   `repetition_delay`: fixed in versions 0 and 1, in version 2
+//!   `repetition_delay_min` · (`repetition_delay_exponent` / 100)ⁿ⁻¹ seconds
+//!   before retry *n*, capped at `repetition_delay_max` (0: no cap);
+//! * the first octet of each `push_protection_parameters` entry is its
+//!   protection type ([`protection_type`](crate::push::protection_type)): the
+//!   DATA-NOTIFICATION is ciphered into a general-glo-ciphering APDU and/or
+//!   signed into a general-signing APDU;
+//! * with `push_operation_method` 2 the push is confirmed: it is sent with the
+//!   confirmed service class and fails unless the destination answers with an
+//!   APDU within the confirmation timeout, and a confirmation is recorded in
+//!   `last_confirmation_date_time`. `confirmation_parameters` holds the
+//!   12-octet `confirmation_start_date` and the 4-octet `confirmation_interval`
+//!   (seconds): before the start date pushes are unconfirmed, and afterwards
+//!   only a push following a confirmation older than the interval is
+//!   confirmed. Empty parameters confirm every push. A head-end
+//!   [`PushListener`](crate::listener::PushListener) answers them.
+//!
+//! A [`PushConnector`](crate::push::PushConnector) opens the medium for
+//! `send_destination_and_method`:
+//! [`SocketConnector`](crate::push::SocketConnector) reaches `host:port`
+//! destinations over TCP and UDP, and the host supplies its own for HDLC lines.
+//! The service frames the APDU for the transport service: a wrapper PDU for TCP
+//! and UDP, an HDLC UI frame for HDLC. A confirmed push counts as confirmed
+//! only when the answer is a DATA-NOTIFICATION carrying the same
+//! Long-Invoke-Id-And-Priority.
+
+use crate::classes::push_setup::PushSetup;
+use crate::interface::{AccessError, InterfaceClass};
+use crate::obis::ObisCode;
+use crate::scheduler::local_seconds;
+use crate::security::signature::{ecdsa_sign, SignError};
+use crate::security::SecuritySuite;
+use crate::service::ciphering::{self, security_control, CipherError, SecurityContext};
+use crate::service::general_ciphering::{GeneralGloDedCiphering, GeneralSigning, GENERAL_GLO_CIPHERING_TAG};
+use crate::service::notification::DataNotification;
+use crate::service::{read_length, ServiceError};
+use crate::transport::hdlc::{Control, HdlcAddress, HdlcFrame};
+use crate::transport::wrapper::{self, WrapperHeader};
+use crate::transport::PhysicalTransport;
+use crate::types::attrs::{CommunicationWindow, DateTime};
+use crate::types::CosemDataType;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// Transport service identifiers of `send_destination_and_method` delivered
+/// by the service.
+pub mod transport_service {
+    /// TCP: wrapper PDUs.
+    pub const TCP: u8 = 0;
+    /// UDP: wrapper PDUs.
+    pub const UDP: u8 = 1;
+    /// HDLC: UI frames.
+    pub const HDLC: u8 = 5;
+}
+
+/// Protection types, the first octet of a `push_protection_parameters` entry.
+pub mod protection_type {
+    /// Authentication (GMAC).
+    pub const AUTHENTICATION: u8 = 0;
+    /// Encryption.
+    pub const ENCRYPTION: u8 = 1;
+    /// Authenticated encryption.
+    pub const AUTHENTICATED_ENCRYPTION: u8 = 2;
+    /// Digital signature.
+    pub const DIGITAL_SIGNATURE: u8 = 3;
+}
+
+/// `push_operation_method` of a confirmed push, retried on missing
+/// confirmation.
+const CONFIRMED: u8 = 2;
+
+/// The service-class bit of Long-Invoke-Id-And-Priority (1 = confirmed).
+pub(crate) const SERVICE_CLASS_CONFIRMED: u32 = 0x4000_0000;
+
+/// DLMS LLC header of an HDLC frame sent by a server.
+const LLC_RESPONSE: [u8; 3] = [0xE6, 0xE7, 0x00];
+
+/// DLMS LLC header of an HDLC frame sent by a client (the confirming head-end).
+const LLC_REQUEST: [u8; 3] = [0xE6, 0xE6, 0x00];
+
+/// Largest confirmation read.
+const MAX_CONFIRMATION: usize = 2048;
+
+/// Opens the medium a push is delivered over.
+pub trait PushConnector {
+    /// Opens a transport to `destination` (from `send_destination_and_method`)
+    /// for `transport_service`: a connection for TCP, a socket for UDP, a line
+    /// for HDLC.
+    fn connect(&mut self, transport_service: u8, destination: &[u8]) -> io::Result<Box<dyn PhysicalTransport>>;
+}
+
+/// Opens TCP connections and UDP sockets to `host:port` destinations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SocketConnector;
+
+impl PushConnector for SocketConnector {
+    fn connect(&mut self, transport_service: u8, destination: &[u8]) -> io::Result<Box<dyn PhysicalTransport>> {
+        let destination = std::str::from_utf8(destination)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "push destination is not a host:port"))?;
+        match transport_service {
+            transport_service::TCP => Ok(Box::new(TcpLink(TcpStream::connect(destination)?))),
+            transport_service::UDP => {
+                let address = destination
+                    .to_socket_addrs()?
+                    .next()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "push destination has no address"))?;
+                let local: SocketAddr = if address.is_ipv4() { ([0; 4], 0).into() } else { ([0u16; 8], 0).into() };
+                let socket = UdpSocket::bind(local)?;
+                socket.connect(address)?;
+                Ok(Box::new(UdpLink(socket)))
+            }
+            other => {
+                Err(io::Error::new(io::ErrorKind::Unsupported, format!("transport service {other} is not a socket")))
+            }
+        }
+    }
+}
+
+/// A TCP connection opened by [`SocketConnector`].
+struct TcpLink(TcpStream);
+
+impl PhysicalTransport for TcpLink {
+    fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        self.0.write_all(data)
+    }
+
+    fn receive(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.0.set_read_timeout(timeout)
+    }
+}
+
+/// A connected UDP socket opened by [`SocketConnector`].
+struct UdpLink(UdpSocket);
+
+impl PhysicalTransport for UdpLink {
+    fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        self.0.send(data).map(|_| ())
+    }
+
+    fn receive(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.recv(buf)
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.0.set_read_timeout(timeout)
+    }
+}
+
+/// Errors raised by a push attempt.
+#[derive(Debug)]
+pub enum PushError {
+    /// A push object or attribute is missing.
+    Access(AccessError),
+    /// The DATA-NOTIFICATION could not be encoded.
+    Service(ServiceError),
+    /// Ciphering failed.
+    Cipher(CipherError),
+    /// Signing failed.
+    Sign(SignError),
+    /// The transport failed.
+    Io(io::Error),
+    /// The transport service is not TCP, UDP or HDLC.
+    UnsupportedTransport(u8),
+    /// The protection parameters require a security context or signing key
+    /// the service was not given.
+    NoSecurity,
+    /// A confirmed push got no confirmation of its invoke id.
+    Unconfirmed,
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushError::Access(e) => write!(f, "push object: {e}"),
+            PushError::Service(e) => write!(f, "push encoding: {e:?}"),
+            PushError::Cipher(e) => write!(f, "push ciphering: {e}"),
+            PushError::Sign(e) => write!(f, "push signing: {e}"),
+            PushError::Io(e) => write!(f, "push transport: {e}"),
+            PushError::UnsupportedTransport(service) => write!(f, "unsupported push transport service {service}"),
+            PushError::NoSecurity => f.write_str("push protection requires a security context"),
+            PushError::Unconfirmed => f.write_str("push not confirmed"),
+        }
+    }
+}
+
+impl std::error::Error for PushError {}
+
+impl From<AccessError> for PushError {
+    fn from(e: AccessError) -> Self {
+        PushError::Access(e)
+    }
+}
+
+impl From<ServiceError> for PushError {
+    fn from(e: ServiceError) -> Self {
+        PushError::Service(e)
+    }
+}
+
+impl From<CipherError> for PushError {
+    fn from(e: CipherError) -> Self {
+        PushError::Cipher(e)
+    }
+}
+
+impl From<SignError> for PushError {
+    fn from(e: SignError) -> Self {
+        PushError::Sign(e)
+    }
+}
+
+impl From<io::Error> for PushError {
+    fn from(e: io::Error) -> Self {
+        PushError::Io(e)
+    }
+}
+
+/// How a push was delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Sent unconfirmed.
+    Sent,
+    /// Sent and confirmed by the destination.
+    Confirmed,
+}
+
+/// One delivery attempt made by [`PushService::poll`].
+#[derive(Debug)]
+pub struct PushAttempt {
+    /// The Push setup object.
+    pub setup: ObisCode,
+    /// 0 for the first attempt, then the retry number.
+    pub attempt: u8,
+    /// The outcome.
+    pub result: Result<Delivery, PushError>,
+    /// When the next retry is due after a failure; `None` once the push is
+    /// delivered or its retries are exhausted.
+    pub retry_at: Option<DateTime>,
+}
+
+/// A triggered push waiting for delivery.
+#[derive(Debug, Clone)]
+struct Pending {
+    /// Earliest attempt (local seconds); `None` until the random start delay
+    /// is drawn inside a communication window.
+    due: Option<i64>,
+    /// Attempts made so far.
+    attempts: u8,
+}
+
+/// Delivers the pushes of the Push setup objects of an object set.
+#[derive(Debug)]
+pub struct PushService {
+    pending: BTreeMap<ObisCode, Pending>,
+    invoke_id: u32,
+    ciphering: Option<SecurityContext>,
+    signing: Option<(SecuritySuite, Vec<u8>)>,
+    wrapper_port: u16,
+    hdlc_address: HdlcAddress,
+    confirmation_timeout: Duration,
+    rng: StdRng,
+}
+
+impl Default for PushService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PushService {
+    /// Creates a service sending from wPort 1 and HDLC address 1, waiting
+    /// 10 s for confirmations.
+    pub fn new() -> Self {
+        Self {
+            pending: BTreeMap::new(),
+            invoke_id: 0,
+            ciphering: None,
+            signing: None,
+            wrapper_port: 1,
+            hdlc_address: HdlcAddress::one_byte(1),
+            confirmation_timeout: Duration::from_secs(10),
+            rng: StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    /// Draws the random start delays from a generator seeded with `seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Ciphers protected pushes with `context` (its system title is the
+    /// originator's; its invocation counter advances with every push).
+    pub fn with_ciphering(mut self, context: SecurityContext) -> Self {
+        self.ciphering = Some(context);
+        self
+    }
+
+    /// Signs pushes requiring a digital signature with the ECDSA
+    /// `private_key` of `suite`.
+    pub fn with_signing(mut self, suite: SecuritySuite, private_key: Vec<u8>) -> Self {
+        self.signing = Some((suite, private_key));
+        self
+    }
+
+    /// Sends wrapper PDUs from wPort `port`.
+    pub fn with_wrapper_port(mut self, port: u16) -> Self {
+        self.wrapper_port = port;
+        self
+    }
+
+    /// Sends HDLC frames from `address`.
+    pub fn with_hdlc_address(mut self, address: HdlcAddress) -> Self {
+        self.hdlc_address = address;
+        self
+    }
+
+    /// Waits `timeout` for the answer to a confirmed push.
+    pub fn with_confirmation_timeout(mut self, timeout: Duration) -> Self {
+        self.confirmation_timeout = timeout;
+        self
+    }
+
+    /// Triggers the push of the Push setup `setup`, as its `push` method does.
+    /// A push already waiting is not restarted.
+    pub fn trigger(&mut self, setup: ObisCode) {
+        self.pending.entry(setup).or_insert(Pending { due: None, attempts: 0 });
+    }
+
+    /// Whether a push of `setup` is waiting for delivery.
+    pub fn is_pending(&self, setup: &ObisCode) -> bool {
+        self.pending.contains_key(setup)
+    }
+
+    /// Takes the triggers of the Push setup objects and makes the attempts due
+    /// at `now`, returning them.
+    pub fn poll(
+        &mut self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        connector: &mut dyn PushConnector,
+        now: &DateTime,
+    ) -> Vec<PushAttempt> {
+        let Some(local) = local_seconds(now) else {
+            return Vec::new();
+        };
+        let mut attempts = Vec::new();
+        for index in 0..objects.len() {
+            let Some(setup) = objects[index].as_any_mut().downcast_mut::<PushSetup>() else {
+                continue;
+            };
+            let logical_name = *setup.logical_name();
+            if setup.take_trigger() {
+                self.trigger(logical_name);
+            }
+            let Some(pending) = self.pending.get_mut(&logical_name) else {
+                continue;
+            };
+            let windows = setup.communication_window();
+            if !windows.is_empty() && !windows.iter().any(|w| in_window(w, now, local)) {
+                if pending.due.is_some_and(|due| due <= local) {
+                    pending.due = None;
+                }
+                continue;
+            }
+            let due = *pending.due.get_or_insert_with(|| {
+                local + self.rng.random_range(0..=i64::from(setup.randomisation_start_interval()))
+            });
+            if local < due {
+                continue;
+            }
+            let setup = setup.clone();
+            let attempt = pending.attempts;
+            let result = self.deliver(objects, connector, &setup, now, local);
+            let retry_at = match &result {
+                Ok(delivery) => {
+                    if *delivery == Delivery::Confirmed {
+                        if let Some(setup) = objects[index].as_any_mut().downcast_mut::<PushSetup>() {
+                            setup.record_confirmation(now.clone());
+                        }
+                    }
+                    None
+                }
+                Err(_) if attempt < setup.number_of_retries() => {
+                    let retry = local + repetition_delay(setup.repetition_delay(), attempt + 1);
+                    if let Some(pending) = self.pending.get_mut(&logical_name) {
+                        pending.attempts += 1;
+                        pending.due = Some(retry);
+                    }
+                    instant(retry, now)
+                }
+                Err(_) => None,
+            };
+            if retry_at.is_none() {
+                self.pending.remove(&logical_name);
+            }
+            attempts.push(PushAttempt { setup: logical_name, attempt, result, retry_at });
+        }
+        attempts
+    }
+
+    /// Builds, protects, frames and sends one push.
+    fn deliver(
+        &mut self,
+        objects: &[Box<dyn InterfaceClass>],
+        connector: &mut dyn PushConnector,
+        setup: &PushSetup,
+        now: &DateTime,
+        local: i64,
+    ) -> Result<Delivery, PushError> {
+        let confirmed = is_confirmed(setup, local);
+        self.invoke_id = (self.invoke_id + 1) & 0x00FF_FFFF;
+        let long_invoke_id_and_priority = self.invoke_id | if confirmed { SERVICE_CLASS_CONFIRMED } else { 0 };
+        let notification = DataNotification {
+            long_invoke_id_and_priority,
+            date_time: now.as_bytes().to_vec(),
+            notification_body: notification_body(objects, setup)?,
+        };
+        let apdu = self.protect(setup, notification.encode()?)?;
+
+        let destination = setup.send_destination_and_method();
+        let sap = u8::try_from(setup.push_client_sap()).unwrap_or_default();
+        let frame = match destination.transport_service {
+            transport_service::TCP | transport_service::UDP => {
+                wrapper::encode(self.wrapper_port, u16::from(sap), &apdu)
+            }
+            transport_service::HDLC => {
+                let information = [&LLC_RESPONSE[..], &apdu].concat();
+                let control = Control::Ui { poll: false };
+                HdlcFrame::new(HdlcAddress::one_byte(sap), self.hdlc_address, control, information).encode()
+            }
+            other => return Err(PushError::UnsupportedTransport(other)),
+        };
+        let mut transport = connector.connect(destination.transport_service, &destination.destination)?;
+        transport.send(&frame)?;
+        if !confirmed {
+            return Ok(Delivery::Sent);
+        }
+        transport.set_read_timeout(Some(self.confirmation_timeout))?;
+        match read_confirmation(transport.as_mut(), destination.transport_service) {
+            Ok(Some(apdu))
+                if DataNotification::decode(&apdu)
+                    .is_ok_and(|answer| answer.long_invoke_id_and_priority == long_invoke_id_and_priority) =>
+            {
+                Ok(Delivery::Confirmed)
+            }
+            Ok(_) => Err(PushError::Unconfirmed),
+            Err(e) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => {
+                Err(PushError::Unconfirmed)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Applies the protection the push protection parameters require.
+    fn protect(&mut self, setup: &PushSetup, apdu: Vec<u8>) -> Result<Vec<u8>, PushError> {
+        let types: Vec<u8> =
+            setup.push_protection_parameters().iter().filter_map(|p| p.data.first().copied()).collect();
+        let required = |kinds: &[u8]| types.iter().any(|t| kinds.contains(t));
+        let authentication = required(&[protection_type::AUTHENTICATION, protection_type::AUTHENTICATED_ENCRYPTION]);
+        let encryption = required(&[protection_type::ENCRYPTION, protection_type::AUTHENTICATED_ENCRYPTION]);
+        let mut apdu = apdu;
+        if authentication || encryption {
+            let context = self.ciphering.as_mut().ok_or(PushError::NoSecurity)?;
+            let mut push_context = context.clone();
+            push_context.security_control = context.security_control & 0x0F
+                | if authentication { security_control::AUTHENTICATION } else { 0 }
+                | if encryption { security_control::ENCRYPTION } else { 0 };
+            let protected = ciphering::protect(&push_context, GENERAL_GLO_CIPHERING_TAG, &apdu)?;
+            // general-glo-ciphering carries SC ‖ IC ‖ ciphertext ‖ tag after
+            // the originator system title.
+            let (_, header) = read_length(&protected[1..])?;
+            apdu = GeneralGloDedCiphering {
+                dedicated: false,
+                system_title: context.system_title.clone(),
+                ciphered_service: protected[1 + header..].to_vec(),
+            }
+            .encode();
+            context.invocation_counter = context.invocation_counter.wrapping_add(1);
+        }
+        if required(&[protection_type::DIGITAL_SIGNATURE]) {
+            let (suite, key) = self.signing.as_ref().ok_or(PushError::NoSecurity)?;
+            let mut signed = GeneralSigning {
+                transaction_id: Vec::new(),
+                originator_system_title: self.ciphering.as_ref().map(|c| c.system_title.clone()).unwrap_or_default(),
+                recipient_system_title: Vec::new(),
+                date_time: Vec::new(),
+                other_information: Vec::new(),
+                content: apdu,
+                signature: Vec::new(),
+            };
+            signed.signature = ecdsa_sign(*suite, key, &signed.signed_data())?;
+            apdu = signed.encode();
+        }
+        Ok(apdu)
+    }
+}
+
+/// Reads the push object list into a notification body: the single value, or
+/// an array of the values.
+fn notification_body(objects: &[Box<dyn InterfaceClass>], setup: &PushSetup) -> Result<CosemDataType, AccessError> {
+    let mut values = setup
+        .push_object_list()
+        .iter()
+        .map(|entry| {
+            let object = objects
+                .iter()
+                .find(|o| o.class_id() == entry.class_id && *o.logical_name() == entry.logical_name)
+                .ok_or_else(|| {
+                    AccessError::ObjectUndefined(format!(
+                        "Push object {} (class {}) not found",
+                        entry.logical_name, entry.class_id
+                    ))
+                })?;
+            object.get_attribute(entry.attribute_index).ok_or_else(|| {
+                AccessError::ObjectUnavailable(format!(
+                    "Push object {} has no attribute {}",
+                    entry.logical_name, entry.attribute_index
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(if values.len() == 1 { values.remove(0) } else { CosemDataType::Array(values) })
+}
+
+/// Reads the answer to a confirmed push until one wrapper PDU or HDLC frame
+/// is complete, returning its APDU; `None` when the transport closes first.
+fn read_confirmation(transport: &mut dyn PhysicalTransport, transport_service: u8) -> io::Result<Option<Vec<u8>>> {
+    let mut answer = Vec::new();
+    let mut chunk = [0u8; MAX_CONFIRMATION];
+    while answer.len() < MAX_CONFIRMATION {
+        let n = transport.receive(&mut chunk)?;
+        if n == 0 {
+            return Ok(None);
+        }
+        answer.extend_from_slice(&chunk[..n]);
+        if let Some(apdu) = unframe_confirmation(&answer, transport_service) {
+            return Ok(Some(apdu));
+        }
+    }
+    Ok(None)
+}
+
+/// The APDU of a complete wrapper PDU or HDLC UI frame, if `answer` holds one.
+/// A complete frame that carries no client APDU yields an empty APDU.
+fn unframe_confirmation(answer: &[u8], transport_service: u8) -> Option<Vec<u8>> {
+    if transport_service == transport_service::HDLC {
+        let frame = HdlcFrame::decode(answer).ok()?;
+        let apdu = match frame.control {
+            Control::Ui { .. } => frame.information.strip_prefix(&LLC_REQUEST[..]).unwrap_or_default(),
+            _ => &[],
+        };
+        return Some(apdu.to_vec());
+    }
+    let header = WrapperHeader::decode(answer).ok()?;
+    answer.get(8..8 + usize::from(header.length)).map(<[u8]>::to_vec)
+}
+
+/// Whether a push at `local` is confirmed (see the module documentation).
+fn is_confirmed(setup: &PushSetup, local: i64) -> bool {
+    if setup.push_operation_method() != CONFIRMED {
+        return false;
+    }
+    let parameters = &setup.confirmation_parameters().data;
+    let (Some(start), Some(interval)) = (parameters.get(..12), parameters.get(12..16)) else {
+        return true;
+    };
+    let start = DateTime::new(start.try_into().unwrap_or([0xFF; 12]));
+    let interval = i64::from(u32::from_be_bytes(interval.try_into().unwrap_or_default()));
+    if local_seconds(&start).is_some_and(|start| local < start) {
+        return false;
+    }
+    local_seconds(setup.last_confirmation_date_time()).is_none_or(|last| local - last >= interval)
+}
+
+/// The delay in seconds before retry `retry` (from 1).
+fn repetition_delay(delay: &CosemDataType, retry: u8) -> i64 {
+    match delay {
+        CosemDataType::Structure(fields) if fields.len() == 3 => {
+            let field = |i: usize| fields[i].as_i64().unwrap_or(0);
+            let (min, exponent, max) = (field(0), field(1), field(2));
+            let mut delay = min;
+            for _ in 1..retry {
+                delay = delay.saturating_mul(exponent) / 100;
+                if max > 0 && delay >= max {
+                    break;
+                }
+            }
+            if max > 0 {
+                delay.min(max)
+            } else {
+                delay
+            }
+        }
+        other => other.as_i64().unwrap_or(0),
+    }
+}
+
+/// Whether `now` falls in `window`: between two specified date-times, or on a
+/// day matching the begin date's wildcards between the begin and end times of
+/// day (across midnight when the end time is earlier).
+fn in_window(window: &CommunicationWindow, now: &DateTime, local: i64) -> bool {
+    if let (Some(begin), Some(end)) = (local_seconds(&window.begin), local_seconds(&window.end)) {
+        return begin <= local && local <= end;
+    }
+    if !window.begin.date().matches(&now.date()) {
+        return false;
+    }
+    let Some(time) = now.time().seconds_of_day() else {
+        return false;
+    };
+    let begin = window.begin.time().seconds_of_day().unwrap_or(0);
+    let end = window.end.time().seconds_of_day().unwrap_or(86_399);
+    if begin <= end {
+        begin <= time && time <= end
+    } else {
+        begin <= time || time <= end
+    }
+}
+
+/// Local seconds as a date-time in the deviation of `now`, when representable.
+fn instant(local: i64, now: &DateTime) -> Option<DateTime> {
+    let deviation = now.deviation().unwrap_or(0);
+    DateTime::from_unix(local - i64::from(deviation) * 60, deviation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::data::Data;
+    use crate::classes::push_setup::PushSetupConfig;
+    use crate::security::signature::ecdsa_verify;
+    use crate::security::SecurityPolicy;
+    use crate::types::attrs::{
+        CaptureObjectDefinition, ConfirmationParameters, PushProtectionParameter, SendDestinationAndMethod,
+    };
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    const METER_ST: [u8; 8] = *b"SIT\x00\x00\x00\x00\x01";
+
+    fn setup_ln() -> ObisCode {
+        ObisCode::new(0, 0, 25, 9, 0, 255)
+    }
+
+    fn value_ln() -> ObisCode {
+        ObisCode::new(0, 0, 96, 1, 0, 255)
+    }
+
+    /// 2024-03-01 at `h:m:s`, Moscow time.
+    fn at(h: u8, m: u8, s: u8) -> DateTime {
+        let utc = DateTime::from_ymdhms(2024, 3, 1, h, m, s).to_unix().unwrap() - 180 * 60;
+        DateTime::from_unix(utc, 180).unwrap()
+    }
+
+    fn config() -> PushSetupConfig {
+        PushSetupConfig {
+            logical_name: setup_ln(),
+            version: 2,
+            push_object_list: vec![CaptureObjectDefinition::new(1, value_ln(), 2, 0)],
+            send_destination_and_method: SendDestinationAndMethod {
+                transport_service: transport_service::TCP,
+                destination: b"10.0.0.1:4059".to_vec(),
+                message: 0,
+            },
+            communication_window: vec![],
+            randomisation_start_interval: 0,
+            number_of_retries: 0,
+            repetition_delay: CosemDataType::LongUnsigned(30),
+            port_reference: vec![],
+            push_client_sap: 0x66,
+            push_protection_parameters: vec![],
+            push_operation_method: 0,
+            confirmation_parameters: ConfirmationParameters { data: vec![] },
+            last_confirmation_date_time: DateTime::from_ymdhms(1900, 1, 1, 0, 0, 0),
+        }
+    }
+
+    fn objects(config: PushSetupConfig) -> Vec<Box<dyn InterfaceClass>> {
+        vec![Box::new(PushSetup::new(config)), Box::new(Data::new(value_ln(), CosemDataType::LongUnsigned(7)))]
+    }
+
+    fn setup(objects: &[Box<dyn InterfaceClass>]) -> &PushSetup {
+        objects[0].as_any().downcast_ref().unwrap()
+    }
+
+    /// The transport service, destination and bytes of every send.
+    type Sent = Rc<RefCell<Vec<(u8, Vec<u8>, Vec<u8>)>>>;
+
+    /// Records what is sent and answers confirmed pushes with `answer`.
+    #[derive(Default)]
+    struct Recorder {
+        sent: Sent,
+        answer: Vec<u8>,
+        refuse: bool,
+    }
+
+    struct Line {
+        service: u8,
+        destination: Vec<u8>,
+        sent: Sent,
+        answer: VecDeque<u8>,
+    }
+
+    impl PhysicalTransport for Line {
+        fn send(&mut self, data: &[u8]) -> io::Result<()> {
+            self.sent.borrow_mut().push((self.service, self.destination.clone(), data.to_vec()));
+            Ok(())
+        }
+
+        fn receive(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.answer.len());
+            for slot in buf.iter_mut().take(n) {
+                *slot = self.answer.pop_front().unwrap();
+            }
+            Ok(n)
+        }
+    }
+
+    impl PushConnector for Recorder {
+        fn connect(&mut self, transport_service: u8, destination: &[u8]) -> io::Result<Box<dyn PhysicalTransport>> {
+            if self.refuse {
+                return Err(io::Error::new(io::ErrorKind::ConnectionRefused, "refused"));
+            }
+            Ok(Box::new(Line {
+                service: transport_service,
+                destination: destination.to_vec(),
+                sent: Rc::clone(&self.sent),
+                answer: self.answer.iter().copied().collect(),
+            }))
+        }
+    }
+
+    impl Recorder {
+        /// The DATA-NOTIFICATION APDUs sent in wrapper PDUs.
+        fn apdus(&self) -> Vec<Vec<u8>> {
+            self.sent.borrow().iter().map(|(_, _, pdu)| wrapper::decode(pdu).unwrap().1).collect()
+        }
+    }
+
+    #[test]
+    fn push_method_triggers_a_wrapped_data_notification() {
+        let mut objects = objects(config());
+        let mut connector = Recorder::default();
+        let mut service = PushService::new();
+        assert!(service.poll(&mut objects, &mut connector, &at(10, 0, 0)).is_empty());
+
+        objects[0].invoke_method(1, None).unwrap();
+        let attempts = service.poll(&mut objects, &mut connector, &at(10, 0, 5));
+        assert_eq!(attempts.len(), 1);
+        assert!(matches!(attempts[0].result, Ok(Delivery::Sent)));
+        assert!(!service.is_pending(&setup_ln()));
+
+        let sent = connector.sent.borrow();
+        let (service_id, destination, pdu) = &sent[0];
+        assert_eq!((*service_id, destination.as_slice()), (transport_service::TCP, &b"10.0.0.1:4059"[..]));
+        let (header, apdu) = wrapper::decode(pdu).unwrap();
+        assert_eq!((header.source, header.destination), (1, 0x66));
+        let notification = DataNotification::decode(&apdu).unwrap();
+        assert_eq!(notification.notification_body, CosemDataType::LongUnsigned(7));
+        assert_eq!(notification.date_time, at(10, 0, 5).as_bytes().to_vec());
+        assert_eq!(notification.long_invoke_id_and_priority & SERVICE_CLASS_CONFIRMED, 0);
+    }
+
+    #[test]
+    fn start_is_randomised_and_waits_for_a_communication_window() {
+        let mut config = config();
+        config.randomisation_start_interval = 60;
+        config.communication_window = vec![CommunicationWindow {
+            // Every day, 02:00–03:00.
+            begin: DateTime::new([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 2, 0, 0, 0, 0x80, 0x00, 0xFF]),
+            end: DateTime::new([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 3, 0, 0, 0, 0x80, 0x00, 0xFF]),
+        }];
+        let mut objects = objects(config);
+        let mut connector = Recorder::default();
+        let mut service = PushService::new().with_seed(7);
+        service.trigger(setup_ln());
+        // Outside the 02:00–03:00 window nothing is sent.
+        assert!(service.poll(&mut objects, &mut connector, &at(1, 30, 0)).is_empty());
+        assert!(service.is_pending(&setup_ln()));
+        // Inside it the push goes out within the randomisation interval.
+        let mut sent_at = None;
+        for second in 0..=60 {
+            if !service.poll(&mut objects, &mut connector, &at(2, 0, second)).is_empty() {
+                sent_at = Some(second);
+                break;
+            }
+        }
+        assert!(sent_at.is_some());
+        assert_eq!(connector.apdus().len(), 1);
+    }
+
+    #[test]
+    fn failed_pushes_retry_with_exponential_delays() {
+        let mut config = config();
+        config.number_of_retries = 3;
+        config.repetition_delay = CosemDataType::Structure(vec![
+            CosemDataType::LongUnsigned(10),
+            CosemDataType::LongUnsigned(200),
+            CosemDataType::LongUnsigned(30),
+        ]);
+        let mut objects = objects(config);
+        let mut connector = Recorder { refuse: true, ..Default::default() };
+        let mut service = PushService::new();
+        service.trigger(setup_ln());
+
+        let mut now = at(10, 0, 0);
+        let mut delays = Vec::new();
+        loop {
+            let attempt = service.poll(&mut objects, &mut connector, &now).remove(0);
+            assert!(matches!(attempt.result, Err(PushError::Io(_))));
+            let Some(retry_at) = attempt.retry_at else { break };
+            // Nothing happens before the retry is due.
+            assert!(service
+                .poll(&mut objects, &mut connector, &instant(local_seconds(&retry_at).unwrap() - 1, &now).unwrap())
+                .is_empty());
+            delays.push(retry_at.to_unix().unwrap() - now.to_unix().unwrap());
+            now = retry_at;
+        }
+        assert_eq!(delays, vec![10, 20, 30]);
+        assert!(!service.is_pending(&setup_ln()));
+        assert_eq!(repetition_delay(&CosemDataType::LongUnsigned(45), 3), 45);
+    }
+
+    #[test]
+    fn confirmed_push_records_the_confirmation() {
+        let mut config = config();
+        config.push_operation_method = CONFIRMED;
+        config.number_of_retries = 1;
+        let mut objects = objects(config);
+        let mut service = PushService::new();
+
+        // No answer: unconfirmed, retried.
+        let mut silent = Recorder::default();
+        service.trigger(setup_ln());
+        let attempt = service.poll(&mut objects, &mut silent, &at(10, 0, 0)).remove(0);
+        assert!(matches!(attempt.result, Err(PushError::Unconfirmed)));
+        assert_eq!(attempt.retry_at, Some(at(10, 0, 30)));
+        let apdu = &silent.apdus()[0];
+        let notification = DataNotification::decode(apdu).unwrap();
+        assert_ne!(notification.long_invoke_id_and_priority & SERVICE_CLASS_CONFIRMED, 0);
+
+        let mut answering = Recorder { answer: confirmation(SERVICE_CLASS_CONFIRMED | 2), ..Default::default() };
+        let attempt = service.poll(&mut objects, &mut answering, &at(10, 0, 30)).remove(0);
+        assert!(matches!(attempt.result, Ok(Delivery::Confirmed)));
+        assert_eq!(setup(&objects).last_confirmation_date_time(), &at(10, 0, 30));
+
+        // Any other answer, or a confirmation of another invoke id, does not confirm.
+        for answer in [wrapper::encode(0x66, 1, &[0x0F]), confirmation(SERVICE_CLASS_CONFIRMED | 2)] {
+            let mut stale = Recorder { answer, ..Default::default() };
+            let mut service = PushService::new();
+            service.trigger(setup_ln());
+            let attempt = service.poll(&mut objects, &mut stale, &at(11, 0, 0)).remove(0);
+            assert!(matches!(attempt.result, Err(PushError::Unconfirmed)));
+        }
+        assert_eq!(setup(&objects).last_confirmation_date_time(), &at(10, 0, 30));
+    }
+
+    /// A head-end's wrapped confirmation of `long_invoke_id_and_priority`.
+    fn confirmation(long_invoke_id_and_priority: u32) -> Vec<u8> {
+        let apdu = DataNotification {
+            long_invoke_id_and_priority,
+            date_time: Vec::new(),
+            notification_body: CosemDataType::Null,
+        }
+        .encode()
+        .unwrap();
+        wrapper::encode(0x66, 1, &apdu)
+    }
+
+    #[test]
+    fn socket_connector_delivers_to_a_push_listener() {
+        use crate::listener::{Framing, PushListener};
+        use std::ops::ControlFlow;
+
+        for service_id in [transport_service::TCP, transport_service::UDP] {
+            let (address, head_end) = if service_id == transport_service::TCP {
+                let socket = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+                let address = socket.local_addr().unwrap();
+                let head_end = std::thread::spawn(move || {
+                    let mut listener = PushListener::new().with_confirmations(true);
+                    listener.serve_tcp(&socket, Framing::Wrapper, |_| ControlFlow::Break(())).unwrap();
+                });
+                (address, head_end)
+            } else {
+                let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+                let address = socket.local_addr().unwrap();
+                let head_end = std::thread::spawn(move || {
+                    let mut listener = PushListener::new().with_confirmations(true);
+                    listener.serve_udp(&socket, Framing::Wrapper, |_| ControlFlow::Break(())).unwrap();
+                });
+                (address, head_end)
+            };
+            let mut config = config();
+            config.push_operation_method = CONFIRMED;
+            config.send_destination_and_method.transport_service = service_id;
+            config.send_destination_and_method.destination = address.to_string().into_bytes();
+            let mut objects = objects(config);
+            let mut service = PushService::new();
+            service.trigger(setup_ln());
+            let attempt = service.poll(&mut objects, &mut SocketConnector, &at(10, 0, 0)).remove(0);
+            assert!(matches!(attempt.result, Ok(Delivery::Confirmed)), "{:?}", attempt.result);
+            head_end.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn confirmation_parameters_limit_confirmed_pushes() {
+        let mut config = config();
+        config.push_operation_method = CONFIRMED;
+        let mut data = at(9, 0, 0).as_bytes().to_vec();
+        data.extend_from_slice(&3600u32.to_be_bytes());
+        config.confirmation_parameters = ConfirmationParameters { data };
+        let mut push = PushSetup::new(config);
+        let local = |t: &DateTime| local_seconds(t).unwrap();
+        // Before the start date pushes are unconfirmed.
+        assert!(!is_confirmed(&push, local(&at(8, 0, 0))));
+        assert!(is_confirmed(&push, local(&at(10, 0, 0))));
+        push.record_confirmation(at(10, 0, 0));
+        assert!(!is_confirmed(&push, local(&at(10, 30, 0))));
+        assert!(is_confirmed(&push, local(&at(11, 0, 0))));
+    }
+
+    #[test]
+    fn protected_pushes_are_ciphered_and_signed() {
+        let context = |ic| {
+            SecurityContext::for_suite(
+                SecurityPolicy::None,
+                SecuritySuite::Suite0,
+                vec![0x11; 16],
+                vec![0x22; 16],
+                METER_ST.to_vec(),
+                ic,
+            )
+            .unwrap()
+        };
+        let mut config = config();
+        config.push_protection_parameters = vec![
+            PushProtectionParameter { data: vec![protection_type::AUTHENTICATED_ENCRYPTION] },
+            PushProtectionParameter { data: vec![protection_type::DIGITAL_SIGNATURE] },
+        ];
+        let mut objects = objects(config);
+        let mut connector = Recorder::default();
+        let key = [0x42u8; 32];
+        let mut service = PushService::new().with_signing(SecuritySuite::Suite1, key.to_vec());
+        service.trigger(setup_ln());
+        let attempt = service.poll(&mut objects, &mut connector, &at(10, 0, 0)).remove(0);
+        assert!(matches!(attempt.result, Err(PushError::NoSecurity)));
+
+        service = service.with_ciphering(context(5));
+        service.trigger(setup_ln());
+        service.poll(&mut objects, &mut connector, &at(10, 0, 0));
+        let signed = GeneralSigning::decode(&connector.apdus()[0]).unwrap();
+        let public_key = p256::ecdsa::SigningKey::from_slice(&key).unwrap();
+        let public_key = public_key.verifying_key().to_sec1_point(false).as_bytes().to_vec();
+        ecdsa_verify(SecuritySuite::Suite1, &public_key, &signed.signed_data(), &signed.signature).unwrap();
+        assert_eq!(signed.originator_system_title, METER_ST.to_vec());
+
+        let general = GeneralGloDedCiphering::decode(&signed.content).unwrap();
+        assert_eq!(general.system_title, METER_ST.to_vec());
+        let mut ciphered = vec![GENERAL_GLO_CIPHERING_TAG];
+        crate::service::push_length(general.ciphered_service.len(), &mut ciphered);
+        ciphered.extend_from_slice(&general.ciphered_service);
+        let (_, plaintext) = ciphering::unprotect(&mut context(0), &ciphered).unwrap();
+        let notification = DataNotification::decode(&plaintext).unwrap();
+        assert_eq!(notification.notification_body, CosemDataType::LongUnsigned(7));
+        // The invocation counter advanced for the next push.
+        assert_eq!(service.ciphering.as_ref().unwrap().invocation_counter, 6);
+    }
+
+    #[test]
+    fn hdlc_pushes_are_ui_frames() {
+        let mut hdlc = config();
+        hdlc.send_destination_and_method.transport_service = transport_service::HDLC;
+        let mut unsupported = config();
+        unsupported.send_destination_and_method.transport_service = 9;
+        let mut connector = Recorder::default();
+        let mut service = PushService::new().with_hdlc_address(HdlcAddress::one_byte(0x21));
+
+        let mut set = objects(hdlc);
+        service.trigger(setup_ln());
+        service.poll(&mut set, &mut connector, &at(10, 0, 0));
+        let frame = HdlcFrame::decode(&connector.sent.borrow()[0].2).unwrap();
+        assert_eq!(frame.control, Control::Ui { poll: false });
+        assert_eq!((frame.destination.value, frame.source.value), (0x66, 0x21));
+        assert_eq!(frame.information[..3], LLC_RESPONSE);
+        assert!(DataNotification::decode(&frame.information[3..]).is_ok());
+
+        let mut set = objects(unsupported);
+        service.trigger(setup_ln());
+        let attempt = service.poll(&mut set, &mut connector, &at(10, 0, 0)).remove(0);
+        assert!(matches!(attempt.result, Err(PushError::UnsupportedTransport(9))));
+    }
+}
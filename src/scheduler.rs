@@ -0,0 +1,583 @@
+//! A time-driven scheduling runtime for the Schedule (class 10), Single action
+//! schedule (class 22) and Special days table (class 11) objects.
+//!
+//! The objects themselves only hold configuration;
+//! [`Scheduler::tick`](crate::scheduler::Scheduler::tick) brings them to life.
+//! Given the current local time it works out which schedule entries and
+//! execution times fell due since the previous tick — resolving date/time
+//! wildcards, special-day exclusions and begin/end dates — and runs the
+//! referenced scripts of their Script table (class 9) with
+//! [`execute_script`](crate::scheduler::execute_script).
+//!
+//! Clock changes follow the Blue Book (IEC 62056-6-2 §4.5.3, §4.5.7):
+//!
+//! * **forwards** — of the instants skipped over, only the latest one of each
+//!   schedule entry is considered. After a clock set reported with
+//!   [`Scheduler::clock_adjusted`](crate::scheduler::Scheduler::clock_adjusted)
+//!   or a power failure resumed with
+//!   [`Scheduler::resume_from`](crate::scheduler::Scheduler::resume_from), a
+//!   Schedule entry runs it when it is still within the entry's
+//!   `validity_window` (minutes, `0xFFFF` = always) and reports it as skipped
+//!   otherwise; a Single action schedule always runs it. A tick that is merely
+//!   late runs the entries that fell due normally.
+//! * **backwards** — nothing runs until the clock passes the latest time
+//!   already served, so no script runs twice for the same switch time.
+//!
+//! Times are compared as local wall-clock time (UTC + deviation), the frame
+//! switch times and execution times are expressed in.
+
+use crate::classes::schedule::Schedule;
+use crate::classes::script_table::ScriptTable;
+use crate::classes::single_action_schedule::SingleActionSchedule;
+use crate::classes::special_days_table::SpecialDaysTable;
+use crate::interface::{AccessError, InterfaceClass};
+use crate::obis::ObisCode;
+use crate::types::attrs::{ExecutedScript, ScheduleTableEntry};
+use crate::types::datetime::{wildcard, Date, DateTime, Time};
+
+/// How far back a single tick looks for missed executions: one year.
+const MAX_CATCH_UP: i64 = 366 * 86_400;
+
+/// `validity_window` value meaning "always valid".
+const ALWAYS_VALID: u16 = 0xFFFF;
+
+/// A script that fell due in a tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueScript {
+    /// Logical name of the Schedule or Single action schedule object.
+    pub source: ObisCode,
+    /// Index of the schedule table entry, `None` for a Single action schedule.
+    pub entry: Option<u16>,
+    /// The script to run.
+    pub script: ExecutedScript,
+    /// The switch / execution time that fell due, in the tick's deviation.
+    pub scheduled: DateTime,
+}
+
+/// The outcome of one [`Scheduler::tick`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickReport {
+    /// Scripts run, in chronological order, with their result.
+    pub executed: Vec<(DueScript, Result<(), AccessError>)>,
+    /// Schedule entries that fell due but were outside their validity window.
+    pub skipped: Vec<DueScript>,
+}
+
+/// Drives the Schedule and Single action schedule objects of an object set.
+///
+/// The scheduler remembers the latest local time it has served; each tick
+/// covers the interval from there to `now`. The first tick only anchors it.
+///
+/// ```
+/// use spodes_rs::scheduler::Scheduler;
+/// use spodes_rs::server::RequestDispatcher;
+/// use spodes_rs::types::datetime::DateTime;
+///
+/// let mut server = RequestDispatcher::new();
+/// let mut scheduler = Scheduler::new();
+/// scheduler.tick(server.objects_mut(), &DateTime::from_ymdhms(2024, 5, 1, 12, 0, 0));
+/// let report = scheduler.tick(server.objects_mut(), &DateTime::from_ymdhms(2024, 5, 1, 12, 0, 1));
+/// assert!(report.executed.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    served: Option<i64>,
+    /// Set by a clock jump until the next tick, which then applies the
+    /// validity windows.
+    jumped: bool,
+}
+
+impl Scheduler {
+    /// Creates a scheduler that anchors on its first tick.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a scheduler that has served everything up to `last` (e.g. the
+    /// time of the last tick before a power failure), so the first tick
+    /// catches up on executions missed since then, within their validity
+    /// windows.
+    pub fn resume_from(last: &DateTime) -> Self {
+        Self { served: local_seconds(last), jumped: true }
+    }
+
+    /// Reports that the clock was set: a forward jump covered by the next
+    /// tick only runs the schedule entries still within their validity
+    /// window.
+    pub fn clock_adjusted(&mut self) {
+        self.jumped = true;
+    }
+
+    /// The latest local time served so far, as seconds since the Unix epoch.
+    pub fn served(&self) -> Option<i64> {
+        self.served
+    }
+
+    /// Forgets the served time; the next tick anchors again without running
+    /// anything.
+    pub fn reset(&mut self) {
+        self.served = None;
+        self.jumped = false;
+    }
+
+    /// Runs every script that fell due in (served, `now`] and advances the
+    /// served time. A `now` that is not fully specified is ignored.
+    pub fn tick(&mut self, objects: &mut [Box<dyn InterfaceClass>], now: &DateTime) -> TickReport {
+        let mut report = TickReport::default();
+        let Some(local) = local_seconds(now) else {
+            return report;
+        };
+        let jumped = std::mem::take(&mut self.jumped);
+        let Some(served) = self.served else {
+            self.served = Some(local);
+            return report;
+        };
+        if local <= served {
+            // The clock was set back (or did not move): wait until it passes
+            // the time already served.
+            return report;
+        }
+        self.served = Some(local);
+        let (due, skipped) = due_scripts(objects, served, local, now.deviation().unwrap_or(0), jumped);
+        report.skipped = skipped;
+        for due in due {
+            let result = execute_script(objects, &due.script);
+            report.executed.push((due, result));
+        }
+        report
+    }
+}
+
+/// Runs the script selected by `script` in its Script table: each action
+/// writes an attribute (service 1) or invokes a method (service 2) of the
+/// addressed object. Script 0 is the empty script.
+///
+/// Fails with `object-undefined` when the Script table or an action's target
+/// is not in `objects`, and with `object-unavailable` when the script does
+/// not exist; the first failing action stops the script.
+pub fn execute_script(objects: &mut [Box<dyn InterfaceClass>], script: &ExecutedScript) -> Result<(), AccessError> {
+    let table = objects
+        .iter()
+        .filter(|o| o.class_id() == 9 && *o.logical_name() == script.script_logical_name)
+        .find_map(|o| o.as_any().downcast_ref::<ScriptTable>())
+        .ok_or_else(|| AccessError::ObjectUndefined(format!("No script table {}", script.script_logical_name)))?;
+    if script.script_selector == 0 {
+        return Ok(());
+    }
+    let actions = table
+        .scripts()
+        .iter()
+        .find(|s| s.script_identifier == script.script_selector)
+        .map(|s| s.actions.clone())
+        .ok_or_else(|| {
+            AccessError::ObjectUnavailable(format!(
+                "No script {} in {}",
+                script.script_selector, script.script_logical_name
+            ))
+        })?;
+    for action in actions {
+        let target = objects
+            .iter_mut()
+            .find(|o| o.class_id() == action.class_id && *o.logical_name() == action.logical_name)
+            .ok_or_else(|| {
+                AccessError::ObjectUndefined(format!("No object {} of class {}", action.logical_name, action.class_id))
+            })?;
+        let index = u8::try_from(action.index)
+            .map_err(|_| AccessError::ObjectUnavailable(format!("Invalid index {}", action.index)))?;
+        match action.service_id {
+            1 => target.set_attribute(index, action.parameter)?,
+            2 => {
+                target.invoke_method(index, Some(action.parameter))?;
+            }
+            other => return Err(AccessError::TypeUnmatched(format!("Unknown script service {other}"))),
+        }
+    }
+    Ok(())
+}
+
+/// Local wall-clock seconds since the Unix epoch (UTC + deviation).
+pub(crate) fn local_seconds(instant: &DateTime) -> Option<i64> {
+    Some(instant.to_unix()? + i64::from(instant.deviation().unwrap_or(0)) * 60)
+}
+
+/// The scripts due in (`served`, `now`] (local seconds), sorted by time, and,
+/// after a clock jump, the schedule entries skipped for being outside their
+/// validity window.
+fn due_scripts(
+    objects: &[Box<dyn InterfaceClass>],
+    served: i64,
+    now: i64,
+    deviation: i16,
+    jumped: bool,
+) -> (Vec<DueScript>, Vec<DueScript>) {
+    let from = (served + 1).max(now - MAX_CATCH_UP);
+    let special_days: Vec<(Date, u8)> = objects
+        .iter()
+        .filter_map(|o| o.as_any().downcast_ref::<SpecialDaysTable>())
+        .flat_map(|table| table.entries())
+        .filter_map(|e| Some((date_of(&e.specialday_date)?, e.day_id)))
+        .collect();
+    let mut due = Vec::new();
+    let mut skipped = Vec::new();
+    for object in objects {
+        if let Some(schedule) = object.as_any().downcast_ref::<Schedule>() {
+            if !schedule.is_enabled() {
+                continue;
+            }
+            for entry in schedule.entries().iter().filter(|e| e.enable) {
+                let Some(switch_time) = time_of(&entry.switch_time) else {
+                    continue;
+                };
+                let runs = |date: &Date| runs_on(entry, date, &special_days);
+                let Some(at) = latest(&switch_time, runs, from, now) else {
+                    continue;
+                };
+                let Some(scheduled) = DateTime::from_unix(at - i64::from(deviation) * 60, deviation) else {
+                    continue;
+                };
+                let script = ExecutedScript {
+                    script_logical_name: entry.script_logical_name,
+                    script_selector: entry.script_selector,
+                };
+                let item = DueScript { source: *schedule.logical_name(), entry: Some(entry.index), script, scheduled };
+                let valid = !jumped
+                    || entry.validity_window == ALWAYS_VALID
+                    || now - at <= i64::from(entry.validity_window) * 60;
+                if valid {
+                    due.push((at, item));
+                } else {
+                    skipped.push(item);
+                }
+            }
+        } else if let Some(single) = object.as_any().downcast_ref::<SingleActionSchedule>() {
+            let at = single
+                .execution_time()
+                .iter()
+                .filter_map(|et| {
+                    let (time, date) = (time_of(&et.time)?, date_of(&et.date)?);
+                    latest(&time, |d: &Date| date.matches(d), from, now)
+                })
+                .max();
+            let scheduled =
+                at.and_then(|at| Some((at, DateTime::from_unix(at - i64::from(deviation) * 60, deviation)?)));
+            if let Some((at, scheduled)) = scheduled {
+                let item = DueScript {
+                    source: *single.logical_name(),
+                    entry: None,
+                    script: single.executed_script().clone(),
+                    scheduled,
+                };
+                due.push((at, item));
+            }
+        }
+    }
+    due.sort_by_key(|(at, _)| *at);
+    (due.into_iter().map(|(_, item)| item).collect(), skipped)
+}
+
+/// The latest local second in [`from`, `to`] whose time of day matches
+/// `pattern` on a day accepted by `runs_on_day`.
+fn latest(pattern: &Time, runs_on_day: impl Fn(&Date) -> bool, from: i64, to: i64) -> Option<i64> {
+    if from > to {
+        return None;
+    }
+    let (first, last) = (from.div_euclid(86_400), to.div_euclid(86_400));
+    (first..=last).rev().find_map(|day| {
+        let start = day * 86_400;
+        if !runs_on_day(&DateTime::from_unix(start, 0)?.date()) {
+            return None;
+        }
+        // Both bounds lie within the day, so they fit a u32.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (lo, hi) = ((from.max(start) - start) as u32, (to.min(start + 86_399) - start) as u32);
+        latest_time(pattern, lo, hi).map(|secs| start + i64::from(secs))
+    })
+}
+
+/// The latest second of the day in [`lo`, `hi`] matching `pattern` (hour,
+/// minute and second may each be a wildcard; hundredths are ignored).
+fn latest_time(pattern: &Time, lo: u32, hi: u32) -> Option<u32> {
+    let values = |field: u8, max: u32| {
+        (0..=max).rev().filter(move |v| field == wildcard::NOT_SPECIFIED || u32::from(field) == *v)
+    };
+    for hour in values(pattern.hour(), 23) {
+        if hour * 3600 > hi || hour * 3600 + 3599 < lo {
+            continue;
+        }
+        for minute in values(pattern.minute(), 59) {
+            let base = hour * 3600 + minute * 60;
+            if base > hi || base + 59 < lo {
+                continue;
+            }
+            if let Some(secs) = values(pattern.second(), 59).map(|s| base + s).find(|t| (lo..=hi).contains(t)) {
+                return Some(secs);
+            }
+        }
+    }
+    None
+}
+
+/// Whether a schedule table entry runs on `date`: within its begin/end dates
+/// and, on a special day, enabled for that day id in `exec_specdays`,
+/// otherwise for the weekday in `exec_weekdays` (bit 0 = Monday).
+fn runs_on(entry: &ScheduleTableEntry, date: &Date, special_days: &[(Date, u8)]) -> bool {
+    if !within(date, date_of(&entry.begin_date), date_of(&entry.end_date)) {
+        return false;
+    }
+    match special_days.iter().find(|(pattern, _)| pattern.matches(date)) {
+        Some(&(_, day_id)) => bit(&entry.exec_specdays, usize::from(day_id)),
+        None => date.weekday().is_some_and(|weekday| bit(&entry.exec_weekdays, usize::from(weekday - 1))),
+    }
+}
+
+/// Whether `date` lies within the begin and end dates. A fully specified
+/// bound is compared as a date, a yearly one (year wildcard) by month and day,
+/// wrapping over the new year when begin > end; other patterns do not bound.
+fn within(date: &Date, begin: Option<Date>, end: Option<Date>) -> bool {
+    let yearly = |d: &Date| {
+        (d.year() == wildcard::YEAR && (1..=12).contains(&d.month()) && (1..=31).contains(&d.day()))
+            .then(|| (d.month(), d.day()))
+    };
+    let key = (date.month(), date.day());
+    if let (Some(b), Some(e)) = (begin.as_ref().and_then(yearly), end.as_ref().and_then(yearly)) {
+        return if b <= e { b <= key && key <= e } else { b <= key || key <= e };
+    }
+    let ymd = |d: &Date| (d.year(), d.month(), d.day());
+    let after_begin = match begin {
+        Some(b) if b.is_specified() => ymd(&b) <= ymd(date),
+        Some(b) => yearly(&b).is_none_or(|b| b <= key),
+        None => true,
+    };
+    let before_end = match end {
+        Some(e) if e.is_specified() => ymd(date) <= ymd(&e),
+        Some(e) => yearly(&e).is_none_or(|e| key <= e),
+        None => true,
+    };
+    after_begin && before_end
+}
+
+/// Bit `n` of a bit-string, most significant bit of the first octet first.
+fn bit(bits: &[u8], n: usize) -> bool {
+    bits.get(n / 8).is_some_and(|b| b & (0x80 >> (n % 8)) != 0)
+}
+
+/// The date of an octet-string holding a date (5 octets) or a date-time (12).
+pub(crate) fn date_of(bytes: &[u8]) -> Option<Date> {
+    Some(Date::new(bytes.get(..5)?.try_into().ok()?))
+}
+
+/// The time of an octet-string holding a time (4 octets), or `hh mm ss` (3).
+pub(crate) fn time_of(bytes: &[u8]) -> Option<Time> {
+    match bytes.len() {
+        3 => Some(Time::new([bytes[0], bytes[1], bytes[2], 0])),
+        n if n >= 4 => Some(Time::new(bytes[..4].try_into().ok()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::data::Data;
+    use crate::classes::schedule::ScheduleConfig;
+    use crate::classes::script_table::ScriptTableConfig;
+    use crate::classes::single_action_schedule::SingleActionScheduleConfig;
+    use crate::classes::special_days_table::SpecialDaysTableConfig;
+    use crate::types::attrs::{ActionSpecification, ExecutionTime, Script, SpecialDayEntry};
+    use crate::types::CosemDataType;
+
+    fn scripts_ln() -> ObisCode {
+        ObisCode::new(0, 0, 10, 0, 100, 255)
+    }
+
+    fn target_ln() -> ObisCode {
+        ObisCode::new(0, 0, 96, 1, 0, 255)
+    }
+
+    /// A script table whose script `n` writes `n` to the target Data object.
+    fn objects(extra: Vec<Box<dyn InterfaceClass>>) -> Vec<Box<dyn InterfaceClass>> {
+        let scripts = (1..=3)
+            .map(|n| Script {
+                script_identifier: n,
+                actions: vec![ActionSpecification {
+                    service_id: 1,
+                    class_id: 1,
+                    logical_name: target_ln(),
+                    index: 2,
+                    parameter: CosemDataType::LongUnsigned(n),
+                }],
+            })
+            .collect();
+        let mut objects: Vec<Box<dyn InterfaceClass>> = vec![
+            Box::new(ScriptTable::new(ScriptTableConfig { logical_name: scripts_ln(), scripts })),
+            Box::new(Data::new(target_ln(), CosemDataType::LongUnsigned(0))),
+        ];
+        objects.extend(extra);
+        objects
+    }
+
+    fn entry(index: u16, selector: u16, switch_time: [u8; 4], validity_window: u16) -> ScheduleTableEntry {
+        ScheduleTableEntry {
+            index,
+            enable: true,
+            script_logical_name: scripts_ln(),
+            script_selector: selector,
+            switch_time: switch_time.to_vec(),
+            validity_window,
+            exec_weekdays: vec![0xFE],
+            exec_specdays: vec![0x00],
+            begin_date: vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            end_date: vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        }
+    }
+
+    fn schedule(entries: Vec<ScheduleTableEntry>) -> Box<dyn InterfaceClass> {
+        Box::new(Schedule::new(ScheduleConfig {
+            logical_name: ObisCode::new(0, 0, 12, 0, 0, 255),
+            entries,
+            enabled: true,
+        }))
+    }
+
+    fn target(objects: &[Box<dyn InterfaceClass>]) -> Option<CosemDataType> {
+        objects[1].get_attribute(2)
+    }
+
+    /// A May 2024 instant, UTC, with the day of week filled in.
+    fn at(day: u8, hour: u8, min: u8, sec: u8) -> DateTime {
+        DateTime::from_unix(DateTime::from_ymdhms(2024, 5, day, hour, min, sec).to_unix().unwrap(), 0).unwrap()
+    }
+
+    #[test]
+    fn runs_an_entry_once_at_its_switch_time() {
+        let mut objects = objects(vec![schedule(vec![entry(1, 1, [6, 0, 0, 0], ALWAYS_VALID)])]);
+        let mut scheduler = Scheduler::new();
+        assert!(scheduler.tick(&mut objects, &at(1, 5, 59, 59)).executed.is_empty());
+
+        let report = scheduler.tick(&mut objects, &at(1, 6, 0, 1));
+        assert_eq!(report.executed.len(), 1);
+        let (due, result) = &report.executed[0];
+        assert_eq!((due.entry, due.scheduled.clone(), result.clone()), (Some(1), at(1, 6, 0, 0), Ok(())));
+        assert_eq!(target(&objects), Some(CosemDataType::LongUnsigned(1)));
+
+        assert!(scheduler.tick(&mut objects, &at(1, 6, 0, 5)).executed.is_empty());
+    }
+
+    #[test]
+    fn wildcard_switch_time_fires_every_hour() {
+        let mut objects = objects(vec![schedule(vec![entry(1, 2, [0xFF, 0, 0, 0xFF], ALWAYS_VALID)])]);
+        let mut scheduler = Scheduler::resume_from(&at(1, 12, 59, 30));
+        let report = scheduler.tick(&mut objects, &at(1, 13, 0, 10));
+        assert_eq!(report.executed[0].0.scheduled, at(1, 13, 0, 0));
+        assert!(scheduler.tick(&mut objects, &at(1, 13, 59, 59)).executed.is_empty());
+        assert_eq!(scheduler.tick(&mut objects, &at(1, 14, 0, 0)).executed.len(), 1);
+    }
+
+    #[test]
+    fn special_days_select_exec_specdays() {
+        // 2024-05-01 is special day id 1: the weekday entry is excluded and
+        // the entry enabled for day id 1 runs instead.
+        let mut holiday = entry(2, 2, [6, 0, 0, 0], ALWAYS_VALID);
+        holiday.exec_weekdays = vec![0x00];
+        holiday.exec_specdays = vec![0x40];
+        let special = SpecialDaysTable::new(SpecialDaysTableConfig {
+            logical_name: ObisCode::new(0, 0, 11, 0, 0, 255),
+            entries: vec![SpecialDayEntry { index: 1, specialday_date: vec![0xFF, 0xFF, 5, 1, 0xFF], day_id: 1 }],
+        });
+        let mut objects =
+            objects(vec![schedule(vec![entry(1, 1, [6, 0, 0, 0], ALWAYS_VALID), holiday]), Box::new(special)]);
+        let mut scheduler = Scheduler::resume_from(&at(1, 5, 0, 0));
+        let report = scheduler.tick(&mut objects, &at(1, 6, 0, 0));
+        assert_eq!(report.executed.iter().map(|(d, _)| d.entry).collect::<Vec<_>>(), vec![Some(2)]);
+
+        let report = scheduler.tick(&mut objects, &at(2, 6, 0, 0));
+        assert_eq!(report.executed.iter().map(|(d, _)| d.entry).collect::<Vec<_>>(), vec![Some(1)]);
+    }
+
+    #[test]
+    fn weekdays_and_dates_bound_the_entry() {
+        let mut weekend = entry(1, 1, [6, 0, 0, 0], ALWAYS_VALID);
+        weekend.exec_weekdays = vec![0x06]; // Saturday, Sunday
+        weekend.end_date = vec![0x07, 0xE8, 5, 4, 0xFF];
+        let mut objects = objects(vec![schedule(vec![weekend])]);
+        let mut scheduler = Scheduler::resume_from(&at(1, 0, 0, 0));
+        // Wed 1 .. Fri 3: no run.
+        assert!(scheduler.tick(&mut objects, &at(3, 23, 0, 0)).executed.is_empty());
+        // Sat 4: runs; Sun 5 is past the end date.
+        assert_eq!(scheduler.tick(&mut objects, &at(4, 6, 0, 0)).executed.len(), 1);
+        assert!(scheduler.tick(&mut objects, &at(5, 23, 0, 0)).executed.is_empty());
+    }
+
+    #[test]
+    fn forward_jump_honours_the_validity_window() {
+        let entries = vec![entry(1, 1, [6, 0, 0, 0], 10), entry(2, 2, [5, 30, 0, 0], 10)];
+        let mut objects = objects(vec![schedule(entries)]);
+        let mut scheduler = Scheduler::resume_from(&at(1, 5, 0, 0));
+        let report = scheduler.tick(&mut objects, &at(1, 6, 5, 0));
+        assert_eq!(report.executed.iter().map(|(d, _)| d.entry).collect::<Vec<_>>(), vec![Some(1)]);
+        assert_eq!(report.skipped.iter().map(|d| d.entry).collect::<Vec<_>>(), vec![Some(2)]);
+        assert_eq!(target(&objects), Some(CosemDataType::LongUnsigned(1)));
+    }
+
+    #[test]
+    fn validity_window_applies_only_after_a_clock_jump() {
+        let entries = vec![entry(1, 1, [6, 0, 0, 0], 10), entry(2, 2, [5, 30, 0, 0], 10)];
+        let mut objects = objects(vec![schedule(entries)]);
+        let mut scheduler = Scheduler::new();
+        scheduler.tick(&mut objects, &at(1, 5, 0, 0));
+        // A late tick runs every entry that fell due.
+        let report = scheduler.tick(&mut objects, &at(1, 6, 5, 0));
+        assert_eq!(report.executed.iter().map(|(d, _)| d.entry).collect::<Vec<_>>(), vec![Some(2), Some(1)]);
+        assert!(report.skipped.is_empty());
+
+        // The clock is set forward past both switch times of the next day.
+        scheduler.clock_adjusted();
+        let report = scheduler.tick(&mut objects, &at(2, 6, 5, 0));
+        assert_eq!(report.executed.iter().map(|(d, _)| d.entry).collect::<Vec<_>>(), vec![Some(1)]);
+        assert_eq!(report.skipped.iter().map(|d| d.entry).collect::<Vec<_>>(), vec![Some(2)]);
+    }
+
+    #[test]
+    fn backward_jump_does_not_run_twice() {
+        let mut objects = objects(vec![schedule(vec![entry(1, 1, [6, 0, 0, 0], ALWAYS_VALID)])]);
+        let mut scheduler = Scheduler::resume_from(&at(1, 5, 59, 0));
+        assert_eq!(scheduler.tick(&mut objects, &at(1, 6, 1, 0)).executed.len(), 1);
+        // Set back to 05:50 and run past 06:00 again.
+        assert!(scheduler.tick(&mut objects, &at(1, 5, 50, 0)).executed.is_empty());
+        assert!(scheduler.tick(&mut objects, &at(1, 6, 0, 30)).executed.is_empty());
+        assert_eq!(scheduler.served(), local_seconds(&at(1, 6, 1, 0)));
+        assert_eq!(scheduler.tick(&mut objects, &at(2, 6, 0, 0)).executed.len(), 1);
+    }
+
+    #[test]
+    fn single_action_schedule_runs_the_latest_missed_time_once() {
+        let single = SingleActionSchedule::new(SingleActionScheduleConfig {
+            logical_name: ObisCode::new(0, 0, 15, 0, 0, 255),
+            executed_script: ExecutedScript { script_logical_name: scripts_ln(), script_selector: 3 },
+            schedule_type: 3,
+            execution_time: vec![
+                ExecutionTime { time: vec![0, 0, 0, 0], date: vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF] },
+                ExecutionTime { time: vec![12, 0, 0, 0], date: vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF] },
+            ],
+        });
+        let mut objects = objects(vec![Box::new(single)]);
+        let mut scheduler = Scheduler::resume_from(&at(1, 11, 0, 0));
+        let report = scheduler.tick(&mut objects, &at(3, 1, 0, 0));
+        assert_eq!(report.executed.len(), 1);
+        assert_eq!(report.executed[0].0.scheduled, at(3, 0, 0, 0));
+        assert_eq!(target(&objects), Some(CosemDataType::LongUnsigned(3)));
+    }
+
+    #[test]
+    fn execute_script_reports_missing_objects() {
+        let mut objects = objects(Vec::new());
+        let missing_table =
+            ExecutedScript { script_logical_name: ObisCode::new(0, 0, 10, 0, 1, 255), script_selector: 1 };
+        assert!(matches!(execute_script(&mut objects, &missing_table), Err(AccessError::ObjectUndefined(_))));
+        let missing_script = ExecutedScript { script_logical_name: scripts_ln(), script_selector: 9 };
+        assert!(matches!(execute_script(&mut objects, &missing_script), Err(AccessError::ObjectUnavailable(_))));
+        let empty = ExecutedScript { script_logical_name: scripts_ln(), script_selector: 0 };
+        assert_eq!(execute_script(&mut objects, &empty), Ok(()));
+        assert_eq!(target(&objects), Some(CosemDataType::LongUnsigned(0)));
+    }
+}
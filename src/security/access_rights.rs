@@ -10,6 +10,8 @@
 //!   authenticated_read_only(4), authenticated_write_only(5), authenticated_read_and_write(6)
 //! - Method: no_access(0), access(1), authenticated_access(2)
 
+use crate::interface::InterfaceClass;
+
 /// Access mode for an attribute (IEC 62056-5-3, 5.3.7.2.2).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AttributeAccessMode {
@@ -177,50 +179,93 @@ impl ObjectListEntry {
     }
 }
 
-/// Builds an object_list entry with default read/write access for all attributes.
-// COSEM attribute/method indices are always <128 in practice (i8-valued on the wire).
-#[allow(clippy::cast_possible_wrap)]
-pub fn full_access_entry(class_id: u16, version: u8, obis: &[u8], attr_count: u8, method_count: u8) -> ObjectListEntry {
-    let attribute_access = (1..=attr_count)
-        .map(|id| AttributeAccessItem {
-            attribute_id: id as i8,
-            access_mode: AttributeAccessMode::ReadWrite,
-            access_selectors: None,
-        })
-        .collect();
-
-    let method_access = (1..=method_count)
-        .map(|id| MethodAccessItem { method_id: id as i8, access_mode: MethodAccessMode::Access })
-        .collect();
+/// Builds an object_list entry granting the access an object's class allows:
+/// read-write for writable attributes, read-only for the others and access to
+/// every method, as described by [`InterfaceClass::metadata`].
+///
+/// An object without metadata gets read-write access to every attribute and
+/// method it lists.
+pub fn full_access_entry(object: &dyn InterfaceClass) -> ObjectListEntry {
+    let (attribute_access, method_access) = match object.metadata() {
+        Some(info) => (
+            info.attributes.iter().map(|a| attribute_item(a.id, a.access_mode())).collect(),
+            info.methods.iter().map(|m| method_item(m.id, m.access_mode())).collect(),
+        ),
+        None => (
+            object.attributes().iter().map(|(id, _)| attribute_item(*id, AttributeAccessMode::ReadWrite)).collect(),
+            object.methods().iter().map(|(id, _)| method_item(*id, MethodAccessMode::Access)).collect(),
+        ),
+    };
+    entry(object, AccessRights { attribute_access, method_access })
+}
 
+/// Builds an object_list entry with read-only access for all attributes and no
+/// access to the methods.
+pub fn read_only_entry(object: &dyn InterfaceClass) -> ObjectListEntry {
+    let (attribute_ids, method_ids): (Vec<u8>, Vec<u8>) = match object.metadata() {
+        Some(info) => (info.attributes.iter().map(|a| a.id).collect(), info.methods.iter().map(|m| m.id).collect()),
+        None => (
+            object.attributes().iter().map(|(id, _)| *id).collect(),
+            object.methods().iter().map(|(id, _)| *id).collect(),
+        ),
+    };
+    let attribute_access =
+        attribute_ids.into_iter().map(|id| attribute_item(id, AttributeAccessMode::ReadOnly)).collect();
+    let method_access = method_ids.into_iter().map(|id| method_item(id, MethodAccessMode::NoAccess)).collect();
+    entry(object, AccessRights { attribute_access, method_access })
+}
+
+fn entry(object: &dyn InterfaceClass, access_rights: AccessRights) -> ObjectListEntry {
     ObjectListEntry {
-        class_id,
-        version,
-        logical_name: obis.to_vec(),
-        access_rights: AccessRights { attribute_access, method_access },
+        class_id: object.class_id(),
+        version: object.version(),
+        logical_name: object.logical_name().to_bytes(),
+        access_rights,
     }
 }
 
-/// Builds an object_list entry with read-only access for all attributes.
 // COSEM attribute/method indices are always <128 in practice (i8-valued on the wire).
 #[allow(clippy::cast_possible_wrap)]
-pub fn read_only_entry(class_id: u16, version: u8, obis: &[u8], attr_count: u8, method_count: u8) -> ObjectListEntry {
-    let attribute_access = (1..=attr_count)
-        .map(|id| AttributeAccessItem {
-            attribute_id: id as i8,
-            access_mode: AttributeAccessMode::ReadOnly,
-            access_selectors: None,
-        })
-        .collect();
-
-    let method_access = (1..=method_count)
-        .map(|id| MethodAccessItem { method_id: id as i8, access_mode: MethodAccessMode::NoAccess })
-        .collect();
+fn attribute_item(id: u8, access_mode: AttributeAccessMode) -> AttributeAccessItem {
+    AttributeAccessItem { attribute_id: id as i8, access_mode, access_selectors: None }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn method_item(id: u8, access_mode: MethodAccessMode) -> MethodAccessItem {
+    MethodAccessItem { method_id: id as i8, access_mode }
+}
 
-    ObjectListEntry {
-        class_id,
-        version,
-        logical_name: obis.to_vec(),
-        access_rights: AccessRights { attribute_access, method_access },
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::data::Data;
+    use crate::classes::register::Register;
+    use crate::obis::ObisCode;
+    use crate::types::attrs::ScalerUnit;
+    use crate::types::CosemDataType;
+
+    #[test]
+    fn full_access_entry_follows_the_class_metadata() {
+        let register = Register::new(
+            ObisCode::new(1, 0, 1, 8, 0, 255),
+            CosemDataType::DoubleLongUnsigned(0),
+            ScalerUnit { scaler: 0, unit: 30 },
+        );
+        let entry = full_access_entry(&register);
+        assert_eq!((entry.class_id, entry.version), (3, 0));
+        assert_eq!(entry.logical_name, vec![1, 0, 1, 8, 0, 255]);
+        assert!(entry.can_read(1) && !entry.can_write(1));
+        assert!(entry.can_write(2) && entry.can_write(3));
+        assert!(!entry.can_read(4));
+        assert!(entry.can_invoke(1) && !entry.can_invoke(2));
+    }
+
+    #[test]
+    fn read_only_entry_denies_writes_and_methods() {
+        let data = Data::new(ObisCode::new(0, 0, 96, 1, 0, 255), CosemDataType::Null);
+        let entry = read_only_entry(&data);
+        assert_eq!(entry.access_rights.attribute_access.len(), 2);
+        assert!(entry.can_read(2) && !entry.can_write(2));
+        assert!(!entry.can_invoke(1));
     }
 }
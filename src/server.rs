@@ -12,6 +12,7 @@
 //! responsibility.
 
 use crate::classes::association_ln::AssociationLn;
+use crate::classes::profile_generic::ProfileGeneric;
 use crate::interface::InterfaceClass;
 use crate::obis::ObisCode;
 use crate::security::AuthMechanism;
@@ -25,7 +26,7 @@ use crate::service::initiate::{InitiateRequest, InitiateResponse};
 use crate::service::notification::DataNotification;
 use crate::service::set::{SetRequest, SetResponse};
 use crate::service::{data_access_result, tag, AttributeDescriptor, DataBlockSa, MethodDescriptor, ServiceError};
-use crate::types::CosemDataType;
+use crate::types::{compact, CosemDataType};
 #[cfg(feature = "tracing")]
 use tracing::{debug, warn};
 
@@ -111,6 +112,8 @@ pub struct RequestDispatcher {
     /// Current association for access rights checking (IEC 62056-5-3, 5.3.7).
     /// When set, GET/SET/ACTION are checked against the association's object_list.
     association: Option<AssociationLn>,
+    /// Whether Profile generic buffers are answered as compact arrays.
+    compact_arrays: bool,
 }
 
 impl Default for RequestDispatcher {
@@ -128,6 +131,7 @@ impl RequestDispatcher {
             pending_get: None,
             pending_set: None,
             association: None,
+            compact_arrays: false,
         }
     }
 
@@ -137,6 +141,14 @@ impl RequestDispatcher {
         self.max_pdu = max_pdu.max(1);
     }
 
+    /// Answers GET requests for a Profile generic buffer (class 7, attribute
+    /// 2) with a `compact-array` when its entries share one type, which
+    /// roughly halves a load profile. Off by default; clients of this crate
+    /// read either form.
+    pub fn set_compact_arrays(&mut self, enabled: bool) {
+        self.compact_arrays = enabled;
+    }
+
     /// Registers an object.
     pub fn add(&mut self, object: Box<dyn InterfaceClass>) {
         #[cfg(feature = "tracing")]
@@ -148,6 +160,12 @@ impl RequestDispatcher {
         self.objects.push(object);
     }
 
+    /// The registered objects, for runtimes that act on them between requests
+    /// (such as the [`Scheduler`](crate::scheduler::Scheduler)).
+    pub fn objects_mut(&mut self) -> &mut [Box<dyn InterfaceClass>] {
+        &mut self.objects
+    }
+
     /// Sets the current association for access rights checking.
     /// When an association is set, all GET/SET/ACTION requests are checked
     /// against the association's object_list access_rights.
@@ -204,10 +222,7 @@ impl RequestDispatcher {
                 .find(entry.class_id, &entry.logical_name)
                 .ok_or_else(|| format!("Push object {} (class {}) not found", entry.logical_name, entry.class_id))?;
             let value = obj
-                .attributes()
-                .into_iter()
-                .find(|(id, _)| *id == entry.attribute_index)
-                .map(|(_, v)| v)
+                .get_attribute(entry.attribute_index)
                 .ok_or_else(|| format!("Push object has no attribute {}", entry.attribute_index))?;
             values.push(value);
         }
@@ -276,10 +291,9 @@ impl RequestDispatcher {
                 );
                 GetDataResult::AccessResult(data_access_result::OBJECT_UNDEFINED)
             },
-            // Attribute ids are always <128 in practice (i8-valued on the wire).
-            #[allow(clippy::cast_possible_wrap)]
-            |obj| match obj.attributes().into_iter().find(|(id, _)| *id as i8 == attr_id) {
-                Some((_, value)) => {
+            // Negative (i8-valued) attribute ids name no attribute.
+            |obj| match u8::try_from(attr_id).ok().and_then(|id| obj.get_attribute(id)) {
+                Some(value) => {
                     #[cfg(feature = "tracing")]
                     debug!(
                         class_id = d.class_id,
@@ -294,6 +308,35 @@ impl RequestDispatcher {
         )
     }
 
+    /// Reads one attribute with its optional selective access. A Profile
+    /// generic buffer is read entry by entry, so only the selected entries are
+    /// loaded from its storage and a storage failure answers `hardware-fault`.
+    fn read_selected(
+        &mut self,
+        d: &AttributeDescriptor,
+        selection: Option<&crate::service::get::AccessSelection>,
+    ) -> GetDataResult {
+        if d.class_id == 7 && d.attribute_id == 2 && self.check_read(d.class_id, &d.instance_id, d.attribute_id) {
+            let profile =
+                self.find(d.class_id, &d.instance_id).and_then(|o| o.as_any().downcast_ref::<ProfileGeneric>());
+            if let Some(profile) = profile {
+                let range = match selection {
+                    Some(sel) => match selected_entries(sel, profile.len()) {
+                        Some(range) => range,
+                        None => return GetDataResult::AccessResult(data_access_result::OTHER_REASON),
+                    },
+                    None => 0..profile.len(),
+                };
+                return match profile.entries(range) {
+                    Ok(rows) => GetDataResult::Data(CosemDataType::Array(rows)),
+                    Err(e) => GetDataResult::AccessResult(e.code()),
+                };
+            }
+        }
+        let read = self.read_attribute(d);
+        apply_selective_access(d, read, selection)
+    }
+
     /// Writes one attribute, returning a data-access-result code.
     /// Checks access rights from the current association's object_list.
     fn write_attribute(&mut self, d: &AttributeDescriptor, value: crate::types::CosemDataType) -> u8 {
@@ -323,7 +366,9 @@ impl RequestDispatcher {
                 // Attribute ids are always <128 in practice (i8-valued on the wire).
                 #[allow(clippy::cast_sign_loss)]
                 let attribute_id = d.attribute_id as u8;
-                match obj.set_attribute(attribute_id, value) {
+                // Reject values of the wrong type before the object sees them.
+                let checked = obj.metadata().map_or(Ok(()), |info| info.check_type(attribute_id, &value));
+                match checked.and_then(|()| obj.set_attribute(attribute_id, value)) {
                     Ok(()) => {
                         #[cfg(feature = "tracing")]
                         debug!(
@@ -334,7 +379,17 @@ impl RequestDispatcher {
                         );
                         data_access_result::SUCCESS
                     }
-                    Err(_) => data_access_result::READ_WRITE_DENIED,
+                    Err(e) => {
+                        #[cfg(feature = "tracing")]
+                        debug!(
+                            class_id = d.class_id,
+                            instance = %d.instance_id,
+                            attr_id = d.attribute_id,
+                            error = %e,
+                            "SET: rejected"
+                        );
+                        e.code()
+                    }
                 }
             },
         )
@@ -371,7 +426,7 @@ impl RequestDispatcher {
             return match self.association.as_mut().expect("association routing").invoke_method(method_id, params) {
                 Ok(crate::types::CosemDataType::Null) => (data_access_result::SUCCESS, None),
                 Ok(value) => (data_access_result::SUCCESS, Some(GetDataResult::Data(value))),
-                Err(_) => (data_access_result::OTHER_REASON, None),
+                Err(e) => (e.code(), None),
             };
         }
         self.find(d.class_id, &d.instance_id).map_or_else(
@@ -410,15 +465,16 @@ impl RequestDispatcher {
                         );
                         (data_access_result::SUCCESS, Some(GetDataResult::Data(value)))
                     }
-                    Err(_) => {
+                    Err(e) => {
                         #[cfg(feature = "tracing")]
                         warn!(
                             class_id = d.class_id,
                             instance = %d.instance_id,
                             method_id = d.method_id,
+                            error = %e,
                             "ACTION: method returned error"
                         );
-                        (data_access_result::OTHER_REASON, None)
+                        (e.code(), None)
                     }
                 }
             },
@@ -720,28 +776,36 @@ impl RequestDispatcher {
         };
         match decoded {
             GetRequest::Normal { invoke_id_and_priority, attribute, access_selection } => {
-                let read = self.read_attribute(&attribute);
-                match apply_selective_access(&attribute, read, access_selection.as_ref()) {
+                match self.read_selected(&attribute, access_selection.as_ref()) {
                     // A too-large value is segmented into datablocks.
                     GetDataResult::Data(value) => {
                         let mut raw = Vec::new();
-                        value.serialize_ber(&mut raw)?;
+                        // Entries of mixed types (null-data columns) fall back to an array.
+                        let compact = match &value {
+                            CosemDataType::Array(rows)
+                                if self.compact_arrays && attribute.class_id == 7 && attribute.attribute_id == 2 =>
+                            {
+                                compact::encode_compact_array(rows, &mut raw).is_ok()
+                            }
+                            _ => false,
+                        };
+                        if !compact {
+                            raw.clear();
+                            value.serialize_ber(&mut raw)?;
+                        }
                         if raw.len() > self.max_pdu {
                             return Ok(self.start_get_blocks(invoke_id_and_priority, raw));
                         }
+                        if compact {
+                            return Ok(GetResponse::encode_normal_raw(invoke_id_and_priority, &raw));
+                        }
                         GetResponse::Normal { invoke_id_and_priority, result: GetDataResult::Data(value) }.encode()
                     }
                     result => GetResponse::Normal { invoke_id_and_priority, result }.encode(),
                 }
             }
             GetRequest::WithList { invoke_id_and_priority, attributes } => {
-                let results = attributes
-                    .iter()
-                    .map(|(a, sel)| {
-                        let read = self.read_attribute(a);
-                        apply_selective_access(a, read, sel.as_ref())
-                    })
-                    .collect();
+                let results = attributes.iter().map(|(a, sel)| self.read_selected(a, sel.as_ref())).collect();
                 GetResponse::WithList { invoke_id_and_priority, results }.encode()
             }
             // Deliver the next block of a segmented result.
@@ -881,25 +945,28 @@ fn apply_selective_access(
         return read;
     }
     let GetDataResult::Data(CosemDataType::Array(rows)) = read else { return read };
+    match selected_entries(sel, rows.len()) {
+        Some(range) => GetDataResult::Data(CosemDataType::Array(rows.get(range).unwrap_or_default().to_vec())),
+        None => GetDataResult::AccessResult(data_access_result::OTHER_REASON),
+    }
+}
+
+/// The 0-based entries of a `len`-entry buffer that selective access keeps,
+/// or `None` for an unsupported selector or a malformed descriptor.
+fn selected_entries(sel: &crate::service::get::AccessSelection, len: usize) -> Option<std::ops::Range<usize>> {
     match sel.selector {
-        1 => GetDataResult::Data(CosemDataType::Array(rows)),
+        1 => Some(0..len),
         2 => {
-            let fields = match &sel.parameters {
-                CosemDataType::Structure(fields) if fields.len() >= 2 => fields,
-                _ => return GetDataResult::AccessResult(data_access_result::OTHER_REASON),
-            };
-            let (Some(from), Some(to)) = (selective_index(&fields[0]), selective_index(&fields[1])) else {
-                return GetDataResult::AccessResult(data_access_result::OTHER_REASON);
-            };
+            let CosemDataType::Structure(fields) = &sel.parameters else { return None };
+            let (from, to) = (selective_index(fields.first()?)?, selective_index(fields.get(1)?)?);
             if from == 0 {
-                return GetDataResult::AccessResult(data_access_result::OTHER_REASON);
+                return None;
             }
             let start = (from - 1) as usize;
-            let end = if to == 0 { rows.len() } else { (to as usize).min(rows.len()) };
-            let slice = if start < end { rows[start..end].to_vec() } else { Vec::new() };
-            GetDataResult::Data(CosemDataType::Array(slice))
+            let end = if to == 0 { len } else { (to as usize).min(len) };
+            Some(start..end.max(start))
         }
-        _ => GetDataResult::AccessResult(data_access_result::OTHER_REASON),
+        _ => None,
     }
 }
 
@@ -971,6 +1038,7 @@ fn not_possible() -> Vec<u8> {
 mod tests {
     use super::*;
     use crate::classes::data::Data;
+    use crate::interface::AccessError;
     use crate::types::CosemDataType;
 
     fn dispatcher_with_data() -> RequestDispatcher {
@@ -1233,20 +1301,24 @@ mod tests {
         fn deserialize_ber(&mut self, _: &[u8]) -> Result<(), crate::types::BerError> {
             Ok(())
         }
-        fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+        fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
             if attribute_id == 2 {
                 self.value = value;
                 Ok(())
             } else {
-                Err("read only".to_string())
+                Err(AccessError::ReadWriteDenied("read only".to_string()))
             }
         }
-        fn invoke_method(&mut self, _: u8, _: Option<CosemDataType>) -> Result<CosemDataType, String> {
-            Err("no methods".to_string())
+        fn invoke_method(&mut self, _: u8, _: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+            Err(AccessError::ObjectUnavailable("no methods".to_string()))
         }
         fn as_any(&self) -> &dyn std::any::Any {
             self
         }
+
+        fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
     }
 
     #[test]
@@ -1256,7 +1328,7 @@ mod tests {
         let big = CosemDataType::OctetString(vec![0xAB; 300]);
         let mut d = RequestDispatcher::new();
         d.set_max_pdu(128);
-        d.add(Box::new(Data::new(obis.clone(), big.clone())));
+        d.add(Box::new(Data::new(obis, big.clone())));
 
         let req = GetRequest::Normal {
             invoke_id_and_priority: 0xC1,
@@ -1286,11 +1358,52 @@ mod tests {
         assert_eq!(value, big);
     }
 
+    #[test]
+    fn profile_buffer_is_answered_as_compact_array() {
+        use crate::classes::profile_generic::{ProfileGeneric, ProfileGenericConfig};
+        use crate::types::attrs::SortMethod;
+        let obis = ObisCode::new(1, 0, 99, 1, 0, 255);
+        let rows: Vec<_> = (0..4)
+            .map(|n| {
+                CosemDataType::Structure(vec![CosemDataType::Unsigned(n), CosemDataType::DoubleLongUnsigned(1000)])
+            })
+            .collect();
+        let mut d = RequestDispatcher::new();
+        d.set_compact_arrays(true);
+        d.add(Box::new(ProfileGeneric::new(ProfileGenericConfig {
+            logical_name: obis,
+            version: 1,
+            buffer: rows.clone(),
+            capture_objects: vec![],
+            capture_period: 0,
+            sort_method: SortMethod::Fifo,
+            sort_object: None,
+            entries_in_use: 0,
+            profile_entries: 10,
+        })));
+        let req = GetRequest::Normal {
+            invoke_id_and_priority: 0xC1,
+            attribute: AttributeDescriptor::new(7, obis, 2),
+            access_selection: None,
+        };
+        let raw = d.dispatch(&req.encode().unwrap()).unwrap();
+        // Tag 19, structure { unsigned, double-long-unsigned }, 4 × 5 octets.
+        assert_eq!(&raw[4..10], &[0x13, 0x02, 0x02, 0x11, 0x06, 20]);
+        let resp = GetResponse::decode(&raw).unwrap();
+        assert_eq!(
+            resp,
+            GetResponse::Normal {
+                invoke_id_and_priority: 0xC1,
+                result: GetDataResult::Data(CosemDataType::Array(rows))
+            }
+        );
+    }
+
     #[test]
     fn set_reassembles_datablocks_and_writes() {
         let obis = ObisCode::new(1, 0, 1, 8, 0, 0xFF);
         let mut d = RequestDispatcher::new();
-        d.add(Box::new(Writable { obis: obis.clone(), value: CosemDataType::LongUnsigned(0) }));
+        d.add(Box::new(Writable { obis, value: CosemDataType::LongUnsigned(0) }));
 
         // The value to write, split into two datablocks.
         let mut value_bytes = Vec::new();
@@ -1299,7 +1412,7 @@ mod tests {
 
         let req1 = SetRequest::WithFirstDatablock {
             invoke_id_and_priority: 0xC1,
-            attribute: AttributeDescriptor::new(1, obis.clone(), 2),
+            attribute: AttributeDescriptor::new(1, obis, 2),
             access_selection: None,
             datablock: DataBlockSa { last_block: false, block_number: 1, raw_data: first.to_vec() },
         };
@@ -1375,7 +1488,7 @@ mod tests {
         let buffer: Vec<CosemDataType> =
             (1u32..=5).map(|i| CosemDataType::Structure(vec![CosemDataType::DoubleLongUnsigned(i)])).collect();
         let profile = ProfileGeneric::new(ProfileGenericConfig {
-            logical_name: obis.clone(),
+            logical_name: obis,
             version: 1,
             buffer,
             capture_objects: vec![],
@@ -1413,6 +1526,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn selective_profile_reads_load_only_the_selected_entries() {
+        use crate::classes::profile_buffer::BufferStorage;
+        use crate::classes::profile_generic::{ProfileGeneric, ProfileGenericConfig};
+        use crate::service::get::AccessSelection;
+        use std::io;
+
+        /// Five entries, of which only the first two can be read.
+        #[derive(Debug)]
+        struct Damaged;
+
+        impl BufferStorage for Damaged {
+            fn len(&self) -> usize {
+                5
+            }
+            fn get(&self, index: usize) -> io::Result<Option<CosemDataType>> {
+                match index {
+                    0 | 1 => Ok(Some(CosemDataType::Structure(vec![CosemDataType::Unsigned(index as u8)]))),
+                    _ => Err(io::Error::new(io::ErrorKind::InvalidData, "bad sector")),
+                }
+            }
+            fn push_back(&mut self, _: &CosemDataType) -> io::Result<()> {
+                Ok(())
+            }
+            fn pop_front(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+            fn insert(&mut self, _: usize, _: &CosemDataType) -> io::Result<()> {
+                Ok(())
+            }
+            fn remove(&mut self, _: usize) -> io::Result<()> {
+                Ok(())
+            }
+            fn clear(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let obis = ObisCode::new(1, 0, 99, 1, 0, 0xFF);
+        let profile = ProfileGeneric::new(ProfileGenericConfig {
+            logical_name: obis,
+            version: 1,
+            buffer: vec![],
+            capture_objects: vec![],
+            capture_period: 0,
+            sort_method: crate::types::attrs::SortMethod::Fifo,
+            sort_object: None,
+            entries_in_use: 0,
+            profile_entries: 10,
+        })
+        .with_storage(Box::new(Damaged));
+        let mut d = RequestDispatcher::new();
+        d.add(Box::new(profile));
+        let get = |d: &mut RequestDispatcher, access_selection| {
+            let req = GetRequest::Normal {
+                invoke_id_and_priority: 0xC1,
+                attribute: AttributeDescriptor::new(7, obis, 2),
+                access_selection,
+            };
+            match GetResponse::decode(&d.dispatch(&req.encode().unwrap()).unwrap()).unwrap() {
+                GetResponse::Normal { result, .. } => result,
+                other => panic!("unexpected {other:?}"),
+            }
+        };
+
+        let first_two = AccessSelection {
+            selector: 2,
+            parameters: CosemDataType::Structure(vec![
+                CosemDataType::DoubleLongUnsigned(1),
+                CosemDataType::DoubleLongUnsigned(2),
+                CosemDataType::LongUnsigned(1),
+                CosemDataType::LongUnsigned(0),
+            ]),
+        };
+        let GetDataResult::Data(CosemDataType::Array(rows)) = get(&mut d, Some(first_two)) else {
+            panic!("selected entries")
+        };
+        assert_eq!(rows.len(), 2);
+        // The whole buffer hits the damaged entries.
+        assert_eq!(get(&mut d, None), GetDataResult::AccessResult(data_access_result::HARDWARE_FAULT));
+    }
+
     #[test]
     fn get_reads_registered_attribute() {
         let mut d = dispatcher_with_data();
@@ -1475,6 +1670,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_and_action_errors_carry_the_object_result_code() {
+        let mut d = dispatcher_with_data();
+        let obis = ObisCode::new(0, 0, 0x80, 0, 0, 0xFF);
+        // Attribute 1 (logical_name) is read-only.
+        let set = SetRequest::Normal {
+            invoke_id_and_priority: 0xC1,
+            attribute: AttributeDescriptor::new(1, obis, 1),
+            access_selection: None,
+            value: CosemDataType::OctetString(vec![0; 6]),
+        };
+        let resp = SetResponse::decode(&d.dispatch(&set.encode().unwrap()).unwrap()).unwrap();
+        assert_eq!(
+            resp,
+            SetResponse::Normal { invoke_id_and_priority: 0xC1, result: data_access_result::READ_WRITE_DENIED }
+        );
+        // Data has no method 2.
+        let action = ActionRequest::Normal {
+            invoke_id_and_priority: 0xC1,
+            method: MethodDescriptor::new(1, obis, 2),
+            parameters: None,
+        };
+        let resp = ActionResponse::decode(&d.dispatch(&action.encode().unwrap()).unwrap()).unwrap();
+        assert_eq!(
+            resp,
+            ActionResponse::Normal {
+                invoke_id_and_priority: 0xC1,
+                result: data_access_result::OBJECT_UNAVAILABLE,
+                return_parameters: None,
+            }
+        );
+    }
+
+    #[test]
+    fn set_checks_the_value_type_against_the_class_metadata() {
+        let mut d = RequestDispatcher::new();
+        d.add(Box::new(crate::spodus::catalog::clock()));
+        let obis = ObisCode::new(0, 0, 1, 0, 0, 255);
+        let mut set_time_zone = |value| {
+            let set = SetRequest::Normal {
+                invoke_id_and_priority: 0xC1,
+                attribute: AttributeDescriptor::new(8, obis, 3),
+                access_selection: None,
+                value,
+            };
+            SetResponse::decode(&d.dispatch(&set.encode().unwrap()).unwrap()).unwrap()
+        };
+        // Clock.time_zone is a long.
+        assert_eq!(
+            set_time_zone(CosemDataType::Unsigned(180)),
+            SetResponse::Normal { invoke_id_and_priority: 0xC1, result: data_access_result::TYPE_UNMATCHED }
+        );
+        assert_eq!(
+            set_time_zone(CosemDataType::Long(180)),
+            SetResponse::Normal { invoke_id_and_priority: 0xC1, result: data_access_result::SUCCESS }
+        );
+    }
+
     #[test]
     fn get_with_list_reads_each_attribute() {
         let mut d = dispatcher_with_data();
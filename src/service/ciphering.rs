@@ -77,6 +77,8 @@ pub mod glo {
     pub const SET_RESPONSE: u8 = 0xCD;
     /// `glo-action-response`.
     pub const ACTION_RESPONSE: u8 = 0xCF;
+    /// `glo-event-notification-request`.
+    pub const EVENT_NOTIFICATION_REQUEST: u8 = 0xCA;
 }
 
 /// Ciphered-APDU tags for service-specific dedicated ciphering.
@@ -210,6 +212,19 @@ impl SecurityContext {
         Ok(())
     }
 
+    /// The last invocation counter accepted from the peer, `None` before the
+    /// first successful unprotect.
+    pub fn last_peer_invocation_counter(&self) -> Option<u32> {
+        self.ic_valid.then_some(self.last_peer_ic)
+    }
+
+    /// Restores the last invocation counter accepted from the peer, e.g. from
+    /// persisted state after a restart, so replays of older APDUs stay
+    /// rejected.
+    pub fn set_last_peer_invocation_counter(&mut self, ic: u32) {
+        self.accept_peer_ic(ic);
+    }
+
     /// Advisory check for the host: true once the invocation counter is
     /// within 1000 of overflow, signalling that the keys should be rotated
     /// (a fresh system-title/key pair resets the counter) before
@@ -197,13 +197,21 @@ impl GeneralSigning {
     /// Encodes the APDU.
     pub fn encode(&self) -> Vec<u8> {
         let mut buf = vec![GENERAL_SIGNING_TAG];
+        buf.extend_from_slice(&self.signed_data());
+        push_octet_string(&self.signature, &mut buf);
+        buf
+    }
+
+    /// The octets the signature covers: the encoded fields before it, from
+    /// the transaction-id to the content.
+    pub fn signed_data(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
         push_octet_string(&self.transaction_id, &mut buf);
         push_octet_string(&self.originator_system_title, &mut buf);
         push_octet_string(&self.recipient_system_title, &mut buf);
         push_octet_string(&self.date_time, &mut buf);
         push_octet_string(&self.other_information, &mut buf);
         push_octet_string(&self.content, &mut buf);
-        push_octet_string(&self.signature, &mut buf);
         buf
     }
 
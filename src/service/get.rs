@@ -204,6 +204,14 @@ pub enum GetResponse {
 }
 
 impl GetResponse {
+    /// Encodes a GET-RESPONSE-NORMAL carrying an already encoded value, such
+    /// as a `compact-array` (see [`crate::types::compact`]).
+    pub fn encode_normal_raw(invoke_id_and_priority: u8, raw_data: &[u8]) -> Vec<u8> {
+        let mut buf = vec![tag::GET_RESPONSE, response_type::NORMAL, invoke_id_and_priority, 0x00];
+        buf.extend_from_slice(raw_data);
+        buf
+    }
+
     /// Encodes the response APDU.
     pub fn encode(&self) -> Result<Vec<u8>, ServiceError> {
         let mut buf = vec![tag::GET_RESPONSE];
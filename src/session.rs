@@ -40,7 +40,7 @@ use crate::service::acse::{AssociationRequest, AssociationResponse, ReleaseReque
 use crate::service::action::{ActionRequest, ActionResponse};
 use crate::service::ciphering::{self, glo, SecurityContext};
 use crate::service::gbt;
-use crate::service::get::{GetRequest, GetResponse};
+use crate::service::get::{AccessSelection, GetDataResult, GetRequest, GetResponse};
 use crate::service::set::{SetRequest, SetResponse};
 use crate::service::{invoke_id_and_priority, tag, AttributeDescriptor, MethodDescriptor, RawApdu};
 use crate::transport::DataLinkLayer;
@@ -678,12 +678,25 @@ impl<L: DataLinkLayer> ClientSession<L> {
 
     /// Reads one attribute (GET-REQUEST-NORMAL).
     pub fn get(&mut self, class_id: u16, instance: ObisCode, attribute_id: i8) -> Result<GetResponse, SessionError> {
+        self.get_with_selection(class_id, instance, attribute_id, None)
+    }
+
+    /// Reads one attribute with optional selective access (GET-REQUEST-NORMAL).
+    /// A long result arrives as its first GET-RESPONSE-WITH-DATABLOCK; see
+    /// [`Self::get_value`] to have the blocks collected.
+    pub fn get_with_selection(
+        &mut self,
+        class_id: u16,
+        instance: ObisCode,
+        attribute_id: i8,
+        access_selection: Option<AccessSelection>,
+    ) -> Result<GetResponse, SessionError> {
         #[cfg(feature = "tracing")]
         debug!(class_id, instance = %instance, attribute_id, "sending GET request");
         let request = GetRequest::Normal {
             invoke_id_and_priority: self.iiap(),
             attribute: AttributeDescriptor::new(class_id, instance, attribute_id),
-            access_selection: None,
+            access_selection,
         };
         let reply = self.transact(&request.encode()?, glo::GET_REQUEST, tag::GET_RESPONSE)?;
         let response = GetResponse::decode(&reply)?;
@@ -692,6 +705,46 @@ impl<L: DataLinkLayer> ClientSession<L> {
         Ok(response)
     }
 
+    /// Reads one attribute with optional selective access and returns its
+    /// complete result. A result delivered by block transfer is fetched with
+    /// GET-REQUEST-NEXT, acknowledging each block, and the reassembled data is
+    /// decoded; a block carrying a data-access-result ends the read with it.
+    pub fn get_value(
+        &mut self,
+        class_id: u16,
+        instance: ObisCode,
+        attribute_id: i8,
+        access_selection: Option<AccessSelection>,
+    ) -> Result<GetDataResult, SessionError> {
+        let mut response = self.get_with_selection(class_id, instance, attribute_id, access_selection)?;
+        let mut data = Vec::new();
+        let mut expected = 1;
+        loop {
+            match response {
+                GetResponse::Normal { result, .. } => return Ok(result),
+                GetResponse::WithDataBlock { raw_data: Err(code), .. } => return Ok(GetDataResult::AccessResult(code)),
+                GetResponse::WithDataBlock { last_block, block_number, raw_data: Ok(chunk), .. } => {
+                    if block_number != expected {
+                        return Err(crate::service::ServiceError::InvalidData.into());
+                    }
+                    data.extend_from_slice(&chunk);
+                    if last_block {
+                        let (value, _) =
+                            CosemDataType::deserialize_ber(&data).map_err(crate::service::ServiceError::from)?;
+                        return Ok(GetDataResult::Data(value));
+                    }
+                    #[cfg(feature = "tracing")]
+                    trace!(block_number, "requesting next GET datablock");
+                    let next = GetRequest::Next { invoke_id_and_priority: self.iiap(), block_number };
+                    let reply = self.transact(&next.encode()?, glo::GET_REQUEST, tag::GET_RESPONSE)?;
+                    response = GetResponse::decode(&reply)?;
+                    expected += 1;
+                }
+                GetResponse::WithList { .. } => return Err(SessionError::UnexpectedApdu(tag::GET_RESPONSE)),
+            }
+        }
+    }
+
     /// Writes one attribute (SET-REQUEST-NORMAL).
     pub fn set(
         &mut self,
@@ -886,7 +939,6 @@ fn is_transient_io(e: &io::Error) -> bool {
 mod tests {
     use super::*;
     use crate::service::acse::{application_context, mechanism, result};
-    use crate::service::get::GetDataResult;
     use crate::transport::wrapper::Wrapper;
     use crate::transport::MemoryTransport;
 
@@ -968,6 +1020,34 @@ mod tests {
         assert_eq!(got, response);
     }
 
+    #[test]
+    fn get_value_collects_datablocks() {
+        let mut value = Vec::new();
+        CosemDataType::OctetString(vec![0x5A; 40]).serialize_ber(&mut value).unwrap();
+        let (first, second) = value.split_at(20);
+        let mut link = LoopLink::new();
+        for (block_number, chunk, last_block) in [(1, first, false), (2, second, true)] {
+            let block = GetResponse::WithDataBlock {
+                invoke_id_and_priority: 0xC1,
+                last_block,
+                block_number,
+                raw_data: Ok(chunk.to_vec()),
+            };
+            link.queue_response(block.encode().unwrap());
+        }
+        let mut session = ClientSession::new(link);
+        let got = session.get_value(7, ObisCode::new(1, 0, 99, 1, 0, 0xFF), 2, None).unwrap();
+        assert_eq!(got, GetDataResult::Data(CosemDataType::OctetString(vec![0x5A; 40])));
+
+        // The second block was requested with GET-REQUEST-NEXT acknowledging block 1.
+        let sent = session.into_inner().sent;
+        assert_eq!(sent.len(), 2);
+        assert_eq!(
+            GetRequest::decode(&sent[1]).unwrap(),
+            GetRequest::Next { invoke_id_and_priority: 0xC1, block_number: 1 }
+        );
+    }
+
     #[test]
     fn get_round_trips_with_ciphering() {
         // A server context that will produce the ciphered GET-RESPONSE.
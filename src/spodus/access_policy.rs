@@ -12,11 +12,20 @@
 //!     array security_list { type: byte, key: octet-string }
 //! }
 //! ```
+//!
+//! The policy list is persisted as a snapshot through [`Persist`].
+
+use std::io;
 
 use crate::classes::data::Data;
 use crate::types::CosemDataType;
 
 use super::obis;
+use super::profile::{elements, fields, octets, unsigned};
+use super::storage::{invalid_data, Persist, Storage};
+
+/// Storage key of the policy-list snapshot.
+const POLICIES_KEY: &str = "access_policies";
 
 /// `type` values of a `security_list` item (§10.6).
 pub mod security_item_type {
@@ -78,6 +87,28 @@ impl AccessPolicy {
     }
 }
 
+impl TryFrom<&CosemDataType> for AccessPolicy {
+    type Error = String;
+
+    /// Decodes a `meters_passwords` structure (§10.6).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 4)?;
+        let security_list = elements(&f[3])?
+            .iter()
+            .map(|item| {
+                let item = fields(item, 2)?;
+                Ok(SecurityItem { item_type: unsigned(&item[0])?, key: octets(&item[1])? })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(AccessPolicy {
+            meter_id: octets(&f[0])?,
+            policy_id: unsigned(&f[1])?,
+            suite_id: unsigned(&f[2])?,
+            security_list,
+        })
+    }
+}
+
 /// The access-policy list (§10.6, `0.0.94.7.132.255`).
 #[derive(Clone, Debug, Default)]
 pub struct AccessPolicies {
@@ -107,6 +138,23 @@ impl AccessPolicies {
     }
 }
 
+impl Persist for AccessPolicies {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let array = self.policies.iter().map(AccessPolicy::to_structure).collect();
+        storage.save_snapshot(POLICIES_KEY, &CosemDataType::Array(array))
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.policies = match storage.load_snapshot(POLICIES_KEY)? {
+            Some(snapshot) => elements(&snapshot)
+                .and_then(|policies| policies.iter().map(AccessPolicy::try_from).collect())
+                .map_err(|e| invalid_data(POLICIES_KEY, e))?,
+            None => Vec::new(),
+        };
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,5 +189,12 @@ mod tests {
         assert_eq!(fields[2], CosemDataType::Unsigned(0));
         let CosemDataType::Array(items) = &fields[3] else { panic!("security_list array") };
         assert_eq!(items.len(), 2);
+
+        // The snapshot restores the same policies.
+        let mut storage = crate::spodus::storage::MemoryStorage::new();
+        policies.save(&mut storage).unwrap();
+        let mut restored = AccessPolicies::new();
+        restored.load(&storage).unwrap();
+        assert_eq!(restored.find(b"SIT12260004"), policies.find(b"SIT12260004"));
     }
 }
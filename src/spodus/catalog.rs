@@ -1,5 +1,6 @@
 //! Builders for the standard catalogue objects of the ИВКЭ (СТО 34.01-5.1-013-2023,
-//! Appendix A): Clock, SAP assignment, Security setup and Association LN.
+//! Appendix A): Clock, SAP assignment, Security setup, Association LN and the
+//! event Push setup.
 //!
 //! These reuse the crate's existing COSEM interface classes, instantiated at the
 //! Appendix-A OBIS codes with СТО-013 defaults.
@@ -8,10 +9,15 @@ use crate::classes::association_ln::{
     AssociationLn, AssociationLnConfig, AssociationLnVersion, AuthenticationMechanism,
 };
 use crate::classes::clock::{Clock, ClockConfig};
+use crate::classes::push_setup::{PushSetup, PushSetupConfig};
 use crate::classes::sap_assignment::{SapAssignment, SapAssignmentConfig};
 use crate::classes::security_setup::{SecuritySetup, SecuritySetupConfig};
 use crate::obis::ObisCode;
-use crate::types::attrs::{AssociatedPartnersId, ContextName, SapAssignmentEntry, XDLMSContextInfo};
+use crate::types::attrs::{
+    AssociatedPartnersId, CaptureObjectDefinition, ConfirmationParameters, ContextName, DateTime, SapAssignmentEntry,
+    SendDestinationAndMethod, XDLMSContextInfo,
+};
+use crate::types::CosemDataType;
 
 use super::obis;
 
@@ -29,7 +35,6 @@ pub mod client_id {
 
 /// The ИВКЭ Clock object (`0.0.1.0.0.255`, IC 8).
 pub fn clock() -> Clock {
-    use crate::types::attrs::DateTime;
     Clock::new(ClockConfig {
         logical_name: ObisCode::new(0, 0, 1, 0, 0, 255),
         time: DateTime([0u8; 12]),
@@ -90,6 +95,34 @@ pub fn association(obis: ObisCode, mechanism: AuthenticationMechanism, security_
     })
 }
 
+/// The ИВКЭ event Push setup (§8.5, `0.0.25.9.0.255`, IC 40 v2) sending the
+/// logical device name and the event push-message object (§8.5.11) to
+/// `destination` from the push-output client (§8.4).
+pub fn push_setup(destination: SendDestinationAndMethod) -> PushSetup {
+    // The push-output client id (34) fits an i8 SAP.
+    #[allow(clippy::cast_possible_wrap)]
+    let push_client_sap = client_id::PUSH as i8;
+    PushSetup::new(PushSetupConfig {
+        logical_name: obis::push_setup(),
+        version: 2,
+        push_object_list: vec![
+            CaptureObjectDefinition::new(1, obis::ivke_logical_name(), 2, 0),
+            CaptureObjectDefinition::new(1, obis::event_message(), 2, 0),
+        ],
+        send_destination_and_method: destination,
+        communication_window: vec![],
+        randomisation_start_interval: 0,
+        number_of_retries: 0,
+        repetition_delay: CosemDataType::LongUnsigned(0),
+        port_reference: vec![],
+        push_client_sap,
+        push_protection_parameters: vec![],
+        push_operation_method: 0,
+        confirmation_parameters: ConfirmationParameters { data: vec![] },
+        last_confirmation_date_time: DateTime([0u8; 12]),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,5 +149,10 @@ mod tests {
         );
         assert_eq!(assoc.class_id(), 15);
         assert_eq!(assoc.logical_name(), &ObisCode::new(0, 0, 40, 0, 1, 255));
+
+        let push = push_setup(SendDestinationAndMethod { transport_service: 0, destination: vec![], message: 0 });
+        assert_eq!(push.class_id(), 40);
+        assert_eq!(push.logical_name(), &obis::push_setup());
+        assert_eq!(push.push_client_sap(), 34);
     }
 }
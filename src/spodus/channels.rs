@@ -59,7 +59,7 @@ impl ChannelList {
             })
             .collect();
         let columns = [ObisCode::new(0, 0, 94, 7, 130, 1), ObisCode::new(0, 0, 94, 7, 130, 2)];
-        reference_profile(obis::channel_list(), &columns, buffer)
+        reference_profile(obis::channel_list(), &columns, buffer, 0)
     }
 }
 
@@ -26,12 +26,12 @@ pub fn poll_meter<L: DataLinkLayer>(
     let mut read = 0;
     for (class_id, obis, attribute) in attributes {
         if let Ok(GetResponse::Normal { result: GetDataResult::Data(value), .. }) =
-            session.get(*class_id, obis.clone(), *attribute)
+            session.get(*class_id, *obis, *attribute)
         {
             // Attribute ids are always <128 in practice (i8-valued on the wire).
             #[allow(clippy::cast_sign_loss)]
             let attribute_id = *attribute as u8;
-            registry.store(meter_id, obis.clone(), attribute_id, value);
+            registry.store(meter_id, *obis, attribute_id, value);
             read += 1;
         }
     }
@@ -68,13 +68,13 @@ mod tests {
         // The downstream "meter" exposes an energy register.
         let energy = ObisCode::new(1, 0, 1, 8, 0, 255);
         let mut meter_server = RequestDispatcher::new();
-        meter_server.add(Box::new(Data::new(energy.clone(), CosemDataType::DoubleLongUnsigned(123_456))));
+        meter_server.add(Box::new(Data::new(energy, CosemDataType::DoubleLongUnsigned(123_456))));
         let mut session = ClientSession::new(LocalLink { server: meter_server, pending: None });
 
         let mut registry = MeterRegistry::new();
         registry.add(MeterDescriptor { meter_id: b"SIT12260004".to_vec(), ..Default::default() });
 
-        let read = poll_meter(&mut session, &mut registry, b"SIT12260004", &[(1, energy.clone(), 2)]);
+        let read = poll_meter(&mut session, &mut registry, b"SIT12260004", &[(1, energy, 2)]);
         assert_eq!(read, 1);
         assert_eq!(registry.cached(b"SIT12260004", &energy, 2), Some(&CosemDataType::DoubleLongUnsigned(123_456)));
     }
@@ -3,8 +3,10 @@
 //! The ИВКЭ keeps a reference list of the meters it has found on its channels,
 //! exposed as a `ProfileGeneric` (IC 7, v1) whose buffer rows follow the Table-6
 //! column layout: `meter_id`, `meter_model`, `channel_id`, `address`,
-//! first-contact time and last-contact time.
+//! first-contact time and last-contact time. The list is persisted as a
+//! snapshot through [`Persist`].
 
+use std::io;
 use std::sync::Arc;
 
 use crate::classes::data::Data;
@@ -15,6 +17,11 @@ use crate::types::attrs::SortMethod;
 use crate::types::CosemDataType;
 
 use super::obis;
+use super::profile::{elements, fields, long_unsigned, octets, unsigned};
+use super::storage::{invalid_data, Persist, Storage};
+
+/// Storage key of the discovered-meters snapshot.
+const DISCOVERED_KEY: &str = "discovered_meters";
 
 /// One entry of the discovered-meters list (§10.5, Table 6).
 #[derive(Clone, Debug, Default)]
@@ -47,6 +54,23 @@ impl DiscoveredMeter {
     }
 }
 
+impl TryFrom<&CosemDataType> for DiscoveredMeter {
+    type Error = String;
+
+    /// Decodes a buffer row (Table 6).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 6)?;
+        Ok(DiscoveredMeter {
+            meter_id: octets(&f[0])?,
+            meter_model: octets(&f[1])?,
+            channel_id: unsigned(&f[2])?,
+            address: long_unsigned(&f[3])?,
+            first_seen: octets(&f[4])?,
+            last_seen: octets(&f[5])?,
+        })
+    }
+}
+
 /// The discovered-meters list (§10.5).
 #[derive(Clone, Debug, Default)]
 pub struct DiscoveredMeters {
@@ -105,6 +129,23 @@ impl DiscoveredMeters {
     }
 }
 
+impl Persist for DiscoveredMeters {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let rows = self.records.iter().map(DiscoveredMeter::to_entry).collect();
+        storage.save_snapshot(DISCOVERED_KEY, &CosemDataType::Array(rows))
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.records = match storage.load_snapshot(DISCOVERED_KEY)? {
+            Some(snapshot) => elements(&snapshot)
+                .and_then(|rows| rows.iter().map(DiscoveredMeter::try_from).collect())
+                .map_err(|e| invalid_data(DISCOVERED_KEY, e))?,
+            None => Vec::new(),
+        };
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
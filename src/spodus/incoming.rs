@@ -0,0 +1,502 @@
+//! Incoming meter push reception (СТО 34.01-5.1-013-2023, §8.5.10 and §8.5.11).
+//!
+//! Meters push their events to the ИВКЭ as DATA-NOTIFICATION or
+//! EVENT-NOTIFICATION APDUs, plain or ciphered, carried by the wrapper (TCP/UDP)
+//! or in HDLC UI frames. [`PushReceiver`] identifies the sending meter — by the
+//! system title of a general-ciphering APDU, otherwise by its link address —
+//! extracts the meter events, stores them in the incoming push-events table and
+//! queues those passing the push-filter mask as event push-messages, ready to be
+//! published upstream with [`Concentrator::publish_events`].
+//!
+//! A meter event is the value of one of its event objects `0.0.96.11.e.255`
+//! (class Data): `e` is the event-journal field and the value the event code.
+//!
+//! The invocation counters of the meter security contexts are persisted as a
+//! snapshot through [`Persist`], so a restarted ИВКЭ keeps rejecting replays;
+//! the keys themselves are configuration and are not stored.
+
+use std::collections::HashMap;
+use std::io;
+
+use crate::listener::{Framing, ListenerError, Notification, PushDevice, PushListener};
+use crate::obis::ObisCode;
+use crate::service::ciphering::SecurityContext;
+use crate::service::notification::DataNotification;
+use crate::types::attrs::{CaptureObjectDefinition, DateTime};
+use crate::types::CosemDataType;
+
+use super::node::Concentrator;
+use super::profile::{double_long_unsigned, elements, fields, octets};
+use super::push::EventMessage;
+use super::records::{transmission_status, IncomingEvent};
+use super::storage::{invalid_data, Persist, Storage};
+
+/// Storage key of the push invocation-counter snapshot.
+const COUNTERS_KEY: &str = "push_counters";
+
+/// A meter allowed to push to the ИВКЭ.
+#[derive(Clone, Debug, Default)]
+pub struct PushSource {
+    /// Meter identifier, as in the meter registry.
+    pub meter_id: Vec<u8>,
+    /// Link address the meter pushes from: the wrapper source wPort or the HDLC
+    /// source (server) address.
+    pub address: Option<u32>,
+    /// Security context for ciphered pushes; its system title is the meter's.
+    /// When set, plain pushes from this meter are rejected.
+    pub security: Option<SecurityContext>,
+    /// The meter Push setup's `push_object_list`, describing the elements of a
+    /// DATA-NOTIFICATION body.
+    pub push_objects: Vec<CaptureObjectDefinition>,
+}
+
+/// Receives meter pushes into a [`Concentrator`]. Unframing, deciphering and
+/// sender identification are done by a [`PushListener`]; this adds the meter
+/// push object lists and the event storage.
+#[derive(Clone, Debug, Default)]
+pub struct PushReceiver {
+    listener: PushListener,
+    push_objects: HashMap<Vec<u8>, Vec<CaptureObjectDefinition>>,
+}
+
+impl PushReceiver {
+    /// Creates a receiver with no known meters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a meter allowed to push.
+    pub fn add(&mut self, source: PushSource) {
+        self.listener.add_device(PushDevice {
+            id: source.meter_id.clone(),
+            system_title: source.security.as_ref().map(|c| c.system_title.clone()).unwrap_or_default(),
+            address: source.address,
+            global: source.security,
+            ..Default::default()
+        });
+        self.push_objects.insert(source.meter_id, source.push_objects);
+    }
+
+    /// Receives a wrapper PDU or an HDLC UI frame, per `framing`. Returns the
+    /// number of events stored.
+    pub fn receive(
+        &mut self,
+        node: &mut Concentrator,
+        framing: Framing,
+        pdu: &[u8],
+        now: &DateTime,
+    ) -> Result<usize, ListenerError> {
+        let received = self.listener.receive(framing, pdu)?;
+        self.store(node, received.device, received.notification, now)
+    }
+
+    /// Receives a wrapper PDU (TCP or UDP), identifying a plain or
+    /// glo-ciphered push by its source wPort. Returns the number of events
+    /// stored.
+    pub fn receive_wrapper(
+        &mut self,
+        node: &mut Concentrator,
+        pdu: &[u8],
+        now: &DateTime,
+    ) -> Result<usize, ListenerError> {
+        let received = self.listener.receive(Framing::Wrapper, pdu)?;
+        self.store(node, received.device, received.notification, now)
+    }
+
+    /// Receives an HDLC UI frame, identifying a plain or glo-ciphered push by
+    /// its source address. Returns the number of events stored.
+    pub fn receive_hdlc(
+        &mut self,
+        node: &mut Concentrator,
+        frame: &[u8],
+        now: &DateTime,
+    ) -> Result<usize, ListenerError> {
+        let received = self.listener.receive(Framing::Hdlc, frame)?;
+        self.store(node, received.device, received.notification, now)
+    }
+
+    /// Receives a push APDU: a DATA-NOTIFICATION or EVENT-NOTIFICATION, plain,
+    /// glo-ciphered (sender identified by `address`) or wrapped in
+    /// general-glo/ded-ciphering (sender identified by its system title). The
+    /// meter events are stored in the incoming push-events table with `now` as
+    /// the ИВКЭ fix time; those passing the push-filter mask are queued as
+    /// event push-messages. Returns the number of events stored.
+    pub fn receive_apdu(
+        &mut self,
+        node: &mut Concentrator,
+        address: Option<u32>,
+        apdu: &[u8],
+        now: &DateTime,
+    ) -> Result<usize, ListenerError> {
+        let received = self.listener.receive_apdu(address, apdu)?;
+        self.store(node, received.device, received.notification, now)
+    }
+
+    /// Stores the events of a notification from a registered meter.
+    fn store(
+        &self,
+        node: &mut Concentrator,
+        device: Option<Vec<u8>>,
+        notification: Notification,
+        now: &DateTime,
+    ) -> Result<usize, ListenerError> {
+        let meter_id = device.ok_or(ListenerError::UnknownDevice)?;
+        let events = match notification {
+            Notification::Data(data) => {
+                let push_objects = self.push_objects.get(&meter_id).map_or(&[][..], Vec::as_slice);
+                data_notification_events(push_objects, &data)
+            }
+            Notification::Event(event) => {
+                let time = event.time.unwrap_or_default();
+                event_of(event.attribute.class_id, &event.attribute.instance_id, &event.value)
+                    .map(|(field, code)| (time, field, code))
+                    .into_iter()
+                    .collect()
+            }
+        };
+        Ok(store_events(node, &meter_id, events, now))
+    }
+}
+
+/// The counters of a security context: `structure { invocation_counter,
+/// last peer invocation_counter or null }`, or null without a context.
+fn counters_of(context: Option<&SecurityContext>) -> CosemDataType {
+    context.map_or(CosemDataType::Null, |c| {
+        CosemDataType::Structure(vec![
+            CosemDataType::DoubleLongUnsigned(c.invocation_counter),
+            c.last_peer_invocation_counter().map_or(CosemDataType::Null, CosemDataType::DoubleLongUnsigned),
+        ])
+    })
+}
+
+/// Restores the counters saved by [`counters_of`] into `context`, if both exist.
+fn restore_counters(context: Option<&mut SecurityContext>, value: &CosemDataType) -> Result<(), String> {
+    let (Some(context), CosemDataType::Structure(_)) = (context, value) else {
+        return Ok(());
+    };
+    let f = fields(value, 2)?;
+    context.invocation_counter = double_long_unsigned(&f[0])?;
+    if !matches!(f[1], CosemDataType::Null) {
+        context.set_last_peer_invocation_counter(double_long_unsigned(&f[1])?);
+    }
+    Ok(())
+}
+
+/// Saves `structure { meter_id, global counters, dedicated counters }` per
+/// registered meter. Loading restores the counters of the meters registered
+/// by then; register the meters before [`Concentrator::load`].
+impl Persist for PushReceiver {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let rows = self
+            .listener
+            .devices()
+            .iter()
+            .map(|device| {
+                CosemDataType::Structure(vec![
+                    CosemDataType::OctetString(device.id.clone()),
+                    counters_of(device.global.as_ref()),
+                    counters_of(device.dedicated.as_ref()),
+                ])
+            })
+            .collect();
+        storage.save_snapshot(COUNTERS_KEY, &CosemDataType::Array(rows))
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        let Some(snapshot) = storage.load_snapshot(COUNTERS_KEY)? else {
+            return Ok(());
+        };
+        let restore = |devices: &mut [PushDevice]| -> Result<(), String> {
+            for row in elements(&snapshot)? {
+                let f = fields(row, 3)?;
+                let id = octets(&f[0])?;
+                if let Some(device) = devices.iter_mut().find(|d| d.id == id) {
+                    restore_counters(device.global.as_mut(), &f[1])?;
+                    restore_counters(device.dedicated.as_mut(), &f[2])?;
+                }
+            }
+            Ok(())
+        };
+        restore(self.listener.devices_mut()).map_err(|e| invalid_data(COUNTERS_KEY, e))
+    }
+}
+
+/// The `(journal field, event code)` of a pushed value, if it comes from a meter
+/// event object `0.0.96.11.e.255` (class Data).
+fn event_of(class_id: u16, instance: &ObisCode, value: &CosemDataType) -> Option<(u8, u16)> {
+    let ln = instance.to_bytes();
+    if class_id != 1 || ln[..4] != [0, 0, 96, 11] || ln[5] != 255 {
+        return None;
+    }
+    let code = match value {
+        CosemDataType::Unsigned(v) | CosemDataType::Enum(v) => u16::from(*v),
+        CosemDataType::LongUnsigned(v) => *v,
+        _ => return None,
+    };
+    Some((ln[4], code))
+}
+
+/// Extracts `(meter time, journal field, code)` events from a DATA-NOTIFICATION
+/// body laid out per the meter's push object list. The meter time is the
+/// notification date-time, else a pushed Clock time.
+fn data_notification_events(
+    push_objects: &[CaptureObjectDefinition],
+    notification: &DataNotification,
+) -> Vec<(Vec<u8>, u8, u16)> {
+    let values = match (&notification.notification_body, push_objects.len()) {
+        (body, 1) => std::slice::from_ref(body),
+        (CosemDataType::Array(items) | CosemDataType::Structure(items), _) => items.as_slice(),
+        (body, _) => std::slice::from_ref(body),
+    };
+    let pushed: Vec<_> = push_objects.iter().zip(values).collect();
+    let time = if notification.date_time.is_empty() {
+        pushed
+            .iter()
+            .find(|(object, _)| object.class_id == 8 && object.attribute_index == 2)
+            .and_then(|(_, value)| DateTime::try_from(*value).ok())
+            .map(|t| t.0.to_vec())
+            .unwrap_or_default()
+    } else {
+        notification.date_time.clone()
+    };
+    pushed
+        .iter()
+        .filter(|(object, _)| object.attribute_index == 2)
+        .filter_map(|(object, value)| event_of(object.class_id, &object.logical_name, value))
+        .map(|(field, code)| (time.clone(), field, code))
+        .collect()
+}
+
+/// Stores the events of one meter and queues those passing the push mask.
+fn store_events(node: &mut Concentrator, meter_id: &[u8], events: Vec<(Vec<u8>, u8, u16)>, now: &DateTime) -> usize {
+    let meter_model = node.meters.find(meter_id).map(|m| m.meter_model.clone()).unwrap_or_default();
+    let count = events.len();
+    for (meter_time, journal_field, code) in events {
+        let relayed = node.push_mask.is_none_or(|mask| journal_field < 32 && mask & (1 << journal_field) != 0);
+        node.incoming_events.record(IncomingEvent {
+            meter_id: meter_id.to_vec(),
+            meter_model: meter_model.clone(),
+            ivke_time: now.0.to_vec(),
+            meter_time: meter_time.clone(),
+            journal_field,
+            code,
+            status: if relayed { transmission_status::WAITING } else { transmission_status::DISABLED },
+        });
+        if relayed {
+            node.events.push(EventMessage {
+                uspd_ln: node.logical_device_name.clone(),
+                meter_number: meter_id.to_vec(),
+                meter_model: meter_model.clone(),
+                date_time: meter_time,
+                journal_id: journal_field,
+                code,
+            });
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{SecurityPolicy, SecuritySuite};
+    use crate::service::ciphering::{self, glo, CipherError};
+    use crate::service::general_ciphering::{GeneralGloDedCiphering, GENERAL_GLO_CIPHERING_TAG};
+    use crate::service::notification::EventNotificationRequest;
+    use crate::service::AttributeDescriptor;
+    use crate::spodus::catalog;
+    use crate::spodus::meter::MeterDescriptor;
+    use crate::transport::hdlc::{Control, HdlcAddress, HdlcFrame};
+    use crate::transport::wrapper;
+    use crate::types::attrs::SendDestinationAndMethod;
+
+    /// DLMS LLC header of an HDLC frame sent by a server (the pushing meter).
+    const LLC_RESPONSE: [u8; 3] = [0xE6, 0xE7, 0x00];
+
+    const METER_ST: [u8; 8] = *b"SIT\x00\x00\x00\x00\x04";
+
+    fn context(invocation_counter: u32) -> SecurityContext {
+        SecurityContext::for_suite(
+            SecurityPolicy::AuthenticationEncryption,
+            SecuritySuite::Suite0,
+            vec![0x11; 16],
+            vec![0x22; 16],
+            METER_ST.to_vec(),
+            invocation_counter,
+        )
+        .unwrap()
+    }
+
+    fn event_object(field: u8) -> ObisCode {
+        ObisCode::new(0, 0, 96, 11, field, 255)
+    }
+
+    fn node() -> Concentrator {
+        let mut node = Concentrator { logical_device_name: b"IVKE000000000001".to_vec(), ..Default::default() };
+        node.meters.add(MeterDescriptor {
+            meter_id: b"SIT12260004".to_vec(),
+            meter_model: b"SiT".to_vec(),
+            channels: vec![],
+        });
+        node
+    }
+
+    #[test]
+    fn plain_data_notification_is_stored_filtered_and_published() {
+        let mut receiver = PushReceiver::new();
+        receiver.add(PushSource {
+            meter_id: b"SIT12260004".to_vec(),
+            address: Some(1),
+            security: None,
+            push_objects: vec![
+                CaptureObjectDefinition::new(8, ObisCode::new(0, 0, 1, 0, 0, 255), 2, 0),
+                CaptureObjectDefinition::new(1, event_object(0), 2, 0),
+                CaptureObjectDefinition::new(1, event_object(2), 2, 0),
+            ],
+        });
+        let mut node = node();
+        node.push_mask = Some(0b01); // relay voltage events (field 0) only
+        node.push_setup = Some(catalog::push_setup(SendDestinationAndMethod {
+            transport_service: 0,
+            destination: b"10.0.0.1:4059".to_vec(),
+            message: 0,
+        }));
+
+        let meter_time = DateTime::from_ymdhms(2026, 10, 18, 12, 0, 0);
+        let notification = DataNotification {
+            long_invoke_id_and_priority: 1,
+            date_time: Vec::new(),
+            notification_body: CosemDataType::Structure(vec![
+                meter_time.clone().into(),
+                CosemDataType::LongUnsigned(0x1C),
+                CosemDataType::LongUnsigned(0x05),
+            ]),
+        };
+        let pdu = wrapper::encode(1, 0x10, &notification.encode().unwrap());
+        let now = DateTime::from_ymdhms(2026, 10, 18, 12, 0, 5);
+        assert_eq!(receiver.receive_wrapper(&mut node, &pdu, &now).unwrap(), 2);
+
+        let rows = node.incoming_events.rows();
+        assert_eq!((rows[0].journal_field, rows[0].code), (0, 0x1C));
+        assert_eq!(rows[0].meter_model, b"SiT".to_vec());
+        assert_eq!(rows[0].meter_time, meter_time.0.to_vec());
+        assert_eq!(rows[0].status, transmission_status::WAITING);
+        assert_eq!(rows[1].status, transmission_status::DISABLED);
+        assert_eq!(node.events.messages().len(), 1);
+
+        let (request, _) = node.publish_events(2).unwrap().expect("a push");
+        assert_eq!(request.destination, b"10.0.0.1:4059".to_vec());
+        let body = DataNotification::decode(&request.body).unwrap().notification_body;
+        let CosemDataType::Array(values) = body else { panic!("push body") };
+        assert_eq!(values[0], CosemDataType::OctetString(b"IVKE000000000001".to_vec()));
+        let CosemDataType::Array(messages) = &values[1] else { panic!("event messages") };
+        assert_eq!(messages.len(), 1);
+
+        // The send failed: the events stay queued and are published again.
+        assert_eq!(node.events.messages().len(), 1);
+        assert_eq!(node.incoming_events.rows()[0].status, transmission_status::WAITING);
+        let (retry, published) = node.publish_events(3).unwrap().expect("the push again");
+        assert_eq!(published.len(), 1);
+        let resent = DataNotification::decode(&retry.body).unwrap().notification_body;
+        assert_eq!(resent, CosemDataType::Array(values));
+
+        // A push received while the retry is in flight is not part of it.
+        let later = DateTime::from_ymdhms(2026, 10, 18, 12, 0, 10);
+        let notification = DataNotification {
+            long_invoke_id_and_priority: 2,
+            date_time: Vec::new(),
+            notification_body: CosemDataType::Structure(vec![
+                later.clone().into(),
+                CosemDataType::LongUnsigned(0x1D),
+                CosemDataType::LongUnsigned(0x05),
+            ]),
+        };
+        let pdu = wrapper::encode(1, 0x10, &notification.encode().unwrap());
+        assert_eq!(receiver.receive_wrapper(&mut node, &pdu, &later).unwrap(), 2);
+        assert_eq!(node.events.messages().len(), 2);
+
+        node.confirm_events(&published);
+        let rows = node.incoming_events.rows();
+        assert_eq!(rows[0].status, transmission_status::SENT_UNCONFIRMED);
+        assert_eq!((rows[2].code, rows[2].status), (0x1D, transmission_status::WAITING));
+        assert_eq!(node.events.messages().len(), 1);
+        assert_eq!(node.events.messages()[0].code, 0x1D);
+
+        let (_, published) = node.publish_events(4).unwrap().expect("the later event");
+        node.confirm_events(&published);
+        assert!(node.events.is_empty());
+        assert_eq!(node.incoming_events.rows()[2].status, transmission_status::SENT_UNCONFIRMED);
+        assert_eq!(node.publish_events(5).unwrap(), None);
+    }
+
+    #[test]
+    fn ciphered_event_notification_is_identified_by_system_title() {
+        let mut receiver = PushReceiver::new();
+        receiver.add(PushSource {
+            meter_id: b"SIT12260004".to_vec(),
+            security: Some(context(0)),
+            ..Default::default()
+        });
+        let mut node = node();
+
+        let event = EventNotificationRequest {
+            time: Some(DateTime::from_ymdhms(2026, 10, 18, 11, 59, 0).0.to_vec()),
+            attribute: AttributeDescriptor::new(1, event_object(1), 2),
+            value: CosemDataType::LongUnsigned(0x21),
+        };
+        let protected = ciphering::protect(&context(7), GENERAL_GLO_CIPHERING_TAG, &event.encode().unwrap()).unwrap();
+        // Strip the tag and length: the general-glo-ciphering body is SC ‖ IC ‖ ciphertext ‖ tag.
+        let general = GeneralGloDedCiphering {
+            dedicated: false,
+            system_title: METER_ST.to_vec(),
+            ciphered_service: protected[2..].to_vec(),
+        };
+        let mut information = LLC_RESPONSE.to_vec();
+        information.extend_from_slice(&general.encode());
+        let frame = HdlcFrame::new(
+            HdlcAddress::one_byte(0x10),
+            HdlcAddress::new(0x4011, 2),
+            Control::Ui { poll: false },
+            information,
+        );
+
+        let now = DateTime::from_ymdhms(2026, 10, 18, 12, 0, 0);
+        assert_eq!(receiver.receive_hdlc(&mut node, &frame.encode(), &now).unwrap(), 1);
+        let row = &node.incoming_events.rows()[0];
+        assert_eq!((row.meter_id.as_slice(), row.journal_field, row.code), (&b"SIT12260004"[..], 1, 0x21));
+        assert_eq!(node.events.messages()[0].uspd_ln, b"IVKE000000000001".to_vec());
+
+        // The same APDU again is a replay.
+        assert!(matches!(
+            receiver.receive_hdlc(&mut node, &frame.encode(), &now),
+            Err(ListenerError::Cipher(CipherError::ReplayDetected))
+        ));
+    }
+
+    #[test]
+    fn unknown_and_unprotected_pushes_are_rejected() {
+        let mut receiver = PushReceiver::new();
+        receiver.add(PushSource {
+            meter_id: b"M1".to_vec(),
+            address: Some(1),
+            security: Some(context(0)),
+            ..Default::default()
+        });
+        let mut node = node();
+        let now = DateTime::from_ymdhms(2026, 10, 18, 12, 0, 0);
+        let event = EventNotificationRequest {
+            time: None,
+            attribute: AttributeDescriptor::new(1, event_object(0), 2),
+            value: CosemDataType::Unsigned(1),
+        }
+        .encode()
+        .unwrap();
+
+        assert!(matches!(receiver.receive_apdu(&mut node, Some(2), &event, &now), Err(ListenerError::UnknownDevice)));
+        assert!(matches!(receiver.receive_apdu(&mut node, Some(1), &event, &now), Err(ListenerError::Unprotected(_))));
+
+        let ciphered = ciphering::protect(&context(1), glo::EVENT_NOTIFICATION_REQUEST, &event).unwrap();
+        assert_eq!(receiver.receive_apdu(&mut node, Some(1), &ciphered, &now).unwrap(), 1);
+        assert!(node.incoming_events.rows()[0].meter_time.is_empty());
+    }
+}
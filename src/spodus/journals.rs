@@ -6,8 +6,10 @@
 //!   and codes follow ГОСТ Р 58940-2020 (timestamp + event code).
 //!
 //! Both are `ProfileGeneric` (IC 7, v1) objects; the typed models here build the
-//! buffer and the capture-object column schema.
+//! buffer and the capture-object column schema. Each journal keeps at most its
+//! `profile_entries` rows (FIFO) and is persisted through [`Persist`].
 
+use std::io;
 use std::sync::Arc;
 
 use crate::classes::data::Data;
@@ -18,6 +20,11 @@ use crate::types::attrs::SortMethod;
 use crate::types::CosemDataType;
 
 use super::obis;
+use super::profile::{fields, long_unsigned, octets, push_bounded, unsigned};
+use super::storage::{self, Persist, Storage};
+
+/// Storage key of the data-exchange-status journal.
+const EXCHANGE_KEY: &str = "exchange_journal";
 
 /// `status` values of an exchange-status record (§10.9, Table 8, column 4).
 pub mod exchange_status {
@@ -79,21 +86,53 @@ impl ExchangeRecord {
     }
 }
 
+impl TryFrom<&CosemDataType> for ExchangeRecord {
+    type Error = String;
+
+    /// Decodes a journal row (Table 8).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 6)?;
+        Ok(ExchangeRecord {
+            task_id: u32::from(long_unsigned(&f[0])?),
+            meter_uid: octets(&f[1])?,
+            start: octets(&f[2])?,
+            status: unsigned(&f[3])?,
+            end: octets(&f[4])?,
+            attempts: unsigned(&f[5])?,
+        })
+    }
+}
+
 /// The data-exchange-status journal (§10.9, `0.0.94.7.135.255`).
 #[derive(Clone, Debug, Default)]
 pub struct ExchangeStatusJournal {
     records: Vec<ExchangeRecord>,
+    profile_entries: u32,
+    unsaved: usize,
 }
 
 impl ExchangeStatusJournal {
-    /// Creates an empty journal.
+    /// Creates an empty, unbounded journal.
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Appends an exchange-status record.
+    /// Bounds the journal to its last `profile_entries` records (0 = unbounded).
+    pub fn with_profile_entries(mut self, profile_entries: u32) -> Self {
+        self.profile_entries = profile_entries;
+        self
+    }
+
+    /// Appends an exchange-status record, evicting the oldest record when the
+    /// journal is full.
     pub fn append(&mut self, record: ExchangeRecord) {
-        self.records.push(record);
+        push_bounded(&mut self.records, record, self.profile_entries);
+        self.unsaved = (self.unsaved + 1).min(self.records.len());
+    }
+
+    /// The recorded exchanges, oldest first.
+    pub fn records(&self) -> &[ExchangeRecord] {
+        &self.records
     }
 
     /// The Table-8 columns as capture-object markers.
@@ -123,49 +162,93 @@ impl ExchangeStatusJournal {
             sort_method: SortMethod::Fifo,
             sort_object: None,
             entries_in_use,
-            profile_entries: 0,
+            profile_entries: self.profile_entries,
         })
     }
 }
 
+impl Persist for ExchangeStatusJournal {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let profile_entries = self.profile_entries;
+        storage::save_journal(storage, EXCHANGE_KEY, &self.records, &mut self.unsaved, profile_entries, |r| {
+            r.to_entry()
+        })
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.records =
+            storage::load_journal(storage, EXCHANGE_KEY, self.profile_entries, |row| ExchangeRecord::try_from(row))?;
+        self.unsaved = 0;
+        Ok(())
+    }
+}
+
 /// An ИВКЭ event journal (§10.13), following ГОСТ Р 58940-2020: each entry is a
 /// timestamp and an event code, captured by a Clock and an event register.
 #[derive(Clone, Debug)]
 pub struct EventJournal {
     logical_name: ObisCode,
     entries: Vec<(Vec<u8>, u16)>,
+    profile_entries: u32,
+    unsaved: usize,
 }
 
 impl EventJournal {
-    /// Creates an empty event journal with the given OBIS (one of the §10.13
-    /// codes, e.g. [`obis::access_control_log`]).
+    /// Creates an empty, unbounded event journal with the given OBIS (one of
+    /// the §10.13 codes, e.g. [`obis::access_control_log`]).
     pub fn new(logical_name: ObisCode) -> Self {
-        EventJournal { logical_name, entries: Vec::new() }
+        EventJournal { logical_name, entries: Vec::new(), profile_entries: 0, unsaved: 0 }
+    }
+
+    /// Bounds the journal to its last `profile_entries` events (0 = unbounded).
+    pub fn with_profile_entries(mut self, profile_entries: u32) -> Self {
+        self.profile_entries = profile_entries;
+        self
     }
 
-    /// Logs an event: `timestamp` (date-time octets) and its code.
+    /// The journal's OBIS code.
+    pub fn logical_name(&self) -> &ObisCode {
+        &self.logical_name
+    }
+
+    /// Logs an event: `timestamp` (date-time octets) and its code, evicting the
+    /// oldest event when the journal is full.
     pub fn log(&mut self, timestamp: Vec<u8>, code: u16) {
-        self.entries.push((timestamp, code));
+        push_bounded(&mut self.entries, (timestamp, code), self.profile_entries);
+        self.unsaved = (self.unsaved + 1).min(self.entries.len());
+    }
+
+    /// The logged events as `(timestamp, code)`, oldest first.
+    pub fn entries(&self) -> &[(Vec<u8>, u16)] {
+        &self.entries
+    }
+
+    /// Storage key of this journal, derived from its OBIS code.
+    fn storage_key(&self) -> String {
+        format!("event_journal.{}", self.logical_name)
+    }
+
+    fn to_entry((timestamp, code): &(Vec<u8>, u16)) -> CosemDataType {
+        CosemDataType::Structure(vec![CosemDataType::DateTime(timestamp.clone()), CosemDataType::LongUnsigned(*code)])
+    }
+
+    fn from_entry(value: &CosemDataType) -> Result<(Vec<u8>, u16), String> {
+        let f = fields(value, 2)?;
+        Ok((octets(&f[0])?, long_unsigned(&f[1])?))
     }
 
     /// Builds the COSEM `ProfileGeneric` (IC 7, v1) journal object.
     pub fn build(&self) -> ProfileGeneric {
-        let buffer: Vec<CosemDataType> = self
-            .entries
-            .iter()
-            .map(|(ts, code)| {
-                CosemDataType::Structure(vec![CosemDataType::DateTime(ts.clone()), CosemDataType::LongUnsigned(*code)])
-            })
-            .collect();
+        let buffer: Vec<CosemDataType> = self.entries.iter().map(Self::to_entry).collect();
         // An in-memory buffer never approaches u32::MAX entries.
         #[allow(clippy::cast_possible_truncation)]
         let entries_in_use = buffer.len() as u32;
         let columns = vec![
             column(ObisCode::new(0, 0, 1, 0, 0, 255)), // Clock timestamp
-            column(self.logical_name.clone()),         // event code register
+            column(self.logical_name),                 // event code register
         ];
         ProfileGeneric::new(ProfileGenericConfig {
-            logical_name: self.logical_name.clone(),
+            logical_name: self.logical_name,
             version: 1,
             buffer,
             capture_objects: columns,
@@ -173,11 +256,25 @@ impl EventJournal {
             sort_method: SortMethod::Fifo,
             sort_object: None,
             entries_in_use,
-            profile_entries: 0,
+            profile_entries: self.profile_entries,
         })
     }
 }
 
+impl Persist for EventJournal {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let key = self.storage_key();
+        let profile_entries = self.profile_entries;
+        storage::save_journal(storage, &key, &self.entries, &mut self.unsaved, profile_entries, Self::to_entry)
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.entries = storage::load_journal(storage, &self.storage_key(), self.profile_entries, Self::from_entry)?;
+        self.unsaved = 0;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,4 +321,24 @@ mod tests {
         let CosemDataType::Array(caps) = &attrs[2].1 else { panic!("capture array") };
         assert_eq!(caps.len(), 2);
     }
+
+    #[test]
+    fn event_journal_evicts_oldest_and_reloads() {
+        let mut journal = EventJournal::new(obis::access_control_log()).with_profile_entries(2);
+        let mut storage = storage::MemoryStorage::new();
+        for code in 1..=3 {
+            journal.log(vec![0x07, 0xE6, 0x07, 0x04], code);
+            journal.save(&mut storage).unwrap();
+        }
+        let codes: Vec<u16> = journal.entries().iter().map(|(_, code)| *code).collect();
+        assert_eq!(codes, [2, 3]);
+
+        let mut restored = EventJournal::new(obis::access_control_log()).with_profile_entries(2);
+        restored.load(&storage).unwrap();
+        assert_eq!(restored.entries(), journal.entries());
+        // A journal with another OBIS does not see these events.
+        let mut other = EventJournal::new(obis::meter_list());
+        other.load(&storage).unwrap();
+        assert!(other.entries().is_empty());
+    }
 }
@@ -0,0 +1,711 @@
+//! Load-profile and billing-profile collection (СТО 34.01-5.1-013-2023, §10).
+//!
+//! The ИВКЭ reads meters' `Profile generic` (IC 7) buffers — load profiles and
+//! daily/monthly billing profiles — incrementally. [`ProfileCollector`]
+//! remembers, per meter and profile, the time of the last collected entry and
+//! asks only for newer entries with a `range_descriptor` (selector 1) on the
+//! profile's clock column — the capture object of the Clock `0.0.1.0.0.255`,
+//! wherever the meter places it; long buffers are fetched by block transfer.
+//! Overlapping entries are dropped, entries with a null time (the СПОДЭС
+//! compressed form) get their time from the capture period, and missing
+//! capture periods are reported as gaps.
+//!
+//! The collected rows, each prefixed with the meter identifier, are served
+//! upstream through a [`ProfileDataFilter`] (all rows) or a [`TableManager`]
+//! (the latest row per meter), and persisted through [`Persist`].
+
+use std::io;
+
+use crate::obis::ObisCode;
+use crate::service::get::{AccessSelection, GetDataResult};
+use crate::session::{ClientSession, SessionError};
+use crate::transport::DataLinkLayer;
+use crate::types::attrs::{CaptureObjectDefinition, DateTime};
+use crate::types::CosemDataType;
+
+use super::profile::{elements, fields, octets, push_bounded};
+use super::profile_filter::ProfileDataFilter;
+use super::storage::{self, invalid_data, Persist, Storage};
+use super::table_manager::TableManager;
+
+/// Storage key of the collection cursors snapshot.
+const CURSORS_KEY: &str = "profile_cursors";
+
+/// Column code of the meter identifier prefixed to every collected row.
+fn meter_uid_column() -> ObisCode {
+    ObisCode::new(0, 0, 94, 7, 128, 10)
+}
+
+/// The position of the Clock (`0.0.1.0.0.255`, class 8) among a profile's
+/// capture objects.
+fn clock_column(capture_objects: &[CosemDataType]) -> Result<usize, CollectError> {
+    let clock = ObisCode::new(0, 0, 1, 0, 0, 255);
+    capture_objects
+        .iter()
+        .position(|c| CaptureObjectDefinition::try_from(c).is_ok_and(|d| d.class_id == 8 && d.logical_name == clock))
+        .ok_or(CollectError::NoClock)
+}
+
+/// A meter profile to collect.
+#[derive(Clone, Debug)]
+pub struct ProfileSpec {
+    /// Logical name of the profile in the meter (e.g. `1.0.99.1.0.255`). It
+    /// must capture the Clock `0.0.1.0.0.255`.
+    pub logical_name: ObisCode,
+    /// Capture period in seconds, used for gap detection and for null entry
+    /// times; 0 for profiles without a fixed period (e.g. monthly billing).
+    pub capture_period: u32,
+    /// Number of collected rows kept by the ИВКЭ (0 = unbounded).
+    pub profile_entries: u32,
+}
+
+impl ProfileSpec {
+    /// Describes a profile with the given capture period, kept unbounded.
+    pub fn new(logical_name: ObisCode, capture_period: u32) -> Self {
+        ProfileSpec { logical_name, capture_period, profile_entries: 0 }
+    }
+
+    /// Bounds the collected rows to the last `profile_entries`.
+    pub fn with_profile_entries(mut self, profile_entries: u32) -> Self {
+        self.profile_entries = profile_entries;
+        self
+    }
+}
+
+/// Missing capture periods between two collected entries of a meter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfileGap {
+    /// Meter identifier.
+    pub meter_id: Vec<u8>,
+    /// Time of the entry before the gap (date-time octets).
+    pub after: Vec<u8>,
+    /// Time of the entry after the gap (date-time octets).
+    pub before: Vec<u8>,
+    /// Number of missing capture periods.
+    pub missing: u32,
+}
+
+/// Outcome of one collection.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CollectReport {
+    /// Entries returned by the meter.
+    pub received: usize,
+    /// New entries stored.
+    pub added: usize,
+    /// Entries dropped as already collected.
+    pub duplicates: usize,
+    /// Capture-period gaps found before the new entries.
+    pub gaps: Vec<ProfileGap>,
+}
+
+/// Errors raised while collecting a profile.
+#[derive(Debug)]
+pub enum CollectError {
+    /// The downstream session failed.
+    Session(SessionError),
+    /// The meter refused the read with this data-access-result.
+    Refused(u8),
+    /// The profile is not registered with the collector.
+    UnknownProfile(ObisCode),
+    /// The meter returned a malformed buffer or capture-object list.
+    InvalidBuffer(String),
+    /// The profile does not capture the Clock, or its capture objects have
+    /// not been read from the meter.
+    NoClock,
+}
+
+impl std::fmt::Display for CollectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollectError::Session(e) => write!(f, "meter session error: {e}"),
+            CollectError::Refused(code) => write!(f, "meter refused the profile read (result {code})"),
+            CollectError::UnknownProfile(obis) => write!(f, "profile {obis} is not collected"),
+            CollectError::InvalidBuffer(e) => write!(f, "invalid profile data: {e}"),
+            CollectError::NoClock => write!(f, "profile does not capture the clock 0-0:1.0.0*255"),
+        }
+    }
+}
+
+impl std::error::Error for CollectError {}
+
+impl From<SessionError> for CollectError {
+    fn from(e: SessionError) -> Self {
+        CollectError::Session(e)
+    }
+}
+
+/// Collection state of one profile.
+#[derive(Clone, Debug)]
+struct CollectedProfile {
+    spec: ProfileSpec,
+    /// The capture-object definitions per meter, as read.
+    capture_objects: Vec<(Vec<u8>, Vec<CosemDataType>)>,
+    /// Time of the last collected entry per meter.
+    cursors: Vec<(Vec<u8>, DateTime)>,
+    /// Collected rows: `meter_id` followed by the meter's entry fields.
+    rows: Vec<CosemDataType>,
+    unsaved: usize,
+}
+
+impl CollectedProfile {
+    fn cursor(&self, meter_id: &[u8]) -> Option<&DateTime> {
+        self.cursors.iter().find(|(id, _)| id == meter_id).map(|(_, time)| time)
+    }
+
+    fn set_cursor(&mut self, meter_id: &[u8], time: DateTime) {
+        match self.cursors.iter_mut().find(|(id, _)| id == meter_id) {
+            Some(entry) => entry.1 = time,
+            None => self.cursors.push((meter_id.to_vec(), time)),
+        }
+    }
+
+    fn capture_objects(&self, meter_id: &[u8]) -> Option<&[CosemDataType]> {
+        self.capture_objects.iter().find(|(id, _)| id == meter_id).map(|(_, objects)| objects.as_slice())
+    }
+
+    fn set_capture_objects(&mut self, meter_id: &[u8], objects: Vec<CosemDataType>) {
+        match self.capture_objects.iter_mut().find(|(id, _)| id == meter_id) {
+            Some(entry) => entry.1 = objects,
+            None => self.capture_objects.push((meter_id.to_vec(), objects)),
+        }
+    }
+
+    /// Column codes of the collected rows: the meter uid, then the capture
+    /// objects of the first meter collected.
+    fn columns(&self) -> Vec<ObisCode> {
+        let first = self.capture_objects.first().map(|(_, objects)| objects.as_slice()).unwrap_or_default();
+        let captured = first.iter().filter_map(|c| CaptureObjectDefinition::try_from(c).ok());
+        std::iter::once(meter_uid_column()).chain(captured.map(|c| c.logical_name)).collect()
+    }
+
+    fn storage_key(&self) -> String {
+        format!("profile.{}", self.spec.logical_name)
+    }
+}
+
+/// Collects meter profiles incrementally and keeps the collected rows.
+#[derive(Clone, Debug, Default)]
+pub struct ProfileCollector {
+    profiles: Vec<CollectedProfile>,
+}
+
+impl ProfileCollector {
+    /// Creates a collector with no profiles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a profile to collect, replacing any profile with the same
+    /// logical name.
+    pub fn add(&mut self, spec: ProfileSpec) {
+        self.profiles.retain(|p| p.spec.logical_name != spec.logical_name);
+        self.profiles.push(CollectedProfile {
+            spec,
+            capture_objects: Vec::new(),
+            cursors: Vec::new(),
+            rows: Vec::new(),
+            unsaved: 0,
+        });
+    }
+
+    fn profile(&self, logical_name: &ObisCode) -> Result<&CollectedProfile, CollectError> {
+        self.profiles
+            .iter()
+            .find(|p| &p.spec.logical_name == logical_name)
+            .ok_or(CollectError::UnknownProfile(*logical_name))
+    }
+
+    fn profile_mut(&mut self, logical_name: &ObisCode) -> Result<&mut CollectedProfile, CollectError> {
+        self.profiles
+            .iter_mut()
+            .find(|p| &p.spec.logical_name == logical_name)
+            .ok_or(CollectError::UnknownProfile(*logical_name))
+    }
+
+    /// Records the capture objects of `meter_id`'s `profile`, as
+    /// [`collect`](Self::collect) reads them on the first collection, for
+    /// entries passed to [`ingest`](Self::ingest) directly.
+    pub fn set_capture_objects(
+        &mut self,
+        meter_id: &[u8],
+        profile: &ObisCode,
+        capture_objects: Vec<CosemDataType>,
+    ) -> Result<(), CollectError> {
+        self.profile_mut(profile)?.set_capture_objects(meter_id, capture_objects);
+        Ok(())
+    }
+
+    /// Time of the last entry collected from `meter_id`'s `profile`.
+    pub fn cursor(&self, meter_id: &[u8], profile: &ObisCode) -> Option<&DateTime> {
+        self.profile(profile).ok()?.cursor(meter_id)
+    }
+
+    /// The collected rows of `profile` (`meter_id` followed by the entry
+    /// fields), oldest collection first.
+    pub fn rows(&self, profile: &ObisCode) -> &[CosemDataType] {
+        self.profile(profile).map(|p| p.rows.as_slice()).unwrap_or_default()
+    }
+
+    /// Reads the entries of `meter_id`'s `profile` newer than the last
+    /// collected one, up to `now` (the ИВКЭ time), over `session`. The first
+    /// collection from a meter reads its capture objects and its whole buffer.
+    pub fn collect<L: DataLinkLayer>(
+        &mut self,
+        session: &mut ClientSession<L>,
+        meter_id: &[u8],
+        profile: &ObisCode,
+        now: &DateTime,
+    ) -> Result<CollectReport, CollectError> {
+        let state = self.profile_mut(profile)?;
+        if state.capture_objects(meter_id).is_none() {
+            let capture_objects = read_array(session, profile, 3, None)?;
+            if let Some(bad) = capture_objects.iter().find_map(|c| CaptureObjectDefinition::try_from(c).err()) {
+                return Err(CollectError::InvalidBuffer(bad));
+            }
+            state.set_capture_objects(meter_id, capture_objects);
+        }
+        let capture_objects = state.capture_objects(meter_id).unwrap_or_default();
+        let clock = &capture_objects[clock_column(capture_objects)?];
+        let selection = state.cursor(meter_id).map(|from| AccessSelection {
+            selector: 1,
+            parameters: CosemDataType::Structure(vec![
+                clock.clone(),
+                from.clone().into(),
+                now.clone().into(),
+                CosemDataType::Array(Vec::new()),
+            ]),
+        });
+        let buffer = read_array(session, profile, 2, selection)?;
+        self.ingest(meter_id, profile, buffer)
+    }
+
+    /// Stores entries read from `meter_id`'s `profile` buffer: entries not
+    /// newer than the meter's cursor are dropped, the rest are stored in time
+    /// order and gaps of more than one capture period are reported. The entry
+    /// times are read from the clock column of the meter's capture objects
+    /// (see [`set_capture_objects`](Self::set_capture_objects)).
+    pub fn ingest(
+        &mut self,
+        meter_id: &[u8],
+        profile: &ObisCode,
+        buffer: Vec<CosemDataType>,
+    ) -> Result<CollectReport, CollectError> {
+        let state = self.profile_mut(profile)?;
+        let clock = clock_column(state.capture_objects(meter_id).ok_or(CollectError::NoClock)?)?;
+        let period = i64::from(state.spec.capture_period);
+        let mut report = CollectReport { received: buffer.len(), ..Default::default() };
+
+        // Resolve each entry's time; a null time follows the previous entry.
+        let mut entries = Vec::with_capacity(buffer.len());
+        let mut previous: Option<DateTime> = None;
+        for entry in buffer {
+            let CosemDataType::Structure(mut fields) = entry else {
+                return Err(CollectError::InvalidBuffer("profile entry is not a structure".to_string()));
+            };
+            let time = match fields.get(clock) {
+                Some(CosemDataType::Null) if period > 0 => previous.as_ref().and_then(|p| p.shifted(period)),
+                Some(value) => DateTime::try_from(value).ok(),
+                None => None,
+            };
+            let Some((time, secs)) = time.and_then(|t| t.to_unix().map(|secs| (t, secs))) else {
+                return Err(CollectError::InvalidBuffer("profile entry has no valid clock value".to_string()));
+            };
+            fields[clock] = time.clone().into();
+            previous = Some(time.clone());
+            entries.push((secs, time, fields));
+        }
+        entries.sort_by_key(|(secs, _, _)| *secs);
+
+        let mut last = state.cursor(meter_id).and_then(|t| t.to_unix().map(|secs| (secs, t.clone())));
+        for (secs, time, fields) in entries {
+            if let Some((last_secs, last_time)) = &last {
+                if secs <= *last_secs {
+                    report.duplicates += 1;
+                    continue;
+                }
+                if period > 0 && secs - last_secs > period {
+                    // Periods between two stored entries fit a u32 comfortably.
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let missing = ((secs - last_secs) / period - 1) as u32;
+                    if missing > 0 {
+                        report.gaps.push(ProfileGap {
+                            meter_id: meter_id.to_vec(),
+                            after: last_time.0.to_vec(),
+                            before: time.0.to_vec(),
+                            missing,
+                        });
+                    }
+                }
+            }
+            let row = std::iter::once(CosemDataType::OctetString(meter_id.to_vec())).chain(fields).collect();
+            push_bounded(&mut state.rows, CosemDataType::Structure(row), state.spec.profile_entries);
+            state.unsaved = (state.unsaved + 1).min(state.rows.len());
+            report.added += 1;
+            last = Some((secs, time));
+        }
+        if let Some((_, time)) = last {
+            state.set_cursor(meter_id, time);
+        }
+        Ok(report)
+    }
+
+    /// A `Profile data filter` at `logical_name` over all collected rows of
+    /// `profile`, for filtered upstream reads.
+    pub fn filter(&self, profile: &ObisCode, logical_name: ObisCode) -> Option<ProfileDataFilter> {
+        let state = self.profile(profile).ok()?;
+        let mut filter = ProfileDataFilter::new(logical_name, state.columns());
+        filter.set_rows(state.rows.clone());
+        Some(filter)
+    }
+
+    /// A `Table manager` at `logical_name` holding the latest collected row of
+    /// `profile` per meter, keyed on the meter identifier.
+    pub fn latest(&self, profile: &ObisCode, logical_name: ObisCode) -> Option<TableManager> {
+        let state = self.profile(profile).ok()?;
+        let mut latest: Vec<CosemDataType> = Vec::new();
+        for row in &state.rows {
+            let meter = |r: &CosemDataType| match r {
+                CosemDataType::Structure(fields) => fields.first().cloned(),
+                _ => None,
+            };
+            match latest.iter_mut().find(|l| meter(l) == meter(row)) {
+                Some(slot) => *slot = row.clone(),
+                None => latest.push(row.clone()),
+            }
+        }
+        let mut table = TableManager::new(logical_name, 0);
+        table.set_rows(latest);
+        Some(table)
+    }
+
+    /// The cursors snapshot: `array structure { profile, array structure {
+    /// meter_id, capture_objects }, array structure { meter_id, time } }`.
+    fn to_snapshot(&self) -> CosemDataType {
+        let profiles = self
+            .profiles
+            .iter()
+            .map(|p| {
+                let cursors = p
+                    .cursors
+                    .iter()
+                    .map(|(id, time)| {
+                        CosemDataType::Structure(vec![CosemDataType::OctetString(id.clone()), time.clone().into()])
+                    })
+                    .collect();
+                let capture_objects = p
+                    .capture_objects
+                    .iter()
+                    .map(|(id, objects)| {
+                        CosemDataType::Structure(vec![
+                            CosemDataType::OctetString(id.clone()),
+                            CosemDataType::Array(objects.clone()),
+                        ])
+                    })
+                    .collect();
+                CosemDataType::Structure(vec![
+                    CosemDataType::OctetString(p.spec.logical_name.to_bytes()),
+                    CosemDataType::Array(capture_objects),
+                    CosemDataType::Array(cursors),
+                ])
+            })
+            .collect();
+        CosemDataType::Array(profiles)
+    }
+
+    fn apply_snapshot(&mut self, snapshot: &CosemDataType) -> Result<(), String> {
+        for entry in elements(snapshot)? {
+            let f = fields(entry, 3)?;
+            let name = octets(&f[0])?;
+            let Some(state) = self.profiles.iter_mut().find(|p| p.spec.logical_name.to_bytes() == name) else {
+                continue;
+            };
+            state.capture_objects = elements(&f[1])?
+                .iter()
+                .map(|c| {
+                    let c = fields(c, 2)?;
+                    Ok((octets(&c[0])?, elements(&c[1])?.to_vec()))
+                })
+                .collect::<Result<_, String>>()?;
+            state.cursors = elements(&f[2])?
+                .iter()
+                .map(|c| {
+                    let c = fields(c, 2)?;
+                    Ok((octets(&c[0])?, DateTime::try_from(&c[1])?))
+                })
+                .collect::<Result<_, String>>()?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads an array attribute of a meter profile, following block transfer.
+fn read_array<L: DataLinkLayer>(
+    session: &mut ClientSession<L>,
+    profile: &ObisCode,
+    attribute: i8,
+    selection: Option<AccessSelection>,
+) -> Result<Vec<CosemDataType>, CollectError> {
+    match session.get_value(7, *profile, attribute, selection)? {
+        GetDataResult::Data(CosemDataType::Array(items)) => Ok(items),
+        GetDataResult::Data(_) => Err(CollectError::InvalidBuffer(format!("attribute {attribute} is not an array"))),
+        GetDataResult::AccessResult(code) => Err(CollectError::Refused(code)),
+    }
+}
+
+impl Persist for ProfileCollector {
+    /// Saves the cursors and capture objects as a snapshot and appends the
+    /// rows collected since the last save to one journal per profile.
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        for state in &mut self.profiles {
+            let key = state.storage_key();
+            let profile_entries = state.spec.profile_entries;
+            storage::save_journal(storage, &key, &state.rows, &mut state.unsaved, profile_entries, Clone::clone)?;
+        }
+        storage.save_snapshot(CURSORS_KEY, &self.to_snapshot())
+    }
+
+    /// Restores the registered profiles' cursors, capture objects and rows.
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        for state in &mut self.profiles {
+            state.rows =
+                storage::load_journal(
+                    storage,
+                    &state.storage_key(),
+                    state.spec.profile_entries,
+                    |row| Ok(row.clone()),
+                )?;
+            state.cursors.clear();
+            state.capture_objects.clear();
+            state.unsaved = 0;
+        }
+        if let Some(snapshot) = storage.load_snapshot(CURSORS_KEY)? {
+            self.apply_snapshot(&snapshot).map_err(|e| invalid_data(CURSORS_KEY, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::classes::data::Data;
+    use crate::classes::profile_generic::{ProfileGeneric, ProfileGenericConfig};
+    use crate::interface::InterfaceClass;
+    use crate::server::RequestDispatcher;
+    use crate::service::get::GetRequest;
+    use crate::spodus::catalog;
+    use crate::spodus::storage::MemoryStorage;
+    use crate::types::attrs::SortMethod;
+
+    fn load_profile() -> ObisCode {
+        ObisCode::new(1, 0, 99, 1, 0, 255)
+    }
+
+    /// A 30-minute load-profile entry at `minutes` past 2025-05-01 00:00 UTC.
+    fn entry(minutes: i64, energy: u32) -> CosemDataType {
+        CosemDataType::Structure(vec![
+            DateTime::from_unix(1_746_057_600 + minutes * 60, 0).unwrap().into(),
+            CosemDataType::DoubleLongUnsigned(energy),
+        ])
+    }
+
+    /// The same entry with its energy column before the clock.
+    fn clock_last(entry: CosemDataType) -> CosemDataType {
+        let CosemDataType::Structure(mut fields) = entry else { panic!("entry") };
+        fields.reverse();
+        CosemDataType::Structure(fields)
+    }
+
+    /// The capture objects of a load profile capturing the clock and energy.
+    fn capture_objects() -> Vec<CosemDataType> {
+        vec![
+            CaptureObjectDefinition::new(8, ObisCode::new(0, 0, 1, 0, 0, 255), 2, 0).into(),
+            CaptureObjectDefinition::new(1, ObisCode::new(1, 0, 1, 29, 0, 255), 2, 0).into(),
+        ]
+    }
+
+    /// A meter serving a load profile with the given entries, in small blocks.
+    fn meter(buffer: Vec<CosemDataType>) -> RequestDispatcher {
+        let clock: Arc<dyn InterfaceClass + Send + Sync> = Arc::new(catalog::clock());
+        meter_with_columns(vec![(clock, 2), energy_column()], buffer)
+    }
+
+    /// The energy column of the test load profiles.
+    fn energy_column() -> (Arc<dyn InterfaceClass + Send + Sync>, u8) {
+        (Arc::new(Data::new(ObisCode::new(1, 0, 1, 29, 0, 255), CosemDataType::Null)), 2)
+    }
+
+    /// A meter whose load profile captures `columns`.
+    fn meter_with_columns(
+        columns: Vec<(Arc<dyn InterfaceClass + Send + Sync>, u8)>,
+        buffer: Vec<CosemDataType>,
+    ) -> RequestDispatcher {
+        #[allow(clippy::cast_possible_truncation)]
+        let entries_in_use = buffer.len() as u32;
+        let mut server = RequestDispatcher::new();
+        server.set_max_pdu(64);
+        server.add(Box::new(ProfileGeneric::new(ProfileGenericConfig {
+            logical_name: load_profile(),
+            version: 1,
+            buffer,
+            capture_objects: columns,
+            capture_period: 1800,
+            sort_method: SortMethod::Fifo,
+            sort_object: None,
+            entries_in_use,
+            profile_entries: 0,
+        })));
+        server
+    }
+
+    /// A loopback link to a local "meter" server, recording the requests.
+    struct LocalLink {
+        server: RequestDispatcher,
+        pending: Option<Vec<u8>>,
+        sent: Vec<Vec<u8>>,
+    }
+
+    impl DataLinkLayer for LocalLink {
+        fn send_apdu(&mut self, apdu: &[u8]) -> io::Result<()> {
+            self.sent.push(apdu.to_vec());
+            self.pending = Some(self.server.dispatch(apdu).expect("dispatch"));
+            Ok(())
+        }
+        fn receive_apdu(&mut self) -> io::Result<Vec<u8>> {
+            Ok(self.pending.take().expect("a response"))
+        }
+    }
+
+    #[test]
+    fn collects_incrementally_with_range_access() {
+        let buffer = vec![entry(0, 10), entry(30, 20), entry(60, 30)];
+        let mut session = ClientSession::new(LocalLink { server: meter(buffer), pending: None, sent: Vec::new() });
+        let mut collector = ProfileCollector::new();
+        collector.add(ProfileSpec::new(load_profile(), 1800));
+        let now = DateTime::from_unix(1_746_057_600 + 7200, 0).unwrap();
+
+        let report = collector.collect(&mut session, b"SIT12260004", &load_profile(), &now).unwrap();
+        assert_eq!((report.received, report.added, report.duplicates), (3, 3, 0));
+        assert_eq!(
+            collector.cursor(b"SIT12260004", &load_profile()),
+            Some(&DateTime::from_unix(1_746_057_600 + 3600, 0).unwrap())
+        );
+
+        // The next collection asks for entries from the cursor on; this meter
+        // ignores the range, so every returned entry is a duplicate.
+        let report = collector.collect(&mut session, b"SIT12260004", &load_profile(), &now).unwrap();
+        assert_eq!((report.received, report.added, report.duplicates), (3, 0, 3));
+        let link = session.into_inner();
+        let requests: Vec<_> = link.sent.iter().filter_map(|apdu| GetRequest::decode(apdu).ok()).collect();
+        let Some(GetRequest::Normal { access_selection: Some(selection), .. }) =
+            requests.iter().rev().find(|r| matches!(r, GetRequest::Normal { .. }))
+        else {
+            panic!("a selective GET");
+        };
+        assert_eq!(selection.selector, 1);
+        // Block transfer was used for the buffer.
+        assert!(requests.iter().any(|r| matches!(r, GetRequest::Next { .. })));
+
+        // The rows are served upstream with the meter id prefixed.
+        let filter = collector.filter(&load_profile(), ObisCode::new(0, 0, 94, 7, 201, 255)).unwrap();
+        assert_eq!(filter.rows().len(), 3);
+        let CosemDataType::Structure(row) = &filter.rows()[2] else { panic!("row") };
+        assert_eq!(row[0], CosemDataType::OctetString(b"SIT12260004".to_vec()));
+        assert_eq!(row[2], CosemDataType::DoubleLongUnsigned(30));
+    }
+
+    #[test]
+    fn capture_objects_are_read_per_meter() {
+        let session = |server| ClientSession::new(LocalLink { server, pending: None, sent: Vec::new() });
+        let clock: Arc<dyn InterfaceClass + Send + Sync> = Arc::new(catalog::clock());
+        let mut first = session(meter(vec![entry(0, 10)]));
+        // The second meter captures the clock after the energy.
+        let mut second = session(meter_with_columns(
+            vec![energy_column(), (clock, 2)],
+            vec![clock_last(entry(0, 20)), clock_last(entry(30, 21))],
+        ));
+        let mut collector = ProfileCollector::new();
+        collector.add(ProfileSpec::new(load_profile(), 1800));
+        let now = DateTime::from_unix(1_746_057_600 + 7200, 0).unwrap();
+        collector.collect(&mut first, b"A", &load_profile(), &now).unwrap();
+        let report = collector.collect(&mut second, b"B", &load_profile(), &now).unwrap();
+        assert_eq!(report.added, 2);
+        assert_eq!(
+            collector.cursor(b"B", &load_profile()),
+            Some(&DateTime::from_unix(1_746_057_600 + 1800, 0).unwrap())
+        );
+        collector.collect(&mut second, b"B", &load_profile(), &now).unwrap();
+
+        // The second meter's range is on its own clock column.
+        let requests: Vec<_> =
+            second.into_inner().sent.iter().filter_map(|apdu| GetRequest::decode(apdu).ok()).collect();
+        let Some(GetRequest::Normal { access_selection: Some(selection), .. }) =
+            requests.iter().rev().find(|r| matches!(r, GetRequest::Normal { .. }))
+        else {
+            panic!("a selective GET");
+        };
+        let CosemDataType::Structure(range) = &selection.parameters else { panic!("range descriptor") };
+        let clock = CaptureObjectDefinition::try_from(&range[0]).unwrap();
+        assert_eq!((clock.class_id, clock.logical_name), (8, ObisCode::new(0, 0, 1, 0, 0, 255)));
+
+        // The per-meter capture objects survive a restart.
+        let mut storage = MemoryStorage::new();
+        collector.save(&mut storage).unwrap();
+        let mut restored = ProfileCollector::new();
+        restored.add(ProfileSpec::new(load_profile(), 1800));
+        restored.load(&storage).unwrap();
+        let state = restored.profile(&load_profile()).unwrap();
+        assert_eq!(clock_column(state.capture_objects(b"B").unwrap()).unwrap(), 1);
+    }
+
+    #[test]
+    fn profiles_without_the_clock_are_refused() {
+        let mut session = ClientSession::new(LocalLink {
+            server: meter_with_columns(vec![energy_column()], vec![]),
+            pending: None,
+            sent: Vec::new(),
+        });
+        let mut collector = ProfileCollector::new();
+        collector.add(ProfileSpec::new(load_profile(), 1800));
+        let now = DateTime::from_unix(1_746_057_600, 0).unwrap();
+        assert!(matches!(collector.collect(&mut session, b"A", &load_profile(), &now), Err(CollectError::NoClock)));
+        // Entries of a meter whose capture objects are unknown have no clock either.
+        assert!(matches!(collector.ingest(b"C", &load_profile(), vec![entry(0, 1)]), Err(CollectError::NoClock)));
+    }
+
+    #[test]
+    fn reports_gaps_and_fills_null_times() {
+        let mut collector = ProfileCollector::new();
+        collector.add(ProfileSpec::new(load_profile(), 1800).with_profile_entries(4));
+        for meter_id in [b"A", b"B"] {
+            collector.set_capture_objects(meter_id, &load_profile(), capture_objects()).unwrap();
+        }
+        // 00:00, then 01:30 (two periods missing), then a null time (02:00).
+        let null_time = CosemDataType::Structure(vec![CosemDataType::Null, CosemDataType::DoubleLongUnsigned(40)]);
+        let report = collector.ingest(b"A", &load_profile(), vec![entry(0, 10), entry(90, 30), null_time]).unwrap();
+        assert_eq!(report.added, 3);
+        assert_eq!(report.gaps.len(), 1);
+        assert_eq!(report.gaps[0].missing, 2);
+        assert_eq!(
+            collector.cursor(b"A", &load_profile()),
+            Some(&DateTime::from_unix(1_746_057_600 + 7200, 0).unwrap())
+        );
+
+        collector.ingest(b"B", &load_profile(), vec![entry(0, 1), entry(30, 2)]).unwrap();
+        // Bounded to four rows; the table keeps the latest row per meter.
+        assert_eq!(collector.rows(&load_profile()).len(), 4);
+        let table = collector.latest(&load_profile(), ObisCode::new(0, 0, 94, 7, 200, 255)).unwrap();
+        assert_eq!(table.rows().len(), 2);
+
+        // Cursors and rows survive a restart.
+        let mut storage = MemoryStorage::new();
+        collector.save(&mut storage).unwrap();
+        let mut restored = ProfileCollector::new();
+        restored.add(ProfileSpec::new(load_profile(), 1800).with_profile_entries(4));
+        restored.load(&storage).unwrap();
+        assert_eq!(restored.rows(&load_profile()), collector.rows(&load_profile()));
+        assert_eq!(restored.cursor(b"B", &load_profile()), collector.cursor(b"B", &load_profile()));
+    }
+}
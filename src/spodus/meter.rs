@@ -4,15 +4,22 @@
 //! `0.0.94.7.128.255`, class Data). Each meter is described by its composite
 //! identifier, model and communication channels. The registry also caches the
 //! last-read attribute values per meter (the aggregation the ИВКЭ serves
-//! upstream without re-polling).
+//! upstream without re-polling). The registry is persisted as a snapshot
+//! through [`Persist`].
 
 use std::collections::HashMap;
+use std::io;
 
 use crate::classes::data::Data;
 use crate::obis::ObisCode;
 use crate::types::CosemDataType;
 
 use super::obis;
+use super::profile::{elements, fields, octets, unsigned};
+use super::storage::{invalid_data, Persist, Storage};
+
+/// Storage key of the registry snapshot.
+const METERS_KEY: &str = "meters";
 
 /// One communication channel of a meter (§10.2, `channel`).
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -54,6 +61,23 @@ impl MeterDescriptor {
     }
 }
 
+impl TryFrom<&CosemDataType> for MeterDescriptor {
+    type Error = String;
+
+    /// Decodes a `device_description` structure (§10.2).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 3)?;
+        let channels = elements(&f[2])?
+            .iter()
+            .map(|c| {
+                let c = fields(c, 2)?;
+                Ok(MeterChannel { id: unsigned(&c[0])?, address: octets(&c[1])? })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(MeterDescriptor { meter_id: octets(&f[0])?, meter_model: octets(&f[1])?, channels })
+    }
+}
+
 /// The configured meter list plus a per-meter aggregation cache.
 #[derive(Clone, Debug, Default)]
 pub struct MeterRegistry {
@@ -112,6 +136,57 @@ impl MeterRegistry {
         let array = self.meters.iter().map(MeterDescriptor::to_structure).collect();
         Data::new(obis::meter_list(), CosemDataType::Array(array))
     }
+
+    /// The snapshot: `structure { meters: array device_description, cache:
+    /// array structure { meter_id, obis, attribute, value } }`.
+    fn to_snapshot(&self) -> CosemDataType {
+        let meters = self.meters.iter().map(MeterDescriptor::to_structure).collect();
+        // Sort the cache so that equal registries produce equal snapshots.
+        let mut ids: Vec<&Vec<u8>> = self.cache.keys().collect();
+        ids.sort();
+        let cache = ids
+            .into_iter()
+            .flat_map(|id| {
+                self.cache[id].iter().map(move |(obis, attribute, value)| {
+                    CosemDataType::Structure(vec![
+                        CosemDataType::OctetString(id.clone()),
+                        CosemDataType::OctetString(obis.to_bytes()),
+                        CosemDataType::Unsigned(*attribute),
+                        value.clone(),
+                    ])
+                })
+            })
+            .collect();
+        CosemDataType::Structure(vec![CosemDataType::Array(meters), CosemDataType::Array(cache)])
+    }
+
+    fn from_snapshot(value: &CosemDataType) -> Result<Self, String> {
+        let f = fields(value, 2)?;
+        let mut registry = MeterRegistry::new();
+        for meter in elements(&f[0])? {
+            registry.meters.push(MeterDescriptor::try_from(meter)?);
+        }
+        for entry in elements(&f[1])? {
+            let e = fields(entry, 4)?;
+            let code = ObisCode::try_from(&octets(&e[1])?[..])?;
+            registry.store(&octets(&e[0])?, code, unsigned(&e[2])?, e[3].clone());
+        }
+        Ok(registry)
+    }
+}
+
+impl Persist for MeterRegistry {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        storage.save_snapshot(METERS_KEY, &self.to_snapshot())
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        *self = match storage.load_snapshot(METERS_KEY)? {
+            Some(snapshot) => Self::from_snapshot(&snapshot).map_err(|e| invalid_data(METERS_KEY, e))?,
+            None => MeterRegistry::new(),
+        };
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -150,13 +225,28 @@ mod tests {
         let mut registry = MeterRegistry::new();
         registry.add(sample());
         let energy = ObisCode::new(1, 0, 1, 8, 0, 255);
-        registry.store(b"SIT12260004", energy.clone(), 2, CosemDataType::DoubleLongUnsigned(1000));
+        registry.store(b"SIT12260004", energy, 2, CosemDataType::DoubleLongUnsigned(1000));
         assert_eq!(registry.cached(b"SIT12260004", &energy, 2), Some(&CosemDataType::DoubleLongUnsigned(1000)));
         // Updating overwrites the cached value.
-        registry.store(b"SIT12260004", energy.clone(), 2, CosemDataType::DoubleLongUnsigned(2000));
+        registry.store(b"SIT12260004", energy, 2, CosemDataType::DoubleLongUnsigned(2000));
         assert_eq!(registry.cached(b"SIT12260004", &energy, 2), Some(&CosemDataType::DoubleLongUnsigned(2000)));
         // Removing the meter drops its cache.
         registry.remove(b"SIT12260004");
         assert_eq!(registry.cached(b"SIT12260004", &energy, 2), None);
     }
+
+    #[test]
+    fn registry_snapshot_round_trips() {
+        let mut registry = MeterRegistry::new();
+        registry.add(sample());
+        let energy = ObisCode::new(1, 0, 1, 8, 0, 255);
+        registry.store(b"SIT12260004", energy, 2, CosemDataType::DoubleLongUnsigned(1000));
+        let mut storage = crate::spodus::storage::MemoryStorage::new();
+        registry.save(&mut storage).unwrap();
+
+        let mut restored = MeterRegistry::new();
+        restored.load(&storage).unwrap();
+        assert_eq!(restored.meters(), registry.meters());
+        assert_eq!(restored.cached(b"SIT12260004", &energy, 2), Some(&CosemDataType::DoubleLongUnsigned(1000)));
+    }
 }
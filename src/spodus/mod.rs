@@ -14,17 +14,22 @@ pub mod catalog;
 pub mod channels;
 pub mod collect;
 pub mod discovered;
+pub mod incoming;
 pub mod journals;
+pub mod load_profile;
 pub mod meter;
 pub mod misc;
 pub mod nameplate;
 pub mod node;
 pub mod obis;
+pub mod polling;
 mod profile;
 pub mod profile_filter;
 pub mod proxy;
 pub mod push;
 pub mod records;
 pub mod status;
+pub mod storage;
 pub mod table_manager;
 pub mod tasks;
+pub mod time_sync;
@@ -64,9 +64,9 @@ impl Nameplate {
     /// individual passport OBIS codes as the columns.
     pub fn profile(&self) -> ProfileGeneric {
         let objects = self.objects();
-        let columns: Vec<_> = objects.iter().map(|o| o.logical_name().clone()).collect();
-        let row = objects.iter().map(|o| o.attributes()[1].1.clone()).collect();
-        reference_profile(obis::nameplate_profile(), &columns, vec![CosemDataType::Structure(row)])
+        let columns: Vec<_> = objects.iter().map(|o| *o.logical_name()).collect();
+        let row = objects.iter().map(|o| o.value().clone()).collect();
+        reference_profile(obis::nameplate_profile(), &columns, vec![CosemDataType::Structure(row)], 0)
     }
 }
 
@@ -5,26 +5,34 @@
 //! upstream [`RequestDispatcher`] that serves the head-end (ИВК) with the
 //! mandatory COSEM object catalogue.
 
+use std::io;
+
 use crate::classes::association_ln::AuthenticationMechanism;
-use crate::interface::InterfaceClass;
+use crate::classes::data::Data;
+use crate::classes::push_setup::{PushDeliveryRequest, PushSetup};
+use crate::listener::{Framing, ListenerError};
 use crate::obis::ObisCode;
 use crate::server::RequestDispatcher;
+use crate::types::attrs::DateTime;
 use crate::types::CosemDataType;
 
 use super::access_policy::AccessPolicies;
 use super::catalog;
 use super::channels::ChannelList;
 use super::discovered::DiscoveredMeters;
+use super::incoming::PushReceiver;
 use super::journals::{EventJournal, ExchangeStatusJournal};
+use super::load_profile::ProfileCollector;
 use super::meter::MeterRegistry;
 use super::misc;
 use super::nameplate::Nameplate;
 use super::obis;
 use super::profile_filter::ProfileDataFilter;
 use super::proxy::DirectChannelTable;
-use super::push::EventMessages;
-use super::records::{CorrectionJournal, IncomingEventsTable, NumericJournal};
+use super::push::{self, EventMessages};
+use super::records::{transmission_status, CorrectionJournal, IncomingEventsTable, NumericJournal};
 use super::status::MeterStatusTable;
+use super::storage::{Persist, Storage};
 use super::table_manager::TableManager;
 use super::tasks::ExchangeTasks;
 
@@ -57,10 +65,22 @@ pub struct Concentrator {
     pub numeric_journal: NumericJournal,
     /// Incoming push-events table (§8.5.10).
     pub incoming_events: IncomingEventsTable,
+    /// Meters allowed to push to the ИВКЭ and their security contexts, see
+    /// [`receive_push`](Concentrator::receive_push).
+    pub pushes: PushReceiver,
     /// ИВКЭ event journals (§10.13).
     pub event_journals: Vec<EventJournal>,
-    /// Aggregated event push-messages (§8.5.11).
+    /// Collected meter load and billing profiles.
+    pub load_profiles: ProfileCollector,
+    /// Aggregated event push-messages (§8.5.11) awaiting publication.
     pub events: EventMessages,
+    /// Upstream event Push setup (`0.0.25.9.0.255`), see [`catalog::push_setup`].
+    pub push_setup: Option<PushSetup>,
+    /// Push-filter mask (`0.0.97.98.10.255`): bit *n* relays events of meter
+    /// event-journal field *n* upstream. `None` relays every event.
+    pub push_mask: Option<u32>,
+    /// ИВКЭ logical device name (`0.0.42.0.0.255`, 16 octets).
+    pub logical_device_name: Vec<u8>,
     /// Time-difference-with-meters delta (`0.0.94.7.141.255`).
     pub time_delta: u8,
     /// Discrete-inputs state bitmask (`0.0.96.3.1.255`).
@@ -75,6 +95,48 @@ impl Concentrator {
         Self::default()
     }
 
+    /// The persisted parts of the model: the meter registry, discovered
+    /// meters, task list, access policies, meter status, incoming events and
+    /// push invocation counters (snapshots), the journals (append-only) and
+    /// the collected profiles.
+    fn persisted(&mut self) -> Vec<&mut dyn Persist> {
+        let mut parts: Vec<&mut dyn Persist> = vec![
+            &mut self.meters,
+            &mut self.discovered,
+            &mut self.exchange_tasks,
+            &mut self.access_policies,
+            &mut self.meter_status,
+            &mut self.incoming_events,
+            &mut self.pushes,
+            &mut self.exchange_journal,
+            &mut self.correction_journal,
+            &mut self.numeric_journal,
+            &mut self.load_profiles,
+        ];
+        parts.extend(self.event_journals.iter_mut().map(|j| j as &mut dyn Persist));
+        parts
+    }
+
+    /// Saves the persisted state to `storage`: registry snapshots are
+    /// replaced, journal records added since the last save are appended.
+    pub fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        for part in self.persisted() {
+            part.save(storage)?;
+        }
+        Ok(())
+    }
+
+    /// Restores the persisted state from `storage`, e.g. after a reboot. The
+    /// configured [`event_journals`](Concentrator::event_journals), journal
+    /// bounds and push meters are kept; each journal reloads its own records
+    /// and each push meter its invocation counters.
+    pub fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        for part in self.persisted() {
+            part.load(storage)?;
+        }
+        Ok(())
+    }
+
     /// Assembles the upstream [`RequestDispatcher`] populated with the full
     /// ИВКЭ object catalogue (Appendix A).
     pub fn dispatcher(&self) -> RequestDispatcher {
@@ -107,12 +169,20 @@ impl Concentrator {
         d.add(Box::new(misc::discrete_inputs(self.discrete_inputs)));
 
         // Notifications (§8.5).
+        d.add(Box::new(Data::new(
+            obis::ivke_logical_name(),
+            CosemDataType::OctetString(self.logical_device_name.clone()),
+        )));
         d.add(Box::new(self.events.build()));
+        d.add(Box::new(push::push_mask(CosemDataType::DoubleLongUnsigned(self.push_mask.unwrap_or(u32::MAX)))));
+        if let Some(push_setup) = &self.push_setup {
+            d.add(Box::new(push_setup.clone()));
+        }
 
         // Group-operation classes (§7): a Table manager over the meter list and
         // a Profile data filter over the numeric journal.
         let mut table_manager = TableManager::new(obis::meter_list(), 0);
-        if let CosemDataType::Array(rows) = self.meters.build_meter_list().attributes()[1].1.clone() {
+        if let CosemDataType::Array(rows) = self.meters.build_meter_list().value().clone() {
             table_manager.set_rows(rows);
         }
         d.add(Box::new(table_manager));
@@ -142,6 +212,84 @@ impl Concentrator {
         }
         d
     }
+
+    /// Receives a meter push (wrapper PDU or HDLC UI frame) through
+    /// [`pushes`](Concentrator::pushes) into the incoming push-events table.
+    /// Returns the number of events stored.
+    pub fn receive_push(&mut self, framing: Framing, pdu: &[u8], now: &DateTime) -> Result<usize, ListenerError> {
+        let mut pushes = std::mem::take(&mut self.pushes);
+        let received = pushes.receive(self, framing, pdu, now);
+        self.pushes = pushes;
+        received
+    }
+
+    /// Publishes the pending event push-messages through the ИВКЭ Push setup:
+    /// assembles the DataNotification for its push object list. The messages
+    /// stay pending until [`confirm_events`](Concentrator::confirm_events)
+    /// reports the push sent, so a failed send is published again. Returns the
+    /// request with the [`PublishedEvents`] it carries, or `Ok(None)` when no
+    /// event is pending.
+    pub fn publish_events(
+        &mut self,
+        long_invoke_id_and_priority: u32,
+    ) -> Result<Option<(PushDeliveryRequest, PublishedEvents)>, String> {
+        if self.events.is_empty() {
+            return Ok(None);
+        }
+        let push_setup = self.push_setup.as_ref().ok_or("No event Push setup configured")?;
+        let request = self.dispatcher().build_push_delivery_request(push_setup, long_invoke_id_and_priority)?;
+        // Pair each message with the oldest waiting incoming-event row it relays.
+        let table = self.incoming_events.rows();
+        let mut rows: Vec<usize> = Vec::new();
+        for message in self.events.messages() {
+            let row = (0..table.len()).find(|&i| {
+                let row = &table[i];
+                row.status == transmission_status::WAITING
+                    && !rows.contains(&i)
+                    && row.meter_id == message.meter_number
+                    && row.meter_time == message.date_time
+                    && row.journal_field == message.journal_id
+                    && row.code == message.code
+            });
+            rows.extend(row);
+        }
+        Ok(Some((request, PublishedEvents { messages: self.events.messages().len(), rows })))
+    }
+
+    /// Records the push built by [`publish_events`](Concentrator::publish_events)
+    /// as sent: removes the messages it carried and marks the incoming events
+    /// they relay as sent (unconfirmed). Events received after the publish
+    /// stay pending for the next push.
+    pub fn confirm_events(&mut self, published: &PublishedEvents) {
+        self.events.remove_oldest(published.messages);
+        self.incoming_events.update_rows(
+            &published.rows,
+            transmission_status::WAITING,
+            transmission_status::SENT_UNCONFIRMED,
+        );
+    }
+}
+
+/// The event messages carried by one [`Concentrator::publish_events`] push,
+/// handed back to [`Concentrator::confirm_events`] once it has been sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublishedEvents {
+    /// Number of pending messages the push carried, oldest first.
+    messages: usize,
+    /// Indices of the incoming-event rows those messages relay.
+    rows: Vec<usize>,
+}
+
+impl PublishedEvents {
+    /// Number of event messages the push carried.
+    pub fn len(&self) -> usize {
+        self.messages
+    }
+
+    /// Whether the push carried no event message.
+    pub fn is_empty(&self) -> bool {
+        self.messages == 0
+    }
 }
 
 #[cfg(test)]
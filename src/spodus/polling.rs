@@ -0,0 +1,729 @@
+//! Parallel meter polling (СТО 34.01-5.1-013-2023, §10.7 and §10.9).
+//!
+//! [`PollingEngine`] runs many downstream [`ClientSession`]s at once, one
+//! worker thread per allowed session. Meters are grouped by their channel
+//! (the first channel of their `device_description`): a half-duplex RS-485
+//! bus gets one session at a time while independent TCP/GPRS meters can be
+//! polled side by side, as set by the per-channel limits.
+//!
+//! Each [`PollJob`] reads a list of attributes from one meter. A failed
+//! attempt is retried with exponential backoff, keeping the values already
+//! read; while a job backs off it is set aside and its session slot polls the
+//! channel's next ready job. The run can be cancelled through a [`CancelToken`] and reports
+//! [`PollEvent`]s as it goes. Read values are stored in the [`MeterRegistry`]
+//! cache and every finished job is recorded in the exchange-status journal;
+//! both are updated on the calling thread.
+
+use std::collections::VecDeque;
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::obis::ObisCode;
+use crate::service::data_access_result;
+use crate::service::get::{GetDataResult, GetResponse};
+use crate::session::{ClientSession, SessionError};
+use crate::transport::DataLinkLayer;
+use crate::types::attrs::DateTime;
+use crate::types::CosemDataType;
+
+use super::collect::AttributeRef;
+use super::journals::{exchange_status, ExchangeRecord, ExchangeStatusJournal};
+use super::meter::{MeterDescriptor, MeterRegistry};
+
+/// One polling job: attributes to read from one meter.
+#[derive(Clone, Debug)]
+pub struct PollJob {
+    /// Exchange task the job belongs to (recorded in the journal).
+    pub task_id: u32,
+    /// Meter identifier (must be in the registry).
+    pub meter_id: Vec<u8>,
+    /// Attributes to read.
+    pub attributes: Vec<AttributeRef>,
+}
+
+/// Retry behaviour for a failing meter.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Attempts per job, the first one included (at least 1).
+    pub max_attempts: u8,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay, which doubles after every failed attempt.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, backing off from 1 s up to 30 s.
+    fn default() -> Self {
+        RetryPolicy { max_attempts: 3, initial_backoff: Duration::from_secs(1), max_backoff: Duration::from_secs(30) }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay after `failed` failed attempts.
+    fn backoff(&self, failed: u8) -> Duration {
+        let factor = 1u32 << u32::from(failed.saturating_sub(1)).min(16);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// A shared flag that stops a polling run. Jobs not yet started are skipped
+/// and jobs backing off end without further attempts.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    state: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation.
+    pub fn cancel(&self) {
+        let (flag, wake) = &*self.state;
+        *flag.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        wake.notify_all();
+    }
+
+    /// Whether cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Progress of a polling run, reported on the calling thread.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PollEvent {
+    /// An attempt on a meter started.
+    Attempt {
+        /// Meter identifier.
+        meter_id: Vec<u8>,
+        /// Attempt number, from 1.
+        attempt: u8,
+    },
+    /// An attempt failed and the meter will be retried.
+    Retrying {
+        /// Meter identifier.
+        meter_id: Vec<u8>,
+        /// The failed attempt's number.
+        attempt: u8,
+        /// The failure.
+        error: String,
+    },
+    /// A job finished and was journalled.
+    Finished {
+        /// Meter identifier.
+        meter_id: Vec<u8>,
+        /// Exchange status (see [`exchange_status`]).
+        status: u8,
+        /// Jobs finished so far.
+        completed: usize,
+        /// Jobs in the run.
+        total: usize,
+    },
+}
+
+/// Totals of a polling run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PollSummary {
+    /// Jobs that read every attribute.
+    pub done: usize,
+    /// Jobs that read some of their attributes.
+    pub partial: usize,
+    /// Jobs that read nothing.
+    pub failed: usize,
+    /// Jobs skipped because the run was cancelled.
+    pub cancelled: usize,
+}
+
+/// What a worker reports back to the calling thread.
+enum WorkerMessage {
+    Event(PollEvent),
+    Done(JobResult),
+}
+
+/// A finished job.
+struct JobResult {
+    job: PollJob,
+    status: u8,
+    attempts: u8,
+    start: DateTime,
+    end: DateTime,
+    values: Vec<(ObisCode, u8, CosemDataType)>,
+}
+
+/// A job in progress: its meter and what its attempts so far have read.
+struct Polling {
+    job: PollJob,
+    meter: MeterDescriptor,
+    start: Option<DateTime>,
+    values: Vec<(ObisCode, u8, CosemDataType)>,
+    pending: Vec<AttributeRef>,
+    refused: Vec<u8>,
+    attempts: u8,
+    link_failed: bool,
+    /// The earliest time of the next attempt, while backing off.
+    not_before: Option<Instant>,
+}
+
+impl Polling {
+    fn new(job: PollJob, meter: MeterDescriptor) -> Self {
+        let pending = job.attributes.clone();
+        Polling {
+            job,
+            meter,
+            start: None,
+            values: Vec::new(),
+            pending,
+            refused: Vec::new(),
+            attempts: 0,
+            link_failed: false,
+            not_before: None,
+        }
+    }
+
+    /// The job's outcome, ending at `end`.
+    fn finish(self, end: DateTime) -> JobResult {
+        let status = if self.pending.is_empty() && self.refused.is_empty() {
+            exchange_status::DONE
+        } else if !self.values.is_empty() {
+            exchange_status::PARTIAL
+        } else if !self.pending.is_empty() {
+            if self.link_failed {
+                exchange_status::BAD_LINK
+            } else {
+                exchange_status::NO_RESPONSE
+            }
+        } else if self.refused.contains(&data_access_result::READ_WRITE_DENIED) {
+            exchange_status::ACCESS_DENIED
+        } else {
+            exchange_status::NOT_SUPPORTED
+        };
+        let start = self.start.unwrap_or_else(|| end.clone());
+        JobResult { job: self.job, status, attempts: self.attempts, start, end, values: self.values }
+    }
+}
+
+/// The jobs of one channel, shared by its workers.
+struct Channel {
+    state: Mutex<ChannelState>,
+    /// Signalled when a job is requeued or finished.
+    changed: Condvar,
+}
+
+struct ChannelState {
+    /// Jobs waiting for an attempt, in order.
+    queue: VecDeque<Polling>,
+    /// Jobs being attempted by a worker.
+    active: usize,
+}
+
+/// How often a worker waiting for a backed-off job checks for cancellation.
+const CANCEL_CHECK: Duration = Duration::from_millis(20);
+
+/// The ИВКЭ time source used for journal timestamps.
+type Clock = Arc<dyn Fn() -> DateTime + Send + Sync>;
+
+/// Polls meters concurrently with per-channel parallelism limits.
+#[derive(Clone)]
+pub struct PollingEngine {
+    retry: RetryPolicy,
+    channel_limits: Vec<(u8, usize)>,
+    default_limit: usize,
+    cancel: CancelToken,
+    clock: Clock,
+}
+
+impl std::fmt::Debug for PollingEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PollingEngine")
+            .field("retry", &self.retry)
+            .field("channel_limits", &self.channel_limits)
+            .field("default_limit", &self.default_limit)
+            .field("cancel", &self.cancel)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for PollingEngine {
+    fn default() -> Self {
+        PollingEngine {
+            retry: RetryPolicy::default(),
+            channel_limits: Vec::new(),
+            default_limit: 1,
+            cancel: CancelToken::new(),
+            // A system clock past year 65534 reads as "not specified".
+            clock: Arc::new(|| {
+                DateTime::from_system_time(SystemTime::now(), 0)
+                    .unwrap_or(DateTime::new([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x00, 0xFF]))
+            }),
+        }
+    }
+}
+
+impl PollingEngine {
+    /// Creates an engine polling one meter at a time per channel, with the
+    /// default retry policy and the system clock (UTC).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the retry policy.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Allows up to `sessions` concurrent sessions on `channel` (1 for a
+    /// half-duplex bus).
+    pub fn with_channel_limit(mut self, channel: u8, sessions: usize) -> Self {
+        self.channel_limits.retain(|(id, _)| *id != channel);
+        self.channel_limits.push((channel, sessions.max(1)));
+        self
+    }
+
+    /// Sets the concurrent-session limit of channels without their own limit.
+    pub fn with_default_limit(mut self, sessions: usize) -> Self {
+        self.default_limit = sessions.max(1);
+        self
+    }
+
+    /// Sets the time source of the journal timestamps.
+    pub fn with_clock(mut self, clock: impl Fn() -> DateTime + Send + Sync + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// The token that cancels this engine's runs.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    fn limit(&self, channel: u8) -> usize {
+        self.channel_limits.iter().find(|(id, _)| *id == channel).map_or(self.default_limit, |(_, limit)| *limit)
+    }
+
+    /// Runs `jobs`. `open` establishes a session with a meter (it is called on
+    /// a worker thread, once per attempt); `progress` receives the events.
+    /// Read values go to `registry`'s cache and each finished job is appended
+    /// to `journal`. Jobs for meters missing from the registry are journalled
+    /// as not found.
+    pub fn run<L, F>(
+        &self,
+        registry: &mut MeterRegistry,
+        journal: &mut ExchangeStatusJournal,
+        jobs: Vec<PollJob>,
+        open: F,
+        mut progress: impl FnMut(&PollEvent),
+    ) -> PollSummary
+    where
+        L: DataLinkLayer,
+        F: Fn(&MeterDescriptor) -> Result<ClientSession<L>, SessionError> + Sync,
+    {
+        let total = jobs.len();
+        let mut summary = PollSummary::default();
+        let mut completed = 0;
+
+        // Group the jobs into per-channel queues.
+        let mut queues: Vec<(u8, VecDeque<Polling>)> = Vec::new();
+        let mut finished = Vec::new();
+        for job in jobs {
+            let Some(meter) = registry.find(&job.meter_id).cloned() else {
+                let now = (self.clock)();
+                finished.push(JobResult {
+                    job,
+                    status: exchange_status::NOT_FOUND,
+                    attempts: 0,
+                    start: now.clone(),
+                    end: now,
+                    values: Vec::new(),
+                });
+                continue;
+            };
+            let channel = meter.channels.first().map_or(0, |c| c.id);
+            let polling = Polling::new(job, meter);
+            match queues.iter_mut().find(|(id, _)| *id == channel) {
+                Some((_, queue)) => queue.push_back(polling),
+                None => queues.push((channel, VecDeque::from([polling]))),
+            }
+        }
+
+        let mut record = |result: JobResult, summary: &mut PollSummary, progress: &mut dyn FnMut(&PollEvent)| {
+            for (obis, attribute, value) in result.values.iter().cloned() {
+                registry.store(&result.job.meter_id, obis, attribute, value);
+            }
+            match result.status {
+                exchange_status::DONE => summary.done += 1,
+                exchange_status::PARTIAL => summary.partial += 1,
+                _ => summary.failed += 1,
+            }
+            journal.append(ExchangeRecord {
+                task_id: result.job.task_id,
+                meter_uid: result.job.meter_id.clone(),
+                start: result.start.0.to_vec(),
+                status: result.status,
+                end: result.end.0.to_vec(),
+                attempts: result.attempts,
+            });
+            completed += 1;
+            progress(&PollEvent::Finished { meter_id: result.job.meter_id, status: result.status, completed, total });
+        };
+        for result in finished {
+            record(result, &mut summary, &mut progress);
+        }
+
+        let channels: Vec<(usize, Channel)> = queues
+            .into_iter()
+            .map(|(channel, queue)| {
+                let workers = self.limit(channel).min(queue.len());
+                (workers, Channel { state: Mutex::new(ChannelState { queue, active: 0 }), changed: Condvar::new() })
+            })
+            .collect();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::scope(|scope| {
+            for (workers, channel) in &channels {
+                for _ in 0..*workers {
+                    let sender = sender.clone();
+                    let open = &open;
+                    scope.spawn(move || self.work(channel, open, &sender));
+                }
+            }
+            drop(sender);
+            for message in receiver {
+                match message {
+                    WorkerMessage::Event(event) => progress(&event),
+                    WorkerMessage::Done(result) => record(result, &mut summary, &mut progress),
+                }
+            }
+        });
+        summary.cancelled = total - completed;
+        summary
+    }
+
+    /// A worker: attempts the channel's ready jobs until none is left. A job
+    /// that is to be retried goes back to the queue for its backoff, so the
+    /// session slot serves the next ready job meanwhile.
+    fn work<L, F>(&self, channel: &Channel, open: &F, events: &mpsc::Sender<WorkerMessage>)
+    where
+        L: DataLinkLayer,
+        F: Fn(&MeterDescriptor) -> Result<ClientSession<L>, SessionError>,
+    {
+        while let Some(mut polling) = self.next_job(channel) {
+            let done = self.cancel.is_cancelled() || self.attempt(&mut polling, open, events);
+            let mut state = channel.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.active -= 1;
+            // Once cancelled, a job not yet started is skipped and one backing
+            // off ends with what it has read.
+            let finished = if !done {
+                state.queue.push_back(polling);
+                None
+            } else {
+                (polling.attempts > 0).then_some(polling)
+            };
+            drop(state);
+            channel.changed.notify_all();
+            if let Some(polling) = finished {
+                if events.send(WorkerMessage::Done(polling.finish((self.clock)()))).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Takes the channel's first job whose backoff has elapsed (any job once
+    /// cancelled), waiting while every queued job backs off. Returns `None`
+    /// once no job is queued or being attempted.
+    fn next_job(&self, channel: &Channel) -> Option<Polling> {
+        let mut state = channel.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let now = Instant::now();
+            let cancelled = self.cancel.is_cancelled();
+            let ready = state.queue.iter().position(|p| cancelled || p.not_before.is_none_or(|at| at <= now));
+            if let Some(polling) = ready.and_then(|index| state.queue.remove(index)) {
+                state.active += 1;
+                return Some(polling);
+            }
+            if state.queue.is_empty() && state.active == 0 {
+                return None;
+            }
+            let next = state.queue.iter().filter_map(|p| p.not_before).min();
+            let wait = next.map_or(CANCEL_CHECK, |at| at.saturating_duration_since(now).min(CANCEL_CHECK));
+            state = channel.changed.wait_timeout(state, wait).unwrap_or_else(|e| e.into_inner()).0;
+        }
+    }
+
+    /// Makes one attempt at a job, on a worker thread. Returns whether the job
+    /// is finished; otherwise it is due for a retry after its backoff.
+    fn attempt<L, F>(&self, polling: &mut Polling, open: &F, events: &mpsc::Sender<WorkerMessage>) -> bool
+    where
+        L: DataLinkLayer,
+        F: Fn(&MeterDescriptor) -> Result<ClientSession<L>, SessionError>,
+    {
+        if polling.start.is_none() {
+            polling.start = Some((self.clock)());
+        }
+        polling.attempts += 1;
+        let meter_id = &polling.job.meter_id;
+        let _ = events
+            .send(WorkerMessage::Event(PollEvent::Attempt { meter_id: meter_id.clone(), attempt: polling.attempts }));
+        let error = match open(&polling.meter) {
+            Ok(mut session) => {
+                polling.link_failed = false;
+                match read_attributes(&mut session, &mut polling.pending, &mut polling.values, &mut polling.refused) {
+                    Ok(()) => return true,
+                    Err(e) => e,
+                }
+            }
+            Err(e) => {
+                polling.link_failed = true;
+                e
+            }
+        };
+        if polling.attempts >= self.retry.max_attempts.max(1) {
+            return true;
+        }
+        let _ = events.send(WorkerMessage::Event(PollEvent::Retrying {
+            meter_id: meter_id.clone(),
+            attempt: polling.attempts,
+            error: error.to_string(),
+        }));
+        polling.not_before = Some(Instant::now() + self.retry.backoff(polling.attempts));
+        false
+    }
+}
+
+/// Reads the `pending` attributes in order, moving each answered one out of
+/// `pending`: values go to `values`, refusals to `refused`. Stops at the first
+/// session failure, leaving the rest pending.
+fn read_attributes<L: DataLinkLayer>(
+    session: &mut ClientSession<L>,
+    pending: &mut Vec<AttributeRef>,
+    values: &mut Vec<(ObisCode, u8, CosemDataType)>,
+    refused: &mut Vec<u8>,
+) -> Result<(), SessionError> {
+    while let Some((class_id, obis, attribute)) = pending.first().cloned() {
+        match session.get(class_id, obis, attribute)? {
+            GetResponse::Normal { result: GetDataResult::Data(value), .. } => {
+                // Attribute ids are always <128 in practice (i8-valued on the wire).
+                #[allow(clippy::cast_sign_loss)]
+                values.push((obis, attribute as u8, value));
+            }
+            GetResponse::Normal { result: GetDataResult::AccessResult(code), .. } => refused.push(code),
+            _ => refused.push(data_access_result::OTHER_REASON),
+        }
+        pending.remove(0);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::classes::data::Data;
+    use crate::server::RequestDispatcher;
+    use crate::spodus::meter::MeterChannel;
+
+    fn energy() -> ObisCode {
+        ObisCode::new(1, 0, 1, 8, 0, 255)
+    }
+
+    /// A loopback link to a local "meter"; while open it counts as one active
+    /// session on its channel.
+    struct LocalLink {
+        server: RequestDispatcher,
+        pending: Option<Vec<u8>>,
+        active: Arc<AtomicUsize>,
+    }
+
+    impl DataLinkLayer for LocalLink {
+        fn send_apdu(&mut self, apdu: &[u8]) -> io::Result<()> {
+            std::thread::sleep(Duration::from_millis(5));
+            self.pending = Some(self.server.dispatch(apdu).expect("dispatch"));
+            Ok(())
+        }
+        fn receive_apdu(&mut self) -> io::Result<Vec<u8>> {
+            Ok(self.pending.take().expect("a response"))
+        }
+    }
+
+    impl Drop for LocalLink {
+        fn drop(&mut self) {
+            self.active.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    fn registry(meters: &[(&[u8], u8)]) -> MeterRegistry {
+        let mut registry = MeterRegistry::new();
+        for (id, channel) in meters {
+            registry.add(MeterDescriptor {
+                meter_id: id.to_vec(),
+                channels: vec![MeterChannel { id: *channel, address: Vec::new() }],
+                ..Default::default()
+            });
+        }
+        registry
+    }
+
+    fn job(meter_id: &[u8]) -> PollJob {
+        PollJob { task_id: 1, meter_id: meter_id.to_vec(), attributes: vec![(1, energy(), 2)] }
+    }
+
+    #[test]
+    fn polls_channels_in_parallel_within_limits() {
+        let mut registry = registry(&[(b"BUS1", 1), (b"BUS2", 1), (b"BUS3", 1), (b"TCP1", 2), (b"TCP2", 2)]);
+        let mut journal = ExchangeStatusJournal::new();
+        let active = [Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0))];
+        let peak = [AtomicUsize::new(0), AtomicUsize::new(0)];
+        let engine = PollingEngine::new().with_channel_limit(1, 1).with_channel_limit(2, 2);
+        let jobs = [b"BUS1", b"BUS2", b"BUS3", b"TCP1", b"TCP2"].map(|id| job(id)).to_vec();
+
+        let mut events = Vec::new();
+        let summary = engine.run(
+            &mut registry,
+            &mut journal,
+            jobs,
+            |meter| {
+                let channel = usize::from(meter.channels[0].id - 1);
+                let now = active[channel].fetch_add(1, Ordering::SeqCst) + 1;
+                peak[channel].fetch_max(now, Ordering::SeqCst);
+                let mut server = RequestDispatcher::new();
+                server.add(Box::new(Data::new(energy(), CosemDataType::DoubleLongUnsigned(42))));
+                Ok(ClientSession::new(LocalLink { server, pending: None, active: active[channel].clone() }))
+            },
+            |event| events.push(event.clone()),
+        );
+
+        assert_eq!(summary, PollSummary { done: 5, ..Default::default() });
+        assert_eq!(peak[0].load(Ordering::SeqCst), 1, "the RS-485 bus is polled one meter at a time");
+        assert!(peak[1].load(Ordering::SeqCst) <= 2);
+        assert_eq!(registry.cached(b"TCP2", &energy(), 2), Some(&CosemDataType::DoubleLongUnsigned(42)));
+        assert_eq!(journal.records().len(), 5);
+        assert!(journal.records().iter().all(|r| r.status == exchange_status::DONE && r.attempts == 1));
+        assert!(matches!(events.last(), Some(PollEvent::Finished { completed: 5, total: 5, .. })));
+    }
+
+    #[test]
+    fn retries_failing_meters_and_journals_the_outcome() {
+        let mut registry = registry(&[(b"FLAKY", 1), (b"DEAD", 2)]);
+        let mut journal = ExchangeStatusJournal::new();
+        let retry = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+        };
+        let engine = PollingEngine::new().with_retry(retry).with_default_limit(2);
+        let flaky_attempts = AtomicUsize::new(0);
+
+        let mut retries = 0;
+        let summary = engine.run(
+            &mut registry,
+            &mut journal,
+            vec![job(b"FLAKY"), job(b"DEAD"), job(b"UNKNOWN")],
+            |meter| {
+                if meter.meter_id == b"DEAD" || flaky_attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(SessionError::Timeout);
+                }
+                let mut server = RequestDispatcher::new();
+                server.add(Box::new(Data::new(energy(), CosemDataType::DoubleLongUnsigned(7))));
+                Ok(ClientSession::new(LocalLink { server, pending: None, active: Arc::new(AtomicUsize::new(1)) }))
+            },
+            |event| retries += usize::from(matches!(event, PollEvent::Retrying { .. })),
+        );
+
+        assert_eq!(summary, PollSummary { done: 1, failed: 2, ..Default::default() });
+        assert_eq!(retries, 1 + 2);
+        let status = |id: &[u8]| journal.records().iter().find(|r| r.meter_uid == id).map(|r| (r.status, r.attempts));
+        assert_eq!(status(b"FLAKY"), Some((exchange_status::DONE, 2)));
+        assert_eq!(status(b"DEAD"), Some((exchange_status::BAD_LINK, 3)));
+        assert_eq!(status(b"UNKNOWN"), Some((exchange_status::NOT_FOUND, 0)));
+    }
+
+    #[test]
+    fn backoff_frees_the_channel_for_the_next_meter() {
+        let mut registry = registry(&[(b"FLAKY", 1), (b"OK", 1)]);
+        let mut journal = ExchangeStatusJournal::new();
+        let retry = RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_millis(200),
+        };
+        let engine = PollingEngine::new().with_retry(retry).with_channel_limit(1, 1);
+        let flaky_attempts = AtomicUsize::new(0);
+
+        let mut attempts = Vec::new();
+        let summary = engine.run(
+            &mut registry,
+            &mut journal,
+            vec![job(b"FLAKY"), job(b"OK")],
+            |meter| {
+                if meter.meter_id == b"FLAKY" && flaky_attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(SessionError::Timeout);
+                }
+                let mut server = RequestDispatcher::new();
+                server.add(Box::new(Data::new(energy(), CosemDataType::DoubleLongUnsigned(7))));
+                Ok(ClientSession::new(LocalLink { server, pending: None, active: Arc::new(AtomicUsize::new(1)) }))
+            },
+            |event| {
+                if let PollEvent::Attempt { meter_id, attempt } = event {
+                    attempts.push((meter_id.clone(), *attempt));
+                }
+            },
+        );
+
+        assert_eq!(summary, PollSummary { done: 2, ..Default::default() });
+        // The second meter is polled while the first one backs off.
+        assert_eq!(attempts, [(b"FLAKY".to_vec(), 1), (b"OK".to_vec(), 1), (b"FLAKY".to_vec(), 2)]);
+        let order: Vec<_> = journal.records().iter().map(|r| (r.meter_uid.clone(), r.attempts)).collect();
+        assert_eq!(order, [(b"OK".to_vec(), 1), (b"FLAKY".to_vec(), 2)]);
+    }
+
+    #[test]
+    fn cancelling_ends_a_job_backing_off() {
+        let mut registry = registry(&[(b"DEAD", 1)]);
+        let mut journal = ExchangeStatusJournal::new();
+        let retry = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(60),
+        };
+        let engine = PollingEngine::new().with_retry(retry);
+        let cancel = engine.cancel_token();
+        let summary = engine.run(
+            &mut registry,
+            &mut journal,
+            vec![job(b"DEAD")],
+            |_| -> Result<ClientSession<LocalLink>, SessionError> { Err(SessionError::Timeout) },
+            |event| {
+                if matches!(event, PollEvent::Retrying { .. }) {
+                    cancel.cancel();
+                }
+            },
+        );
+        assert_eq!(summary, PollSummary { failed: 1, ..Default::default() });
+        assert_eq!(journal.records()[0].attempts, 1);
+        assert_eq!(journal.records()[0].status, exchange_status::BAD_LINK);
+    }
+
+    #[test]
+    fn cancelled_run_skips_remaining_jobs() {
+        let mut registry = registry(&[(b"A", 1), (b"B", 1)]);
+        let mut journal = ExchangeStatusJournal::new();
+        let engine = PollingEngine::new();
+        engine.cancel_token().cancel();
+        let summary = engine.run(
+            &mut registry,
+            &mut journal,
+            vec![job(b"A"), job(b"B")],
+            |_| -> Result<ClientSession<LocalLink>, SessionError> { panic!("no session is opened") },
+            |_| {},
+        );
+        assert_eq!(summary, PollSummary { cancelled: 2, ..Default::default() });
+        assert!(journal.records().is_empty());
+    }
+}
@@ -1,4 +1,5 @@
-//! Shared builder for the ИВКЭ reference `ProfileGeneric` objects.
+//! Shared builder for the ИВКЭ reference `ProfileGeneric` objects, and the
+//! field readers used to decode their rows back into the typed models.
 
 use std::sync::Arc;
 
@@ -11,16 +12,18 @@ use crate::types::CosemDataType;
 
 /// Builds a СТО-013 reference `ProfileGeneric` (IC 7, v1): each code in
 /// `column_codes` becomes a capture-object column marker (`Data` at that code,
-/// attribute 2) and `buffer` holds the rows.
+/// attribute 2), `buffer` holds the rows and `profile_entries` is the buffer
+/// capacity (0 when unbounded).
 pub(crate) fn reference_profile(
     logical_name: ObisCode,
     column_codes: &[ObisCode],
     buffer: Vec<CosemDataType>,
+    profile_entries: u32,
 ) -> ProfileGeneric {
     let capture_objects = column_codes
         .iter()
         .map(|code| {
-            let object: Arc<dyn InterfaceClass + Send + Sync> = Arc::new(Data::new(code.clone(), CosemDataType::Null));
+            let object: Arc<dyn InterfaceClass + Send + Sync> = Arc::new(Data::new(*code, CosemDataType::Null));
             (object, 2u8)
         })
         .collect();
@@ -36,6 +39,64 @@ pub(crate) fn reference_profile(
         sort_method: SortMethod::Fifo,
         sort_object: None,
         entries_in_use,
-        profile_entries: 0,
+        profile_entries,
     })
 }
+
+/// Appends `row` to a journal buffer holding at most `profile_entries` rows (0
+/// when unbounded), evicting the oldest rows first (FIFO).
+pub(crate) fn push_bounded<T>(rows: &mut Vec<T>, row: T, profile_entries: u32) {
+    rows.push(row);
+    let capacity = profile_entries as usize;
+    if capacity > 0 && rows.len() > capacity {
+        rows.drain(..rows.len() - capacity);
+    }
+}
+
+/// The fields of a row `structure` that must have exactly `count` elements.
+pub(crate) fn fields(value: &CosemDataType, count: usize) -> Result<&[CosemDataType], String> {
+    match value {
+        CosemDataType::Structure(items) if items.len() == count => Ok(items),
+        _ => Err(format!("expected a structure of {count} elements")),
+    }
+}
+
+/// The elements of an `array`.
+pub(crate) fn elements(value: &CosemDataType) -> Result<&[CosemDataType], String> {
+    match value {
+        CosemDataType::Array(items) => Ok(items),
+        _ => Err("expected an array".to_string()),
+    }
+}
+
+/// An octet-string (or date-time) field.
+pub(crate) fn octets(value: &CosemDataType) -> Result<Vec<u8>, String> {
+    match value {
+        CosemDataType::OctetString(v) | CosemDataType::DateTime(v) => Ok(v.clone()),
+        _ => Err("expected an octet-string".to_string()),
+    }
+}
+
+/// An unsigned (or enum) field.
+pub(crate) fn unsigned(value: &CosemDataType) -> Result<u8, String> {
+    match value {
+        CosemDataType::Unsigned(v) | CosemDataType::Enum(v) => Ok(*v),
+        _ => Err("expected an unsigned".to_string()),
+    }
+}
+
+/// A long-unsigned field.
+pub(crate) fn long_unsigned(value: &CosemDataType) -> Result<u16, String> {
+    match value {
+        CosemDataType::LongUnsigned(v) => Ok(*v),
+        _ => Err("expected a long-unsigned".to_string()),
+    }
+}
+
+/// A double-long-unsigned field.
+pub(crate) fn double_long_unsigned(value: &CosemDataType) -> Result<u32, String> {
+    match value {
+        CosemDataType::DoubleLongUnsigned(v) => Ok(*v),
+        _ => Err("expected a double-long-unsigned".to_string()),
+    }
+}
@@ -15,7 +15,7 @@
 use std::any::Any;
 use std::cmp::Ordering;
 
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 
@@ -151,6 +151,18 @@ fn as_int(v: &CosemDataType) -> Option<i128> {
     })
 }
 
+/// Attribute descriptors of the Profile data filter class.
+const ATTRIBUTES: &[AttributeInfo] = &[AttributeInfo::new(1, "logical_name", DataKind::OctetString)];
+
+/// Method descriptors of the Profile data filter class; the parameters are the СПОДУС
+/// filter and entry structures.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "retrieve_number_of_entries", DataKind::Any),
+    MethodInfo::new(2, "retrieve_entries", DataKind::Any),
+    MethodInfo::new(3, "retrieve_entries_by_row", DataKind::Any),
+    MethodInfo::new(4, "remove_entries", DataKind::Any),
+];
+
 impl InterfaceClass for ProfileDataFilter {
     fn class_id(&self) -> u16 {
         8201
@@ -177,6 +189,16 @@ impl InterfaceClass for ProfileDataFilter {
         ]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Profile data filter",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         buf.push(0x02);
         buf.push(0x02);
@@ -188,7 +210,7 @@ impl InterfaceClass for ProfileDataFilter {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => {
                 let (_, filters) = Self::parse(params);
@@ -221,13 +243,19 @@ impl InterfaceClass for ProfileDataFilter {
                 self.rows = kept;
                 Ok(CosemDataType::Null)
             }
-            other => Err(format!("method {other} not supported for the Profile data filter class")),
+            other => Err(AccessError::ObjectUnavailable(format!(
+                "method {other} not supported for the Profile data filter class"
+            ))),
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 #[cfg(test)]
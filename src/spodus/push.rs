@@ -56,6 +56,26 @@ impl EventMessages {
         self.messages.push(message);
     }
 
+    /// The pending events, oldest first.
+    pub fn messages(&self) -> &[EventMessage] {
+        &self.messages
+    }
+
+    /// Whether no event is pending.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Removes and returns the pending events.
+    pub fn take(&mut self) -> Vec<EventMessage> {
+        std::mem::take(&mut self.messages)
+    }
+
+    /// Removes the `count` oldest pending events.
+    pub fn remove_oldest(&mut self, count: usize) {
+        self.messages.drain(..count.min(self.messages.len()));
+    }
+
     /// Builds the COSEM `Data` (IC 1) object holding the `array message` (§8.5.11).
     pub fn build(&self) -> Data {
         let array = self.messages.iter().map(EventMessage::to_structure).collect();
@@ -1,18 +1,30 @@
 //! ИВКЭ record journals (СТО 34.01-5.1-013-2023, §10.10 and §10.11).
 //!
 //! * The **object-correction journal** (§10.10, `0.0.94.7.136.255`) records the
-//!   last correction time of the ИВКЭ configuration objects.
+//!   last correction time of the ИВКЭ configuration objects, and of meter
+//!   clocks with their time before and after the correction.
 //! * The **numeric meter journal** (§10.11, `0.0.94.7.137.255`) holds meter
 //!   readings disaggregated one value per row.
 //!
-//! Both are `ProfileGeneric` (IC 7, v1) objects.
+//! Both are `ProfileGeneric` (IC 7, v1) objects, bounded by their
+//! `profile_entries` and persisted through [`Persist`].
+
+use std::io;
 
 use crate::classes::profile_generic::ProfileGeneric;
 use crate::obis::ObisCode;
 use crate::types::CosemDataType;
 
 use super::obis;
-use super::profile::reference_profile;
+use super::profile::{elements, fields, long_unsigned, octets, push_bounded, reference_profile, unsigned};
+use super::storage::{self, invalid_data, Persist, Storage};
+
+/// Storage key of the object-correction journal.
+const CORRECTION_KEY: &str = "correction_journal";
+/// Storage key of the numeric meter journal.
+const NUMERIC_KEY: &str = "numeric_journal";
+/// Storage key of the incoming push-events table snapshot.
+const INCOMING_KEY: &str = "incoming_events";
 
 /// One object-correction record (§10.10, Table 9).
 #[derive(Clone, Debug, Default)]
@@ -21,39 +33,102 @@ pub struct CorrectionRecord {
     pub object_obis: Vec<u8>,
     /// Last correction time (date-time octets).
     pub time: Vec<u8>,
+    /// The object's time before the correction, for clock corrections
+    /// (date-time octets, empty otherwise).
+    pub old_time: Vec<u8>,
+    /// The object's time after the correction, for clock corrections
+    /// (date-time octets, empty otherwise).
+    pub new_time: Vec<u8>,
+}
+
+impl CorrectionRecord {
+    /// The Table-9 buffer row.
+    fn to_row(&self) -> CosemDataType {
+        CosemDataType::Structure(vec![
+            CosemDataType::OctetString(self.object_obis.clone()),
+            CosemDataType::DateTime(self.time.clone()),
+        ])
+    }
+
+    /// The stored row: the Table-9 columns followed by the old and new time.
+    fn to_stored_row(&self) -> CosemDataType {
+        CosemDataType::Structure(vec![
+            CosemDataType::OctetString(self.object_obis.clone()),
+            CosemDataType::DateTime(self.time.clone()),
+            CosemDataType::OctetString(self.old_time.clone()),
+            CosemDataType::OctetString(self.new_time.clone()),
+        ])
+    }
+}
+
+impl TryFrom<&CosemDataType> for CorrectionRecord {
+    type Error = String;
+
+    /// Decodes a stored row, or a Table-9 row without the old and new time.
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 4).or_else(|_| fields(value, 2))?;
+        Ok(CorrectionRecord {
+            object_obis: octets(&f[0])?,
+            time: octets(&f[1])?,
+            old_time: f.get(2).map(octets).transpose()?.unwrap_or_default(),
+            new_time: f.get(3).map(octets).transpose()?.unwrap_or_default(),
+        })
+    }
 }
 
 /// The object-correction journal (§10.10).
 #[derive(Clone, Debug, Default)]
 pub struct CorrectionJournal {
     records: Vec<CorrectionRecord>,
+    profile_entries: u32,
+    unsaved: usize,
 }
 
 impl CorrectionJournal {
-    /// Creates an empty journal.
+    /// Creates an empty, unbounded journal.
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Records a correction.
+    /// Bounds the journal to its last `profile_entries` records (0 = unbounded).
+    pub fn with_profile_entries(mut self, profile_entries: u32) -> Self {
+        self.profile_entries = profile_entries;
+        self
+    }
+
+    /// Records a correction, evicting the oldest record when the journal is full.
     pub fn record(&mut self, record: CorrectionRecord) {
-        self.records.push(record);
+        push_bounded(&mut self.records, record, self.profile_entries);
+        self.unsaved = (self.unsaved + 1).min(self.records.len());
+    }
+
+    /// The recorded corrections, oldest first.
+    pub fn records(&self) -> &[CorrectionRecord] {
+        &self.records
     }
 
     /// Builds the COSEM `ProfileGeneric` (IC 7, v1) object (§10.10).
     pub fn build(&self) -> ProfileGeneric {
-        let buffer = self
-            .records
-            .iter()
-            .map(|r| {
-                CosemDataType::Structure(vec![
-                    CosemDataType::OctetString(r.object_obis.clone()),
-                    CosemDataType::DateTime(r.time.clone()),
-                ])
-            })
-            .collect();
+        let buffer = self.records.iter().map(CorrectionRecord::to_row).collect();
         let columns = [ObisCode::new(0, 0, 94, 7, 136, 0), ObisCode::new(0, 0, 94, 7, 136, 1)];
-        reference_profile(obis::object_correction_journal(), &columns, buffer)
+        reference_profile(obis::object_correction_journal(), &columns, buffer, self.profile_entries)
+    }
+}
+
+impl Persist for CorrectionJournal {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let profile_entries = self.profile_entries;
+        storage::save_journal(storage, CORRECTION_KEY, &self.records, &mut self.unsaved, profile_entries, |r| {
+            r.to_stored_row()
+        })
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.records = storage::load_journal(storage, CORRECTION_KEY, self.profile_entries, |row| {
+            CorrectionRecord::try_from(row)
+        })?;
+        self.unsaved = 0;
+        Ok(())
     }
 }
 
@@ -77,40 +152,73 @@ pub struct NumericRecord {
     pub ivke_time: Vec<u8>,
 }
 
+impl NumericRecord {
+    fn to_row(&self) -> CosemDataType {
+        CosemDataType::Structure(vec![
+            CosemDataType::OctetString(self.meter_id.clone()),
+            CosemDataType::OctetString(self.journal_obis.clone()),
+            CosemDataType::OctetString(self.reading_obis.clone()),
+            CosemDataType::Unsigned(self.attribute),
+            CosemDataType::DateTime(self.meter_time.clone()),
+            self.value.clone(),
+            CosemDataType::DateTime(self.ivke_time.clone()),
+        ])
+    }
+}
+
+impl TryFrom<&CosemDataType> for NumericRecord {
+    type Error = String;
+
+    /// Decodes a journal row (Table 10).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 7)?;
+        Ok(NumericRecord {
+            meter_id: octets(&f[0])?,
+            journal_obis: octets(&f[1])?,
+            reading_obis: octets(&f[2])?,
+            attribute: unsigned(&f[3])?,
+            meter_time: octets(&f[4])?,
+            value: f[5].clone(),
+            ivke_time: octets(&f[6])?,
+        })
+    }
+}
+
 /// The numeric meter journal (§10.11).
 #[derive(Clone, Debug, Default)]
 pub struct NumericJournal {
     records: Vec<NumericRecord>,
+    profile_entries: u32,
+    unsaved: usize,
 }
 
 impl NumericJournal {
-    /// Creates an empty journal.
+    /// Creates an empty, unbounded journal.
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Records a disaggregated meter reading.
+    /// Bounds the journal to its last `profile_entries` records (0 = unbounded).
+    pub fn with_profile_entries(mut self, profile_entries: u32) -> Self {
+        self.profile_entries = profile_entries;
+        self
+    }
+
+    /// Records a disaggregated meter reading, evicting the oldest record when
+    /// the journal is full.
     pub fn record(&mut self, record: NumericRecord) {
-        self.records.push(record);
+        push_bounded(&mut self.records, record, self.profile_entries);
+        self.unsaved = (self.unsaved + 1).min(self.records.len());
+    }
+
+    /// The recorded readings, oldest first.
+    pub fn records(&self) -> &[NumericRecord] {
+        &self.records
     }
 
     /// Builds the COSEM `ProfileGeneric` (IC 7, v1) object (§10.11).
     pub fn build(&self) -> ProfileGeneric {
-        let buffer = self
-            .records
-            .iter()
-            .map(|r| {
-                CosemDataType::Structure(vec![
-                    CosemDataType::OctetString(r.meter_id.clone()),
-                    CosemDataType::OctetString(r.journal_obis.clone()),
-                    CosemDataType::OctetString(r.reading_obis.clone()),
-                    CosemDataType::Unsigned(r.attribute),
-                    CosemDataType::DateTime(r.meter_time.clone()),
-                    r.value.clone(),
-                    CosemDataType::DateTime(r.ivke_time.clone()),
-                ])
-            })
-            .collect();
+        let buffer = self.records.iter().map(NumericRecord::to_row).collect();
         let columns = [
             ObisCode::new(0, 0, 94, 7, 128, 10),
             ObisCode::new(0, 0, 94, 7, 137, 1),
@@ -120,7 +228,21 @@ impl NumericJournal {
             ObisCode::new(0, 0, 94, 7, 137, 5),
             ObisCode::new(0, 0, 94, 7, 137, 6),
         ];
-        reference_profile(obis::numeric_meter_journal(), &columns, buffer)
+        reference_profile(obis::numeric_meter_journal(), &columns, buffer, self.profile_entries)
+    }
+}
+
+impl Persist for NumericJournal {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let profile_entries = self.profile_entries;
+        storage::save_journal(storage, NUMERIC_KEY, &self.records, &mut self.unsaved, profile_entries, |r| r.to_row())
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.records =
+            storage::load_journal(storage, NUMERIC_KEY, self.profile_entries, |row| NumericRecord::try_from(row))?;
+        self.unsaved = 0;
+        Ok(())
     }
 }
 
@@ -155,6 +277,38 @@ pub struct IncomingEvent {
     pub status: u8,
 }
 
+impl IncomingEvent {
+    fn to_row(&self) -> CosemDataType {
+        CosemDataType::Structure(vec![
+            CosemDataType::OctetString(self.meter_id.clone()),
+            CosemDataType::OctetString(self.meter_model.clone()),
+            CosemDataType::DateTime(self.ivke_time.clone()),
+            CosemDataType::DateTime(self.meter_time.clone()),
+            CosemDataType::Unsigned(self.journal_field),
+            CosemDataType::LongUnsigned(self.code),
+            CosemDataType::Unsigned(self.status),
+        ])
+    }
+}
+
+impl TryFrom<&CosemDataType> for IncomingEvent {
+    type Error = String;
+
+    /// Decodes a table row (Table 4).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 7)?;
+        Ok(IncomingEvent {
+            meter_id: octets(&f[0])?,
+            meter_model: octets(&f[1])?,
+            ivke_time: octets(&f[2])?,
+            meter_time: octets(&f[3])?,
+            journal_field: unsigned(&f[4])?,
+            code: long_unsigned(&f[5])?,
+            status: unsigned(&f[6])?,
+        })
+    }
+}
+
 /// The incoming push-events table (§8.5.10, `0.0.94.7.140.255`).
 #[derive(Clone, Debug, Default)]
 pub struct IncomingEventsTable {
@@ -172,23 +326,38 @@ impl IncomingEventsTable {
         self.rows.push(event);
     }
 
+    /// The recorded events, oldest first.
+    pub fn rows(&self) -> &[IncomingEvent] {
+        &self.rows
+    }
+
+    /// Moves every row whose transmission status is `from` to `to`, returning
+    /// the number of rows changed.
+    pub fn update_status(&mut self, from: u8, to: u8) -> usize {
+        let mut changed = 0;
+        for row in self.rows.iter_mut().filter(|r| r.status == from) {
+            row.status = to;
+            changed += 1;
+        }
+        changed
+    }
+
+    /// Moves the rows at the given indices whose transmission status is still
+    /// `from` to `to`, returning the number of rows changed.
+    pub fn update_rows(&mut self, rows: &[usize], from: u8, to: u8) -> usize {
+        let mut changed = 0;
+        for &index in rows {
+            if let Some(row) = self.rows.get_mut(index).filter(|r| r.status == from) {
+                row.status = to;
+                changed += 1;
+            }
+        }
+        changed
+    }
+
     /// Builds the COSEM `ProfileGeneric` (IC 7, v1) object (§8.5.10).
     pub fn build(&self) -> ProfileGeneric {
-        let buffer = self
-            .rows
-            .iter()
-            .map(|e| {
-                CosemDataType::Structure(vec![
-                    CosemDataType::OctetString(e.meter_id.clone()),
-                    CosemDataType::OctetString(e.meter_model.clone()),
-                    CosemDataType::DateTime(e.ivke_time.clone()),
-                    CosemDataType::DateTime(e.meter_time.clone()),
-                    CosemDataType::Unsigned(e.journal_field),
-                    CosemDataType::LongUnsigned(e.code),
-                    CosemDataType::Unsigned(e.status),
-                ])
-            })
-            .collect();
+        let buffer = self.rows.iter().map(IncomingEvent::to_row).collect();
         let columns = [
             ObisCode::new(0, 0, 94, 7, 128, 10),
             ObisCode::new(0, 0, 94, 7, 140, 2),
@@ -198,7 +367,26 @@ impl IncomingEventsTable {
             ObisCode::new(0, 0, 94, 7, 140, 6),
             ObisCode::new(0, 0, 94, 7, 140, 7),
         ];
-        reference_profile(obis::incoming_events_table(), &columns, buffer)
+        reference_profile(obis::incoming_events_table(), &columns, buffer, 0)
+    }
+}
+
+/// The table is saved as a snapshot rather than a journal: the transmission
+/// status of its rows changes after they are recorded.
+impl Persist for IncomingEventsTable {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let rows = self.rows.iter().map(IncomingEvent::to_row).collect();
+        storage.save_snapshot(INCOMING_KEY, &CosemDataType::Array(rows))
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.rows = match storage.load_snapshot(INCOMING_KEY)? {
+            Some(snapshot) => elements(&snapshot)
+                .and_then(|rows| rows.iter().map(IncomingEvent::try_from).collect())
+                .map_err(|e| invalid_data(INCOMING_KEY, e))?,
+            None => Vec::new(),
+        };
+        Ok(())
     }
 }
 
@@ -213,6 +401,7 @@ mod tests {
         journal.record(CorrectionRecord {
             object_obis: obis::meter_list().to_bytes(),
             time: vec![0x07, 0xE6, 0x07, 0x04],
+            ..Default::default()
         });
         let profile = journal.build();
         assert_eq!(profile.logical_name(), &obis::object_correction_journal());
@@ -224,6 +413,30 @@ mod tests {
         assert_eq!(caps.len(), 2);
     }
 
+    #[test]
+    fn correction_times_are_persisted() {
+        let mut journal = CorrectionJournal::new();
+        journal.record(CorrectionRecord {
+            object_obis: vec![0, 0, 1, 0, 0, 255],
+            time: vec![0x07, 0xE6, 0x07, 0x04],
+            old_time: vec![0x07, 0xE6, 0x07, 0x03],
+            new_time: vec![0x07, 0xE6, 0x07, 0x04],
+        });
+        let mut storage = storage::MemoryStorage::new();
+        journal.save(&mut storage).unwrap();
+        // Rows stored before the old and new time were kept still load.
+        let table9 = journal.build().attributes()[1].1.clone();
+        storage.append_journal(CORRECTION_KEY, elements(&table9).unwrap(), 0).unwrap();
+
+        let mut restored = CorrectionJournal::new();
+        restored.load(&storage).unwrap();
+        let records = restored.records();
+        assert_eq!(records[0].old_time, vec![0x07, 0xE6, 0x07, 0x03]);
+        assert_eq!(records[0].new_time, vec![0x07, 0xE6, 0x07, 0x04]);
+        assert!(records[1].old_time.is_empty());
+        assert_eq!(records[1].time, records[0].time);
+    }
+
     #[test]
     fn numeric_journal_builds_profile() {
         let mut journal = NumericJournal::new();
@@ -247,6 +460,37 @@ mod tests {
         assert_eq!(caps.len(), 7);
     }
 
+    #[test]
+    fn numeric_journal_is_bounded_and_persisted() {
+        let reading = |n: u32| NumericRecord {
+            meter_id: b"SIT12260004".to_vec(),
+            journal_obis: vec![1, 0, 98, 2, 0, 255],
+            reading_obis: vec![1, 0, 1, 8, 1, 255],
+            attribute: 2,
+            meter_time: vec![0x07, 0xE6, 0x07, 0x04],
+            value: CosemDataType::DoubleLongUnsigned(n),
+            ivke_time: vec![0x07, 0xE6, 0x07, 0x04],
+        };
+        let mut journal = NumericJournal::new().with_profile_entries(3);
+        let mut storage = storage::MemoryStorage::new();
+        for n in 0..2 {
+            journal.record(reading(n));
+        }
+        journal.save(&mut storage).unwrap();
+        for n in 2..5 {
+            journal.record(reading(n));
+        }
+        journal.save(&mut storage).unwrap();
+        let values: Vec<_> = journal.records().iter().map(|r| r.value.clone()).collect();
+        assert_eq!(values, [2, 3, 4].map(CosemDataType::DoubleLongUnsigned));
+        assert_eq!(journal.build().attributes()[7].1, CosemDataType::DoubleLongUnsigned(3));
+
+        let mut restored = NumericJournal::new().with_profile_entries(3);
+        restored.load(&storage).unwrap();
+        let values: Vec<_> = restored.records().iter().map(|r| r.value.clone()).collect();
+        assert_eq!(values, [2, 3, 4].map(CosemDataType::DoubleLongUnsigned));
+    }
+
     #[test]
     fn incoming_events_table_builds_profile() {
         let mut table = IncomingEventsTable::new();
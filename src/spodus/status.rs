@@ -3,14 +3,21 @@
 //! Per-meter status snapshot, exposed as a `ProfileGeneric` (IC 7, v1) with the
 //! Table-7 columns: meter uid, last successful/attempted session times, current
 //! relay state/mode and power limit, the last read meter time, the fix times of
-//! those values and the load-profile period.
+//! those values and the load-profile period. The table is persisted as a
+//! snapshot through [`Persist`].
+
+use std::io;
 
 use crate::classes::profile_generic::ProfileGeneric;
 use crate::obis::ObisCode;
 use crate::types::CosemDataType;
 
 use super::obis;
-use super::profile::reference_profile;
+use super::profile::{elements, fields, long_unsigned, octets, reference_profile, unsigned};
+use super::storage::{invalid_data, Persist, Storage};
+
+/// Storage key of the status table snapshot.
+const STATUS_KEY: &str = "meter_status";
 
 /// One meter's status row (§10.8, Table 7).
 #[derive(Clone, Debug, Default)]
@@ -60,6 +67,29 @@ impl MeterStatus {
     }
 }
 
+impl TryFrom<&CosemDataType> for MeterStatus {
+    type Error = String;
+
+    /// Decodes a table row (Table 7).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 12)?;
+        Ok(MeterStatus {
+            meter_uid: octets(&f[0])?,
+            last_success: octets(&f[1])?,
+            last_attempt: octets(&f[2])?,
+            relay_state: unsigned(&f[3])?,
+            relay_mode: unsigned(&f[4])?,
+            power_limit: unsigned(&f[5])?,
+            last_meter_time: octets(&f[6])?,
+            relay_state_time: octets(&f[7])?,
+            relay_mode_time: octets(&f[8])?,
+            power_limit_time: octets(&f[9])?,
+            current_time_fix: octets(&f[10])?,
+            load_profile_period: long_unsigned(&f[11])?,
+        })
+    }
+}
+
 /// The meter status table (§10.8).
 #[derive(Clone, Debug, Default)]
 pub struct MeterStatusTable {
@@ -77,6 +107,24 @@ impl MeterStatusTable {
         self.rows.push(status);
     }
 
+    /// Looks up a meter's status row by its unique identifier.
+    pub fn find(&self, meter_uid: &[u8]) -> Option<&MeterStatus> {
+        self.rows.iter().find(|r| r.meter_uid == meter_uid)
+    }
+
+    /// Returns a meter's status row for update, adding an empty row for it if
+    /// the meter has none yet.
+    pub fn entry(&mut self, meter_uid: &[u8]) -> &mut MeterStatus {
+        let index = match self.rows.iter().position(|r| r.meter_uid == meter_uid) {
+            Some(index) => index,
+            None => {
+                self.rows.push(MeterStatus { meter_uid: meter_uid.to_vec(), ..Default::default() });
+                self.rows.len() - 1
+            }
+        };
+        &mut self.rows[index]
+    }
+
     /// Builds the COSEM `ProfileGeneric` (IC 7, v1) object (§10.8).
     pub fn build(&self) -> ProfileGeneric {
         let buffer = self.rows.iter().map(MeterStatus::to_row).collect();
@@ -94,7 +142,24 @@ impl MeterStatusTable {
             ObisCode::new(0, 0, 94, 7, 134, 10),
             ObisCode::new(0, 0, 94, 7, 134, 11),
         ];
-        reference_profile(obis::meter_status_table(), &columns, buffer)
+        reference_profile(obis::meter_status_table(), &columns, buffer, 0)
+    }
+}
+
+impl Persist for MeterStatusTable {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let rows = self.rows.iter().map(MeterStatus::to_row).collect();
+        storage.save_snapshot(STATUS_KEY, &CosemDataType::Array(rows))
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.rows = match storage.load_snapshot(STATUS_KEY)? {
+            Some(snapshot) => elements(&snapshot)
+                .and_then(|rows| rows.iter().map(MeterStatus::try_from).collect())
+                .map_err(|e| invalid_data(STATUS_KEY, e))?,
+            None => Vec::new(),
+        };
+        Ok(())
     }
 }
 
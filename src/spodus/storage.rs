@@ -0,0 +1,568 @@
+//! Persistent storage of the ИВКЭ state (СТО 34.01-5.1-013-2023, §10).
+//!
+//! The concentrator's registries (meter list and aggregation cache, task list,
+//! access policies) are saved as whole **snapshots**; its journals are saved
+//! as **append-only** record streams bounded by the journal's
+//! `profile_entries`. Values are stored in their A-XDR encoding, the same
+//! structures the ИВКЭ serves upstream.
+//!
+//! [`Storage`] is the backend interface; [`MemoryStorage`] keeps everything in
+//! memory and [`FileStorage`] in a directory, with atomic snapshot replacement
+//! (write to a temporary file, sync, rename) and journals split into segment
+//! files of which only the last one is ever appended to. The typed models
+//! implement [`Persist`]; [`Concentrator::save`](super::node::Concentrator::save)
+//! and [`Concentrator::load`](super::node::Concentrator::load) persist them all.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::types::{BerError, CosemDataType};
+
+/// A storage backend for snapshots and append-only journals, addressed by key.
+pub trait Storage {
+    /// Atomically replaces the snapshot stored under `key`.
+    fn save_snapshot(&mut self, key: &str, value: &CosemDataType) -> io::Result<()>;
+
+    /// Loads the snapshot stored under `key`, or `None` if there is none.
+    fn load_snapshot(&self, key: &str) -> io::Result<Option<CosemDataType>>;
+
+    /// Appends `records` to the journal `key`, which keeps at least its last
+    /// `profile_entries` records (all records when 0).
+    fn append_journal(&mut self, key: &str, records: &[CosemDataType], profile_entries: u32) -> io::Result<()>;
+
+    /// Loads the last `profile_entries` records of the journal `key` (all
+    /// records when 0), oldest first.
+    fn load_journal(&self, key: &str, profile_entries: u32) -> io::Result<Vec<CosemDataType>>;
+}
+
+/// A model that can be saved to and restored from a [`Storage`].
+pub trait Persist {
+    /// Saves the model: a registry writes its snapshot, a journal appends the
+    /// records recorded since the last save or load.
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()>;
+
+    /// Replaces the model's contents with the stored state. Missing state
+    /// leaves the model empty.
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()>;
+}
+
+/// An in-memory [`Storage`] (volatile; useful for tests and as a reference).
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    snapshots: HashMap<String, CosemDataType>,
+    journals: HashMap<String, Vec<CosemDataType>>,
+}
+
+impl MemoryStorage {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn save_snapshot(&mut self, key: &str, value: &CosemDataType) -> io::Result<()> {
+        self.snapshots.insert(key.to_string(), value.clone());
+        Ok(())
+    }
+
+    fn load_snapshot(&self, key: &str) -> io::Result<Option<CosemDataType>> {
+        Ok(self.snapshots.get(key).cloned())
+    }
+
+    fn append_journal(&mut self, key: &str, records: &[CosemDataType], profile_entries: u32) -> io::Result<()> {
+        let journal = self.journals.entry(key.to_string()).or_default();
+        journal.extend_from_slice(records);
+        let capacity = profile_entries as usize;
+        if capacity > 0 && journal.len() > capacity {
+            journal.drain(..journal.len() - capacity);
+        }
+        Ok(())
+    }
+
+    fn load_journal(&self, key: &str, profile_entries: u32) -> io::Result<Vec<CosemDataType>> {
+        let journal = self.journals.get(key).map(Vec::as_slice).unwrap_or_default();
+        Ok(last(journal, profile_entries).to_vec())
+    }
+}
+
+/// Default number of records per journal segment file.
+pub const DEFAULT_SEGMENT_RECORDS: usize = 1024;
+
+/// A directory-backed [`Storage`].
+///
+/// * A snapshot `key` lives in `key.snap`, replaced atomically through
+///   `key.snap.tmp`.
+/// * A journal `key` lives in segment files `key.NNNNNNNNNN.seg`, each holding
+///   up to [`segment_records`](FileStorage::with_segment_records) A-XDR
+///   records. Only the newest segment is appended to; whole segments are
+///   deleted once the newer ones alone hold `profile_entries` records. A record
+///   torn by a crash at the end of the newest segment is ignored on load and
+///   cut off before the next append; any other undecodable record fails with
+///   `InvalidData` rather than losing the records after it.
+///
+/// The segment record counts of a journal are read once, on its first append,
+/// and then kept in memory, so appends only write: a directory must be
+/// written through a single `FileStorage`.
+#[derive(Clone, Debug)]
+pub struct FileStorage {
+    dir: PathBuf,
+    segment_records: usize,
+    /// Per journal, the `(index, records)` of its segments, oldest first.
+    counts: HashMap<String, Vec<(u64, usize)>>,
+}
+
+impl FileStorage {
+    /// Opens (creating it if needed) a storage directory.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(FileStorage { dir, segment_records: DEFAULT_SEGMENT_RECORDS, counts: HashMap::new() })
+    }
+
+    /// Sets the number of records per journal segment (at least 1).
+    pub fn with_segment_records(mut self, records: usize) -> Self {
+        self.segment_records = records.max(1);
+        self
+    }
+
+    /// The storage directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Rejects keys that would escape the storage directory.
+    fn check_key(key: &str) -> io::Result<()> {
+        if key.is_empty() || key.contains(['/', '\\']) || key.starts_with('.') {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid storage key {key:?}")));
+        }
+        Ok(())
+    }
+
+    /// The segment files of journal `key` as `(index, path)`, oldest first.
+    fn segments(&self, key: &str) -> io::Result<Vec<(u64, PathBuf)>> {
+        let prefix = format!("{key}.");
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else { continue };
+            let index = name.strip_prefix(&prefix).and_then(|rest| rest.strip_suffix(".seg"));
+            if let Some(index) = index.and_then(|i| i.parse::<u64>().ok()) {
+                segments.push((index, entry.path()));
+            }
+        }
+        segments.sort_unstable_by_key(|(index, _)| *index);
+        Ok(segments)
+    }
+
+    fn segment_path(&self, key: &str, index: u64) -> PathBuf {
+        self.dir.join(format!("{key}.{index:010}.seg"))
+    }
+
+    /// Counts the records of each segment of journal `key`, cutting a torn
+    /// tail off the newest segment, the one appended to next.
+    fn scan_journal(&self, key: &str) -> io::Result<Vec<(u64, usize)>> {
+        let segments = self.segments(key)?;
+        let mut counts = Vec::with_capacity(segments.len());
+        for (position, (index, path)) in segments.iter().enumerate() {
+            let newest = position + 1 == segments.len();
+            let (records, valid) = read_segment(path, newest)?;
+            counts.push((*index, records.len()));
+            if newest && valid < fs::metadata(path)?.len() as usize {
+                OpenOptions::new().write(true).open(path)?.set_len(valid as u64)?;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Appends `records` to the segments of journal `key`, starting a new
+    /// segment whenever the newest is full, then deletes the old segments
+    /// beyond the `profile_entries` bound. `counts` is kept up to date.
+    fn append_segments(
+        &self,
+        key: &str,
+        counts: &mut Vec<(u64, usize)>,
+        records: &[CosemDataType],
+        profile_entries: u32,
+    ) -> io::Result<()> {
+        let (mut index, mut count) = counts.last().copied().unwrap_or((0, 0));
+        let mut file: Option<File> = None;
+        for record in records {
+            if count >= self.segment_records || file.is_none() && counts.is_empty() {
+                if count >= self.segment_records {
+                    if let Some(f) = file.take() {
+                        f.sync_data()?;
+                    }
+                    index += 1;
+                    count = 0;
+                }
+                counts.push((index, 0));
+            }
+            if file.is_none() {
+                file = Some(OpenOptions::new().create(true).append(true).open(self.segment_path(key, index))?);
+            }
+            let mut bytes = Vec::new();
+            record
+                .serialize_ber(&mut bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{e:?}")))?;
+            if let Some(f) = file.as_mut() {
+                f.write_all(&bytes)?;
+            }
+            count += 1;
+            if let Some(last) = counts.last_mut() {
+                last.1 = count;
+            }
+        }
+        if let Some(f) = file {
+            f.sync_data()?;
+        }
+
+        // Drop whole old segments while the newer ones still hold the bound.
+        let capacity = profile_entries as usize;
+        if capacity > 0 {
+            let mut total: usize = counts.iter().map(|(_, n)| n).sum();
+            let mut dropped = 0;
+            for (index, n) in &counts[..counts.len().saturating_sub(1)] {
+                if total - n < capacity {
+                    break;
+                }
+                fs::remove_file(self.segment_path(key, *index))?;
+                total -= n;
+                dropped += 1;
+            }
+            counts.drain(..dropped);
+        }
+        Ok(())
+    }
+}
+
+impl Storage for FileStorage {
+    fn save_snapshot(&mut self, key: &str, value: &CosemDataType) -> io::Result<()> {
+        Self::check_key(key)?;
+        let mut bytes = Vec::new();
+        value.serialize_ber(&mut bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{e:?}")))?;
+        let path = self.dir.join(format!("{key}.snap"));
+        let tmp = self.dir.join(format!("{key}.snap.tmp"));
+        let mut file = File::create(&tmp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &path)?;
+        // Persist the rename itself; not every platform can sync a directory.
+        if let Ok(dir) = File::open(&self.dir) {
+            let _ = dir.sync_all();
+        }
+        Ok(())
+    }
+
+    fn load_snapshot(&self, key: &str) -> io::Result<Option<CosemDataType>> {
+        Self::check_key(key)?;
+        let bytes = match fs::read(self.dir.join(format!("{key}.snap"))) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let (value, _) = CosemDataType::deserialize_ber(&bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("corrupt snapshot {key}: {e:?}")))?;
+        Ok(Some(value))
+    }
+
+    fn append_journal(&mut self, key: &str, records: &[CosemDataType], profile_entries: u32) -> io::Result<()> {
+        Self::check_key(key)?;
+        let mut counts = match self.counts.remove(key) {
+            Some(counts) => counts,
+            None => self.scan_journal(key)?,
+        };
+        // On failure the counts stay dropped: the next append rescans the files.
+        self.append_segments(key, &mut counts, records, profile_entries)?;
+        self.counts.insert(key.to_string(), counts);
+        Ok(())
+    }
+
+    fn load_journal(&self, key: &str, profile_entries: u32) -> io::Result<Vec<CosemDataType>> {
+        Self::check_key(key)?;
+        let segments = self.segments(key)?;
+        let mut records = Vec::new();
+        for (position, (_, path)) in segments.iter().enumerate() {
+            records.extend(read_segment(path, position + 1 == segments.len())?.0);
+        }
+        let keep = last(&records, profile_entries).len();
+        records.drain(..records.len() - keep);
+        Ok(records)
+    }
+}
+
+/// Decodes the records of one segment file. Only in the `newest` segment may
+/// the last record be torn — cut short by a crash during an append — and it
+/// is then left out; any other undecodable record is `InvalidData`. Returns
+/// the records and the length of the well-formed prefix.
+fn read_segment(path: &Path, newest: bool) -> io::Result<(Vec<CosemDataType>, usize)> {
+    let bytes = fs::read(path)?;
+    let mut rest = bytes.as_slice();
+    let mut records = Vec::new();
+    while !rest.is_empty() {
+        match CosemDataType::deserialize_ber(rest) {
+            Ok((record, tail)) => {
+                records.push(record);
+                rest = tail;
+            }
+            Err(BerError::InvalidLength) if newest => break,
+            Err(e) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("corrupt journal segment {} at octet {}: {e:?}", path.display(), bytes.len() - rest.len()),
+                ))
+            }
+        }
+    }
+    Ok((records, bytes.len() - rest.len()))
+}
+
+/// The last `profile_entries` items of `records` (all when 0).
+fn last(records: &[CosemDataType], profile_entries: u32) -> &[CosemDataType] {
+    let capacity = profile_entries as usize;
+    if capacity > 0 && records.len() > capacity {
+        &records[records.len() - capacity..]
+    } else {
+        records
+    }
+}
+
+/// Wraps a decode error of a stored value.
+pub(crate) fn invalid_data(key: &str, error: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{key}: {error}"))
+}
+
+/// Saves a journal: appends its last `unsaved` rows and resets the counter.
+pub(crate) fn save_journal<T>(
+    storage: &mut dyn Storage,
+    key: &str,
+    rows: &[T],
+    unsaved: &mut usize,
+    profile_entries: u32,
+    to_row: impl Fn(&T) -> CosemDataType,
+) -> io::Result<()> {
+    let pending = (*unsaved).min(rows.len());
+    if pending > 0 {
+        let records: Vec<_> = rows[rows.len() - pending..].iter().map(to_row).collect();
+        storage.append_journal(key, &records, profile_entries)?;
+    }
+    *unsaved = 0;
+    Ok(())
+}
+
+/// Loads a journal's rows, bounded by its `profile_entries`.
+pub(crate) fn load_journal<T>(
+    storage: &dyn Storage,
+    key: &str,
+    profile_entries: u32,
+    from_row: impl Fn(&CosemDataType) -> Result<T, String>,
+) -> io::Result<Vec<T>> {
+    storage
+        .load_journal(key, profile_entries)?
+        .iter()
+        .map(|row| from_row(row).map_err(|e| invalid_data(key, e)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::listener::{Framing, ListenerError};
+    use crate::obis::ObisCode;
+    use crate::security::{SecurityPolicy, SecuritySuite};
+    use crate::service::ciphering::{self, glo, CipherError, SecurityContext};
+    use crate::service::notification::EventNotificationRequest;
+    use crate::service::AttributeDescriptor;
+    use crate::spodus::discovered::DiscoveredMeter;
+    use crate::spodus::incoming::PushSource;
+    use crate::spodus::journals::{EventJournal, ExchangeRecord, ExchangeStatusJournal};
+    use crate::spodus::meter::MeterDescriptor;
+    use crate::spodus::node::Concentrator;
+    use crate::spodus::obis;
+    use crate::spodus::records::IncomingEvent;
+    use crate::transport::wrapper;
+    use crate::types::attrs::DateTime;
+
+    fn context() -> SecurityContext {
+        SecurityContext::for_suite(
+            SecurityPolicy::AuthenticationEncryption,
+            SecuritySuite::Suite0,
+            vec![0x11; 16],
+            vec![0x22; 16],
+            b"SIT\x00\x00\x00\x00\x04".to_vec(),
+            0,
+        )
+        .unwrap()
+    }
+
+    /// A fresh, empty directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spodes-storage-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn file_snapshot_is_replaced() {
+        let dir = temp_dir("snapshot");
+        let mut storage = FileStorage::open(&dir).unwrap();
+        assert_eq!(storage.load_snapshot("meters").unwrap(), None);
+        storage.save_snapshot("meters", &CosemDataType::Unsigned(1)).unwrap();
+        storage.save_snapshot("meters", &CosemDataType::Array(vec![CosemDataType::Unsigned(2)])).unwrap();
+        let reopened = FileStorage::open(&dir).unwrap();
+        assert_eq!(
+            reopened.load_snapshot("meters").unwrap(),
+            Some(CosemDataType::Array(vec![CosemDataType::Unsigned(2)]))
+        );
+        assert!(!dir.join("meters.snap.tmp").exists());
+        assert_eq!(
+            storage.save_snapshot("../escape", &CosemDataType::Null).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn file_journal_rolls_and_drops_segments() {
+        let dir = temp_dir("journal");
+        let mut storage = FileStorage::open(&dir).unwrap().with_segment_records(2);
+        let records: Vec<_> = (0..7).map(CosemDataType::LongUnsigned).collect();
+        for chunk in records.chunks(3) {
+            storage.append_journal("j", chunk, 3).unwrap();
+        }
+        assert_eq!(storage.load_journal("j", 3).unwrap(), records[4..]);
+        // Segments [0,1] [2,3] were dropped; [4,5] [6] remain.
+        assert_eq!(storage.segments("j").unwrap().len(), 2);
+
+        // A record torn by a crash at the end of the last segment is ignored.
+        let (_, last) = storage.segments("j").unwrap().pop().unwrap();
+        OpenOptions::new().append(true).open(&last).unwrap().write_all(&[0x12, 0x00]).unwrap();
+        assert_eq!(storage.load_journal("j", 0).unwrap(), records[4..]);
+        // ...and cut off before the next append after the restart.
+        let mut storage = FileStorage::open(&dir).unwrap().with_segment_records(2);
+        storage.append_journal("j", &records[..1], 3).unwrap();
+        assert_eq!(storage.load_journal("j", 0).unwrap(), [&records[4..], &records[..1]].concat());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn file_journal_reports_corruption_instead_of_truncating() {
+        let dir = temp_dir("corrupt");
+        let mut storage = FileStorage::open(&dir).unwrap().with_segment_records(4);
+        let records: Vec<_> = (0..6).map(CosemDataType::LongUnsigned).collect();
+        storage.append_journal("j", &records, 0).unwrap();
+        let segments = storage.segments("j").unwrap();
+
+        // A bad tag in the middle of the newest segment is reported and its
+        // records are left in place.
+        let (_, newest) = &segments[1];
+        let mut bytes = fs::read(newest).unwrap();
+        bytes[0] = 0xFF;
+        fs::write(newest, &bytes).unwrap();
+        assert_eq!(storage.load_journal("j", 0).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let mut reopened = FileStorage::open(&dir).unwrap().with_segment_records(4);
+        assert_eq!(reopened.append_journal("j", &records[..1], 0).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(fs::read(newest).unwrap(), bytes);
+
+        // A record cut short in an older segment is corruption, not a torn tail.
+        bytes[0] = 0x12;
+        fs::write(newest, &bytes).unwrap();
+        let (_, oldest) = &segments[0];
+        let length = fs::metadata(oldest).unwrap().len();
+        OpenOptions::new().write(true).open(oldest).unwrap().set_len(length - 1).unwrap();
+        assert_eq!(storage.load_journal("j", 0).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let mut reopened = FileStorage::open(&dir).unwrap().with_segment_records(4);
+        assert_eq!(reopened.append_journal("j", &records[..1], 0).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(fs::metadata(oldest).unwrap().len(), length - 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn file_journal_appends_from_cached_counts() {
+        let dir = temp_dir("counts");
+        let mut storage = FileStorage::open(&dir).unwrap().with_segment_records(2);
+        let records: Vec<_> = (0..6).map(CosemDataType::LongUnsigned).collect();
+        storage.append_journal("j", &records[..2], 4).unwrap();
+        // Later appends do not read the segments back: an unreadable full
+        // segment is still counted, and dropped once past the bound.
+        let (_, first) = storage.segments("j").unwrap().remove(0);
+        fs::write(&first, [0xFF]).unwrap();
+        storage.append_journal("j", &records[2..4], 4).unwrap();
+        assert_eq!(storage.segments("j").unwrap().len(), 2);
+        storage.append_journal("j", &records[4..], 4).unwrap();
+        assert_eq!(storage.segments("j").unwrap().len(), 2);
+        assert_eq!(storage.load_journal("j", 4).unwrap(), records[2..]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn concentrator_state_survives_restart() {
+        let dir = temp_dir("node");
+        let configure = || {
+            let mut node = Concentrator::new();
+            node.exchange_journal = ExchangeStatusJournal::new().with_profile_entries(2);
+            node.event_journals.push(EventJournal::new(obis::access_control_log()));
+            node.pushes.add(PushSource {
+                meter_id: b"SIT12260004".to_vec(),
+                address: Some(1),
+                security: Some(context()),
+                ..Default::default()
+            });
+            node
+        };
+
+        let mut node = configure();
+        node.meters.add(MeterDescriptor { meter_id: b"SIT12260004".to_vec(), ..Default::default() });
+        node.discovered.record(DiscoveredMeter {
+            meter_id: b"SIT12260004".to_vec(),
+            channel_id: 1,
+            address: 0x10,
+            last_seen: vec![0x07, 0xE6, 0x07, 0x04],
+            ..Default::default()
+        });
+        node.meter_status.entry(b"SIT12260004").load_profile_period = 900;
+        node.incoming_events.record(IncomingEvent {
+            meter_id: b"SIT12260004".to_vec(),
+            code: 0x1C,
+            ..Default::default()
+        });
+        // A ciphered push from the meter advances its accepted invocation counter.
+        let event = EventNotificationRequest {
+            time: None,
+            attribute: AttributeDescriptor::new(1, ObisCode::new(0, 0, 96, 11, 0, 255), 2),
+            value: CosemDataType::Unsigned(1),
+        };
+        let mut sender = context();
+        sender.invocation_counter = 5;
+        let apdu = ciphering::protect(&sender, glo::EVENT_NOTIFICATION_REQUEST, &event.encode().unwrap()).unwrap();
+        let pdu = wrapper::encode(1, 0x10, &apdu);
+        let now = DateTime::from_ymdhms(2026, 10, 18, 12, 0, 0);
+        assert_eq!(node.receive_push(Framing::Wrapper, &pdu, &now).unwrap(), 1);
+        node.event_journals[0].log(vec![0x07, 0xE6, 0x07, 0x04], 0x11);
+        let mut storage = FileStorage::open(&dir).unwrap();
+        for task_id in 1..=3 {
+            node.exchange_journal.append(ExchangeRecord { task_id, ..Default::default() });
+            node.save(&mut storage).unwrap();
+        }
+
+        let mut rebooted = configure();
+        rebooted.load(&FileStorage::open(&dir).unwrap()).unwrap();
+        assert_eq!(rebooted.meters.meters(), node.meters.meters());
+        let tasks: Vec<u32> = rebooted.exchange_journal.records().iter().map(|r| r.task_id).collect();
+        assert_eq!(tasks, [2, 3]);
+        assert_eq!(rebooted.event_journals[0].entries(), node.event_journals[0].entries());
+        assert_eq!(rebooted.discovered.len(), 1);
+        assert_eq!(rebooted.meter_status.find(b"SIT12260004").map(|s| s.load_profile_period), Some(900));
+        let codes: Vec<u16> = rebooted.incoming_events.rows().iter().map(|e| e.code).collect();
+        assert_eq!(codes, [0x1C, 1]);
+        // The replayed push is still rejected after the restart.
+        assert!(matches!(
+            rebooted.receive_push(Framing::Wrapper, &pdu, &now),
+            Err(ListenerError::Cipher(CipherError::ReplayDetected))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
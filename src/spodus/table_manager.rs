@@ -8,7 +8,7 @@
 
 use std::any::Any;
 
-use crate::interface::InterfaceClass;
+use crate::interface::{AccessError, AttributeInfo, ClassInfo, DataKind, InterfaceClass, MethodInfo};
 use crate::obis::ObisCode;
 use crate::types::{BerError, CosemDataType};
 
@@ -48,10 +48,11 @@ impl TableManager {
     }
 
     /// Method 1: `add_update_entries` — merge full rows by unique key.
-    fn add_update_entries(&mut self, entries: Vec<CosemDataType>) -> Result<CosemDataType, String> {
+    fn add_update_entries(&mut self, entries: Vec<CosemDataType>) -> Result<CosemDataType, AccessError> {
         let key_index = self.key_index;
         for entry in entries {
-            let key = key_of(&entry, key_index).ok_or("entry is not a keyed structure")?;
+            let key = key_of(&entry, key_index)
+                .ok_or_else(|| AccessError::TypeUnmatched("entry is not a keyed structure".to_string()))?;
             match self.rows.iter_mut().find(|r| key_of(r, key_index).as_ref() == Some(&key)) {
                 Some(existing) => *existing = entry,
                 None => self.rows.push(entry),
@@ -88,17 +89,29 @@ impl TableManager {
 
     /// Extracts the `entries_list` from a method's `{object_obis, entries_list}`
     /// data structure.
-    fn entries_list(params: Option<CosemDataType>) -> Result<Vec<CosemDataType>, String> {
+    fn entries_list(params: Option<CosemDataType>) -> Result<Vec<CosemDataType>, AccessError> {
         match params {
             Some(CosemDataType::Structure(fields)) => match fields.into_iter().nth(1) {
                 Some(CosemDataType::Array(entries)) => Ok(entries),
-                _ => Err("entries_list must be an array".to_string()),
+                _ => Err(AccessError::TypeUnmatched("entries_list must be an array".to_string())),
             },
-            _ => Err("expected a {object_obis, entries_list} structure".to_string()),
+            _ => Err(AccessError::TypeUnmatched("expected a {object_obis, entries_list} structure".to_string())),
         }
     }
 }
 
+/// Attribute descriptors of the Table manager class.
+const ATTRIBUTES: &[AttributeInfo] = &[AttributeInfo::new(1, "logical_name", DataKind::OctetString)];
+
+/// Method descriptors of the Table manager class; the parameters are the СПОДУС
+/// filter and entry structures.
+const METHODS: &[MethodInfo] = &[
+    MethodInfo::new(1, "add_update_entries", DataKind::Any),
+    MethodInfo::new(2, "remove_entries", DataKind::Any),
+    MethodInfo::new(3, "retrieve_number_of_entries", DataKind::Any),
+    MethodInfo::new(4, "retrieve_entries", DataKind::Any),
+];
+
 impl InterfaceClass for TableManager {
     fn class_id(&self) -> u16 {
         8200
@@ -125,6 +138,16 @@ impl InterfaceClass for TableManager {
         ]
     }
 
+    fn metadata(&self) -> Option<ClassInfo> {
+        Some(ClassInfo {
+            name: "Table manager",
+            class_id: self.class_id(),
+            version: self.version(),
+            attributes: ATTRIBUTES,
+            methods: METHODS,
+        })
+    }
+
     fn serialize_ber(&self, buf: &mut Vec<u8>) -> Result<(), BerError> {
         buf.push(0x02); // structure
         buf.push(0x02); // two elements
@@ -136,7 +159,7 @@ impl InterfaceClass for TableManager {
         Ok(())
     }
 
-    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, String> {
+    fn invoke_method(&mut self, method_id: u8, params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
         match method_id {
             1 => {
                 let entries = Self::entries_list(params)?;
@@ -156,13 +179,19 @@ impl InterfaceClass for TableManager {
                 let keys = Self::entries_list(params)?;
                 Ok(self.retrieve_entries(&keys))
             }
-            other => Err(format!("method {other} not supported for the Table manager class")),
+            other => {
+                Err(AccessError::ObjectUnavailable(format!("method {other} not supported for the Table manager class")))
+            }
         }
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 #[cfg(test)]
@@ -18,11 +18,20 @@
 //!     priority: long-unsigned
 //! }
 //! ```
+//!
+//! The task list is persisted as a snapshot through [`Persist`].
+
+use std::io;
 
 use crate::classes::data::Data;
 use crate::types::CosemDataType;
 
 use super::obis;
+use super::profile::{elements, fields, long_unsigned, octets, unsigned};
+use super::storage::{invalid_data, Persist, Storage};
+
+/// Storage key of the task-list snapshot.
+const TASKS_KEY: &str = "exchange_tasks";
 
 /// `service_id` values of a task script (§10.7).
 pub mod service_id {
@@ -68,6 +77,25 @@ impl Script {
     }
 }
 
+impl TryFrom<&CosemDataType> for Script {
+    type Error = String;
+
+    /// Decodes a `script` structure (§10.7).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 7)?;
+        let opt = |v: &CosemDataType| (*v != CosemDataType::Null).then(|| v.clone());
+        Ok(Script {
+            service_id: unsigned(&f[0])?,
+            class_id: unsigned(&f[1])?,
+            obis: octets(&f[2])?,
+            index: unsigned(&f[3])?,
+            range_descriptor: opt(&f[4]),
+            entry_descriptor: opt(&f[5]),
+            value: opt(&f[6]),
+        })
+    }
+}
+
 /// One scheduled execution time (§10.7, `execution_time_date`).
 #[derive(Clone, Debug, Default)]
 pub struct ExecutionTime {
@@ -124,6 +152,32 @@ impl ExchangeTask {
     }
 }
 
+impl TryFrom<&CosemDataType> for ExchangeTask {
+    type Error = String;
+
+    /// Decodes an `exchange` structure (§10.7).
+    fn try_from(value: &CosemDataType) -> Result<Self, Self::Error> {
+        let f = fields(value, 6)?;
+        let meter_ids = elements(&f[1])?.iter().map(octets).collect::<Result<_, _>>()?;
+        let scripts = elements(&f[2])?.iter().map(Script::try_from).collect::<Result<_, _>>()?;
+        let execution_times = elements(&f[4])?
+            .iter()
+            .map(|t| {
+                let t = fields(t, 2)?;
+                Ok(ExecutionTime { time: octets(&t[0])?, date: octets(&t[1])? })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(ExchangeTask {
+            task_id: u32::from(long_unsigned(&f[0])?),
+            meter_ids,
+            scripts,
+            execution_type: unsigned(&f[3])?,
+            execution_times,
+            priority: long_unsigned(&f[5])?,
+        })
+    }
+}
+
 /// The data-exchange task list (§10.7, `0.0.94.7.133.255`).
 #[derive(Clone, Debug, Default)]
 pub struct ExchangeTasks {
@@ -153,6 +207,23 @@ impl ExchangeTasks {
     }
 }
 
+impl Persist for ExchangeTasks {
+    fn save(&mut self, storage: &mut dyn Storage) -> io::Result<()> {
+        let array = self.tasks.iter().map(ExchangeTask::to_structure).collect();
+        storage.save_snapshot(TASKS_KEY, &CosemDataType::Array(array))
+    }
+
+    fn load(&mut self, storage: &dyn Storage) -> io::Result<()> {
+        self.tasks = match storage.load_snapshot(TASKS_KEY)? {
+            Some(snapshot) => elements(&snapshot)
+                .and_then(|tasks| tasks.iter().map(ExchangeTask::try_from).collect())
+                .map_err(|e| invalid_data(TASKS_KEY, e))?,
+            None => Vec::new(),
+        };
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,5 +265,12 @@ mod tests {
         assert_eq!(script[0], CosemDataType::Unsigned(service_id::GET));
         // absent optional fields are null-data.
         assert_eq!(script[4], CosemDataType::Null);
+
+        // The snapshot restores the same task array.
+        let mut storage = crate::spodus::storage::MemoryStorage::new();
+        tasks.save(&mut storage).unwrap();
+        let mut restored = ExchangeTasks::new();
+        restored.load(&storage).unwrap();
+        assert_eq!(restored.build().attributes(), object.attributes());
     }
 }
@@ -0,0 +1,351 @@
+//! Meter clock synchronisation (СТО 34.01-5.1-013-2023, §10.8 and §10.10).
+//!
+//! The ИВКЭ reads each meter's Clock (`0.0.1.0.0.255`, IC 8), compares it with
+//! its own time source and, when the deviation exceeds the time-difference
+//! delta (`0.0.94.7.141.255`), corrects the meter: by `shift_time` (method 6)
+//! when the deviation fits the СПОДЭС correction size, or by writing the time
+//! attribute when the policy allows it. Corrections are limited in frequency
+//! per meter and recorded in the object-correction journal (§10.10) and the
+//! meter status table (§10.8).
+
+use crate::obis::ObisCode;
+use crate::service::action::ActionResponse;
+use crate::service::data_access_result;
+use crate::service::get::{GetDataResult, GetResponse};
+use crate::service::set::SetResponse;
+use crate::session::{ClientSession, SessionError};
+use crate::transport::DataLinkLayer;
+use crate::types::attrs::DateTime;
+use crate::types::CosemDataType;
+
+use super::node::Concentrator;
+use super::records::CorrectionRecord;
+
+/// Largest correction applied by `shift_time`, in seconds (Blue Book range of
+/// method 6).
+pub const MAX_SHIFT: u16 = 900;
+
+/// Logical name of a meter's Clock object.
+pub fn meter_clock() -> ObisCode {
+    ObisCode::new(0, 0, 1, 0, 0, 255)
+}
+
+/// Limits applied when correcting meter clocks.
+#[derive(Clone, Debug)]
+pub struct TimeSyncPolicy {
+    /// Largest deviation corrected with `shift_time`, in seconds (at most
+    /// [`MAX_SHIFT`]).
+    pub max_shift: u16,
+    /// Minimum time between two corrections of the same meter, in seconds.
+    pub min_interval: u32,
+    /// Whether deviations beyond `max_shift` may be corrected by writing the
+    /// Clock time attribute.
+    pub allow_set: bool,
+}
+
+impl Default for TimeSyncPolicy {
+    /// СПОДЭС defaults: shifts of up to 900 s, at most once a day, no time SET.
+    fn default() -> Self {
+        TimeSyncPolicy { max_shift: MAX_SHIFT, min_interval: 86_400, allow_set: false }
+    }
+}
+
+/// Result of one synchronisation attempt. Deviations are `meter - ИВКЭ`, in
+/// seconds; times are the meter's date-time octets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimeSyncOutcome {
+    /// The deviation is within the time-difference delta; nothing was done.
+    InSync {
+        /// Clock deviation.
+        deviation: i64,
+    },
+    /// The clock was corrected with `shift_time`.
+    Shifted {
+        /// Clock deviation before the correction.
+        deviation: i64,
+        /// Meter time before the correction.
+        before: Vec<u8>,
+        /// Meter time after the correction.
+        after: Vec<u8>,
+    },
+    /// The clock was corrected by writing the time attribute.
+    Set {
+        /// Clock deviation before the correction.
+        deviation: i64,
+        /// Meter time before the correction.
+        before: Vec<u8>,
+        /// Meter time after the correction.
+        after: Vec<u8>,
+    },
+    /// A correction is due but the meter was corrected too recently.
+    Deferred {
+        /// Clock deviation.
+        deviation: i64,
+    },
+    /// The deviation exceeds `max_shift` and the policy forbids a time SET.
+    OutOfRange {
+        /// Clock deviation.
+        deviation: i64,
+    },
+}
+
+/// Errors raised while synchronising a meter clock.
+#[derive(Debug)]
+pub enum TimeSyncError {
+    /// The downstream session failed.
+    Session(SessionError),
+    /// The meter refused the read or the correction with this result code.
+    Refused(u8),
+    /// The meter returned a time that is not a fully specified date-time.
+    InvalidTime(Vec<u8>),
+}
+
+impl std::fmt::Display for TimeSyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeSyncError::Session(e) => write!(f, "meter session error: {e}"),
+            TimeSyncError::Refused(code) => write!(f, "meter refused clock access (result {code})"),
+            TimeSyncError::InvalidTime(time) => write!(f, "meter time is not a valid date-time: {time:02X?}"),
+        }
+    }
+}
+
+impl std::error::Error for TimeSyncError {}
+
+impl From<SessionError> for TimeSyncError {
+    fn from(e: SessionError) -> Self {
+        TimeSyncError::Session(e)
+    }
+}
+
+/// Synchronises meter clocks against the ИВКЭ time source, remembering when
+/// each meter was last corrected.
+#[derive(Clone, Debug, Default)]
+pub struct ClockSync {
+    policy: TimeSyncPolicy,
+    last_correction: Vec<(Vec<u8>, i64)>,
+}
+
+impl ClockSync {
+    /// Creates a synchroniser with the given policy.
+    pub fn new(policy: TimeSyncPolicy) -> Self {
+        ClockSync { policy, last_correction: Vec::new() }
+    }
+
+    /// The correction policy.
+    pub fn policy(&self) -> &TimeSyncPolicy {
+        &self.policy
+    }
+
+    /// Reads `meter_id`'s clock over `session`, compares it with `now` (the
+    /// ИВКЭ time) and corrects it if the deviation exceeds the concentrator's
+    /// `time_delta`. The read meter time and `now` are stored as the meter's
+    /// `last_meter_time` / `current_time_fix`; an applied correction is also
+    /// journalled in `correction_journal` against the meter Clock OBIS, with
+    /// the meter time before and after it.
+    pub fn sync<L: DataLinkLayer>(
+        &mut self,
+        session: &mut ClientSession<L>,
+        node: &mut Concentrator,
+        meter_id: &[u8],
+        now: &DateTime,
+    ) -> Result<TimeSyncOutcome, TimeSyncError> {
+        let now_secs = now.to_unix().ok_or_else(|| TimeSyncError::InvalidTime(now.0.to_vec()))?;
+        let before = read_clock(session)?;
+        let meter_secs = before.to_unix().ok_or_else(|| TimeSyncError::InvalidTime(before.0.to_vec()))?;
+        let deviation = meter_secs - now_secs;
+
+        let status = node.meter_status.entry(meter_id);
+        status.last_meter_time = before.0.to_vec();
+        status.current_time_fix = now.0.to_vec();
+
+        if deviation.abs() <= i64::from(node.time_delta) {
+            return Ok(TimeSyncOutcome::InSync { deviation });
+        }
+        if let Some((_, last)) = self.last_correction.iter().find(|(id, _)| id == meter_id) {
+            if now_secs - last < i64::from(self.policy.min_interval) {
+                return Ok(TimeSyncOutcome::Deferred { deviation });
+            }
+        }
+
+        let max_shift = i64::from(self.policy.max_shift.min(MAX_SHIFT));
+        let (outcome, after) = if deviation.abs() <= max_shift {
+            // |deviation| <= MAX_SHIFT, so the shift fits a long.
+            #[allow(clippy::cast_possible_truncation)]
+            let shift = -deviation as i16;
+            match session.action(8, meter_clock(), 6, Some(CosemDataType::Long(shift)))? {
+                ActionResponse::Normal { result: 0, .. } => {}
+                ActionResponse::Normal { result, .. } => return Err(TimeSyncError::Refused(result)),
+                _ => return Err(TimeSyncError::Refused(data_access_result::OTHER_REASON)),
+            }
+            let after = before.shifted(-deviation).ok_or_else(|| TimeSyncError::InvalidTime(before.0.to_vec()))?;
+            (TimeSyncOutcome::Shifted { deviation, before: before.0.to_vec(), after: after.0.to_vec() }, after)
+        } else if self.policy.allow_set {
+            match session.set(8, meter_clock(), 2, now.clone().into())? {
+                SetResponse::Normal { result: data_access_result::SUCCESS, .. } => {}
+                SetResponse::Normal { result, .. } => return Err(TimeSyncError::Refused(result)),
+                _ => return Err(TimeSyncError::Refused(data_access_result::OTHER_REASON)),
+            }
+            (TimeSyncOutcome::Set { deviation, before: before.0.to_vec(), after: now.0.to_vec() }, now.clone())
+        } else {
+            return Ok(TimeSyncOutcome::OutOfRange { deviation });
+        };
+
+        match self.last_correction.iter_mut().find(|(id, _)| id == meter_id) {
+            Some(entry) => entry.1 = now_secs,
+            None => self.last_correction.push((meter_id.to_vec(), now_secs)),
+        }
+        node.correction_journal.record(CorrectionRecord {
+            object_obis: meter_clock().to_bytes(),
+            time: now.0.to_vec(),
+            old_time: before.0.to_vec(),
+            new_time: after.0.to_vec(),
+        });
+        Ok(outcome)
+    }
+}
+
+/// Reads the meter Clock time attribute (attr 2).
+fn read_clock<L: DataLinkLayer>(session: &mut ClientSession<L>) -> Result<DateTime, TimeSyncError> {
+    match session.get(8, meter_clock(), 2)? {
+        GetResponse::Normal { result: GetDataResult::Data(value), .. } => {
+            DateTime::try_from(&value).map_err(|_| TimeSyncError::InvalidTime(clock_octets(&value)))
+        }
+        GetResponse::Normal { result: GetDataResult::AccessResult(code), .. } => Err(TimeSyncError::Refused(code)),
+        _ => Err(TimeSyncError::Refused(data_access_result::OTHER_REASON)),
+    }
+}
+
+/// The octets of a time value the meter returned, for diagnosis: the
+/// octet-string itself, or the A-XDR encoding of a value of another type.
+fn clock_octets(value: &CosemDataType) -> Vec<u8> {
+    match value {
+        CosemDataType::OctetString(octets) | CosemDataType::DateTime(octets) => octets.clone(),
+        other => {
+            let mut encoded = Vec::new();
+            // An unencodable value is reported without octets.
+            let _ = other.serialize_ber(&mut encoded);
+            encoded
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::generic::GenericObject;
+    use crate::interface::InterfaceClass;
+    use crate::server::RequestDispatcher;
+    use crate::spodus::catalog;
+    use std::io;
+
+    /// A loopback link that dispatches each request to a local "meter" server.
+    struct LocalLink {
+        server: RequestDispatcher,
+        pending: Option<Vec<u8>>,
+    }
+
+    impl DataLinkLayer for LocalLink {
+        fn send_apdu(&mut self, apdu: &[u8]) -> io::Result<()> {
+            self.pending = Some(self.server.dispatch(apdu).expect("dispatch"));
+            Ok(())
+        }
+        fn receive_apdu(&mut self) -> io::Result<Vec<u8>> {
+            Ok(self.pending.take().expect("a response"))
+        }
+    }
+
+    /// A session to a meter whose clock reads `time`.
+    fn meter_session(time: &DateTime) -> ClientSession<LocalLink> {
+        let mut clock = catalog::clock();
+        clock.set_attribute(2, time.clone().into()).expect("set time");
+        let mut server = RequestDispatcher::new();
+        server.add(Box::new(clock));
+        ClientSession::new(LocalLink { server, pending: None })
+    }
+
+    /// Reads the meter clock back through the session.
+    fn meter_time(session: &mut ClientSession<LocalLink>) -> DateTime {
+        read_clock(session).expect("meter clock")
+    }
+
+    #[test]
+    fn small_deviation_is_left_alone() {
+        let now = DateTime::from_unix(1_746_117_000, 180).unwrap();
+        let mut session = meter_session(&DateTime::from_unix(1_746_117_003, 180).unwrap());
+        let mut node = Concentrator { time_delta: 5, ..Default::default() };
+        let mut sync = ClockSync::default();
+
+        let outcome = sync.sync(&mut session, &mut node, b"M1", &now).unwrap();
+        assert_eq!(outcome, TimeSyncOutcome::InSync { deviation: 3 });
+        assert_eq!(node.meter_status.find(b"M1").unwrap().current_time_fix, now.0.to_vec());
+        assert_eq!(node.correction_journal.build().attributes()[1].1, CosemDataType::Array(vec![]));
+    }
+
+    #[test]
+    fn deviation_is_shifted_and_journalled_once_per_interval() {
+        let now = DateTime::from_unix(1_746_117_000, 180).unwrap();
+        let slow = DateTime::from_unix(1_746_117_000 - 120, 180).unwrap();
+        let mut session = meter_session(&slow);
+        let mut node = Concentrator { time_delta: 5, ..Default::default() };
+        let mut sync = ClockSync::default();
+
+        let outcome = sync.sync(&mut session, &mut node, b"M1", &now).unwrap();
+        assert_eq!(
+            outcome,
+            TimeSyncOutcome::Shifted { deviation: -120, before: slow.0.to_vec(), after: now.0.to_vec() }
+        );
+        assert_eq!(meter_time(&mut session), now);
+        assert_eq!(node.meter_status.find(b"M1").unwrap().last_meter_time, slow.0.to_vec());
+        let CosemDataType::Array(rows) = &node.correction_journal.build().attributes()[1].1 else { panic!("buffer") };
+        assert_eq!(
+            rows[0],
+            CosemDataType::Structure(vec![
+                CosemDataType::OctetString(meter_clock().to_bytes()),
+                CosemDataType::DateTime(now.0.to_vec()),
+            ])
+        );
+        let record = &node.correction_journal.records()[0];
+        assert_eq!((&record.old_time, &record.new_time), (&slow.0.to_vec(), &now.0.to_vec()));
+
+        // The meter drifts again within the same day: correction is deferred.
+        let later = DateTime::from_unix(1_746_117_000 + 3600, 180).unwrap();
+        let mut session = meter_session(&DateTime::from_unix(1_746_117_000 + 3600 + 60, 180).unwrap());
+        let outcome = sync.sync(&mut session, &mut node, b"M1", &later).unwrap();
+        assert_eq!(outcome, TimeSyncOutcome::Deferred { deviation: 60 });
+    }
+
+    #[test]
+    fn large_deviation_needs_time_set() {
+        let now = DateTime::from_unix(1_746_117_000, 180).unwrap();
+        let far = DateTime::from_unix(1_746_117_000 + 3600, 180).unwrap();
+        let mut node = Concentrator { time_delta: 5, ..Default::default() };
+
+        let mut session = meter_session(&far);
+        let outcome = ClockSync::default().sync(&mut session, &mut node, b"M1", &now).unwrap();
+        assert_eq!(outcome, TimeSyncOutcome::OutOfRange { deviation: 3600 });
+        assert_eq!(meter_time(&mut session), far);
+
+        let mut sync = ClockSync::new(TimeSyncPolicy { allow_set: true, ..Default::default() });
+        let outcome = sync.sync(&mut session, &mut node, b"M1", &now).unwrap();
+        assert_eq!(outcome, TimeSyncOutcome::Set { deviation: 3600, before: far.0.to_vec(), after: now.0.to_vec() });
+        assert_eq!(meter_time(&mut session), now);
+        let record = &node.correction_journal.records()[0];
+        assert_eq!((&record.old_time, &record.new_time), (&far.0.to_vec(), &now.0.to_vec()));
+    }
+
+    #[test]
+    fn invalid_meter_time_is_reported_with_its_octets() {
+        let clock = GenericObject::new(8, 0, meter_clock()).with_attribute(
+            2,
+            CosemDataType::OctetString(vec![0x07, 0xEA, 0x0A]),
+            false,
+        );
+        let mut server = RequestDispatcher::new();
+        server.add(Box::new(clock));
+        let mut session = ClientSession::new(LocalLink { server, pending: None });
+        let now = DateTime::from_unix(1_746_117_000, 180).unwrap();
+        let error = ClockSync::default().sync(&mut session, &mut Concentrator::default(), b"M1", &now).unwrap_err();
+        assert!(matches!(error, TimeSyncError::InvalidTime(octets) if octets == [0x07, 0xEA, 0x0A]));
+    }
+}
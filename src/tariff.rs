@@ -0,0 +1,649 @@
+//! A tariff engine for the Activity calendar (class 20), evaluated together
+//! with the Special days table (class 11) and Register activation (class 6).
+//!
+//! For a given local time a [`Calendar`](crate::tariff::Calendar) resolves the
+//! active season (the one with the latest `season_start` not after it, year
+//! wildcards recurring every year), its week profile, the day profile of the
+//! weekday — replaced by the special day's `day_id` on a special day — and the
+//! day profile action in force. Before the first action of a day the last
+//! action of the previous day stays in force (IEC 62056-6-2 §4.5.5).
+//!
+//! [`TariffEngine::update`](crate::tariff::TariffEngine::update) follows the
+//! clock: it activates the passive calendar at
+//! `activate_passive_calendar_time`, and when the tariff changes runs the
+//! action's script and switches the `active_mask` of the linked Register
+//! activation. [`timeline`](crate::tariff::timeline) lists the tariff periods
+//! of a time range.
+
+use crate::classes::activity_calendar::ActivityCalendar;
+use crate::classes::register_activation::RegisterActivation;
+use crate::classes::special_days_table::SpecialDaysTable;
+use crate::interface::{AccessError, InterfaceClass};
+use crate::obis::ObisCode;
+use crate::scheduler::{date_of, execute_script, local_seconds, time_of};
+use crate::types::attrs::{DayProfile, ExecutedScript, SeasonProfile, SpecialDayEntry, WeekProfile};
+use crate::types::datetime::{wildcard, DateTime, Time};
+use crate::types::CosemDataType;
+use std::collections::BTreeSet;
+
+/// The tariff in force at an instant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveTariff {
+    /// Name of the active season profile.
+    pub season_profile_name: Vec<u8>,
+    /// Name of the week profile of that season.
+    pub week_profile_name: Vec<u8>,
+    /// The day profile of the day.
+    pub day_id: u8,
+    /// Whether the day profile comes from the Special days table.
+    pub special_day: bool,
+    /// The script of the day profile action in force.
+    pub script: ExecutedScript,
+}
+
+/// A time range with a constant tariff script, from [`timeline`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TariffPeriod {
+    /// Start of the period (inclusive).
+    pub from: DateTime,
+    /// End of the period (exclusive).
+    pub to: DateTime,
+    /// The tariff in force at the start; the profiles may change within the
+    /// period while the script stays the same.
+    pub tariff: ActiveTariff,
+}
+
+/// The season, week and day profiles of one side (active or passive) of an
+/// Activity calendar.
+#[derive(Debug, Clone, Copy)]
+pub struct Calendar<'a> {
+    /// Season profiles.
+    pub seasons: &'a [SeasonProfile],
+    /// Week profile table.
+    pub weeks: &'a [WeekProfile],
+    /// Day profile table.
+    pub days: &'a [DayProfile],
+}
+
+impl<'a> Calendar<'a> {
+    /// The active calendar of `calendar` (attributes 3..5).
+    pub fn active(calendar: &'a ActivityCalendar) -> Self {
+        Self {
+            seasons: calendar.season_profile_active(),
+            weeks: calendar.week_profile_table_active(),
+            days: calendar.day_profile_table_active(),
+        }
+    }
+
+    /// The passive calendar of `calendar` (attributes 7..9).
+    pub fn passive(calendar: &'a ActivityCalendar) -> Self {
+        Self {
+            seasons: calendar.season_profile_passive(),
+            weeks: calendar.week_profile_table_passive(),
+            days: calendar.day_profile_table_passive(),
+        }
+    }
+
+    /// The tariff in force at `instant`, or `None` when the instant is not
+    /// fully specified or the profiles do not resolve (no season has started,
+    /// a week or day profile is missing, or no action has run yet).
+    pub fn tariff_at(&self, special_days: &[SpecialDayEntry], instant: &DateTime) -> Option<ActiveTariff> {
+        self.tariff_at_local(special_days, local_seconds(instant)?)
+    }
+
+    /// [`Calendar::tariff_at`] for local seconds since the Unix epoch.
+    fn tariff_at_local(&self, special_days: &[SpecialDayEntry], local: i64) -> Option<ActiveTariff> {
+        let (season, week, day_id, special_day) = self.day_profile(special_days, local)?;
+        let day = local.div_euclid(86_400);
+        // A day with no action yet continues the last action of an earlier day.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let script = self.action(day_id, Some(local.rem_euclid(86_400) as u32)).or_else(|| {
+            (1..=7).find_map(|back| {
+                let (_, _, previous, _) = self.day_profile(special_days, (day - back) * 86_400 + 86_399)?;
+                self.action(previous, None)
+            })
+        })?;
+        Some(ActiveTariff {
+            season_profile_name: season.season_profile_name.clone(),
+            week_profile_name: week.week_profile_name.clone(),
+            day_id,
+            special_day,
+            script,
+        })
+    }
+
+    /// The season, week profile and day id in force at `local`, and whether
+    /// the day id comes from a special day.
+    fn day_profile(
+        &self,
+        special_days: &[SpecialDayEntry],
+        local: i64,
+    ) -> Option<(&'a SeasonProfile, &'a WeekProfile, u8, bool)> {
+        let season = self
+            .seasons
+            .iter()
+            .filter_map(|s| Some((season_start(&s.season_start, local)?, s)))
+            .max_by_key(|(start, _)| *start)?
+            .1;
+        let week = self.weeks.iter().find(|w| w.week_profile_name == season.week_name)?;
+        let date = DateTime::from_unix(local, 0)?.date();
+        let special = special_days.iter().find(|e| date_of(&e.specialday_date).is_some_and(|p| p.matches(&date)));
+        if let Some(special) = special {
+            return Some((season, week, special.day_id, true));
+        }
+        let day_id = match date.weekday()? {
+            1 => week.monday,
+            2 => week.tuesday,
+            3 => week.wednesday,
+            4 => week.thursday,
+            5 => week.friday,
+            6 => week.saturday,
+            _ => week.sunday,
+        };
+        Some((season, week, day_id, false))
+    }
+
+    /// The script of the latest action of day profile `day_id` starting at or
+    /// before `until` seconds of the day (any time when `None`).
+    fn action(&self, day_id: u8, until: Option<u32>) -> Option<ExecutedScript> {
+        let day = self.days.iter().find(|d| d.day_id == day_id)?;
+        day.day_schedule
+            .iter()
+            .filter_map(|a| Some((time_of(&a.start_time)?.seconds_of_day()?, a)))
+            .filter(|(start, _)| until.is_none_or(|until| *start <= until))
+            .max_by_key(|(start, _)| *start)
+            .map(|(_, a)| ExecutedScript {
+                script_logical_name: a.script_logical_name,
+                script_selector: a.script_selector,
+            })
+    }
+}
+
+/// The latest start of a season at or before `local`. A year wildcard recurs
+/// every year, unspecified time fields count as 0; month and day must be
+/// specified.
+fn season_start(bytes: &[u8], local: i64) -> Option<i64> {
+    let date = date_of(bytes)?;
+    let (month, day) = (date.month(), date.day());
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let time = bytes.get(5..).and_then(time_of);
+    let field = |f: fn(&Time) -> u8| time.as_ref().map(f).filter(|&v| v != wildcard::NOT_SPECIFIED).unwrap_or(0);
+    let (hour, minute, second) = (field(|t| t.hour()), field(|t| t.minute()), field(|t| t.second()));
+    let this_year = DateTime::from_unix(local, 0)?.date().year();
+    let years = if date.year() == wildcard::YEAR { vec![this_year, this_year - 1] } else { vec![date.year()] };
+    years
+        .into_iter()
+        .filter_map(|year| DateTime::from_ymdhms(year, month, day, hour, minute, second).to_unix())
+        .find(|&start| start <= local)
+}
+
+/// The time at which the passive calendar of `calendar` becomes active, in
+/// local seconds, when fully specified.
+fn activation_time(calendar: &ActivityCalendar) -> Option<i64> {
+    let bytes: [u8; 12] = calendar.activate_passive_calendar_time().try_into().ok()?;
+    local_seconds(&DateTime::new(bytes))
+}
+
+/// Local seconds as a date-time with the given deviation, when representable.
+fn instant(local: i64, deviation: i16) -> Option<DateTime> {
+    DateTime::from_unix(local - i64::from(deviation) * 60, deviation)
+}
+
+/// The tariff periods of `calendar` in [`from`, `to`), in `from`'s deviation.
+///
+/// The active calendar applies, and the passive one from its
+/// `activate_passive_calendar_time` on when that falls within the range.
+/// A period ends when the script changes, not when only the season, week or
+/// day profile does. Ranges where no tariff resolves are left out.
+pub fn timeline(
+    calendar: &ActivityCalendar,
+    special_days: &[SpecialDayEntry],
+    from: &DateTime,
+    to: &DateTime,
+) -> Vec<TariffPeriod> {
+    let (Some(lo), Some(hi)) = (local_seconds(from), local_seconds(to)) else {
+        return Vec::new();
+    };
+    let deviation = from.deviation().unwrap_or(0);
+    let activation = activation_time(calendar).filter(|&at| lo < at && at < hi);
+    let views = [Calendar::active(calendar), Calendar::passive(calendar)];
+
+    // Every instant the tariff may change at: midnights, action starts,
+    // season starts and the calendar activation.
+    let mut changes = BTreeSet::from([lo]);
+    changes.extend(activation);
+    for day in lo.div_euclid(86_400)..=hi.div_euclid(86_400) {
+        let midnight = day * 86_400;
+        changes.insert(midnight);
+        for view in &views {
+            let starts = view.days.iter().flat_map(|d| &d.day_schedule);
+            changes.extend(
+                starts.filter_map(|a| time_of(&a.start_time)?.seconds_of_day()).map(|s| midnight + i64::from(s)),
+            );
+            changes.extend(view.seasons.iter().filter_map(|s| season_start(&s.season_start, midnight + 86_399)));
+        }
+    }
+
+    let mut periods = Vec::new();
+    let mut open: Option<(i64, ActiveTariff)> = None;
+    for at in changes.into_iter().filter(|&at| lo <= at && at < hi) {
+        let view = if activation.is_some_and(|a| at >= a) { views[1] } else { views[0] };
+        let tariff = view.tariff_at_local(special_days, at);
+        if open.as_ref().map(|(_, t)| &t.script) == tariff.as_ref().map(|t| &t.script) {
+            continue;
+        }
+        if let Some((start, tariff)) = open.take() {
+            if let (Some(from), Some(to)) = (instant(start, deviation), instant(at, deviation)) {
+                periods.push(TariffPeriod { from, to, tariff });
+            }
+        }
+        open = tariff.map(|t| (at, t));
+    }
+    if let Some((start, tariff)) = open {
+        if let (Some(from), Some(to)) = (instant(start, deviation), instant(hi, deviation)) {
+            periods.push(TariffPeriod { from, to, tariff });
+        }
+    }
+    periods
+}
+
+/// What one [`TariffEngine::update`] did.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TariffUpdate {
+    /// Whether the passive calendar was activated.
+    pub passive_activated: bool,
+    /// The new tariff, when it changed.
+    pub switched: Option<ActiveTariff>,
+    /// The result of running the new tariff's script, when it changed.
+    pub script: Option<Result<(), AccessError>>,
+}
+
+/// Drives an Activity calendar: activates the passive calendar when due and
+/// applies tariff changes to the object set.
+///
+/// On a tariff change the day profile action's script is run in its Script
+/// table (see [`execute_script`]) and, when a Register activation is linked,
+/// its `active_mask` is set to the mask mapped to the script selector with
+/// [`TariffEngine::with_mask`], by default the selector's position in
+/// `mask_list` (selector 1 = first mask).
+///
+/// The engine remembers the latest local time it has updated to; the first
+/// update only anchors it, so an activation time already past on a restart
+/// is not applied again. A host that keeps the served time across restarts
+/// resumes with [`TariffEngine::resume_from`] and catches up on an
+/// activation that fell due while it was down.
+#[derive(Debug, Clone)]
+pub struct TariffEngine {
+    calendar: ObisCode,
+    register_activation: Option<ObisCode>,
+    masks: Vec<(u16, Vec<u8>)>,
+    current: Option<ActiveTariff>,
+    served: Option<i64>,
+}
+
+impl TariffEngine {
+    /// Creates an engine for the Activity calendar `calendar`.
+    pub fn new(calendar: ObisCode) -> Self {
+        Self { calendar, register_activation: None, masks: Vec::new(), current: None, served: None }
+    }
+
+    /// Resumes an engine that has served everything up to `last` (e.g. the
+    /// time of the last update before a power failure), so the first update
+    /// activates a passive calendar that fell due since then.
+    pub fn resume_from(mut self, last: &DateTime) -> Self {
+        self.served = local_seconds(last);
+        self
+    }
+
+    /// The latest local time updated to so far, as seconds since the Unix
+    /// epoch.
+    pub fn served(&self) -> Option<i64> {
+        self.served
+    }
+
+    /// Links the Register activation whose active mask follows the tariff.
+    pub fn with_register_activation(mut self, logical_name: ObisCode) -> Self {
+        self.register_activation = Some(logical_name);
+        self
+    }
+
+    /// Maps a tariff script selector to a Register activation mask name.
+    pub fn with_mask(mut self, script_selector: u16, mask_name: impl Into<Vec<u8>>) -> Self {
+        self.masks.push((script_selector, mask_name.into()));
+        self
+    }
+
+    /// The tariff in force at the last update.
+    pub fn current(&self) -> Option<&ActiveTariff> {
+        self.current.as_ref()
+    }
+
+    /// The tariff in force at `instant` by the calendar's active profiles and
+    /// the Special days tables in `objects`.
+    pub fn tariff_at(&self, objects: &[Box<dyn InterfaceClass>], instant: &DateTime) -> Option<ActiveTariff> {
+        let calendar = find_calendar(objects, &self.calendar)?;
+        Calendar::active(calendar).tariff_at(&special_days(objects), instant)
+    }
+
+    /// The tariff periods in [`from`, `to`) (see [`timeline`]).
+    pub fn timeline(&self, objects: &[Box<dyn InterfaceClass>], from: &DateTime, to: &DateTime) -> Vec<TariffPeriod> {
+        find_calendar(objects, &self.calendar)
+            .map(|calendar| timeline(calendar, &special_days(objects), from, to))
+            .unwrap_or_default()
+    }
+
+    /// Brings the objects up to `now`: activates the passive calendar when its
+    /// activation time has passed since the previous update, then applies the
+    /// tariff in force if its script changed. A change of profiles alone only
+    /// updates [`TariffEngine::current`].
+    ///
+    /// Fails with `object-undefined` when the calendar or the linked Register
+    /// activation is missing, and with the Register activation's error when
+    /// the mask cannot be set; the tariff is then applied again on the next
+    /// update. A `now` that is not fully specified is ignored.
+    pub fn update(
+        &mut self,
+        objects: &mut [Box<dyn InterfaceClass>],
+        now: &DateTime,
+    ) -> Result<TariffUpdate, AccessError> {
+        let mut update = TariffUpdate::default();
+        let Some(local) = local_seconds(now) else {
+            return Ok(update);
+        };
+        let undefined = || AccessError::ObjectUndefined(format!("No activity calendar {}", self.calendar));
+        let calendar = find_calendar(objects, &self.calendar).ok_or_else(undefined)?;
+        let due = activation_time(calendar).filter(|&at| at <= local && self.served.is_some_and(|served| served < at));
+        if due.is_some() {
+            let index = objects
+                .iter()
+                .position(|o| o.class_id() == 20 && *o.logical_name() == self.calendar)
+                .ok_or_else(undefined)?;
+            objects[index].invoke_method(1, None)?;
+            update.passive_activated = true;
+        }
+        self.served = Some(local);
+
+        let tariff = self.tariff_at(objects, now);
+        if tariff.as_ref().map(|t| &t.script) == self.current.as_ref().map(|t| &t.script) {
+            self.current = tariff;
+            return Ok(update);
+        }
+        let Some(tariff) = tariff else {
+            self.current = None;
+            return Ok(update);
+        };
+        update.script = Some(execute_script(objects, &tariff.script));
+        if let Some(logical_name) = &self.register_activation {
+            let target = objects
+                .iter_mut()
+                .find(|o| o.class_id() == 6 && o.logical_name() == logical_name)
+                .ok_or_else(|| AccessError::ObjectUndefined(format!("No register activation {logical_name}")))?;
+            let selector = tariff.script.script_selector;
+            let mask = match self.masks.iter().find(|(s, _)| *s == selector) {
+                Some((_, name)) => name.clone(),
+                None => target
+                    .as_any()
+                    .downcast_ref::<RegisterActivation>()
+                    .and_then(|ra| ra.mask_list().get(usize::from(selector).checked_sub(1)?))
+                    .map(|m| m.mask_name.clone())
+                    .ok_or_else(|| AccessError::ObjectUnavailable(format!("No mask for tariff script {selector}")))?,
+            };
+            target.set_attribute(4, CosemDataType::OctetString(mask))?;
+        }
+        self.current = Some(tariff.clone());
+        update.switched = Some(tariff);
+        Ok(update)
+    }
+}
+
+/// The Activity calendar `logical_name` in `objects`.
+fn find_calendar<'a>(objects: &'a [Box<dyn InterfaceClass>], logical_name: &ObisCode) -> Option<&'a ActivityCalendar> {
+    objects
+        .iter()
+        .filter(|o| o.class_id() == 20 && o.logical_name() == logical_name)
+        .find_map(|o| o.as_any().downcast_ref::<ActivityCalendar>())
+}
+
+/// The entries of every Special days table in `objects`.
+fn special_days(objects: &[Box<dyn InterfaceClass>]) -> Vec<SpecialDayEntry> {
+    objects
+        .iter()
+        .filter_map(|o| o.as_any().downcast_ref::<SpecialDaysTable>())
+        .flat_map(|table| table.entries().iter().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::activity_calendar::ActivityCalendarConfig;
+    use crate::classes::register_activation::RegisterActivationConfig;
+    use crate::classes::script_table::{ScriptTable, ScriptTableConfig};
+    use crate::classes::special_days_table::SpecialDaysTableConfig;
+    use crate::types::attrs::{DayProfileAction, RegisterActMask, Script};
+
+    fn scripts_ln() -> ObisCode {
+        ObisCode::new(0, 0, 10, 0, 100, 255)
+    }
+
+    fn calendar_ln() -> ObisCode {
+        ObisCode::new(0, 0, 13, 0, 0, 255)
+    }
+
+    fn activation_ln() -> ObisCode {
+        ObisCode::new(0, 0, 14, 0, 0, 255)
+    }
+
+    fn day(day_id: u8, actions: &[(u8, u16)]) -> DayProfile {
+        let day_schedule = actions
+            .iter()
+            .map(|&(hour, selector)| DayProfileAction {
+                start_time: vec![hour, 0, 0, 0],
+                script_logical_name: scripts_ln(),
+                script_selector: selector,
+            })
+            .collect();
+        DayProfile { day_id, day_schedule }
+    }
+
+    fn week(name: &[u8], workday: u8, weekend: u8) -> WeekProfile {
+        WeekProfile {
+            week_profile_name: name.to_vec(),
+            monday: workday,
+            tuesday: workday,
+            wednesday: workday,
+            thursday: workday,
+            friday: workday,
+            saturday: weekend,
+            sunday: weekend,
+        }
+    }
+
+    fn season(name: &[u8], month: u8, week_name: &[u8]) -> SeasonProfile {
+        SeasonProfile {
+            season_profile_name: name.to_vec(),
+            season_start: vec![0xFF, 0xFF, month, 1, 0xFF, 0, 0, 0, 0, 0x80, 0, 0xFF],
+            week_name: week_name.to_vec(),
+        }
+    }
+
+    /// Summer (April..September): T1 07:00-23:00 on workdays, T2 otherwise.
+    /// Winter: T3 all day. The passive calendar is T3 all year.
+    fn calendar(activation: Vec<u8>) -> ActivityCalendar {
+        ActivityCalendar::new(ActivityCalendarConfig {
+            logical_name: calendar_ln(),
+            calendar_name_active: b"ACT".to_vec(),
+            season_profile_active: vec![season(b"summer", 4, b"W1"), season(b"winter", 10, b"W2")],
+            week_profile_table_active: vec![week(b"W1", 1, 2), week(b"W2", 3, 3)],
+            day_profile_table_active: vec![day(1, &[(7, 1), (23, 2)]), day(2, &[(0, 2)]), day(3, &[(0, 3)])],
+            calendar_name_passive: b"PAS".to_vec(),
+            season_profile_passive: vec![season(b"all", 1, b"W3")],
+            week_profile_table_passive: vec![week(b"W3", 3, 3)],
+            day_profile_table_passive: vec![day(3, &[(0, 3)])],
+            activate_passive_calendar_time: activation,
+        })
+    }
+
+    fn at(month: u8, day: u8, hour: u8) -> DateTime {
+        DateTime::from_unix(DateTime::from_ymdhms(2024, month, day, hour, 0, 0).to_unix().unwrap(), 0).unwrap()
+    }
+
+    fn selector(tariff: Option<ActiveTariff>) -> Option<u16> {
+        tariff.map(|t| t.script.script_selector)
+    }
+
+    #[test]
+    fn resolves_season_week_and_day_profiles() {
+        let obj = calendar(vec![0xFF; 12]);
+        let cal = Calendar::active(&obj);
+        // Wednesday 1 May, 08:00: summer workday, T1.
+        let tariff = cal.tariff_at(&[], &at(5, 1, 8)).unwrap();
+        assert_eq!((tariff.season_profile_name.as_slice(), tariff.day_id), (&b"summer"[..], 1));
+        assert_eq!(tariff.script, ExecutedScript { script_logical_name: scripts_ln(), script_selector: 1 });
+        // 06:00 still runs Tuesday's 23:00 action.
+        assert_eq!(selector(cal.tariff_at(&[], &at(5, 1, 6))), Some(2));
+        // Saturday.
+        assert_eq!(selector(cal.tariff_at(&[], &at(5, 4, 10))), Some(2));
+        // Winter, including the months before April (started the year before).
+        assert_eq!(selector(cal.tariff_at(&[], &at(11, 1, 10))), Some(3));
+        assert_eq!(cal.tariff_at(&[], &at(2, 1, 10)).unwrap().season_profile_name, b"winter");
+    }
+
+    #[test]
+    fn special_days_replace_the_weekday_profile() {
+        let obj = calendar(vec![0xFF; 12]);
+        let holiday = SpecialDayEntry { index: 1, specialday_date: vec![0xFF, 0xFF, 5, 1, 0xFF], day_id: 2 };
+        let tariff = Calendar::active(&obj).tariff_at(&[holiday], &at(5, 1, 8)).unwrap();
+        assert!(tariff.special_day);
+        assert_eq!((tariff.day_id, tariff.script.script_selector), (2, 2));
+    }
+
+    #[test]
+    fn timeline_lists_tariff_periods() {
+        let obj = calendar(vec![0xFF; 12]);
+        let periods = timeline(&obj, &[], &at(5, 1, 0), &at(5, 2, 0));
+        let spans: Vec<_> = periods.iter().map(|p| (p.from.time().hour(), p.tariff.script.script_selector)).collect();
+        assert_eq!(spans, vec![(0, 2), (7, 1), (23, 2)]);
+        assert_eq!(periods[2].to, at(5, 2, 0));
+
+        // The season changes at midnight on 1 October.
+        let periods = timeline(&obj, &[], &at(9, 30, 22), &at(10, 1, 2));
+        let spans: Vec<_> = periods.iter().map(|p| (p.from.clone(), p.tariff.script.script_selector)).collect();
+        assert_eq!(spans, vec![(at(9, 30, 22), 1), (at(9, 30, 23), 2), (at(10, 1, 0), 3)]);
+    }
+
+    #[test]
+    fn a_profile_change_with_the_same_script_is_no_switch() {
+        // Sunday 5 May runs T2 all day; Monday's day profile starts at
+        // midnight but keeps T2 until 07:00.
+        let obj = calendar(vec![0xFF; 12]);
+        let periods = timeline(&obj, &[], &at(5, 5, 12), &at(5, 6, 12));
+        let spans: Vec<_> = periods.iter().map(|p| (p.from.clone(), p.tariff.day_id)).collect();
+        assert_eq!(spans, vec![(at(5, 5, 12), 2), (at(5, 6, 7), 1)]);
+
+        let scripts = (1..=3).map(|n| Script { script_identifier: n, actions: vec![] }).collect();
+        let mut objects: Vec<Box<dyn InterfaceClass>> =
+            vec![Box::new(obj), Box::new(ScriptTable::new(ScriptTableConfig { logical_name: scripts_ln(), scripts }))];
+        let mut engine = TariffEngine::new(calendar_ln());
+        assert!(engine.update(&mut objects, &at(5, 5, 12)).unwrap().switched.is_some());
+        let update = engine.update(&mut objects, &at(5, 6, 1)).unwrap();
+        assert_eq!((update.switched, update.script), (None, None));
+        assert_eq!(engine.current().map(|t| (t.day_id, t.script.script_selector)), Some((1, 2)));
+    }
+
+    #[test]
+    fn timeline_switches_to_the_passive_calendar() {
+        let obj = calendar(at(5, 1, 12).as_bytes().to_vec());
+        let periods = timeline(&obj, &[], &at(5, 1, 8), &at(5, 1, 16));
+        let spans: Vec<_> = periods.iter().map(|p| (p.from.time().hour(), p.tariff.script.script_selector)).collect();
+        assert_eq!(spans, vec![(8, 1), (12, 3)]);
+    }
+
+    #[test]
+    fn engine_switches_masks_and_activates_the_passive_calendar() {
+        let scripts = (1..=3).map(|n| Script { script_identifier: n, actions: vec![] }).collect();
+        let masks = [b"T1", b"T2", b"T3"].map(|name| RegisterActMask { mask_name: name.to_vec(), index_list: vec![1] });
+        let mut objects: Vec<Box<dyn InterfaceClass>> = vec![
+            Box::new(calendar(at(5, 2, 0).as_bytes().to_vec())),
+            Box::new(ScriptTable::new(ScriptTableConfig { logical_name: scripts_ln(), scripts })),
+            Box::new(RegisterActivation::new(RegisterActivationConfig {
+                logical_name: activation_ln(),
+                register_assignment: vec![],
+                mask_list: masks.to_vec(),
+                active_mask: vec![],
+            })),
+            Box::new(SpecialDaysTable::new(SpecialDaysTableConfig {
+                logical_name: ObisCode::new(0, 0, 11, 0, 0, 255),
+                entries: vec![],
+            })),
+        ];
+        let mut engine = TariffEngine::new(calendar_ln()).with_register_activation(activation_ln());
+        let active_mask = |objects: &[Box<dyn InterfaceClass>]| objects[2].get_attribute(4);
+
+        let update = engine.update(&mut objects, &at(5, 1, 8)).unwrap();
+        assert_eq!((update.passive_activated, update.script), (false, Some(Ok(()))));
+        assert_eq!(selector(update.switched), Some(1));
+        assert_eq!(active_mask(&objects), Some(CosemDataType::OctetString(b"T1".to_vec())));
+
+        assert_eq!(engine.update(&mut objects, &at(5, 1, 9)).unwrap(), TariffUpdate::default());
+
+        engine.update(&mut objects, &at(5, 1, 23)).unwrap();
+        assert_eq!(active_mask(&objects), Some(CosemDataType::OctetString(b"T2".to_vec())));
+
+        let update = engine.update(&mut objects, &at(5, 2, 1)).unwrap();
+        assert!(update.passive_activated);
+        assert_eq!(selector(update.switched), Some(3));
+        assert_eq!(objects[0].get_attribute(2), Some(CosemDataType::OctetString(b"PAS".to_vec())));
+        assert_eq!(active_mask(&objects), Some(CosemDataType::OctetString(b"T3".to_vec())));
+        assert!(!engine.update(&mut objects, &at(5, 2, 2)).unwrap().passive_activated);
+    }
+
+    #[test]
+    fn restarted_engine_does_not_reactivate_a_past_calendar() {
+        let mut objects: Vec<Box<dyn InterfaceClass>> = vec![Box::new(calendar(at(5, 2, 0).as_bytes().to_vec()))];
+        let name = |objects: &[Box<dyn InterfaceClass>]| objects[0].get_attribute(2);
+        let mut engine = TariffEngine::new(calendar_ln());
+        engine.update(&mut objects, &at(5, 1, 23)).unwrap();
+        assert!(engine.update(&mut objects, &at(5, 2, 1)).unwrap().passive_activated);
+
+        // A new passive calendar is loaded, its activation time not yet set.
+        objects[0].set_attribute(6, CosemDataType::OctetString(b"NEW".to_vec())).unwrap();
+        let mut restarted = TariffEngine::new(calendar_ln());
+        assert!(!restarted.update(&mut objects, &at(5, 2, 2)).unwrap().passive_activated);
+        assert!(!restarted.update(&mut objects, &at(5, 2, 3)).unwrap().passive_activated);
+        assert_eq!(name(&objects), Some(CosemDataType::OctetString(b"PAS".to_vec())));
+
+        // Resumed from before the activation time, it catches up.
+        let mut objects: Vec<Box<dyn InterfaceClass>> = vec![Box::new(calendar(at(5, 2, 0).as_bytes().to_vec()))];
+        let mut resumed = TariffEngine::new(calendar_ln()).resume_from(&at(5, 1, 23));
+        assert!(resumed.update(&mut objects, &at(5, 2, 3)).unwrap().passive_activated);
+        assert_eq!(name(&objects), Some(CosemDataType::OctetString(b"PAS".to_vec())));
+        assert_eq!(resumed.served(), local_seconds(&at(5, 2, 3)));
+    }
+
+    #[test]
+    fn engine_uses_explicit_mask_names() {
+        let masks = vec![RegisterActMask { mask_name: b"day".to_vec(), index_list: vec![1] }];
+        let mut objects: Vec<Box<dyn InterfaceClass>> = vec![
+            Box::new(calendar(vec![0xFF; 12])),
+            Box::new(RegisterActivation::new(RegisterActivationConfig {
+                logical_name: activation_ln(),
+                register_assignment: vec![],
+                mask_list: masks,
+                active_mask: vec![],
+            })),
+        ];
+        let mut engine = TariffEngine::new(calendar_ln()).with_register_activation(activation_ln()).with_mask(1, "day");
+        let update = engine.update(&mut objects, &at(5, 1, 8)).unwrap();
+        // No script table: the script fails but the mask still switches.
+        assert!(matches!(update.script, Some(Err(AccessError::ObjectUndefined(_)))));
+        assert_eq!(objects[1].get_attribute(4), Some(CosemDataType::OctetString(b"day".to_vec())));
+        assert_eq!(engine.current().map(|t| t.day_id), Some(1));
+
+        let missing = engine.update(&mut objects, &at(5, 1, 23));
+        assert!(matches!(missing, Err(AccessError::ObjectUnavailable(_))));
+        assert_eq!(engine.current().map(|t| t.script.script_selector), Some(1));
+    }
+}
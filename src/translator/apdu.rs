@@ -0,0 +1,1096 @@
+//! Translation of encoded xDLMS APDUs to and from the element tree.
+//!
+//! Every APDU is decoded with its [`crate::service`] type and mapped field by
+//! field; translating back builds the same type and encodes it. Scalars are
+//! written as big-endian hex, flags as `true`/`false`, and embedded data as
+//! the element of the value (see [`super::data`]).
+
+use super::{data, Element, TranslateError};
+use crate::service::acse::{self, AssociationRequest, AssociationResponse, ReleaseRequest};
+use crate::service::action::{ActionRequest, ActionResponse};
+use crate::service::ciphering::{ded, glo};
+use crate::service::error::{ConfirmedServiceError, ExceptionResponse};
+use crate::service::gbt::{GeneralBlockTransfer, GENERAL_BLOCK_TRANSFER};
+use crate::service::general_ciphering::{
+    GeneralCiphering, GeneralGloDedCiphering, GeneralSigning, KeyInfo, GENERAL_CIPHERING_TAG,
+    GENERAL_DED_CIPHERING_TAG, GENERAL_GLO_CIPHERING_TAG, GENERAL_SIGNING_TAG,
+};
+use crate::service::get::{AccessSelection, GetDataResult, GetRequest, GetResponse};
+use crate::service::initiate::{InitiateRequest, InitiateResponse, INITIATE_REQUEST_TAG, INITIATE_RESPONSE_TAG};
+use crate::service::notification::{DataNotification, EventNotificationRequest};
+use crate::service::set::{SetRequest, SetResponse};
+use crate::service::{tag, AttributeDescriptor, DataBlockSa, MethodDescriptor, RawApdu};
+use crate::types::CosemDataType;
+
+/// Element names of the service-specific ciphered APDUs.
+const CIPHERED: [(u8, &str); 15] = [
+    (glo::INITIATE_REQUEST, "glo_InitiateRequest"),
+    (glo::INITIATE_RESPONSE, "glo_InitiateResponse"),
+    (glo::GET_REQUEST, "glo_GetRequest"),
+    (glo::SET_REQUEST, "glo_SetRequest"),
+    (glo::EVENT_NOTIFICATION_REQUEST, "glo_EventNotificationRequest"),
+    (glo::ACTION_REQUEST, "glo_ActionRequest"),
+    (glo::GET_RESPONSE, "glo_GetResponse"),
+    (glo::SET_RESPONSE, "glo_SetResponse"),
+    (glo::ACTION_RESPONSE, "glo_ActionResponse"),
+    (ded::GET_REQUEST, "ded_GetRequest"),
+    (ded::SET_REQUEST, "ded_SetRequest"),
+    (ded::ACTION_REQUEST, "ded_ActionRequest"),
+    (ded::GET_RESPONSE, "ded_GetResponse"),
+    (ded::SET_RESPONSE, "ded_SetResponse"),
+    (ded::ACTION_RESPONSE, "ded_ActionResponse"),
+];
+
+/// The element of an encoded APDU; an APDU that does not translate back to
+/// the same octets is kept whole as `RawApdu`.
+pub(super) fn to_element(apdu: &[u8]) -> Element {
+    typed(apdu)
+        .filter(|element| from_element(element).ok().as_deref() == Some(apdu))
+        .unwrap_or_else(|| Element::hex("RawApdu", apdu))
+}
+
+fn typed(apdu: &[u8]) -> Option<Element> {
+    let &apdu_tag = apdu.first()?;
+    Some(match apdu_tag {
+        tag::GET_REQUEST => get_request(&GetRequest::decode(apdu).ok()?),
+        tag::GET_RESPONSE => get_response(&GetResponse::decode(apdu).ok()?),
+        tag::SET_REQUEST => set_request(&SetRequest::decode(apdu).ok()?),
+        tag::SET_RESPONSE => set_response(&SetResponse::decode(apdu).ok()?),
+        tag::ACTION_REQUEST => action_request(&ActionRequest::decode(apdu).ok()?),
+        tag::ACTION_RESPONSE => action_response(&ActionResponse::decode(apdu).ok()?),
+        tag::EVENT_NOTIFICATION_REQUEST => event_notification(&EventNotificationRequest::decode(apdu).ok()?),
+        tag::DATA_NOTIFICATION => data_notification(&DataNotification::decode(apdu).ok()?),
+        tag::EXCEPTION_RESPONSE => {
+            let e = ExceptionResponse::decode(apdu).ok()?;
+            Element::node(
+                "ExceptionResponse",
+                vec![Element::hex("StateError", &[e.state_error]), Element::hex("ServiceError", &[e.service_error])],
+            )
+        }
+        tag::CONFIRMED_SERVICE_ERROR => {
+            let e = ConfirmedServiceError::decode(apdu).ok()?;
+            Element::node(
+                "ConfirmedServiceError",
+                vec![
+                    Element::hex("Service", &[e.service]),
+                    Element::hex("Category", &[e.category]),
+                    Element::hex("ErrorValue", &[e.value]),
+                ],
+            )
+        }
+        acse::AARQ_TAG => association_request(&AssociationRequest::decode(apdu).ok()?),
+        acse::AARE_TAG => association_response(&AssociationResponse::decode(apdu).ok()?),
+        acse::RLRQ_TAG => release("ReleaseRequest", &ReleaseRequest::decode_rlrq(apdu).ok()?),
+        acse::RLRE_TAG => release("ReleaseResponse", &ReleaseRequest::decode_rlre(apdu).ok()?),
+        INITIATE_REQUEST_TAG => initiate_request(&InitiateRequest::decode(apdu).ok()?),
+        INITIATE_RESPONSE_TAG => initiate_response(&InitiateResponse::decode(apdu).ok()?),
+        GENERAL_GLO_CIPHERING_TAG | GENERAL_DED_CIPHERING_TAG => {
+            let g = GeneralGloDedCiphering::decode(apdu).ok()?;
+            let name = if g.dedicated { "GeneralDedCiphering" } else { "GeneralGloCiphering" };
+            Element::node(
+                name,
+                vec![
+                    Element::hex("SystemTitle", &g.system_title),
+                    Element::hex("CipheredContent", &g.ciphered_service),
+                ],
+            )
+        }
+        GENERAL_CIPHERING_TAG => general_ciphering(&GeneralCiphering::decode(apdu).ok()?),
+        GENERAL_SIGNING_TAG => general_signing(&GeneralSigning::decode(apdu).ok()?),
+        GENERAL_BLOCK_TRANSFER => general_block_transfer(&GeneralBlockTransfer::decode(apdu).ok()?),
+        _ => {
+            let &(_, name) = CIPHERED.iter().find(|(t, _)| *t == apdu_tag)?;
+            let raw = RawApdu::from_bytes(apdu).ok()?;
+            let body = raw.body();
+            if body.len() < 5 {
+                return None;
+            }
+            Element::node(
+                name,
+                vec![
+                    Element::hex("SecurityControl", &body[..1]),
+                    Element::hex("InvocationCounter", &body[1..5]),
+                    Element::hex("CipheredContent", &body[5..]),
+                ],
+            )
+        }
+    })
+}
+
+/// Encodes the APDU described by `element`.
+pub(super) fn from_element(element: &Element) -> Result<Vec<u8>, TranslateError> {
+    let e = element;
+    match e.name.as_str() {
+        "RawApdu" => e.octets(),
+        "GetRequest" => get_request_from(e),
+        "GetResponse" => get_response_from(e),
+        "SetRequest" => set_request_from(e),
+        "SetResponse" => set_response_from(e),
+        "ActionRequest" => action_request_from(e),
+        "ActionResponse" => action_response_from(e),
+        "EventNotificationRequest" => Ok(EventNotificationRequest {
+            time: opt_octets(e, "Time")?,
+            attribute: attribute_of(e)?,
+            value: data_of(e, "AttributeValue")?,
+        }
+        .encode()?),
+        "DataNotification" => Ok(DataNotification {
+            long_invoke_id_and_priority: u32::from_be_bytes(e.child("LongInvokeIdAndPriority")?.fixed()?),
+            date_time: e.child("DateTime")?.octets()?,
+            notification_body: data_of(e, "NotificationBody")?,
+        }
+        .encode()?),
+        "ExceptionResponse" => {
+            Ok(ExceptionResponse { state_error: byte(e, "StateError")?, service_error: byte(e, "ServiceError")? }
+                .encode())
+        }
+        "ConfirmedServiceError" => Ok(ConfirmedServiceError {
+            service: byte(e, "Service")?,
+            category: byte(e, "Category")?,
+            value: byte(e, "ErrorValue")?,
+        }
+        .encode()),
+        "AssociationRequest" => association_request_from(e),
+        "AssociationResponse" => association_response_from(e),
+        "ReleaseRequest" => Ok(release_from(e)?.encode_rlrq()),
+        "ReleaseResponse" => Ok(release_from(e)?.encode_rlre()),
+        "InitiateRequest" => initiate_request_from(e),
+        "InitiateResponse" => initiate_response_from(e),
+        "GeneralGloCiphering" | "GeneralDedCiphering" => Ok(GeneralGloDedCiphering {
+            dedicated: e.name == "GeneralDedCiphering",
+            system_title: e.child("SystemTitle")?.octets()?,
+            ciphered_service: e.child("CipheredContent")?.octets()?,
+        }
+        .encode()),
+        "GeneralCiphering" => general_ciphering_from(e),
+        "GeneralSigning" => general_signing_from(e),
+        "GeneralBlockTransfer" => general_block_transfer_from(e),
+        name => {
+            let &(apdu_tag, _) = CIPHERED.iter().find(|(_, n)| *n == name).ok_or_else(|| e.unexpected())?;
+            let mut body = e.child("SecurityControl")?.fixed::<1>()?.to_vec();
+            body.extend_from_slice(&e.child("InvocationCounter")?.fixed::<4>()?);
+            body.extend_from_slice(&e.child("CipheredContent")?.octets()?);
+            Ok(RawApdu::new(apdu_tag, body).encode())
+        }
+    }
+}
+
+// Field helpers.
+
+fn byte(e: &Element, name: &str) -> Result<u8, TranslateError> {
+    let [b] = e.child(name)?.fixed()?;
+    Ok(b)
+}
+
+fn opt_byte(e: &Element, name: &str) -> Result<Option<u8>, TranslateError> {
+    e.find(name).map(|c| c.fixed().map(|[b]| b)).transpose()
+}
+
+fn opt_octets(e: &Element, name: &str) -> Result<Option<Vec<u8>>, TranslateError> {
+    e.find(name).map(Element::octets).transpose()
+}
+
+fn opt_hex(name: &str, value: Option<&[u8]>) -> Option<Element> {
+    value.map(|v| Element::hex(name, v))
+}
+
+fn flag(name: &str, value: bool) -> Element {
+    Element::leaf(name, value.to_string())
+}
+
+fn invoke_id(value: u8) -> Element {
+    Element::hex("InvokeIdAndPriority", &[value])
+}
+
+fn invoke_id_of(e: &Element) -> Result<u8, TranslateError> {
+    byte(e, "InvokeIdAndPriority")
+}
+
+fn block_number(value: u32) -> Element {
+    Element::hex("BlockNumber", &value.to_be_bytes())
+}
+
+fn block_number_of(e: &Element) -> Result<u32, TranslateError> {
+    Ok(u32::from_be_bytes(e.child("BlockNumber")?.fixed()?))
+}
+
+/// A wrapper element around one data value.
+fn data_node(name: &str, value: &CosemDataType) -> Element {
+    Element::node(name, vec![data::to_element(value)])
+}
+
+fn data_of(e: &Element, name: &str) -> Result<CosemDataType, TranslateError> {
+    data::from_element(e.child(name)?.only_child()?)
+}
+
+fn data_list(name: &str, values: &[CosemDataType]) -> Element {
+    Element::list(name, values.iter().map(data::to_element).collect())
+}
+
+fn data_list_of(e: &Element, name: &str) -> Result<Vec<CosemDataType>, TranslateError> {
+    e.child(name)?.children.iter().map(data::from_element).collect()
+}
+
+/// The three fields of a 9-octet attribute or method descriptor.
+fn descriptor(name: &str, member: &str, encoded: &[u8]) -> Element {
+    Element::node(
+        name,
+        vec![
+            Element::hex("ClassId", &encoded[..2]),
+            Element::hex("InstanceId", &encoded[2..8]),
+            Element::hex(member, &encoded[8..]),
+        ],
+    )
+}
+
+fn descriptor_octets(e: &Element, member: &str) -> Result<Vec<u8>, TranslateError> {
+    let mut encoded = e.child("ClassId")?.fixed::<2>()?.to_vec();
+    encoded.extend_from_slice(&e.child("InstanceId")?.fixed::<6>()?);
+    encoded.extend_from_slice(&e.child(member)?.fixed::<1>()?);
+    Ok(encoded)
+}
+
+fn attribute(value: &AttributeDescriptor) -> Element {
+    let mut encoded = Vec::new();
+    value.encode(&mut encoded);
+    descriptor("AttributeDescriptor", "AttributeId", &encoded)
+}
+
+fn attribute_of(e: &Element) -> Result<AttributeDescriptor, TranslateError> {
+    attribute_from(e.child("AttributeDescriptor")?)
+}
+
+fn attribute_from(e: &Element) -> Result<AttributeDescriptor, TranslateError> {
+    Ok(AttributeDescriptor::decode(&descriptor_octets(e, "AttributeId")?)?.0)
+}
+
+fn method(value: &MethodDescriptor) -> Element {
+    let mut encoded = Vec::new();
+    value.encode(&mut encoded);
+    descriptor("MethodDescriptor", "MethodId", &encoded)
+}
+
+fn method_from(e: &Element) -> Result<MethodDescriptor, TranslateError> {
+    Ok(MethodDescriptor::decode(&descriptor_octets(e, "MethodId")?)?.0)
+}
+
+fn selection(value: &Option<AccessSelection>) -> Option<Element> {
+    value.as_ref().map(|s| {
+        Element::node(
+            "AccessSelection",
+            vec![Element::hex("AccessSelector", &[s.selector]), data_node("AccessParameters", &s.parameters)],
+        )
+    })
+}
+
+fn selection_of(e: &Element) -> Result<Option<AccessSelection>, TranslateError> {
+    e.find("AccessSelection")
+        .map(|s| {
+            Ok(AccessSelection { selector: byte(s, "AccessSelector")?, parameters: data_of(s, "AccessParameters")? })
+        })
+        .transpose()
+}
+
+fn attribute_list(attributes: &[(AttributeDescriptor, Option<AccessSelection>)]) -> Element {
+    Element::list(
+        "AttributeDescriptorList",
+        attributes
+            .iter()
+            .map(|(a, s)| {
+                let mut children = vec![attribute(a)];
+                children.extend(selection(s));
+                Element::node("_AttributeDescriptorWithSelection", children)
+            })
+            .collect(),
+    )
+}
+
+fn attribute_list_of(e: &Element) -> Result<Vec<(AttributeDescriptor, Option<AccessSelection>)>, TranslateError> {
+    e.child("AttributeDescriptorList")?.children.iter().map(|c| Ok((attribute_of(c)?, selection_of(c)?))).collect()
+}
+
+fn data_block(name: &str, block: &DataBlockSa) -> Element {
+    Element::node(
+        name,
+        vec![
+            flag("LastBlock", block.last_block),
+            block_number(block.block_number),
+            Element::hex("RawData", &block.raw_data),
+        ],
+    )
+}
+
+fn data_block_of(e: &Element, name: &str) -> Result<DataBlockSa, TranslateError> {
+    let b = e.child(name)?;
+    Ok(DataBlockSa {
+        last_block: b.child("LastBlock")?.flag()?,
+        block_number: block_number_of(b)?,
+        raw_data: b.child("RawData")?.octets()?,
+    })
+}
+
+fn data_result(result: &GetDataResult) -> Element {
+    match result {
+        GetDataResult::Data(value) => data_node("Data", value),
+        GetDataResult::AccessResult(code) => Element::hex("DataAccessResult", &[*code]),
+    }
+}
+
+fn data_result_from(e: &Element) -> Result<GetDataResult, TranslateError> {
+    match e.name.as_str() {
+        "Data" => Ok(GetDataResult::Data(data::from_element(e.only_child()?)?)),
+        "DataAccessResult" => Ok(GetDataResult::AccessResult(u8::from_be_bytes(e.fixed()?))),
+        _ => Err(e.unexpected()),
+    }
+}
+
+/// The element wrapping one APDU (`UserInformation`, for instance).
+fn nested(name: &str, apdu: &[u8]) -> Element {
+    Element::node(name, vec![to_element(apdu)])
+}
+
+fn nested_of(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    from_element(e.only_child()?)
+}
+
+// GET.
+
+fn get_request(request: &GetRequest) -> Element {
+    let variant = match request {
+        GetRequest::Normal { invoke_id_and_priority, attribute: a, access_selection } => {
+            let mut children = vec![invoke_id(*invoke_id_and_priority), attribute(a)];
+            children.extend(selection(access_selection));
+            Element::node("GetRequestNormal", children)
+        }
+        GetRequest::Next { invoke_id_and_priority, block_number: n } => {
+            Element::node("GetRequestNext", vec![invoke_id(*invoke_id_and_priority), block_number(*n)])
+        }
+        GetRequest::WithList { invoke_id_and_priority, attributes } => {
+            Element::node("GetRequestWithList", vec![invoke_id(*invoke_id_and_priority), attribute_list(attributes)])
+        }
+    };
+    Element::node("GetRequest", vec![variant])
+}
+
+fn get_request_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    let v = e.only_child()?;
+    let invoke_id_and_priority = invoke_id_of(v)?;
+    let request = match v.name.as_str() {
+        "GetRequestNormal" => GetRequest::Normal {
+            invoke_id_and_priority,
+            attribute: attribute_of(v)?,
+            access_selection: selection_of(v)?,
+        },
+        "GetRequestNext" => GetRequest::Next { invoke_id_and_priority, block_number: block_number_of(v)? },
+        "GetRequestWithList" => GetRequest::WithList { invoke_id_and_priority, attributes: attribute_list_of(v)? },
+        _ => return Err(v.unexpected()),
+    };
+    Ok(request.encode()?)
+}
+
+fn get_response(response: &GetResponse) -> Element {
+    let variant = match response {
+        GetResponse::Normal { invoke_id_and_priority, result } => Element::node(
+            "GetResponseNormal",
+            vec![invoke_id(*invoke_id_and_priority), Element::node("Result", vec![data_result(result)])],
+        ),
+        GetResponse::WithDataBlock { invoke_id_and_priority, last_block, block_number: n, raw_data } => {
+            let data = match raw_data {
+                Ok(raw) => Element::hex("RawData", raw),
+                Err(code) => Element::hex("DataAccessResult", &[*code]),
+            };
+            Element::node(
+                "GetResponseWithDataBlock",
+                vec![invoke_id(*invoke_id_and_priority), flag("LastBlock", *last_block), block_number(*n), data],
+            )
+        }
+        GetResponse::WithList { invoke_id_and_priority, results } => Element::node(
+            "GetResponseWithList",
+            vec![
+                invoke_id(*invoke_id_and_priority),
+                Element::list("Result", results.iter().map(data_result).collect()),
+            ],
+        ),
+    };
+    Element::node("GetResponse", vec![variant])
+}
+
+fn get_response_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    let v = e.only_child()?;
+    let invoke_id_and_priority = invoke_id_of(v)?;
+    let response = match v.name.as_str() {
+        "GetResponseNormal" => {
+            GetResponse::Normal { invoke_id_and_priority, result: data_result_from(v.child("Result")?.only_child()?)? }
+        }
+        "GetResponseWithDataBlock" => GetResponse::WithDataBlock {
+            invoke_id_and_priority,
+            last_block: v.child("LastBlock")?.flag()?,
+            block_number: block_number_of(v)?,
+            raw_data: match v.find("RawData") {
+                Some(raw) => Ok(raw.octets()?),
+                None => Err(byte(v, "DataAccessResult")?),
+            },
+        },
+        "GetResponseWithList" => GetResponse::WithList {
+            invoke_id_and_priority,
+            results: v.child("Result")?.children.iter().map(data_result_from).collect::<Result<_, _>>()?,
+        },
+        _ => return Err(v.unexpected()),
+    };
+    Ok(response.encode()?)
+}
+
+// SET.
+
+fn set_request(request: &SetRequest) -> Element {
+    let variant = match request {
+        SetRequest::Normal { invoke_id_and_priority, attribute: a, access_selection, value } => {
+            let mut children = vec![invoke_id(*invoke_id_and_priority), attribute(a)];
+            children.extend(selection(access_selection));
+            children.push(data_node("Value", value));
+            Element::node("SetRequestNormal", children)
+        }
+        SetRequest::WithFirstDatablock { invoke_id_and_priority, attribute: a, access_selection, datablock } => {
+            let mut children = vec![invoke_id(*invoke_id_and_priority), attribute(a)];
+            children.extend(selection(access_selection));
+            children.push(data_block("DataBlock", datablock));
+            Element::node("SetRequestWithFirstDataBlock", children)
+        }
+        SetRequest::WithDatablock { invoke_id_and_priority, datablock } => Element::node(
+            "SetRequestWithDataBlock",
+            vec![invoke_id(*invoke_id_and_priority), data_block("DataBlock", datablock)],
+        ),
+        SetRequest::WithList { invoke_id_and_priority, attributes, values } => Element::node(
+            "SetRequestWithList",
+            vec![invoke_id(*invoke_id_and_priority), attribute_list(attributes), data_list("ValueList", values)],
+        ),
+    };
+    Element::node("SetRequest", vec![variant])
+}
+
+fn set_request_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    let v = e.only_child()?;
+    let invoke_id_and_priority = invoke_id_of(v)?;
+    let request = match v.name.as_str() {
+        "SetRequestNormal" => SetRequest::Normal {
+            invoke_id_and_priority,
+            attribute: attribute_of(v)?,
+            access_selection: selection_of(v)?,
+            value: data_of(v, "Value")?,
+        },
+        "SetRequestWithFirstDataBlock" => SetRequest::WithFirstDatablock {
+            invoke_id_and_priority,
+            attribute: attribute_of(v)?,
+            access_selection: selection_of(v)?,
+            datablock: data_block_of(v, "DataBlock")?,
+        },
+        "SetRequestWithDataBlock" => {
+            SetRequest::WithDatablock { invoke_id_and_priority, datablock: data_block_of(v, "DataBlock")? }
+        }
+        "SetRequestWithList" => SetRequest::WithList {
+            invoke_id_and_priority,
+            attributes: attribute_list_of(v)?,
+            values: data_list_of(v, "ValueList")?,
+        },
+        _ => return Err(v.unexpected()),
+    };
+    Ok(request.encode()?)
+}
+
+fn set_response(response: &SetResponse) -> Element {
+    let variant = match response {
+        SetResponse::Normal { invoke_id_and_priority, result } => Element::node(
+            "SetResponseNormal",
+            vec![invoke_id(*invoke_id_and_priority), Element::hex("Result", &[*result])],
+        ),
+        SetResponse::Datablock { invoke_id_and_priority, block_number: n } => {
+            Element::node("SetResponseDataBlock", vec![invoke_id(*invoke_id_and_priority), block_number(*n)])
+        }
+        SetResponse::LastDatablock { invoke_id_and_priority, result, block_number: n } => Element::node(
+            "SetResponseLastDataBlock",
+            vec![invoke_id(*invoke_id_and_priority), Element::hex("Result", &[*result]), block_number(*n)],
+        ),
+        SetResponse::WithList { invoke_id_and_priority, results } => Element::node(
+            "SetResponseWithList",
+            vec![
+                invoke_id(*invoke_id_and_priority),
+                Element::list("Result", results.iter().map(|r| Element::hex("DataAccessResult", &[*r])).collect()),
+            ],
+        ),
+    };
+    Element::node("SetResponse", vec![variant])
+}
+
+fn set_response_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    let v = e.only_child()?;
+    let invoke_id_and_priority = invoke_id_of(v)?;
+    let response = match v.name.as_str() {
+        "SetResponseNormal" => SetResponse::Normal { invoke_id_and_priority, result: byte(v, "Result")? },
+        "SetResponseDataBlock" => SetResponse::Datablock { invoke_id_and_priority, block_number: block_number_of(v)? },
+        "SetResponseLastDataBlock" => SetResponse::LastDatablock {
+            invoke_id_and_priority,
+            result: byte(v, "Result")?,
+            block_number: block_number_of(v)?,
+        },
+        "SetResponseWithList" => SetResponse::WithList {
+            invoke_id_and_priority,
+            results: v.child("Result")?.children.iter().map(|r| r.fixed().map(|[b]| b)).collect::<Result<_, _>>()?,
+        },
+        _ => return Err(v.unexpected()),
+    };
+    Ok(response.encode())
+}
+
+// ACTION.
+
+fn action_request(request: &ActionRequest) -> Element {
+    let variant = match request {
+        ActionRequest::Normal { invoke_id_and_priority, method: m, parameters } => {
+            let mut children = vec![invoke_id(*invoke_id_and_priority), method(m)];
+            children.extend(parameters.as_ref().map(|p| data_node("MethodInvocationParameters", p)));
+            Element::node("ActionRequestNormal", children)
+        }
+        ActionRequest::NextPblock { invoke_id_and_priority, block_number: n } => {
+            Element::node("ActionRequestNextPBlock", vec![invoke_id(*invoke_id_and_priority), block_number(*n)])
+        }
+        ActionRequest::WithFirstPblock { invoke_id_and_priority, method: m, datablock } => Element::node(
+            "ActionRequestWithFirstPBlock",
+            vec![invoke_id(*invoke_id_and_priority), method(m), data_block("PBlock", datablock)],
+        ),
+        ActionRequest::WithPblock { invoke_id_and_priority, datablock } => Element::node(
+            "ActionRequestWithPBlock",
+            vec![invoke_id(*invoke_id_and_priority), data_block("PBlock", datablock)],
+        ),
+        ActionRequest::WithList { invoke_id_and_priority, methods, parameters } => Element::node(
+            "ActionRequestWithList",
+            vec![
+                invoke_id(*invoke_id_and_priority),
+                Element::list("MethodDescriptorList", methods.iter().map(method).collect()),
+                data_list("MethodInvocationParameters", parameters),
+            ],
+        ),
+    };
+    Element::node("ActionRequest", vec![variant])
+}
+
+fn action_request_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    let v = e.only_child()?;
+    let invoke_id_and_priority = invoke_id_of(v)?;
+    let request = match v.name.as_str() {
+        "ActionRequestNormal" => ActionRequest::Normal {
+            invoke_id_and_priority,
+            method: method_from(v.child("MethodDescriptor")?)?,
+            parameters: match v.find("MethodInvocationParameters") {
+                Some(p) => Some(data::from_element(p.only_child()?)?),
+                None => None,
+            },
+        },
+        "ActionRequestNextPBlock" => {
+            ActionRequest::NextPblock { invoke_id_and_priority, block_number: block_number_of(v)? }
+        }
+        "ActionRequestWithFirstPBlock" => ActionRequest::WithFirstPblock {
+            invoke_id_and_priority,
+            method: method_from(v.child("MethodDescriptor")?)?,
+            datablock: data_block_of(v, "PBlock")?,
+        },
+        "ActionRequestWithPBlock" => {
+            ActionRequest::WithPblock { invoke_id_and_priority, datablock: data_block_of(v, "PBlock")? }
+        }
+        "ActionRequestWithList" => ActionRequest::WithList {
+            invoke_id_and_priority,
+            methods: v.child("MethodDescriptorList")?.children.iter().map(method_from).collect::<Result<_, _>>()?,
+            parameters: data_list_of(v, "MethodInvocationParameters")?,
+        },
+        _ => return Err(v.unexpected()),
+    };
+    Ok(request.encode()?)
+}
+
+/// An action-result with its optional return parameters.
+fn action_result(name: &str, result: u8, return_parameters: Option<&GetDataResult>) -> Element {
+    let mut children = vec![Element::hex("Result", &[result])];
+    children.extend(return_parameters.map(|r| Element::node("ReturnParameters", vec![data_result(r)])));
+    Element::node(name, children)
+}
+
+fn action_result_from(e: &Element) -> Result<(u8, Option<GetDataResult>), TranslateError> {
+    let return_parameters = match e.find("ReturnParameters") {
+        Some(r) => Some(data_result_from(r.only_child()?)?),
+        None => None,
+    };
+    Ok((byte(e, "Result")?, return_parameters))
+}
+
+fn action_response(response: &ActionResponse) -> Element {
+    let variant = match response {
+        ActionResponse::Normal { invoke_id_and_priority, result, return_parameters } => {
+            let mut element = action_result("ActionResponseNormal", *result, return_parameters.as_ref());
+            element.children.insert(0, invoke_id(*invoke_id_and_priority));
+            element
+        }
+        ActionResponse::WithPblock { invoke_id_and_priority, datablock } => Element::node(
+            "ActionResponseWithPBlock",
+            vec![invoke_id(*invoke_id_and_priority), data_block("PBlock", datablock)],
+        ),
+        ActionResponse::NextPblock { invoke_id_and_priority, block_number: n } => {
+            Element::node("ActionResponseNextPBlock", vec![invoke_id(*invoke_id_and_priority), block_number(*n)])
+        }
+        ActionResponse::WithList { invoke_id_and_priority, results } => Element::node(
+            "ActionResponseWithList",
+            vec![
+                invoke_id(*invoke_id_and_priority),
+                Element::list(
+                    "ResultList",
+                    results
+                        .iter()
+                        .map(|(r, p)| action_result("_ActionResponseWithOptionalData", *r, p.as_ref()))
+                        .collect(),
+                ),
+            ],
+        ),
+    };
+    Element::node("ActionResponse", vec![variant])
+}
+
+fn action_response_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    let v = e.only_child()?;
+    let invoke_id_and_priority = invoke_id_of(v)?;
+    let response = match v.name.as_str() {
+        "ActionResponseNormal" => {
+            let (result, return_parameters) = action_result_from(v)?;
+            ActionResponse::Normal { invoke_id_and_priority, result, return_parameters }
+        }
+        "ActionResponseWithPBlock" => {
+            ActionResponse::WithPblock { invoke_id_and_priority, datablock: data_block_of(v, "PBlock")? }
+        }
+        "ActionResponseNextPBlock" => {
+            ActionResponse::NextPblock { invoke_id_and_priority, block_number: block_number_of(v)? }
+        }
+        "ActionResponseWithList" => ActionResponse::WithList {
+            invoke_id_and_priority,
+            results: v.child("ResultList")?.children.iter().map(action_result_from).collect::<Result<_, _>>()?,
+        },
+        _ => return Err(v.unexpected()),
+    };
+    Ok(response.encode()?)
+}
+
+// Notifications.
+
+fn event_notification(request: &EventNotificationRequest) -> Element {
+    let mut children: Vec<Element> = opt_hex("Time", request.time.as_deref()).into_iter().collect();
+    children.push(attribute(&request.attribute));
+    children.push(data_node("AttributeValue", &request.value));
+    Element::node("EventNotificationRequest", children)
+}
+
+fn data_notification(notification: &DataNotification) -> Element {
+    Element::node(
+        "DataNotification",
+        vec![
+            Element::hex("LongInvokeIdAndPriority", &notification.long_invoke_id_and_priority.to_be_bytes()),
+            Element::hex("DateTime", &notification.date_time),
+            data_node("NotificationBody", &notification.notification_body),
+        ],
+    )
+}
+
+// ACSE and xDLMS-Initiate.
+
+fn association_request(request: &AssociationRequest) -> Element {
+    let mut children: Vec<Element> =
+        opt_hex("ProtocolVersion", request.protocol_version.as_ref().map(|v| &v[..])).into_iter().collect();
+    children.push(Element::hex("ApplicationContextName", &[request.application_context]));
+    children.extend(opt_hex("CallingAPTitle", request.calling_ap_title.as_deref()));
+    children.extend(request.sender_acse_requirements.map(|r| Element::hex("SenderACSERequirements", &[r])));
+    children.extend(request.mechanism_name.map(|m| Element::hex("MechanismName", &[m])));
+    children.extend(opt_hex("CallingAuthentication", request.calling_authentication_value.as_deref()));
+    children.push(nested("UserInformation", &request.user_information));
+    Element::node("AssociationRequest", children)
+}
+
+fn association_request_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    Ok(AssociationRequest {
+        protocol_version: e.find("ProtocolVersion").map(Element::fixed).transpose()?,
+        application_context: byte(e, "ApplicationContextName")?,
+        calling_ap_title: opt_octets(e, "CallingAPTitle")?,
+        sender_acse_requirements: opt_byte(e, "SenderACSERequirements")?,
+        mechanism_name: opt_byte(e, "MechanismName")?,
+        calling_authentication_value: opt_octets(e, "CallingAuthentication")?,
+        user_information: nested_of(e.child("UserInformation")?)?,
+    }
+    .encode())
+}
+
+fn association_response(response: &AssociationResponse) -> Element {
+    let mut children: Vec<Element> =
+        opt_hex("ProtocolVersion", response.protocol_version.as_ref().map(|v| &v[..])).into_iter().collect();
+    children.push(Element::hex("ApplicationContextName", &[response.application_context]));
+    children.push(Element::hex("AssociationResult", &[response.result]));
+    let source = if response.diagnostic_is_provider { "ACSEServiceProvider" } else { "ACSEServiceUser" };
+    children.push(Element::node("ResultSourceDiagnostic", vec![Element::hex(source, &[response.diagnostic])]));
+    children.extend(opt_hex("RespondingAPTitle", response.responding_ap_title.as_deref()));
+    children.extend(response.mechanism_name.map(|m| Element::hex("MechanismName", &[m])));
+    children.extend(opt_hex("RespondingAuthentication", response.responding_authentication_value.as_deref()));
+    children.push(nested("UserInformation", &response.user_information));
+    Element::node("AssociationResponse", children)
+}
+
+fn association_response_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    let diagnostic = e.child("ResultSourceDiagnostic")?.only_child()?;
+    let diagnostic_is_provider = match diagnostic.name.as_str() {
+        "ACSEServiceUser" => false,
+        "ACSEServiceProvider" => true,
+        _ => return Err(diagnostic.unexpected()),
+    };
+    Ok(AssociationResponse {
+        protocol_version: e.find("ProtocolVersion").map(Element::fixed).transpose()?,
+        application_context: byte(e, "ApplicationContextName")?,
+        result: byte(e, "AssociationResult")?,
+        diagnostic: u8::from_be_bytes(diagnostic.fixed()?),
+        diagnostic_is_provider,
+        responding_ap_title: opt_octets(e, "RespondingAPTitle")?,
+        mechanism_name: opt_byte(e, "MechanismName")?,
+        responding_authentication_value: opt_octets(e, "RespondingAuthentication")?,
+        user_information: nested_of(e.child("UserInformation")?)?,
+    }
+    .encode())
+}
+
+fn release(name: &str, request: &ReleaseRequest) -> Element {
+    let mut children: Vec<Element> = request.reason.map(|r| Element::hex("Reason", &[r])).into_iter().collect();
+    children.extend(request.user_information.as_ref().map(|info| nested("UserInformation", info)));
+    Element::node(name, children)
+}
+
+fn release_from(e: &Element) -> Result<ReleaseRequest, TranslateError> {
+    Ok(ReleaseRequest {
+        reason: opt_byte(e, "Reason")?,
+        user_information: e.find("UserInformation").map(nested_of).transpose()?,
+    })
+}
+
+fn conformance(value: u32) -> Element {
+    Element::hex("Conformance", &value.to_be_bytes()[1..])
+}
+
+fn conformance_of(e: &Element) -> Result<u32, TranslateError> {
+    let [a, b, c] = e.child("Conformance")?.fixed()?;
+    Ok(u32::from_be_bytes([0, a, b, c]))
+}
+
+fn initiate_request(request: &InitiateRequest) -> Element {
+    let mut children: Vec<Element> = opt_hex("DedicatedKey", request.dedicated_key.as_deref()).into_iter().collect();
+    children.push(flag("ResponseAllowed", request.response_allowed));
+    children.extend(
+        request.proposed_quality_of_service.map(|q| Element::hex("ProposedQualityOfService", &q.to_be_bytes())),
+    );
+    children.push(Element::hex("ProposedDlmsVersionNumber", &[request.proposed_dlms_version]));
+    children.push(conformance(request.proposed_conformance));
+    children.push(Element::hex("ProposedMaxPduSize", &request.client_max_receive_pdu_size.to_be_bytes()));
+    Element::node("InitiateRequest", children)
+}
+
+fn initiate_request_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    Ok(InitiateRequest {
+        dedicated_key: opt_octets(e, "DedicatedKey")?,
+        response_allowed: e.child("ResponseAllowed")?.flag()?,
+        proposed_quality_of_service: e
+            .find("ProposedQualityOfService")
+            .map(|q| q.fixed().map(i8::from_be_bytes))
+            .transpose()?,
+        proposed_dlms_version: byte(e, "ProposedDlmsVersionNumber")?,
+        proposed_conformance: conformance_of(e)?,
+        client_max_receive_pdu_size: u16::from_be_bytes(e.child("ProposedMaxPduSize")?.fixed()?),
+    }
+    .encode())
+}
+
+fn initiate_response(response: &InitiateResponse) -> Element {
+    let mut children: Vec<Element> = response
+        .negotiated_quality_of_service
+        .map(|q| Element::hex("NegotiatedQualityOfService", &q.to_be_bytes()))
+        .into_iter()
+        .collect();
+    children.push(Element::hex("NegotiatedDlmsVersionNumber", &[response.negotiated_dlms_version]));
+    children.push(conformance(response.negotiated_conformance));
+    children.push(Element::hex("NegotiatedMaxPduSize", &response.server_max_receive_pdu_size.to_be_bytes()));
+    children.push(Element::hex("VaaName", &response.vaa_name.to_be_bytes()));
+    Element::node("InitiateResponse", children)
+}
+
+fn initiate_response_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    Ok(InitiateResponse {
+        negotiated_quality_of_service: e
+            .find("NegotiatedQualityOfService")
+            .map(|q| q.fixed().map(i8::from_be_bytes))
+            .transpose()?,
+        negotiated_dlms_version: byte(e, "NegotiatedDlmsVersionNumber")?,
+        negotiated_conformance: conformance_of(e)?,
+        server_max_receive_pdu_size: u16::from_be_bytes(e.child("NegotiatedMaxPduSize")?.fixed()?),
+        vaa_name: u16::from_be_bytes(e.child("VaaName")?.fixed()?),
+    }
+    .encode())
+}
+
+// General ciphering, signing and block transfer.
+
+/// The addressing fields shared by general-ciphering and general-signing.
+fn addressing(
+    transaction_id: &[u8],
+    originator: &[u8],
+    recipient: &[u8],
+    date_time: &[u8],
+    other: &[u8],
+) -> Vec<Element> {
+    vec![
+        Element::hex("TransactionId", transaction_id),
+        Element::hex("OriginatorSystemTitle", originator),
+        Element::hex("RecipientSystemTitle", recipient),
+        Element::hex("DateTime", date_time),
+        Element::hex("OtherInformation", other),
+    ]
+}
+
+fn general_ciphering(g: &GeneralCiphering) -> Element {
+    let mut children = addressing(
+        &g.transaction_id,
+        &g.originator_system_title,
+        &g.recipient_system_title,
+        &g.date_time,
+        &g.other_information,
+    );
+    if let KeyInfo::AgreedKey { key_parameters, key_ciphered_data } = &g.key_info {
+        children.push(Element::node(
+            "KeyInfo",
+            vec![Element::node(
+                "AgreedKey",
+                vec![Element::hex("KeyParameters", key_parameters), Element::hex("KeyCipheredData", key_ciphered_data)],
+            )],
+        ));
+    }
+    children.push(Element::hex("CipheredContent", &g.ciphered_content));
+    Element::node("GeneralCiphering", children)
+}
+
+fn general_ciphering_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    let key_info = match e.find("KeyInfo") {
+        None => KeyInfo::None,
+        Some(info) => {
+            let agreed = info.child("AgreedKey")?;
+            KeyInfo::AgreedKey {
+                key_parameters: agreed.child("KeyParameters")?.octets()?,
+                key_ciphered_data: agreed.child("KeyCipheredData")?.octets()?,
+            }
+        }
+    };
+    Ok(GeneralCiphering {
+        transaction_id: e.child("TransactionId")?.octets()?,
+        originator_system_title: e.child("OriginatorSystemTitle")?.octets()?,
+        recipient_system_title: e.child("RecipientSystemTitle")?.octets()?,
+        date_time: e.child("DateTime")?.octets()?,
+        other_information: e.child("OtherInformation")?.octets()?,
+        key_info,
+        ciphered_content: e.child("CipheredContent")?.octets()?,
+    }
+    .encode())
+}
+
+fn general_signing(g: &GeneralSigning) -> Element {
+    let mut children = addressing(
+        &g.transaction_id,
+        &g.originator_system_title,
+        &g.recipient_system_title,
+        &g.date_time,
+        &g.other_information,
+    );
+    children.push(nested("Content", &g.content));
+    children.push(Element::hex("Signature", &g.signature));
+    Element::node("GeneralSigning", children)
+}
+
+fn general_signing_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    Ok(GeneralSigning {
+        transaction_id: e.child("TransactionId")?.octets()?,
+        originator_system_title: e.child("OriginatorSystemTitle")?.octets()?,
+        recipient_system_title: e.child("RecipientSystemTitle")?.octets()?,
+        date_time: e.child("DateTime")?.octets()?,
+        other_information: e.child("OtherInformation")?.octets()?,
+        content: nested_of(e.child("Content")?)?,
+        signature: e.child("Signature")?.octets()?,
+    }
+    .encode())
+}
+
+fn general_block_transfer(g: &GeneralBlockTransfer) -> Element {
+    Element::node(
+        "GeneralBlockTransfer",
+        vec![
+            flag("LastBlock", g.last_block),
+            flag("Streaming", g.streaming),
+            Element::hex("Window", &[g.window]),
+            Element::hex("BlockNumber", &g.block_number.to_be_bytes()),
+            Element::hex("BlockNumberAck", &g.block_number_ack.to_be_bytes()),
+            Element::hex("BlockData", &g.block_data),
+        ],
+    )
+}
+
+fn general_block_transfer_from(e: &Element) -> Result<Vec<u8>, TranslateError> {
+    Ok(GeneralBlockTransfer {
+        last_block: e.child("LastBlock")?.flag()?,
+        streaming: e.child("Streaming")?.flag()?,
+        window: byte(e, "Window")?,
+        block_number: u16::from_be_bytes(e.child("BlockNumber")?.fixed()?),
+        block_number_ack: u16::from_be_bytes(e.child("BlockNumberAck")?.fixed()?),
+        block_data: e.child("BlockData")?.octets()?,
+    }
+    .encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obis::ObisCode;
+    use crate::service::acse::application_context;
+    use crate::service::invoke_id_and_priority;
+
+    fn round_trip(apdu: &[u8], root: &str) {
+        let element = to_element(apdu);
+        assert_eq!(element.name, root, "{element:?}");
+        assert_eq!(from_element(&element).unwrap(), apdu);
+        assert_eq!(super::super::apdu_from_xml(&super::super::apdu_to_xml(apdu)).unwrap(), apdu);
+        assert_eq!(super::super::apdu_from_json(&super::super::apdu_to_json(apdu)).unwrap(), apdu);
+    }
+
+    #[test]
+    fn services_round_trip() {
+        let iip = invoke_id_and_priority(3, true, false);
+        let register = AttributeDescriptor::new(3, ObisCode::new(1, 0, 1, 8, 0, 0xFF), 2);
+        let block = DataBlockSa { last_block: false, block_number: 2, raw_data: vec![1, 2, 3] };
+        let reset = MethodDescriptor { class_id: 70, instance_id: ObisCode::new(0, 0, 96, 3, 10, 0xFF), method_id: 1 };
+        let value = CosemDataType::Structure(vec![CosemDataType::DoubleLong(-5), CosemDataType::Enum(30)]);
+
+        let set = SetRequest::WithFirstDatablock {
+            invoke_id_and_priority: iip,
+            attribute: register.clone(),
+            access_selection: None,
+            datablock: block.clone(),
+        };
+        round_trip(&set.encode().unwrap(), "SetRequest");
+        let set_list = SetRequest::WithList {
+            invoke_id_and_priority: iip,
+            attributes: vec![(register.clone(), None)],
+            values: vec![value.clone()],
+        };
+        round_trip(&set_list.encode().unwrap(), "SetRequest");
+        let response = SetResponse::WithList { invoke_id_and_priority: iip, results: vec![0, 3] };
+        round_trip(&response.encode(), "SetResponse");
+
+        let action =
+            ActionRequest::Normal { invoke_id_and_priority: iip, method: reset.clone(), parameters: Some(value) };
+        round_trip(&action.encode().unwrap(), "ActionRequest");
+        let action = ActionRequest::WithPblock { invoke_id_and_priority: iip, datablock: block };
+        round_trip(&action.encode().unwrap(), "ActionRequest");
+        let action = ActionResponse::WithList {
+            invoke_id_and_priority: iip,
+            results: vec![(0, Some(GetDataResult::Data(CosemDataType::Unsigned(1)))), (1, None)],
+        };
+        round_trip(&action.encode().unwrap(), "ActionResponse");
+
+        let get = GetResponse::WithDataBlock {
+            invoke_id_and_priority: iip,
+            last_block: true,
+            block_number: 7,
+            raw_data: Err(14),
+        };
+        round_trip(&get.encode().unwrap(), "GetResponse");
+
+        let event = EventNotificationRequest { time: None, attribute: register, value: CosemDataType::Boolean(false) };
+        round_trip(&event.encode().unwrap(), "EventNotificationRequest");
+        let push = DataNotification {
+            long_invoke_id_and_priority: 0x8000_0001,
+            date_time: Vec::new(),
+            notification_body: CosemDataType::OctetString(b"meter".to_vec()),
+        };
+        round_trip(&push.encode().unwrap(), "DataNotification");
+        round_trip(&ExceptionResponse { state_error: 1, service_error: 2 }.encode(), "ExceptionResponse");
+    }
+
+    #[test]
+    fn association_nests_the_initiate_request() {
+        let initiate = InitiateRequest {
+            dedicated_key: None,
+            response_allowed: true,
+            proposed_quality_of_service: None,
+            proposed_dlms_version: 6,
+            proposed_conformance: 0x00_1E_1D,
+            client_max_receive_pdu_size: 0xFFFF,
+        };
+        let aarq = AssociationRequest {
+            protocol_version: None,
+            application_context: application_context::LN,
+            calling_ap_title: None,
+            sender_acse_requirements: Some(0x80),
+            mechanism_name: Some(1),
+            calling_authentication_value: Some(b"12345678".to_vec()),
+            user_information: initiate.encode(),
+        };
+        let apdu = aarq.encode();
+        round_trip(&apdu, "AssociationRequest");
+        let xml = super::super::apdu_to_xml(&apdu);
+        assert!(xml.contains("<UserInformation>\n    <InitiateRequest>"), "{xml}");
+        assert!(xml.contains("<Conformance Value=\"001E1D\" />"), "{xml}");
+
+        let aare = AssociationResponse {
+            application_context: application_context::LN,
+            user_information: vec![0xFF, 0x00],
+            ..AssociationResponse::default()
+        };
+        let apdu = aare.encode();
+        round_trip(&apdu, "AssociationResponse");
+        assert!(super::super::apdu_to_xml(&apdu).contains("<RawApdu Value=\"FF00\" />"));
+        let release = ReleaseRequest { reason: Some(0), user_information: None };
+        round_trip(&release.encode_rlrq(), "ReleaseRequest");
+    }
+
+    #[test]
+    fn ciphered_wrappers_show_the_security_header() {
+        let apdu = RawApdu::new(glo::GET_REQUEST, vec![0x30, 0, 0, 0, 9, 0xAA, 0xBB]).encode();
+        round_trip(&apdu, "glo_GetRequest");
+        assert!(super::super::apdu_to_xml(&apdu).contains("<InvocationCounter Value=\"00000009\" />"));
+
+        let general =
+            GeneralGloDedCiphering { dedicated: true, system_title: vec![1; 8], ciphered_service: apdu.clone() };
+        round_trip(&general.encode(), "GeneralDedCiphering");
+        let general = GeneralCiphering {
+            transaction_id: vec![1],
+            originator_system_title: vec![2; 8],
+            recipient_system_title: Vec::new(),
+            date_time: Vec::new(),
+            other_information: Vec::new(),
+            key_info: KeyInfo::AgreedKey { key_parameters: vec![1], key_ciphered_data: vec![3; 4] },
+            ciphered_content: vec![0x30, 0, 0, 0, 1],
+        };
+        round_trip(&general.encode(), "GeneralCiphering");
+        let signing = GeneralSigning {
+            transaction_id: vec![1],
+            originator_system_title: vec![2; 8],
+            recipient_system_title: vec![3; 8],
+            date_time: Vec::new(),
+            other_information: Vec::new(),
+            content: apdu,
+            signature: vec![4; 64],
+        };
+        round_trip(&signing.encode(), "GeneralSigning");
+        let gbt = GeneralBlockTransfer {
+            last_block: false,
+            streaming: true,
+            window: 3,
+            block_number: 1,
+            block_number_ack: 0,
+            block_data: vec![0xC4, 0x01],
+        };
+        round_trip(&gbt.encode(), "GeneralBlockTransfer");
+    }
+}
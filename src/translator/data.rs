@@ -0,0 +1,97 @@
+//! Translation of [`CosemDataType`] values to and from the element tree.
+
+use super::{hex, Element, TranslateError};
+use crate::types::CosemDataType;
+
+/// Width in octets and signedness of the integer data elements.
+pub(super) fn integer_kind(name: &str) -> Option<(usize, bool)> {
+    match name {
+        "Int8" => Some((1, true)),
+        "UInt8" | "Enum" => Some((1, false)),
+        "Int16" => Some((2, true)),
+        "UInt16" => Some((2, false)),
+        "Int32" => Some((4, true)),
+        "UInt32" => Some((4, false)),
+        _ => None,
+    }
+}
+
+/// The element of a data value.
+pub(super) fn to_element(value: &CosemDataType) -> Element {
+    match value {
+        CosemDataType::Null => Element::empty("NullData"),
+        CosemDataType::Array(items) => Element::list("Array", items.iter().map(to_element).collect()),
+        CosemDataType::Structure(items) => Element::list("Structure", items.iter().map(to_element).collect()),
+        CosemDataType::Boolean(v) => Element::leaf("Boolean", v.to_string()),
+        CosemDataType::Integer(v) => Element::hex("Int8", &v.to_be_bytes()),
+        CosemDataType::Long(v) => Element::hex("Int16", &v.to_be_bytes()),
+        CosemDataType::Unsigned(v) => Element::hex("UInt8", &[*v]),
+        CosemDataType::LongUnsigned(v) => Element::hex("UInt16", &v.to_be_bytes()),
+        CosemDataType::DoubleLong(v) => Element::hex("Int32", &v.to_be_bytes()),
+        CosemDataType::DoubleLongUnsigned(v) => Element::hex("UInt32", &v.to_be_bytes()),
+        CosemDataType::OctetString(v) => Element::hex("OctetString", v),
+        CosemDataType::DateTime(v) => Element::hex("DateTime", v),
+        CosemDataType::BitString(v) => {
+            Element::leaf("BitString", v.iter().map(|b| format!("{b:08b}")).collect::<String>())
+        }
+        CosemDataType::Enum(v) => Element::hex("Enum", &[*v]),
+    }
+}
+
+/// The data value of an element.
+pub(super) fn from_element(element: &Element) -> Result<CosemDataType, TranslateError> {
+    let items = || element.children.iter().map(from_element).collect::<Result<Vec<_>, _>>();
+    Ok(match element.name.as_str() {
+        "NullData" => CosemDataType::Null,
+        "Array" => CosemDataType::Array(items()?),
+        "Structure" => CosemDataType::Structure(items()?),
+        "Boolean" => CosemDataType::Boolean(element.flag()?),
+        "Int8" => CosemDataType::Integer(i8::from_be_bytes(element.fixed()?)),
+        "Int16" => CosemDataType::Long(i16::from_be_bytes(element.fixed()?)),
+        "UInt8" => CosemDataType::Unsigned(u8::from_be_bytes(element.fixed()?)),
+        "UInt16" => CosemDataType::LongUnsigned(u16::from_be_bytes(element.fixed()?)),
+        "Int32" => CosemDataType::DoubleLong(i32::from_be_bytes(element.fixed()?)),
+        "UInt32" => CosemDataType::DoubleLongUnsigned(u32::from_be_bytes(element.fixed()?)),
+        "OctetString" => CosemDataType::OctetString(element.octets()?),
+        "DateTime" => CosemDataType::DateTime(element.octets()?),
+        "BitString" => CosemDataType::BitString(bits(element)?),
+        "Enum" => CosemDataType::Enum(u8::from_be_bytes(element.fixed()?)),
+        _ => return Err(element.unexpected()),
+    })
+}
+
+/// Parses a `0`/`1` bit string into whole octets.
+fn bits(element: &Element) -> Result<Vec<u8>, TranslateError> {
+    let text = element.text();
+    if text.len() % 8 != 0 || !text.bytes().all(|b| b == b'0' || b == b'1') {
+        return Err(TranslateError::Element(format!("{}: expected whole octets of 0/1 digits", element.name)));
+    }
+    Ok((0..text.len()).step_by(8).map(|i| text[i..i + 8].bytes().fold(0, |acc, b| acc << 1 | (b - b'0'))).collect())
+}
+
+/// The integer value of an integer data leaf, for JSON output.
+pub(super) fn integer_value(element: &Element) -> Option<i64> {
+    let (width, signed) = integer_kind(&element.name)?;
+    let bytes = element.octets().ok().filter(|b| b.len() == width)?;
+    let unsigned = bytes.iter().fold(0u64, |acc, &b| acc << 8 | u64::from(b));
+    let bits = 8 * width;
+    if signed && unsigned >> (bits - 1) != 0 {
+        // Sign-extend the two's complement value of `bits` bits.
+        #[allow(clippy::cast_possible_wrap)]
+        let extended = (unsigned | !((1u64 << bits) - 1)) as i64;
+        Some(extended)
+    } else {
+        i64::try_from(unsigned).ok()
+    }
+}
+
+/// The hex value of an integer data leaf given as a JSON number.
+pub(super) fn integer_hex(name: &str, value: i64) -> Option<String> {
+    let (width, signed) = integer_kind(name)?;
+    let bits = 8 * width;
+    let (min, max) = if signed { (-(1i64 << (bits - 1)), (1i64 << (bits - 1)) - 1) } else { (0, (1i64 << bits) - 1) };
+    if !(min..=max).contains(&value) {
+        return None;
+    }
+    Some(hex(&value.to_be_bytes()[8 - width..]))
+}
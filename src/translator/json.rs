@@ -0,0 +1,232 @@
+//! JSON syntax of the element tree (see the module documentation of
+//! [`super`] for the mapping).
+
+use super::{data, Element, TranslateError};
+
+/// Renders `element` as compact JSON: `{"Name": …}`.
+pub(super) fn write(element: &Element) -> String {
+    let mut out = String::new();
+    write_element(element, &mut out);
+    out
+}
+
+fn write_element(element: &Element, out: &mut String) {
+    out.push('{');
+    write_string(&element.name, out);
+    out.push(':');
+    write_value(element, out);
+    out.push('}');
+}
+
+fn write_value(element: &Element, out: &mut String) {
+    if element.list {
+        out.push('[');
+        for (i, child) in element.children.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_element(child, out);
+        }
+        out.push(']');
+    } else if !element.children.is_empty() {
+        out.push('{');
+        for (i, child) in element.children.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_string(&child.name, out);
+            out.push(':');
+            write_value(child, out);
+        }
+        out.push('}');
+    } else if let Some(value) = &element.value {
+        if let Some(number) = data::integer_value(element) {
+            out.push_str(&number.to_string());
+        } else if element.name == "Boolean" && (value == "true" || value == "false") {
+            out.push_str(value);
+        } else {
+            write_string(value, out);
+        }
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn write_string(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Parses one `{"Name": …}` element.
+pub(super) fn parse(text: &str) -> Result<Element, TranslateError> {
+    let mut parser = Parser { text, pos: 0 };
+    let element = parser.element()?;
+    parser.skip_whitespace();
+    if parser.pos != text.len() {
+        return Err(parser.error("content after the root object"));
+    }
+    Ok(element)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> TranslateError {
+        TranslateError::Syntax { offset: self.pos, message: message.to_owned() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `token` (after whitespace) if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), TranslateError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{token}`")))
+        }
+    }
+
+    /// A single-key object.
+    fn element(&mut self) -> Result<Element, TranslateError> {
+        self.expect("{")?;
+        let name = self.string()?;
+        self.expect(":")?;
+        let element = self.value(&name)?;
+        if !self.eat("}") {
+            return Err(self.error("expected `}` (an element object has a single key)"));
+        }
+        Ok(element)
+    }
+
+    /// The value of the element named `name`.
+    fn value(&mut self, name: &str) -> Result<Element, TranslateError> {
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some('{') => {
+                self.pos += 1;
+                let mut element = Element::node(name, Vec::new());
+                if !self.eat("}") {
+                    loop {
+                        let child = self.string()?;
+                        self.expect(":")?;
+                        element.children.push(self.value(&child)?);
+                        if self.eat("}") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(element)
+            }
+            Some('[') => {
+                self.pos += 1;
+                let mut element = Element::list(name, Vec::new());
+                if !self.eat("]") {
+                    loop {
+                        element.children.push(self.element()?);
+                        if self.eat("]") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(element)
+            }
+            Some('"') => Ok(Element::leaf(name, self.string()?)),
+            Some('t') if self.eat("true") => Ok(Element::leaf(name, "true")),
+            Some('f') if self.eat("false") => Ok(Element::leaf(name, "false")),
+            Some('n') if self.eat("null") => Ok(Element::empty(name)),
+            Some('-' | '0'..='9') => {
+                let offset = self.pos;
+                let number = self.integer()?;
+                let value = data::integer_hex(name, number).ok_or_else(|| {
+                    TranslateError::Element(format!("{name}: {number} is not a valid value (at offset {offset})"))
+                })?;
+                Ok(Element::leaf(name, value))
+            }
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn integer(&mut self) -> Result<i64, TranslateError> {
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let digits = rest[sign..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - sign);
+        let end = sign + digits;
+        if rest[end..].starts_with(['.', 'e', 'E']) {
+            return Err(self.error("expected an integer"));
+        }
+        let number = rest[..end].parse().map_err(|_| self.error("invalid integer"))?;
+        self.pos += end;
+        Ok(number)
+    }
+
+    fn string(&mut self) -> Result<String, TranslateError> {
+        self.expect("\"")?;
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, '"')) => '"',
+                        Some((_, '\\')) => '\\',
+                        Some((_, '/')) => '/',
+                        Some((_, 'b')) => '\u{8}',
+                        Some((_, 'f')) => '\u{c}',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((j, 'u')) => {
+                            let code = self.rest().get(j + 1..j + 5).and_then(|h| u32::from_str_radix(h, 16).ok());
+                            let c = code.and_then(char::from_u32).ok_or_else(|| self.error("invalid \\u escape"))?;
+                            for _ in 0..4 {
+                                chars.next();
+                            }
+                            c
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    out.push(escaped);
+                }
+                c => out.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
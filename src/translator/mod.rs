@@ -0,0 +1,339 @@
+//! XML and JSON representations of COSEM data and xDLMS APDUs.
+//!
+//! The XML form follows the DLMS UA XML encoding of the Green Book
+//! (IEC 62056-5-3, Annex H, as produced by the common DLMS translators):
+//! every value is an element named after its type, scalars carry their
+//! big-endian hex encoding in a `Value` attribute and arrays/structures a
+//! `Qty` element count.
+//!
+//! ```text
+//! <Structure Qty="02">
+//!   <UInt16 Value="0005" />
+//!   <UInt8 Value="05" />
+//! </Structure>
+//! ```
+//!
+//! The JSON form is a mechanical mapping of the same element tree, so both
+//! keep the type of every value and translate back to the identical bytes:
+//! an element is a single-key object `{"Name": …}`, a list (an element with a
+//! `Qty`) an array of such objects, any other container an object of its
+//! children in order, a leaf its `Value` string and an empty element `null`.
+//! Integer data values are written as JSON numbers and booleans as JSON
+//! booleans:
+//!
+//! ```text
+//! {"Structure":[{"UInt16":5},{"UInt8":5}]}
+//! ```
+//!
+//! An APDU is translated through the typed decoders of [`crate::service`].
+//! Ciphered APDUs are shown with their security header and the ciphertext in
+//! hex; an APDU that does not decode (or would not re-encode to the same
+//! bytes) is kept whole as `<RawApdu Value="…" />`, so translation is always
+//! lossless.
+//!
+//! # Examples
+//!
+//! ```
+//! use spodes_rs::translator;
+//! use spodes_rs::types::CosemDataType;
+//!
+//! let value = CosemDataType::Structure(vec![CosemDataType::LongUnsigned(5), CosemDataType::Unsigned(5)]);
+//! let json = translator::data_to_json(&value);
+//! assert_eq!(json, r#"{"Structure":[{"UInt16":5},{"UInt8":5}]}"#);
+//! assert_eq!(translator::data_from_json(&json).unwrap(), value);
+//! ```
+
+use crate::service::ServiceError;
+use crate::types::{BerError, CosemDataType};
+
+mod apdu;
+mod data;
+mod json;
+mod xml;
+
+/// Errors from translating XML or JSON back to data or APDUs.
+#[derive(Debug, PartialEq)]
+pub enum TranslateError {
+    /// The text is not well-formed XML/JSON; the offset is in bytes.
+    Syntax {
+        /// Byte offset of the error in the input.
+        offset: usize,
+        /// What was wrong.
+        message: String,
+    },
+    /// The text is well-formed but does not describe a valid value or APDU.
+    Element(String),
+    /// A translated APDU could not be encoded.
+    Service(ServiceError),
+    /// A translated data value could not be encoded.
+    Data(BerError),
+}
+
+impl std::fmt::Display for TranslateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranslateError::Syntax { offset, message } => write!(f, "syntax error at offset {offset}: {message}"),
+            TranslateError::Element(message) => write!(f, "{message}"),
+            TranslateError::Service(e) => write!(f, "service error: {e}"),
+            TranslateError::Data(e) => write!(f, "data error: {e:?}"),
+        }
+    }
+}
+
+impl std::error::Error for TranslateError {}
+
+impl From<ServiceError> for TranslateError {
+    fn from(e: ServiceError) -> Self {
+        TranslateError::Service(e)
+    }
+}
+
+impl From<BerError> for TranslateError {
+    fn from(e: BerError) -> Self {
+        TranslateError::Data(e)
+    }
+}
+
+/// Renders a data value as XML.
+pub fn data_to_xml(value: &CosemDataType) -> String {
+    xml::write(&data::to_element(value))
+}
+
+/// Parses a data value from XML.
+pub fn data_from_xml(text: &str) -> Result<CosemDataType, TranslateError> {
+    data::from_element(&xml::parse(text)?)
+}
+
+/// Renders a data value as JSON.
+pub fn data_to_json(value: &CosemDataType) -> String {
+    json::write(&data::to_element(value))
+}
+
+/// Parses a data value from JSON.
+pub fn data_from_json(text: &str) -> Result<CosemDataType, TranslateError> {
+    data::from_element(&json::parse(text)?)
+}
+
+/// Renders an encoded APDU as XML.
+pub fn apdu_to_xml(apdu: &[u8]) -> String {
+    xml::write(&apdu::to_element(apdu))
+}
+
+/// Parses an APDU from XML and encodes it.
+pub fn apdu_from_xml(text: &str) -> Result<Vec<u8>, TranslateError> {
+    apdu::from_element(&xml::parse(text)?)
+}
+
+/// Renders an encoded APDU as JSON.
+pub fn apdu_to_json(apdu: &[u8]) -> String {
+    json::write(&apdu::to_element(apdu))
+}
+
+/// Parses an APDU from JSON and encodes it.
+pub fn apdu_from_json(text: &str) -> Result<Vec<u8>, TranslateError> {
+    apdu::from_element(&json::parse(text)?)
+}
+
+/// One element of the translated tree, shared by the XML and JSON forms.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Element {
+    name: String,
+    /// The `Value` attribute of a leaf.
+    value: Option<String>,
+    children: Vec<Element>,
+    /// Whether the children form a counted list (`Qty`).
+    list: bool,
+}
+
+impl Element {
+    /// A leaf with a value.
+    fn leaf(name: &str, value: impl Into<String>) -> Element {
+        Element { name: name.to_owned(), value: Some(value.into()), ..Element::default() }
+    }
+
+    /// A leaf with a hex value.
+    fn hex(name: &str, bytes: &[u8]) -> Element {
+        Element::leaf(name, hex(bytes))
+    }
+
+    /// An element with neither value nor children.
+    fn empty(name: &str) -> Element {
+        Element { name: name.to_owned(), ..Element::default() }
+    }
+
+    /// A container of differently named children.
+    fn node(name: &str, children: Vec<Element>) -> Element {
+        Element { name: name.to_owned(), children, ..Element::default() }
+    }
+
+    /// A counted list.
+    fn list(name: &str, children: Vec<Element>) -> Element {
+        Element { name: name.to_owned(), children, list: true, ..Element::default() }
+    }
+
+    /// The value of a leaf (empty when absent).
+    fn text(&self) -> &str {
+        self.value.as_deref().unwrap_or("")
+    }
+
+    /// The child named `name`.
+    fn child(&self, name: &str) -> Result<&Element, TranslateError> {
+        self.find(name).ok_or_else(|| TranslateError::Element(format!("{}: missing <{name}>", self.name)))
+    }
+
+    /// The child named `name`, when present.
+    fn find(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// The single child of a wrapper element.
+    fn only_child(&self) -> Result<&Element, TranslateError> {
+        match self.children.as_slice() {
+            [child] => Ok(child),
+            _ => Err(TranslateError::Element(format!("{}: expected exactly one child element", self.name))),
+        }
+    }
+
+    /// The hex value of a leaf as octets.
+    fn octets(&self) -> Result<Vec<u8>, TranslateError> {
+        unhex(self.text()).ok_or_else(|| TranslateError::Element(format!("{}: invalid hex value", self.name)))
+    }
+
+    /// The hex value of a leaf as exactly `N` octets.
+    fn fixed<const N: usize>(&self) -> Result<[u8; N], TranslateError> {
+        self.octets()?
+            .try_into()
+            .map_err(|_| TranslateError::Element(format!("{}: expected {} hex digits", self.name, 2 * N)))
+    }
+
+    /// A `true`/`false` leaf.
+    fn flag(&self) -> Result<bool, TranslateError> {
+        match self.text() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(TranslateError::Element(format!("{}: expected true or false", self.name))),
+        }
+    }
+
+    fn unexpected(&self) -> TranslateError {
+        TranslateError::Element(format!("unexpected element <{}>", self.name))
+    }
+}
+
+/// Upper-case hex of `bytes`.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+/// Parses hex digits (either case) into octets.
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obis::ObisCode;
+    use crate::service::get::{AccessSelection, GetDataResult, GetRequest, GetResponse};
+    use crate::service::{invoke_id_and_priority, AttributeDescriptor};
+
+    fn sample_data() -> CosemDataType {
+        CosemDataType::Array(vec![
+            CosemDataType::Structure(vec![
+                CosemDataType::LongUnsigned(5),
+                CosemDataType::Unsigned(5),
+                CosemDataType::Integer(-2),
+                CosemDataType::Long(-300),
+                CosemDataType::DoubleLong(-70_000),
+                CosemDataType::DoubleLongUnsigned(4_000_000_000),
+                CosemDataType::Enum(3),
+            ]),
+            CosemDataType::Structure(vec![
+                CosemDataType::Null,
+                CosemDataType::Boolean(true),
+                CosemDataType::OctetString(b"<&\"x\">".to_vec()),
+                CosemDataType::OctetString(Vec::new()),
+                CosemDataType::DateTime(vec![0x07, 0xEA, 10, 18, 7, 12, 0, 0, 0xFF, 0x80, 0, 0]),
+                CosemDataType::BitString(vec![0xA5]),
+                CosemDataType::Array(Vec::new()),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn data_round_trips_through_xml_and_json() {
+        let value = sample_data();
+        let xml = data_to_xml(&value);
+        assert!(xml.contains("<UInt16 Value=\"0005\" />"), "{xml}");
+        assert!(xml.contains("<UInt8 Value=\"05\" />"), "{xml}");
+        assert!(xml.contains("<BitString Value=\"10100101\" />"), "{xml}");
+        assert_eq!(data_from_xml(&xml).unwrap(), value);
+
+        let json = data_to_json(&value);
+        assert!(json.contains(r#"{"UInt16":5},{"UInt8":5},{"Int8":-2},{"Int16":-300}"#), "{json}");
+        assert!(json.contains(r#"{"NullData":null},{"Boolean":true}"#), "{json}");
+        assert_eq!(data_from_json(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn type_tags_keep_equal_numbers_apart() {
+        let long = data_from_json(r#"{"UInt16":5}"#).unwrap();
+        let short = data_from_json(r#"{"UInt8":5}"#).unwrap();
+        assert_eq!(long, CosemDataType::LongUnsigned(5));
+        assert_eq!(short, CosemDataType::Unsigned(5));
+        assert_eq!(data_from_xml(r#"<Int8 Value="ff"/>"#).unwrap(), CosemDataType::Integer(-1));
+        assert!(matches!(data_from_json(r#"{"UInt8":256}"#), Err(TranslateError::Element(_))));
+        assert!(data_from_xml(r#"<Array Qty="02"><UInt8 Value="01"/></Array>"#).is_err());
+        assert!(matches!(data_from_xml("<UInt8 Value=\"01\">"), Err(TranslateError::Syntax { .. })));
+    }
+
+    #[test]
+    fn get_apdus_round_trip() {
+        let iip = invoke_id_and_priority(1, true, true);
+        let clock = AttributeDescriptor::new(8, ObisCode::new(0, 0, 1, 0, 0, 0xFF), 2);
+        let request = GetRequest::WithList {
+            invoke_id_and_priority: iip,
+            attributes: vec![
+                (clock.clone(), None),
+                (
+                    AttributeDescriptor::new(7, ObisCode::new(1, 0, 99, 1, 0, 0xFF), 2),
+                    Some(AccessSelection { selector: 2, parameters: sample_data() }),
+                ),
+            ],
+        }
+        .encode()
+        .unwrap();
+        let xml = apdu_to_xml(&request);
+        assert!(
+            xml.starts_with("<GetRequest>\n  <GetRequestWithList>\n    <InvokeIdAndPriority Value=\"C1\" />"),
+            "{xml}"
+        );
+        assert!(xml.contains("<InstanceId Value=\"0000010000FF\" />"), "{xml}");
+        assert_eq!(apdu_from_xml(&xml).unwrap(), request);
+        assert_eq!(apdu_from_json(&apdu_to_json(&request)).unwrap(), request);
+
+        let response = GetResponse::WithList {
+            invoke_id_and_priority: iip,
+            results: vec![GetDataResult::Data(sample_data()), GetDataResult::AccessResult(4)],
+        }
+        .encode()
+        .unwrap();
+        let json = apdu_to_json(&response);
+        assert!(json.contains(r#"{"DataAccessResult":"04"}"#), "{json}");
+        assert_eq!(apdu_from_json(&json).unwrap(), response);
+        assert_eq!(apdu_from_xml(&apdu_to_xml(&response)).unwrap(), response);
+    }
+
+    #[test]
+    fn unknown_or_malformed_apdus_are_kept_raw() {
+        for apdu in [vec![0xC0, 0x01], vec![0x7E, 0x01, 0x02], Vec::new()] {
+            let xml = apdu_to_xml(&apdu);
+            assert!(xml.starts_with("<RawApdu"), "{xml}");
+            assert_eq!(apdu_from_xml(&xml).unwrap(), apdu);
+            assert_eq!(apdu_from_json(&apdu_to_json(&apdu)).unwrap(), apdu);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! XML syntax of the element tree: `<Name Value="…" />` leaves, `Qty`-counted
+//! lists and plain containers, indented by two spaces per level.
+
+use super::{Element, TranslateError};
+
+/// Renders `element` as indented XML.
+pub(super) fn write(element: &Element) -> String {
+    let mut out = String::new();
+    write_element(element, 0, &mut out);
+    out
+}
+
+fn write_element(element: &Element, depth: usize, out: &mut String) {
+    out.push_str(&"  ".repeat(depth));
+    out.push('<');
+    out.push_str(&element.name);
+    if let Some(value) = &element.value {
+        out.push_str(" Value=\"");
+        escape(value, out);
+        out.push('"');
+    }
+    if element.list {
+        out.push_str(&format!(" Qty=\"{:02X}\"", element.children.len()));
+    }
+    if element.children.is_empty() {
+        out.push_str(" />\n");
+        return;
+    }
+    out.push_str(">\n");
+    for child in &element.children {
+        write_element(child, depth + 1, out);
+    }
+    out.push_str(&"  ".repeat(depth));
+    out.push_str("</");
+    out.push_str(&element.name);
+    out.push_str(">\n");
+}
+
+fn escape(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Parses one XML element (with an optional declaration and comments around it).
+pub(super) fn parse(text: &str) -> Result<Element, TranslateError> {
+    let mut parser = Parser { text, pos: 0 };
+    parser.skip_misc()?;
+    let element = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos != text.len() {
+        return Err(parser.error("content after the root element"));
+    }
+    Ok(element)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> TranslateError {
+        TranslateError::Syntax { offset: self.pos, message: message.to_owned() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips whitespace, XML declarations/processing instructions and comments.
+    fn skip_misc(&mut self) -> Result<(), TranslateError> {
+        loop {
+            self.skip_whitespace();
+            let end = if self.rest().starts_with("<?") {
+                "?>"
+            } else if self.rest().starts_with("<!--") {
+                "-->"
+            } else {
+                return Ok(());
+            };
+            let len = self.rest().find(end).ok_or_else(|| self.error("unterminated declaration or comment"))?;
+            self.pos += len + end.len();
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), TranslateError> {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{token}`")))
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, TranslateError> {
+        let start = self.pos;
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(&self.text[start..start + len])
+    }
+
+    fn attribute_value(&mut self) -> Result<String, TranslateError> {
+        let quote = match self.rest().chars().next() {
+            Some(q @ ('"' | '\'')) => q,
+            _ => return Err(self.error("expected a quoted attribute value")),
+        };
+        self.pos += 1;
+        let len = self.rest().find(quote).ok_or_else(|| self.error("unterminated attribute value"))?;
+        let raw = &self.text[self.pos..self.pos + len];
+        let value = unescape(raw).ok_or_else(|| self.error("unknown character reference"))?;
+        self.pos += len + 1;
+        Ok(value)
+    }
+
+    fn element(&mut self) -> Result<Element, TranslateError> {
+        self.expect("<")?;
+        let mut element = Element::empty(self.name()?);
+        let mut qty = None;
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return check_qty(element, qty);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let attribute = self.name()?.to_owned();
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let offset = self.pos;
+            let value = self.attribute_value()?;
+            match attribute.as_str() {
+                "Value" => element.value = Some(value),
+                "Qty" => {
+                    let count = usize::from_str_radix(&value, 16)
+                        .map_err(|_| TranslateError::Syntax { offset, message: "invalid Qty".to_owned() })?;
+                    qty = Some(count);
+                }
+                _ => {} // other attributes carry no information here
+            }
+        }
+        loop {
+            self.skip_misc()?;
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                if self.name()? != element.name {
+                    return Err(self.error(&format!("expected </{}>", element.name)));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return check_qty(element, qty);
+            }
+            if self.rest().starts_with('<') {
+                element.children.push(self.element()?);
+            } else if self.rest().is_empty() {
+                return Err(self.error(&format!("unclosed <{}>", element.name)));
+            } else {
+                return Err(self.error("unexpected text content"));
+            }
+        }
+    }
+}
+
+/// Marks a `Qty` element as a list and checks its count.
+fn check_qty(mut element: Element, qty: Option<usize>) -> Result<Element, TranslateError> {
+    if let Some(count) = qty {
+        if count != element.children.len() {
+            return Err(TranslateError::Element(format!(
+                "{}: Qty is {count} but {} elements follow",
+                element.name,
+                element.children.len()
+            )));
+        }
+        element.list = true;
+    }
+    Ok(element)
+}
+
+fn unescape(raw: &str) -> Option<String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let end = rest[amp..].find(';')? + amp;
+        out.push(match &rest[amp + 1..end] {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => return None,
+        });
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
@@ -15,58 +15,7 @@ pub type LogicalName = ObisCode;
 /// This maintains backward compatibility with the generic CosemDataType.
 pub type Choice = CosemDataType;
 
-/// Date-time value (12 octets) per IEC 62056-6-2, 4.1.6.1.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct DateTime(pub [u8; 12]);
-
-impl DateTime {
-    /// Creates a new DateTime from raw bytes.
-    pub fn new(bytes: [u8; 12]) -> Self {
-        Self(bytes)
-    }
-
-    /// Returns the raw bytes.
-    pub fn as_bytes(&self) -> &[u8; 12] {
-        &self.0
-    }
-
-    /// Creates a DateTime from year, month, day, hour, minute, second.
-    pub fn from_ymdhms(year: u16, month: u8, day: u8, hour: u8, min: u8, sec: u8) -> Self {
-        let mut buf = [0u8; 12];
-        buf[0..2].copy_from_slice(&year.to_be_bytes());
-        buf[2] = month;
-        buf[3] = day;
-        buf[4] = 0xFF; // day of week (any)
-        buf[5] = hour;
-        buf[6] = min;
-        buf[7] = sec;
-        buf[8] = 0; // hundredths
-        buf[9..12].copy_from_slice(&[0, 0, 0]); // deviation, clocks, reserved
-        Self(buf)
-    }
-}
-
-impl From<DateTime> for CosemDataType {
-    fn from(dt: DateTime) -> Self {
-        // COSEM date-time on the wire is octet-string SIZE(12), not A-XDR tag 25.
-        CosemDataType::OctetString(dt.0.to_vec())
-    }
-}
-
-impl TryFrom<&CosemDataType> for DateTime {
-    type Error = String;
-
-    fn try_from(value: &CosemDataType) -> Result<Self, String> {
-        match value {
-            CosemDataType::DateTime(bytes) | CosemDataType::OctetString(bytes) if bytes.len() == 12 => {
-                let mut buf = [0u8; 12];
-                buf.copy_from_slice(bytes);
-                Ok(DateTime(buf))
-            }
-            _ => Err("expected 12-byte date-time or octet-string".to_string()),
-        }
-    }
-}
+pub use crate::types::datetime::{Date, DateTime, Time};
 
 /// Bit-string value (held as raw octets).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -103,7 +52,7 @@ impl From<BitString> for CosemDataType {
 pub struct ScalerUnit {
     /// The scaler (power of 10): value = raw × 10^scaler.
     pub scaler: i8,
-    /// The unit code per IEC 62056-6-2, Table 42.
+    /// The unit code per IEC 62056-6-2, Table 42 (see [`Unit`](super::unit::Unit)).
     pub unit: u8,
 }
 
@@ -1706,7 +1655,7 @@ pub struct ScheduleTableEntry {
     pub script_selector: u16,
     /// Switch time.
     pub switch_time: Vec<u8>,
-    /// Validity window in seconds.
+    /// Validity window in minutes (`0xFFFF`: always valid).
     pub validity_window: u16,
     /// Execution weekdays bit-string.
     pub exec_weekdays: Vec<u8>,
@@ -3009,6 +2958,23 @@ mod tests {
         assert_eq!(dt.0[6], 30);
     }
 
+    #[test]
+    fn datetime_unix_round_trip() {
+        // 2025-05-01 16:30:00 UTC == 1746117000, a Thursday.
+        let utc = DateTime::from_unix(1_746_117_000, 0).unwrap();
+        assert_eq!(utc.0[..8], [0x07, 0xE9, 5, 1, 4, 16, 30, 0]);
+        assert_eq!(utc.to_unix(), Some(1_746_117_000));
+
+        // Moscow local time (UTC+3) encodes 19:30 with deviation 180.
+        let msk = DateTime::from_unix(1_746_117_000, 180).unwrap();
+        assert_eq!(msk.0[5], 19);
+        assert_eq!(msk.0[9..11], 180i16.to_be_bytes());
+        assert_eq!(msk.to_unix(), Some(1_746_117_000));
+
+        assert_eq!(DateTime::from_unix(951_782_400, 0).unwrap().0[2..4], [2, 29]); // 2000-02-29
+        assert_eq!(DateTime([0xFF; 12]).to_unix(), None);
+    }
+
     #[test]
     fn datetime_try_from_octet_string() {
         let bytes = vec![0x07, 0xE5, 0x01, 0x01, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
@@ -0,0 +1,376 @@
+//! Typed mapping between Rust values and [`CosemDataType`].
+//!
+//! [`ToCosem`] and [`FromCosem`] map a Rust type to its COSEM encoding:
+//!
+//! | Rust                                 | COSEM                              |
+//! |--------------------------------------|------------------------------------|
+//! | `bool`, `i8`..`u32`                  | boolean, integer .. double-long-unsigned |
+//! | tuples, `#[derive]`d structs         | structure (fields in order)         |
+//! | `Vec<T>`, `[T; N]`                   | array                              |
+//! | `Option<T>`                          | null-data for `None`               |
+//! | `#[derive]`d unit-only enums         | enum (the variant's discriminant)  |
+//! | [`ObisCode`], [`DateTime`], [`Date`], [`Time`] | octet-string(6 / 12 / 5 / 4) |
+//!
+//! With the `derive` feature, `#[derive(ToCosem, FromCosem)]` implements both
+//! traits for structs and enums; `#[cosem(octets)]` maps a `Vec<u8>` or
+//! `[u8; N]` field to an octet-string and `#[cosem(transparent)]` encodes a
+//! single-field struct as its field.
+//!
+//! Decoding failures carry the path to the offending element, e.g.
+//! `field 3.capture_objects[2].logical_name: expected octet-string(6)`.
+
+use std::fmt;
+
+use super::datetime::{Date, DateTime, Time};
+use super::CosemDataType;
+use crate::obis::ObisCode;
+
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use spodes_rs_derive::{FromCosem, ToCosem};
+
+/// Encodes a value as a [`CosemDataType`].
+pub trait ToCosem {
+    /// Returns the COSEM encoding of `self`.
+    fn to_cosem(&self) -> CosemDataType;
+}
+
+/// Decodes a value from a [`CosemDataType`].
+pub trait FromCosem: Sized {
+    /// Decodes `value`, reporting where it did not match the expected shape.
+    fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError>;
+}
+
+/// One step of a [`DecodeError`] path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A structure field (its name, or its position for tuples).
+    Field(&'static str),
+    /// An array element.
+    Index(usize),
+}
+
+/// A value did not have the expected COSEM shape.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    /// Path from the decoded value to the offending element.
+    pub path: Vec<PathSegment>,
+    /// The expected COSEM type, e.g. `octet-string(6)`.
+    pub expected: String,
+}
+
+impl DecodeError {
+    /// An error at the current element.
+    pub fn expected(expected: impl Into<String>) -> Self {
+        DecodeError { path: Vec::new(), expected: expected.into() }
+    }
+
+    /// Prefixes the path with the structure field `name`.
+    pub fn in_field(mut self, name: &'static str) -> Self {
+        self.path.insert(0, PathSegment::Field(name));
+        self
+    }
+
+    /// Prefixes the path with the array element `index`.
+    pub fn at_index(mut self, index: usize) -> Self {
+        self.path.insert(0, PathSegment::Index(index));
+        self
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "field ")?;
+            for (i, segment) in self.path.iter().enumerate() {
+                match segment {
+                    PathSegment::Field(name) if i == 0 => write!(f, "{name}")?,
+                    PathSegment::Field(name) => write!(f, ".{name}")?,
+                    PathSegment::Index(index) => write!(f, "[{index}]")?,
+                }
+            }
+            write!(f, ": ")?;
+        }
+        write!(f, "expected {}", self.expected)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for String {
+    fn from(e: DecodeError) -> Self {
+        e.to_string()
+    }
+}
+
+/// The elements of a structure of exactly `len` fields.
+pub fn structure(value: &CosemDataType, len: usize) -> Result<&[CosemDataType], DecodeError> {
+    match value {
+        CosemDataType::Structure(items) if items.len() == len => Ok(items),
+        _ => Err(DecodeError::expected(format!("structure({len})"))),
+    }
+}
+
+/// Decodes field `index` of a structure, named `name` in error paths.
+pub fn field<T: FromCosem>(items: &[CosemDataType], index: usize, name: &'static str) -> Result<T, DecodeError> {
+    T::from_cosem(&items[index]).map_err(|e| e.in_field(name))
+}
+
+/// The value of a COSEM enum.
+pub fn enum_value(value: &CosemDataType) -> Result<u8, DecodeError> {
+    match value {
+        CosemDataType::Enum(v) => Ok(*v),
+        _ => Err(DecodeError::expected("enum")),
+    }
+}
+
+/// Octet-string mapping for byte containers (`#[cosem(octets)]`).
+pub mod octets {
+    use super::{CosemDataType, DecodeError};
+
+    /// A byte container encoded as an octet-string: `Vec<u8>` (any length) or
+    /// `[u8; N]` (exactly N octets).
+    pub trait Octets: Sized {
+        /// The COSEM type expected when decoding, e.g. `octet-string(6)`.
+        fn expected() -> String;
+        /// The octets.
+        fn as_octets(&self) -> &[u8];
+        /// Builds the container, or `None` if `bytes` has the wrong length.
+        fn from_octets(bytes: &[u8]) -> Option<Self>;
+    }
+
+    impl Octets for Vec<u8> {
+        fn expected() -> String {
+            "octet-string".to_string()
+        }
+        fn as_octets(&self) -> &[u8] {
+            self
+        }
+        fn from_octets(bytes: &[u8]) -> Option<Self> {
+            Some(bytes.to_vec())
+        }
+    }
+
+    impl<const N: usize> Octets for [u8; N] {
+        fn expected() -> String {
+            format!("octet-string({N})")
+        }
+        fn as_octets(&self) -> &[u8] {
+            self
+        }
+        fn from_octets(bytes: &[u8]) -> Option<Self> {
+            bytes.try_into().ok()
+        }
+    }
+
+    /// Encodes `bytes` as an octet-string.
+    pub fn to_cosem<T: Octets>(bytes: &T) -> CosemDataType {
+        CosemDataType::OctetString(bytes.as_octets().to_vec())
+    }
+
+    /// Decodes an octet-string.
+    pub fn from_cosem<T: Octets>(value: &CosemDataType) -> Result<T, DecodeError> {
+        match value {
+            CosemDataType::OctetString(bytes) => T::from_octets(bytes),
+            _ => None,
+        }
+        .ok_or_else(|| DecodeError::expected(T::expected()))
+    }
+}
+
+impl ToCosem for CosemDataType {
+    fn to_cosem(&self) -> CosemDataType {
+        self.clone()
+    }
+}
+
+impl FromCosem for CosemDataType {
+    fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+        Ok(value.clone())
+    }
+}
+
+macro_rules! scalar {
+    ($($ty:ty => $variant:ident, $name:literal;)*) => {$(
+        impl ToCosem for $ty {
+            fn to_cosem(&self) -> CosemDataType {
+                CosemDataType::$variant(*self)
+            }
+        }
+
+        impl FromCosem for $ty {
+            fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+                match value {
+                    CosemDataType::$variant(v) => Ok(*v),
+                    _ => Err(DecodeError::expected($name)),
+                }
+            }
+        }
+    )*};
+}
+
+scalar! {
+    bool => Boolean, "boolean";
+    i8 => Integer, "integer";
+    i16 => Long, "long";
+    u8 => Unsigned, "unsigned";
+    u16 => LongUnsigned, "long-unsigned";
+    i32 => DoubleLong, "double-long";
+    u32 => DoubleLongUnsigned, "double-long-unsigned";
+}
+
+impl<T: ToCosem> ToCosem for Option<T> {
+    fn to_cosem(&self) -> CosemDataType {
+        self.as_ref().map_or(CosemDataType::Null, T::to_cosem)
+    }
+}
+
+impl<T: FromCosem> FromCosem for Option<T> {
+    fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+        match value {
+            CosemDataType::Null => Ok(None),
+            value => T::from_cosem(value).map(Some),
+        }
+    }
+}
+
+impl<T: ToCosem> ToCosem for Vec<T> {
+    fn to_cosem(&self) -> CosemDataType {
+        CosemDataType::Array(self.iter().map(T::to_cosem).collect())
+    }
+}
+
+impl<T: FromCosem> FromCosem for Vec<T> {
+    fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+        match value {
+            CosemDataType::Array(items) => {
+                items.iter().enumerate().map(|(i, item)| T::from_cosem(item).map_err(|e| e.at_index(i))).collect()
+            }
+            _ => Err(DecodeError::expected("array")),
+        }
+    }
+}
+
+impl<T: ToCosem, const N: usize> ToCosem for [T; N] {
+    fn to_cosem(&self) -> CosemDataType {
+        CosemDataType::Array(self.iter().map(T::to_cosem).collect())
+    }
+}
+
+impl<T: FromCosem, const N: usize> FromCosem for [T; N] {
+    fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+        match value {
+            CosemDataType::Array(items) if items.len() == N => {
+                let items = Vec::<T>::from_cosem(value)?;
+                Ok(items.try_into().unwrap_or_else(|_| unreachable!("length checked")))
+            }
+            _ => Err(DecodeError::expected(format!("array({N})"))),
+        }
+    }
+}
+
+macro_rules! tuple {
+    ($len:literal: $($ty:ident $index:tt),+) => {
+        impl<$($ty: ToCosem),+> ToCosem for ($($ty,)+) {
+            fn to_cosem(&self) -> CosemDataType {
+                CosemDataType::Structure(vec![$(self.$index.to_cosem()),+])
+            }
+        }
+
+        impl<$($ty: FromCosem),+> FromCosem for ($($ty,)+) {
+            fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+                let items = structure(value, $len)?;
+                Ok(($(field::<$ty>(items, $index, stringify!($index))?,)+))
+            }
+        }
+    };
+}
+
+tuple!(1: A 0);
+tuple!(2: A 0, B 1);
+tuple!(3: A 0, B 1, C 2);
+tuple!(4: A 0, B 1, C 2, D 3);
+tuple!(5: A 0, B 1, C 2, D 3, E 4);
+tuple!(6: A 0, B 1, C 2, D 3, E 4, F 5);
+tuple!(7: A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple!(8: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+macro_rules! octet_newtype {
+    ($($ty:ty, $len:literal;)*) => {$(
+        impl ToCosem for $ty {
+            fn to_cosem(&self) -> CosemDataType {
+                CosemDataType::OctetString(self.0.to_vec())
+            }
+        }
+
+        impl FromCosem for $ty {
+            fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+                octets::from_cosem::<[u8; $len]>(value).map(Self)
+            }
+        }
+    )*};
+}
+
+octet_newtype! {
+    Date, 5;
+    Time, 4;
+}
+
+impl ToCosem for DateTime {
+    fn to_cosem(&self) -> CosemDataType {
+        self.clone().into()
+    }
+}
+
+impl FromCosem for DateTime {
+    fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+        DateTime::try_from(value).map_err(|_| DecodeError::expected("octet-string(12)"))
+    }
+}
+
+impl ToCosem for ObisCode {
+    fn to_cosem(&self) -> CosemDataType {
+        CosemDataType::OctetString(self.to_bytes().to_vec())
+    }
+}
+
+impl FromCosem for ObisCode {
+    fn from_cosem(value: &CosemDataType) -> Result<Self, DecodeError> {
+        octets::from_cosem::<[u8; 6]>(value).map(ObisCode::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn std_types_round_trip() {
+        let value = (7u16, ObisCode::new(1, 0, 1, 8, 0, 255), Some(-2i8), vec![true, false], [1u32, 2]);
+        let encoded = value.to_cosem();
+        assert_eq!(
+            encoded,
+            CosemDataType::Structure(vec![
+                CosemDataType::LongUnsigned(7),
+                CosemDataType::OctetString(vec![1, 0, 1, 8, 0, 255]),
+                CosemDataType::Integer(-2),
+                CosemDataType::Array(vec![CosemDataType::Boolean(true), CosemDataType::Boolean(false)]),
+                CosemDataType::Array(vec![CosemDataType::DoubleLongUnsigned(1), CosemDataType::DoubleLongUnsigned(2)]),
+            ])
+        );
+        assert_eq!(FromCosem::from_cosem(&encoded), Ok(value));
+        assert_eq!(Option::<u8>::from_cosem(&CosemDataType::Null), Ok(None));
+    }
+
+    #[test]
+    fn decode_errors_carry_the_path() {
+        let value = CosemDataType::Structure(vec![
+            CosemDataType::Unsigned(1),
+            CosemDataType::Array(vec![CosemDataType::OctetString(vec![0; 6]), CosemDataType::OctetString(vec![0; 5])]),
+        ]);
+        let err = <(u8, Vec<ObisCode>)>::from_cosem(&value).unwrap_err();
+        assert_eq!(err.to_string(), "field 1[1]: expected octet-string(6)");
+        assert_eq!(<[u8; 3]>::from_cosem(&CosemDataType::Null).unwrap_err().to_string(), "expected array(3)");
+        assert_eq!(octets::from_cosem::<Vec<u8>>(&CosemDataType::Null).unwrap_err().expected, "octet-string");
+    }
+}
@@ -0,0 +1,433 @@
+//! Compact encoding of COSEM data (IEC 62056-6-2 §4.1.6.1, §4.3.8).
+//!
+//! A [`TypeDescription`] names the type of a value once, so that values of
+//! that type can be encoded without tags: fixed-size types as their content
+//! octets only, octet-strings and bit-strings with their length, arrays and
+//! structures without element counts (the description holds them). Two
+//! encodings use it:
+//!
+//! * **`compact-array`** (tag 19) — the description of the elements followed
+//!   by an octet-string of their contents. [`encode_compact_array`] writes
+//!   one; [`CosemDataType::deserialize_ber`] reads it back as an `array`, so
+//!   large Profile generic buffers can be sent compact without the client
+//!   noticing.
+//! * **Compact data** — the `template_description` attribute holds the
+//!   description of the captured structure and `compact_buffer` its contents;
+//!   [`decode_rows`] turns the two back into values.
+
+use super::{read_length, write_length, BerError, CosemDataType};
+
+/// The `compact-array` tag (19).
+pub const COMPACT_ARRAY: u8 = 0x13;
+
+/// The type of a COSEM value, without its contents (the A-XDR
+/// `TypeDescription`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeDescription {
+    /// `null-data`: no contents.
+    Null,
+    /// `array` of `count` elements of one type.
+    Array {
+        /// Number of elements.
+        count: u16,
+        /// Type of every element.
+        element: Box<TypeDescription>,
+    },
+    /// `structure` of the given element types.
+    Structure(Vec<TypeDescription>),
+    /// `boolean`.
+    Boolean,
+    /// `bit-string`, with a length.
+    BitString,
+    /// `double-long`.
+    DoubleLong,
+    /// `double-long-unsigned`.
+    DoubleLongUnsigned,
+    /// `octet-string`, with a length.
+    OctetString,
+    /// `integer`.
+    Integer,
+    /// `long`.
+    Long,
+    /// `unsigned`.
+    Unsigned,
+    /// `long-unsigned`.
+    LongUnsigned,
+    /// `enum`.
+    Enum,
+    /// `date-time`: 12 octets.
+    DateTime,
+}
+
+impl TypeDescription {
+    /// The description of `value`. Every element of an array must have the
+    /// same description (an empty array has `null-data` elements).
+    ///
+    /// # Errors
+    /// `InvalidValue` for mixed array elements, `InvalidLength` for an array
+    /// of more than 65 535 elements.
+    pub fn of(value: &CosemDataType) -> Result<Self, BerError> {
+        Ok(match value {
+            CosemDataType::Null => TypeDescription::Null,
+            CosemDataType::Array(items) => {
+                let count = u16::try_from(items.len()).map_err(|_| BerError::InvalidLength)?;
+                TypeDescription::Array { count, element: Box::new(Self::of_elements(items)?) }
+            }
+            CosemDataType::Structure(items) => {
+                TypeDescription::Structure(items.iter().map(Self::of).collect::<Result<_, _>>()?)
+            }
+            CosemDataType::Boolean(_) => TypeDescription::Boolean,
+            CosemDataType::BitString(_) => TypeDescription::BitString,
+            CosemDataType::DoubleLong(_) => TypeDescription::DoubleLong,
+            CosemDataType::DoubleLongUnsigned(_) => TypeDescription::DoubleLongUnsigned,
+            CosemDataType::OctetString(_) => TypeDescription::OctetString,
+            CosemDataType::Integer(_) => TypeDescription::Integer,
+            CosemDataType::Long(_) => TypeDescription::Long,
+            CosemDataType::Unsigned(_) => TypeDescription::Unsigned,
+            CosemDataType::LongUnsigned(_) => TypeDescription::LongUnsigned,
+            CosemDataType::Enum(_) => TypeDescription::Enum,
+            CosemDataType::DateTime(_) => TypeDescription::DateTime,
+        })
+    }
+
+    /// The common description of `items` (`null-data` when empty).
+    fn of_elements(items: &[CosemDataType]) -> Result<Self, BerError> {
+        let Some(first) = items.first() else {
+            return Ok(TypeDescription::Null);
+        };
+        let element = Self::of(first)?;
+        for item in &items[1..] {
+            if Self::of(item)? != element {
+                return Err(BerError::InvalidValue);
+            }
+        }
+        Ok(element)
+    }
+
+    /// Appends the A-XDR encoding of the description to `buf`.
+    pub fn serialize(&self, buf: &mut Vec<u8>) {
+        match self {
+            TypeDescription::Array { count, element } => {
+                buf.push(0x01);
+                buf.extend_from_slice(&count.to_be_bytes());
+                element.serialize(buf);
+            }
+            TypeDescription::Structure(elements) => {
+                buf.push(0x02);
+                write_length(elements.len(), buf);
+                for element in elements {
+                    element.serialize(buf);
+                }
+            }
+            simple => buf.push(simple.tag()),
+        }
+    }
+
+    /// Decodes a description from `data`, returning it and the unconsumed
+    /// remainder.
+    pub fn deserialize(data: &[u8]) -> Result<(Self, &[u8]), BerError> {
+        let (&tag, rest) = data.split_first().ok_or(BerError::InvalidTag)?;
+        Ok(match tag {
+            0x00 => (TypeDescription::Null, rest),
+            0x01 => {
+                let count = rest.get(..2).ok_or(BerError::InvalidLength)?;
+                let count = u16::from_be_bytes([count[0], count[1]]);
+                let (element, rest) = Self::deserialize(&rest[2..])?;
+                (TypeDescription::Array { count, element: Box::new(element) }, rest)
+            }
+            0x02 => {
+                let (count, mut rest) = read_length(rest)?;
+                let mut elements = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let (element, next) = Self::deserialize(rest)?;
+                    elements.push(element);
+                    rest = next;
+                }
+                (TypeDescription::Structure(elements), rest)
+            }
+            0x03 => (TypeDescription::Boolean, rest),
+            0x04 => (TypeDescription::BitString, rest),
+            0x05 => (TypeDescription::DoubleLong, rest),
+            0x06 => (TypeDescription::DoubleLongUnsigned, rest),
+            0x09 => (TypeDescription::OctetString, rest),
+            0x0F => (TypeDescription::Integer, rest),
+            0x10 => (TypeDescription::Long, rest),
+            0x11 => (TypeDescription::Unsigned, rest),
+            0x12 => (TypeDescription::LongUnsigned, rest),
+            0x16 => (TypeDescription::Enum, rest),
+            0x19 => (TypeDescription::DateTime, rest),
+            _ => return Err(BerError::InvalidTag),
+        })
+    }
+
+    /// Appends the contents of `value` (no tags, no element counts) to `buf`.
+    ///
+    /// # Errors
+    /// `InvalidValue` when `value` does not have this description.
+    pub fn encode(&self, value: &CosemDataType, buf: &mut Vec<u8>) -> Result<(), BerError> {
+        match (self, value) {
+            (TypeDescription::Null, CosemDataType::Null) => {}
+            (TypeDescription::Array { count, element }, CosemDataType::Array(items))
+                if usize::from(*count) == items.len() =>
+            {
+                for item in items {
+                    element.encode(item, buf)?;
+                }
+            }
+            (TypeDescription::Structure(elements), CosemDataType::Structure(items))
+                if elements.len() == items.len() =>
+            {
+                for (element, item) in elements.iter().zip(items) {
+                    element.encode(item, buf)?;
+                }
+            }
+            (TypeDescription::Boolean, CosemDataType::Boolean(b)) => buf.push(u8::from(*b)),
+            (TypeDescription::BitString, CosemDataType::BitString(bytes))
+            | (TypeDescription::OctetString, CosemDataType::OctetString(bytes)) => {
+                write_length(bytes.len(), buf);
+                buf.extend_from_slice(bytes);
+            }
+            (TypeDescription::DoubleLong, CosemDataType::DoubleLong(v)) => buf.extend_from_slice(&v.to_be_bytes()),
+            (TypeDescription::DoubleLongUnsigned, CosemDataType::DoubleLongUnsigned(v)) => {
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            (TypeDescription::Integer, CosemDataType::Integer(v)) => buf.extend_from_slice(&v.to_be_bytes()),
+            (TypeDescription::Long, CosemDataType::Long(v)) => buf.extend_from_slice(&v.to_be_bytes()),
+            (TypeDescription::Unsigned, CosemDataType::Unsigned(v))
+            | (TypeDescription::Enum, CosemDataType::Enum(v)) => buf.push(*v),
+            (TypeDescription::LongUnsigned, CosemDataType::LongUnsigned(v)) => {
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            (TypeDescription::DateTime, CosemDataType::DateTime(bytes)) if bytes.len() == 12 => {
+                buf.extend_from_slice(bytes);
+            }
+            _ => return Err(BerError::InvalidValue),
+        }
+        Ok(())
+    }
+
+    /// Decodes a value of this description from its contents in `data`,
+    /// returning it and the unconsumed remainder.
+    pub fn decode<'a>(&self, data: &'a [u8]) -> Result<(CosemDataType, &'a [u8]), BerError> {
+        fn take<const N: usize>(data: &[u8]) -> Result<([u8; N], &[u8]), BerError> {
+            let (head, rest) = data.split_at_checked(N).ok_or(BerError::InvalidLength)?;
+            Ok((head.try_into().map_err(|_| BerError::InvalidLength)?, rest))
+        }
+        Ok(match self {
+            TypeDescription::Null => (CosemDataType::Null, data),
+            TypeDescription::Array { count, element } => {
+                // Elements without contents would let a short description
+                // expand into an unbounded value.
+                if *count > 0 && element.is_empty() {
+                    return Err(BerError::InvalidValue);
+                }
+                let mut items = Vec::with_capacity(usize::from(*count).min(data.len()));
+                let mut rest = data;
+                for _ in 0..*count {
+                    let (item, next) = element.decode(rest)?;
+                    items.push(item);
+                    rest = next;
+                }
+                (CosemDataType::Array(items), rest)
+            }
+            TypeDescription::Structure(elements) => {
+                let mut items = Vec::with_capacity(elements.len());
+                let mut rest = data;
+                for element in elements {
+                    let (item, next) = element.decode(rest)?;
+                    items.push(item);
+                    rest = next;
+                }
+                (CosemDataType::Structure(items), rest)
+            }
+            TypeDescription::Boolean => {
+                let ([b], rest) = take::<1>(data)?;
+                (CosemDataType::Boolean(b != 0), rest)
+            }
+            TypeDescription::BitString | TypeDescription::OctetString => {
+                let (len, rest) = read_length(data)?;
+                let (bytes, rest) = rest.split_at_checked(len).ok_or(BerError::InvalidLength)?;
+                let bytes = bytes.to_vec();
+                let value = if *self == TypeDescription::BitString {
+                    CosemDataType::BitString(bytes)
+                } else {
+                    CosemDataType::OctetString(bytes)
+                };
+                (value, rest)
+            }
+            TypeDescription::DoubleLong => {
+                let (v, rest) = take(data)?;
+                (CosemDataType::DoubleLong(i32::from_be_bytes(v)), rest)
+            }
+            TypeDescription::DoubleLongUnsigned => {
+                let (v, rest) = take(data)?;
+                (CosemDataType::DoubleLongUnsigned(u32::from_be_bytes(v)), rest)
+            }
+            TypeDescription::Integer => {
+                let (v, rest) = take(data)?;
+                (CosemDataType::Integer(i8::from_be_bytes(v)), rest)
+            }
+            TypeDescription::Long => {
+                let (v, rest) = take(data)?;
+                (CosemDataType::Long(i16::from_be_bytes(v)), rest)
+            }
+            TypeDescription::Unsigned => {
+                let ([v], rest) = take::<1>(data)?;
+                (CosemDataType::Unsigned(v), rest)
+            }
+            TypeDescription::LongUnsigned => {
+                let (v, rest) = take(data)?;
+                (CosemDataType::LongUnsigned(u16::from_be_bytes(v)), rest)
+            }
+            TypeDescription::Enum => {
+                let ([v], rest) = take::<1>(data)?;
+                (CosemDataType::Enum(v), rest)
+            }
+            TypeDescription::DateTime => {
+                let (v, rest) = take::<12>(data)?;
+                (CosemDataType::DateTime(v.to_vec()), rest)
+            }
+        })
+    }
+
+    /// Whether values of this description have no contents at all.
+    fn is_empty(&self) -> bool {
+        match self {
+            TypeDescription::Null => true,
+            TypeDescription::Array { count, element } => *count == 0 || element.is_empty(),
+            TypeDescription::Structure(elements) => elements.iter().all(Self::is_empty),
+            _ => false,
+        }
+    }
+
+    /// The data tag of a simple type.
+    fn tag(&self) -> u8 {
+        match self {
+            TypeDescription::Null => 0x00,
+            TypeDescription::Array { .. } => 0x01,
+            TypeDescription::Structure(_) => 0x02,
+            TypeDescription::Boolean => 0x03,
+            TypeDescription::BitString => 0x04,
+            TypeDescription::DoubleLong => 0x05,
+            TypeDescription::DoubleLongUnsigned => 0x06,
+            TypeDescription::OctetString => 0x09,
+            TypeDescription::Integer => 0x0F,
+            TypeDescription::Long => 0x10,
+            TypeDescription::Unsigned => 0x11,
+            TypeDescription::LongUnsigned => 0x12,
+            TypeDescription::Enum => 0x16,
+            TypeDescription::DateTime => 0x19,
+        }
+    }
+}
+
+/// Appends `elements` to `buf` as a `compact-array`: tag 19, the description
+/// of the elements and an octet-string of their contents.
+///
+/// # Errors
+/// `InvalidValue` when the elements do not share one description.
+pub fn encode_compact_array(elements: &[CosemDataType], buf: &mut Vec<u8>) -> Result<(), BerError> {
+    let element = TypeDescription::of_elements(elements)?;
+    let mut contents = Vec::new();
+    for item in elements {
+        element.encode(item, &mut contents)?;
+    }
+    buf.push(COMPACT_ARRAY);
+    element.serialize(buf);
+    write_length(contents.len(), buf);
+    buf.extend_from_slice(&contents);
+    Ok(())
+}
+
+/// Decodes the body of a `compact-array` (after its tag) as an `array`,
+/// returning it and the unconsumed remainder.
+pub(crate) fn decode_compact_array(data: &[u8]) -> Result<(CosemDataType, &[u8]), BerError> {
+    let (element, rest) = TypeDescription::deserialize(data)?;
+    let (len, rest) = read_length(rest)?;
+    let (contents, rest) = rest.split_at_checked(len).ok_or(BerError::InvalidLength)?;
+    Ok((CosemDataType::Array(decode_all(&element, contents)?), rest))
+}
+
+/// Decodes a compact data buffer with its `template_description` into rows:
+/// one value of the template per record in `buffer`, in order.
+///
+/// # Errors
+/// A malformed template, or a buffer that does not hold whole records of it.
+pub fn decode_rows(template: &[u8], buffer: &[u8]) -> Result<Vec<CosemDataType>, BerError> {
+    let (description, rest) = TypeDescription::deserialize(template)?;
+    if !rest.is_empty() {
+        return Err(BerError::InvalidLength);
+    }
+    decode_all(&description, buffer)
+}
+
+/// Decodes consecutive values of `description` until `contents` is consumed.
+fn decode_all(description: &TypeDescription, mut contents: &[u8]) -> Result<Vec<CosemDataType>, BerError> {
+    if description.is_empty() && !contents.is_empty() {
+        return Err(BerError::InvalidValue);
+    }
+    let mut values = Vec::new();
+    while !contents.is_empty() {
+        let (value, rest) = description.decode(contents)?;
+        values.push(value);
+        contents = rest;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(time: u8, value: u32) -> CosemDataType {
+        CosemDataType::Structure(vec![
+            CosemDataType::DateTime(vec![0x07, 0xE9, 6, 1, 0xFF, time, 0, 0, 0, 0, 0, 0]),
+            CosemDataType::DoubleLongUnsigned(value),
+            CosemDataType::OctetString(vec![time]),
+        ])
+    }
+
+    #[test]
+    fn compact_array_round_trips_as_array() {
+        let rows = vec![row(0, 10), row(1, 20), row(2, 30)];
+        let mut compact = Vec::new();
+        encode_compact_array(&rows, &mut compact).unwrap();
+        // Tag, structure of three types, then 3 × (12 + 4 + 2) content octets.
+        assert_eq!(&compact[..6], &[0x13, 0x02, 0x03, 0x19, 0x06, 0x09]);
+        assert_eq!(compact[6], 54);
+        let mut full = Vec::new();
+        CosemDataType::Array(rows.clone()).serialize_ber(&mut full).unwrap();
+        assert!(compact.len() < full.len());
+
+        let (decoded, rest) = CosemDataType::deserialize_ber(&compact).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(decoded, CosemDataType::Array(rows));
+
+        let mixed = [CosemDataType::Unsigned(1), CosemDataType::Null];
+        assert_eq!(encode_compact_array(&mixed, &mut Vec::new()), Err(BerError::InvalidValue));
+    }
+
+    #[test]
+    fn template_describes_and_decodes_rows() {
+        let value = CosemDataType::Structure(vec![
+            CosemDataType::LongUnsigned(0x1234),
+            CosemDataType::Array(vec![CosemDataType::Integer(-1), CosemDataType::Integer(2)]),
+            CosemDataType::Boolean(true),
+            CosemDataType::Null,
+        ]);
+        let description = TypeDescription::of(&value).unwrap();
+        let mut template = Vec::new();
+        description.serialize(&mut template);
+        assert_eq!(template, [0x02, 0x04, 0x12, 0x01, 0x00, 0x02, 0x0F, 0x03, 0x00]);
+
+        let mut buffer = Vec::new();
+        description.encode(&value, &mut buffer).unwrap();
+        assert_eq!(buffer, [0x12, 0x34, 0xFF, 0x02, 0x01]);
+        description.encode(&value, &mut buffer).unwrap();
+        assert_eq!(decode_rows(&template, &buffer).unwrap(), [value.clone(), value]);
+
+        // A truncated record and a description that expands from nothing.
+        assert!(decode_rows(&template, &buffer[..7]).is_err());
+        assert_eq!(decode_rows(&[0x01, 0xFF, 0xFF, 0x00], &[0x01]), Err(BerError::InvalidValue));
+    }
+}
@@ -0,0 +1,424 @@
+//! Borrowed, zero-copy view of A-XDR encoded data.
+//!
+//! [`CosemDataRef`] decodes a value in place: octet strings borrow the input
+//! buffer and array/structure elements are decoded lazily while iterating
+//! [`Elements`]. The whole value is validated once by
+//! [`CosemDataRef::deserialize_ber`], so iteration itself cannot fail.
+//! A `compact-array` is read as an `array` whose untagged elements are
+//! decoded from the borrowed type description, as the owned decoder does.
+//! Convert to the owned [`CosemDataType`] only where a value is kept.
+
+use super::compact::COMPACT_ARRAY;
+use super::{read_length, BerError, CosemDataType};
+
+/// A COSEM data value borrowing its encoded form (see [`CosemDataType`]).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CosemDataRef<'a> {
+    /// `null-data` (tag 0).
+    Null,
+    /// `array` (tag 1), or a `compact-array` (tag 19).
+    Array(Elements<'a>),
+    /// `structure` (tag 2).
+    Structure(Elements<'a>),
+    /// `boolean` (tag 3).
+    Boolean(bool),
+    /// `integer` (tag 15).
+    Integer(i8),
+    /// `long` (tag 16).
+    Long(i16),
+    /// `unsigned` (tag 17).
+    Unsigned(u8),
+    /// `long-unsigned` (tag 18).
+    LongUnsigned(u16),
+    /// `double-long` (tag 5).
+    DoubleLong(i32),
+    /// `double-long-unsigned` (tag 6).
+    DoubleLongUnsigned(u32),
+    /// `octet-string` (tag 9).
+    OctetString(&'a [u8]),
+    /// `date-time` (tag 25).
+    DateTime(&'a [u8]),
+    /// `bit-string` (tag 4), as raw octets.
+    BitString(&'a [u8]),
+    /// `enum` (tag 22).
+    Enum(u8),
+}
+
+/// The elements of an array or structure, decoded one at a time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Elements<'a> {
+    remaining: usize,
+    data: &'a [u8],
+    layout: Layout<'a>,
+}
+
+/// How the elements of a container are encoded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Layout<'a> {
+    /// Each element with its tag.
+    Tagged,
+    /// Contents only, typed by the encoded type descriptions of a compact
+    /// array: the one description of every element (`repeat`), or those of
+    /// the remaining structure members in order.
+    Compact { descriptions: &'a [u8], repeat: bool },
+}
+
+impl<'a> Elements<'a> {
+    /// Number of elements not yet iterated.
+    pub fn len(&self) -> usize {
+        self.remaining
+    }
+
+    /// Whether all elements have been iterated.
+    pub fn is_empty(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// The encoded form of the remaining elements (their contents only within
+    /// a `compact-array`).
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = CosemDataRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // Validated when the container was decoded.
+        let (item, rest) = match self.layout {
+            Layout::Tagged => CosemDataRef::deserialize_ber(self.data).ok()?,
+            Layout::Compact { descriptions, repeat } => {
+                let (description, others) = split_description(descriptions).ok()?;
+                if !repeat {
+                    self.layout = Layout::Compact { descriptions: others, repeat };
+                }
+                CosemDataRef::decode_compact(description, self.data).ok()?
+            }
+        };
+        self.remaining -= 1;
+        self.data = rest;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Elements<'_> {}
+
+/// Splits `len` octets off `data`.
+fn take(data: &[u8], len: usize) -> Result<(&[u8], &[u8]), BerError> {
+    if data.len() >= len {
+        Ok(data.split_at(len))
+    } else {
+        Err(BerError::InvalidLength)
+    }
+}
+
+/// Splits one encoded type description off `data`.
+fn split_description(data: &[u8]) -> Result<(&[u8], &[u8]), BerError> {
+    let (&tag, body) = data.split_first().ok_or(BerError::InvalidTag)?;
+    let rest = match tag {
+        0x01 => split_description(body.get(2..).ok_or(BerError::InvalidLength)?)?.1,
+        0x02 => {
+            let (count, mut rest) = read_length(body)?;
+            for _ in 0..count {
+                rest = split_description(rest)?.1;
+            }
+            rest
+        }
+        0x00 | 0x03..=0x06 | 0x09 | 0x0F..=0x12 | 0x16 | 0x19 => body,
+        _ => return Err(BerError::InvalidTag),
+    };
+    Ok(data.split_at(data.len() - rest.len()))
+}
+
+/// Whether values of the type `description` have no contents at all.
+fn is_empty_description(description: &[u8]) -> Result<bool, BerError> {
+    match description.first() {
+        Some(0x00) => Ok(true),
+        Some(0x01) => Ok(description[1..3] == [0, 0] || is_empty_description(&description[3..])?),
+        Some(0x02) => {
+            let (count, mut members) = read_length(&description[1..])?;
+            for _ in 0..count {
+                let (member, next) = split_description(members)?;
+                if !is_empty_description(member)? {
+                    return Ok(false);
+                }
+                members = next;
+            }
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+impl<'a> CosemDataRef<'a> {
+    /// Decodes one A-XDR (BER) value from `data` without copying, returning it
+    /// and the unconsumed remainder. Nested elements are validated but not
+    /// materialised.
+    pub fn deserialize_ber(data: &'a [u8]) -> Result<(Self, &'a [u8]), BerError> {
+        let (&tag, body) = data.split_first().ok_or(BerError::InvalidTag)?;
+        match tag {
+            0x00 => Ok((CosemDataRef::Null, body)),
+            0x01 | 0x02 => {
+                let (count, start) = read_length(body)?;
+                let mut rest = start;
+                for _ in 0..count {
+                    rest = Self::deserialize_ber(rest)?.1;
+                }
+                let elements =
+                    Elements { remaining: count, data: &start[..start.len() - rest.len()], layout: Layout::Tagged };
+                let value = if tag == 0x01 { CosemDataRef::Array(elements) } else { CosemDataRef::Structure(elements) };
+                Ok((value, rest))
+            }
+            0x09 | 0x19 | 0x04 => {
+                let (len, rest) = read_length(body)?;
+                let (octets, rest) = take(rest, len)?;
+                let value = match tag {
+                    0x09 => CosemDataRef::OctetString(octets),
+                    0x19 => CosemDataRef::DateTime(octets),
+                    _ => CosemDataRef::BitString(octets),
+                };
+                Ok((value, rest))
+            }
+            COMPACT_ARRAY => {
+                let (element, rest) = split_description(body)?;
+                let (len, rest) = read_length(rest)?;
+                let (contents, rest) = take(rest, len)?;
+                // Elements without contents would let a short description
+                // expand into an unbounded value.
+                if is_empty_description(element)? && !contents.is_empty() {
+                    return Err(BerError::InvalidValue);
+                }
+                let (mut count, mut left) = (0, contents);
+                while !left.is_empty() {
+                    left = Self::decode_compact(element, left)?.1;
+                    count += 1;
+                }
+                let layout = Layout::Compact { descriptions: element, repeat: true };
+                Ok((CosemDataRef::Array(Elements { remaining: count, data: contents, layout }), rest))
+            }
+            _ => Self::decode_contents(tag, body),
+        }
+    }
+
+    /// Decodes the contents of a fixed-size value of type `tag`.
+    fn decode_contents(tag: u8, body: &'a [u8]) -> Result<(Self, &'a [u8]), BerError> {
+        match tag {
+            0x03 => take(body, 1).map(|(b, rest)| (CosemDataRef::Boolean(b[0] != 0), rest)),
+            // Raw octet round-tripped bit-for-bit, as in `CosemDataType`.
+            #[allow(clippy::cast_possible_wrap)]
+            0x0F => take(body, 1).map(|(b, rest)| (CosemDataRef::Integer(b[0] as i8), rest)),
+            0x10 => take(body, 2).map(|(b, rest)| (CosemDataRef::Long(i16::from_be_bytes([b[0], b[1]])), rest)),
+            0x11 => take(body, 1).map(|(b, rest)| (CosemDataRef::Unsigned(b[0]), rest)),
+            0x12 => take(body, 2).map(|(b, rest)| (CosemDataRef::LongUnsigned(u16::from_be_bytes([b[0], b[1]])), rest)),
+            0x05 => take(body, 4)
+                .map(|(b, rest)| (CosemDataRef::DoubleLong(i32::from_be_bytes([b[0], b[1], b[2], b[3]])), rest)),
+            0x06 => take(body, 4).map(|(b, rest)| {
+                (CosemDataRef::DoubleLongUnsigned(u32::from_be_bytes([b[0], b[1], b[2], b[3]])), rest)
+            }),
+            0x16 => take(body, 1).map(|(b, rest)| (CosemDataRef::Enum(b[0]), rest)),
+            _ => Err(BerError::InvalidTag),
+        }
+    }
+
+    /// Decodes a value of the type `description` from its contents in `data`
+    /// (see [`TypeDescription::decode`](super::compact::TypeDescription::decode)).
+    fn decode_compact(description: &'a [u8], data: &'a [u8]) -> Result<(Self, &'a [u8]), BerError> {
+        let (&tag, body) = description.split_first().ok_or(BerError::InvalidTag)?;
+        match tag {
+            0x00 => Ok((CosemDataRef::Null, data)),
+            0x01 => {
+                let count = usize::from(u16::from_be_bytes([body[0], body[1]]));
+                let element = &body[2..];
+                if count > 0 && is_empty_description(element)? {
+                    return Err(BerError::InvalidValue);
+                }
+                let mut rest = data;
+                for _ in 0..count {
+                    rest = Self::decode_compact(element, rest)?.1;
+                }
+                let layout = Layout::Compact { descriptions: element, repeat: true };
+                let elements = Elements { remaining: count, data: &data[..data.len() - rest.len()], layout };
+                Ok((CosemDataRef::Array(elements), rest))
+            }
+            0x02 => {
+                let (count, members) = read_length(body)?;
+                let (mut descriptions, mut rest) = (members, data);
+                for _ in 0..count {
+                    let (member, next) = split_description(descriptions)?;
+                    rest = Self::decode_compact(member, rest)?.1;
+                    descriptions = next;
+                }
+                let layout = Layout::Compact { descriptions: members, repeat: false };
+                let elements = Elements { remaining: count, data: &data[..data.len() - rest.len()], layout };
+                Ok((CosemDataRef::Structure(elements), rest))
+            }
+            0x04 | 0x09 => {
+                let (len, rest) = read_length(data)?;
+                let (octets, rest) = take(rest, len)?;
+                let value =
+                    if tag == 0x09 { CosemDataRef::OctetString(octets) } else { CosemDataRef::BitString(octets) };
+                Ok((value, rest))
+            }
+            // A date-time is its 12 octets, without a length.
+            0x19 => take(data, 12).map(|(octets, rest)| (CosemDataRef::DateTime(octets), rest)),
+            _ => Self::decode_contents(tag, data),
+        }
+    }
+
+    /// The elements of an array or structure.
+    pub fn elements(&self) -> Option<Elements<'a>> {
+        match self {
+            CosemDataRef::Array(elements) | CosemDataRef::Structure(elements) => Some(*elements),
+            _ => None,
+        }
+    }
+
+    /// The octets of an octet string, date-time or bit string.
+    pub fn octets(&self) -> Option<&'a [u8]> {
+        match self {
+            CosemDataRef::OctetString(octets) | CosemDataRef::DateTime(octets) | CosemDataRef::BitString(octets) => {
+                Some(octets)
+            }
+            _ => None,
+        }
+    }
+
+    /// An integer value of any width (including enum), widened to `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            CosemDataRef::Integer(v) => Some(i64::from(v)),
+            CosemDataRef::Long(v) => Some(i64::from(v)),
+            CosemDataRef::Unsigned(v) | CosemDataRef::Enum(v) => Some(i64::from(v)),
+            CosemDataRef::LongUnsigned(v) => Some(i64::from(v)),
+            CosemDataRef::DoubleLong(v) => Some(i64::from(v)),
+            CosemDataRef::DoubleLongUnsigned(v) => Some(i64::from(v)),
+            _ => None,
+        }
+    }
+
+    /// Copies the value into an owned [`CosemDataType`].
+    pub fn to_owned_value(&self) -> CosemDataType {
+        match *self {
+            CosemDataRef::Null => CosemDataType::Null,
+            CosemDataRef::Array(elements) => CosemDataType::Array(elements.map(|e| e.to_owned_value()).collect()),
+            CosemDataRef::Structure(elements) => {
+                CosemDataType::Structure(elements.map(|e| e.to_owned_value()).collect())
+            }
+            CosemDataRef::Boolean(v) => CosemDataType::Boolean(v),
+            CosemDataRef::Integer(v) => CosemDataType::Integer(v),
+            CosemDataRef::Long(v) => CosemDataType::Long(v),
+            CosemDataRef::Unsigned(v) => CosemDataType::Unsigned(v),
+            CosemDataRef::LongUnsigned(v) => CosemDataType::LongUnsigned(v),
+            CosemDataRef::DoubleLong(v) => CosemDataType::DoubleLong(v),
+            CosemDataRef::DoubleLongUnsigned(v) => CosemDataType::DoubleLongUnsigned(v),
+            CosemDataRef::OctetString(v) => CosemDataType::OctetString(v.to_vec()),
+            CosemDataRef::DateTime(v) => CosemDataType::DateTime(v.to_vec()),
+            CosemDataRef::BitString(v) => CosemDataType::BitString(v.to_vec()),
+            CosemDataRef::Enum(v) => CosemDataType::Enum(v),
+        }
+    }
+}
+
+impl From<CosemDataRef<'_>> for CosemDataType {
+    fn from(value: CosemDataRef<'_>) -> Self {
+        value.to_owned_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::compact::encode_compact_array;
+
+    fn profile_buffer(rows: u32) -> Vec<u8> {
+        let value = CosemDataType::Array(
+            (0..rows)
+                .map(|i| {
+                    CosemDataType::Structure(vec![
+                        CosemDataType::OctetString(vec![0x07, 0xE9, 5, 1, 4, 0, 0, 0, 0, 0, 0, 0]),
+                        CosemDataType::DoubleLongUnsigned(i * 10),
+                        CosemDataType::Long(-1),
+                    ])
+                })
+                .collect(),
+        );
+        let mut buf = Vec::new();
+        value.serialize_ber(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn borrowed_decoding_matches_owned() {
+        let buf = profile_buffer(200);
+        let (owned, owned_rest) = CosemDataType::deserialize_ber(&buf).unwrap();
+        let (view, rest) = CosemDataRef::deserialize_ber(&buf).unwrap();
+        assert_eq!(rest, owned_rest);
+        assert_eq!(view.to_owned_value(), owned);
+
+        let mut rows = view.elements().unwrap();
+        assert_eq!(rows.len(), 200);
+        let row: Vec<_> = rows.nth(3).unwrap().elements().unwrap().collect();
+        assert_eq!(row[0].octets().map(<[u8]>::len), Some(12));
+        assert!(buf.as_ptr_range().contains(&row[0].octets().unwrap().as_ptr()), "octets borrow the input");
+        assert_eq!(row[1].as_i64(), Some(30));
+        assert_eq!(rows.len(), 196);
+    }
+
+    #[test]
+    fn malformed_input_is_rejected_up_front() {
+        let mut buf = profile_buffer(3);
+        buf.pop();
+        assert_eq!(CosemDataRef::deserialize_ber(&buf), Err(BerError::InvalidLength));
+        assert_eq!(CosemDataRef::deserialize_ber(&[0x01, 0x01, 0xEE]), Err(BerError::InvalidTag));
+        assert_eq!(CosemDataRef::deserialize_ber(&[0x09, 0x84, 0xFF, 0xFF, 0xFF, 0xFF]), Err(BerError::InvalidLength));
+    }
+
+    #[test]
+    fn compact_arrays_decode_like_owned() {
+        let rows: Vec<_> = (0..3u32)
+            .map(|i| {
+                CosemDataType::Structure(vec![
+                    CosemDataType::DateTime(vec![0x07, 0xE9, 6, 1, 0xFF, 0, 0, 0, 0, 0, 0, 0]),
+                    CosemDataType::DoubleLongUnsigned(i * 10),
+                    CosemDataType::OctetString(vec![1; i as usize]),
+                    CosemDataType::Array(vec![CosemDataType::Long(-1), CosemDataType::Long(2)]),
+                ])
+            })
+            .collect();
+        let mut buf = Vec::new();
+        encode_compact_array(&rows, &mut buf).unwrap();
+        assert_eq!(buf[0], COMPACT_ARRAY);
+        buf.push(0xAA);
+        let (owned, owned_rest) = CosemDataType::deserialize_ber(&buf).unwrap();
+        let (view, rest) = CosemDataRef::deserialize_ber(&buf).unwrap();
+        assert_eq!((rest, owned_rest), (&[0xAA][..], &[0xAA][..]));
+        assert_eq!(view.to_owned_value(), owned);
+        assert_eq!(owned, CosemDataType::Array(rows));
+
+        let mut elements = view.elements().unwrap();
+        assert_eq!(elements.len(), 3);
+        let row: Vec<_> = elements.nth(2).unwrap().elements().unwrap().collect();
+        assert_eq!(row[1].as_i64(), Some(20));
+        assert!(buf.as_ptr_range().contains(&row[2].octets().unwrap().as_ptr()), "octets borrow the input");
+
+        // Malformed compact arrays fail as the owned decoder fails.
+        for (bad, error) in [
+            (&[0x13, 0x11, 0x05][..], BerError::InvalidLength),
+            (&[0x13, 0x09, 0x01, 0x05], BerError::InvalidLength),
+            (&[0x13, 0x00, 0x01, 0x00], BerError::InvalidValue),
+            (&[0x13, 0xEE, 0x00], BerError::InvalidTag),
+        ] {
+            let owned = CosemDataType::deserialize_ber(bad).map(|(value, _)| value);
+            assert_eq!(CosemDataRef::deserialize_ber(bad).map(|(view, _)| view.to_owned_value()), owned);
+            assert_eq!(owned, Err(error));
+        }
+    }
+}
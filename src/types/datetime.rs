@@ -0,0 +1,702 @@
+//! COSEM date, time and date-time values (IEC 62056-6-2, 4.1.6.1).
+//!
+//! [`Date`] (5 octets), [`Time`] (4 octets) and [`DateTime`] (12 octets) keep
+//! their wire form and interpret it on demand. Any field may be a wildcard
+//! (see [`wildcard`]): a value with wildcards is a *pattern* — "every day at
+//! 00:00", "the last Sunday of March" — rather than an instant, and can be
+//! tested against concrete values with `matches`. Ordering is only defined
+//! between fully specified values.
+//!
+//! СПОДЭС convention: the deviation is the offset of local time from UTC in
+//! minutes (local = UTC + deviation, 180 for Moscow).
+
+use std::cmp::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::types::CosemDataType;
+use serde::{Deserialize, Serialize};
+
+/// Wildcard field values.
+pub mod wildcard {
+    /// Year not specified.
+    pub const YEAR: u16 = 0xFFFF;
+    /// Month, day, day-of-week, hour, minute, second or hundredths not specified.
+    pub const NOT_SPECIFIED: u8 = 0xFF;
+    /// Month: the month daylight saving begins.
+    pub const DAYLIGHT_SAVINGS_BEGIN: u8 = 0xFE;
+    /// Month: the month daylight saving ends.
+    pub const DAYLIGHT_SAVINGS_END: u8 = 0xFD;
+    /// Day of month: the last day of the month.
+    pub const LAST_DAY: u8 = 0xFE;
+    /// Day of month: the second-last day of the month.
+    pub const SECOND_LAST_DAY: u8 = 0xFD;
+    /// Deviation not specified.
+    pub const DEVIATION: i16 = i16::MIN;
+}
+
+/// Clock-status bits (IEC 62056-6-2, 4.1.6.1).
+pub mod clock_status {
+    /// The time could not be recovered after an incident.
+    pub const INVALID_VALUE: u8 = 0x01;
+    /// The time could be recovered but its value is not certain.
+    pub const DOUBTFUL_VALUE: u8 = 0x02;
+    /// The clock base differs from the one in the Clock object.
+    pub const DIFFERENT_CLOCK_BASE: u8 = 0x04;
+    /// The clock-status octet itself is not valid.
+    pub const INVALID_CLOCK_STATUS: u8 = 0x08;
+    /// Daylight saving is active.
+    pub const DAYLIGHT_SAVING_ACTIVE: u8 = 0x80;
+    /// Clock status not specified.
+    pub const NOT_SPECIFIED: u8 = 0xFF;
+}
+
+use wildcard::NOT_SPECIFIED;
+
+/// Number of days in `month` of `year`, or `None` for an invalid month.
+pub fn days_in_month(year: u16, month: u8) -> Option<u8> {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => Some(31),
+        4 | 6 | 9 | 11 => Some(30),
+        2 if leap => Some(29),
+        2 => Some(28),
+        _ => None,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date (H. Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of [`days_from_civil`]: `(year, month, day)` of a day count.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // month/day are 1..=12 / 1..=31
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// COSEM day of week (Monday = 1 .. Sunday = 7) of a day count.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // 1..=7
+fn weekday_from_days(days: i64) -> u8 {
+    // 1970-01-01 was a Thursday.
+    ((days + 3).rem_euclid(7) + 1) as u8
+}
+
+/// Checks one field against its valid range, allowing the wildcard codes in `wildcards`.
+fn check_field(name: &str, value: u8, range: std::ops::RangeInclusive<u8>, wildcards: &[u8]) -> Result<(), String> {
+    if range.contains(&value) || wildcards.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!("invalid {name}: {value:#04x}"))
+    }
+}
+
+/// Date value (5 octets): year (2), month, day of month, day of week.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Date(pub [u8; 5]);
+
+impl Date {
+    /// Creates a Date from raw bytes.
+    pub fn new(bytes: [u8; 5]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the raw bytes.
+    pub fn as_bytes(&self) -> &[u8; 5] {
+        &self.0
+    }
+
+    /// Creates a Date from year, month and day; each may be a wildcard. The day
+    /// of week is computed when the date is fully specified and valid.
+    pub fn from_ymd(year: u16, month: u8, day: u8) -> Self {
+        let mut date = Self([0, 0, month, day, NOT_SPECIFIED]);
+        date.0[0..2].copy_from_slice(&year.to_be_bytes());
+        if let Some(weekday) = date.weekday() {
+            date.0[4] = weekday;
+        }
+        date
+    }
+
+    /// Year (`0xFFFF` if not specified).
+    pub fn year(&self) -> u16 {
+        u16::from_be_bytes([self.0[0], self.0[1]])
+    }
+
+    /// Month (1..=12 or a wildcard).
+    pub fn month(&self) -> u8 {
+        self.0[2]
+    }
+
+    /// Day of month (1..=31 or a wildcard).
+    pub fn day(&self) -> u8 {
+        self.0[3]
+    }
+
+    /// Day of week as encoded (1 = Monday .. 7 = Sunday, or `0xFF`).
+    pub fn day_of_week(&self) -> u8 {
+        self.0[4]
+    }
+
+    /// Days since 1970-01-01, when year, month and day are specified and valid.
+    fn days(&self) -> Option<i64> {
+        let (year, month, day) = (self.year(), self.month(), self.day());
+        if year == wildcard::YEAR || day == 0 || day > days_in_month(year, month)? {
+            return None;
+        }
+        Some(days_from_civil(i64::from(year), month, day))
+    }
+
+    /// Whether year, month and day are all specified (day of week may be not).
+    pub fn is_specified(&self) -> bool {
+        self.days().is_some()
+    }
+
+    /// The day of week computed from the calendar date (1 = Monday .. 7 =
+    /// Sunday), or `None` when the date is not fully specified.
+    pub fn weekday(&self) -> Option<u8> {
+        self.days().map(weekday_from_days)
+    }
+
+    /// Checks every field is in range or a valid wildcard, that a specified day
+    /// exists in its month and that a specified day of week agrees with it.
+    pub fn validate(&self) -> Result<(), String> {
+        use wildcard::*;
+        check_field("month", self.month(), 1..=12, &[DAYLIGHT_SAVINGS_END, DAYLIGHT_SAVINGS_BEGIN, NOT_SPECIFIED])?;
+        check_field("day of month", self.day(), 1..=31, &[SECOND_LAST_DAY, LAST_DAY, NOT_SPECIFIED])?;
+        check_field("day of week", self.day_of_week(), 1..=7, &[NOT_SPECIFIED])?;
+        if self.year() != YEAR && (1..=31).contains(&self.day()) {
+            if let Some(last) = days_in_month(self.year(), self.month()) {
+                if self.day() > last {
+                    return Err(format!("invalid day of month: {}-{:02}-{:02}", self.year(), self.month(), self.day()));
+                }
+            }
+        }
+        match self.weekday() {
+            Some(weekday) if self.day_of_week() != NOT_SPECIFIED && self.day_of_week() != weekday => {
+                Err(format!("day of week {} does not match the date (expected {weekday})", self.day_of_week()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the fully specified `date` matches this pattern. Daylight-saving
+    /// month wildcards never match here; use [`Date::matches_dst`].
+    pub fn matches(&self, date: &Date) -> bool {
+        self.matches_dst(date, None)
+    }
+
+    /// Whether the fully specified `date` matches this pattern, with the
+    /// daylight-saving begin and end months given by `dst` (as in the Clock
+    /// object's `daylight_savings_begin` / `_end`).
+    ///
+    /// Wildcard fields match any value. A specified day of week must equal the
+    /// date's; combined with [`wildcard::LAST_DAY`] (or
+    /// [`wildcard::SECOND_LAST_DAY`]) it selects the last (second-last)
+    /// occurrence of that weekday in the month, e.g. `FFFF 03 FE 07` is the last
+    /// Sunday of March.
+    pub fn matches_dst(&self, date: &Date, dst: Option<(u8, u8)>) -> bool {
+        let Some(days) = date.days() else {
+            return false;
+        };
+        let (year, month, day) = (date.year(), date.month(), date.day());
+        let Some(last) = days_in_month(year, month) else {
+            return false;
+        };
+        let month_matches = match self.month() {
+            NOT_SPECIFIED => true,
+            wildcard::DAYLIGHT_SAVINGS_BEGIN => dst.is_some_and(|(begin, _)| begin == month),
+            wildcard::DAYLIGHT_SAVINGS_END => dst.is_some_and(|(_, end)| end == month),
+            m => m == month,
+        };
+        let weekday = self.day_of_week();
+        let day_matches = match (self.day(), weekday) {
+            (NOT_SPECIFIED, _) => true,
+            (wildcard::LAST_DAY, NOT_SPECIFIED) => day == last,
+            (wildcard::SECOND_LAST_DAY, NOT_SPECIFIED) => day + 1 == last,
+            (wildcard::LAST_DAY, _) => day + 7 > last,
+            (wildcard::SECOND_LAST_DAY, _) => day + 14 > last && day + 7 <= last,
+            (d, _) => d == day,
+        };
+        (self.year() == wildcard::YEAR || self.year() == year)
+            && month_matches
+            && day_matches
+            && (weekday == NOT_SPECIFIED || weekday == weekday_from_days(days))
+    }
+}
+
+impl PartialOrd for Date {
+    /// Orders fully specified dates chronologically; `None` if either has a
+    /// wildcard in year, month or day.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        Some(self.days()?.cmp(&other.days()?).then_with(|| self.0.cmp(&other.0)))
+    }
+}
+
+impl From<Date> for CosemDataType {
+    fn from(date: Date) -> Self {
+        CosemDataType::OctetString(date.0.to_vec())
+    }
+}
+
+impl TryFrom<&CosemDataType> for Date {
+    type Error = String;
+
+    fn try_from(value: &CosemDataType) -> Result<Self, String> {
+        match value {
+            CosemDataType::OctetString(bytes) if bytes.len() == 5 => {
+                let mut buf = [0u8; 5];
+                buf.copy_from_slice(bytes);
+                Ok(Date(buf))
+            }
+            _ => Err("expected 5-byte date octet-string".to_string()),
+        }
+    }
+}
+
+/// Time value (4 octets): hour, minute, second, hundredths.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Time(pub [u8; 4]);
+
+impl Time {
+    /// Creates a Time from raw bytes.
+    pub fn new(bytes: [u8; 4]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the raw bytes.
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+
+    /// Creates a Time from hour, minute and second (each may be `0xFF`);
+    /// hundredths are zero.
+    pub fn from_hms(hour: u8, minute: u8, second: u8) -> Self {
+        Self([hour, minute, second, 0])
+    }
+
+    /// Hour (0..=23 or `0xFF`).
+    pub fn hour(&self) -> u8 {
+        self.0[0]
+    }
+
+    /// Minute (0..=59 or `0xFF`).
+    pub fn minute(&self) -> u8 {
+        self.0[1]
+    }
+
+    /// Second (0..=59 or `0xFF`).
+    pub fn second(&self) -> u8 {
+        self.0[2]
+    }
+
+    /// Hundredths of a second (0..=99 or `0xFF`).
+    pub fn hundredths(&self) -> u8 {
+        self.0[3]
+    }
+
+    /// Seconds since midnight, when hour, minute and second are specified and
+    /// valid; hundredths are ignored.
+    pub fn seconds_of_day(&self) -> Option<u32> {
+        let (hour, minute, second) = (self.hour(), self.minute(), self.second());
+        (hour <= 23 && minute <= 59 && second <= 59)
+            .then(|| u32::from(hour) * 3600 + u32::from(minute) * 60 + u32::from(second))
+    }
+
+    /// Whether hour, minute and second are all specified.
+    pub fn is_specified(&self) -> bool {
+        self.seconds_of_day().is_some()
+    }
+
+    /// Hundredths since midnight, an unspecified hundredths field counting as 0.
+    fn hundredths_of_day(&self) -> Option<u32> {
+        let hundredths = match self.hundredths() {
+            NOT_SPECIFIED => 0,
+            h => u32::from(h),
+        };
+        Some(self.seconds_of_day()? * 100 + hundredths)
+    }
+
+    /// Checks every field is in range or `0xFF`.
+    pub fn validate(&self) -> Result<(), String> {
+        check_field("hour", self.hour(), 0..=23, &[NOT_SPECIFIED])?;
+        check_field("minute", self.minute(), 0..=59, &[NOT_SPECIFIED])?;
+        check_field("second", self.second(), 0..=59, &[NOT_SPECIFIED])?;
+        check_field("hundredths", self.hundredths(), 0..=99, &[NOT_SPECIFIED])
+    }
+
+    /// Whether `time` matches this pattern: every specified field is equal (an
+    /// unspecified hundredths field in `time` counts as 0).
+    pub fn matches(&self, time: &Time) -> bool {
+        self.0.iter().zip(time.0).enumerate().all(|(i, (&pattern, value))| {
+            let value = if i == 3 && value == NOT_SPECIFIED { 0 } else { value };
+            pattern == NOT_SPECIFIED || pattern == value
+        })
+    }
+}
+
+impl PartialOrd for Time {
+    /// Orders fully specified times; `None` if either has a wildcard in hour,
+    /// minute or second.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        Some(self.hundredths_of_day()?.cmp(&other.hundredths_of_day()?).then_with(|| self.0.cmp(&other.0)))
+    }
+}
+
+impl From<Time> for CosemDataType {
+    fn from(time: Time) -> Self {
+        CosemDataType::OctetString(time.0.to_vec())
+    }
+}
+
+impl TryFrom<&CosemDataType> for Time {
+    type Error = String;
+
+    fn try_from(value: &CosemDataType) -> Result<Self, String> {
+        match value {
+            CosemDataType::OctetString(bytes) if bytes.len() == 4 => {
+                let mut buf = [0u8; 4];
+                buf.copy_from_slice(bytes);
+                Ok(Time(buf))
+            }
+            _ => Err("expected 4-byte time octet-string".to_string()),
+        }
+    }
+}
+
+/// Date-time value (12 octets) per IEC 62056-6-2, 4.1.6.1: [`Date`], [`Time`],
+/// deviation (2, signed minutes) and clock status.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DateTime(pub [u8; 12]);
+
+impl DateTime {
+    /// Creates a new DateTime from raw bytes.
+    pub fn new(bytes: [u8; 12]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the raw bytes.
+    pub fn as_bytes(&self) -> &[u8; 12] {
+        &self.0
+    }
+
+    /// Creates a DateTime from year, month, day, hour, minute, second.
+    pub fn from_ymdhms(year: u16, month: u8, day: u8, hour: u8, min: u8, sec: u8) -> Self {
+        let mut buf = [0u8; 12];
+        buf[0..2].copy_from_slice(&year.to_be_bytes());
+        buf[2] = month;
+        buf[3] = day;
+        buf[4] = 0xFF; // day of week (any)
+        buf[5] = hour;
+        buf[6] = min;
+        buf[7] = sec;
+        buf[8] = 0; // hundredths
+        buf[9..12].copy_from_slice(&[0, 0, 0]); // deviation, clocks, reserved
+        Self(buf)
+    }
+
+    /// Assembles a DateTime from its parts. Pass [`wildcard::DEVIATION`] and
+    /// [`clock_status::NOT_SPECIFIED`] for unspecified fields.
+    pub fn from_parts(date: &Date, time: &Time, deviation: i16, clock_status: u8) -> Self {
+        let mut buf = [0u8; 12];
+        buf[0..5].copy_from_slice(&date.0);
+        buf[5..9].copy_from_slice(&time.0);
+        buf[9..11].copy_from_slice(&deviation.to_be_bytes());
+        buf[11] = clock_status;
+        Self(buf)
+    }
+
+    /// Creates a DateTime from seconds since the Unix epoch (UTC), expressed as
+    /// local time with the given `deviation` in minutes (local = UTC + deviation,
+    /// the СПОДЭС convention, e.g. 180 for Moscow). The day of week is computed,
+    /// hundredths and the clock-status octet are zero. `None` when the local
+    /// year falls outside 0..=0xFFFE (0xFFFF is the "not specified" wildcard).
+    pub fn from_unix(secs: i64, deviation: i16) -> Option<Self> {
+        let local = secs.checked_add(i64::from(deviation) * 60)?;
+        let days = local.div_euclid(86_400);
+        let tod = local.rem_euclid(86_400);
+        let (year, month, day) = civil_from_days(days);
+        let year = u16::try_from(year).ok().filter(|&y| y != wildcard::YEAR)?;
+        // Time-of-day fields are range-bounded by construction.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mut dt = Self::from_ymdhms(year, month, day, (tod / 3600) as u8, (tod % 3600 / 60) as u8, (tod % 60) as u8);
+        dt.0[4] = weekday_from_days(days);
+        dt.0[9..11].copy_from_slice(&deviation.to_be_bytes());
+        Some(dt)
+    }
+
+    /// Like [`DateTime::from_unix`] for a [`SystemTime`], keeping hundredths.
+    pub fn from_system_time(time: SystemTime, deviation: i16) -> Option<Self> {
+        let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => (i64::try_from(after.as_secs()).ok()?, after.subsec_nanos()),
+            Err(e) => {
+                let before = e.duration();
+                let secs = -i64::try_from(before.as_secs()).ok()?;
+                match before.subsec_nanos() {
+                    0 => (secs, 0),
+                    n => (secs - 1, 1_000_000_000 - n),
+                }
+            }
+        };
+        let mut dt = Self::from_unix(secs, deviation)?;
+        // nanos < 10^9, so the hundredths are 0..=99.
+        #[allow(clippy::cast_possible_truncation)]
+        let hundredths = (nanos / 10_000_000) as u8;
+        dt.0[8] = hundredths;
+        Some(dt)
+    }
+
+    /// The date part.
+    pub fn date(&self) -> Date {
+        let mut buf = [0u8; 5];
+        buf.copy_from_slice(&self.0[0..5]);
+        Date(buf)
+    }
+
+    /// The time part.
+    pub fn time(&self) -> Time {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(&self.0[5..9]);
+        Time(buf)
+    }
+
+    /// Deviation of local time from UTC in minutes, `None` if not specified.
+    pub fn deviation(&self) -> Option<i16> {
+        match i16::from_be_bytes([self.0[9], self.0[10]]) {
+            wildcard::DEVIATION => None,
+            d => Some(d),
+        }
+    }
+
+    /// Clock-status octet (see [`clock_status`]), `None` if not specified.
+    pub fn clock_status(&self) -> Option<u8> {
+        match self.0[11] {
+            clock_status::NOT_SPECIFIED => None,
+            status => Some(status),
+        }
+    }
+
+    /// Whether the date and time are fully specified (an instant, not a pattern).
+    pub fn is_specified(&self) -> bool {
+        self.to_unix().is_some()
+    }
+
+    /// Checks the date and time fields (see [`Date::validate`]) and that the
+    /// deviation is within ±720 minutes or unspecified.
+    pub fn validate(&self) -> Result<(), String> {
+        self.date().validate()?;
+        self.time().validate()?;
+        match self.deviation() {
+            Some(d) if !(-720..=720).contains(&d) => Err(format!("invalid deviation: {d}")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the instant as seconds since the Unix epoch (UTC), or `None` when
+    /// any of the year..second fields is unspecified or out of range. An
+    /// unspecified deviation (`0x8000`) is taken as UTC; unspecified hundredths
+    /// are ignored.
+    pub fn to_unix(&self) -> Option<i64> {
+        let days = self.date().days()?;
+        let local = days * 86_400 + i64::from(self.time().seconds_of_day()?);
+        Some(local - i64::from(self.deviation().unwrap_or(0)) * 60)
+    }
+
+    /// The instant as a [`SystemTime`], including hundredths.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        let secs = self.to_unix()?;
+        let hundredths = self.time().hundredths_of_day()? % 100;
+        let since_epoch = Duration::from_millis(u64::from(hundredths) * 10);
+        match u64::try_from(secs) {
+            Ok(secs) => UNIX_EPOCH.checked_add(Duration::from_secs(secs) + since_epoch),
+            Err(_) => UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))?.checked_add(since_epoch),
+        }
+    }
+
+    /// The same instant in UTC (deviation 0), keeping hundredths and the
+    /// clock-status octet; `None` when not fully specified.
+    pub fn to_utc(&self) -> Option<Self> {
+        let mut utc = Self::from_unix(self.to_unix()?, 0)?;
+        utc.0[8] = self.0[8];
+        utc.0[11] = self.0[11];
+        Some(utc)
+    }
+
+    /// Returns this instant moved by `secs` seconds, keeping the deviation and
+    /// clock-status octets, or `None` when the date-time is not fully specified
+    /// or the result is out of range.
+    pub fn shifted(&self, secs: i64) -> Option<Self> {
+        // An unspecified deviation: `to_unix` reads the time as UTC.
+        let mut shifted = Self::from_unix(self.to_unix()?.checked_add(secs)?, self.deviation().unwrap_or(0))?;
+        shifted.0[8] = self.0[8];
+        shifted.0[9..12].copy_from_slice(&self.0[9..12]);
+        Some(shifted)
+    }
+
+    /// Whether the fully specified `instant` matches this pattern (see
+    /// [`Date::matches`] and [`Time::matches`]). When both deviations are
+    /// specified and differ, `instant` is first expressed in this pattern's
+    /// deviation.
+    pub fn matches(&self, instant: &DateTime) -> bool {
+        self.matches_dst(instant, None)
+    }
+
+    /// [`DateTime::matches`] with daylight-saving month wildcards resolved by
+    /// `dst` (see [`Date::matches_dst`]).
+    pub fn matches_dst(&self, instant: &DateTime, dst: Option<(u8, u8)>) -> bool {
+        let local = match (self.deviation(), instant.deviation()) {
+            (Some(pattern), Some(actual)) if pattern != actual => match instant.to_unix() {
+                Some(secs) => {
+                    let Some(mut local) = Self::from_unix(secs, pattern) else {
+                        return false;
+                    };
+                    local.0[8] = instant.0[8];
+                    local
+                }
+                None => return false,
+            },
+            _ => instant.clone(),
+        };
+        local.time().is_specified() && self.date().matches_dst(&local.date(), dst) && self.time().matches(&local.time())
+    }
+}
+
+impl PartialOrd for DateTime {
+    /// Orders fully specified date-times by instant (deviation applied, then
+    /// hundredths); `None` if either is not fully specified. Equal instants
+    /// with different encodings are ordered by their bytes, so the order stays
+    /// consistent with equality.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        let key = |dt: &Self| Some((dt.to_unix()?, dt.time().hundredths_of_day()? % 100));
+        Some(key(self)?.cmp(&key(other)?).then_with(|| self.0.cmp(&other.0)))
+    }
+}
+
+impl From<DateTime> for CosemDataType {
+    fn from(dt: DateTime) -> Self {
+        // COSEM date-time on the wire is octet-string SIZE(12), not A-XDR tag 25.
+        CosemDataType::OctetString(dt.0.to_vec())
+    }
+}
+
+impl TryFrom<&CosemDataType> for DateTime {
+    type Error = String;
+
+    fn try_from(value: &CosemDataType) -> Result<Self, String> {
+        match value {
+            CosemDataType::DateTime(bytes) | CosemDataType::OctetString(bytes) if bytes.len() == 12 => {
+                let mut buf = [0u8; 12];
+                buf.copy_from_slice(bytes);
+                Ok(DateTime(buf))
+            }
+            _ => Err("expected 12-byte date-time or octet-string".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_validation_and_weekday() {
+        // 2024-02-29 was a Thursday.
+        let leap = Date::from_ymd(2024, 2, 29);
+        assert_eq!(leap.day_of_week(), 4);
+        assert!(leap.validate().is_ok());
+        assert!(Date::from_ymd(2023, 2, 29).validate().is_err());
+        assert!(Date::new([0x07, 0xE8, 2, 29, 1]).validate().is_err()); // wrong weekday
+        assert!(Date::new([0xFF, 0xFF, 0xFE, 0xFE, 7]).validate().is_ok());
+        assert!(Date::new([0xFF, 0xFF, 13, 1, 0xFF]).validate().is_err());
+        assert!(Time::from_hms(24, 0, 0).validate().is_err());
+        assert!(Time::new([0xFF, 30, 0, 0xFF]).validate().is_ok());
+        assert!(DateTime::from_parts(&leap, &Time::from_hms(0, 0, 0), 900, 0).validate().is_err());
+    }
+
+    #[test]
+    fn date_patterns_match_wildcards() {
+        let last_sunday_of_march = Date::new([0xFF, 0xFF, 3, wildcard::LAST_DAY, 7]);
+        assert!(last_sunday_of_march.matches(&Date::from_ymd(2025, 3, 30)));
+        assert!(!last_sunday_of_march.matches(&Date::from_ymd(2025, 3, 23)));
+        assert!(!last_sunday_of_march.matches(&Date::from_ymd(2025, 3, 31)));
+
+        let month_end = Date::new([0xFF, 0xFF, 0xFF, wildcard::LAST_DAY, 0xFF]);
+        assert!(month_end.matches(&Date::from_ymd(2024, 2, 29)));
+        assert!(!month_end.matches(&Date::from_ymd(2023, 2, 27)));
+        assert!(Date::new([0xFF, 0xFF, 0xFF, wildcard::SECOND_LAST_DAY, 0xFF]).matches(&Date::from_ymd(2023, 2, 27)));
+
+        let dst_begin = Date::new([0xFF, 0xFF, wildcard::DAYLIGHT_SAVINGS_BEGIN, 1, 0xFF]);
+        assert!(!dst_begin.matches(&Date::from_ymd(2025, 3, 1)));
+        assert!(dst_begin.matches_dst(&Date::from_ymd(2025, 3, 1), Some((3, 10))));
+
+        let mondays_2025 = Date::new([0x07, 0xE9, 0xFF, 0xFF, 1]);
+        assert!(mondays_2025.matches(&Date::from_ymd(2025, 5, 5)));
+        assert!(!mondays_2025.matches(&Date::from_ymd(2025, 5, 6)));
+        assert!(!mondays_2025.matches(&Date::new([0xFF, 0xFF, 5, 5, 1])), "patterns match only concrete dates");
+    }
+
+    #[test]
+    fn datetime_matching_and_ordering_use_the_instant() {
+        let daily_midnight = DateTime::new([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0xFF, 0x80, 0x00, 0xFF]);
+        assert!(daily_midnight.matches(&DateTime::from_unix(1_746_057_600, 0).unwrap()));
+        assert!(!daily_midnight.matches(&DateTime::from_unix(1_746_057_601, 0).unwrap()));
+
+        // Midnight Moscow time is 21:00 UTC the day before.
+        let mut msk_midnight = daily_midnight.clone();
+        msk_midnight.0[9..11].copy_from_slice(&180i16.to_be_bytes());
+        assert!(msk_midnight.matches(&DateTime::from_unix(1_746_057_600 - 3 * 3600, 0).unwrap()));
+
+        let utc = DateTime::from_unix(1_746_117_000, 0).unwrap();
+        let msk = DateTime::from_unix(1_746_117_000, 180).unwrap();
+        assert_ne!(utc, msk);
+        assert_eq!(msk.to_utc().map(|t| t.to_unix()), Some(Some(1_746_117_000)));
+        assert!(DateTime::from_unix(1_746_117_001, 0).unwrap() > msk);
+        assert!(utc.partial_cmp(&msk).is_some());
+        assert_eq!(daily_midnight.partial_cmp(&utc), None);
+    }
+
+    #[test]
+    fn system_time_round_trip_keeps_hundredths() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_746_117_000_250);
+        let dt = DateTime::from_system_time(time, 180).unwrap();
+        assert_eq!(dt.time().hundredths(), 25);
+        assert_eq!(dt.to_system_time(), Some(time));
+        let before_epoch = UNIX_EPOCH - Duration::from_millis(1500);
+        assert_eq!(DateTime::from_system_time(before_epoch, 0).unwrap().to_system_time(), Some(before_epoch));
+    }
+
+    #[test]
+    fn out_of_range_instants_are_rejected() {
+        assert_eq!(DateTime::from_unix(-62_200_000_000, 0), None);
+        let last = DateTime::from_ymdhms(0xFFFE, 12, 31, 23, 59, 59).to_unix().unwrap();
+        assert_eq!(DateTime::from_unix(last, 0).map(|dt| dt.date().year()), Some(0xFFFE));
+        assert_eq!(DateTime::from_unix(last + 1, 0), None);
+        assert_eq!(DateTime::from_unix(i64::MAX, 180), None);
+        let now = DateTime::from_unix(1_746_117_000, 180).unwrap();
+        assert_eq!(now.shifted(i64::MAX), None);
+        assert_eq!(now.shifted(i64::MIN), None);
+    }
+}
@@ -0,0 +1,140 @@
+//! Streaming A-XDR encoder.
+//!
+//! [`BerEncoder`] writes values straight to an [`io::Write`] sink. An array
+//! or structure is opened with its element count and its elements follow
+//! one by one, so a large profile buffer can be encoded row by row without
+//! building the whole [`CosemDataType`] tree. The encoder checks that every
+//! opened container receives exactly the declared number of elements.
+
+use std::io::{self, Write};
+
+use super::{write_length, CosemDataType};
+
+/// Writes A-XDR encoded values to a sink.
+#[derive(Debug)]
+pub struct BerEncoder<W: Write> {
+    sink: W,
+    /// Elements still expected by each open container, innermost last.
+    open: Vec<usize>,
+    /// Reused encoding buffer.
+    scratch: Vec<u8>,
+}
+
+impl<W: Write> BerEncoder<W> {
+    /// Creates an encoder writing to `sink`.
+    pub fn new(sink: W) -> Self {
+        BerEncoder { sink, open: Vec::new(), scratch: Vec::new() }
+    }
+
+    /// Opens an array of `count` elements.
+    pub fn begin_array(&mut self, count: usize) -> io::Result<()> {
+        self.begin(0x01, count)
+    }
+
+    /// Opens a structure of `count` elements.
+    pub fn begin_structure(&mut self, count: usize) -> io::Result<()> {
+        self.begin(0x02, count)
+    }
+
+    /// Writes a complete value (an element of the innermost open container).
+    pub fn value(&mut self, value: &CosemDataType) -> io::Result<()> {
+        self.element();
+        self.scratch.clear();
+        value
+            .serialize_ber(&mut self.scratch)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("{e:?}")))?;
+        self.sink.write_all(&self.scratch)
+    }
+
+    /// Writes an already encoded value verbatim (e.g. one element of a
+    /// [`CosemDataRef`](super::CosemDataRef) view, via
+    /// [`Elements::as_bytes`](super::Elements::as_bytes)).
+    pub fn raw(&mut self, encoded: &[u8]) -> io::Result<()> {
+        self.element();
+        self.sink.write_all(encoded)
+    }
+
+    /// Number of containers still open.
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Flushes and returns the sink; fails if a container is incomplete.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(missing) = self.open.last() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{missing} element(s) missing in an open array/structure"),
+            ));
+        }
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+
+    fn begin(&mut self, tag: u8, count: usize) -> io::Result<()> {
+        self.element();
+        self.scratch.clear();
+        self.scratch.push(tag);
+        write_length(count, &mut self.scratch);
+        self.sink.write_all(&self.scratch)?;
+        self.open.push(count);
+        self.close_completed();
+        Ok(())
+    }
+
+    /// Accounts for one element in the innermost open container.
+    fn element(&mut self) {
+        if let Some(remaining) = self.open.last_mut() {
+            // Completed containers are closed at once, so `remaining` is > 0.
+            *remaining -= 1;
+            self.close_completed();
+        }
+    }
+
+    /// Pops the containers that have received all their elements.
+    fn close_completed(&mut self) {
+        while self.open.last() == Some(&0) {
+            self.open.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streamed_encoding_matches_tree_encoding() {
+        let row = |i: u32| CosemDataType::Structure(vec![CosemDataType::DoubleLongUnsigned(i), CosemDataType::Enum(1)]);
+        let tree = CosemDataType::Array((0..300).map(row).collect());
+        let mut expected = Vec::new();
+        tree.serialize_ber(&mut expected).unwrap();
+
+        let mut encoder = BerEncoder::new(Vec::new());
+        encoder.begin_array(300).unwrap();
+        for i in 0..300 {
+            if i % 2 == 0 {
+                encoder.value(&row(i)).unwrap();
+            } else {
+                encoder.begin_structure(2).unwrap();
+                encoder.value(&CosemDataType::DoubleLongUnsigned(i)).unwrap();
+                encoder.value(&CosemDataType::Enum(1)).unwrap();
+            }
+        }
+        assert_eq!(encoder.depth(), 0);
+        assert_eq!(encoder.finish().unwrap(), expected);
+    }
+
+    #[test]
+    fn incomplete_container_is_an_error() {
+        let mut encoder = BerEncoder::new(Vec::new());
+        encoder.begin_array(2).unwrap();
+        encoder.value(&CosemDataType::Null).unwrap();
+        assert_eq!(encoder.finish().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+
+        let mut encoder = BerEncoder::new(Vec::new());
+        encoder.begin_structure(0).unwrap();
+        encoder.value(&CosemDataType::Null).unwrap();
+        assert_eq!(encoder.finish().unwrap(), vec![0x02, 0x00, 0x00]);
+    }
+}
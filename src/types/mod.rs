@@ -1,4 +1,14 @@
 pub mod attrs;
+pub mod codec;
+pub mod compact;
+pub mod data_ref;
+pub mod datetime;
+pub mod encoder;
+pub mod unit;
+
+pub use data_ref::{CosemDataRef, Elements};
+pub use encoder::BerEncoder;
+pub use unit::Unit;
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -143,7 +153,7 @@ impl CosemDataType {
     }
 
     /// Decodes one A-XDR (BER) value from `data`, returning it and the
-    /// unconsumed remainder.
+    /// unconsumed remainder. A `compact-array` decodes to an `array`.
     pub fn deserialize_ber(data: &[u8]) -> Result<(Self, &[u8]), BerError> {
         if data.is_empty() {
             return Err(BerError::InvalidTag);
@@ -257,9 +267,45 @@ impl CosemDataType {
                     Err(BerError::InvalidLength)
                 }
             }
+            // compact-array [19]: read back as an array.
+            compact::COMPACT_ARRAY => compact::decode_compact_array(&data[1..]),
             _ => Err(BerError::InvalidTag),
         }
     }
+
+    /// An integer value of any width (including enum), widened to `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            CosemDataType::Integer(v) => Some(i64::from(v)),
+            CosemDataType::Long(v) => Some(i64::from(v)),
+            CosemDataType::Unsigned(v) | CosemDataType::Enum(v) => Some(i64::from(v)),
+            CosemDataType::LongUnsigned(v) => Some(i64::from(v)),
+            CosemDataType::DoubleLong(v) => Some(i64::from(v)),
+            CosemDataType::DoubleLongUnsigned(v) => Some(i64::from(v)),
+            _ => None,
+        }
+    }
+
+    /// `value` as an integer of the same type as `self` (e.g. a register's
+    /// current value), saturating at the type's range; `None` when `self` is
+    /// not an integer.
+    pub fn integer_like(&self, value: i64) -> Option<Self> {
+        fn saturate<T: TryFrom<i64> + Copy>(value: i64, min: T, max: T) -> T {
+            T::try_from(value).unwrap_or(if value < 0 { min } else { max })
+        }
+        Some(match self {
+            CosemDataType::Integer(_) => CosemDataType::Integer(saturate(value, i8::MIN, i8::MAX)),
+            CosemDataType::Long(_) => CosemDataType::Long(saturate(value, i16::MIN, i16::MAX)),
+            CosemDataType::Unsigned(_) => CosemDataType::Unsigned(saturate(value, u8::MIN, u8::MAX)),
+            CosemDataType::Enum(_) => CosemDataType::Enum(saturate(value, u8::MIN, u8::MAX)),
+            CosemDataType::LongUnsigned(_) => CosemDataType::LongUnsigned(saturate(value, u16::MIN, u16::MAX)),
+            CosemDataType::DoubleLong(_) => CosemDataType::DoubleLong(saturate(value, i32::MIN, i32::MAX)),
+            CosemDataType::DoubleLongUnsigned(_) => {
+                CosemDataType::DoubleLongUnsigned(saturate(value, u32::MIN, u32::MAX))
+            }
+            _ => return None,
+        })
+    }
 }
 
 /// Writes a BER/A-XDR length octet (short or long form).
@@ -352,6 +398,17 @@ mod tests {
         buf
     }
 
+    #[test]
+    fn integer_values_widen_and_narrow() {
+        assert_eq!(CosemDataType::Long(-5).as_i64(), Some(-5));
+        assert_eq!(CosemDataType::DoubleLongUnsigned(u32::MAX).as_i64(), Some(i64::from(u32::MAX)));
+        assert_eq!(CosemDataType::OctetString(vec![]).as_i64(), None);
+        assert_eq!(CosemDataType::LongUnsigned(0).integer_like(70_000), Some(CosemDataType::LongUnsigned(u16::MAX)));
+        assert_eq!(CosemDataType::Unsigned(0).integer_like(-1), Some(CosemDataType::Unsigned(0)));
+        assert_eq!(CosemDataType::DoubleLong(0).integer_like(-7), Some(CosemDataType::DoubleLong(-7)));
+        assert_eq!(CosemDataType::Null.integer_like(1), None);
+    }
+
     /// A crafted long-form length must not overflow or allocate: more than
     /// 4 length octets, or a declared length beyond the buffer, is rejected.
     #[test]
@@ -364,6 +421,17 @@ mod tests {
         assert!(CosemDataType::deserialize_ber(&oversized).is_err());
     }
 
+    /// A compact-array whose short-form lengths run past the end of the data
+    /// is rejected instead of panicking.
+    #[test]
+    fn crafted_compact_array_length_is_rejected() {
+        // Contents declared as 5 octets, none present.
+        assert_eq!(CosemDataType::deserialize_ber(&[0x13, 0x11, 0x05]), Err(BerError::InvalidLength));
+        // Octet-string elements whose length exceeds the contents.
+        assert_eq!(CosemDataType::deserialize_ber(&[0x13, 0x09, 0x01, 0x05]), Err(BerError::InvalidLength));
+        assert_eq!(compact::decode_rows(&[0x09], &[0x05, 0x01]), Err(BerError::InvalidLength));
+    }
+
     /// Reference A-XDR vector from DLMS UA 1000-1 (context_name encoding example,
     /// context_id(1)): a 7-element structure → 02 07 11 02 11 10 12 02 F4 11 05 11 08 11 01 11 01.
     #[test]
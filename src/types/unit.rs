@@ -0,0 +1,306 @@
+//! Physical units of COSEM register values (IEC 62056-6-2, Table 42).
+//!
+//! A `scaler_unit` attribute carries the unit as a bare enum code; [`Unit`]
+//! names those codes and gives their conventional symbols, and
+//! [`format_scaled`] renders a raw register value with the scaler applied.
+
+use super::attrs::ScalerUnit;
+use super::CosemDataType;
+use std::fmt;
+
+macro_rules! units {
+    ($($(#[$doc:meta])* $variant:ident = $code:literal, $symbol:literal, $name:literal;)*) => {
+        /// A unit code of IEC 62056-6-2, Table 42.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Unit {
+            $($(#[$doc])* $variant,)*
+            /// A code that Table 42 leaves reserved.
+            Reserved(u8),
+        }
+
+        impl Unit {
+            /// The unit of a Table 42 code.
+            pub fn from_code(code: u8) -> Unit {
+                match code {
+                    $($code => Unit::$variant,)*
+                    other => Unit::Reserved(other),
+                }
+            }
+
+            /// The Table 42 code.
+            pub fn code(self) -> u8 {
+                match self {
+                    $(Unit::$variant => $code,)*
+                    Unit::Reserved(code) => code,
+                }
+            }
+
+            /// The conventional symbol (`"Wh"`, `"°C"`); empty for
+            /// [`Unit::Count`], [`Unit::Other`] and reserved codes.
+            pub fn symbol(self) -> &'static str {
+                match self {
+                    $(Unit::$variant => $symbol,)*
+                    Unit::Reserved(_) => "",
+                }
+            }
+
+            /// The quantity the unit measures, as worded in Table 42.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Unit::$variant => $name,)*
+                    Unit::Reserved(_) => "reserved",
+                }
+            }
+        }
+    };
+}
+
+units! {
+    /// `a` — time, year.
+    Year = 1, "a", "year";
+    /// `mo` — time, month.
+    Month = 2, "mo", "month";
+    /// `wk` — time, week.
+    Week = 3, "wk", "week";
+    /// `d` — time, day.
+    Day = 4, "d", "day";
+    /// `h` — time, hour.
+    Hour = 5, "h", "hour";
+    /// `min` — time, minute.
+    Minute = 6, "min", "minute";
+    /// `s` — time, second.
+    Second = 7, "s", "second";
+    /// `°` — phase angle, degree.
+    Degree = 8, "°", "phase angle";
+    /// `°C` — temperature.
+    DegreeCelsius = 9, "°C", "temperature";
+    /// Local currency.
+    Currency = 10, "currency", "local currency";
+    /// `m` — length.
+    Metre = 11, "m", "length";
+    /// `m/s` — speed.
+    MetrePerSecond = 12, "m/s", "speed";
+    /// `m³` — volume.
+    CubicMetre = 13, "m³", "volume";
+    /// `m³` — corrected volume.
+    CorrectedCubicMetre = 14, "m³", "corrected volume";
+    /// `m³/h` — volume flux.
+    CubicMetrePerHour = 15, "m³/h", "volume flux";
+    /// `m³/h` — corrected volume flux.
+    CorrectedCubicMetrePerHour = 16, "m³/h", "corrected volume flux";
+    /// `m³/d` — volume flux.
+    CubicMetrePerDay = 17, "m³/d", "volume flux";
+    /// `m³/d` — corrected volume flux.
+    CorrectedCubicMetrePerDay = 18, "m³/d", "corrected volume flux";
+    /// `l` — volume.
+    Litre = 19, "l", "volume";
+    /// `kg` — mass.
+    Kilogram = 20, "kg", "mass";
+    /// `N` — force.
+    Newton = 21, "N", "force";
+    /// `Nm` — energy.
+    NewtonMetre = 22, "Nm", "energy";
+    /// `Pa` — pressure.
+    Pascal = 23, "Pa", "pressure";
+    /// `bar` — pressure.
+    Bar = 24, "bar", "pressure";
+    /// `J` — energy.
+    Joule = 25, "J", "energy";
+    /// `J/h` — thermal power.
+    JoulePerHour = 26, "J/h", "thermal power";
+    /// `W` — active power.
+    Watt = 27, "W", "active power";
+    /// `VA` — apparent power.
+    VoltAmpere = 28, "VA", "apparent power";
+    /// `var` — reactive power.
+    Var = 29, "var", "reactive power";
+    /// `Wh` — active energy.
+    WattHour = 30, "Wh", "active energy";
+    /// `VAh` — apparent energy.
+    VoltAmpereHour = 31, "VAh", "apparent energy";
+    /// `varh` — reactive energy.
+    VarHour = 32, "varh", "reactive energy";
+    /// `A` — current.
+    Ampere = 33, "A", "current";
+    /// `C` — electrical charge.
+    Coulomb = 34, "C", "electrical charge";
+    /// `V` — voltage.
+    Volt = 35, "V", "voltage";
+    /// `V/m` — electric field strength.
+    VoltPerMetre = 36, "V/m", "electric field strength";
+    /// `F` — capacitance.
+    Farad = 37, "F", "capacitance";
+    /// `Ω` — resistance.
+    Ohm = 38, "Ω", "resistance";
+    /// `Ωm²/m` — resistivity.
+    OhmSquareMetrePerMetre = 39, "Ωm²/m", "resistivity";
+    /// `Wb` — magnetic flux.
+    Weber = 40, "Wb", "magnetic flux";
+    /// `T` — magnetic flux density.
+    Tesla = 41, "T", "magnetic flux density";
+    /// `A/m` — magnetic field strength.
+    AmperePerMetre = 42, "A/m", "magnetic field strength";
+    /// `H` — inductance.
+    Henry = 43, "H", "inductance";
+    /// `Hz` — frequency.
+    Hertz = 44, "Hz", "frequency";
+    /// `1/(Wh)` — active energy meter constant.
+    PerWattHour = 45, "1/(Wh)", "active energy meter constant";
+    /// `1/(varh)` — reactive energy meter constant.
+    PerVarHour = 46, "1/(varh)", "reactive energy meter constant";
+    /// `1/(VAh)` — apparent energy meter constant.
+    PerVoltAmpereHour = 47, "1/(VAh)", "apparent energy meter constant";
+    /// `V²h` — volt-squared hours.
+    VoltSquaredHour = 48, "V²h", "volt-squared hours";
+    /// `A²h` — ampere-squared hours.
+    AmpereSquaredHour = 49, "A²h", "ampere-squared hours";
+    /// `kg/s` — mass flux.
+    KilogramPerSecond = 50, "kg/s", "mass flux";
+    /// `S` — conductance.
+    Siemens = 51, "S", "conductance";
+    /// `K` — temperature.
+    Kelvin = 52, "K", "temperature";
+    /// `1/(V²h)` — volt-squared hour meter constant.
+    PerVoltSquaredHour = 53, "1/(V²h)", "volt-squared hour meter constant";
+    /// `1/(A²h)` — ampere-squared hour meter constant.
+    PerAmpereSquaredHour = 54, "1/(A²h)", "ampere-squared hour meter constant";
+    /// `1/m³` — volume meter constant.
+    PerCubicMetre = 55, "1/m³", "volume meter constant";
+    /// `%` — percentage.
+    Percent = 56, "%", "percentage";
+    /// `Ah` — ampere-hours.
+    AmpereHour = 57, "Ah", "ampere-hours";
+    /// `Wh/m³` — energy per volume.
+    WattHourPerCubicMetre = 60, "Wh/m³", "energy per volume";
+    /// `J/m³` — calorific value.
+    JoulePerCubicMetre = 61, "J/m³", "calorific value";
+    /// `mol %` — molar fraction.
+    MolePercent = 62, "mol %", "molar fraction";
+    /// `g/m³` — mass density.
+    GramPerCubicMetre = 63, "g/m³", "mass density";
+    /// `Pa s` — dynamic viscosity.
+    PascalSecond = 64, "Pa s", "dynamic viscosity";
+    /// `J/kg` — specific energy.
+    JoulePerKilogram = 65, "J/kg", "specific energy";
+    /// `g/cm²` — pressure.
+    GramPerSquareCentimetre = 66, "g/cm²", "pressure";
+    /// `atm` — pressure.
+    Atmosphere = 67, "atm", "pressure";
+    /// `dBm` — signal strength.
+    DecibelMilliwatt = 70, "dBm", "signal strength";
+    /// `dBµV` — signal strength.
+    DecibelMicrovolt = 71, "dBµV", "signal strength";
+    /// `dB` — logarithmic unit.
+    Decibel = 72, "dB", "logarithmic unit";
+    /// Other unit.
+    Other = 254, "", "other unit";
+    /// No unit, unitless, count.
+    Count = 255, "", "count";
+}
+
+impl From<u8> for Unit {
+    fn from(code: u8) -> Self {
+        Unit::from_code(code)
+    }
+}
+
+impl From<Unit> for u8 {
+    fn from(unit: Unit) -> Self {
+        unit.code()
+    }
+}
+
+impl fmt::Display for Unit {
+    /// The symbol, or the quantity name for units without one.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.symbol() {
+            "" => f.write_str(self.name()),
+            symbol => f.write_str(symbol),
+        }
+    }
+}
+
+/// Renders `raw × 10^scaler` exactly in decimal, followed by the unit symbol
+/// when the unit has one: `format_scaled(123456, -3, Unit::WattHour)` is
+/// `"123.456 Wh"`.
+pub fn format_scaled(raw: i64, scaler: i8, unit: Unit) -> String {
+    let digits = raw.unsigned_abs().to_string();
+    let sign = if raw < 0 { "-" } else { "" };
+    let number = if scaler >= 0 {
+        if raw == 0 {
+            digits
+        } else {
+            format!("{digits}{}", "0".repeat(usize::from(scaler.unsigned_abs())))
+        }
+    } else {
+        let places = usize::from(scaler.unsigned_abs());
+        let padded = format!("{digits:0>width$}", width = places + 1);
+        let (whole, fraction) = padded.split_at(padded.len() - places);
+        format!("{whole}.{fraction}")
+    };
+    match unit.symbol() {
+        "" => format!("{sign}{number}"),
+        symbol => format!("{sign}{number} {symbol}"),
+    }
+}
+
+impl ScalerUnit {
+    /// The unit code as a [`Unit`].
+    pub fn unit_kind(&self) -> Unit {
+        Unit::from_code(self.unit)
+    }
+
+    /// Renders a raw register value with the scaler applied and the unit
+    /// symbol appended (see [`format_scaled`]).
+    pub fn format(&self, raw: i64) -> String {
+        format_scaled(raw, self.scaler, self.unit_kind())
+    }
+
+    /// Renders a register `value` attribute, or `None` when it is not an
+    /// integer of any width.
+    pub fn format_value(&self, value: &CosemDataType) -> Option<String> {
+        let raw = match *value {
+            CosemDataType::Integer(v) => i64::from(v),
+            CosemDataType::Long(v) => i64::from(v),
+            CosemDataType::Unsigned(v) | CosemDataType::Enum(v) => i64::from(v),
+            CosemDataType::LongUnsigned(v) => i64::from(v),
+            CosemDataType::DoubleLong(v) => i64::from(v),
+            CosemDataType::DoubleLongUnsigned(v) => i64::from(v),
+            _ => return None,
+        };
+        Some(self.format(raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        for code in 0..=u8::MAX {
+            assert_eq!(Unit::from_code(code).code(), code);
+        }
+        assert_eq!(Unit::from_code(30), Unit::WattHour);
+        assert_eq!(Unit::from_code(58), Unit::Reserved(58));
+        assert_eq!(Unit::Var.symbol(), "var");
+        assert_eq!(Unit::Count.to_string(), "count");
+        assert_eq!(Unit::Ohm.to_string(), "Ω");
+    }
+
+    #[test]
+    fn scaled_values_render_exactly() {
+        assert_eq!(format_scaled(123_456, -3, Unit::WattHour), "123.456 Wh");
+        assert_eq!(format_scaled(5, -3, Unit::Ampere), "0.005 A");
+        assert_eq!(format_scaled(-5, -1, Unit::Watt), "-0.5 W");
+        assert_eq!(format_scaled(2301, 0, Unit::Volt), "2301 V");
+        assert_eq!(format_scaled(12, 2, Unit::Count), "1200");
+        assert_eq!(format_scaled(0, 3, Unit::Hertz), "0 Hz");
+        assert_eq!(format_scaled(i64::MIN, -2, Unit::Count), "-92233720368547758.08");
+
+        let su = ScalerUnit::new(-2, 35);
+        assert_eq!(su.unit_kind(), Unit::Volt);
+        assert_eq!(su.format_value(&CosemDataType::LongUnsigned(23012)).as_deref(), Some("230.12 V"));
+        assert_eq!(su.format_value(&CosemDataType::OctetString(vec![1])), None);
+    }
+}
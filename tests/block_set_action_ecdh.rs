@@ -9,7 +9,7 @@ use spodes_rs::classes::association_ln::{
 };
 use spodes_rs::classes::data::Data;
 use spodes_rs::classes::register::Register;
-use spodes_rs::interface::InterfaceClass;
+use spodes_rs::interface::{AccessError, InterfaceClass};
 use spodes_rs::obis::ObisCode;
 use spodes_rs::security::{gost3410, SecuritySuite};
 use spodes_rs::server::RequestDispatcher;
@@ -54,21 +54,25 @@ impl InterfaceClass for WritableData {
     fn deserialize_ber(&mut self, _data: &[u8]) -> Result<(), BerError> {
         Ok(())
     }
-    fn invoke_method(&mut self, _method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, String> {
-        Err("no methods".to_string())
+    fn invoke_method(&mut self, _method_id: u8, _params: Option<CosemDataType>) -> Result<CosemDataType, AccessError> {
+        Err(AccessError::ObjectUnavailable("no methods".to_string()))
     }
-    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), String> {
+    fn set_attribute(&mut self, attribute_id: u8, value: CosemDataType) -> Result<(), AccessError> {
         match attribute_id {
             2 => {
                 self.value = value;
                 Ok(())
             }
-            _ => Err("attribute not writable".to_string()),
+            _ => Err(AccessError::ReadWriteDenied("attribute not writable".to_string())),
         }
     }
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -128,7 +132,7 @@ fn test_set_writable_data_attribute() {
 
     // WritableData supports SET on attribute 2.
     let obis = ObisCode::new(0, 0, 96, 1, 0, 0xFF);
-    let result = session.set(1, obis.clone(), 2, CosemDataType::OctetString(b"new_value".to_vec()));
+    let result = session.set(1, obis, 2, CosemDataType::OctetString(b"new_value".to_vec()));
     assert!(result.is_ok());
 
     // Value should be updated.
@@ -151,7 +155,7 @@ fn test_set_data_attribute_updates_value() {
     let mut session = ClientSession::new(link);
 
     let obis = ObisCode::new(0, 0, 96, 1, 0, 0xFF);
-    let result = session.set(1, obis.clone(), 2, CosemDataType::OctetString(b"new_value".to_vec()));
+    let result = session.set(1, obis, 2, CosemDataType::OctetString(b"new_value".to_vec()));
     assert!(result.is_ok());
 
     let value = match session.get(1, obis, 2) {
@@ -175,7 +179,7 @@ fn test_set_register_updates_value() {
 
     // Register attribute 2 (value) is writable.
     let obis = ObisCode::new(1, 0, 1, 8, 0, 0xFF);
-    let result = session.set(3, obis.clone(), 2, CosemDataType::DoubleLongUnsigned(999));
+    let result = session.set(3, obis, 2, CosemDataType::DoubleLongUnsigned(999));
     assert!(result.is_ok());
 
     let value = match session.get(3, obis, 2) {
@@ -279,7 +283,7 @@ fn test_set_block_transfer_data_updates_value() {
 
     let large_value = CosemDataType::Array((0..50).map(CosemDataType::Unsigned).collect());
     let obis = ObisCode::new(1, 0, 99, 1, 0, 0xFF);
-    assert!(session.set(1, obis.clone(), 2, large_value).is_ok());
+    assert!(session.set(1, obis, 2, large_value).is_ok());
 
     // Large GET returns the first data-block of the written array (tag 0x01).
     match session.get(1, obis, 2) {
@@ -0,0 +1,86 @@
+//! `#[derive(ToCosem, FromCosem)]` mapping of user-defined structures.
+#![cfg(feature = "derive")]
+
+use spodes_rs::obis::ObisCode;
+use spodes_rs::types::codec::{FromCosem, ToCosem};
+use spodes_rs::types::CosemDataType;
+
+#[derive(Debug, Clone, PartialEq, ToCosem, FromCosem)]
+struct CaptureObject {
+    class_id: u16,
+    logical_name: ObisCode,
+    attribute_index: i8,
+    data_index: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, ToCosem, FromCosem)]
+struct Capture {
+    capture_objects: Vec<CaptureObject>,
+    capture_period: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ToCosem, FromCosem)]
+enum SortMethod {
+    Fifo = 1,
+    Lifo,
+    Largest,
+}
+
+#[derive(Debug, Clone, PartialEq, ToCosem, FromCosem)]
+struct Profile(#[cosem(octets)] [u8; 6], SortMethod, Option<u32>, Capture);
+
+#[derive(Debug, Clone, PartialEq, ToCosem, FromCosem)]
+#[cosem(transparent)]
+struct MeterId(#[cosem(octets)] Vec<u8>);
+
+fn capture_object(index: u16) -> CaptureObject {
+    CaptureObject {
+        class_id: 3,
+        logical_name: ObisCode::new(1, 0, 1, 8, 0, 255),
+        attribute_index: 2,
+        data_index: index,
+    }
+}
+
+#[test]
+fn derived_structures_round_trip() {
+    let profile = Profile(
+        [1, 0, 99, 1, 0, 255],
+        SortMethod::Lifo,
+        None,
+        Capture { capture_objects: vec![capture_object(0), capture_object(1)], capture_period: 1800 },
+    );
+    let encoded = profile.to_cosem();
+    let CosemDataType::Structure(fields) = &encoded else { panic!("a structure") };
+    assert_eq!(fields[0], CosemDataType::OctetString(vec![1, 0, 99, 1, 0, 255]));
+    assert_eq!(fields[1], CosemDataType::Enum(2));
+    assert_eq!(fields[2], CosemDataType::Null);
+    assert_eq!(Profile::from_cosem(&encoded), Ok(profile));
+
+    let id = MeterId(b"0001".to_vec());
+    assert_eq!(id.to_cosem(), CosemDataType::OctetString(b"0001".to_vec()));
+    assert_eq!(MeterId::from_cosem(&id.to_cosem()), Ok(id));
+}
+
+#[test]
+fn decode_errors_name_the_offending_field() {
+    let mut objects = vec![capture_object(0), capture_object(1), capture_object(2)];
+    objects[2].class_id = 7;
+    let mut encoded =
+        Profile([0; 6], SortMethod::Fifo, Some(10), Capture { capture_objects: objects, capture_period: 0 }).to_cosem();
+    // Truncate the third capture object's logical name.
+    if let CosemDataType::Structure(fields) = &mut encoded {
+        if let CosemDataType::Structure(capture) = &mut fields[3] {
+            if let CosemDataType::Array(objects) = &mut capture[0] {
+                if let CosemDataType::Structure(object) = &mut objects[2] {
+                    object[1] = CosemDataType::OctetString(vec![0; 5]);
+                }
+            }
+        }
+    }
+    let err = Profile::from_cosem(&encoded).unwrap_err();
+    assert_eq!(err.to_string(), "field 3.capture_objects[2].logical_name: expected octet-string(6)");
+
+    let err = SortMethod::from_cosem(&CosemDataType::Enum(9)).unwrap_err();
+    assert_eq!(err.to_string(), "expected enum (one of 1, 2, 3)");
+}
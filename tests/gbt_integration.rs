@@ -81,7 +81,7 @@ fn gbt_unconfirmed_round_trips_a_large_get_response() {
 
     let (client_link, server_link) = channel_pair();
     let block_payload_max = gbt::DEFAULT_BLOCK_SIZE - gbt::HEADER_MAX;
-    let server_obis = obis.clone();
+    let server_obis = obis;
     let server_value = large_value.clone();
     let handle = std::thread::spawn(move || serve_one(server_link, server_obis, server_value, block_payload_max, 0));
 
@@ -99,7 +99,7 @@ fn gbt_confirmed_window_round_trips_a_large_get_response() {
 
     let (client_link, server_link) = channel_pair();
     let block_payload_max = 32;
-    let server_obis = obis.clone();
+    let server_obis = obis;
     let server_value = large_value.clone();
     let handle = std::thread::spawn(move || serve_one(server_link, server_obis, server_value, block_payload_max, 2));
 
@@ -121,7 +121,7 @@ fn small_response_is_not_segmented_even_with_gbt_enabled() {
 
     let (client_link, server_link) = channel_pair();
     let block_payload_max = gbt::DEFAULT_BLOCK_SIZE - gbt::HEADER_MAX;
-    let server_obis = obis.clone();
+    let server_obis = obis;
     let server_value = small_value.clone();
     let handle = std::thread::spawn(move || serve_one(server_link, server_obis, server_value, block_payload_max, 0));
 
@@ -12,7 +12,7 @@ use spodes_rs::classes::register_activation::{RegisterActivation, RegisterActiva
 use spodes_rs::classes::schedule::{Schedule, ScheduleConfig};
 use spodes_rs::classes::script_table::{ScriptTable, ScriptTableConfig};
 use spodes_rs::classes::special_days_table::{SpecialDaysTable, SpecialDaysTableConfig};
-use spodes_rs::interface::InterfaceClass;
+use spodes_rs::interface::{AccessError, InterfaceClass};
 use spodes_rs::obis::ObisCode;
 use spodes_rs::serialization::{deserialize_object, serialize_object};
 use spodes_rs::types::attrs::{
@@ -26,7 +26,7 @@ use std::sync::Arc;
 fn test_data_serialization_deserialization() {
     let obis = ObisCode::new(0, 0, 96, 1, 0, 255);
     let value = CosemDataType::Integer(42);
-    let data = Data::new(obis.clone(), value.clone());
+    let data = Data::new(obis, value.clone());
 
     let serialized = serialize_object(&data).expect("Serialization failed");
     let mut deserialized = Data::new(obis, CosemDataType::Null);
@@ -41,7 +41,7 @@ fn test_register_serialization_deserialization() {
     let obis = ObisCode::new(1, 0, 1, 8, 0, 255);
     let value = CosemDataType::DoubleLong(1000);
     let scaler_unit = ScalerUnit::new(0, 0x1B);
-    let register = Register::new(obis.clone(), value.clone(), scaler_unit);
+    let register = Register::new(obis, value.clone(), scaler_unit);
 
     let serialized = serialize_object(&register).expect("Serialization failed");
     let mut deserialized = Register::new(obis, CosemDataType::Null, ScalerUnit::new(0, 0));
@@ -76,7 +76,7 @@ fn test_profile_generic_serialization_deserialization() {
     let profile_entries = 100;
 
     let config = ProfileGenericConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         version: 1,
         buffer: buffer.clone(),
         capture_objects,
@@ -156,7 +156,7 @@ fn test_clock_serialization_deserialization() {
     let daylight_savings_end = DateTime([0x07, 0xE5, 0x10, 0x29, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
 
     let config = ClockConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         time: time.clone(),
         time_zone: 180,
         status: 1,
@@ -250,6 +250,32 @@ fn test_clock_adjust_to_minute() {
     }
 }
 
+#[test]
+fn test_clock_shift_time() {
+    let obis = ObisCode::new(0, 0, 1, 0, 0, 255);
+    // 2021-05-01 16:25:18 local, deviation +180 min, clock status 0x80 (DST active).
+    let time = DateTime([0x07, 0xE5, 0x05, 0x01, 0x06, 0x10, 0x19, 0x12, 0x00, 0x00, 0xB4, 0x80]);
+    let config = ClockConfig {
+        logical_name: obis,
+        time,
+        time_zone: 180,
+        status: 0,
+        daylight_savings_begin: DateTime([0u8; 12]),
+        daylight_savings_end: DateTime([0u8; 12]),
+        daylight_savings_deviation: 0,
+        daylight_savings_enabled: false,
+        clock_base: 1,
+    };
+    let mut clock = Clock::new(config);
+
+    // -60 s across a minute boundary; deviation and status octets are kept.
+    clock.invoke_method(6, Some(CosemDataType::Long(-60))).expect("shift_time failed");
+    assert_eq!(clock.time().0, [0x07, 0xE5, 0x05, 0x01, 0x06, 0x10, 0x18, 0x12, 0x00, 0x00, 0xB4, 0x80]);
+
+    assert!(clock.invoke_method(6, Some(CosemDataType::Long(901))).is_err());
+    assert!(clock.invoke_method(6, Some(CosemDataType::Unsigned(1))).is_err());
+}
+
 #[test]
 fn test_clock_adjust_to_preset_time() {
     let obis = ObisCode::new(0, 0, 1, 0, 0, 255);
@@ -290,7 +316,7 @@ fn test_extended_register_serialization_deserialization() {
     ]);
 
     let extended_register =
-        ExtendedRegister::new(obis.clone(), value.clone(), scaler_unit, status.clone(), capture_time.clone());
+        ExtendedRegister::new(obis, value.clone(), scaler_unit, status.clone(), capture_time.clone());
 
     let serialized = serialize_object(&extended_register).expect("Serialization failed");
     let mut deserialized = ExtendedRegister::new(
@@ -378,7 +404,7 @@ fn test_demand_register_serialization_deserialization() {
     let number_of_periods = 24u16;
 
     let config = DemandRegisterConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         current_average_value: current_average_value.clone(),
         last_average_value: last_average_value.clone(),
         scaler_unit,
@@ -521,7 +547,7 @@ fn test_register_activation_serialization_deserialization() {
     let active_mask = vec![0x54, 0x41, 0x52, 0x49, 0x46, 0x46, 0x31]; // "TARIFF1"
 
     let config = RegisterActivationConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         register_assignment: register_assignment.clone(),
         mask_list: mask_list.clone(),
         active_mask: active_mask.clone(),
@@ -606,6 +632,33 @@ fn test_register_activation_delete_mask() {
     assert_eq!(register_activation.attributes()[3].1, CosemDataType::OctetString(vec![]));
 }
 
+#[test]
+fn test_register_activation_set_active_mask() {
+    let mask_list = vec![
+        RegisterActMask { mask_name: b"T1".to_vec(), index_list: vec![1] },
+        RegisterActMask { mask_name: b"T2".to_vec(), index_list: vec![2] },
+    ];
+    let config = RegisterActivationConfig {
+        logical_name: ObisCode::new(0, 0, 14, 0, 0, 255),
+        register_assignment: vec![],
+        mask_list,
+        active_mask: b"T1".to_vec(),
+    };
+    let mut register_activation = RegisterActivation::new(config);
+
+    register_activation.set_attribute(4, CosemDataType::OctetString(b"T2".to_vec())).expect("Set active mask failed");
+    assert_eq!(register_activation.active_mask(), b"T2");
+    assert!(matches!(
+        register_activation.set_attribute(4, CosemDataType::OctetString(b"T9".to_vec())),
+        Err(AccessError::ScopeOfAccessViolated(_))
+    ));
+    assert!(matches!(
+        register_activation.set_attribute(3, CosemDataType::Array(vec![])),
+        Err(AccessError::ReadWriteDenied(_))
+    ));
+    assert_eq!(register_activation.active_mask(), b"T2");
+}
+
 #[test]
 fn test_script_table_serialization_deserialization() {
     let obis = ObisCode::new(0, 0, 10, 100, 0, 255);
@@ -618,7 +671,7 @@ fn test_script_table_serialization_deserialization() {
     };
     let scripts = vec![Script { script_identifier: 1, actions: vec![action] }];
 
-    let config = ScriptTableConfig { logical_name: obis.clone(), scripts: scripts.clone() };
+    let config = ScriptTableConfig { logical_name: obis, scripts: scripts.clone() };
     let script_table = ScriptTable::new(config);
 
     let serialized = serialize_object(&script_table).expect("Serialization failed");
@@ -654,7 +707,7 @@ fn test_script_table_execute() {
     let result = script_table.invoke_method(1, Some(invalid_script_id));
     assert!(result.is_err());
     if let Err(e) = result {
-        assert_eq!(e, "Script with ID 2 not found");
+        assert_eq!(e, AccessError::ObjectUnavailable("Script with ID 2 not found".to_string()));
     }
 }
 
@@ -674,7 +727,7 @@ fn test_schedule_serialization_deserialization() {
         end_date: vec![],
     }];
 
-    let config = ScheduleConfig { logical_name: obis.clone(), entries: entries.clone(), enabled: true };
+    let config = ScheduleConfig { logical_name: obis, entries: entries.clone(), enabled: true };
     let schedule = Schedule::new(config);
 
     let serialized = serialize_object(&schedule).expect("Serialization failed");
@@ -746,7 +799,7 @@ fn test_special_days_table_serialization_deserialization() {
         },
     ];
 
-    let config = SpecialDaysTableConfig { logical_name: obis.clone(), entries: entries.clone() };
+    let config = SpecialDaysTableConfig { logical_name: obis, entries: entries.clone() };
     let special_days_table = SpecialDaysTable::new(config);
 
     let serialized = serialize_object(&special_days_table).expect("Serialization failed");
@@ -790,7 +843,7 @@ fn test_special_days_table_insert_method() {
     ]);
     let result = special_days_table.invoke_method(1, Some(invalid_date));
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "Invalid DateTime length");
+    assert_eq!(result.unwrap_err(), AccessError::TypeUnmatched("Invalid DateTime length".to_string()));
 }
 
 #[test]
@@ -849,7 +902,7 @@ fn test_association_ln_serialization_deserialization_version0() {
     let association_status: u8 = 1;
 
     let config = AssociationLnConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         version: AssociationLnVersion::Version0,
         object_list,
         associated_partners_id: associated_partners_id.clone(),
@@ -923,7 +976,7 @@ fn test_association_ln_serialization_deserialization_version1() {
     let security_setup_reference = ObisCode::new(0, 0, 43, 0, 0, 255);
 
     let config = AssociationLnConfig {
-        logical_name: obis.clone(),
+        logical_name: obis,
         version: AssociationLnVersion::Version1,
         object_list,
         associated_partners_id: associated_partners_id.clone(),
@@ -1014,7 +1067,7 @@ fn test_association_ln_lls_authentication() {
     let wrong_secret = vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
     let result = association_ln.invoke_method(1, Some(CosemDataType::OctetString(wrong_secret)));
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "LLS authentication failed");
+    assert_eq!(result.unwrap_err(), AccessError::ReadWriteDenied("LLS authentication failed".to_string()));
 }
 
 #[test]